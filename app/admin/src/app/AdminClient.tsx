@@ -876,6 +876,16 @@ interface TableData {
   offset: number;
 }
 
+interface QueryPage {
+  cursor: string | null;
+  columns: { name: string; type_name: string; nullable: boolean | null }[];
+  rows: unknown[][];
+  count: number;
+  has_more: boolean;
+  duration_ms: number;
+  sql: string;
+}
+
 interface SessionInfo {
   id: string;
   user_id: string;
@@ -900,7 +910,8 @@ function DatabaseTab() {
 
   // Query state
   const [query, setQuery] = useState("SELECT * FROM users LIMIT 10");
-  const [queryResult, setQueryResult] = useState<{ rows: Record<string, unknown>[]; count: number; duration_ms: number; sql: string } | null>(null);
+  const [queryResult, setQueryResult] = useState<QueryPage | null>(null);
+  const [isLoadingMoreRows, setIsLoadingMoreRows] = useState(false);
   const [isRunningQuery, setIsRunningQuery] = useState(false);
   const [queryError, setQueryError] = useState<string | null>(null);
 
@@ -964,6 +975,10 @@ function DatabaseTab() {
   const handleRunQuery = async () => {
     setIsRunningQuery(true);
     setQueryError(null);
+    if (queryResult?.cursor) {
+      // Release the previous result's cursor on the server
+      fetch(`${API_BASE}/admin/db/query/${queryResult.cursor}`, { method: "DELETE", credentials: "include" });
+    }
     setQueryResult(null);
     try {
       const res = await fetch(`${API_BASE}/admin/db/query`, {
@@ -972,16 +987,11 @@ function DatabaseTab() {
         credentials: "include",
         body: JSON.stringify({ sql: query }),
       });
-      const data = await res.json() as { rows?: Record<string, unknown>[]; count?: number; duration_ms?: number; sql?: string; error?: string };
+      const data = await res.json() as QueryPage & { error?: string; message?: string };
       if (res.ok && data.rows) {
-        setQueryResult({
-          rows: data.rows,
-          count: data.count || 0,
-          duration_ms: data.duration_ms || 0,
-          sql: data.sql || query,
-        });
+        setQueryResult(data);
       } else {
-        setQueryError(data.error || "Query failed");
+        setQueryError(data.message || data.error || "Query failed");
       }
     } catch (error) {
       setQueryError(`Query failed: ${error}`);
@@ -990,6 +1000,54 @@ function DatabaseTab() {
     }
   };
 
+  const handleLoadMoreRows = async () => {
+    if (!queryResult?.cursor) return;
+    setIsLoadingMoreRows(true);
+    try {
+      const res = await fetch(`${API_BASE}/admin/db/query/${queryResult.cursor}`, { credentials: "include" });
+      const data = await res.json() as QueryPage & { error?: string; message?: string };
+      if (res.ok && data.rows) {
+        setQueryResult({
+          ...data,
+          rows: [...queryResult.rows, ...data.rows],
+          count: queryResult.count + data.count,
+        });
+      } else {
+        setQueryError(data.message || data.error || "Failed to load more rows");
+        setQueryResult({ ...queryResult, cursor: null, has_more: false });
+      }
+    } catch (error) {
+      setQueryError(`Failed to load more rows: ${error}`);
+    } finally {
+      setIsLoadingMoreRows(false);
+    }
+  };
+
+  const handleExportCsv = async () => {
+    setQueryError(null);
+    try {
+      const res = await fetch(`${API_BASE}/admin/db/query/csv`, {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        credentials: "include",
+        body: JSON.stringify({ sql: query }),
+      });
+      if (!res.ok) {
+        const data = await res.json() as { error?: string; message?: string };
+        setQueryError(data.message || data.error || "Export failed");
+        return;
+      }
+      const url = URL.createObjectURL(await res.blob());
+      const link = document.createElement("a");
+      link.href = url;
+      link.download = "query.csv";
+      link.click();
+      URL.revokeObjectURL(url);
+    } catch (error) {
+      setQueryError(`Export failed: ${error}`);
+    }
+  };
+
   const fetchSessions = async () => {
     setIsLoadingSessions(true);
     try {
@@ -1216,7 +1274,14 @@ function DatabaseTab() {
               >
                 {isRunningQuery ? "Running..." : "▶ Run Query"}
               </button>
-              <span className={styles.dbQueryHint}>Only SELECT queries allowed</span>
+              <button
+                className={styles.dbRunButton}
+                onClick={handleExportCsv}
+                disabled={isRunningQuery || !query.trim()}
+              >
+                Export CSV
+              </button>
+              <span className={styles.dbQueryHint}>Read-only; statements time out after a few seconds</span>
             </div>
           </div>
 
@@ -1227,24 +1292,23 @@ function DatabaseTab() {
           {queryResult && (
             <div className={styles.dbQueryResult}>
               <div className={styles.dbQueryMeta}>
-                {queryResult.count} rows in {queryResult.duration_ms}ms
+                {queryResult.count} rows{queryResult.has_more ? "+" : ""} in {queryResult.duration_ms}ms
               </div>
               <div className={styles.dbTableScroll}>
                 <table className={styles.dbDataTable}>
                   <thead>
                     <tr>
-                      {queryResult.rows.length > 0 &&
-                        Object.keys(queryResult.rows[0]).map((col) => (
-                          <th key={col}>{col}</th>
-                        ))}
+                      {queryResult.columns.map((col) => (
+                        <th key={col.name} title={col.type_name}>{col.name}</th>
+                      ))}
                     </tr>
                   </thead>
                   <tbody>
                     {queryResult.rows.map((row, i) => (
                       <tr key={i}>
-                        {Object.entries(row).map(([col, val]) => (
-                          <td key={col}>
-                            {typeof val === "object" ? JSON.stringify(val) : String(val ?? "")}
+                        {row.map((val, j) => (
+                          <td key={j}>
+                            {typeof val === "object" && val !== null ? JSON.stringify(val) : String(val ?? "")}
                           </td>
                         ))}
                       </tr>
@@ -1252,6 +1316,13 @@ function DatabaseTab() {
                   </tbody>
                 </table>
               </div>
+              {queryResult.has_more && (
+                <div className={styles.dbPagination}>
+                  <button disabled={isLoadingMoreRows} onClick={handleLoadMoreRows}>
+                    {isLoadingMoreRows ? "Loading..." : "Load more"}
+                  </button>
+                </div>
+              )}
             </div>
           )}
         </div>
@@ -1,7 +1,7 @@
 //! Application configuration
 
 use config::{Config, Environment, File};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// Main application configuration
 #[derive(Debug, Clone, Deserialize)]
@@ -13,6 +13,21 @@ pub struct AppConfig {
     /// Storage config (R2/S3) - used in Phase 14
     #[allow(dead_code)]
     pub storage: StorageConfig,
+    /// Reminder delivery (scheduler, SMTP and Web Push)
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    /// Account deletion grace period and purger
+    #[serde(default)]
+    pub accounts: AccountsConfig,
+    /// Admin logical backups
+    #[serde(default)]
+    pub backups: BackupsConfig,
+    /// Admin read-only SQL console
+    #[serde(default)]
+    pub sql_console: SqlConsoleConfig,
+    /// Request rate limits
+    #[serde(default)]
+    pub rate_limits: RateLimitsConfig,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -71,12 +86,45 @@ pub struct AuthConfig {
     /// OAuth providers configuration
     #[serde(default)]
     pub oauth: Option<OAuthConfig>,
+    /// WebAuthn relying party ID (defaults to the cookie domain)
+    #[serde(default)]
+    pub passkey_rp_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
 pub struct OAuthConfig {
     pub google: Option<OAuthProviderConfig>,
     pub azure: Option<OAuthProviderConfig>,
+    /// Additional OpenID Connect providers (Keycloak, Authentik, ...)
+    #[serde(default)]
+    pub oidc: Vec<OidcProviderConfig>,
+}
+
+/// Config-driven OpenID Connect provider
+///
+/// With `issuer` set, endpoints come from `.well-known/openid-configuration`
+/// and the ID token is validated against the provider's JWKS. Plain OAuth 2.0
+/// providers (e.g. GitHub) leave `issuer` unset and give explicit endpoints;
+/// identity is then read from `userinfo_endpoint`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OidcProviderConfig {
+    /// URL-safe provider ID used in /auth/signin/{id} and /auth/callback/{id}
+    pub id: String,
+    /// Display name for the sign-in button
+    pub name: String,
+    #[serde(default)]
+    pub issuer: Option<String>,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: String,
+    #[serde(default = "default_oidc_scopes")]
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub authorization_endpoint: Option<String>,
+    #[serde(default)]
+    pub token_endpoint: Option<String>,
+    #[serde(default)]
+    pub userinfo_endpoint: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -117,6 +165,263 @@ pub struct StorageConfig {
     pub secret_access_key: Option<String>,
 }
 
+/// Reminder scheduler and delivery channels
+///
+/// Inbox delivery is always available; email and push are enabled by
+/// configuring `smtp` and `vapid`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotificationsConfig {
+    /// Run the scheduler in this instance
+    #[serde(default = "default_true")]
+    pub scheduler_enabled: bool,
+    /// Seconds between scheduler runs
+    #[serde(default = "default_notification_interval")]
+    pub interval_seconds: u64,
+    #[serde(default)]
+    pub smtp: Option<SmtpConfig>,
+    #[serde(default)]
+    pub vapid: Option<VapidConfig>,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            scheduler_enabled: true,
+            interval_seconds: default_notification_interval(),
+            smtp: None,
+            vapid: None,
+        }
+    }
+}
+
+/// Account deletion
+///
+/// Deleting an account schedules it; the purger removes the user's stored
+/// objects and rows once the grace period is over.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountsConfig {
+    /// Days a deleted account can still be restored
+    #[serde(default = "default_deletion_grace_days")]
+    pub deletion_grace_days: i32,
+    /// Run the purger in this instance
+    #[serde(default = "default_true")]
+    pub purger_enabled: bool,
+    /// Seconds between purger runs
+    #[serde(default = "default_purge_interval")]
+    pub purge_interval_seconds: u64,
+}
+
+impl Default for AccountsConfig {
+    fn default() -> Self {
+        Self {
+            deletion_grace_days: default_deletion_grace_days(),
+            purger_enabled: true,
+            purge_interval_seconds: default_purge_interval(),
+        }
+    }
+}
+
+/// Admin logical backups, written to storage under `_backups/`
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackupsConfig {
+    /// Complete backups kept when pruning
+    #[serde(default = "default_backup_retention")]
+    pub retention_count: usize,
+}
+
+impl Default for BackupsConfig {
+    fn default() -> Self {
+        Self {
+            retention_count: default_backup_retention(),
+        }
+    }
+}
+
+/// Admin SQL console
+///
+/// Queries run over the console's own pool, logged in as `role`, in a READ
+/// ONLY transaction, one page at a time through a cursor that stays open
+/// between pages.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SqlConsoleConfig {
+    /// Connection URL logging in as the console role; the console is
+    /// unavailable without one
+    #[serde(default)]
+    pub database_url: Option<String>,
+    /// Connections in the console pool
+    #[serde(default = "default_console_pool_size")]
+    pub pool_size: u32,
+    /// Read-only database role queries run as (created by migration 0014)
+    #[serde(default = "default_console_role")]
+    pub role: String,
+    /// Longest a single statement (or page fetch) may run
+    #[serde(default = "default_console_statement_timeout")]
+    pub statement_timeout_ms: u64,
+    /// Rows per page when the request does not ask for a size
+    #[serde(default = "default_console_page_size")]
+    pub page_size: i64,
+    /// Seconds an unread cursor is kept open
+    #[serde(default = "default_console_cursor_ttl")]
+    pub cursor_ttl_seconds: u64,
+    /// Cursors and CSV exports open at once; each holds a console connection
+    #[serde(default = "default_console_max_cursors")]
+    pub max_open_cursors: usize,
+    /// Most rows written by a CSV export
+    #[serde(default = "default_console_export_rows")]
+    pub export_row_limit: i64,
+}
+
+impl Default for SqlConsoleConfig {
+    fn default() -> Self {
+        Self {
+            database_url: None,
+            pool_size: default_console_pool_size(),
+            role: default_console_role(),
+            statement_timeout_ms: default_console_statement_timeout(),
+            page_size: default_console_page_size(),
+            cursor_ttl_seconds: default_console_cursor_ttl(),
+            max_open_cursors: default_console_max_cursors(),
+            export_row_limit: default_console_export_rows(),
+        }
+    }
+}
+
+/// Request rate limiting
+///
+/// Each request is checked against the first policy matching its path and
+/// method; requests no policy matches are not limited.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateLimitsConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Where buckets live; `postgres` by default in production, where
+    /// several instances run
+    #[serde(default)]
+    pub backend: RateLimitBackend,
+    #[serde(default = "default_rate_limit_policies")]
+    pub policies: Vec<RateLimitPolicy>,
+}
+
+impl Default for RateLimitsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            backend: RateLimitBackend::default(),
+            policies: default_rate_limit_policies(),
+        }
+    }
+}
+
+/// Token bucket store
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitBackend {
+    /// Per-instance buckets in memory
+    #[default]
+    Memory,
+    /// Buckets shared by all instances in `rate_limit_buckets`
+    Postgres,
+}
+
+impl std::str::FromStr for RateLimitBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "memory" => Ok(Self::Memory),
+            "postgres" => Ok(Self::Postgres),
+            other => Err(format!("unknown rate limit backend: {}", other)),
+        }
+    }
+}
+
+/// What a policy counts requests by
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitKey {
+    /// Signed-in user, or client IP when signed out
+    User,
+    /// Session, or client IP when signed out
+    Session,
+    /// Client IP
+    #[default]
+    Ip,
+    /// One bucket for everyone
+    Route,
+}
+
+/// Token bucket for a set of routes
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateLimitPolicy {
+    /// Bucket name; also shown in audit entries
+    pub name: String,
+    /// Path prefixes the policy covers (full paths, e.g. `/api/feedback`;
+    /// `*` stands for one segment)
+    pub paths: Vec<String>,
+    /// HTTP methods covered; empty for all
+    #[serde(default)]
+    pub methods: Vec<String>,
+    #[serde(default)]
+    pub key: RateLimitKey,
+    /// Requests allowed in a burst
+    pub capacity: u32,
+    /// Seconds for an empty bucket to refill completely
+    pub period_seconds: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SmtpConfig {
+    pub host: String,
+    #[serde(default = "default_smtp_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Sender mailbox, e.g. `Ignition <reminders@example.com>`
+    pub from: String,
+    #[serde(default)]
+    pub security: SmtpSecurity,
+}
+
+/// Transport security for the SMTP connection
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Plain connection (local relays and test sinks only)
+    None,
+    /// Upgrade with STARTTLS (usually port 587)
+    #[default]
+    Starttls,
+    /// TLS from the start (usually port 465)
+    Tls,
+}
+
+impl std::str::FromStr for SmtpSecurity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "starttls" => Ok(Self::Starttls),
+            "tls" => Ok(Self::Tls),
+            other => Err(format!("unknown SMTP security mode: {}", other)),
+        }
+    }
+}
+
+/// Web Push application server identity (RFC 8292)
+#[derive(Debug, Clone, Deserialize)]
+pub struct VapidConfig {
+    /// P-256 private key (base64url): the raw 32-byte scalar or PKCS#8
+    pub private_key: String,
+    /// Uncompressed public key (base64url); required with a raw private key
+    #[serde(default)]
+    pub public_key: Option<String>,
+    /// Contact for push services, e.g. `mailto:ops@example.com`
+    pub subject: String,
+}
+
 // Default value functions
 fn default_host() -> String {
     "0.0.0.0".to_string()
@@ -149,6 +454,14 @@ fn default_allowed_origins() -> Vec<String> {
     ]
 }
 
+fn default_oidc_scopes() -> Vec<String> {
+    vec![
+        "openid".to_string(),
+        "email".to_string(),
+        "profile".to_string(),
+    ]
+}
+
 fn default_region() -> String {
     "auto".to_string()
 }
@@ -161,6 +474,98 @@ fn default_frontend_url() -> String {
     "http://localhost:3000".to_string()
 }
 
+fn default_true() -> bool {
+    true
+}
+
+fn default_notification_interval() -> u64 {
+    60
+}
+
+fn default_deletion_grace_days() -> i32 {
+    30
+}
+
+fn default_purge_interval() -> u64 {
+    300
+}
+
+fn default_backup_retention() -> usize {
+    7
+}
+
+fn default_console_pool_size() -> u32 {
+    5
+}
+
+fn default_console_role() -> String {
+    "ignition_console".to_string()
+}
+
+fn default_console_statement_timeout() -> u64 {
+    5000
+}
+
+fn default_console_page_size() -> i64 {
+    200
+}
+
+fn default_console_cursor_ttl() -> u64 {
+    120
+}
+
+fn default_console_max_cursors() -> usize {
+    4
+}
+
+fn default_console_export_rows() -> i64 {
+    100_000
+}
+
+fn default_rate_limit_policies() -> Vec<RateLimitPolicy> {
+    let policy = |name: &str, paths: &[&str], method: &str, key, capacity, period_seconds| {
+        RateLimitPolicy {
+            name: name.to_string(),
+            paths: paths.iter().map(|p| p.to_string()).collect(),
+            methods: vec![method.to_string()],
+            key,
+            capacity,
+            period_seconds,
+        }
+    };
+    vec![
+        policy("admin_claim", &["/admin-access/claim"], "POST", RateLimitKey::Ip, 5, 900),
+        policy("oauth_callback", &["/auth/callback"], "GET", RateLimitKey::Ip, 20, 60),
+        policy(
+            "passkey_login",
+            &["/auth/passkey/login/options", "/auth/passkey/login"],
+            "POST",
+            RateLimitKey::Ip,
+            20,
+            60,
+        ),
+        policy("feedback", &["/api/feedback"], "POST", RateLimitKey::User, 10, 3600),
+        policy(
+            "uploads",
+            &[
+                "/api/blobs/upload",
+                "/blobs/upload",
+                "/api/reference/upload",
+                "/reference/upload",
+                "/api/ideas/*/attachments",
+            ],
+            "POST",
+            RateLimitKey::User,
+            30,
+            600,
+        ),
+    ]
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
 impl AppConfig {
     /// Load configuration from files and environment
     pub fn load() -> anyhow::Result<Self> {
@@ -175,7 +580,7 @@ impl AppConfig {
         // Debug: Log all AUTH_* and STORAGE_* env vars at startup
         tracing::info!("=== Config Loading: Environment Variables ===");
         for (key, value) in std::env::vars() {
-            if key.starts_with("AUTH_") || key.starts_with("STORAGE_") || key.starts_with("DATABASE") || key.starts_with("SERVER_") {
+            if key.starts_with("AUTH_") || key.starts_with("STORAGE_") || key.starts_with("DATABASE") || key.starts_with("SERVER_") || key.starts_with("NOTIFICATIONS_") {
                 let display_value = if key.contains("SECRET") || key.contains("PASSWORD") || key.contains("KEY") {
                     if value.is_empty() { "(empty)" } else { "(set)" }
                 } else if value.len() > 50 {
@@ -213,6 +618,11 @@ impl AppConfig {
                 vec!["http://localhost:3000", "http://localhost:3001"],
             )?
             .set_default("storage.region", "auto")?
+            // Production runs several instances, which must share rate limit buckets
+            .set_default(
+                "rate_limits.backend",
+                if env == "production" { "postgres" } else { "memory" },
+            )?
             // Load from config file if exists
             .add_source(File::with_name("config/default").required(false))
             .add_source(File::with_name(&format!("config/{}", env)).required(false))
@@ -249,6 +659,12 @@ impl AppConfig {
                 app_config.auth.cookie_domain = cookie_domain;
             }
         }
+        if let Ok(rp_id) = std::env::var("AUTH_PASSKEY_RP_ID") {
+            if !rp_id.is_empty() {
+                tracing::info!("Loading AUTH_PASSKEY_RP_ID from environment: {}", rp_id);
+                app_config.auth.passkey_rp_id = Some(rp_id);
+            }
+        }
 
         // Manual OAuth override - the config crate separator("_") splits ALL underscores,
         // so AUTH_OAUTH_GOOGLE_CLIENT_ID becomes auth.oauth.google.client.id instead of
@@ -294,6 +710,21 @@ impl AppConfig {
             }
         }
 
+        // Generic OIDC providers are a list, which env vars cannot express with the
+        // "_" separator, so AUTH_OIDC_PROVIDERS carries them as a JSON array.
+        if let Ok(raw) = std::env::var("AUTH_OIDC_PROVIDERS") {
+            if !raw.trim().is_empty() {
+                let providers: Vec<OidcProviderConfig> = serde_json::from_str(&raw)
+                    .map_err(|e| anyhow::anyhow!("Invalid AUTH_OIDC_PROVIDERS: {}", e))?;
+                tracing::info!("Loading {} OIDC provider(s) from environment", providers.len());
+                app_config
+                    .auth
+                    .oauth
+                    .get_or_insert_with(OAuthConfig::default)
+                    .oidc = providers;
+            }
+        }
+
         // Manual Storage override - same issue as OAuth: separator("_") splits ALL underscores,
         // so STORAGE_ACCESS_KEY_ID becomes storage.access.key.id instead of storage.access_key_id.
         let storage_endpoint = std::env::var("STORAGE_ENDPOINT").ok().filter(|s| !s.is_empty());
@@ -313,6 +744,129 @@ impl AppConfig {
             }
         }
 
+        // Manual Notifications override - same separator issue for interval_seconds,
+        // scheduler_enabled and the SMTP/VAPID keys.
+        if let Ok(enabled) = std::env::var("NOTIFICATIONS_SCHEDULER_ENABLED") {
+            if !enabled.is_empty() {
+                app_config.notifications.scheduler_enabled = enabled
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid NOTIFICATIONS_SCHEDULER_ENABLED: {}", enabled))?;
+            }
+        }
+        if let Ok(interval) = std::env::var("NOTIFICATIONS_INTERVAL_SECONDS") {
+            if !interval.is_empty() {
+                app_config.notifications.interval_seconds = interval
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid NOTIFICATIONS_INTERVAL_SECONDS: {}", interval))?;
+            }
+        }
+
+        let smtp_host = std::env::var("NOTIFICATIONS_SMTP_HOST").ok().filter(|s| !s.is_empty());
+        let smtp_from = std::env::var("NOTIFICATIONS_SMTP_FROM").ok().filter(|s| !s.is_empty());
+        if let (Some(host), Some(from)) = (smtp_host, smtp_from) {
+            tracing::info!("Loading SMTP config from environment variables");
+            let port = match std::env::var("NOTIFICATIONS_SMTP_PORT").ok().filter(|s| !s.is_empty()) {
+                Some(port) => port
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid NOTIFICATIONS_SMTP_PORT: {}", port))?,
+                None => default_smtp_port(),
+            };
+            let security = match std::env::var("NOTIFICATIONS_SMTP_SECURITY").ok().filter(|s| !s.is_empty()) {
+                Some(mode) => mode.parse().map_err(|e: String| anyhow::anyhow!(e))?,
+                None => SmtpSecurity::default(),
+            };
+            app_config.notifications.smtp = Some(SmtpConfig {
+                host,
+                port,
+                username: std::env::var("NOTIFICATIONS_SMTP_USERNAME").ok().filter(|s| !s.is_empty()),
+                password: std::env::var("NOTIFICATIONS_SMTP_PASSWORD").ok().filter(|s| !s.is_empty()),
+                from,
+                security,
+            });
+        }
+
+        let vapid_private_key = std::env::var("NOTIFICATIONS_VAPID_PRIVATE_KEY").ok().filter(|s| !s.is_empty());
+        let vapid_subject = std::env::var("NOTIFICATIONS_VAPID_SUBJECT").ok().filter(|s| !s.is_empty());
+        if let (Some(private_key), Some(subject)) = (vapid_private_key, vapid_subject) {
+            tracing::info!("Loading VAPID keys from environment variables");
+            app_config.notifications.vapid = Some(VapidConfig {
+                private_key,
+                public_key: std::env::var("NOTIFICATIONS_VAPID_PUBLIC_KEY").ok().filter(|s| !s.is_empty()),
+                subject,
+            });
+        }
+
+        // Manual Accounts override
+        if let Ok(days) = std::env::var("ACCOUNTS_DELETION_GRACE_DAYS") {
+            if !days.is_empty() {
+                let days: i32 = days
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid ACCOUNTS_DELETION_GRACE_DAYS: {}", days))?;
+                if days < 0 {
+                    anyhow::bail!("ACCOUNTS_DELETION_GRACE_DAYS must not be negative");
+                }
+                app_config.accounts.deletion_grace_days = days;
+            }
+        }
+        if let Ok(enabled) = std::env::var("ACCOUNTS_PURGER_ENABLED") {
+            if !enabled.is_empty() {
+                app_config.accounts.purger_enabled = enabled
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid ACCOUNTS_PURGER_ENABLED: {}", enabled))?;
+            }
+        }
+
+        // Manual Backups override
+        if let Ok(count) = std::env::var("BACKUPS_RETENTION_COUNT") {
+            if !count.is_empty() {
+                app_config.backups.retention_count = count
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid BACKUPS_RETENTION_COUNT: {}", count))?;
+            }
+        }
+
+        // Manual Rate limits override
+        if let Ok(enabled) = std::env::var("RATE_LIMITS_ENABLED") {
+            if !enabled.is_empty() {
+                app_config.rate_limits.enabled = enabled
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid RATE_LIMITS_ENABLED: {}", enabled))?;
+            }
+        }
+        if let Ok(backend) = std::env::var("RATE_LIMITS_BACKEND") {
+            if !backend.is_empty() {
+                app_config.rate_limits.backend = backend.parse().map_err(|e| anyhow::anyhow!("{}", e))?;
+            }
+        }
+        for policy in &app_config.rate_limits.policies {
+            if policy.capacity == 0 || policy.period_seconds == 0 {
+                anyhow::bail!("Rate limit policy {} needs a capacity and period", policy.name);
+            }
+        }
+
+        // Manual SQL console override
+        if let Ok(url) = std::env::var("SQL_CONSOLE_DATABASE_URL") {
+            if !url.is_empty() {
+                app_config.sql_console.database_url = Some(url);
+            }
+        }
+        if let Ok(role) = std::env::var("SQL_CONSOLE_ROLE") {
+            if !role.is_empty() {
+                app_config.sql_console.role = role;
+            }
+        }
+        if let Ok(ms) = std::env::var("SQL_CONSOLE_STATEMENT_TIMEOUT_MS") {
+            if !ms.is_empty() {
+                app_config.sql_console.statement_timeout_ms = ms.parse().map_err(|_| {
+                    anyhow::anyhow!("Invalid SQL_CONSOLE_STATEMENT_TIMEOUT_MS: {}", ms)
+                })?;
+            }
+        }
+        // Running and explaining need a connection beyond the open cursors
+        if app_config.sql_console.max_open_cursors as u32 >= app_config.sql_console.pool_size {
+            anyhow::bail!("SQL console pool_size must exceed max_open_cursors");
+        }
+
         Ok(app_config)
     }
 
@@ -0,0 +1,79 @@
+//! Account Deletion Models
+//!
+//! Scheduled account deletions and the report of what their purge removed.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub const ACCOUNT_DELETION_STATUS_SCHEDULED: &str = "scheduled";
+pub const ACCOUNT_DELETION_STATUS_PURGING: &str = "purging";
+pub const ACCOUNT_DELETION_STATUS_COMPLETED: &str = "completed";
+pub const ACCOUNT_DELETION_STATUS_CANCELLED: &str = "cancelled";
+
+/// Deletion of one account, from request to purge report
+#[derive(Debug, Clone, FromRow)]
+pub struct AccountDeletion {
+    pub user_id: Uuid,
+    pub status: String,
+    pub requested_by: Option<Uuid>,
+    pub requested_at: DateTime<Utc>,
+    pub purge_after: DateTime<Utc>,
+    pub attempts: i32,
+    pub leased_until: Option<DateTime<Utc>>,
+    /// Worker holding the lease; a new one for every claim
+    pub lease_owner: Option<Uuid>,
+    pub objects_removed: i32,
+    pub rows_removed: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Pending deletion as shown to the account owner
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountDeletionResponse {
+    pub status: String,
+    pub requested_at: DateTime<Utc>,
+    pub purge_after: DateTime<Utc>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+}
+
+impl From<AccountDeletion> for AccountDeletionResponse {
+    fn from(d: AccountDeletion) -> Self {
+        Self {
+            status: d.status,
+            requested_at: d.requested_at,
+            purge_after: d.purge_after,
+            cancelled_at: d.cancelled_at,
+        }
+    }
+}
+
+/// What a completed purge removed
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountPurgeReport {
+    pub user_id: Uuid,
+    /// Stored objects deleted, across all attempts
+    pub objects_removed: i32,
+    /// Rows deleted per table
+    pub rows_removed: BTreeMap<String, u64>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<AccountDeletion> for AccountPurgeReport {
+    fn from(d: AccountDeletion) -> Self {
+        Self {
+            user_id: d.user_id,
+            objects_removed: d.objects_removed,
+            rows_removed: d
+                .rows_removed
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or_default(),
+            completed_at: d.completed_at,
+        }
+    }
+}
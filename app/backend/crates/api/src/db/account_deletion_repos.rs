@@ -0,0 +1,329 @@
+//! Account Deletion Repository
+//!
+//! Schedules, cancels and claims account deletions, and removes a user's
+//! rows once their stored objects are gone.
+//!
+//! Few tables declare foreign keys to `users`, so rows are deleted table by
+//! table: everything in `ARCHIVE_TABLES` (children first), then the account
+//! tables in `ACCOUNT_TABLES`. Shared rows the user authored keep existing
+//! with the author cleared, and the audit log is retained.
+
+use std::collections::BTreeMap;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::account_deletion_models::*;
+use super::api_token_repos::API_TOKEN_REVOKE_ALL_FOR_USER;
+use super::archive_repos::{archive_delete_sql, ARCHIVE_TABLES};
+use super::repos::SESSION_DELETE_ALL_FOR_USER;
+use crate::error::AppError;
+
+/// Tables owned by the account but left out of export archives
+pub const ACCOUNT_TABLES: &[&str] = &[
+    "sessions",
+    "accounts",
+    "authenticators",
+    "webauthn_challenges",
+    "api_tokens",
+    "user_roles",
+    "sync_events",
+    "push_subscriptions",
+    "notification_deliveries",
+    "notification_settings",
+    "market_recommendations",
+    "account_archive_jobs",
+    "admin_saved_queries",
+];
+
+/// Columns naming the user as author of rows other users still see
+pub const ACCOUNT_AUTHOR_COLUMNS: &[(&str, &str)] = &[
+    ("feedback", "resolved_by"),
+    ("user_roles", "granted_by"),
+    ("universal_quests", "created_by"),
+    ("market_items", "created_by_user_id"),
+    ("listening_prompt_templates", "created_by"),
+    ("listening_prompt_presets", "created_by"),
+];
+
+/// Remove user `$1`'s rows from one of `ACCOUNT_TABLES`
+pub fn account_delete_sql(table: &str) -> String {
+    format!("DELETE FROM {} WHERE user_id = $1", table)
+}
+
+/// Clear one of `ACCOUNT_AUTHOR_COLUMNS` where it names user `$1`
+pub fn account_detach_sql(table: &str, column: &str) -> String {
+    format!("UPDATE {0} SET {1} = NULL WHERE {1} = $1", table, column)
+}
+
+// Column list for account_deletions - matches AccountDeletion struct field order
+macro_rules! account_deletion_columns {
+    () => {
+        r#"user_id, status, requested_by, requested_at, purge_after, attempts, leased_until,
+    lease_owner, objects_removed, rows_removed, error, cancelled_at, completed_at"#
+    };
+}
+
+pub struct AccountDeletionRepo;
+
+// Only a cancelled deletion can be scheduled again
+pub const ACCOUNT_DELETION_SCHEDULE: &str = concat!(
+    r#"
+    INSERT INTO account_deletions (user_id, purge_after)
+    VALUES ($1, NOW() + make_interval(days => $2))
+    ON CONFLICT (user_id) DO UPDATE SET
+        status = 'scheduled',
+        requested_by = NULL,
+        requested_at = NOW(),
+        purge_after = EXCLUDED.purge_after,
+        attempts = 0,
+        leased_until = NULL,
+        lease_owner = NULL,
+        error = NULL,
+        cancelled_at = NULL
+    WHERE account_deletions.status = 'cancelled'
+    RETURNING "#,
+    account_deletion_columns!()
+);
+
+pub const ACCOUNT_DELETION_GET: &str = concat!(
+    "SELECT ",
+    account_deletion_columns!(),
+    " FROM account_deletions WHERE user_id = $1"
+);
+
+pub const ACCOUNT_DELETION_CANCEL: &str = concat!(
+    r#"
+    UPDATE account_deletions
+    SET status = 'cancelled', cancelled_at = NOW()
+    WHERE user_id = $1 AND status = 'scheduled'
+    RETURNING "#,
+    account_deletion_columns!()
+);
+
+// Due deletions and purges whose lease ran out (crashed or failed)
+pub const ACCOUNT_DELETION_CLAIM_DUE: &str = concat!(
+    r#"
+    UPDATE account_deletions
+    SET status = 'purging', attempts = attempts + 1,
+        leased_until = NOW() + INTERVAL '15 minutes', lease_owner = gen_random_uuid()
+    WHERE user_id IN (
+        SELECT user_id FROM account_deletions
+        WHERE (status = 'scheduled' AND purge_after <= NOW())
+           OR (status = 'purging' AND leased_until < NOW())
+        ORDER BY purge_after
+        LIMIT $1
+        FOR UPDATE SKIP LOCKED
+    )
+    RETURNING "#,
+    account_deletion_columns!()
+);
+
+// Skips the grace period; refuses a purge another worker holds
+pub const ACCOUNT_DELETION_CLAIM_NOW: &str = concat!(
+    r#"
+    INSERT INTO account_deletions (user_id, status, requested_by, purge_after, attempts,
+                                   leased_until, lease_owner)
+    VALUES ($1, 'purging', $2, NOW(), 1, NOW() + INTERVAL '15 minutes', gen_random_uuid())
+    ON CONFLICT (user_id) DO UPDATE SET
+        status = 'purging',
+        requested_by = EXCLUDED.requested_by,
+        purge_after = NOW(),
+        attempts = account_deletions.attempts + 1,
+        leased_until = EXCLUDED.leased_until,
+        lease_owner = EXCLUDED.lease_owner,
+        cancelled_at = NULL
+    WHERE account_deletions.status IN ('scheduled', 'cancelled')
+       OR (account_deletions.status = 'purging' AND account_deletions.leased_until < NOW())
+    RETURNING "#,
+    account_deletion_columns!()
+);
+
+// Objects referenced by rows; usually under the user's prefix, but not always
+pub const ACCOUNT_DELETION_RECORD_OBJECTS: &str =
+    "UPDATE account_deletions SET objects_removed = objects_removed + $2 WHERE user_id = $1";
+
+// The lease is kept, so the purge is retried once it runs out
+pub const ACCOUNT_DELETION_FAIL: &str = r#"
+    UPDATE account_deletions SET error = $3
+    WHERE user_id = $1 AND status = 'purging' AND lease_owner = $2
+"#;
+
+pub const ACCOUNT_DELETION_USER_EXISTS: &str = "SELECT EXISTS (SELECT 1 FROM users WHERE id = $1)";
+
+pub const ACCOUNT_DELETION_DELETE_USER: &str = "DELETE FROM users WHERE id = $1";
+
+pub const ACCOUNT_DELETION_COMPLETE: &str = concat!(
+    r#"
+    UPDATE account_deletions
+    SET status = 'completed', rows_removed = $2, error = NULL, leased_until = NULL,
+        completed_at = NOW()
+    WHERE user_id = $1 AND status = 'purging' AND lease_owner = $3
+    RETURNING "#,
+    account_deletion_columns!()
+);
+
+impl AccountDeletionRepo {
+    /// Schedule deletion after `grace_days`, signing the account out
+    ///
+    /// Every session is ended and every API token revoked, so nothing keeps
+    /// using the account during the grace period without signing in again.
+    /// Asking again while a deletion is pending returns it unchanged.
+    pub async fn schedule(
+        pool: &PgPool,
+        user_id: Uuid,
+        grace_days: i32,
+    ) -> Result<AccountDeletion, AppError> {
+        let mut tx = pool.begin().await?;
+        let scheduled = sqlx::query_as::<_, AccountDeletion>(ACCOUNT_DELETION_SCHEDULE)
+            .bind(user_id)
+            .bind(grace_days)
+            .fetch_optional(&mut *tx)
+            .await?;
+        sqlx::query(SESSION_DELETE_ALL_FOR_USER)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(API_TOKEN_REVOKE_ALL_FOR_USER)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        match scheduled {
+            Some(deletion) => Ok(deletion),
+            None => Self::get(pool, user_id)
+                .await?
+                .ok_or_else(|| AppError::Internal("Account deletion vanished".to_string())),
+        }
+    }
+
+    pub async fn get(pool: &PgPool, user_id: Uuid) -> Result<Option<AccountDeletion>, AppError> {
+        let deletion = sqlx::query_as::<_, AccountDeletion>(ACCOUNT_DELETION_GET)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+        Ok(deletion)
+    }
+
+    /// Cancel a deletion still in its grace period
+    pub async fn cancel(pool: &PgPool, user_id: Uuid) -> Result<Option<AccountDeletion>, AppError> {
+        let deletion = sqlx::query_as::<_, AccountDeletion>(ACCOUNT_DELETION_CANCEL)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+        Ok(deletion)
+    }
+
+    /// Lease up to `limit` deletions ready to purge
+    pub async fn claim_due(pool: &PgPool, limit: i64) -> Result<Vec<AccountDeletion>, AppError> {
+        let deletions = sqlx::query_as::<_, AccountDeletion>(ACCOUNT_DELETION_CLAIM_DUE)
+            .bind(limit)
+            .fetch_all(pool)
+            .await?;
+        Ok(deletions)
+    }
+
+    /// Lease a deletion for an immediate purge requested by an admin
+    ///
+    /// `None` when the purge already completed or is held by another worker.
+    pub async fn claim_now(
+        pool: &PgPool,
+        user_id: Uuid,
+        admin_id: Uuid,
+    ) -> Result<Option<AccountDeletion>, AppError> {
+        let deletion = sqlx::query_as::<_, AccountDeletion>(ACCOUNT_DELETION_CLAIM_NOW)
+            .bind(user_id)
+            .bind(admin_id)
+            .fetch_optional(pool)
+            .await?;
+        Ok(deletion)
+    }
+
+    /// Add to the count of stored objects removed so far
+    pub async fn record_objects(pool: &PgPool, user_id: Uuid, count: i32) -> Result<(), AppError> {
+        sqlx::query(ACCOUNT_DELETION_RECORD_OBJECTS)
+            .bind(user_id)
+            .bind(count)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn user_exists(pool: &PgPool, user_id: Uuid) -> Result<bool, AppError> {
+        let exists = sqlx::query_scalar(ACCOUNT_DELETION_USER_EXISTS)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
+        Ok(exists)
+    }
+
+    /// Record a purge error, if `lease_owner` still holds the lease
+    pub async fn fail(
+        pool: &PgPool,
+        user_id: Uuid,
+        lease_owner: Uuid,
+        error: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query(ACCOUNT_DELETION_FAIL)
+            .bind(user_id)
+            .bind(lease_owner)
+            .bind(error)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Delete the user and all their rows, and mark the deletion completed
+    ///
+    /// Runs in one transaction, so a failure leaves the rows for the retry.
+    /// Rolls back when `lease_owner` no longer holds the purge.
+    pub async fn complete(
+        pool: &PgPool,
+        user_id: Uuid,
+        lease_owner: Uuid,
+    ) -> Result<AccountDeletion, AppError> {
+        let mut tx = pool.begin().await?;
+        let mut removed: BTreeMap<String, u64> = BTreeMap::new();
+
+        let data_tables = ARCHIVE_TABLES
+            .iter()
+            .rev()
+            .map(|t| (t.table, archive_delete_sql(t)));
+        let account_tables = ACCOUNT_TABLES.iter().map(|t| (*t, account_delete_sql(t)));
+        for (table, sql) in data_tables.chain(account_tables) {
+            let result = sqlx::query(&sql).bind(user_id).execute(&mut *tx).await?;
+            if result.rows_affected() > 0 {
+                *removed.entry(table.to_string()).or_default() += result.rows_affected();
+            }
+        }
+
+        for (table, column) in ACCOUNT_AUTHOR_COLUMNS {
+            sqlx::query(&account_detach_sql(table, column))
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let result = sqlx::query(ACCOUNT_DELETION_DELETE_USER)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() > 0 {
+            removed.insert("users".to_string(), result.rows_affected());
+        }
+
+        let rows_removed = serde_json::to_value(&removed)
+            .map_err(|e| AppError::Internal(format!("Failed to encode purge report: {}", e)))?;
+        let deletion = sqlx::query_as::<_, AccountDeletion>(ACCOUNT_DELETION_COMPLETE)
+            .bind(user_id)
+            .bind(rows_removed)
+            .bind(lease_owner)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::Internal("Account purge lost its lease".to_string()))?;
+
+        tx.commit().await?;
+        Ok(deletion)
+    }
+}
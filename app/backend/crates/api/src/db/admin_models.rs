@@ -2,6 +2,8 @@
 //!
 //! Types for admin-only operations: user management, stats, feedback moderation, etc.
 
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -373,6 +375,15 @@ pub struct TableInfo {
 // Backup/Restore
 // ============================================
 
+/// Backup layout version; bump when files or manifest fields change
+/// incompatibly
+pub const BACKUP_FORMAT_VERSION: i32 = 1;
+
+/// Manifest written (last) for a finished backup
+pub const BACKUP_STATUS_COMPLETE: &str = "complete";
+/// No manifest: still being written, or the run died
+pub const BACKUP_STATUS_INCOMPLETE: &str = "incomplete";
+
 /// Backup info
 #[derive(Debug, Clone, Serialize)]
 pub struct BackupInfo {
@@ -386,6 +397,32 @@ pub struct BackupInfo {
 #[derive(Debug, Serialize)]
 pub struct BackupsListResponse {
     pub backups: Vec<BackupInfo>,
+    /// Latest applied migration; restores need a backup taken at this version
+    pub schema_version: Option<i64>,
+    /// Complete backups kept by pruning
+    pub retention_count: usize,
+}
+
+/// One table in a backup, stored as `<table>.ndjson.gz`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupTable {
+    pub name: String,
+    pub rows: u64,
+    /// Compressed size
+    pub size_bytes: u64,
+}
+
+/// `manifest.json` of a backup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: i32,
+    pub backup_id: String,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Option<Uuid>,
+    /// Latest applied migration when the snapshot was taken
+    pub schema_version: Option<i64>,
+    /// Tables in foreign-key order (referenced tables first)
+    pub tables: Vec<BackupTable>,
 }
 
 /// Create backup response
@@ -394,6 +431,15 @@ pub struct CreateBackupResponse {
     pub success: bool,
     pub backup_id: String,
     pub message: String,
+    pub manifest: BackupManifest,
+    /// Older backups removed by retention
+    pub pruned: Vec<String>,
+}
+
+/// Prune backups response
+#[derive(Debug, Serialize)]
+pub struct PruneBackupsResponse {
+    pub removed: Vec<String>,
 }
 
 /// Restore request
@@ -407,6 +453,91 @@ pub struct RestoreRequest {
 pub struct RestoreResponse {
     pub success: bool,
     pub message: String,
+    /// Rows loaded per table
+    pub rows_restored: BTreeMap<String, u64>,
+}
+
+// ============================================
+// SQL Console
+// ============================================
+
+/// Largest page a console request may ask for
+pub const CONSOLE_MAX_PAGE_SIZE: i64 = 1000;
+
+/// Run a console query
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConsoleQueryRequest {
+    pub sql: String,
+    /// Rows in the first page; defaults to the configured page size
+    pub page_size: Option<i64>,
+}
+
+/// Show the plan of a console query
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConsoleExplainRequest {
+    pub sql: String,
+    /// Run the query and report actual timings (EXPLAIN ANALYZE)
+    #[serde(default)]
+    pub analyze: bool,
+}
+
+/// Fetch the next page of an open cursor
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConsolePageQuery {
+    pub page_size: Option<i64>,
+}
+
+/// Result column as reported by the database
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsoleColumn {
+    pub name: String,
+    /// Postgres type name, lowercase (`int4`, `timestamptz`, `text[]`)
+    pub type_name: String,
+    /// Whether the column can be NULL, when the database can tell
+    pub nullable: Option<bool>,
+}
+
+/// One page of a console result
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsolePage {
+    /// Pass to `GET /admin/db/query/{cursor}` for the next page; `None` once
+    /// the result is exhausted
+    pub cursor: Option<Uuid>,
+    pub columns: Vec<ConsoleColumn>,
+    /// Row values in column order
+    pub rows: Vec<Vec<serde_json::Value>>,
+    pub count: usize,
+    pub has_more: bool,
+    pub duration_ms: u128,
+    pub sql: String,
+}
+
+/// Plan of a console query
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsoleExplainResponse {
+    /// `EXPLAIN (FORMAT JSON)` output
+    pub plan: serde_json::Value,
+    pub analyze: bool,
+    pub duration_ms: u128,
+    pub sql: String,
+}
+
+/// Query an admin saved for reuse
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct SavedQuery {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub sql: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Save a query; saving under an existing name replaces it
+#[derive(Debug, Clone, Deserialize)]
+pub struct SaveQueryRequest {
+    pub name: String,
+    pub sql: String,
 }
 
 // ============================================
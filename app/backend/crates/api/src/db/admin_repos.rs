@@ -2,7 +2,7 @@
 //!
 //! Database operations for admin-only functionality.
 
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 use uuid::Uuid;
 
 use super::admin_models::*;
@@ -14,35 +14,40 @@ use crate::error::AppError;
 
 pub struct AdminClaimRepo;
 
+pub const ADMIN_CLAIM_HAS_ANY_ADMINS: &str = "SELECT COUNT(*) FROM users WHERE is_admin = TRUE";
+
+pub const ADMIN_CLAIM_IS_USER_ADMIN: &str =
+    "SELECT COALESCE(is_admin, FALSE) FROM users WHERE id = $1";
+
+pub const ADMIN_CLAIM_SET_USER_ADMIN: &str = "UPDATE users SET is_admin = TRUE WHERE id = $1";
+
+pub const ADMIN_CLAIM_COUNT_ADMINS: &str = "SELECT COUNT(*) FROM users WHERE is_admin = TRUE";
+
 impl AdminClaimRepo {
     /// Check if any admins exist in the system
     pub async fn has_any_admins(pool: &PgPool) -> Result<bool, AppError> {
-        let count = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM users WHERE is_admin = TRUE"
-        )
-        .fetch_one(pool)
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to count admins: {}", e)))?;
+        let count = sqlx::query_scalar::<_, i64>(ADMIN_CLAIM_HAS_ANY_ADMINS)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to count admins: {}", e)))?;
 
         Ok(count > 0)
     }
 
     /// Check if a user is an admin
     pub async fn is_user_admin(pool: &PgPool, user_id: &Uuid) -> Result<bool, AppError> {
-        let is_admin = sqlx::query_scalar::<_, bool>(
-            "SELECT COALESCE(is_admin, FALSE) FROM users WHERE id = $1"
-        )
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to check admin status: {}", e)))?;
+        let is_admin = sqlx::query_scalar::<_, bool>(ADMIN_CLAIM_IS_USER_ADMIN)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to check admin status: {}", e)))?;
 
         Ok(is_admin.unwrap_or(false))
     }
 
     /// Set a user as admin (for initial bootstrap only)
     pub async fn set_user_admin(pool: &PgPool, user_id: &Uuid) -> Result<(), AppError> {
-        sqlx::query("UPDATE users SET is_admin = TRUE WHERE id = $1")
+        sqlx::query(ADMIN_CLAIM_SET_USER_ADMIN)
             .bind(user_id)
             .execute(pool)
             .await
@@ -53,12 +58,10 @@ impl AdminClaimRepo {
 
     /// Count total admins
     pub async fn count_admins(pool: &PgPool) -> Result<i64, AppError> {
-        let count = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM users WHERE is_admin = TRUE"
-        )
-        .fetch_one(pool)
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to count admins: {}", e)))?;
+        let count = sqlx::query_scalar::<_, i64>(ADMIN_CLAIM_COUNT_ADMINS)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to count admins: {}", e)))?;
 
         Ok(count)
     }
@@ -70,32 +73,108 @@ impl AdminClaimRepo {
 
 pub struct AdminUserRepo;
 
+pub const ADMIN_USER_LIST_USERS: &str = r#"
+    SELECT
+        u.id,
+        u.email,
+        u.name,
+        u.image,
+        u.role,
+        u.approved,
+        u.tos_accepted,
+        u.last_activity_at,
+        u.created_at,
+        up.current_level as level,
+        up.total_xp
+    FROM users u
+    LEFT JOIN user_progress up ON u.id = up.user_id
+    ORDER BY u.created_at DESC
+    LIMIT 1000
+"#;
+
+pub const ADMIN_USER_GET_USER: &str = r#"
+    SELECT
+        u.id,
+        u.email,
+        u.name,
+        u.image,
+        u.role,
+        u.approved,
+        u.tos_accepted,
+        u.last_activity_at,
+        u.created_at,
+        up.current_level as level,
+        up.total_xp
+    FROM users u
+    LEFT JOIN user_progress up ON u.id = up.user_id
+    WHERE u.id = $1
+"#;
+
+pub const ADMIN_USER_DELETE_USER_DELETE_ACTIVITY_EVENTS: &str =
+    "DELETE FROM activity_events WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_USER_PROGRESS: &str =
+    "DELETE FROM user_progress WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_FOCUS_SESSIONS: &str =
+    "DELETE FROM focus_sessions WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_CALENDAR_EVENTS: &str =
+    "DELETE FROM calendar_events WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_USER_QUEST_PROGRESS: &str =
+    "DELETE FROM user_quest_progress WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_HABIT_COMPLETIONS: &str =
+    "DELETE FROM habit_completions WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_HABITS: &str = "DELETE FROM habits WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_GOAL_MILESTONES: &str =
+    "DELETE FROM goal_milestones WHERE goal_id IN (SELECT id FROM goals WHERE user_id = $1)";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_GOAL_LINKS: &str =
+    "DELETE FROM goal_links WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_GOALS: &str = "DELETE FROM goals WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_IDEAS: &str = "DELETE FROM ideas WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_READING_SESSIONS: &str =
+    "DELETE FROM reading_sessions WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_BOOKS: &str = "DELETE FROM books WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_FEEDBACK: &str = "DELETE FROM feedback WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_USER_ONBOARDING_RESPONSES: &str =
+    "DELETE FROM user_onboarding_responses WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_USER_ONBOARDING_STATE: &str =
+    "DELETE FROM user_onboarding_state WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_USER_SETTINGS: &str =
+    "DELETE FROM user_settings WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_POINTS_LEDGER: &str =
+    "DELETE FROM points_ledger WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_USER_WALLET: &str =
+    "DELETE FROM user_wallet WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_SESSIONS: &str = "DELETE FROM sessions WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_ACCOUNTS: &str = "DELETE FROM accounts WHERE user_id = $1";
+
+pub const ADMIN_USER_DELETE_USER_DELETE_USERS: &str = "DELETE FROM users WHERE id = $1";
+
 impl AdminUserRepo {
     /// List all users with their stats
     pub async fn list_users(pool: &PgPool) -> Result<AdminUsersResponse, AppError> {
-        let rows = sqlx::query_as::<_, AdminUserRow>(
-            r#"
-            SELECT 
-                u.id,
-                u.email,
-                u.name,
-                u.image,
-                u.role,
-                u.approved,
-                u.tos_accepted,
-                u.last_activity_at,
-                u.created_at,
-                up.current_level as level,
-                up.total_xp
-            FROM users u
-            LEFT JOIN user_progress up ON u.id = up.user_id
-            ORDER BY u.created_at DESC
-            LIMIT 1000
-            "#,
-        )
-        .fetch_all(pool)
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to fetch users: {}", e)))?;
+        let rows = sqlx::query_as::<_, AdminUserRow>(ADMIN_USER_LIST_USERS)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to fetch users: {}", e)))?;
 
         let total = rows.len() as i64;
         let users = rows.into_iter().map(|r| r.into()).collect();
@@ -108,29 +187,11 @@ impl AdminUserRepo {
         pool: &PgPool,
         user_id: Uuid,
     ) -> Result<Option<AdminUserWithStats>, AppError> {
-        let row = sqlx::query_as::<_, AdminUserRow>(
-            r#"
-            SELECT 
-                u.id,
-                u.email,
-                u.name,
-                u.image,
-                u.role,
-                u.approved,
-                u.tos_accepted,
-                u.last_activity_at,
-                u.created_at,
-                up.current_level as level,
-                up.total_xp
-            FROM users u
-            LEFT JOIN user_progress up ON u.id = up.user_id
-            WHERE u.id = $1
-            "#,
-        )
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to fetch user: {}", e)))?;
+        let row = sqlx::query_as::<_, AdminUserRow>(ADMIN_USER_GET_USER)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to fetch user: {}", e)))?;
 
         Ok(row.map(|r| r.into()))
     }
@@ -141,14 +202,14 @@ impl AdminUserRepo {
 
         // Delete in order to respect foreign keys
         // Activity and progress
-        sqlx::query("DELETE FROM activity_events WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_ACTIVITY_EVENTS)
             .bind(user_id)
             .execute(pool)
             .await
             .ok();
         tables_cleaned += 1;
 
-        sqlx::query("DELETE FROM user_progress WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_USER_PROGRESS)
             .bind(user_id)
             .execute(pool)
             .await
@@ -156,7 +217,7 @@ impl AdminUserRepo {
         tables_cleaned += 1;
 
         // Focus sessions
-        sqlx::query("DELETE FROM focus_sessions WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_FOCUS_SESSIONS)
             .bind(user_id)
             .execute(pool)
             .await
@@ -164,7 +225,7 @@ impl AdminUserRepo {
         tables_cleaned += 1;
 
         // Calendar
-        sqlx::query("DELETE FROM calendar_events WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_CALENDAR_EVENTS)
             .bind(user_id)
             .execute(pool)
             .await
@@ -172,7 +233,7 @@ impl AdminUserRepo {
         tables_cleaned += 1;
 
         // Quests
-        sqlx::query("DELETE FROM user_quest_progress WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_USER_QUEST_PROGRESS)
             .bind(user_id)
             .execute(pool)
             .await
@@ -180,14 +241,14 @@ impl AdminUserRepo {
         tables_cleaned += 1;
 
         // Habits
-        sqlx::query("DELETE FROM habit_completions WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_HABIT_COMPLETIONS)
             .bind(user_id)
             .execute(pool)
             .await
             .ok();
         tables_cleaned += 1;
 
-        sqlx::query("DELETE FROM habits WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_HABITS)
             .bind(user_id)
             .execute(pool)
             .await
@@ -195,14 +256,21 @@ impl AdminUserRepo {
         tables_cleaned += 1;
 
         // Goals
-        sqlx::query("DELETE FROM goal_milestones WHERE goal_id IN (SELECT id FROM goals WHERE user_id = $1)")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_GOAL_MILESTONES)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .ok();
+        tables_cleaned += 1;
+
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_GOAL_LINKS)
             .bind(user_id)
             .execute(pool)
             .await
             .ok();
         tables_cleaned += 1;
 
-        sqlx::query("DELETE FROM goals WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_GOALS)
             .bind(user_id)
             .execute(pool)
             .await
@@ -210,7 +278,7 @@ impl AdminUserRepo {
         tables_cleaned += 1;
 
         // Ideas
-        sqlx::query("DELETE FROM ideas WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_IDEAS)
             .bind(user_id)
             .execute(pool)
             .await
@@ -218,14 +286,14 @@ impl AdminUserRepo {
         tables_cleaned += 1;
 
         // Books
-        sqlx::query("DELETE FROM reading_sessions WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_READING_SESSIONS)
             .bind(user_id)
             .execute(pool)
             .await
             .ok();
         tables_cleaned += 1;
 
-        sqlx::query("DELETE FROM books WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_BOOKS)
             .bind(user_id)
             .execute(pool)
             .await
@@ -233,7 +301,7 @@ impl AdminUserRepo {
         tables_cleaned += 1;
 
         // Feedback
-        sqlx::query("DELETE FROM feedback WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_FEEDBACK)
             .bind(user_id)
             .execute(pool)
             .await
@@ -241,14 +309,14 @@ impl AdminUserRepo {
         tables_cleaned += 1;
 
         // Onboarding
-        sqlx::query("DELETE FROM user_onboarding_responses WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_USER_ONBOARDING_RESPONSES)
             .bind(user_id)
             .execute(pool)
             .await
             .ok();
         tables_cleaned += 1;
 
-        sqlx::query("DELETE FROM user_onboarding_state WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_USER_ONBOARDING_STATE)
             .bind(user_id)
             .execute(pool)
             .await
@@ -256,7 +324,7 @@ impl AdminUserRepo {
         tables_cleaned += 1;
 
         // User settings
-        sqlx::query("DELETE FROM user_settings WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_USER_SETTINGS)
             .bind(user_id)
             .execute(pool)
             .await
@@ -264,14 +332,14 @@ impl AdminUserRepo {
         tables_cleaned += 1;
 
         // Wallet
-        sqlx::query("DELETE FROM points_ledger WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_POINTS_LEDGER)
             .bind(user_id)
             .execute(pool)
             .await
             .ok();
         tables_cleaned += 1;
 
-        sqlx::query("DELETE FROM user_wallet WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_USER_WALLET)
             .bind(user_id)
             .execute(pool)
             .await
@@ -279,14 +347,14 @@ impl AdminUserRepo {
         tables_cleaned += 1;
 
         // Sessions and accounts
-        sqlx::query("DELETE FROM sessions WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_SESSIONS)
             .bind(user_id)
             .execute(pool)
             .await
             .ok();
         tables_cleaned += 1;
 
-        sqlx::query("DELETE FROM accounts WHERE user_id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_ACCOUNTS)
             .bind(user_id)
             .execute(pool)
             .await
@@ -294,7 +362,7 @@ impl AdminUserRepo {
         tables_cleaned += 1;
 
         // Finally delete the user
-        sqlx::query("DELETE FROM users WHERE id = $1")
+        sqlx::query(ADMIN_USER_DELETE_USER_DELETE_USERS)
             .bind(user_id)
             .execute(pool)
             .await
@@ -319,6 +387,59 @@ struct CountRow {
     count: Option<i64>,
 }
 
+pub const ADMIN_STATS_GET_STATS: &str = r#"
+    SELECT id, name, email, created_at, last_activity_at
+    FROM users
+    ORDER BY created_at DESC
+    LIMIT 10
+"#;
+
+pub const ADMIN_STATS_GET_USER_STATS_TOTAL: &str = "SELECT COUNT(*) as count FROM users";
+
+pub const ADMIN_STATS_GET_USER_STATS_TOS: &str =
+    "SELECT COUNT(*) as count FROM users WHERE tos_accepted = true";
+
+pub const ADMIN_STATS_GET_USER_STATS_ADMINS: &str =
+    "SELECT COUNT(*) as count FROM users WHERE role = 'admin'";
+
+pub const ADMIN_STATS_GET_USER_STATS_ACTIVE_7D: &str =
+    "SELECT COUNT(*) as count FROM users WHERE last_activity_at > NOW() - INTERVAL '7 days'";
+
+pub const ADMIN_STATS_GET_USER_STATS_ACTIVE_30D: &str =
+    "SELECT COUNT(*) as count FROM users WHERE last_activity_at > NOW() - INTERVAL '30 days'";
+
+pub const ADMIN_STATS_GET_CONTENT_STATS_EXERCISES: &str = "SELECT COUNT(*) as count FROM exercises";
+
+pub const ADMIN_STATS_GET_CONTENT_STATS_UNIVERSAL_QUESTS: &str =
+    "SELECT COUNT(*) as count FROM universal_quests";
+
+pub const ADMIN_STATS_GET_CONTENT_STATS_USER_QUESTS: &str =
+    "SELECT COUNT(*) as count FROM user_quests";
+
+pub const ADMIN_STATS_GET_CONTENT_STATS_MARKET_ITEMS: &str =
+    "SELECT COUNT(*) as count FROM market_items";
+
+pub const ADMIN_STATS_GET_ACTIVITY_STATS_FOCUS: &str =
+    "SELECT COUNT(*) as count FROM focus_sessions";
+
+pub const ADMIN_STATS_GET_ACTIVITY_STATS_COMPLETED: &str =
+    "SELECT COUNT(*) as count FROM focus_sessions WHERE status = 'completed'";
+
+pub const ADMIN_STATS_GET_ACTIVITY_STATS_HABITS: &str =
+    "SELECT COUNT(*) as count FROM habit_completions";
+
+pub const ADMIN_STATS_GET_ACTIVITY_STATS_GOALS: &str = "SELECT COUNT(*) as count FROM goals";
+
+pub const ADMIN_STATS_GET_ACTIVITY_STATS_IDEAS: &str = "SELECT COUNT(*) as count FROM ideas";
+
+pub const ADMIN_STATS_GET_ACTIVITY_STATS_BOOKS: &str = "SELECT COUNT(*) as count FROM books";
+
+pub const ADMIN_STATS_GET_GAMIFICATION_STATS_PURCHASES: &str =
+    "SELECT COUNT(*) as count FROM user_purchases";
+
+pub const ADMIN_STATS_GET_GAMIFICATION_STATS_ACHIEVEMENTS: &str =
+    "SELECT COUNT(*) as count FROM user_achievements";
+
 impl AdminStatsRepo {
     /// Get comprehensive platform statistics
     pub async fn get_stats(pool: &PgPool) -> Result<AdminStatsResponse, AppError> {
@@ -335,17 +456,10 @@ impl AdminStatsRepo {
         let gamification_stats = Self::get_gamification_stats(pool).await.unwrap_or_default();
 
         // Recent users
-        let recent_users = sqlx::query_as::<_, RecentUser>(
-            r#"
-            SELECT id, name, email, created_at, last_activity_at
-            FROM users
-            ORDER BY created_at DESC
-            LIMIT 10
-            "#,
-        )
-        .fetch_all(pool)
-        .await
-        .unwrap_or_default();
+        let recent_users = sqlx::query_as::<_, RecentUser>(ADMIN_STATS_GET_STATS)
+            .fetch_all(pool)
+            .await
+            .unwrap_or_default();
 
         Ok(AdminStatsResponse {
             users: user_stats,
@@ -358,43 +472,35 @@ impl AdminStatsRepo {
     }
 
     async fn get_user_stats(pool: &PgPool) -> Result<UserStats, AppError> {
-        let total = sqlx::query_as::<_, CountRow>("SELECT COUNT(*) as count FROM users")
+        let total = sqlx::query_as::<_, CountRow>(ADMIN_STATS_GET_USER_STATS_TOTAL)
+            .fetch_one(pool)
+            .await
+            .map(|r| r.count.unwrap_or(0))
+            .unwrap_or(0);
+
+        let tos = sqlx::query_as::<_, CountRow>(ADMIN_STATS_GET_USER_STATS_TOS)
+            .fetch_one(pool)
+            .await
+            .map(|r| r.count.unwrap_or(0))
+            .unwrap_or(0);
+
+        let admins = sqlx::query_as::<_, CountRow>(ADMIN_STATS_GET_USER_STATS_ADMINS)
+            .fetch_one(pool)
+            .await
+            .map(|r| r.count.unwrap_or(0))
+            .unwrap_or(0);
+
+        let active_7d = sqlx::query_as::<_, CountRow>(ADMIN_STATS_GET_USER_STATS_ACTIVE_7D)
             .fetch_one(pool)
             .await
             .map(|r| r.count.unwrap_or(0))
             .unwrap_or(0);
 
-        let tos = sqlx::query_as::<_, CountRow>(
-            "SELECT COUNT(*) as count FROM users WHERE tos_accepted = true",
-        )
-        .fetch_one(pool)
-        .await
-        .map(|r| r.count.unwrap_or(0))
-        .unwrap_or(0);
-
-        let admins = sqlx::query_as::<_, CountRow>(
-            "SELECT COUNT(*) as count FROM users WHERE role = 'admin'",
-        )
-        .fetch_one(pool)
-        .await
-        .map(|r| r.count.unwrap_or(0))
-        .unwrap_or(0);
-
-        let active_7d = sqlx::query_as::<_, CountRow>(
-            "SELECT COUNT(*) as count FROM users WHERE last_activity_at > NOW() - INTERVAL '7 days'"
-        )
-        .fetch_one(pool)
-        .await
-        .map(|r| r.count.unwrap_or(0))
-        .unwrap_or(0);
-
-        let active_30d = sqlx::query_as::<_, CountRow>(
-            "SELECT COUNT(*) as count FROM users WHERE last_activity_at > NOW() - INTERVAL '30 days'"
-        )
-        .fetch_one(pool)
-        .await
-        .map(|r| r.count.unwrap_or(0))
-        .unwrap_or(0);
+        let active_30d = sqlx::query_as::<_, CountRow>(ADMIN_STATS_GET_USER_STATS_ACTIVE_30D)
+            .fetch_one(pool)
+            .await
+            .map(|r| r.count.unwrap_or(0))
+            .unwrap_or(0);
 
         Ok(UserStats {
             total_users: total,
@@ -406,27 +512,27 @@ impl AdminStatsRepo {
     }
 
     async fn get_content_stats(pool: &PgPool) -> Result<ContentStats, AppError> {
-        let exercises = sqlx::query_as::<_, CountRow>("SELECT COUNT(*) as count FROM exercises")
+        let exercises = sqlx::query_as::<_, CountRow>(ADMIN_STATS_GET_CONTENT_STATS_EXERCISES)
             .fetch_one(pool)
             .await
             .map(|r| r.count.unwrap_or(0))
             .unwrap_or(0);
 
         let universal_quests =
-            sqlx::query_as::<_, CountRow>("SELECT COUNT(*) as count FROM universal_quests")
+            sqlx::query_as::<_, CountRow>(ADMIN_STATS_GET_CONTENT_STATS_UNIVERSAL_QUESTS)
                 .fetch_one(pool)
                 .await
                 .map(|r| r.count.unwrap_or(0))
                 .unwrap_or(0);
 
-        let user_quests = sqlx::query_as::<_, CountRow>("SELECT COUNT(*) as count FROM quests")
+        let user_quests = sqlx::query_as::<_, CountRow>(ADMIN_STATS_GET_CONTENT_STATS_USER_QUESTS)
             .fetch_one(pool)
             .await
             .map(|r| r.count.unwrap_or(0))
             .unwrap_or(0);
 
         let market_items =
-            sqlx::query_as::<_, CountRow>("SELECT COUNT(*) as count FROM market_items")
+            sqlx::query_as::<_, CountRow>(ADMIN_STATS_GET_CONTENT_STATS_MARKET_ITEMS)
                 .fetch_one(pool)
                 .await
                 .map(|r| r.count.unwrap_or(0))
@@ -444,39 +550,37 @@ impl AdminStatsRepo {
     }
 
     async fn get_activity_stats(pool: &PgPool) -> Result<ActivityStats, AppError> {
-        let focus = sqlx::query_as::<_, CountRow>("SELECT COUNT(*) as count FROM focus_sessions")
+        let focus = sqlx::query_as::<_, CountRow>(ADMIN_STATS_GET_ACTIVITY_STATS_FOCUS)
             .fetch_one(pool)
             .await
             .map(|r| r.count.unwrap_or(0))
             .unwrap_or(0);
 
-        let completed = sqlx::query_as::<_, CountRow>(
-            "SELECT COUNT(*) as count FROM focus_sessions WHERE status = 'completed'",
-        )
-        .fetch_one(pool)
-        .await
-        .map(|r| r.count.unwrap_or(0))
-        .unwrap_or(0);
+        let completed = sqlx::query_as::<_, CountRow>(ADMIN_STATS_GET_ACTIVITY_STATS_COMPLETED)
+            .fetch_one(pool)
+            .await
+            .map(|r| r.count.unwrap_or(0))
+            .unwrap_or(0);
 
-        let habits = sqlx::query_as::<_, CountRow>("SELECT COUNT(*) as count FROM habit_completions")
+        let habits = sqlx::query_as::<_, CountRow>(ADMIN_STATS_GET_ACTIVITY_STATS_HABITS)
             .fetch_one(pool)
             .await
             .map(|r| r.count.unwrap_or(0))
             .unwrap_or(0);
 
-        let goals = sqlx::query_as::<_, CountRow>("SELECT COUNT(*) as count FROM goals")
+        let goals = sqlx::query_as::<_, CountRow>(ADMIN_STATS_GET_ACTIVITY_STATS_GOALS)
             .fetch_one(pool)
             .await
             .map(|r| r.count.unwrap_or(0))
             .unwrap_or(0);
 
-        let ideas = sqlx::query_as::<_, CountRow>("SELECT COUNT(*) as count FROM ideas")
+        let ideas = sqlx::query_as::<_, CountRow>(ADMIN_STATS_GET_ACTIVITY_STATS_IDEAS)
             .fetch_one(pool)
             .await
             .map(|r| r.count.unwrap_or(0))
             .unwrap_or(0);
 
-        let books = sqlx::query_as::<_, CountRow>("SELECT COUNT(*) as count FROM books")
+        let books = sqlx::query_as::<_, CountRow>(ADMIN_STATS_GET_ACTIVITY_STATS_BOOKS)
             .fetch_one(pool)
             .await
             .map(|r| r.count.unwrap_or(0))
@@ -498,14 +602,14 @@ impl AdminStatsRepo {
 
     async fn get_gamification_stats(pool: &PgPool) -> Result<GamificationStats, AppError> {
         let purchases =
-            sqlx::query_as::<_, CountRow>("SELECT COUNT(*) as count FROM user_purchases")
+            sqlx::query_as::<_, CountRow>(ADMIN_STATS_GET_GAMIFICATION_STATS_PURCHASES)
                 .fetch_one(pool)
                 .await
                 .map(|r| r.count.unwrap_or(0))
                 .unwrap_or(0);
 
         let achievements =
-            sqlx::query_as::<_, CountRow>("SELECT COUNT(*) as count FROM user_achievements")
+            sqlx::query_as::<_, CountRow>(ADMIN_STATS_GET_GAMIFICATION_STATS_ACHIEVEMENTS)
                 .fetch_one(pool)
                 .await
                 .map(|r| r.count.unwrap_or(0))
@@ -526,35 +630,56 @@ impl AdminStatsRepo {
 
 pub struct AdminFeedbackRepo;
 
+pub const ADMIN_FEEDBACK_LIST_FEEDBACK: &str = r#"
+    SELECT
+        f.id,
+        f.user_id,
+        u.email as user_email,
+        f.feedback_type,
+        f.title,
+        f.description,
+        f.status,
+        COALESCE(f.priority, 'normal') as priority,
+        f.admin_response,
+        f.resolved_by,
+        f.resolved_at,
+        f.created_at
+    FROM feedback f
+    LEFT JOIN users u ON f.user_id = u.id
+    ORDER BY
+        CASE f.status WHEN 'open' THEN 0 WHEN 'in_progress' THEN 1 ELSE 2 END,
+        f.created_at DESC
+    LIMIT 500
+"#;
+
+pub const ADMIN_FEEDBACK_UPDATE_FEEDBACK_UPDATE_FEEDBACK: &str = r#"
+    UPDATE feedback SET
+        status = COALESCE($2, status),
+        priority = COALESCE($3, priority),
+        admin_response = COALESCE($4, admin_response),
+        resolved_by = CASE WHEN $2 = 'resolved' THEN $5 ELSE resolved_by END,
+        resolved_at = CASE WHEN $2 = 'resolved' THEN NOW() ELSE resolved_at END,
+        updated_at = NOW()
+    WHERE id = $1
+"#;
+
+pub const ADMIN_FEEDBACK_UPDATE_FEEDBACK_FEEDBACK: &str = r#"
+    SELECT
+        f.id, f.user_id, u.email as user_email, f.feedback_type, f.title,
+        f.description, f.status, COALESCE(f.priority, 'normal') as priority,
+        f.admin_response, f.resolved_by, f.resolved_at, f.created_at
+    FROM feedback f
+    LEFT JOIN users u ON f.user_id = u.id
+    WHERE f.id = $1
+"#;
+
 impl AdminFeedbackRepo {
     /// List all feedback with user info
     pub async fn list_feedback(pool: &PgPool) -> Result<AdminFeedbackResponse, AppError> {
-        let feedback = sqlx::query_as::<_, AdminFeedback>(
-            r#"
-            SELECT 
-                f.id,
-                f.user_id,
-                u.email as user_email,
-                f.feedback_type,
-                f.title,
-                f.description,
-                f.status,
-                f.priority,
-                f.admin_response,
-                f.resolved_by,
-                f.resolved_at,
-                f.created_at
-            FROM feedback f
-            LEFT JOIN users u ON f.user_id = u.id
-            ORDER BY 
-                CASE f.status WHEN 'open' THEN 0 WHEN 'in_progress' THEN 1 ELSE 2 END,
-                f.created_at DESC
-            LIMIT 500
-            "#,
-        )
-        .fetch_all(pool)
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to fetch feedback: {}", e)))?;
+        let feedback = sqlx::query_as::<_, AdminFeedback>(ADMIN_FEEDBACK_LIST_FEEDBACK)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to fetch feedback: {}", e)))?;
 
         Ok(AdminFeedbackResponse { feedback })
     }
@@ -566,55 +691,26 @@ impl AdminFeedbackRepo {
         admin_id: Uuid,
         update: UpdateFeedbackRequest,
     ) -> Result<AdminFeedback, AppError> {
-        // Build update query dynamically
-        let mut set_parts = vec!["updated_at = NOW()".to_string()];
-
-        if let Some(status) = &update.status {
-            set_parts.push(format!("status = '{}'", status));
-            if status == "resolved" {
-                set_parts.push(format!("resolved_by = '{}'", admin_id));
-                set_parts.push("resolved_at = NOW()".to_string());
-            }
-        }
-
-        if let Some(priority) = &update.priority {
-            set_parts.push(format!("priority = '{}'", priority));
-        }
+        let result = sqlx::query(ADMIN_FEEDBACK_UPDATE_FEEDBACK_UPDATE_FEEDBACK)
+            .bind(feedback_id)
+            .bind(&update.status)
+            .bind(&update.priority)
+            .bind(&update.admin_response)
+            .bind(admin_id)
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to update feedback: {}", e)))?;
 
-        if let Some(response) = &update.admin_response {
-            set_parts.push(format!(
-                "admin_response = '{}'",
-                response.replace('\'', "''")
-            ));
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Feedback not found".to_string()));
         }
 
-        let query = format!(
-            "UPDATE feedback SET {} WHERE id = $1 RETURNING *",
-            set_parts.join(", ")
-        );
-
         // Re-fetch with join since we can't return user_email from UPDATE
-        sqlx::query(&query)
+        let feedback = sqlx::query_as::<_, AdminFeedback>(ADMIN_FEEDBACK_UPDATE_FEEDBACK_FEEDBACK)
             .bind(feedback_id)
-            .execute(pool)
+            .fetch_one(pool)
             .await
-            .map_err(|e| AppError::Internal(format!("Failed to update feedback: {}", e)))?;
-
-        let feedback = sqlx::query_as::<_, AdminFeedback>(
-            r#"
-            SELECT 
-                f.id, f.user_id, u.email as user_email, f.feedback_type, f.title,
-                f.description, f.status, f.priority, f.admin_response,
-                f.resolved_by, f.resolved_at, f.created_at
-            FROM feedback f
-            LEFT JOIN users u ON f.user_id = u.id
-            WHERE f.id = $1
-            "#,
-        )
-        .bind(feedback_id)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to fetch updated feedback: {}", e)))?;
+            .map_err(|e| AppError::Internal(format!("Failed to fetch updated feedback: {}", e)))?;
 
         Ok(feedback)
     }
@@ -626,41 +722,75 @@ impl AdminFeedbackRepo {
 
 pub struct AdminQuestRepo;
 
+pub const ADMIN_QUEST_LIST_QUESTS: &str = r#"
+    SELECT
+        id, key, title as name, description, type as quest_type, category,
+        xp_reward, coin_reward, skill_key, skill_star_reward,
+        is_recurring, recurrence_period, is_active, sort_order, created_at
+    FROM universal_quests
+    ORDER BY sort_order, created_at DESC
+"#;
+
+pub const ADMIN_QUEST_GET_QUEST: &str = r#"
+    SELECT id, key, title as name, description, type as quest_type, category,
+           xp_reward, coin_reward, skill_key, skill_star_reward,
+           is_recurring, recurrence_period, is_active, sort_order, created_at
+    FROM universal_quests
+    WHERE id = $1
+"#;
+
+pub const ADMIN_QUEST_CREATE_QUEST: &str = r#"
+    INSERT INTO universal_quests
+        (id, key, title, description, type, category, xp_reward, coin_reward,
+         skill_key, skill_star_reward, is_recurring, recurrence_period, is_active,
+         sort_order, created_by, created_at, updated_at)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, true, 0, $13, NOW(), NOW())
+    RETURNING id, key, title as name, description, type as quest_type, category,
+              xp_reward, coin_reward, skill_key, skill_star_reward, is_recurring,
+              recurrence_period, is_active, sort_order, created_at
+"#;
+
+pub const ADMIN_QUEST_UPDATE_QUEST: &str = r#"
+    UPDATE universal_quests SET
+        key = COALESCE($2, key),
+        title = COALESCE($3, title),
+        description = COALESCE($4, description),
+        type = COALESCE($5, type),
+        category = COALESCE($6, category),
+        xp_reward = COALESCE($7, xp_reward),
+        coin_reward = COALESCE($8, coin_reward),
+        skill_key = COALESCE($9, skill_key),
+        skill_star_reward = COALESCE($10, skill_star_reward),
+        is_recurring = COALESCE($11, is_recurring),
+        recurrence_period = COALESCE($12, recurrence_period),
+        is_active = COALESCE($13, is_active),
+        updated_at = NOW()
+    WHERE id = $1
+    RETURNING id, key, title as name, description, type as quest_type, category,
+              xp_reward, coin_reward, skill_key, skill_star_reward, is_recurring,
+              recurrence_period, is_active, sort_order, created_at
+"#;
+
+pub const ADMIN_QUEST_DELETE_QUEST: &str = "DELETE FROM universal_quests WHERE id = $1";
+
 impl AdminQuestRepo {
     /// List all universal quests
     pub async fn list_quests(pool: &PgPool) -> Result<AdminQuestsResponse, AppError> {
-        let quests = sqlx::query_as::<_, AdminQuest>(
-            r#"
-            SELECT 
-                id, key, name, description, quest_type, category,
-                xp_reward, coin_reward, skill_key, skill_star_reward,
-                is_recurring, recurrence_period, is_active, sort_order, created_at
-            FROM universal_quests
-            ORDER BY sort_order, created_at DESC
-            "#,
-        )
-        .fetch_all(pool)
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to fetch quests: {}", e)))?;
+        let quests = sqlx::query_as::<_, AdminQuest>(ADMIN_QUEST_LIST_QUESTS)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to fetch quests: {}", e)))?;
 
         Ok(AdminQuestsResponse { quests })
     }
 
     /// Get a single quest
     pub async fn get_quest(pool: &PgPool, quest_id: Uuid) -> Result<Option<AdminQuest>, AppError> {
-        let quest = sqlx::query_as::<_, AdminQuest>(
-            r#"
-            SELECT id, key, name, description, quest_type, category,
-                   xp_reward, coin_reward, skill_key, skill_star_reward,
-                   is_recurring, recurrence_period, is_active, sort_order, created_at
-            FROM universal_quests
-            WHERE id = $1
-            "#,
-        )
-        .bind(quest_id)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to fetch quest: {}", e)))?;
+        let quest = sqlx::query_as::<_, AdminQuest>(ADMIN_QUEST_GET_QUEST)
+            .bind(quest_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to fetch quest: {}", e)))?;
 
         Ok(quest)
     }
@@ -678,31 +808,23 @@ impl AdminQuestRepo {
         let skill_star_reward = request.skill_star_reward.unwrap_or(1);
         let is_recurring = request.is_recurring.unwrap_or(false);
 
-        let quest = sqlx::query_as::<_, AdminQuest>(
-            r#"
-            INSERT INTO universal_quests 
-                (id, key, name, description, quest_type, category, xp_reward, coin_reward, 
-                 skill_key, skill_star_reward, is_recurring, recurrence_period, is_active, sort_order, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, true, 0, NOW(), NOW())
-            RETURNING id, key, name, description, quest_type, category, xp_reward, coin_reward,
-                      skill_key, skill_star_reward, is_recurring, recurrence_period, is_active, sort_order, created_at
-            "#,
-        )
-        .bind(id)
-        .bind(&request.key)
-        .bind(&request.name)
-        .bind(&request.description)
-        .bind(&quest_type)
-        .bind(&request.category)
-        .bind(xp_reward)
-        .bind(coin_reward)
-        .bind(&request.skill_key)
-        .bind(skill_star_reward)
-        .bind(is_recurring)
-        .bind(&request.recurrence_period)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to create quest: {}", e)))?;
+        let quest = sqlx::query_as::<_, AdminQuest>(ADMIN_QUEST_CREATE_QUEST)
+            .bind(id)
+            .bind(&request.key)
+            .bind(&request.name)
+            .bind(&request.description)
+            .bind(&quest_type)
+            .bind(&request.category)
+            .bind(xp_reward)
+            .bind(coin_reward)
+            .bind(&request.skill_key)
+            .bind(skill_star_reward)
+            .bind(is_recurring)
+            .bind(&request.recurrence_period)
+            .bind(admin_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to create quest: {}", e)))?;
 
         Ok(quest)
     }
@@ -713,57 +835,20 @@ impl AdminQuestRepo {
         quest_id: Uuid,
         request: UpdateQuestRequest,
     ) -> Result<AdminQuest, AppError> {
-        let mut set_parts = vec!["updated_at = NOW()".to_string()];
-
-        if let Some(key) = &request.key {
-            set_parts.push(format!("key = '{}'", key.replace('\'', "''")));
-        }
-        if let Some(name) = &request.name {
-            set_parts.push(format!("name = '{}'", name.replace('\'', "''")));
-        }
-        if let Some(desc) = &request.description {
-            set_parts.push(format!("description = '{}'", desc.replace('\'', "''")));
-        }
-        if let Some(qt) = &request.quest_type {
-            set_parts.push(format!("quest_type = '{}'", qt));
-        }
-        if let Some(cat) = &request.category {
-            set_parts.push(format!("category = '{}'", cat));
-        }
-        if let Some(xp) = request.xp_reward {
-            set_parts.push(format!("xp_reward = {}", xp));
-        }
-        if let Some(coin) = request.coin_reward {
-            set_parts.push(format!("coin_reward = {}", coin));
-        }
-        if let Some(sk) = &request.skill_key {
-            set_parts.push(format!("skill_key = '{}'", sk));
-        }
-        if let Some(ssr) = request.skill_star_reward {
-            set_parts.push(format!("skill_star_reward = {}", ssr));
-        }
-        if let Some(ir) = request.is_recurring {
-            set_parts.push(format!("is_recurring = {}", ir));
-        }
-        if let Some(rp) = &request.recurrence_period {
-            set_parts.push(format!("recurrence_period = '{}'", rp));
-        }
-        if let Some(active) = request.is_active {
-            set_parts.push(format!("is_active = {}", active));
-        }
-
-        let query = format!(
-            r#"
-            UPDATE universal_quests SET {}
-            WHERE id = $1
-            RETURNING id, key, name, description, quest_type, category, xp_reward, coin_reward,
-                      skill_key, skill_star_reward, is_recurring, recurrence_period, is_active, sort_order, created_at
-            "#,
-            set_parts.join(", ")
-        );
-
-        let quest = sqlx::query_as::<_, AdminQuest>(&query)
+        let quest = sqlx::query_as::<_, AdminQuest>(ADMIN_QUEST_UPDATE_QUEST)
             .bind(quest_id)
+            .bind(&request.key)
+            .bind(&request.name)
+            .bind(&request.description)
+            .bind(&request.quest_type)
+            .bind(&request.category)
+            .bind(request.xp_reward)
+            .bind(request.coin_reward)
+            .bind(&request.skill_key)
+            .bind(request.skill_star_reward)
+            .bind(request.is_recurring)
+            .bind(&request.recurrence_period)
+            .bind(request.is_active)
             .fetch_one(pool)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to update quest: {}", e)))?;
@@ -773,7 +858,7 @@ impl AdminQuestRepo {
 
     /// Delete a quest
     pub async fn delete_quest(pool: &PgPool, quest_id: Uuid) -> Result<bool, AppError> {
-        let result = sqlx::query("DELETE FROM universal_quests WHERE id = $1")
+        let result = sqlx::query(ADMIN_QUEST_DELETE_QUEST)
             .bind(quest_id)
             .execute(pool)
             .await
@@ -789,39 +874,77 @@ impl AdminQuestRepo {
 
 pub struct AdminSkillRepo;
 
+pub const ADMIN_SKILL_LIST_SKILLS: &str = r#"
+    SELECT
+        key as id, name, description, color, max_level,
+        xp_scaling_base, xp_scaling_multiplier, sort_order as display_order, is_active
+    FROM skill_definitions
+    ORDER BY sort_order ASC
+"#;
+
+pub const ADMIN_SKILL_GET_SKILL: &str = r#"
+    SELECT key as id, name, description, color, max_level,
+           xp_scaling_base, xp_scaling_multiplier, sort_order as display_order, is_active
+    FROM skill_definitions
+    WHERE key = $1
+"#;
+
+pub const ADMIN_SKILL_UPSERT_SKILL_ORDER_ROW: &str =
+    "SELECT COALESCE(MAX(sort_order), 0) + 1 as count FROM skill_definitions";
+
+pub const ADMIN_SKILL_UPSERT_SKILL_SKILL: &str = r#"
+    INSERT INTO skill_definitions
+        (key, name, description, color, max_level, xp_scaling_base, xp_scaling_multiplier,
+         sort_order, is_active, created_at, updated_at)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, true, NOW(), NOW())
+    ON CONFLICT (key) DO UPDATE SET
+        name = EXCLUDED.name,
+        description = EXCLUDED.description,
+        color = EXCLUDED.color,
+        max_level = EXCLUDED.max_level,
+        xp_scaling_base = EXCLUDED.xp_scaling_base,
+        xp_scaling_multiplier = EXCLUDED.xp_scaling_multiplier,
+        updated_at = NOW()
+    RETURNING key as id, name, description, color, max_level, xp_scaling_base,
+              xp_scaling_multiplier, sort_order as display_order, is_active
+"#;
+
+pub const ADMIN_SKILL_UPDATE_SKILL: &str = r#"
+    UPDATE skill_definitions SET
+        name = COALESCE($2, name),
+        description = COALESCE($3, description),
+        color = COALESCE($4, color),
+        max_level = COALESCE($5, max_level),
+        xp_scaling_base = COALESCE($6, xp_scaling_base),
+        xp_scaling_multiplier = COALESCE($7, xp_scaling_multiplier),
+        is_active = COALESCE($8, is_active),
+        sort_order = COALESCE($9, sort_order),
+        updated_at = NOW()
+    WHERE key = $1
+    RETURNING key as id, name, description, color, max_level, xp_scaling_base,
+              xp_scaling_multiplier, sort_order as display_order, is_active
+"#;
+
+pub const ADMIN_SKILL_DELETE_SKILL: &str = "DELETE FROM skill_definitions WHERE key = $1";
+
 impl AdminSkillRepo {
     /// List all skill definitions
     pub async fn list_skills(pool: &PgPool) -> Result<AdminSkillsResponse, AppError> {
-        let skills = sqlx::query_as::<_, AdminSkill>(
-            r#"
-            SELECT 
-                id, name, description, color, max_level,
-                xp_scaling_base, xp_scaling_multiplier, display_order, is_active
-            FROM skill_definitions
-            ORDER BY display_order ASC
-            "#,
-        )
-        .fetch_all(pool)
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to fetch skills: {}", e)))?;
+        let skills = sqlx::query_as::<_, AdminSkill>(ADMIN_SKILL_LIST_SKILLS)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to fetch skills: {}", e)))?;
 
         Ok(AdminSkillsResponse { skills })
     }
 
     /// Get a single skill
     pub async fn get_skill(pool: &PgPool, skill_id: &str) -> Result<Option<AdminSkill>, AppError> {
-        let skill = sqlx::query_as::<_, AdminSkill>(
-            r#"
-            SELECT id, name, description, color, max_level,
-                   xp_scaling_base, xp_scaling_multiplier, display_order, is_active
-            FROM skill_definitions
-            WHERE id = $1
-            "#,
-        )
-        .bind(skill_id)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to fetch skill: {}", e)))?;
+        let skill = sqlx::query_as::<_, AdminSkill>(ADMIN_SKILL_GET_SKILL)
+            .bind(skill_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to fetch skill: {}", e)))?;
 
         Ok(skill)
     }
@@ -837,41 +960,24 @@ impl AdminSkillRepo {
         let xp_mult = request.xp_scaling_multiplier.unwrap_or(1.5);
 
         // Get next display order
-        let order_row = sqlx::query_as::<_, CountRow>(
-            "SELECT COALESCE(MAX(display_order), 0) + 1 as count FROM skill_definitions",
-        )
-        .fetch_one(pool)
-        .await
-        .map(|r| r.count.unwrap_or(0) as i32)
-        .unwrap_or(0);
-
-        let skill = sqlx::query_as::<_, AdminSkill>(
-            r#"
-            INSERT INTO skill_definitions 
-                (id, name, description, color, max_level, xp_scaling_base, xp_scaling_multiplier, display_order, is_active, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, true, NOW(), NOW())
-            ON CONFLICT (id) DO UPDATE SET
-                name = EXCLUDED.name,
-                description = EXCLUDED.description,
-                color = EXCLUDED.color,
-                max_level = EXCLUDED.max_level,
-                xp_scaling_base = EXCLUDED.xp_scaling_base,
-                xp_scaling_multiplier = EXCLUDED.xp_scaling_multiplier,
-                updated_at = NOW()
-            RETURNING id, name, description, color, max_level, xp_scaling_base, xp_scaling_multiplier, display_order, is_active
-            "#,
-        )
-        .bind(&request.id)
-        .bind(&request.name)
-        .bind(&request.description)
-        .bind(&color)
-        .bind(max_level)
-        .bind(xp_base)
-        .bind(xp_mult)
-        .bind(order_row)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to upsert skill: {}", e)))?;
+        let order_row = sqlx::query_as::<_, CountRow>(ADMIN_SKILL_UPSERT_SKILL_ORDER_ROW)
+            .fetch_one(pool)
+            .await
+            .map(|r| r.count.unwrap_or(0) as i32)
+            .unwrap_or(0);
+
+        let skill = sqlx::query_as::<_, AdminSkill>(ADMIN_SKILL_UPSERT_SKILL_SKILL)
+            .bind(&request.id)
+            .bind(&request.name)
+            .bind(&request.description)
+            .bind(&color)
+            .bind(max_level)
+            .bind(xp_base)
+            .bind(xp_mult)
+            .bind(order_row)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to upsert skill: {}", e)))?;
 
         Ok(skill)
     }
@@ -882,44 +988,16 @@ impl AdminSkillRepo {
         skill_id: &str,
         request: UpdateSkillRequest,
     ) -> Result<AdminSkill, AppError> {
-        let mut set_parts = vec!["updated_at = NOW()".to_string()];
-
-        if let Some(name) = &request.name {
-            set_parts.push(format!("name = '{}'", name.replace('\'', "''")));
-        }
-        if let Some(desc) = &request.description {
-            set_parts.push(format!("description = '{}'", desc.replace('\'', "''")));
-        }
-        if let Some(color) = &request.color {
-            set_parts.push(format!("color = '{}'", color));
-        }
-        if let Some(ml) = request.max_level {
-            set_parts.push(format!("max_level = {}", ml));
-        }
-        if let Some(xb) = request.xp_scaling_base {
-            set_parts.push(format!("xp_scaling_base = {}", xb));
-        }
-        if let Some(xm) = request.xp_scaling_multiplier {
-            set_parts.push(format!("xp_scaling_multiplier = {}", xm));
-        }
-        if let Some(active) = request.is_active {
-            set_parts.push(format!("is_active = {}", active));
-        }
-        if let Some(order) = request.display_order {
-            set_parts.push(format!("display_order = {}", order));
-        }
-
-        let query = format!(
-            r#"
-            UPDATE skill_definitions SET {}
-            WHERE id = $1
-            RETURNING id, name, description, color, max_level, xp_scaling_base, xp_scaling_multiplier, display_order, is_active
-            "#,
-            set_parts.join(", ")
-        );
-
-        let skill = sqlx::query_as::<_, AdminSkill>(&query)
+        let skill = sqlx::query_as::<_, AdminSkill>(ADMIN_SKILL_UPDATE_SKILL)
             .bind(skill_id)
+            .bind(&request.name)
+            .bind(&request.description)
+            .bind(&request.color)
+            .bind(request.max_level)
+            .bind(request.xp_scaling_base)
+            .bind(request.xp_scaling_multiplier)
+            .bind(request.is_active)
+            .bind(request.display_order)
             .fetch_one(pool)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to update skill: {}", e)))?;
@@ -929,7 +1007,7 @@ impl AdminSkillRepo {
 
     /// Delete a skill
     pub async fn delete_skill(pool: &PgPool, skill_id: &str) -> Result<bool, AppError> {
-        let result = sqlx::query("DELETE FROM skill_definitions WHERE id = $1")
+        let result = sqlx::query(ADMIN_SKILL_DELETE_SKILL)
             .bind(skill_id)
             .execute(pool)
             .await
@@ -945,38 +1023,45 @@ impl AdminSkillRepo {
 
 pub struct AdminDbRepo;
 
+/// Key tables whose row counts are reported by the health check
+pub const HEALTH_TABLES: &[&str] = &[
+    "users",
+    "sessions",
+    "accounts",
+    "focus_sessions",
+    "habits",
+    "goals",
+    "user_quests",
+    "universal_quests",
+    "feedback",
+    "ideas",
+    "books",
+];
+
+/// Row count query for one of `HEALTH_TABLES`
+pub fn count_table_sql(table: &str) -> String {
+    format!("SELECT COUNT(*) as count FROM {}", table)
+}
+
+pub const ADMIN_DB_GET_HEALTH: &str = "SELECT 1";
+
 impl AdminDbRepo {
     /// Check database health and get table stats
     pub async fn get_health(pool: &PgPool) -> Result<DbHealthResponse, AppError> {
         // Verify connectivity
-        sqlx::query("SELECT 1")
+        sqlx::query(ADMIN_DB_GET_HEALTH)
             .fetch_one(pool)
             .await
             .map_err(|e| AppError::Internal(format!("Database unhealthy: {}", e)))?;
 
         // Get table counts for key tables
-        let tables = vec![
-            "users",
-            "sessions",
-            "accounts",
-            "focus_sessions",
-            "habits",
-            "goals",
-            "quests",
-            "universal_quests",
-            "feedback",
-            "ideas",
-            "books",
-        ];
-
         let mut table_infos = Vec::new();
-        for table in tables {
-            let count =
-                sqlx::query_as::<_, CountRow>(&format!("SELECT COUNT(*) as count FROM {}", table))
-                    .fetch_one(pool)
-                    .await
-                    .map(|r| r.count.unwrap_or(0))
-                    .unwrap_or(0);
+        for table in HEALTH_TABLES {
+            let count = sqlx::query_as::<_, CountRow>(&count_table_sql(table))
+                .fetch_one(pool)
+                .await
+                .map(|r| r.count.unwrap_or(0))
+                .unwrap_or(0);
 
             table_infos.push(TableInfo {
                 name: table.to_string(),
@@ -992,6 +1077,199 @@ impl AdminDbRepo {
     }
 }
 
+// ============================================
+// Backup Repository
+// ============================================
+
+pub struct AdminBackupRepo;
+
+/// Tables left out of backups: migration bookkeeping, login sessions and the
+/// audit log, which a restore must never rewrite
+pub const BACKUP_EXCLUDED_TABLES: &[&str] = &["_sqlx_migrations", "sessions", "audit_log"];
+
+// Consistent view across all tables while reading a backup
+pub const ADMIN_BACKUP_SNAPSHOT: &str =
+    "SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY";
+
+pub const ADMIN_BACKUP_LIST_TABLES: &str =
+    "SELECT tablename::text FROM pg_tables WHERE schemaname = 'public' ORDER BY tablename";
+
+pub const ADMIN_BACKUP_LIST_FOREIGN_KEYS: &str = r#"
+    SELECT child.relname::text, parent.relname::text
+    FROM pg_constraint c
+    JOIN pg_class child ON child.oid = c.conrelid
+    JOIN pg_class parent ON parent.oid = c.confrelid
+    WHERE c.contype = 'f' AND c.connamespace = 'public'::regnamespace
+"#;
+
+pub const ADMIN_BACKUP_LIST_SERIAL_COLUMNS: &str = r#"
+    SELECT table_name::text, column_name::text
+    FROM information_schema.columns
+    WHERE table_schema = 'public' AND column_default LIKE 'nextval(%'
+"#;
+
+/// All rows of a table, one JSON object per row
+pub fn backup_select_sql(table: &str) -> String {
+    format!("SELECT row_to_json(t)::text FROM {} t", table)
+}
+
+/// Empty every table about to be restored
+pub fn backup_truncate_sql(tables: &[String]) -> String {
+    format!("TRUNCATE TABLE {}", tables.join(", "))
+}
+
+/// Switch a table's own triggers (sync events) off or on; foreign keys stay
+/// enforced
+pub fn backup_triggers_sql(table: &str, enable: bool) -> String {
+    let action = if enable { "ENABLE" } else { "DISABLE" };
+    format!("ALTER TABLE {} {} TRIGGER USER", table, action)
+}
+
+/// Insert a batch of rows (`$1`, a JSON array of row objects)
+pub fn backup_insert_sql(table: &str) -> String {
+    format!(
+        "INSERT INTO {0} SELECT * FROM jsonb_populate_recordset(NULL::{0}, $1)",
+        table
+    )
+}
+
+/// Move a serial column's sequence past the restored values
+pub fn backup_reset_sequence_sql(table: &str, column: &str) -> String {
+    format!(
+        "SELECT setval(pg_get_serial_sequence('{0}', '{1}'), COALESCE(MAX({1}), 0) + 1, false) FROM {0}",
+        table, column
+    )
+}
+
+impl AdminBackupRepo {
+    /// Tables to back up, by name
+    pub async fn tables(conn: &mut PgConnection) -> Result<Vec<String>, AppError> {
+        let tables: Vec<String> = sqlx::query_scalar(ADMIN_BACKUP_LIST_TABLES)
+            .fetch_all(&mut *conn)
+            .await?;
+        Ok(tables
+            .into_iter()
+            .filter(|t| !BACKUP_EXCLUDED_TABLES.contains(&t.as_str()))
+            .collect())
+    }
+
+    /// Foreign keys as (referencing table, referenced table)
+    pub async fn foreign_keys(conn: &mut PgConnection) -> Result<Vec<(String, String)>, AppError> {
+        let keys = sqlx::query_as(ADMIN_BACKUP_LIST_FOREIGN_KEYS)
+            .fetch_all(&mut *conn)
+            .await?;
+        Ok(keys)
+    }
+
+    /// Columns backed by a sequence, as (table, column)
+    pub async fn serial_columns(
+        conn: &mut PgConnection,
+    ) -> Result<Vec<(String, String)>, AppError> {
+        let columns = sqlx::query_as(ADMIN_BACKUP_LIST_SERIAL_COLUMNS)
+            .fetch_all(&mut *conn)
+            .await?;
+        Ok(columns)
+    }
+}
+
+// ============================================
+// SQL Console Repository
+// ============================================
+
+pub struct AdminConsoleRepo;
+
+/// Cursor a console transaction reads its result through
+const CONSOLE_CURSOR: &str = "console_cursor";
+
+/// Quote an identifier such as a role name
+fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+/// First statements of a console transaction: read-only, time-bounded and
+/// running as the console role
+pub fn console_session_sql(role: &str, statement_timeout_ms: u64, idle_timeout_ms: u64) -> String {
+    format!(
+        "SET TRANSACTION READ ONLY; \
+         SET LOCAL statement_timeout = {}; \
+         SET LOCAL idle_in_transaction_session_timeout = {}; \
+         SET LOCAL ROLE {}",
+        statement_timeout_ms,
+        idle_timeout_ms,
+        quote_ident(role)
+    )
+}
+
+/// Open the console cursor over an admin query
+pub fn console_declare_sql(sql: &str) -> String {
+    format!("DECLARE {} NO SCROLL CURSOR FOR {}", CONSOLE_CURSOR, sql)
+}
+
+/// Read the next rows from the console cursor
+pub fn console_fetch_sql(rows: i64) -> String {
+    format!("FETCH FORWARD {} FROM {}", rows, CONSOLE_CURSOR)
+}
+
+/// Plan of an admin query as JSON
+pub fn console_explain_sql(sql: &str, analyze: bool) -> String {
+    let options = if analyze { "FORMAT JSON, ANALYZE" } else { "FORMAT JSON" };
+    format!("EXPLAIN ({}) {}", options, sql)
+}
+
+pub const ADMIN_CONSOLE_LIST_SAVED: &str = r#"
+    SELECT id, user_id, name, sql, created_at, updated_at
+    FROM admin_saved_queries
+    WHERE user_id = $1
+    ORDER BY name
+"#;
+
+pub const ADMIN_CONSOLE_SAVE: &str = r#"
+    INSERT INTO admin_saved_queries (user_id, name, sql)
+    VALUES ($1, $2, $3)
+    ON CONFLICT (user_id, name) DO UPDATE SET sql = EXCLUDED.sql, updated_at = NOW()
+    RETURNING id, user_id, name, sql, created_at, updated_at
+"#;
+
+pub const ADMIN_CONSOLE_DELETE_SAVED: &str =
+    "DELETE FROM admin_saved_queries WHERE id = $1 AND user_id = $2";
+
+impl AdminConsoleRepo {
+    /// An admin's saved queries, by name
+    pub async fn list_saved(pool: &PgPool, user_id: Uuid) -> Result<Vec<SavedQuery>, AppError> {
+        let queries = sqlx::query_as::<_, SavedQuery>(ADMIN_CONSOLE_LIST_SAVED)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+        Ok(queries)
+    }
+
+    /// Save a query, replacing the admin's query of the same name
+    pub async fn save(
+        pool: &PgPool,
+        user_id: Uuid,
+        name: &str,
+        sql: &str,
+    ) -> Result<SavedQuery, AppError> {
+        let query = sqlx::query_as::<_, SavedQuery>(ADMIN_CONSOLE_SAVE)
+            .bind(user_id)
+            .bind(name)
+            .bind(sql)
+            .fetch_one(pool)
+            .await?;
+        Ok(query)
+    }
+
+    /// Delete one of the admin's saved queries
+    pub async fn delete_saved(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(ADMIN_CONSOLE_DELETE_SAVED)
+            .bind(id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
 // ============================================
 // Audit Log Repository
 // ============================================
@@ -1014,6 +1292,40 @@ struct AuditLogRow {
     created_at: chrono::DateTime<chrono::Utc>,
 }
 
+pub const ADMIN_AUDIT_LIST_ENTRIES_TOTAL: &str = r#"
+    SELECT COUNT(*) as count FROM audit_log a
+    WHERE ($1::text IS NULL OR a.event_type = $1)
+      AND ($2::uuid IS NULL OR a.user_id = $2)
+      AND ($3::text IS NULL OR a.resource_type = $3)
+      AND ($4::text IS NULL OR a.status = $4)
+"#;
+
+pub const ADMIN_AUDIT_LIST_ENTRIES_AUDIT_LOG: &str = r#"
+    SELECT
+        a.id,
+        a.user_id,
+        u.email as user_email,
+        a.event_type,
+        a.resource_type,
+        a.resource_id::text as resource_id,
+        COALESCE(a.action, '') as action,
+        a.status,
+        a.details,
+        a.ip_address::text as ip_address,
+        a.created_at
+    FROM audit_log a
+    LEFT JOIN users u ON a.user_id = u.id
+    WHERE ($1::text IS NULL OR a.event_type = $1)
+      AND ($2::uuid IS NULL OR a.user_id = $2)
+      AND ($3::text IS NULL OR a.resource_type = $3)
+      AND ($4::text IS NULL OR a.status = $4)
+    ORDER BY a.created_at DESC
+    LIMIT $5 OFFSET $6
+"#;
+
+pub const ADMIN_AUDIT_GET_EVENT_TYPES: &str =
+    "SELECT DISTINCT event_type FROM audit_log ORDER BY event_type";
+
 impl AdminAuditRepo {
     /// List audit log entries with filters
     pub async fn list_entries(
@@ -1023,60 +1335,25 @@ impl AdminAuditRepo {
         let limit = query.limit.unwrap_or(100).min(500);
         let offset = query.offset.unwrap_or(0);
 
-        // Build dynamic WHERE clause
-        let mut conditions = vec!["1=1".to_string()];
-
-        if let Some(ref et) = query.event_type {
-            conditions.push(format!("a.event_type = '{}'", et.replace('\'', "''")));
-        }
-        if let Some(uid) = query.user_id {
-            conditions.push(format!("a.user_id = '{}'", uid));
-        }
-        if let Some(ref rt) = query.resource_type {
-            conditions.push(format!("a.resource_type = '{}'", rt.replace('\'', "''")));
-        }
-        if let Some(ref st) = query.status {
-            conditions.push(format!("a.status = '{}'", st.replace('\'', "''")));
-        }
-
-        let where_clause = conditions.join(" AND ");
-
-        // Get total count
-        let count_query = format!(
-            "SELECT COUNT(*) as count FROM audit_log a WHERE {}",
-            where_clause
-        );
-        let total = sqlx::query_as::<_, CountRow>(&count_query)
+        // Get total count (unset filters match everything)
+        let total = sqlx::query_as::<_, CountRow>(ADMIN_AUDIT_LIST_ENTRIES_TOTAL)
+            .bind(&query.event_type)
+            .bind(query.user_id)
+            .bind(&query.resource_type)
+            .bind(&query.status)
             .fetch_one(pool)
             .await
             .map(|r| r.count.unwrap_or(0))
             .unwrap_or(0);
 
         // Get entries with user email
-        let entries_query = format!(
-            r#"
-            SELECT 
-                a.id,
-                a.user_id,
-                u.email as user_email,
-                a.event_type,
-                a.resource_type,
-                a.resource_id::text as resource_id,
-                a.action,
-                a.status,
-                a.details,
-                a.ip_address::text as ip_address,
-                a.created_at
-            FROM audit_log a
-            LEFT JOIN users u ON a.user_id = u.id
-            WHERE {}
-            ORDER BY a.created_at DESC
-            LIMIT {} OFFSET {}
-            "#,
-            where_clause, limit, offset
-        );
-
-        let rows = sqlx::query_as::<_, AuditLogRow>(&entries_query)
+        let rows = sqlx::query_as::<_, AuditLogRow>(ADMIN_AUDIT_LIST_ENTRIES_AUDIT_LOG)
+            .bind(&query.event_type)
+            .bind(query.user_id)
+            .bind(&query.resource_type)
+            .bind(&query.status)
+            .bind(limit)
+            .bind(offset)
             .fetch_all(pool)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to fetch audit log: {}", e)))?;
@@ -1103,12 +1380,10 @@ impl AdminAuditRepo {
 
     /// Get distinct event types for filter dropdown
     pub async fn get_event_types(pool: &PgPool) -> Result<Vec<String>, AppError> {
-        let rows = sqlx::query_as::<_, (String,)>(
-            "SELECT DISTINCT event_type FROM audit_log ORDER BY event_type",
-        )
-        .fetch_all(pool)
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to fetch event types: {}", e)))?;
+        let rows = sqlx::query_as::<_, (String,)>(ADMIN_AUDIT_GET_EVENT_TYPES)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to fetch event types: {}", e)))?;
 
         Ok(rows.into_iter().map(|r| r.0).collect())
     }
@@ -0,0 +1,78 @@
+//! API Token Models
+//!
+//! Personal API tokens used by scripts and integrations via `Authorization: Bearer`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Maximum token name length
+pub const MAX_API_TOKEN_NAME_LEN: usize = 100;
+
+/// Longest allowed token lifetime
+pub const MAX_API_TOKEN_EXPIRY_DAYS: i64 = 365;
+
+/// API token database model (the raw token is never stored)
+#[derive(Debug, Clone, FromRow)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    /// SHA-256 hex digest of the raw token
+    pub token_hash: String,
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub last_used_ip: Option<String>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Create API token request
+#[derive(Debug, Deserialize)]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+    /// Days until expiry; omit for a token that never expires
+    pub expires_in_days: Option<i64>,
+}
+
+/// API token as listed to its owner
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiTokenResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub last_used_ip: Option<String>,
+    pub revoked: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ApiToken> for ApiTokenResponse {
+    fn from(t: ApiToken) -> Self {
+        Self {
+            id: t.id,
+            name: t.name,
+            token_prefix: t.token_prefix,
+            scopes: t.scopes,
+            expires_at: t.expires_at,
+            last_used_at: t.last_used_at,
+            last_used_ip: t.last_used_ip,
+            revoked: t.revoked_at.is_some(),
+            created_at: t.created_at,
+        }
+    }
+}
+
+/// Newly created token; `token` is shown exactly once
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedApiTokenResponse {
+    pub token: String,
+    #[serde(flatten)]
+    pub info: ApiTokenResponse,
+}
@@ -0,0 +1,146 @@
+//! API Token Repository
+//!
+//! Database operations for personal API tokens.
+
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::api_token_models::ApiToken;
+use super::repos::hash_session_token;
+use crate::error::AppError;
+use crate::shared::auth::csrf::verify_token;
+
+/// Prefix that makes tokens recognizable (e.g. by secret scanners)
+pub const API_TOKEN_PREFIX: &str = "ign_";
+
+macro_rules! api_token_columns {
+    () => {
+        r#"id, user_id, name, token_hash, token_prefix, scopes, expires_at,
+    last_used_at, last_used_ip, revoked_at, created_at"#
+    };
+}
+
+/// Generate a new raw API token
+pub fn generate_api_token() -> String {
+    format!(
+        "{}{}",
+        API_TOKEN_PREFIX,
+        super::repos::generate_session_token()
+    )
+}
+
+pub struct ApiTokenRepo;
+
+pub const API_TOKEN_CREATE: &str = concat!(
+    r#"
+    INSERT INTO api_tokens (user_id, name, token_hash, token_prefix, scopes, expires_at)
+    VALUES ($1, $2, $3, $4, $5, $6)
+    RETURNING "#,
+    api_token_columns!()
+);
+
+pub const API_TOKEN_FIND_ACTIVE: &str = concat!(
+    "SELECT ",
+    api_token_columns!(),
+    r#"
+    FROM api_tokens
+    WHERE token_hash = $1
+      AND revoked_at IS NULL
+      AND (expires_at IS NULL OR expires_at > NOW())
+"#
+);
+
+pub const API_TOKEN_LIST_FOR_USER: &str = concat!(
+    "SELECT ",
+    api_token_columns!(),
+    " FROM api_tokens WHERE user_id = $1 ORDER BY created_at DESC"
+);
+
+pub const API_TOKEN_REVOKE: &str = r#"
+    UPDATE api_tokens SET revoked_at = NOW()
+    WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL
+"#;
+
+pub const API_TOKEN_REVOKE_ALL_FOR_USER: &str =
+    "UPDATE api_tokens SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL";
+
+pub const API_TOKEN_TOUCH: &str = r#"
+    UPDATE api_tokens
+    SET last_used_at = NOW(), last_used_ip = COALESCE($2, last_used_ip)
+    WHERE id = $1
+"#;
+
+impl ApiTokenRepo {
+    /// Create a token; returns the row and the raw token (shown once)
+    pub async fn create(
+        pool: &PgPool,
+        user_id: Uuid,
+        name: &str,
+        scopes: &[String],
+        expires_in_days: Option<i64>,
+    ) -> Result<(ApiToken, String), AppError> {
+        let token = generate_api_token();
+        let expires_at = expires_in_days.map(|days| Utc::now() + Duration::days(days));
+        // Prefix plus a few characters is enough to recognize a token, not to use it
+        let display_prefix: String = token.chars().take(API_TOKEN_PREFIX.len() + 6).collect();
+
+        let row = sqlx::query_as::<_, ApiToken>(API_TOKEN_CREATE)
+            .bind(user_id)
+            .bind(name)
+            .bind(hash_session_token(&token))
+            .bind(&display_prefix)
+            .bind(scopes)
+            .bind(expires_at)
+            .fetch_one(pool)
+            .await?;
+
+        Ok((row, token))
+    }
+
+    /// Find an active (unrevoked, unexpired) token by its raw value
+    ///
+    /// Lookup is by SHA-256 hash, with a constant-time comparison of the digest.
+    pub async fn find_active(pool: &PgPool, token: &str) -> Result<Option<ApiToken>, AppError> {
+        let token_hash = hash_session_token(token);
+
+        let row = sqlx::query_as::<_, ApiToken>(API_TOKEN_FIND_ACTIVE)
+            .bind(&token_hash)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(row.filter(|t| verify_token(&t.token_hash, &token_hash)))
+    }
+
+    /// List a user's tokens (including revoked ones), newest first
+    pub async fn list_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<ApiToken>, AppError> {
+        let rows = sqlx::query_as::<_, ApiToken>(API_TOKEN_LIST_FOR_USER)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+
+        Ok(rows)
+    }
+
+    /// Revoke one of a user's tokens; returns false if not found or already revoked
+    pub async fn revoke(pool: &PgPool, user_id: Uuid, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(API_TOKEN_REVOKE)
+            .bind(id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record token use
+    pub async fn touch(pool: &PgPool, id: Uuid, ip_address: Option<&str>) -> Result<(), AppError> {
+        sqlx::query(API_TOKEN_TOUCH)
+            .bind(id)
+            .bind(ip_address)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
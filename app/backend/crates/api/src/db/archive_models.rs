@@ -0,0 +1,106 @@
+//! Account Archive Models
+//!
+//! Export archives of a user's data and the background jobs that write and
+//! import them.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Archive layout version; bump when files or manifest fields change
+/// incompatibly
+pub const ARCHIVE_FORMAT_VERSION: i32 = 1;
+
+pub const ARCHIVE_JOB_KIND_EXPORT: &str = "export";
+pub const ARCHIVE_JOB_KIND_IMPORT: &str = "import";
+
+pub const ARCHIVE_JOB_STATUS_PENDING: &str = "pending";
+pub const ARCHIVE_JOB_STATUS_RUNNING: &str = "running";
+pub const ARCHIVE_JOB_STATUS_COMPLETED: &str = "completed";
+pub const ARCHIVE_JOB_STATUS_FAILED: &str = "failed";
+
+/// Archive path of the manifest
+pub const ARCHIVE_MANIFEST_PATH: &str = "manifest.json";
+/// Directory holding one JSON file per domain
+pub const ARCHIVE_DATA_DIR: &str = "data";
+/// Directory holding blob bytes, laid out as `<category>/<file>`
+pub const ARCHIVE_BLOBS_DIR: &str = "blobs";
+
+/// Export or import job
+#[derive(Debug, Clone, FromRow)]
+pub struct ArchiveJob {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub status: String,
+    pub storage_key: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub summary: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Job as shown to its owner (the storage key stays internal)
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveJobResponse {
+    pub id: Uuid,
+    pub kind: String,
+    pub status: String,
+    pub size_bytes: Option<i64>,
+    pub summary: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<ArchiveJob> for ArchiveJobResponse {
+    fn from(j: ArchiveJob) -> Self {
+        Self {
+            id: j.id,
+            kind: j.kind,
+            status: j.status,
+            size_bytes: j.size_bytes,
+            summary: j.summary,
+            error: j.error,
+            created_at: j.created_at,
+            started_at: j.started_at,
+            completed_at: j.completed_at,
+        }
+    }
+}
+
+/// Import an archive previously uploaded through `/blobs/upload`
+#[derive(Debug, Deserialize)]
+pub struct StartImportRequest {
+    pub blob_id: Uuid,
+}
+
+/// `manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub format_version: i32,
+    /// Latest applied migration when the archive was written
+    pub schema_version: Option<i64>,
+    pub exported_at: DateTime<Utc>,
+    pub user_id: Uuid,
+    pub email: Option<String>,
+    /// Row count per table
+    pub tables: BTreeMap<String, usize>,
+    /// Archive paths of the blob files
+    pub blobs: Vec<String>,
+}
+
+/// Manifest and rows of an archive; blob files are read and written
+/// separately
+#[derive(Debug, Clone)]
+pub struct ArchiveContents {
+    pub manifest: ArchiveManifest,
+    /// Rows (as `row_to_json` objects) per table
+    pub tables: BTreeMap<String, Vec<serde_json::Value>>,
+}
@@ -0,0 +1,752 @@
+//! Account Archive Repository
+//!
+//! Reads a user's rows for an export archive, restores them into another
+//! account with fresh IDs, and tracks export/import jobs.
+//!
+//! The tables are listed in `ARCHIVE_TABLES`. Rows travel as `row_to_json`
+//! objects and are written back with `jsonb_populate_record`, so new columns
+//! round-trip without changes here. Most relations are not declared as
+//! foreign keys, so each table lists the columns that hold IDs of other
+//! archived rows.
+//!
+//! Balances, ledgers, rewards and quest state are exported but never
+//! imported: the archive is user-supplied, and those rows are only ever
+//! written by the server as the user earns and spends.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::Value;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::archive_models::*;
+use crate::error::AppError;
+
+/// How an archived table's rows belong to the user
+#[derive(Debug, Clone, Copy)]
+pub enum ArchiveScope {
+    /// Rows carry `user_id`
+    User,
+    /// Rows belong to an archived parent row through `column`
+    Parent {
+        table: &'static str,
+        column: &'static str,
+    },
+}
+
+/// A table included in export archives
+#[derive(Debug, Clone, Copy)]
+pub struct ArchiveTable {
+    pub table: &'static str,
+    /// File under `data/` the rows are written to
+    pub domain: &'static str,
+    pub scope: ArchiveScope,
+    /// Columns holding IDs of other archived rows (remapped on import)
+    pub refs: &'static [&'static str],
+    /// Rows created for every account on first use; replaced on import
+    pub bootstrap: bool,
+    /// Restored on import; false for tables only the server may write
+    pub imported: bool,
+}
+
+/// Columns holding object storage keys, which must sit under the user's prefix
+pub const STORAGE_KEY_COLUMNS: &[&str] = &["r2_key", "thumbnail_r2_key", "waveform_r2_key"];
+
+const fn user_table(
+    table: &'static str,
+    domain: &'static str,
+    refs: &'static [&'static str],
+) -> ArchiveTable {
+    ArchiveTable {
+        table,
+        domain,
+        scope: ArchiveScope::User,
+        refs,
+        bootstrap: false,
+        imported: true,
+    }
+}
+
+const fn bootstrap_table(table: &'static str, domain: &'static str) -> ArchiveTable {
+    ArchiveTable {
+        bootstrap: true,
+        ..user_table(table, domain, &[])
+    }
+}
+
+const fn export_only(table: ArchiveTable) -> ArchiveTable {
+    ArchiveTable {
+        imported: false,
+        ..table
+    }
+}
+
+const fn child_table(
+    table: &'static str,
+    domain: &'static str,
+    parent: &'static str,
+    column: &'static str,
+    refs: &'static [&'static str],
+) -> ArchiveTable {
+    ArchiveTable {
+        table,
+        domain,
+        scope: ArchiveScope::Parent {
+            table: parent,
+            column,
+        },
+        refs,
+        bootstrap: false,
+        imported: true,
+    }
+}
+
+/// Tables in an export archive, parents before children
+///
+/// Sessions, credentials, tokens, audit and delivery logs are deliberately
+/// left out: they belong to the account, not to the user's data.
+pub const ARCHIVE_TABLES: &[ArchiveTable] = &[
+    // Profile
+    bootstrap_table("user_settings", "profile"),
+    bootstrap_table("user_interests", "profile"),
+    bootstrap_table("user_onboarding_state", "profile"),
+    bootstrap_table("user_onboarding_responses", "profile"),
+    // Habits and goals
+    user_table("habits", "habits_goals", &[]),
+    user_table("habit_completions", "habits_goals", &["habit_id"]),
+    user_table("goals", "habits_goals", &[]),
+    child_table("goal_milestones", "habits_goals", "goals", "goal_id", &[]),
+    user_table("goal_links", "habits_goals", &["goal_id", "target_id"]),
+    // Focus
+    user_table("focus_libraries", "focus", &[]),
+    child_table(
+        "focus_library_tracks",
+        "focus",
+        "focus_libraries",
+        "library_id",
+        &[],
+    ),
+    user_table("focus_sessions", "focus", &[]),
+    user_table("focus_pause_state", "focus", &["session_id"]),
+    // Exercise
+    user_table("exercises", "exercise", &[]),
+    user_table("workouts", "exercise", &[]),
+    child_table(
+        "workout_sections",
+        "exercise",
+        "workouts",
+        "workout_id",
+        &[],
+    ),
+    child_table(
+        "workout_exercises",
+        "exercise",
+        "workouts",
+        "workout_id",
+        &["section_id", "exercise_id"],
+    ),
+    user_table("workout_sessions", "exercise", &["workout_id"]),
+    child_table(
+        "exercise_sets",
+        "exercise",
+        "workout_sessions",
+        "session_id",
+        &["exercise_id"],
+    ),
+    user_table(
+        "personal_records",
+        "exercise",
+        &["exercise_id", "exercise_set_id"],
+    ),
+    user_table("training_programs", "exercise", &[]),
+    child_table(
+        "program_weeks",
+        "exercise",
+        "training_programs",
+        "program_id",
+        &[],
+    ),
+    child_table(
+        "program_workouts",
+        "exercise",
+        "program_weeks",
+        "program_week_id",
+        &["workout_id"],
+    ),
+    // Planning
+    user_table(
+        "calendar_events",
+        "planning",
+        &["workout_id", "habit_id", "goal_id", "parent_event_id"],
+    ),
+    user_table("daily_plans", "planning", &[]),
+    user_table("plan_templates", "planning", &[]),
+    // Quests and gamification
+    export_only(user_table("user_quests", "quests", &[])),
+    export_only(user_table("user_quest_progress", "quests", &[])),
+    export_only(user_table("points_ledger", "gamification", &["event_id"])),
+    export_only(bootstrap_table("user_progress", "gamification")),
+    export_only(bootstrap_table("user_wallet", "gamification")),
+    export_only(bootstrap_table("user_skills", "gamification")),
+    export_only(bootstrap_table("user_streaks", "gamification")),
+    export_only(user_table("user_achievements", "gamification", &[])),
+    export_only(user_table("user_rewards", "gamification", &[])),
+    export_only(user_table("user_purchases", "gamification", &[])),
+    export_only(user_table("market_transactions", "gamification", &[])),
+    // Books
+    user_table("books", "books", &[]),
+    user_table("reading_sessions", "books", &["book_id"]),
+    // Learn
+    user_table("user_lesson_progress", "learn", &[]),
+    user_table("user_drill_stats", "learn", &[]),
+    user_table("drill_sessions", "learn", &[]),
+    child_table(
+        "drill_session_answers",
+        "learn",
+        "drill_sessions",
+        "session_id",
+        &[],
+    ),
+    // Reference tracks
+    user_table("reference_tracks", "reference", &[]),
+    user_table("track_annotations", "reference", &["track_id"]),
+    user_table("track_regions", "reference", &["track_id"]),
+    child_table(
+        "track_analyses",
+        "reference",
+        "reference_tracks",
+        "track_id",
+        &[],
+    ),
+    child_table(
+        "analysis_frame_manifests",
+        "reference",
+        "track_analyses",
+        "analysis_id",
+        &[],
+    ),
+    child_table(
+        "analysis_frame_data",
+        "reference",
+        "analysis_frame_manifests",
+        "manifest_id",
+        &[],
+    ),
+    child_table(
+        "analysis_events",
+        "reference",
+        "track_analyses",
+        "analysis_id",
+        &[],
+    ),
+    user_table("user_references", "reference", &[]),
+    // Capture
+    user_table("inbox_items", "capture", &[]),
+    user_table("ideas", "capture", &[]),
+    user_table(
+        "idea_attachments",
+        "capture",
+        &["idea_id", "reference_track_id"],
+    ),
+    user_table("infobase_entries", "capture", &[]),
+    user_table("infobase_links", "capture", &["source_id", "target_id"]),
+    user_table("content_revisions", "capture", &["entity_id"]),
+    user_table(
+        "inbox_conversions",
+        "capture",
+        &["inbox_item_id", "target_id"],
+    ),
+    user_table("feedback", "capture", &[]),
+    user_table("activity_events", "activity", &[]),
+];
+
+/// Look up an archived table
+pub fn archive_table(table: &str) -> Option<&'static ArchiveTable> {
+    ARCHIVE_TABLES.iter().find(|t| t.table == table)
+}
+
+/// IDs of a table's rows belonging to user `$1`
+fn archive_ids_sql(table: &ArchiveTable) -> String {
+    match table.scope {
+        ArchiveScope::User => format!("SELECT id FROM {} WHERE user_id = $1", table.table),
+        ArchiveScope::Parent {
+            table: parent,
+            column,
+        } => {
+            let parent = archive_table(parent).expect("archive parent is archived");
+            format!(
+                "SELECT id FROM {} WHERE {} IN ({})",
+                table.table,
+                column,
+                archive_ids_sql(parent)
+            )
+        }
+    }
+}
+
+/// Rows of one archived table belonging to user `$1`
+pub fn archive_select_sql(table: &ArchiveTable) -> String {
+    match table.scope {
+        ArchiveScope::User => format!(
+            "SELECT row_to_json(t) FROM {} t WHERE user_id = $1",
+            table.table
+        ),
+        ArchiveScope::Parent {
+            table: parent,
+            column,
+        } => {
+            let parent = archive_table(parent).expect("archive parent is archived");
+            format!(
+                "SELECT row_to_json(t) FROM {} t WHERE {} IN ({})",
+                table.table,
+                column,
+                archive_ids_sql(parent)
+            )
+        }
+    }
+}
+
+/// Insert one archived row (`$1`, a JSON object)
+pub fn archive_insert_sql(table: &ArchiveTable) -> String {
+    format!(
+        "INSERT INTO {0} SELECT * FROM jsonb_populate_record(NULL::{0}, $1)",
+        table.table
+    )
+}
+
+/// Remove a bootstrap table's rows for user `$1` before restoring
+pub fn archive_clear_sql(table: &ArchiveTable) -> String {
+    format!("DELETE FROM {} WHERE user_id = $1", table.table)
+}
+
+/// Remove all of an archived table's rows belonging to user `$1`
+///
+/// Child tables select through their parent, so run these children first
+/// (`ARCHIVE_TABLES` in reverse).
+pub fn archive_delete_sql(table: &ArchiveTable) -> String {
+    match table.scope {
+        ArchiveScope::User => archive_clear_sql(table),
+        ArchiveScope::Parent {
+            table: parent,
+            column,
+        } => {
+            let parent = archive_table(parent).expect("archive parent is archived");
+            format!(
+                "DELETE FROM {} WHERE {} IN ({})",
+                table.table,
+                column,
+                archive_ids_sql(parent)
+            )
+        }
+    }
+}
+
+/// Whether user `$1` has rows in any non-bootstrap table an import writes
+pub fn archive_has_data_sql() -> String {
+    let checks: Vec<String> = ARCHIVE_TABLES
+        .iter()
+        .filter(|t| t.imported && !t.bootstrap && matches!(t.scope, ArchiveScope::User))
+        .map(|t| format!("EXISTS (SELECT 1 FROM {} WHERE user_id = $1)", t.table))
+        .collect();
+    format!("SELECT {}", checks.join(" OR "))
+}
+
+/// Give an archived row its new IDs and owner
+///
+/// `ids` maps every archived row ID to its replacement; references to rows
+/// outside the archive (seeded content, other users) are left alone. Storage
+/// keys under the old user's prefix are moved to the new one; any other key
+/// is dropped, so an import can never point at someone else's objects.
+pub fn remap_row(
+    row: &mut Value,
+    table: &ArchiveTable,
+    ids: &HashMap<Uuid, Uuid>,
+    old_user: Uuid,
+    new_user: Uuid,
+) {
+    let Some(fields) = row.as_object_mut() else {
+        return;
+    };
+
+    let parent_column = match table.scope {
+        ArchiveScope::Parent { column, .. } => Some(column),
+        ArchiveScope::User => None,
+    };
+    let id_columns = std::iter::once("id")
+        .chain(parent_column)
+        .chain(table.refs.iter().copied());
+    for column in id_columns {
+        if let Some(value) = fields.get_mut(column) {
+            let new_id = value
+                .as_str()
+                .and_then(|s| s.parse::<Uuid>().ok())
+                .and_then(|old| ids.get(&old));
+            if let Some(new_id) = new_id {
+                *value = Value::String(new_id.to_string());
+            }
+        }
+    }
+
+    if fields.contains_key("user_id") {
+        fields.insert("user_id".to_string(), Value::String(new_user.to_string()));
+    }
+
+    let old_prefix = format!("{}/", old_user);
+    for column in STORAGE_KEY_COLUMNS {
+        if let Some(value) = fields.get_mut(*column) {
+            *value = match value.as_str().and_then(|s| s.strip_prefix(&old_prefix)) {
+                Some(key) => Value::String(format!("{}/{}", new_user, key)),
+                None => Value::Null,
+            };
+        }
+    }
+}
+
+pub struct ArchiveRepo;
+
+// Consistent view across all tables while collecting
+pub const ARCHIVE_SNAPSHOT: &str = "SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY";
+
+impl ArchiveRepo {
+    /// Read every archived row for a user, keyed by table
+    pub async fn collect(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<BTreeMap<String, Vec<Value>>, AppError> {
+        let mut tx = pool.begin().await?;
+        sqlx::query(ARCHIVE_SNAPSHOT).execute(&mut *tx).await?;
+
+        let mut tables = BTreeMap::new();
+        for table in ARCHIVE_TABLES {
+            let rows: Vec<Value> = sqlx::query_scalar(&archive_select_sql(table))
+                .bind(user_id)
+                .fetch_all(&mut *tx)
+                .await?;
+            tables.insert(table.table.to_string(), rows);
+        }
+
+        tx.commit().await?;
+        Ok(tables)
+    }
+
+    /// Restore archived rows into `user_id` with fresh IDs
+    ///
+    /// The account must not hold data of its own yet; rows every account
+    /// starts with (settings, interests, onboarding) are replaced, while the
+    /// account's own wallet and progress are kept. Returns the number of
+    /// rows restored per table.
+    pub async fn restore(
+        pool: &PgPool,
+        user_id: Uuid,
+        contents: &ArchiveContents,
+    ) -> Result<BTreeMap<String, usize>, AppError> {
+        if contents.manifest.format_version > ARCHIVE_FORMAT_VERSION {
+            return Err(AppError::BadRequest(format!(
+                "Archive format {} is newer than supported ({})",
+                contents.manifest.format_version, ARCHIVE_FORMAT_VERSION
+            )));
+        }
+
+        let mut tx = pool.begin().await?;
+
+        let has_data: bool = sqlx::query_scalar(&archive_has_data_sql())
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        if has_data {
+            return Err(AppError::BadRequest(
+                "Archives can only be imported into an account without data".to_string(),
+            ));
+        }
+
+        for table in ARCHIVE_TABLES.iter().filter(|t| t.imported && t.bootstrap) {
+            sqlx::query(&archive_clear_sql(table))
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        // Assign every new ID up front so references resolve in any order
+        let ids: HashMap<Uuid, Uuid> = contents
+            .tables
+            .values()
+            .flatten()
+            .filter_map(|row| row.get("id")?.as_str()?.parse::<Uuid>().ok())
+            .map(|old| (old, Uuid::new_v4()))
+            .collect();
+
+        let mut restored = BTreeMap::new();
+        for table in ARCHIVE_TABLES.iter().filter(|t| t.imported) {
+            let Some(rows) = contents.tables.get(table.table) else {
+                continue;
+            };
+            let sql = archive_insert_sql(table);
+            for row in rows {
+                let mut row = row.clone();
+                remap_row(&mut row, table, &ids, contents.manifest.user_id, user_id);
+                sqlx::query(&sql)
+                    .bind(&row)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| {
+                        AppError::BadRequest(format!("Invalid {} row: {}", table.table, e))
+                    })?;
+            }
+            restored.insert(table.table.to_string(), rows.len());
+        }
+
+        tx.commit().await?;
+        Ok(restored)
+    }
+}
+
+// Column list for account_archive_jobs - matches ArchiveJob struct field order
+macro_rules! archive_job_columns {
+    () => {
+        r#"id, user_id, kind, status, storage_key, size_bytes, summary, error,
+    created_at, started_at, completed_at"#
+    };
+}
+
+pub struct ArchiveJobRepo;
+
+// The partial unique index allows one active job per user
+pub const ARCHIVE_JOB_CREATE: &str = concat!(
+    r#"
+    INSERT INTO account_archive_jobs (user_id, kind, storage_key)
+    VALUES ($1, $2, $3)
+    ON CONFLICT DO NOTHING
+    RETURNING "#,
+    archive_job_columns!()
+);
+
+pub const ARCHIVE_JOB_CLAIM: &str = r#"
+    UPDATE account_archive_jobs
+    SET status = 'running', started_at = NOW(), lease_owner = $2,
+        leased_until = NOW() + INTERVAL '5 minutes'
+    WHERE id = $1 AND status = 'pending'
+"#;
+
+pub const ARCHIVE_JOB_RENEW: &str = r#"
+    UPDATE account_archive_jobs
+    SET leased_until = NOW() + INTERVAL '5 minutes'
+    WHERE id = $1 AND status = 'running' AND lease_owner = $2
+"#;
+
+pub const ARCHIVE_JOB_COMPLETE: &str = r#"
+    UPDATE account_archive_jobs
+    SET status = 'completed', storage_key = $3, size_bytes = $4, summary = $5,
+        leased_until = NULL, completed_at = NOW()
+    WHERE id = $1 AND status = 'running' AND lease_owner = $2
+"#;
+
+pub const ARCHIVE_JOB_FAIL: &str = r#"
+    UPDATE account_archive_jobs
+    SET status = 'failed', error = $3, leased_until = NULL, completed_at = NOW()
+    WHERE id = $1 AND status = 'running' AND lease_owner = $2
+"#;
+
+// Jobs whose worker stopped renewing, and jobs never picked up after queueing
+pub const ARCHIVE_JOB_FAIL_EXPIRED: &str = r#"
+    UPDATE account_archive_jobs
+    SET status = 'failed', error = 'Interrupted before it finished', leased_until = NULL,
+        completed_at = NOW()
+    WHERE ((status = 'running' AND leased_until < NOW())
+           OR (status = 'pending' AND created_at < NOW() - INTERVAL '5 minutes'))
+      AND ($1::uuid IS NULL OR user_id = $1)
+"#;
+
+pub const ARCHIVE_JOB_GET: &str = concat!(
+    "SELECT ",
+    archive_job_columns!(),
+    " FROM account_archive_jobs WHERE id = $1 AND user_id = $2"
+);
+
+pub const ARCHIVE_JOB_LIST: &str = concat!(
+    "SELECT ",
+    archive_job_columns!(),
+    r#"
+    FROM account_archive_jobs
+    WHERE user_id = $1
+    ORDER BY created_at DESC
+    LIMIT $2
+"#
+);
+
+impl ArchiveJobRepo {
+    /// Queue a job; `None` when the user already has one pending or running
+    ///
+    /// A job of the user's that was abandoned is failed first, so it does
+    /// not block the new one.
+    pub async fn create(
+        pool: &PgPool,
+        user_id: Uuid,
+        kind: &str,
+        storage_key: Option<&str>,
+    ) -> Result<Option<ArchiveJob>, AppError> {
+        Self::fail_expired(pool, Some(user_id)).await?;
+        let job = sqlx::query_as::<_, ArchiveJob>(ARCHIVE_JOB_CREATE)
+            .bind(user_id)
+            .bind(kind)
+            .bind(storage_key)
+            .fetch_optional(pool)
+            .await?;
+        Ok(job)
+    }
+
+    /// Start a pending job under `lease_owner`; false if it was already taken
+    pub async fn claim(pool: &PgPool, id: Uuid, lease_owner: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(ARCHIVE_JOB_CLAIM)
+            .bind(id)
+            .bind(lease_owner)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Extend the lease; false once it is no longer held
+    pub async fn renew(pool: &PgPool, id: Uuid, lease_owner: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(ARCHIVE_JOB_RENEW)
+            .bind(id)
+            .bind(lease_owner)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Record success; false if the lease was lost first
+    pub async fn complete(
+        pool: &PgPool,
+        id: Uuid,
+        lease_owner: Uuid,
+        storage_key: &str,
+        size_bytes: i64,
+        summary: &Value,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query(ARCHIVE_JOB_COMPLETE)
+            .bind(id)
+            .bind(lease_owner)
+            .bind(storage_key)
+            .bind(size_bytes)
+            .bind(summary)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Record failure; false if the lease was lost first
+    pub async fn fail(
+        pool: &PgPool,
+        id: Uuid,
+        lease_owner: Uuid,
+        error: &str,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query(ARCHIVE_JOB_FAIL)
+            .bind(id)
+            .bind(lease_owner)
+            .bind(error)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Fail abandoned jobs, of one user or of everyone
+    pub async fn fail_expired(pool: &PgPool, user_id: Option<Uuid>) -> Result<u64, AppError> {
+        let result = sqlx::query(ARCHIVE_JOB_FAIL_EXPIRED)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn get(
+        pool: &PgPool,
+        user_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<ArchiveJob>, AppError> {
+        let job = sqlx::query_as::<_, ArchiveJob>(ARCHIVE_JOB_GET)
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+        Ok(job)
+    }
+
+    pub async fn list(
+        pool: &PgPool,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<ArchiveJob>, AppError> {
+        let jobs = sqlx::query_as::<_, ArchiveJob>(ARCHIVE_JOB_LIST)
+            .bind(user_id)
+            .bind(limit)
+            .fetch_all(pool)
+            .await?;
+        Ok(jobs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parents_are_archived_before_children() {
+        for (i, table) in ARCHIVE_TABLES.iter().enumerate() {
+            if let ArchiveScope::Parent { table: parent, .. } = table.scope {
+                let parent_index = ARCHIVE_TABLES.iter().position(|t| t.table == parent);
+                assert!(
+                    parent_index.is_some_and(|p| p < i),
+                    "{} listed before its parent {}",
+                    table.table,
+                    parent
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_nested_child_select_walks_up_to_user() {
+        let sql = archive_select_sql(archive_table("program_workouts").unwrap());
+
+        assert_eq!(
+            sql,
+            "SELECT row_to_json(t) FROM program_workouts t WHERE program_week_id IN \
+             (SELECT id FROM program_weeks WHERE program_id IN \
+             (SELECT id FROM training_programs WHERE user_id = $1))"
+        );
+    }
+
+    #[test]
+    fn test_remap_row_replaces_ids_owner_and_storage_keys() {
+        let old_user = Uuid::new_v4();
+        let new_user = Uuid::new_v4();
+        let (old_track, new_track) = (Uuid::new_v4(), Uuid::new_v4());
+        let (old_region, new_region) = (Uuid::new_v4(), Uuid::new_v4());
+        let ids = HashMap::from([(old_track, new_track), (old_region, new_region)]);
+        let unrelated = Uuid::new_v4();
+
+        let mut row = serde_json::json!({
+            "id": old_region,
+            "user_id": old_user,
+            "track_id": old_track,
+            "other_id": unrelated,
+            "r2_key": format!("{}/audio/x.mp3", old_user),
+            "thumbnail_r2_key": format!("{}/images/x.png", unrelated),
+        });
+        remap_row(
+            &mut row,
+            archive_table("track_regions").unwrap(),
+            &ids,
+            old_user,
+            new_user,
+        );
+
+        assert_eq!(row["id"], serde_json::json!(new_region));
+        assert_eq!(row["user_id"], serde_json::json!(new_user));
+        assert_eq!(row["track_id"], serde_json::json!(new_track));
+        assert_eq!(row["other_id"], serde_json::json!(unrelated));
+        assert_eq!(row["r2_key"], format!("{}/audio/x.mp3", new_user));
+        assert_eq!(row["thumbnail_r2_key"], Value::Null);
+    }
+}
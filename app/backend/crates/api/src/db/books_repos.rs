@@ -8,6 +8,9 @@ use uuid::Uuid;
 use crate::error::AppError;
 
 use super::books_models::*;
+use super::gamification_models::AwardPointsInput;
+use super::gamification_repos::GamificationRepo;
+use super::habits_goals_repos::GoalsRepo;
 
 // ============================================================================
 // BOOK REPOSITORY
@@ -15,6 +18,53 @@ use super::books_models::*;
 
 pub struct BookRepo;
 
+pub const BOOK_LIST_BY_STATUS: &str = r#"
+    SELECT id, user_id, title, author, total_pages, current_page,
+           status, started_at, completed_at, rating, notes,
+           cover_blob_id, created_at, updated_at
+    FROM books
+    WHERE user_id = $1 AND status = $2
+    ORDER BY updated_at DESC
+"#;
+
+pub const BOOK_LIST: &str = r#"
+    SELECT id, user_id, title, author, total_pages, current_page,
+           status, started_at, completed_at, rating, notes,
+           cover_blob_id, created_at, updated_at
+    FROM books
+    WHERE user_id = $1
+    ORDER BY updated_at DESC
+"#;
+
+pub const BOOK_GET_BY_ID: &str = r#"
+    SELECT id, user_id, title, author, total_pages, current_page,
+           status, started_at, completed_at, rating, notes,
+           cover_blob_id, created_at, updated_at
+    FROM books
+    WHERE id = $1 AND user_id = $2
+"#;
+
+pub const BOOK_CREATE: &str = r#"
+    INSERT INTO books (user_id, title, author, total_pages, status)
+    VALUES ($1, $2, $3, $4, $5)
+    RETURNING id, user_id, title, author, total_pages, current_page,
+              status, started_at, completed_at, rating, notes,
+              cover_blob_id, created_at, updated_at
+"#;
+
+pub const BOOK_UPDATE: &str = r#"
+    UPDATE books SET
+        title = $3, author = $4, total_pages = $5, current_page = $6,
+        status = $7, rating = $8, notes = $9,
+        started_at = $10, completed_at = $11
+    WHERE id = $1 AND user_id = $2
+    RETURNING id, user_id, title, author, total_pages, current_page,
+              status, started_at, completed_at, rating, notes,
+              cover_blob_id, created_at, updated_at
+"#;
+
+pub const BOOK_DELETE: &str = "DELETE FROM books WHERE id = $1 AND user_id = $2";
+
 impl BookRepo {
     /// List user's books
     pub async fn list(
@@ -23,34 +73,16 @@ impl BookRepo {
         status: Option<&str>,
     ) -> Result<BooksListResponse, AppError> {
         let books = if let Some(s) = status {
-            sqlx::query_as::<_, Book>(
-                r#"
-                SELECT id, user_id, title, author, total_pages, current_page,
-                       status, started_at, completed_at, rating, notes,
-                       cover_blob_id, created_at, updated_at
-                FROM books
-                WHERE user_id = $1 AND status = $2
-                ORDER BY updated_at DESC
-                "#,
-            )
-            .bind(user_id)
-            .bind(s)
-            .fetch_all(pool)
-            .await?
+            sqlx::query_as::<_, Book>(BOOK_LIST_BY_STATUS)
+                .bind(user_id)
+                .bind(s)
+                .fetch_all(pool)
+                .await?
         } else {
-            sqlx::query_as::<_, Book>(
-                r#"
-                SELECT id, user_id, title, author, total_pages, current_page,
-                       status, started_at, completed_at, rating, notes,
-                       cover_blob_id, created_at, updated_at
-                FROM books
-                WHERE user_id = $1
-                ORDER BY updated_at DESC
-                "#,
-            )
-            .bind(user_id)
-            .fetch_all(pool)
-            .await?
+            sqlx::query_as::<_, Book>(BOOK_LIST)
+                .bind(user_id)
+                .fetch_all(pool)
+                .await?
         };
 
         let total = books.len() as i64;
@@ -67,19 +99,11 @@ impl BookRepo {
         id: Uuid,
         user_id: Uuid,
     ) -> Result<Option<Book>, AppError> {
-        let book = sqlx::query_as::<_, Book>(
-            r#"
-            SELECT id, user_id, title, author, total_pages, current_page,
-                   status, started_at, completed_at, rating, notes,
-                   cover_blob_id, created_at, updated_at
-            FROM books
-            WHERE id = $1 AND user_id = $2
-            "#,
-        )
-        .bind(id)
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let book = sqlx::query_as::<_, Book>(BOOK_GET_BY_ID)
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
 
         Ok(book)
     }
@@ -92,22 +116,14 @@ impl BookRepo {
     ) -> Result<Book, AppError> {
         let status = req.status.as_deref().unwrap_or("want_to_read");
 
-        let book = sqlx::query_as::<_, Book>(
-            r#"
-            INSERT INTO books (user_id, title, author, total_pages, status)
-            VALUES ($1, $2, $3, $4, $5)
-            RETURNING id, user_id, title, author, total_pages, current_page,
-                      status, started_at, completed_at, rating, notes,
-                      cover_blob_id, created_at, updated_at
-            "#,
-        )
-        .bind(user_id)
-        .bind(&req.title)
-        .bind(&req.author)
-        .bind(req.total_pages)
-        .bind(status)
-        .fetch_one(pool)
-        .await?;
+        let book = sqlx::query_as::<_, Book>(BOOK_CREATE)
+            .bind(user_id)
+            .bind(&req.title)
+            .bind(&req.author)
+            .bind(req.total_pages)
+            .bind(status)
+            .fetch_one(pool)
+            .await?;
 
         Ok(book)
     }
@@ -147,42 +163,37 @@ impl BookRepo {
             existing.started_at
         };
 
-        let book = sqlx::query_as::<_, Book>(
-            r#"
-            UPDATE books SET
-                title = $3, author = $4, total_pages = $5, current_page = $6,
-                status = $7, rating = $8, notes = $9,
-                started_at = $10, completed_at = $11
-            WHERE id = $1 AND user_id = $2
-            RETURNING id, user_id, title, author, total_pages, current_page,
-                      status, started_at, completed_at, rating, notes,
-                      cover_blob_id, created_at, updated_at
-            "#,
-        )
-        .bind(id)
-        .bind(user_id)
-        .bind(title)
-        .bind(author)
-        .bind(total_pages)
-        .bind(current_page)
-        .bind(status)
-        .bind(rating)
-        .bind(notes)
-        .bind(started_at)
-        .bind(completed_at)
-        .fetch_one(pool)
-        .await?;
+        let mut tx = pool.begin().await?;
+        let book = sqlx::query_as::<_, Book>(BOOK_UPDATE)
+            .bind(id)
+            .bind(user_id)
+            .bind(title)
+            .bind(author)
+            .bind(total_pages)
+            .bind(current_page)
+            .bind(status)
+            .bind(rating)
+            .bind(notes)
+            .bind(started_at)
+            .bind(completed_at)
+            .fetch_one(&mut *tx)
+            .await?;
+        GoalsRepo::refresh_linked(&mut tx, user_id, "book", id).await?;
+        tx.commit().await?;
 
         Ok(book)
     }
 
-    /// Delete book
+    /// Delete book; goals linking to it stop counting it
     pub async fn delete(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
-        let result = sqlx::query("DELETE FROM books WHERE id = $1 AND user_id = $2")
+        let mut tx = pool.begin().await?;
+        let result = sqlx::query(BOOK_DELETE)
             .bind(id)
             .bind(user_id)
-            .execute(pool)
+            .execute(&mut *tx)
             .await?;
+        GoalsRepo::refresh_linked(&mut tx, user_id, "book", id).await?;
+        tx.commit().await?;
 
         Ok(result.rows_affected() > 0)
     }
@@ -194,6 +205,58 @@ impl BookRepo {
 
 pub struct ReadingSessionRepo;
 
+pub const READING_SESSION_LIST_FOR_BOOK: &str = r#"
+    SELECT id, book_id, user_id, pages_read, duration_minutes,
+           started_at, notes, xp_awarded, coins_awarded
+    FROM reading_sessions
+    WHERE book_id = $1 AND user_id = $2
+    ORDER BY started_at DESC
+"#;
+
+pub const READING_SESSION_LOG_READING_BOOK: &str = r#"
+    SELECT id, user_id, title, author, total_pages, current_page,
+           status, started_at, completed_at, rating, notes,
+           cover_blob_id, created_at, updated_at
+    FROM books
+    WHERE id = $1 AND user_id = $2
+    FOR UPDATE
+"#;
+
+pub const READING_SESSION_LOG_READING_SESSION: &str = r#"
+    INSERT INTO reading_sessions (book_id, user_id, pages_read, duration_minutes, started_at, notes, xp_awarded, coins_awarded)
+    VALUES ($1, $2, $3, $4, NOW(), $5, $6, $7)
+    RETURNING id, book_id, user_id, pages_read, duration_minutes,
+              started_at, notes, xp_awarded, coins_awarded
+"#;
+
+pub const READING_SESSION_LOG_READING_UPDATED_BOOK: &str = r#"
+    UPDATE books SET
+        current_page = $3,
+        status = CASE WHEN $4 THEN 'completed' ELSE CASE WHEN status = 'want_to_read' THEN 'reading' ELSE status END END,
+        started_at = COALESCE(started_at, NOW()),
+        completed_at = CASE WHEN $4 THEN NOW() ELSE completed_at END
+    WHERE id = $1 AND user_id = $2
+    RETURNING id, user_id, title, author, total_pages, current_page,
+              status, started_at, completed_at, rating, notes,
+              cover_blob_id, created_at, updated_at
+"#;
+
+pub const READING_SESSION_GET_STATS_STATS: &str = r#"
+    SELECT
+        COUNT(*) FILTER (WHERE status = 'completed') as books_completed,
+        COUNT(*) FILTER (WHERE status = 'reading') as books_reading,
+        COUNT(*) as total_books,
+        COALESCE(SUM(current_page), 0) as total_pages_read
+    FROM books
+    WHERE user_id = $1
+"#;
+
+pub const READING_SESSION_GET_STATS_READING_TIME: &str = r#"
+    SELECT COALESCE(SUM(duration_minutes), 0) as total
+    FROM reading_sessions
+    WHERE user_id = $1
+"#;
+
 impl ReadingSessionRepo {
     /// List reading sessions for a book
     pub async fn list_for_book(
@@ -201,19 +264,11 @@ impl ReadingSessionRepo {
         book_id: Uuid,
         user_id: Uuid,
     ) -> Result<SessionsListResponse, AppError> {
-        let sessions = sqlx::query_as::<_, ReadingSession>(
-            r#"
-            SELECT id, book_id, user_id, pages_read, duration_minutes,
-                   started_at, notes, xp_awarded, coins_awarded
-            FROM reading_sessions
-            WHERE book_id = $1 AND user_id = $2
-            ORDER BY started_at DESC
-            "#,
-        )
-        .bind(book_id)
-        .bind(user_id)
-        .fetch_all(pool)
-        .await?;
+        let sessions = sqlx::query_as::<_, ReadingSession>(READING_SESSION_LIST_FOR_BOOK)
+            .bind(book_id)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
 
         let total = sessions.len() as i64;
 
@@ -231,20 +286,12 @@ impl ReadingSessionRepo {
         req: &LogReadingRequest,
     ) -> Result<LogReadingResult, AppError> {
         // Get and lock book
-        let book = sqlx::query_as::<_, Book>(
-            r#"
-            SELECT id, user_id, title, author, total_pages, current_page,
-                   status, started_at, completed_at, rating, notes,
-                   cover_blob_id, created_at, updated_at
-            FROM books
-            WHERE id = $1 AND user_id = $2
-            FOR UPDATE
-            "#,
-        )
-        .bind(book_id)
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let mut tx = pool.begin().await?;
+        let book = sqlx::query_as::<_, Book>(READING_SESSION_LOG_READING_BOOK)
+            .bind(book_id)
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?;
 
         let book = book.ok_or_else(|| AppError::NotFound("Book not found".to_string()))?;
 
@@ -263,43 +310,43 @@ impl ReadingSessionRepo {
         let coins = (req.pages_read / 10).max(0);
 
         // Create session
-        let session = sqlx::query_as::<_, ReadingSession>(
-            r#"
-            INSERT INTO reading_sessions (book_id, user_id, pages_read, duration_minutes, started_at, notes, xp_awarded, coins_awarded)
-            VALUES ($1, $2, $3, $4, NOW(), $5, $6, $7)
-            RETURNING id, book_id, user_id, pages_read, duration_minutes,
-                      started_at, notes, xp_awarded, coins_awarded
-            "#,
-        )
-        .bind(book_id)
-        .bind(user_id)
-        .bind(req.pages_read)
-        .bind(req.duration_minutes)
-        .bind(&req.notes)
-        .bind(xp)
-        .bind(coins)
-        .fetch_one(pool)
-        .await?;
+        let session = sqlx::query_as::<_, ReadingSession>(READING_SESSION_LOG_READING_SESSION)
+            .bind(book_id)
+            .bind(user_id)
+            .bind(req.pages_read)
+            .bind(req.duration_minutes)
+            .bind(&req.notes)
+            .bind(xp)
+            .bind(coins)
+            .fetch_one(&mut *tx)
+            .await?;
 
         // Update book
-        let updated_book = sqlx::query_as::<_, Book>(
-            r#"
-            UPDATE books SET
-                current_page = $3,
-                status = CASE WHEN $4 THEN 'completed' ELSE CASE WHEN status = 'want_to_read' THEN 'reading' ELSE status END END,
-                started_at = COALESCE(started_at, NOW()),
-                completed_at = CASE WHEN $4 THEN NOW() ELSE completed_at END
-            WHERE id = $1 AND user_id = $2
-            RETURNING id, user_id, title, author, total_pages, current_page,
-                      status, started_at, completed_at, rating, notes,
-                      cover_blob_id, created_at, updated_at
-            "#,
+        let updated_book = sqlx::query_as::<_, Book>(READING_SESSION_LOG_READING_UPDATED_BOOK)
+            .bind(book_id)
+            .bind(user_id)
+            .bind(new_page)
+            .bind(is_completed)
+            .fetch_one(&mut *tx)
+            .await?;
+        GoalsRepo::refresh_linked(&mut tx, user_id, "book", book_id).await?;
+        tx.commit().await?;
+
+        GamificationRepo::award_points(
+            pool,
+            user_id,
+            &AwardPointsInput {
+                xp: Some(xp),
+                coins: Some(coins),
+                skill_stars: None,
+                skill_key: None,
+                event_type: "reading_logged".to_string(),
+                event_id: Some(book_id),
+                reason: Some(format!("Read {} pages of {}", req.pages_read, book.title)),
+                idempotency_key: Some(format!("reading_session_{}", session.id)),
+                quantity: req.duration_minutes,
+            },
         )
-        .bind(book_id)
-        .bind(user_id)
-        .bind(new_page)
-        .bind(is_completed)
-        .fetch_one(pool)
         .await?;
 
         Ok(LogReadingResult {
@@ -321,36 +368,20 @@ impl ReadingSessionRepo {
             total_pages_read: Option<i64>,
         }
 
-        let stats = sqlx::query_as::<_, StatsRow>(
-            r#"
-            SELECT
-                COUNT(*) FILTER (WHERE status = 'completed') as books_completed,
-                COUNT(*) FILTER (WHERE status = 'reading') as books_reading,
-                COUNT(*) as total_books,
-                COALESCE(SUM(current_page), 0) as total_pages_read
-            FROM books
-            WHERE user_id = $1
-            "#,
-        )
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
+        let stats = sqlx::query_as::<_, StatsRow>(READING_SESSION_GET_STATS_STATS)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
 
         #[derive(sqlx::FromRow)]
         struct TimeRow {
             total: Option<i64>,
         }
 
-        let reading_time = sqlx::query_as::<_, TimeRow>(
-            r#"
-            SELECT COALESCE(SUM(duration_minutes), 0) as total
-            FROM reading_sessions
-            WHERE user_id = $1
-            "#,
-        )
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
+        let reading_time = sqlx::query_as::<_, TimeRow>(READING_SESSION_GET_STATS_READING_TIME)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
 
         Ok(ReadingStatsResponse {
             books_completed: stats.books_completed.unwrap_or(0),
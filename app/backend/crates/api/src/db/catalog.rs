@@ -0,0 +1,774 @@
+//! Query catalog
+//!
+//! Every SQL statement run by the repositories, registered by name so the
+//! whole set can be checked against a migrated schema before deploy, with
+//! `ignition-api check-queries` or the catalog test.
+//!
+//! Statements are checked with `PREPARE`, which rejects unknown tables and
+//! columns and parameters whose types cannot be deduced consistently. It does
+//! not see the Rust side, so row decoding (`SELECT *` into a struct) and bind
+//! types are still only covered by the repository tests.
+//!
+//! The admin table browser and SQL console run operator-supplied SQL and are
+//! not catalogued, nor are the per-table backup statements, which are built
+//! from the live table list.
+
+use std::borrow::Cow;
+
+use sqlx::{Executor, PgPool};
+
+use super::{
+    account_deletion_repos, admin_repos, api_token_repos, archive_repos, books_repos,
+    exercise_repos, feature_flag_repos, focus_repos, frames_repos, gamification_repos,
+    habits_goals_repos, idea_attachment_repos, inbox_repos, infobase_link_repos, learn_repos,
+    market_repos, notification_repos, oauth_repos, onboarding_repos, passkey_repos,
+    personalization_repos, plan_repos, platform_repos, quests_repos, rate_limit_repos,
+    reference_repos, references_repos, repos, revision_repos, search_repos, sync_repos,
+    template_repos,
+};
+use crate::routes::db::user_settings_repos;
+use crate::routes::{admin, exercise, sync, today};
+use crate::shared::audit;
+
+/// A named SQL statement
+pub struct CatalogQuery {
+    pub name: Cow<'static, str>,
+    pub sql: Cow<'static, str>,
+}
+
+/// A catalogued statement that failed to prepare
+#[derive(Debug)]
+pub struct CatalogFailure {
+    pub name: String,
+    pub error: String,
+}
+
+/// Register statement constants as `module::NAME`
+macro_rules! register {
+    ($($module:ident: [$($name:ident),* $(,)?]),* $(,)?) => {
+        vec![$($(CatalogQuery {
+            name: Cow::Borrowed(concat!(stringify!($module), "::", stringify!($name))),
+            sql: Cow::Borrowed($module::$name),
+        },)*)*]
+    };
+}
+
+/// All catalogued statements
+pub fn queries() -> Vec<CatalogQuery> {
+    let mut queries = register! {
+        account_deletion_repos: [
+            ACCOUNT_DELETION_SCHEDULE,
+            ACCOUNT_DELETION_GET,
+            ACCOUNT_DELETION_CANCEL,
+            ACCOUNT_DELETION_CLAIM_DUE,
+            ACCOUNT_DELETION_CLAIM_NOW,
+            ACCOUNT_DELETION_RECORD_OBJECTS,
+            ACCOUNT_DELETION_FAIL,
+            ACCOUNT_DELETION_USER_EXISTS,
+            ACCOUNT_DELETION_DELETE_USER,
+            ACCOUNT_DELETION_COMPLETE,
+        ],
+        admin_repos: [
+            ADMIN_CLAIM_HAS_ANY_ADMINS,
+            ADMIN_CLAIM_IS_USER_ADMIN,
+            ADMIN_CLAIM_SET_USER_ADMIN,
+            ADMIN_CLAIM_COUNT_ADMINS,
+            ADMIN_USER_LIST_USERS,
+            ADMIN_USER_GET_USER,
+            ADMIN_USER_DELETE_USER_DELETE_ACTIVITY_EVENTS,
+            ADMIN_USER_DELETE_USER_DELETE_USER_PROGRESS,
+            ADMIN_USER_DELETE_USER_DELETE_FOCUS_SESSIONS,
+            ADMIN_USER_DELETE_USER_DELETE_CALENDAR_EVENTS,
+            ADMIN_USER_DELETE_USER_DELETE_USER_QUEST_PROGRESS,
+            ADMIN_USER_DELETE_USER_DELETE_HABIT_COMPLETIONS,
+            ADMIN_USER_DELETE_USER_DELETE_HABITS,
+            ADMIN_USER_DELETE_USER_DELETE_GOAL_MILESTONES,
+            ADMIN_USER_DELETE_USER_DELETE_GOAL_LINKS,
+            ADMIN_USER_DELETE_USER_DELETE_GOALS,
+            ADMIN_USER_DELETE_USER_DELETE_IDEAS,
+            ADMIN_USER_DELETE_USER_DELETE_READING_SESSIONS,
+            ADMIN_USER_DELETE_USER_DELETE_BOOKS,
+            ADMIN_USER_DELETE_USER_DELETE_FEEDBACK,
+            ADMIN_USER_DELETE_USER_DELETE_USER_ONBOARDING_RESPONSES,
+            ADMIN_USER_DELETE_USER_DELETE_USER_ONBOARDING_STATE,
+            ADMIN_USER_DELETE_USER_DELETE_USER_SETTINGS,
+            ADMIN_USER_DELETE_USER_DELETE_POINTS_LEDGER,
+            ADMIN_USER_DELETE_USER_DELETE_USER_WALLET,
+            ADMIN_USER_DELETE_USER_DELETE_SESSIONS,
+            ADMIN_USER_DELETE_USER_DELETE_ACCOUNTS,
+            ADMIN_USER_DELETE_USER_DELETE_USERS,
+            ADMIN_STATS_GET_STATS,
+            ADMIN_STATS_GET_USER_STATS_TOTAL,
+            ADMIN_STATS_GET_USER_STATS_TOS,
+            ADMIN_STATS_GET_USER_STATS_ADMINS,
+            ADMIN_STATS_GET_USER_STATS_ACTIVE_7D,
+            ADMIN_STATS_GET_USER_STATS_ACTIVE_30D,
+            ADMIN_STATS_GET_CONTENT_STATS_EXERCISES,
+            ADMIN_STATS_GET_CONTENT_STATS_UNIVERSAL_QUESTS,
+            ADMIN_STATS_GET_CONTENT_STATS_USER_QUESTS,
+            ADMIN_STATS_GET_CONTENT_STATS_MARKET_ITEMS,
+            ADMIN_STATS_GET_ACTIVITY_STATS_FOCUS,
+            ADMIN_STATS_GET_ACTIVITY_STATS_COMPLETED,
+            ADMIN_STATS_GET_ACTIVITY_STATS_HABITS,
+            ADMIN_STATS_GET_ACTIVITY_STATS_GOALS,
+            ADMIN_STATS_GET_ACTIVITY_STATS_IDEAS,
+            ADMIN_STATS_GET_ACTIVITY_STATS_BOOKS,
+            ADMIN_STATS_GET_GAMIFICATION_STATS_PURCHASES,
+            ADMIN_STATS_GET_GAMIFICATION_STATS_ACHIEVEMENTS,
+            ADMIN_FEEDBACK_LIST_FEEDBACK,
+            ADMIN_FEEDBACK_UPDATE_FEEDBACK_UPDATE_FEEDBACK,
+            ADMIN_FEEDBACK_UPDATE_FEEDBACK_FEEDBACK,
+            ADMIN_QUEST_LIST_QUESTS,
+            ADMIN_QUEST_GET_QUEST,
+            ADMIN_QUEST_CREATE_QUEST,
+            ADMIN_QUEST_UPDATE_QUEST,
+            ADMIN_QUEST_DELETE_QUEST,
+            ADMIN_SKILL_LIST_SKILLS,
+            ADMIN_SKILL_GET_SKILL,
+            ADMIN_SKILL_UPSERT_SKILL_ORDER_ROW,
+            ADMIN_SKILL_UPSERT_SKILL_SKILL,
+            ADMIN_SKILL_UPDATE_SKILL,
+            ADMIN_SKILL_DELETE_SKILL,
+            ADMIN_DB_GET_HEALTH,
+            ADMIN_BACKUP_SNAPSHOT,
+            ADMIN_BACKUP_LIST_TABLES,
+            ADMIN_BACKUP_LIST_FOREIGN_KEYS,
+            ADMIN_BACKUP_LIST_SERIAL_COLUMNS,
+            ADMIN_CONSOLE_LIST_SAVED,
+            ADMIN_CONSOLE_SAVE,
+            ADMIN_CONSOLE_DELETE_SAVED,
+            ADMIN_AUDIT_LIST_ENTRIES_TOTAL,
+            ADMIN_AUDIT_LIST_ENTRIES_AUDIT_LOG,
+            ADMIN_AUDIT_GET_EVENT_TYPES,
+        ],
+        api_token_repos: [
+            API_TOKEN_CREATE,
+            API_TOKEN_FIND_ACTIVE,
+            API_TOKEN_LIST_FOR_USER,
+            API_TOKEN_REVOKE,
+            API_TOKEN_REVOKE_ALL_FOR_USER,
+            API_TOKEN_TOUCH,
+        ],
+        archive_repos: [
+            ARCHIVE_SNAPSHOT,
+            ARCHIVE_JOB_CREATE,
+            ARCHIVE_JOB_CLAIM,
+            ARCHIVE_JOB_RENEW,
+            ARCHIVE_JOB_COMPLETE,
+            ARCHIVE_JOB_FAIL,
+            ARCHIVE_JOB_FAIL_EXPIRED,
+            ARCHIVE_JOB_GET,
+            ARCHIVE_JOB_LIST,
+        ],
+        books_repos: [
+            BOOK_LIST_BY_STATUS,
+            BOOK_LIST,
+            BOOK_GET_BY_ID,
+            BOOK_CREATE,
+            BOOK_UPDATE,
+            BOOK_DELETE,
+            READING_SESSION_LIST_FOR_BOOK,
+            READING_SESSION_LOG_READING_BOOK,
+            READING_SESSION_LOG_READING_SESSION,
+            READING_SESSION_LOG_READING_UPDATED_BOOK,
+            READING_SESSION_GET_STATS_STATS,
+            READING_SESSION_GET_STATS_READING_TIME,
+        ],
+        exercise_repos: [
+            EXERCISE_LIST_BY_CATEGORY,
+            EXERCISE_LIST,
+            EXERCISE_CREATE,
+            EXERCISE_GET_BY_ID,
+            EXERCISE_DELETE,
+            EXERCISE_SEED_BUILTIN,
+            WORKOUT_LIST_TEMPLATES,
+            WORKOUT_LIST,
+            WORKOUT_GET_WORKOUT_EXERCISES,
+            WORKOUT_CREATE,
+            WORKOUT_GET_BY_ID,
+            WORKOUT_DELETE,
+            WORKOUT_SESSION_LIST,
+            WORKOUT_SESSION_START,
+            WORKOUT_SESSION_LOG_SET_SESSION,
+            WORKOUT_SESSION_LOG_SET_SET,
+            WORKOUT_SESSION_COMPLETE_SESSION,
+            WORKOUT_SESSION_COMPLETE_SETS_LOGGED,
+            WORKOUT_SESSION_COMPLETE_UPDATE_WORKOUT_SESSIONS,
+            WORKOUT_SESSION_COMPLETE_WORKOUTS,
+            WORKOUT_SESSION_GET_ACTIVE,
+            PROGRAM_LIST,
+            PROGRAM_CREATE,
+            PROGRAM_GET_BY_ID,
+            PROGRAM_ACTIVATE_UPDATE_TRAINING_PROGRAMS,
+            PROGRAM_ACTIVATE_PROGRAM,
+        ],
+        feature_flag_repos: [
+            FEATURE_FLAG_LIST,
+            FEATURE_FLAG_GET,
+            FEATURE_FLAG_CREATE,
+            FEATURE_FLAG_UPDATE,
+            FEATURE_FLAG_DELETE,
+        ],
+        focus_repos: [
+            FOCUS_SESSION_START_SESSION_UPDATE_FOCUS_SESSIONS,
+            FOCUS_SESSION_START_SESSION_DELETE_FOCUS_PAUSE_STATE,
+            FOCUS_SESSION_START_SESSION_SESSION,
+            FOCUS_SESSION_GET_SESSION,
+            FOCUS_SESSION_GET_ACTIVE_SESSION,
+            FOCUS_SESSION_COMPLETE_SESSION_UPDATE_FOCUS_SESSIONS,
+            FOCUS_SESSION_COMPLETE_SESSION_DELETE_FOCUS_PAUSE_STATE,
+            FOCUS_SESSION_ABANDON_SESSION_UPDATE_FOCUS_SESSIONS,
+            FOCUS_SESSION_ABANDON_SESSION_DELETE_FOCUS_PAUSE_STATE,
+            FOCUS_SESSION_LIST_SESSIONS_SESSIONS,
+            FOCUS_SESSION_LIST_SESSIONS_TOTAL,
+            FOCUS_SESSION_GET_STATS_SINCE,
+            FOCUS_SESSION_GET_STATS,
+            FOCUS_PAUSE_GET_PAUSE_STATE,
+            FOCUS_PAUSE_PAUSE_SESSION_UPDATE_FOCUS_SESSIONS,
+            FOCUS_PAUSE_PAUSE_SESSION_STATE,
+            FOCUS_PAUSE_RESUME_SESSION_SESSION,
+            FOCUS_PAUSE_RESUME_SESSION_DELETE_FOCUS_PAUSE_STATE,
+            FOCUS_PAUSE_CLEAR_PAUSE_STATE,
+            FOCUS_LIBRARY_LIST_LIBRARIES,
+            FOCUS_LIBRARY_LIST_TOTAL,
+            FOCUS_LIBRARY_GET,
+            FOCUS_LIBRARY_CREATE,
+            FOCUS_LIBRARY_DELETE_DELETE_FOCUS_LIBRARY_TRACKS,
+            FOCUS_LIBRARY_DELETE_DELETE_FOCUS_LIBRARIES,
+            FOCUS_LIBRARY_TOGGLE_FAVORITE,
+        ],
+        frames_repos: [
+            FRAME_MANIFEST_CREATE,
+            FRAME_MANIFEST_GET_BY_ANALYSIS,
+            FRAME_MANIFEST_GET_BY_ID,
+            FRAME_MANIFEST_UPDATE_EVENTS,
+            FRAME_MANIFEST_DELETE,
+            FRAME_DATA_CREATE_CHUNK,
+            FRAME_DATA_GET_CHUNKS_FOR_RANGE,
+            FRAME_DATA_GET_ALL_CHUNKS,
+            FRAME_DATA_GET_CHUNK_BY_INDEX,
+            ANALYSIS_EVENTS_CREATE,
+            ANALYSIS_EVENTS_CREATE_BATCH,
+            ANALYSIS_EVENTS_GET_FOR_ANALYSIS,
+        ],
+        gamification_repos: [
+            USER_PROGRESS_GET_OR_CREATE_USER_PROGRESS,
+            USER_PROGRESS_GET_OR_CREATE_PROGRESS,
+            USER_PROGRESS_AWARD_XP_POINTS_LEDGER,
+            USER_PROGRESS_AWARD_XP_UPDATE_USER_PROGRESS,
+            USER_PROGRESS_AWARD_XP_INSERT_POINTS_LEDGER,
+            USER_WALLET_GET_OR_CREATE_USER_WALLET,
+            USER_WALLET_GET_OR_CREATE_WALLET,
+            USER_WALLET_AWARD_COINS_POINTS_LEDGER,
+            USER_WALLET_AWARD_COINS_NEW_BALANCE,
+            USER_WALLET_AWARD_COINS_INSERT_POINTS_LEDGER,
+            USER_WALLET_SPEND_COINS_NEW_BALANCE,
+            USER_WALLET_SPEND_COINS_INSERT_POINTS_LEDGER,
+            STREAKS_GET_STREAK,
+            STREAKS_UPDATE_STREAK_INSERT_USER_STREAKS,
+            STREAKS_UPDATE_STREAK_UPDATE_USER_STREAKS,
+            STREAKS_GET_MAX_CURRENT_STREAK,
+            STREAKS_GET_MAX_LONGEST_STREAK,
+            ACHIEVEMENTS_GET_DEFINITIONS,
+            ACHIEVEMENTS_GET_USER_ACHIEVEMENTS,
+            ACHIEVEMENTS_HAS_ACHIEVEMENT,
+            ACHIEVEMENTS_UNLOCK_ACHIEVEMENT,
+            ACHIEVEMENTS_GET_ACHIEVEMENT_COUNT,
+            GAMIFICATION_GET_ACHIEVEMENT_TEASER_ACHIEVEMENTS,
+            GAMIFICATION_GET_ACHIEVEMENT_TEASER_FOCUS_COUNT,
+            GAMIFICATION_GET_ACHIEVEMENT_TEASER_QUEST_COUNT,
+        ],
+        habits_goals_repos: [
+            HABITS_CREATE,
+            HABITS_GET_BY_ID,
+            HABITS_GET_FOR_UPDATE,
+            HABITS_LIST,
+            HABITS_COMPLETIONS_ON,
+            HABITS_COMPLETION_COUNTS,
+            HABITS_COMPLETE_HABIT_INSERT_HABIT_COMPLETIONS,
+            HABITS_UNCOMPLETE,
+            HABITS_UPDATE_STREAKS,
+            HABITS_UPDATE,
+            HABITS_SET_ACTIVE,
+            HABITS_DELETE_COMPLETIONS,
+            HABITS_DELETE_UNLINK_EVENTS,
+            HABITS_DELETE,
+            HABITS_REORDER,
+            GOALS_CREATE,
+            GOALS_GET_BY_ID_GOAL,
+            GOALS_GET_FOR_UPDATE,
+            GOALS_LIST_LINKED_FOR_UPDATE,
+            GOALS_LIST,
+            GOALS_LIST_GOAL_MILESTONES,
+            GOALS_LIST_GOAL_LINKS,
+            GOALS_REFRESH,
+            GOALS_UPDATE,
+            GOALS_SET_ARCHIVED,
+            GOALS_DELETE_MILESTONES,
+            GOALS_DELETE_LINKS,
+            GOALS_DELETE_UNLINK_EVENTS,
+            GOALS_DELETE,
+            GOALS_REORDER,
+            GOALS_ADD_MILESTONE,
+            GOALS_GET_MILESTONE_FOR_UPDATE,
+            GOALS_COMPLETE_MILESTONE_UPDATE_GOAL_MILESTONES,
+            GOALS_UPDATE_MILESTONE,
+            GOALS_DELETE_MILESTONE,
+            GOALS_LINK_TARGET_TITLE,
+            GOALS_ADD_LINK,
+            GOALS_DELETE_LINK,
+        ],
+        idea_attachment_repos: [
+            IDEA_ATTACHMENT_CREATE,
+            IDEA_ATTACHMENT_LIST,
+            IDEA_ATTACHMENT_GET,
+            IDEA_ATTACHMENT_GET_FOR_UPDATE,
+            IDEA_ATTACHMENT_SET_TRACK,
+            IDEA_ATTACHMENT_DELETE,
+        ],
+        inbox_repos: [
+            INBOX_LIST_WITH_ARCHIVED,
+            INBOX_LIST,
+            INBOX_LIST_TOTAL,
+            INBOX_LIST_UNREAD,
+            INBOX_GET,
+            INBOX_CREATE,
+            INBOX_UPDATE,
+            INBOX_MARK_ALL_READ,
+            INBOX_DELETE,
+            INBOX_UNREAD_COUNT,
+            INBOX_GET_FOR_UPDATE,
+            INBOX_MARK_PROCESSED,
+            INBOX_CONVERSION_CREATE,
+            INBOX_SOURCE_FOR_TARGET,
+            INBOX_TRIAGE_READ,
+            INBOX_TRIAGE_ARCHIVE,
+            INBOX_TRIAGE_PROCESS,
+            INBOX_TRIAGE_DELETE,
+            INBOX_EXPIRE,
+        ],
+        infobase_link_repos: [
+            INFOBASE_LINK_DELETE_FOR_SOURCE,
+            INFOBASE_LINK_INSERT,
+            INFOBASE_LINK_RESOLVE_TITLE,
+            INFOBASE_LINK_RETARGET_DELETED,
+            INFOBASE_LINK_INBOUND_SOURCES,
+            INFOBASE_LINK_SET_CONTENT,
+            INFOBASE_LINK_DROP_RENAME_DUPLICATES,
+            INFOBASE_LINK_RENAME_TARGET,
+            INFOBASE_LINK_BACKLINKS,
+            INFOBASE_LINK_BROKEN,
+            INFOBASE_LINK_GRAPH_NODES,
+            INFOBASE_LINK_GRAPH_EDGES,
+        ],
+        learn_repos: [
+            LEARN_LIST_TOPICS,
+            LEARN_LIST_LESSONS,
+            LEARN_GET_LESSON_CONTENT,
+            LEARN_START_LESSON_LEARN_LESSONS,
+            LEARN_START_LESSON_PROGRESS,
+            LEARN_COMPLETE_LESSON_LESSON,
+            LEARN_COMPLETE_LESSON_USER_LESSON_PROGRESS,
+            LEARN_COMPLETE_LESSON_INSERT_USER_LESSON_PROGRESS,
+            LEARN_LIST_DRILLS,
+            LEARN_SUBMIT_DRILL_DRILL,
+            LEARN_SUBMIT_DRILL_USER_DRILL_STATS,
+            LEARN_SUBMIT_DRILL_INSERT_USER_DRILL_STATS,
+            LEARN_GET_REVIEW_ITEMS_LESSONS_DUE,
+            LEARN_GET_REVIEW_ITEMS_DRILLS_DUE,
+            LEARN_GET_PROGRESS_SUMMARY_STATS,
+            LEARN_GET_PROGRESS_SUMMARY_TOTAL_LESSONS,
+            LEARN_GET_PROGRESS_SUMMARY_DRILLS_PRACTICED,
+            DRILL_SESSION_START_DRILL,
+            DRILL_SESSION_START_SESSION,
+            DRILL_SESSION_LOAD,
+            DRILL_SESSION_ANSWER_LAST_ANSWER_AT,
+            DRILL_SESSION_ANSWER_INSERT_DRILL_SESSION_ANSWERS,
+            DRILL_SESSION_ANSWER_ANSWERED_COUNT,
+            DRILL_SESSION_COMPLETE_ANSWERS,
+            DRILL_SESSION_COMPLETE_UPDATE_DRILL_SESSIONS,
+            DRILL_SESSION_COMPLETE_INSERT_USER_DRILL_STATS,
+            DRILL_SESSION_COMPLETE_XP_REWARD,
+            DRILL_SESSION_GET_STATS,
+        ],
+        market_repos: [
+            MARKET_LIST_ITEMS_BY_CATEGORY,
+            MARKET_LIST_ITEMS,
+            MARKET_GET_ITEM_BY_KEY,
+            MARKET_PURCHASE_ITEM,
+            MARKET_PURCHASE_WALLET_ROW,
+            MARKET_PURCHASE_NEW_BALANCE,
+            MARKET_PURCHASE_UPDATE_MARKET_ITEMS,
+            MARKET_PURCHASE_PURCHASE,
+            MARKET_REDEEM_PURCHASE,
+            MARKET_REDEEM_ITEM,
+            MARKET_REDEEM_UPDATE_USER_PURCHASES,
+            MARKET_GET_PURCHASE_HISTORY,
+            MARKET_GET_WALLET,
+            MARKET_CREATE_ITEM,
+        ],
+        notification_repos: [
+            NOTIFICATION_MATERIALIZE_CALENDAR_EVENTS,
+            NOTIFICATION_MATERIALIZE_HABIT_CHECKINS,
+            NOTIFICATION_MATERIALIZE_EXPIRING_QUESTS,
+            NOTIFICATION_MATERIALIZE_FOCUS_ENDS,
+            NOTIFICATION_SKIP_EXPIRED,
+            NOTIFICATION_CLAIM_DUE,
+            NOTIFICATION_MARK_SENT,
+            NOTIFICATION_SCHEDULE_RETRY,
+            NOTIFICATION_MARK_FINISHED,
+            NOTIFICATION_LIST_RECENT,
+            NOTIFICATION_PRUNE,
+            PUSH_SUBSCRIPTION_UPSERT,
+            PUSH_SUBSCRIPTION_LIST_FOR_USER,
+            PUSH_SUBSCRIPTION_DELETE,
+            PUSH_SUBSCRIPTION_DELETE_BY_ID,
+            PUSH_SUBSCRIPTION_TOUCH,
+        ],
+        oauth_repos: [
+            OAUTH_STATE_INSERT,
+            OAUTH_STATE_TAKE,
+            OAUTH_STATE_CLEANUP_EXPIRED,
+        ],
+        onboarding_repos: [
+            ONBOARDING_FLOW_LIST,
+            ONBOARDING_FLOW_GET_FOR_UPDATE,
+            ONBOARDING_FLOW_CREATE,
+            ONBOARDING_FLOW_UPDATE,
+            ONBOARDING_FLOW_IN_USE,
+            ONBOARDING_FLOW_DELETE,
+            ONBOARDING_FLOW_HAS_DRAFT,
+            ONBOARDING_FLOW_NEW_VERSION,
+            ONBOARDING_FLOW_COPY_STEPS,
+            ONBOARDING_FLOW_DEACTIVATE_OTHERS,
+            ONBOARDING_FLOW_PUBLISH,
+            ONBOARDING_FLOW_COUNT_STEPS,
+            ONBOARDING_STEP_MAKE_ROOM,
+            ONBOARDING_STEP_CREATE,
+            ONBOARDING_STEP_UPDATE,
+            ONBOARDING_STEP_DELETE,
+            ONBOARDING_FUNNEL_TOTALS,
+            ONBOARDING_FUNNEL_STEPS,
+        ],
+        passkey_repos: [
+            WEBAUTHN_CHALLENGE_INSERT,
+            WEBAUTHN_CHALLENGE_TAKE,
+            WEBAUTHN_CHALLENGE_CLEANUP_EXPIRED,
+            AUTHENTICATOR_FIND_BY_CREDENTIAL_ID,
+            AUTHENTICATOR_LIST_FOR_USER,
+            AUTHENTICATOR_CREATE,
+            AUTHENTICATOR_RECORD_USE,
+            AUTHENTICATOR_DELETE_FOR_USER,
+        ],
+        personalization_repos: [
+            PERSONALIZATION_PROFILE_INTERESTS,
+            PERSONALIZATION_SETTINGS_INTERESTS,
+            PERSONALIZATION_ONBOARDING_RESPONSES,
+            PERSONALIZATION_RECENT_ACTIVITY,
+            PERSONALIZATION_LESSONS,
+            PERSONALIZATION_PROMPTS,
+            PERSONALIZATION_STALLED_GOALS,
+            PERSONALIZATION_UNFINISHED_BOOKS,
+        ],
+        plan_repos: [
+            PLAN_DAY,
+            PLAN_EVENTS,
+            PLAN_HABITS,
+            PLAN_GOALS,
+            PLAN_QUESTS,
+            PLAN_TEMPLATE_LIST,
+            PLAN_TEMPLATE_CREATE,
+            PLAN_TEMPLATE_GET_OWNED,
+            PLAN_TEMPLATE_NAME_TAKEN,
+            PLAN_TEMPLATE_UPDATE,
+            PLAN_TEMPLATE_DELETE,
+            PLAN_TEMPLATE_USE,
+        ],
+        platform_repos: [
+            CALENDAR_LIST,
+            CALENDAR_LIST_IN_RANGE,
+            CALENDAR_GET,
+            CALENDAR_CREATE,
+            CALENDAR_UPDATE,
+            CALENDAR_DELETE,
+            DAILY_PLAN_GET_FOR_DATE,
+            DAILY_PLAN_UPSERT,
+            FEEDBACK_LIST,
+            FEEDBACK_CREATE,
+            INFOBASE_LIST_SEARCH,
+            INFOBASE_LIST_BY_CATEGORY,
+            INFOBASE_LIST,
+            INFOBASE_GET,
+            INFOBASE_GET_FOR_UPDATE,
+            INFOBASE_CREATE,
+            INFOBASE_UPDATE,
+            INFOBASE_DELETE,
+            IDEAS_LIST,
+            IDEAS_GET,
+            IDEAS_GET_FOR_UPDATE,
+            IDEAS_CREATE,
+            IDEAS_UPDATE,
+            IDEAS_DELETE,
+            ONBOARDING_GET_ACTIVE_FLOW,
+            ONBOARDING_GET_FLOW,
+            ONBOARDING_GET_FLOW_STEPS,
+            ONBOARDING_GET_STEP,
+            ONBOARDING_GET_USER_STATE,
+            ONBOARDING_GET_RESPONSES,
+            ONBOARDING_START,
+            ONBOARDING_COMPLETE_STEP_INSERT_USER_ONBOARDING_RESPONSES,
+            ONBOARDING_COMPLETE_STEP_FINISH,
+            ONBOARDING_COMPLETE_STEP_ADVANCE,
+            ONBOARDING_SKIP_UPDATE_USER_ONBOARDING_STATE,
+            ONBOARDING_SKIP_INSERT_USER_SETTINGS,
+            ONBOARDING_RESET_DELETE_USER_ONBOARDING_RESPONSES,
+            ONBOARDING_RESET_INSERT_USER_ONBOARDING_STATE,
+            USER_SETTINGS_GET,
+            USER_SETTINGS_TIMEZONE_EXISTS,
+            USER_SETTINGS_UPDATE,
+        ],
+        quests_repos: [
+            QUESTS_CREATE,
+            QUESTS_GET_BY_ID,
+            QUESTS_LIST_BY_STATUS,
+            QUESTS_LIST,
+            QUESTS_ACCEPT_QUEST,
+            QUESTS_COMPLETE_QUEST,
+            QUESTS_ABANDON_QUEST,
+            UNIVERSAL_QUEST_LIST_ACTIVE,
+            UNIVERSAL_QUEST_LIST_AUTOMATIC,
+            UNIVERSAL_QUEST_GET_BY_ID,
+            QUEST_PROGRESS_LIST_FOR_USER,
+            QUEST_PROGRESS_GET,
+            QUEST_PROGRESS_RESET_EXPIRED,
+            QUEST_PROGRESS_ADVANCE,
+            QUEST_PROGRESS_CLAIM,
+            QUEST_PROGRESS_UNDO_CLAIM,
+        ],
+        rate_limit_repos: [
+            RATE_LIMIT_TAKE,
+            RATE_LIMIT_PRUNE,
+        ],
+        reference_repos: [
+            REFERENCE_TRACK_CREATE,
+            REFERENCE_TRACK_FIND_BY_ID,
+            REFERENCE_TRACK_FIND_BY_ID_FOR_USER,
+            REFERENCE_TRACK_LIST_FOR_USER_TRACKS,
+            REFERENCE_TRACK_LIST_FOR_USER_TOTAL,
+            REFERENCE_TRACK_LIST_BY_USER_EMAIL_USER,
+            REFERENCE_TRACK_LIST_BY_USER_EMAIL_TRACKS,
+            REFERENCE_TRACK_LIST_BY_USER_EMAIL_TOTAL,
+            REFERENCE_TRACK_UPDATE,
+            REFERENCE_TRACK_DELETE,
+            TRACK_ANALYSIS_GET_BY_ID,
+            TRACK_ANALYSIS_CREATE,
+            TRACK_ANALYSIS_GET_LATEST_BY_TYPE,
+            TRACK_ANALYSIS_GET_LATEST,
+            TRACK_ANALYSIS_UPDATE_STATUS,
+            TRACK_ANALYSIS_MARK_STARTED,
+            TRACK_ANNOTATION_CREATE,
+            TRACK_ANNOTATION_LIST_FOR_TRACK,
+            TRACK_ANNOTATION_FIND_BY_ID_FOR_USER,
+            TRACK_ANNOTATION_UPDATE,
+            TRACK_ANNOTATION_DELETE,
+            TRACK_REGION_CREATE,
+            TRACK_REGION_LIST_FOR_TRACK,
+            TRACK_REGION_FIND_BY_ID_FOR_USER,
+            TRACK_REGION_UPDATE,
+            TRACK_REGION_DELETE,
+        ],
+        references_repos: [
+            REFERENCES_LIST_BY_CATEGORY,
+            REFERENCES_COUNT_BY_CATEGORY,
+            REFERENCES_LIST,
+            REFERENCES_COUNT,
+            REFERENCES_GET,
+            REFERENCES_CREATE,
+            REFERENCES_UPDATE,
+            REFERENCES_DELETE,
+        ],
+        repos: [
+            USER_FIND_BY_ID,
+            USER_FIND_BY_EMAIL,
+            USER_CREATE,
+            USER_UPDATE_LAST_ACTIVITY,
+            USER_VERIFY_AGE,
+            USER_ACCEPT_TOS,
+            USER_UPDATE_ROLE,
+            ACCOUNT_FIND_BY_PROVIDER,
+            ACCOUNT_FIND_BY_USER_ID,
+            ACCOUNT_UPSERT,
+            SESSION_FIND_BY_TOKEN,
+            SESSION_CREATE,
+            SESSION_ROTATE,
+            SESSION_TOUCH,
+            SESSION_DELETE,
+            SESSION_DELETE_BY_TOKEN,
+            SESSION_LIST_FOR_USER,
+            SESSION_DELETE_FOR_USER,
+            SESSION_DELETE_OTHERS_FOR_USER,
+            SESSION_DELETE_ALL_FOR_USER,
+            SESSION_CLEANUP_EXPIRED,
+            AUDIT_LOG_LOG,
+            RBAC_HAS_ENTITLEMENT,
+            RBAC_GET_ENTITLEMENTS,
+            RBAC_ASSIGN_ROLE,
+        ],
+        revision_repos: [
+            REVISION_INSERT,
+            REVISION_LIST,
+            REVISION_GET,
+            REVISION_DELETE_FOR_ENTITY,
+        ],
+        search_repos: [
+            SEARCH,
+        ],
+        sync_repos: [
+            SYNC_EVENT_LIST_SINCE,
+            SYNC_EVENT_LATEST_ID,
+            SYNC_EVENT_CURSOR_IS_RESUMABLE,
+            SYNC_EVENT_PRUNE,
+        ],
+        template_repos: [
+            TEMPLATE_LIST,
+            TEMPLATE_COUNT,
+            TEMPLATE_GET_BY_ID,
+            TEMPLATE_CREATE,
+            TEMPLATE_UPDATE,
+            TEMPLATE_DELETE,
+            PRESET_LIST,
+            PRESET_LIST_BY_TEMPLATE,
+            PRESET_LIST_STANDALONE,
+            PRESET_GET_BY_ID,
+            PRESET_CREATE,
+            PRESET_UPDATE,
+            PRESET_DELETE,
+        ],
+        admin: [
+            LIST_TABLES,
+            GET_TABLE_DATA_VALID_TABLES,
+            GET_TABLE_DATA_COLUMNS,
+            LIST_SESSIONS,
+            DELETE_SESSION,
+        ],
+        user_settings_repos: [
+            USER_SETTINGS_GET_ALL,
+            USER_SETTINGS_GET_ONE,
+            USER_SETTINGS_UPSERT,
+            USER_SETTINGS_DELETE,
+        ],
+        exercise: [
+            START_SESSION,
+            GET_ACTIVE_SESSION_WORKOUTS,
+            GET_ACTIVE_SESSION_SETS_LOGGED,
+        ],
+        sync: [
+            FETCH_PROGRESS,
+            FETCH_FOCUS_STATUS,
+            FETCH_PLAN_STATUS,
+            FETCH_UNREAD_INBOX_COUNT,
+            FETCH_ACTIVE_QUESTS_COUNT,
+            FETCH_PENDING_HABITS_COUNT,
+            FETCH_OVERDUE_ITEMS_COUNT,
+        ],
+        today: [
+            FETCH_USER_STATE_PLAN_ROW,
+            FETCH_USER_STATE_FOCUS_ACTIVE,
+            FETCH_USER_STATE_ACTIVE_STREAK,
+            FETCH_USER_STATE_FIRST_DAY,
+            FETCH_USER_STATE_RETURNING_AFTER_GAP,
+            FETCH_PLAN_SUMMARY,
+            FETCH_PERSONALIZATION_SETTINGS,
+            FETCH_PERSONALIZATION_ONBOARDING,
+            FETCH_DYNAMIC_UI_PENDING_HABITS,
+            FETCH_DYNAMIC_UI_ACTIVE_QUESTS,
+            FETCH_DYNAMIC_UI_UNREAD_INBOX,
+            FETCH_DYNAMIC_UI_RESUME_LAST,
+        ],
+        audit: [
+            POSTGRES_AUDIT_SINK_RECORD,
+            WRITE_AUDIT,
+        ],
+    };
+
+    // Statements built per table from fixed table lists
+    queries.extend(admin_repos::HEALTH_TABLES.iter().map(|table| CatalogQuery {
+        name: format!("admin_repos::count_table_sql({})", table).into(),
+        sql: admin_repos::count_table_sql(table).into(),
+    }));
+    queries.extend(
+        platform_repos::EXPORT_TABLES
+            .iter()
+            .map(|table| CatalogQuery {
+                name: format!("platform_repos::export_table_sql({})", table).into(),
+                sql: platform_repos::export_table_sql(table).into(),
+            }),
+    );
+
+    for table in archive_repos::ARCHIVE_TABLES {
+        queries.push(CatalogQuery {
+            name: format!("archive_repos::archive_select_sql({})", table.table).into(),
+            sql: archive_repos::archive_select_sql(table).into(),
+        });
+        if table.imported {
+            queries.push(CatalogQuery {
+                name: format!("archive_repos::archive_insert_sql({})", table.table).into(),
+                sql: archive_repos::archive_insert_sql(table).into(),
+            });
+        }
+        if table.imported && table.bootstrap {
+            queries.push(CatalogQuery {
+                name: format!("archive_repos::archive_clear_sql({})", table.table).into(),
+                sql: archive_repos::archive_clear_sql(table).into(),
+            });
+        }
+        queries.push(CatalogQuery {
+            name: format!("archive_repos::archive_delete_sql({})", table.table).into(),
+            sql: archive_repos::archive_delete_sql(table).into(),
+        });
+    }
+    queries.push(CatalogQuery {
+        name: "archive_repos::archive_has_data_sql".into(),
+        sql: archive_repos::archive_has_data_sql().into(),
+    });
+
+    queries.extend(
+        account_deletion_repos::ACCOUNT_TABLES
+            .iter()
+            .map(|table| CatalogQuery {
+                name: format!("account_deletion_repos::account_delete_sql({})", table).into(),
+                sql: account_deletion_repos::account_delete_sql(table).into(),
+            }),
+    );
+    queries.extend(
+        account_deletion_repos::ACCOUNT_AUTHOR_COLUMNS
+            .iter()
+            .map(|(table, column)| CatalogQuery {
+                name: format!(
+                    "account_deletion_repos::account_detach_sql({}, {})",
+                    table, column
+                )
+                .into(),
+                sql: account_deletion_repos::account_detach_sql(table, column).into(),
+            }),
+    );
+
+    queries
+}
+
+/// Prepare every catalogued statement, returning the ones that fail
+pub async fn check(pool: &PgPool) -> Result<Vec<CatalogFailure>, sqlx::Error> {
+    let mut conn = pool.acquire().await?;
+    let mut failures = Vec::new();
+
+    for query in queries() {
+        if let Err(e) = (&mut *conn).prepare(query.sql.as_ref()).await {
+            failures.push(CatalogFailure {
+                name: query.name.into_owned(),
+                error: e.to_string(),
+            });
+        }
+    }
+
+    Ok(failures)
+}
@@ -9,6 +9,8 @@ use uuid::Uuid;
 use crate::error::AppError;
 
 use super::exercise_models::*;
+use super::gamification_models::AwardPointsInput;
+use super::gamification_repos::GamificationRepo;
 
 // ============================================================================
 // EXERCISE REPOSITORY
@@ -16,6 +18,56 @@ use super::exercise_models::*;
 
 pub struct ExerciseRepo;
 
+pub const EXERCISE_LIST_BY_CATEGORY: &str = r#"
+    SELECT id, name, description, category,
+           muscle_groups,
+           equipment,
+           is_custom, is_builtin, user_id, created_at
+    FROM exercises
+    WHERE (is_builtin = true OR user_id = $1)
+      AND category = $2
+    ORDER BY is_builtin DESC, name
+"#;
+
+pub const EXERCISE_LIST: &str = r#"
+    SELECT id, name, description, category,
+           muscle_groups,
+           equipment,
+           is_custom, is_builtin, user_id, created_at
+    FROM exercises
+    WHERE is_builtin = true OR user_id = $1
+    ORDER BY is_builtin DESC, name
+"#;
+
+pub const EXERCISE_CREATE: &str = r#"
+    INSERT INTO exercises (user_id, name, description, category, muscle_groups, equipment, is_custom)
+    VALUES ($1, $2, $3, $4, $5, $6, true)
+    RETURNING id, name, description, category,
+              muscle_groups,
+              equipment,
+              is_custom, is_builtin, user_id, created_at
+"#;
+
+pub const EXERCISE_GET_BY_ID: &str = r#"
+    SELECT id, name, description, category,
+           muscle_groups,
+           equipment,
+           is_custom, is_builtin, user_id, created_at
+    FROM exercises
+    WHERE id = $1 AND (is_builtin = true OR user_id = $2)
+"#;
+
+pub const EXERCISE_DELETE: &str = r#"
+    DELETE FROM exercises
+    WHERE id = $1 AND user_id = $2 AND is_custom = true
+"#;
+
+pub const EXERCISE_SEED_BUILTIN: &str = r#"
+    INSERT INTO exercises (name, description, category, muscle_groups, equipment, is_builtin)
+    VALUES ($1, $2, $3, $4, $5, true)
+    ON CONFLICT DO NOTHING
+"#;
+
 impl ExerciseRepo {
     /// List exercises (builtin + user's custom)
     pub async fn list(
@@ -24,37 +76,16 @@ impl ExerciseRepo {
         category: Option<&str>,
     ) -> Result<ExercisesListResponse, AppError> {
         let exercises = if let Some(cat) = category {
-            sqlx::query_as::<_, Exercise>(
-                r#"
-                SELECT id, name, description, category,
-                       muscle_groups,
-                       equipment,
-                       is_custom, is_builtin, user_id, created_at
-                FROM exercises
-                WHERE (is_builtin = true OR user_id = $1)
-                  AND category = $2
-                ORDER BY is_builtin DESC, name
-                "#,
-            )
-            .bind(user_id)
-            .bind(cat)
-            .fetch_all(pool)
-            .await?
+            sqlx::query_as::<_, Exercise>(EXERCISE_LIST_BY_CATEGORY)
+                .bind(user_id)
+                .bind(cat)
+                .fetch_all(pool)
+                .await?
         } else {
-            sqlx::query_as::<_, Exercise>(
-                r#"
-                SELECT id, name, description, category,
-                       muscle_groups,
-                       equipment,
-                       is_custom, is_builtin, user_id, created_at
-                FROM exercises
-                WHERE is_builtin = true OR user_id = $1
-                ORDER BY is_builtin DESC, name
-                "#,
-            )
-            .bind(user_id)
-            .fetch_all(pool)
-            .await?
+            sqlx::query_as::<_, Exercise>(EXERCISE_LIST)
+                .bind(user_id)
+                .fetch_all(pool)
+                .await?
         };
 
         let total = exercises.len() as i64;
@@ -71,24 +102,15 @@ impl ExerciseRepo {
         user_id: Uuid,
         req: &CreateExerciseRequest,
     ) -> Result<Exercise, AppError> {
-        let exercise = sqlx::query_as::<_, Exercise>(
-            r#"
-            INSERT INTO exercises (user_id, name, description, category, muscle_groups, equipment, is_custom)
-            VALUES ($1, $2, $3, $4, $5, $6, true)
-            RETURNING id, name, description, category,
-                      muscle_groups,
-                      equipment,
-                      is_custom, is_builtin, user_id, created_at
-            "#,
-        )
-        .bind(user_id)
-        .bind(&req.name)
-        .bind(&req.description)
-        .bind(&req.category)
-        .bind(req.muscle_groups.as_deref())
-        .bind(req.equipment.as_deref())
-        .fetch_one(pool)
-        .await?;
+        let exercise = sqlx::query_as::<_, Exercise>(EXERCISE_CREATE)
+            .bind(user_id)
+            .bind(&req.name)
+            .bind(&req.description)
+            .bind(&req.category)
+            .bind(req.muscle_groups.as_deref())
+            .bind(req.equipment.as_deref())
+            .fetch_one(pool)
+            .await?;
 
         Ok(exercise)
     }
@@ -99,36 +121,22 @@ impl ExerciseRepo {
         id: Uuid,
         user_id: Uuid,
     ) -> Result<Option<Exercise>, AppError> {
-        let exercise = sqlx::query_as::<_, Exercise>(
-            r#"
-            SELECT id, name, description, category,
-                   muscle_groups,
-                   equipment,
-                   is_custom, is_builtin, user_id, created_at
-            FROM exercises
-            WHERE id = $1 AND (is_builtin = true OR user_id = $2)
-            "#,
-        )
-        .bind(id)
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let exercise = sqlx::query_as::<_, Exercise>(EXERCISE_GET_BY_ID)
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
 
         Ok(exercise)
     }
 
     /// Delete custom exercise
     pub async fn delete(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
-        let result = sqlx::query(
-            r#"
-            DELETE FROM exercises
-            WHERE id = $1 AND user_id = $2 AND is_custom = true
-            "#,
-        )
-        .bind(id)
-        .bind(user_id)
-        .execute(pool)
-        .await?;
+        let result = sqlx::query(EXERCISE_DELETE)
+            .bind(id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
 
         Ok(result.rows_affected() > 0)
     }
@@ -140,20 +148,14 @@ impl ExerciseRepo {
     ) -> Result<i32, AppError> {
         let mut count = 0;
         for req in exercises {
-            let result = sqlx::query(
-                r#"
-                INSERT INTO exercises (name, description, category, muscle_groups, equipment, is_builtin)
-                VALUES ($1, $2, $3, $4, $5, true)
-                ON CONFLICT DO NOTHING
-                "#,
-            )
-            .bind(&req.name)
-            .bind(&req.description)
-            .bind(&req.category)
-            .bind(req.muscle_groups.as_deref())
-            .bind(req.equipment.as_deref())
-            .execute(pool)
-            .await?;
+            let result = sqlx::query(EXERCISE_SEED_BUILTIN)
+                .bind(&req.name)
+                .bind(&req.description)
+                .bind(&req.category)
+                .bind(req.muscle_groups.as_deref())
+                .bind(req.equipment.as_deref())
+                .execute(pool)
+                .await?;
 
             if result.rows_affected() > 0 {
                 count += 1;
@@ -169,6 +171,48 @@ impl ExerciseRepo {
 
 pub struct WorkoutRepo;
 
+pub const WORKOUT_LIST_TEMPLATES: &str = r#"
+    SELECT id, user_id, name, description, estimated_duration,
+           is_template, created_at, updated_at
+    FROM workouts
+    WHERE user_id = $1 AND is_template = true
+    ORDER BY updated_at DESC
+"#;
+
+pub const WORKOUT_LIST: &str = r#"
+    SELECT id, user_id, name, description, estimated_duration,
+           is_template, created_at, updated_at
+    FROM workouts
+    WHERE user_id = $1
+    ORDER BY updated_at DESC
+"#;
+
+pub const WORKOUT_GET_WORKOUT_EXERCISES: &str = r#"
+    SELECT we.id, we.exercise_id, e.name as exercise_name,
+           we.sets, we.reps, we.weight, we.duration,
+           we.rest_seconds, we.notes, we.sort_order
+    FROM workout_exercises we
+    JOIN exercises e ON we.exercise_id = e.id
+    WHERE we.workout_id = $1
+    ORDER BY we.sort_order
+"#;
+
+pub const WORKOUT_CREATE: &str = r#"
+    INSERT INTO workouts (user_id, name, description, estimated_duration, is_template)
+    VALUES ($1, $2, $3, $4, $5)
+    RETURNING id, user_id, name, description, estimated_duration,
+              is_template, created_at, updated_at
+"#;
+
+pub const WORKOUT_GET_BY_ID: &str = r#"
+    SELECT id, user_id, name, description, estimated_duration,
+           is_template, created_at, updated_at
+    FROM workouts
+    WHERE id = $1 AND user_id = $2
+"#;
+
+pub const WORKOUT_DELETE: &str = "DELETE FROM workouts WHERE id = $1 AND user_id = $2";
+
 impl WorkoutRepo {
     /// List user's workouts
     pub async fn list(
@@ -177,31 +221,15 @@ impl WorkoutRepo {
         templates_only: bool,
     ) -> Result<WorkoutsListResponse, AppError> {
         let workouts: Vec<Workout> = if templates_only {
-            sqlx::query_as::<_, Workout>(
-                r#"
-                SELECT id, user_id, name, description, estimated_duration,
-                       is_template, created_at, updated_at
-                FROM workouts
-                WHERE user_id = $1 AND is_template = true
-                ORDER BY updated_at DESC
-                "#,
-            )
-            .bind(user_id)
-            .fetch_all(pool)
-            .await?
+            sqlx::query_as::<_, Workout>(WORKOUT_LIST_TEMPLATES)
+                .bind(user_id)
+                .fetch_all(pool)
+                .await?
         } else {
-            sqlx::query_as::<_, Workout>(
-                r#"
-                SELECT id, user_id, name, description, estimated_duration,
-                       is_template, created_at, updated_at
-                FROM workouts
-                WHERE user_id = $1
-                ORDER BY updated_at DESC
-                "#,
-            )
-            .bind(user_id)
-            .fetch_all(pool)
-            .await?
+            sqlx::query_as::<_, Workout>(WORKOUT_LIST)
+                .bind(user_id)
+                .fetch_all(pool)
+                .await?
         };
 
         let total = workouts.len() as i64;
@@ -246,20 +274,10 @@ impl WorkoutRepo {
             sort_order: i32,
         }
 
-        let rows = sqlx::query_as::<_, WorkoutExerciseRow>(
-            r#"
-            SELECT we.id, we.exercise_id, e.name as exercise_name,
-                   we.sets, we.reps, we.weight, we.duration,
-                   we.rest_seconds, we.notes, we.sort_order
-            FROM workout_exercises we
-            JOIN exercises e ON we.exercise_id = e.id
-            WHERE we.workout_id = $1
-            ORDER BY we.sort_order
-            "#,
-        )
-        .bind(workout_id)
-        .fetch_all(pool)
-        .await?;
+        let rows = sqlx::query_as::<_, WorkoutExerciseRow>(WORKOUT_GET_WORKOUT_EXERCISES)
+            .bind(workout_id)
+            .fetch_all(pool)
+            .await?;
 
         Ok(rows
             .into_iter()
@@ -284,21 +302,14 @@ impl WorkoutRepo {
         user_id: Uuid,
         req: &CreateWorkoutRequest,
     ) -> Result<Workout, AppError> {
-        let workout = sqlx::query_as::<_, Workout>(
-            r#"
-            INSERT INTO workouts (user_id, name, description, estimated_duration, is_template)
-            VALUES ($1, $2, $3, $4, $5)
-            RETURNING id, user_id, name, description, estimated_duration,
-                      is_template, created_at, updated_at
-            "#,
-        )
-        .bind(user_id)
-        .bind(&req.name)
-        .bind(&req.description)
-        .bind(req.estimated_duration)
-        .bind(req.is_template.unwrap_or(false))
-        .fetch_one(pool)
-        .await?;
+        let workout = sqlx::query_as::<_, Workout>(WORKOUT_CREATE)
+            .bind(user_id)
+            .bind(&req.name)
+            .bind(&req.description)
+            .bind(req.estimated_duration)
+            .bind(req.is_template.unwrap_or(false))
+            .fetch_one(pool)
+            .await?;
 
         Ok(workout)
     }
@@ -309,18 +320,11 @@ impl WorkoutRepo {
         id: Uuid,
         user_id: Uuid,
     ) -> Result<Option<WorkoutResponse>, AppError> {
-        let workout = sqlx::query_as::<_, Workout>(
-            r#"
-            SELECT id, user_id, name, description, estimated_duration,
-                   is_template, created_at, updated_at
-            FROM workouts
-            WHERE id = $1 AND user_id = $2
-            "#,
-        )
-        .bind(id)
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let workout = sqlx::query_as::<_, Workout>(WORKOUT_GET_BY_ID)
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
 
         match workout {
             Some(w) => {
@@ -341,7 +345,7 @@ impl WorkoutRepo {
 
     /// Delete workout
     pub async fn delete(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
-        let result = sqlx::query("DELETE FROM workouts WHERE id = $1 AND user_id = $2")
+        let result = sqlx::query(WORKOUT_DELETE)
             .bind(id)
             .bind(user_id)
             .execute(pool)
@@ -357,6 +361,65 @@ impl WorkoutRepo {
 
 pub struct WorkoutSessionRepo;
 
+pub const WORKOUT_SESSION_LIST: &str = r#"
+    SELECT ws.id, ws.workout_id, w.name as workout_name,
+           ws.started_at, ws.completed_at, ws.notes, ws.rating,
+           ws.xp_awarded, ws.coins_awarded,
+           (SELECT COUNT(*) FROM exercise_sets es WHERE es.session_id = ws.id) as sets_logged
+    FROM workout_sessions ws
+    LEFT JOIN workouts w ON ws.workout_id = w.id
+    WHERE ws.user_id = $1
+    ORDER BY ws.started_at DESC
+    LIMIT $2
+"#;
+
+pub const WORKOUT_SESSION_START: &str = r#"
+    INSERT INTO workout_sessions (user_id, workout_id, started_at)
+    VALUES ($1, $2, NOW())
+    RETURNING id, user_id, workout_id, started_at, completed_at,
+              notes, rating, xp_awarded, coins_awarded
+"#;
+
+pub const WORKOUT_SESSION_LOG_SET_SESSION: &str =
+    "SELECT id FROM workout_sessions WHERE id = $1 AND user_id = $2 AND completed_at IS NULL";
+
+pub const WORKOUT_SESSION_LOG_SET_SET: &str = r#"
+    INSERT INTO exercise_sets (session_id, exercise_id, set_number, reps, weight,
+                               duration, is_warmup, is_dropset, rpe, notes, completed_at)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, NOW())
+    RETURNING id, session_id, exercise_id, set_number, reps,
+              weight, duration, is_warmup, is_dropset,
+              rpe, notes, completed_at
+"#;
+
+pub const WORKOUT_SESSION_COMPLETE_SESSION: &str = r#"
+    SELECT id, user_id, workout_id, started_at, completed_at,
+           notes, rating, xp_awarded, coins_awarded
+    FROM workout_sessions
+    WHERE id = $1 AND user_id = $2 AND completed_at IS NULL
+"#;
+
+pub const WORKOUT_SESSION_COMPLETE_SETS_LOGGED: &str =
+    "SELECT COUNT(*) FROM exercise_sets WHERE session_id = $1";
+
+pub const WORKOUT_SESSION_COMPLETE_UPDATE_WORKOUT_SESSIONS: &str = r#"
+    UPDATE workout_sessions
+    SET completed_at = NOW(), notes = $3, rating = $4,
+        xp_awarded = $5, coins_awarded = $6
+    WHERE id = $1 AND user_id = $2
+"#;
+
+pub const WORKOUT_SESSION_COMPLETE_WORKOUTS: &str = "SELECT name FROM workouts WHERE id = $1";
+
+pub const WORKOUT_SESSION_GET_ACTIVE: &str = r#"
+    SELECT id, user_id, workout_id, started_at, completed_at,
+           notes, rating, xp_awarded, coins_awarded
+    FROM workout_sessions
+    WHERE user_id = $1 AND completed_at IS NULL
+    ORDER BY started_at DESC
+    LIMIT 1
+"#;
+
 impl WorkoutSessionRepo {
     /// List user's workout sessions
     pub async fn list(
@@ -378,23 +441,11 @@ impl WorkoutSessionRepo {
             sets_logged: Option<i64>,
         }
 
-        let sessions = sqlx::query_as::<_, SessionRow>(
-            r#"
-            SELECT ws.id, ws.workout_id, w.name as workout_name,
-                   ws.started_at, ws.completed_at, ws.notes, ws.rating,
-                   ws.xp_awarded, ws.coins_awarded,
-                   (SELECT COUNT(*) FROM exercise_sets es WHERE es.session_id = ws.id) as sets_logged
-            FROM workout_sessions ws
-            LEFT JOIN workouts w ON ws.workout_id = w.id
-            WHERE ws.user_id = $1
-            ORDER BY ws.started_at DESC
-            LIMIT $2
-            "#,
-        )
-        .bind(user_id)
-        .bind(limit)
-        .fetch_all(pool)
-        .await?;
+        let sessions = sqlx::query_as::<_, SessionRow>(WORKOUT_SESSION_LIST)
+            .bind(user_id)
+            .bind(limit)
+            .fetch_all(pool)
+            .await?;
 
         let total = sessions.len() as i64;
 
@@ -428,18 +479,11 @@ impl WorkoutSessionRepo {
         user_id: Uuid,
         workout_id: Option<Uuid>,
     ) -> Result<WorkoutSession, AppError> {
-        let session = sqlx::query_as::<_, WorkoutSession>(
-            r#"
-            INSERT INTO workout_sessions (user_id, workout_id, started_at)
-            VALUES ($1, $2, NOW())
-            RETURNING id, user_id, workout_id, started_at, completed_at,
-                      notes, rating, xp_awarded, coins_awarded
-            "#,
-        )
-        .bind(user_id)
-        .bind(workout_id)
-        .fetch_one(pool)
-        .await?;
+        let session = sqlx::query_as::<_, WorkoutSession>(WORKOUT_SESSION_START)
+            .bind(user_id)
+            .bind(workout_id)
+            .fetch_one(pool)
+            .await?;
 
         Ok(session)
     }
@@ -452,13 +496,11 @@ impl WorkoutSessionRepo {
         req: &LogSetRequest,
     ) -> Result<ExerciseSet, AppError> {
         // Verify session belongs to user
-        let session: Option<Uuid> = sqlx::query_scalar(
-            "SELECT id FROM workout_sessions WHERE id = $1 AND user_id = $2 AND completed_at IS NULL",
-        )
-        .bind(session_id)
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let session: Option<Uuid> = sqlx::query_scalar(WORKOUT_SESSION_LOG_SET_SESSION)
+            .bind(session_id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
 
         if session.is_none() {
             return Err(AppError::NotFound(
@@ -466,28 +508,19 @@ impl WorkoutSessionRepo {
             ));
         }
 
-        let set = sqlx::query_as::<_, ExerciseSet>(
-            r#"
-            INSERT INTO exercise_sets (session_id, exercise_id, set_number, reps, weight,
-                                       duration, is_warmup, is_dropset, rpe, notes, completed_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, NOW())
-            RETURNING id, session_id, exercise_id, set_number, reps,
-                      weight, duration, is_warmup, is_dropset,
-                      rpe, notes, completed_at
-            "#,
-        )
-        .bind(session_id)
-        .bind(req.exercise_id)
-        .bind(req.set_number)
-        .bind(req.reps)
-        .bind(req.weight)
-        .bind(req.duration)
-        .bind(req.is_warmup.unwrap_or(false))
-        .bind(req.is_dropset.unwrap_or(false))
-        .bind(req.rpe)
-        .bind(&req.notes)
-        .fetch_one(pool)
-        .await?;
+        let set = sqlx::query_as::<_, ExerciseSet>(WORKOUT_SESSION_LOG_SET_SET)
+            .bind(session_id)
+            .bind(req.exercise_id)
+            .bind(req.set_number)
+            .bind(req.reps)
+            .bind(req.weight)
+            .bind(req.duration)
+            .bind(req.is_warmup.unwrap_or(false))
+            .bind(req.is_dropset.unwrap_or(false))
+            .bind(req.rpe)
+            .bind(&req.notes)
+            .fetch_one(pool)
+            .await?;
 
         Ok(set)
     }
@@ -500,18 +533,11 @@ impl WorkoutSessionRepo {
         req: &CompleteSessionRequest,
     ) -> Result<CompleteSessionResult, AppError> {
         // Get session
-        let session = sqlx::query_as::<_, WorkoutSession>(
-            r#"
-            SELECT id, user_id, workout_id, started_at, completed_at,
-                   notes, rating, xp_awarded, coins_awarded
-            FROM workout_sessions
-            WHERE id = $1 AND user_id = $2 AND completed_at IS NULL
-            "#,
-        )
-        .bind(session_id)
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let session = sqlx::query_as::<_, WorkoutSession>(WORKOUT_SESSION_COMPLETE_SESSION)
+            .bind(session_id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
 
         let session = session.ok_or_else(|| {
             AppError::NotFound("Session not found or already completed".to_string())
@@ -522,11 +548,10 @@ impl WorkoutSessionRepo {
         let duration_minutes = (now - session.started_at).num_minutes();
 
         // Count sets
-        let sets_logged: Option<i64> =
-            sqlx::query_scalar("SELECT COUNT(*) FROM exercise_sets WHERE session_id = $1")
-                .bind(session_id)
-                .fetch_one(pool)
-                .await?;
+        let sets_logged: Option<i64> = sqlx::query_scalar(WORKOUT_SESSION_COMPLETE_SETS_LOGGED)
+            .bind(session_id)
+            .fetch_one(pool)
+            .await?;
         let sets_logged = sets_logged.unwrap_or(0);
 
         // Calculate rewards: 1 XP per minute, 1 coin per 5 sets
@@ -534,22 +559,15 @@ impl WorkoutSessionRepo {
         let coins = (sets_logged / 5).max(1) as i32;
 
         // Update session
-        let updated = sqlx::query(
-            r#"
-            UPDATE workout_sessions
-            SET completed_at = NOW(), notes = $3, rating = $4,
-                xp_awarded = $5, coins_awarded = $6
-            WHERE id = $1 AND user_id = $2
-            "#,
-        )
-        .bind(session_id)
-        .bind(user_id)
-        .bind(&req.notes)
-        .bind(req.rating)
-        .bind(xp)
-        .bind(coins)
-        .execute(pool)
-        .await?;
+        let updated = sqlx::query(WORKOUT_SESSION_COMPLETE_UPDATE_WORKOUT_SESSIONS)
+            .bind(session_id)
+            .bind(user_id)
+            .bind(&req.notes)
+            .bind(req.rating)
+            .bind(xp)
+            .bind(coins)
+            .execute(pool)
+            .await?;
 
         if updated.rows_affected() == 0 {
             return Err(AppError::Internal("Failed to update session".to_string()));
@@ -557,7 +575,7 @@ impl WorkoutSessionRepo {
 
         // Get workout name
         let workout_name: Option<String> = if let Some(wid) = session.workout_id {
-            sqlx::query_scalar("SELECT name FROM workouts WHERE id = $1")
+            sqlx::query_scalar(WORKOUT_SESSION_COMPLETE_WORKOUTS)
                 .bind(wid)
                 .fetch_optional(pool)
                 .await?
@@ -565,6 +583,23 @@ impl WorkoutSessionRepo {
             None
         };
 
+        GamificationRepo::award_points(
+            pool,
+            user_id,
+            &AwardPointsInput {
+                xp: Some(xp),
+                coins: Some(coins),
+                skill_stars: None,
+                skill_key: None,
+                event_type: "workout_complete".to_string(),
+                event_id: Some(session_id),
+                reason: Some("Workout completed".to_string()),
+                idempotency_key: Some(format!("workout_complete_{}", session_id)),
+                quantity: Some(duration_minutes.min(i32::MAX as i64) as i32),
+            },
+        )
+        .await?;
+
         Ok(CompleteSessionResult {
             session: WorkoutSessionResponse {
                 id: session_id,
@@ -590,19 +625,10 @@ impl WorkoutSessionRepo {
         pool: &PgPool,
         user_id: Uuid,
     ) -> Result<Option<WorkoutSession>, AppError> {
-        let session = sqlx::query_as::<_, WorkoutSession>(
-            r#"
-            SELECT id, user_id, workout_id, started_at, completed_at,
-                   notes, rating, xp_awarded, coins_awarded
-            FROM workout_sessions
-            WHERE user_id = $1 AND completed_at IS NULL
-            ORDER BY started_at DESC
-            LIMIT 1
-            "#,
-        )
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let session = sqlx::query_as::<_, WorkoutSession>(WORKOUT_SESSION_GET_ACTIVE)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
 
         Ok(session)
     }
@@ -614,22 +640,50 @@ impl WorkoutSessionRepo {
 
 pub struct ProgramRepo;
 
+pub const PROGRAM_LIST: &str = r#"
+    SELECT id, user_id, name, description, duration_weeks, goal,
+           difficulty, is_active, started_at, completed_at,
+           created_at, updated_at
+    FROM training_programs
+    WHERE user_id = $1
+    ORDER BY is_active DESC, updated_at DESC
+"#;
+
+pub const PROGRAM_CREATE: &str = r#"
+    INSERT INTO training_programs (user_id, name, description, duration_weeks, goal, difficulty)
+    VALUES ($1, $2, $3, $4, $5, $6)
+    RETURNING id, user_id, name, description, duration_weeks, goal,
+              difficulty, is_active, started_at, completed_at,
+              created_at, updated_at
+"#;
+
+pub const PROGRAM_GET_BY_ID: &str = r#"
+    SELECT id, user_id, name, description, duration_weeks, goal,
+           difficulty, is_active, started_at, completed_at,
+           created_at, updated_at
+    FROM training_programs
+    WHERE id = $1 AND user_id = $2
+"#;
+
+pub const PROGRAM_ACTIVATE_UPDATE_TRAINING_PROGRAMS: &str =
+    "UPDATE training_programs SET is_active = false WHERE user_id = $1";
+
+pub const PROGRAM_ACTIVATE_PROGRAM: &str = r#"
+    UPDATE training_programs
+    SET is_active = true, started_at = COALESCE(started_at, NOW())
+    WHERE id = $1 AND user_id = $2
+    RETURNING id, user_id, name, description, duration_weeks, goal,
+              difficulty, is_active, started_at, completed_at,
+              created_at, updated_at
+"#;
+
 impl ProgramRepo {
     /// List user's programs
     pub async fn list(pool: &PgPool, user_id: Uuid) -> Result<ProgramsListResponse, AppError> {
-        let programs = sqlx::query_as::<_, TrainingProgram>(
-            r#"
-            SELECT id, user_id, name, description, duration_weeks, goal,
-                   difficulty, is_active, started_at, completed_at,
-                   created_at, updated_at
-            FROM training_programs
-            WHERE user_id = $1
-            ORDER BY is_active DESC, updated_at DESC
-            "#,
-        )
-        .bind(user_id)
-        .fetch_all(pool)
-        .await?;
+        let programs = sqlx::query_as::<_, TrainingProgram>(PROGRAM_LIST)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
 
         let total = programs.len() as i64;
 
@@ -645,23 +699,15 @@ impl ProgramRepo {
         user_id: Uuid,
         req: &CreateProgramRequest,
     ) -> Result<TrainingProgram, AppError> {
-        let program = sqlx::query_as::<_, TrainingProgram>(
-            r#"
-            INSERT INTO training_programs (user_id, name, description, duration_weeks, goal, difficulty)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING id, user_id, name, description, duration_weeks, goal,
-                      difficulty, is_active, started_at, completed_at,
-                      created_at, updated_at
-            "#,
-        )
-        .bind(user_id)
-        .bind(&req.name)
-        .bind(&req.description)
-        .bind(req.duration_weeks.unwrap_or(4))
-        .bind(&req.goal)
-        .bind(&req.difficulty)
-        .fetch_one(pool)
-        .await?;
+        let program = sqlx::query_as::<_, TrainingProgram>(PROGRAM_CREATE)
+            .bind(user_id)
+            .bind(&req.name)
+            .bind(&req.description)
+            .bind(req.duration_weeks.unwrap_or(4))
+            .bind(&req.goal)
+            .bind(&req.difficulty)
+            .fetch_one(pool)
+            .await?;
 
         Ok(program)
     }
@@ -672,19 +718,11 @@ impl ProgramRepo {
         id: Uuid,
         user_id: Uuid,
     ) -> Result<Option<TrainingProgram>, AppError> {
-        let program = sqlx::query_as::<_, TrainingProgram>(
-            r#"
-            SELECT id, user_id, name, description, duration_weeks, goal,
-                   difficulty, is_active, started_at, completed_at,
-                   created_at, updated_at
-            FROM training_programs
-            WHERE id = $1 AND user_id = $2
-            "#,
-        )
-        .bind(id)
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let program = sqlx::query_as::<_, TrainingProgram>(PROGRAM_GET_BY_ID)
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
 
         Ok(program)
     }
@@ -696,26 +734,17 @@ impl ProgramRepo {
         user_id: Uuid,
     ) -> Result<TrainingProgram, AppError> {
         // Deactivate other programs
-        sqlx::query("UPDATE training_programs SET is_active = false WHERE user_id = $1")
+        sqlx::query(PROGRAM_ACTIVATE_UPDATE_TRAINING_PROGRAMS)
             .bind(user_id)
             .execute(pool)
             .await?;
 
         // Activate this one
-        let program = sqlx::query_as::<_, TrainingProgram>(
-            r#"
-            UPDATE training_programs
-            SET is_active = true, started_at = COALESCE(started_at, NOW())
-            WHERE id = $1 AND user_id = $2
-            RETURNING id, user_id, name, description, duration_weeks, goal,
-                      difficulty, is_active, started_at, completed_at,
-                      created_at, updated_at
-            "#,
-        )
-        .bind(id)
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
+        let program = sqlx::query_as::<_, TrainingProgram>(PROGRAM_ACTIVATE_PROGRAM)
+            .bind(id)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
 
         Ok(program)
     }
@@ -0,0 +1,61 @@
+//! Feature Flag Models
+//!
+//! Runtime flags with percentage rollouts and user, role or entitlement targeting.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Gates analysis frames and the legacy analysis routes
+pub const FLAG_MUSIC_ANALYSIS: &str = "music_analysis";
+
+/// Longest flag name
+pub const MAX_FLAG_NAME_LEN: usize = 64;
+
+/// Feature flag database model
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct FeatureFlag {
+    pub id: Uuid,
+    pub flag_name: String,
+    /// Kill switch: a disabled flag is off for everyone
+    pub enabled: bool,
+    pub description: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+    /// Share of untargeted users the flag is on for
+    pub rollout_percentage: i32,
+    pub target_user_ids: Vec<Uuid>,
+    pub target_roles: Vec<String>,
+    pub target_entitlements: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create feature flag request
+#[derive(Debug, Deserialize)]
+pub struct CreateFeatureFlagRequest {
+    pub flag_name: String,
+    #[serde(default)]
+    pub enabled: bool,
+    pub description: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+    pub rollout_percentage: Option<i32>,
+    #[serde(default)]
+    pub target_user_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub target_roles: Vec<String>,
+    #[serde(default)]
+    pub target_entitlements: Vec<String>,
+}
+
+/// Update feature flag request; omitted fields are left unchanged
+#[derive(Debug, Deserialize)]
+pub struct UpdateFeatureFlagRequest {
+    pub enabled: Option<bool>,
+    pub description: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+    pub rollout_percentage: Option<i32>,
+    pub target_user_ids: Option<Vec<Uuid>>,
+    pub target_roles: Option<Vec<String>>,
+    pub target_entitlements: Option<Vec<String>>,
+}
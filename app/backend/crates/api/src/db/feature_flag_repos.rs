@@ -0,0 +1,125 @@
+//! Feature Flag Repository
+//!
+//! Database operations for feature flags. Evaluation happens against the
+//! in-memory copy held by `services::feature_flags`.
+
+use sqlx::PgPool;
+
+use super::feature_flag_models::*;
+use crate::error::AppError;
+
+macro_rules! feature_flag_columns {
+    () => {
+        r#"id, flag_name, enabled, description, metadata, rollout_percentage,
+    target_user_ids, target_roles, target_entitlements, created_at, updated_at"#
+    };
+}
+
+pub struct FeatureFlagRepo;
+
+pub const FEATURE_FLAG_LIST: &str = concat!(
+    "SELECT ",
+    feature_flag_columns!(),
+    " FROM feature_flags ORDER BY flag_name"
+);
+
+pub const FEATURE_FLAG_GET: &str = concat!(
+    "SELECT ",
+    feature_flag_columns!(),
+    " FROM feature_flags WHERE flag_name = $1"
+);
+
+pub const FEATURE_FLAG_CREATE: &str = concat!(
+    r#"
+    INSERT INTO feature_flags (flag_name, enabled, description, metadata, rollout_percentage,
+                               target_user_ids, target_roles, target_entitlements)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+    ON CONFLICT (flag_name) DO NOTHING
+    RETURNING "#,
+    feature_flag_columns!()
+);
+
+pub const FEATURE_FLAG_UPDATE: &str = concat!(
+    r#"
+    UPDATE feature_flags
+    SET enabled = COALESCE($2, enabled),
+        description = COALESCE($3, description),
+        metadata = COALESCE($4, metadata),
+        rollout_percentage = COALESCE($5, rollout_percentage),
+        target_user_ids = COALESCE($6, target_user_ids),
+        target_roles = COALESCE($7, target_roles),
+        target_entitlements = COALESCE($8, target_entitlements),
+        updated_at = NOW()
+    WHERE flag_name = $1
+    RETURNING "#,
+    feature_flag_columns!()
+);
+
+pub const FEATURE_FLAG_DELETE: &str = "DELETE FROM feature_flags WHERE flag_name = $1";
+
+impl FeatureFlagRepo {
+    /// All flags, by name
+    pub async fn list(pool: &PgPool) -> Result<Vec<FeatureFlag>, AppError> {
+        let flags = sqlx::query_as::<_, FeatureFlag>(FEATURE_FLAG_LIST)
+            .fetch_all(pool)
+            .await?;
+        Ok(flags)
+    }
+
+    /// One flag by name
+    pub async fn get(pool: &PgPool, flag_name: &str) -> Result<Option<FeatureFlag>, AppError> {
+        let flag = sqlx::query_as::<_, FeatureFlag>(FEATURE_FLAG_GET)
+            .bind(flag_name)
+            .fetch_optional(pool)
+            .await?;
+        Ok(flag)
+    }
+
+    /// Create a flag; `None` if the name is taken
+    pub async fn create(
+        pool: &PgPool,
+        input: &CreateFeatureFlagRequest,
+    ) -> Result<Option<FeatureFlag>, AppError> {
+        let flag = sqlx::query_as::<_, FeatureFlag>(FEATURE_FLAG_CREATE)
+            .bind(&input.flag_name)
+            .bind(input.enabled)
+            .bind(&input.description)
+            .bind(&input.metadata)
+            .bind(input.rollout_percentage.unwrap_or(100))
+            .bind(&input.target_user_ids)
+            .bind(&input.target_roles)
+            .bind(&input.target_entitlements)
+            .fetch_optional(pool)
+            .await?;
+        Ok(flag)
+    }
+
+    /// Update a flag's settings
+    pub async fn update(
+        pool: &PgPool,
+        flag_name: &str,
+        input: &UpdateFeatureFlagRequest,
+    ) -> Result<Option<FeatureFlag>, AppError> {
+        let flag = sqlx::query_as::<_, FeatureFlag>(FEATURE_FLAG_UPDATE)
+            .bind(flag_name)
+            .bind(input.enabled)
+            .bind(&input.description)
+            .bind(&input.metadata)
+            .bind(input.rollout_percentage)
+            .bind(&input.target_user_ids)
+            .bind(&input.target_roles)
+            .bind(&input.target_entitlements)
+            .fetch_optional(pool)
+            .await?;
+        Ok(flag)
+    }
+
+    /// Delete a flag; routes it gates stop being served
+    pub async fn delete(pool: &PgPool, flag_name: &str) -> Result<bool, AppError> {
+        let result = sqlx::query(FEATURE_FLAG_DELETE)
+            .bind(flag_name)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -17,6 +17,100 @@ use crate::error::AppError;
 
 pub struct FocusSessionRepo;
 
+pub const FOCUS_SESSION_START_SESSION_UPDATE_FOCUS_SESSIONS: &str = r#"
+    UPDATE focus_sessions
+    SET status = 'abandoned', abandoned_at = NOW()
+    WHERE user_id = $1 AND status IN ('active', 'paused')
+"#;
+
+pub const FOCUS_SESSION_START_SESSION_DELETE_FOCUS_PAUSE_STATE: &str =
+    "DELETE FROM focus_pause_state WHERE user_id = $1";
+
+pub const FOCUS_SESSION_START_SESSION_SESSION: &str = r#"
+    INSERT INTO focus_sessions
+    (user_id, mode, duration_seconds, expires_at, task_id, task_title, status)
+    VALUES ($1, $2, $3, $4, $5, $6, 'active')
+    RETURNING id, user_id, mode, duration_seconds, started_at, completed_at,
+              abandoned_at, expires_at, paused_at, paused_remaining_seconds,
+              status, xp_awarded, coins_awarded, task_id, task_title, created_at
+"#;
+
+pub const FOCUS_SESSION_GET_SESSION: &str = r#"
+    SELECT id, user_id, mode, duration_seconds, started_at, completed_at,
+           abandoned_at, expires_at, paused_at, paused_remaining_seconds,
+           status, xp_awarded, coins_awarded, task_id, task_title, created_at
+    FROM focus_sessions WHERE id = $1 AND user_id = $2
+"#;
+
+pub const FOCUS_SESSION_GET_ACTIVE_SESSION: &str = r#"
+    SELECT id, user_id, mode, duration_seconds, started_at, completed_at,
+           abandoned_at, expires_at, paused_at, paused_remaining_seconds,
+           status, xp_awarded, coins_awarded, task_id, task_title, created_at
+    FROM focus_sessions
+    WHERE user_id = $1 AND status IN ('active', 'paused')
+    ORDER BY started_at DESC
+    LIMIT 1
+"#;
+
+pub const FOCUS_SESSION_COMPLETE_SESSION_UPDATE_FOCUS_SESSIONS: &str = r#"
+    UPDATE focus_sessions
+    SET status = 'completed', completed_at = NOW(), xp_awarded = $1, coins_awarded = $2
+    WHERE id = $3 AND user_id = $4
+    RETURNING id, user_id, mode, duration_seconds, started_at, completed_at,
+              abandoned_at, expires_at, paused_at, paused_remaining_seconds,
+              status, xp_awarded, coins_awarded, task_id, task_title, created_at
+"#;
+
+pub const FOCUS_SESSION_COMPLETE_SESSION_DELETE_FOCUS_PAUSE_STATE: &str =
+    "DELETE FROM focus_pause_state WHERE user_id = $1";
+
+pub const FOCUS_SESSION_ABANDON_SESSION_UPDATE_FOCUS_SESSIONS: &str = r#"
+    UPDATE focus_sessions
+    SET status = 'abandoned', abandoned_at = NOW()
+    WHERE id = $1 AND user_id = $2
+    RETURNING id, user_id, mode, duration_seconds, started_at, completed_at,
+              abandoned_at, expires_at, paused_at, paused_remaining_seconds,
+              status, xp_awarded, coins_awarded, task_id, task_title, created_at
+"#;
+
+pub const FOCUS_SESSION_ABANDON_SESSION_DELETE_FOCUS_PAUSE_STATE: &str =
+    "DELETE FROM focus_pause_state WHERE user_id = $1";
+
+pub const FOCUS_SESSION_LIST_SESSIONS_SESSIONS: &str = r#"
+    SELECT id, user_id, mode, duration_seconds, started_at, completed_at,
+           abandoned_at, expires_at, paused_at, paused_remaining_seconds,
+           status, xp_awarded, coins_awarded, task_id, task_title, created_at
+    FROM focus_sessions
+    WHERE user_id = $1
+    ORDER BY started_at DESC
+    LIMIT $2 OFFSET $3
+"#;
+
+pub const FOCUS_SESSION_LIST_SESSIONS_TOTAL: &str =
+    "SELECT COUNT(*) FROM focus_sessions WHERE user_id = $1";
+
+pub const FOCUS_SESSION_GET_STATS_SINCE: &str = r#"
+    SELECT
+      COUNT(*) FILTER (WHERE status = 'completed') as completed,
+      COUNT(*) FILTER (WHERE status = 'abandoned') as abandoned,
+      SUM(duration_seconds) FILTER (WHERE status = 'completed') as total_seconds,
+      SUM(xp_awarded) as total_xp,
+      SUM(coins_awarded) as total_coins
+    FROM focus_sessions
+    WHERE user_id = $1 AND started_at >= $2
+"#;
+
+pub const FOCUS_SESSION_GET_STATS: &str = r#"
+    SELECT
+      COUNT(*) FILTER (WHERE status = 'completed') as completed,
+      COUNT(*) FILTER (WHERE status = 'abandoned') as abandoned,
+      SUM(duration_seconds) FILTER (WHERE status = 'completed') as total_seconds,
+      SUM(xp_awarded) as total_xp,
+      SUM(coins_awarded) as total_coins
+    FROM focus_sessions
+    WHERE user_id = $1
+"#;
+
 impl FocusSessionRepo {
     /// Start a new focus session
     pub async fn start_session(
@@ -25,17 +119,13 @@ impl FocusSessionRepo {
         req: &CreateFocusRequest,
     ) -> Result<FocusSession, AppError> {
         // Abandon any existing active session
-        sqlx::query(
-            r#"UPDATE focus_sessions
-               SET status = 'abandoned', abandoned_at = NOW()
-               WHERE user_id = $1 AND status IN ('active', 'paused')"#,
-        )
-        .bind(user_id)
-        .execute(pool)
-        .await?;
+        sqlx::query(FOCUS_SESSION_START_SESSION_UPDATE_FOCUS_SESSIONS)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
 
         // Clear pause state
-        sqlx::query("DELETE FROM focus_pause_state WHERE user_id = $1")
+        sqlx::query(FOCUS_SESSION_START_SESSION_DELETE_FOCUS_PAUSE_STATE)
             .bind(user_id)
             .execute(pool)
             .await?;
@@ -44,22 +134,15 @@ impl FocusSessionRepo {
         let expires_at = Utc::now() + Duration::seconds((req.duration_seconds * 2) as i64);
 
         // Create new session
-        let session = sqlx::query_as::<_, FocusSession>(
-            r#"INSERT INTO focus_sessions
-               (user_id, mode, duration_seconds, expires_at, task_id, task_title, status)
-               VALUES ($1, $2, $3, $4, $5, $6, 'active')
-               RETURNING id, user_id, mode, duration_seconds, started_at, completed_at,
-                         abandoned_at, expires_at, paused_at, paused_remaining_seconds,
-                         status, xp_awarded, coins_awarded, task_id, task_title, created_at"#,
-        )
-        .bind(user_id)
-        .bind(&req.mode)
-        .bind(req.duration_seconds)
-        .bind(expires_at)
-        .bind(req.task_id)
-        .bind(&req.task_title)
-        .fetch_one(pool)
-        .await?;
+        let session = sqlx::query_as::<_, FocusSession>(FOCUS_SESSION_START_SESSION_SESSION)
+            .bind(user_id)
+            .bind(&req.mode)
+            .bind(req.duration_seconds)
+            .bind(expires_at)
+            .bind(req.task_id)
+            .bind(&req.task_title)
+            .fetch_one(pool)
+            .await?;
 
         Ok(session)
     }
@@ -70,16 +153,11 @@ impl FocusSessionRepo {
         session_id: Uuid,
         user_id: Uuid,
     ) -> Result<Option<FocusSession>, AppError> {
-        let session = sqlx::query_as::<_, FocusSession>(
-            r#"SELECT id, user_id, mode, duration_seconds, started_at, completed_at,
-                      abandoned_at, expires_at, paused_at, paused_remaining_seconds,
-                      status, xp_awarded, coins_awarded, task_id, task_title, created_at
-               FROM focus_sessions WHERE id = $1 AND user_id = $2"#,
-        )
-        .bind(session_id)
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let session = sqlx::query_as::<_, FocusSession>(FOCUS_SESSION_GET_SESSION)
+            .bind(session_id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
 
         Ok(session)
     }
@@ -89,18 +167,10 @@ impl FocusSessionRepo {
         pool: &PgPool,
         user_id: Uuid,
     ) -> Result<Option<FocusSession>, AppError> {
-        let session = sqlx::query_as::<_, FocusSession>(
-            r#"SELECT id, user_id, mode, duration_seconds, started_at, completed_at,
-                      abandoned_at, expires_at, paused_at, paused_remaining_seconds,
-                      status, xp_awarded, coins_awarded, task_id, task_title, created_at
-               FROM focus_sessions
-               WHERE user_id = $1 AND status IN ('active', 'paused')
-               ORDER BY started_at DESC
-               LIMIT 1"#,
-        )
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let session = sqlx::query_as::<_, FocusSession>(FOCUS_SESSION_GET_ACTIVE_SESSION)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
 
         Ok(session)
     }
@@ -134,23 +204,16 @@ impl FocusSessionRepo {
         };
 
         // Update session
-        let updated = sqlx::query_as::<_, FocusSession>(
-            r#"UPDATE focus_sessions
-               SET status = 'completed', completed_at = NOW(), xp_awarded = $1, coins_awarded = $2
-               WHERE id = $3 AND user_id = $4
-               RETURNING id, user_id, mode, duration_seconds, started_at, completed_at,
-                         abandoned_at, expires_at, paused_at, paused_remaining_seconds,
-                         status, xp_awarded, coins_awarded, task_id, task_title, created_at"#,
-        )
-        .bind(xp)
-        .bind(coins)
-        .bind(session_id)
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
+        let updated = sqlx::query_as::<_, FocusSession>(FOCUS_SESSION_COMPLETE_SESSION_UPDATE_FOCUS_SESSIONS)
+            .bind(xp)
+            .bind(coins)
+            .bind(session_id)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
 
         // Clear pause state
-        sqlx::query("DELETE FROM focus_pause_state WHERE user_id = $1")
+        sqlx::query(FOCUS_SESSION_COMPLETE_SESSION_DELETE_FOCUS_PAUSE_STATE)
             .bind(user_id)
             .execute(pool)
             .await?;
@@ -169,6 +232,8 @@ impl FocusSessionRepo {
                 event_id: Some(session_id),
                 reason: Some("Focus session completed".to_string()),
                 idempotency_key: Some(idempotency_key),
+                // Breaks don't count toward focus quests
+                quantity: (session.mode == "focus").then_some(session.duration_seconds / 60),
             },
         )
         .await?;
@@ -198,21 +263,14 @@ impl FocusSessionRepo {
             )));
         }
 
-        let updated = sqlx::query_as::<_, FocusSession>(
-            r#"UPDATE focus_sessions
-               SET status = 'abandoned', abandoned_at = NOW()
-               WHERE id = $1 AND user_id = $2
-               RETURNING id, user_id, mode, duration_seconds, started_at, completed_at,
-                         abandoned_at, expires_at, paused_at, paused_remaining_seconds,
-                         status, xp_awarded, coins_awarded, task_id, task_title, created_at"#,
-        )
-        .bind(session_id)
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
+        let updated = sqlx::query_as::<_, FocusSession>(FOCUS_SESSION_ABANDON_SESSION_UPDATE_FOCUS_SESSIONS)
+            .bind(session_id)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
 
         // Clear pause state
-        sqlx::query("DELETE FROM focus_pause_state WHERE user_id = $1")
+        sqlx::query(FOCUS_SESSION_ABANDON_SESSION_DELETE_FOCUS_PAUSE_STATE)
             .bind(user_id)
             .execute(pool)
             .await?;
@@ -229,23 +287,15 @@ impl FocusSessionRepo {
     ) -> Result<FocusSessionsListResponse, AppError> {
         let offset = (page - 1) * page_size;
 
-        let sessions = sqlx::query_as::<_, FocusSession>(
-            r#"SELECT id, user_id, mode, duration_seconds, started_at, completed_at,
-                      abandoned_at, expires_at, paused_at, paused_remaining_seconds,
-                      status, xp_awarded, coins_awarded, task_id, task_title, created_at
-               FROM focus_sessions
-               WHERE user_id = $1
-               ORDER BY started_at DESC
-               LIMIT $2 OFFSET $3"#,
-        )
-        .bind(user_id)
-        .bind(page_size)
-        .bind(offset)
-        .fetch_all(pool)
-        .await?;
+        let sessions = sqlx::query_as::<_, FocusSession>(FOCUS_SESSION_LIST_SESSIONS_SESSIONS)
+            .bind(user_id)
+            .bind(page_size)
+            .bind(offset)
+            .fetch_all(pool)
+            .await?;
 
         let total =
-            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM focus_sessions WHERE user_id = $1")
+            sqlx::query_scalar::<_, i64>(FOCUS_SESSION_LIST_SESSIONS_TOTAL)
                 .bind(user_id)
                 .fetch_one(pool)
                 .await?;
@@ -265,34 +315,16 @@ impl FocusSessionRepo {
         since: Option<chrono::DateTime<Utc>>,
     ) -> Result<FocusStatsResponse, AppError> {
         let stats = if let Some(since_date) = since {
-            sqlx::query_as::<_, (i64, i64, Option<i64>, Option<i64>, Option<i64>)>(
-                r#"SELECT
-                     COUNT(*) FILTER (WHERE status = 'completed') as completed,
-                     COUNT(*) FILTER (WHERE status = 'abandoned') as abandoned,
-                     SUM(duration_seconds) FILTER (WHERE status = 'completed') as total_seconds,
-                     SUM(xp_awarded) as total_xp,
-                     SUM(coins_awarded) as total_coins
-                   FROM focus_sessions
-                   WHERE user_id = $1 AND started_at >= $2"#,
-            )
-            .bind(user_id)
-            .bind(since_date)
-            .fetch_one(pool)
-            .await?
+            sqlx::query_as::<_, (i64, i64, Option<i64>, Option<i64>, Option<i64>)>(FOCUS_SESSION_GET_STATS_SINCE)
+                .bind(user_id)
+                .bind(since_date)
+                .fetch_one(pool)
+                .await?
         } else {
-            sqlx::query_as::<_, (i64, i64, Option<i64>, Option<i64>, Option<i64>)>(
-                r#"SELECT
-                     COUNT(*) FILTER (WHERE status = 'completed') as completed,
-                     COUNT(*) FILTER (WHERE status = 'abandoned') as abandoned,
-                     SUM(duration_seconds) FILTER (WHERE status = 'completed') as total_seconds,
-                     SUM(xp_awarded) as total_xp,
-                     SUM(coins_awarded) as total_coins
-                   FROM focus_sessions
-                   WHERE user_id = $1"#,
-            )
-            .bind(user_id)
-            .fetch_one(pool)
-            .await?
+            sqlx::query_as::<_, (i64, i64, Option<i64>, Option<i64>, Option<i64>)>(FOCUS_SESSION_GET_STATS)
+                .bind(user_id)
+                .fetch_one(pool)
+                .await?
         };
 
         Ok(FocusStatsResponse {
@@ -311,20 +343,56 @@ impl FocusSessionRepo {
 
 pub struct FocusPauseRepo;
 
+pub const FOCUS_PAUSE_GET_PAUSE_STATE: &str = r#"
+    SELECT id, user_id, session_id, mode, is_paused, time_remaining_seconds,
+           paused_at, resumed_at, created_at, updated_at
+    FROM focus_pause_state WHERE user_id = $1
+"#;
+
+pub const FOCUS_PAUSE_PAUSE_SESSION_UPDATE_FOCUS_SESSIONS: &str = r#"
+    UPDATE focus_sessions
+    SET status = 'paused', paused_at = NOW(), paused_remaining_seconds = $1
+    WHERE id = $2
+"#;
+
+pub const FOCUS_PAUSE_PAUSE_SESSION_STATE: &str = r#"
+    INSERT INTO focus_pause_state
+    (user_id, session_id, mode, is_paused, time_remaining_seconds, paused_at)
+    VALUES ($1, $2, $3, true, $4, NOW())
+    ON CONFLICT (session_id) DO UPDATE
+    SET mode = EXCLUDED.mode,
+        is_paused = true,
+        time_remaining_seconds = EXCLUDED.time_remaining_seconds,
+        paused_at = NOW(),
+        updated_at = NOW()
+    RETURNING id, user_id, session_id, mode, is_paused, time_remaining_seconds,
+              paused_at, resumed_at, created_at, updated_at
+"#;
+
+pub const FOCUS_PAUSE_RESUME_SESSION_SESSION: &str = r#"
+    UPDATE focus_sessions
+    SET status = 'active', paused_at = NULL, expires_at = $1
+    WHERE id = $2 AND user_id = $3
+    RETURNING id, user_id, mode, duration_seconds, started_at, completed_at,
+              abandoned_at, expires_at, paused_at, paused_remaining_seconds,
+              status, xp_awarded, coins_awarded, task_id, task_title, created_at
+"#;
+
+pub const FOCUS_PAUSE_RESUME_SESSION_DELETE_FOCUS_PAUSE_STATE: &str =
+    "DELETE FROM focus_pause_state WHERE user_id = $1";
+
+pub const FOCUS_PAUSE_CLEAR_PAUSE_STATE: &str = "DELETE FROM focus_pause_state WHERE user_id = $1";
+
 impl FocusPauseRepo {
     /// Get pause state for user
     pub async fn get_pause_state(
         pool: &PgPool,
         user_id: Uuid,
     ) -> Result<Option<FocusPauseState>, AppError> {
-        let state = sqlx::query_as::<_, FocusPauseState>(
-            r#"SELECT id, user_id, session_id, mode, is_paused, time_remaining_seconds,
-                      paused_at, resumed_at, created_at, updated_at
-               FROM focus_pause_state WHERE user_id = $1"#,
-        )
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let state = sqlx::query_as::<_, FocusPauseState>(FOCUS_PAUSE_GET_PAUSE_STATE)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
 
         Ok(state)
     }
@@ -347,36 +415,20 @@ impl FocusPauseRepo {
             .unwrap_or(session.duration_seconds);
 
         // Update session status
-        sqlx::query(
-            r#"UPDATE focus_sessions
-               SET status = 'paused', paused_at = NOW(), paused_remaining_seconds = $1
-               WHERE id = $2"#,
-        )
-        .bind(time_remaining)
-        .bind(session.id)
-        .execute(pool)
-        .await?;
+        sqlx::query(FOCUS_PAUSE_PAUSE_SESSION_UPDATE_FOCUS_SESSIONS)
+            .bind(time_remaining)
+            .bind(session.id)
+            .execute(pool)
+            .await?;
 
         // Upsert pause state
-        let state = sqlx::query_as::<_, FocusPauseState>(
-            r#"INSERT INTO focus_pause_state
-               (user_id, session_id, mode, is_paused, time_remaining_seconds, paused_at)
-               VALUES ($1, $2, $3, true, $4, NOW())
-               ON CONFLICT (session_id) DO UPDATE
-               SET mode = EXCLUDED.mode,
-                   is_paused = true,
-                   time_remaining_seconds = EXCLUDED.time_remaining_seconds,
-                   paused_at = NOW(),
-                   updated_at = NOW()
-               RETURNING id, user_id, session_id, mode, is_paused, time_remaining_seconds,
-                         paused_at, resumed_at, created_at, updated_at"#,
-        )
-        .bind(user_id)
-        .bind(session.id)
-        .bind(&session.mode)
-        .bind(time_remaining)
-        .fetch_one(pool)
-        .await?;
+        let state = sqlx::query_as::<_, FocusPauseState>(FOCUS_PAUSE_PAUSE_SESSION_STATE)
+            .bind(user_id)
+            .bind(session.id)
+            .bind(&session.mode)
+            .bind(time_remaining)
+            .fetch_one(pool)
+            .await?;
 
         Ok(state)
     }
@@ -394,22 +446,15 @@ impl FocusPauseRepo {
         let new_expires_at = Utc::now() + Duration::seconds(time_remaining as i64);
 
         // Update session
-        let session = sqlx::query_as::<_, FocusSession>(
-            r#"UPDATE focus_sessions
-               SET status = 'active', paused_at = NULL, expires_at = $1
-               WHERE id = $2 AND user_id = $3
-               RETURNING id, user_id, mode, duration_seconds, started_at, completed_at,
-                         abandoned_at, expires_at, paused_at, paused_remaining_seconds,
-                         status, xp_awarded, coins_awarded, task_id, task_title, created_at"#,
-        )
-        .bind(new_expires_at)
-        .bind(session_id)
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
+        let session = sqlx::query_as::<_, FocusSession>(FOCUS_PAUSE_RESUME_SESSION_SESSION)
+            .bind(new_expires_at)
+            .bind(session_id)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
 
         // Clear pause state
-        sqlx::query("DELETE FROM focus_pause_state WHERE user_id = $1")
+        sqlx::query(FOCUS_PAUSE_RESUME_SESSION_DELETE_FOCUS_PAUSE_STATE)
             .bind(user_id)
             .execute(pool)
             .await?;
@@ -419,7 +464,7 @@ impl FocusPauseRepo {
 
     /// Clear pause state
     pub async fn clear_pause_state(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
-        sqlx::query("DELETE FROM focus_pause_state WHERE user_id = $1")
+        sqlx::query(FOCUS_PAUSE_CLEAR_PAUSE_STATE)
             .bind(user_id)
             .execute(pool)
             .await?;
@@ -434,6 +479,33 @@ impl FocusPauseRepo {
 
 pub struct FocusLibraryRepo;
 
+pub const FOCUS_LIBRARY_LIST_LIBRARIES: &str =
+    "SELECT * FROM focus_libraries WHERE user_id = $1 ORDER BY is_favorite DESC, created_at DESC LIMIT $2 OFFSET $3";
+
+pub const FOCUS_LIBRARY_LIST_TOTAL: &str =
+    "SELECT COUNT(*) FROM focus_libraries WHERE user_id = $1";
+
+pub const FOCUS_LIBRARY_GET: &str = "SELECT * FROM focus_libraries WHERE id = $1 AND user_id = $2";
+
+pub const FOCUS_LIBRARY_CREATE: &str = r#"
+    INSERT INTO focus_libraries (user_id, name, description, library_type)
+    VALUES ($1, $2, $3, $4)
+    RETURNING *
+"#;
+
+pub const FOCUS_LIBRARY_DELETE_DELETE_FOCUS_LIBRARY_TRACKS: &str =
+    "DELETE FROM focus_library_tracks WHERE library_id = $1";
+
+pub const FOCUS_LIBRARY_DELETE_DELETE_FOCUS_LIBRARIES: &str =
+    "DELETE FROM focus_libraries WHERE id = $1 AND user_id = $2";
+
+pub const FOCUS_LIBRARY_TOGGLE_FAVORITE: &str = r#"
+    UPDATE focus_libraries
+    SET is_favorite = NOT is_favorite, updated_at = NOW()
+    WHERE id = $1 AND user_id = $2
+    RETURNING *
+"#;
+
 impl FocusLibraryRepo {
     /// List focus libraries for user
     pub async fn list(
@@ -444,21 +516,17 @@ impl FocusLibraryRepo {
     ) -> Result<FocusLibrariesListResponse, AppError> {
         let offset = (page - 1) * page_size;
 
-        let libraries = sqlx::query_as::<_, FocusLibrary>(
-            "SELECT * FROM focus_libraries WHERE user_id = $1 ORDER BY is_favorite DESC, created_at DESC LIMIT $2 OFFSET $3",
-        )
-        .bind(user_id)
-        .bind(page_size)
-        .bind(offset)
-        .fetch_all(pool)
-        .await?;
+        let libraries = sqlx::query_as::<_, FocusLibrary>(FOCUS_LIBRARY_LIST_LIBRARIES)
+            .bind(user_id)
+            .bind(page_size)
+            .bind(offset)
+            .fetch_all(pool)
+            .await?;
 
-        let total: (i64,) = sqlx::query_as(
-            "SELECT COUNT(*) FROM focus_libraries WHERE user_id = $1",
-        )
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
+        let total: (i64,) = sqlx::query_as(FOCUS_LIBRARY_LIST_TOTAL)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
 
         Ok(FocusLibrariesListResponse {
             libraries: libraries.into_iter().map(FocusLibraryResponse::from).collect(),
@@ -474,14 +542,12 @@ impl FocusLibraryRepo {
         user_id: Uuid,
         library_id: Uuid,
     ) -> Result<FocusLibrary, AppError> {
-        let library = sqlx::query_as::<_, FocusLibrary>(
-            "SELECT * FROM focus_libraries WHERE id = $1 AND user_id = $2",
-        )
-        .bind(library_id)
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?
-        .ok_or(AppError::NotFound("Library not found".into()))?;
+        let library = sqlx::query_as::<_, FocusLibrary>(FOCUS_LIBRARY_GET)
+            .bind(library_id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(AppError::NotFound("Library not found".into()))?;
 
         Ok(library)
     }
@@ -494,17 +560,13 @@ impl FocusLibraryRepo {
     ) -> Result<FocusLibrary, AppError> {
         let library_type = req.library_type.as_deref().unwrap_or("custom");
 
-        let library = sqlx::query_as::<_, FocusLibrary>(
-            "INSERT INTO focus_libraries (user_id, name, description, library_type)
-             VALUES ($1, $2, $3, $4)
-             RETURNING *",
-        )
-        .bind(user_id)
-        .bind(&req.name)
-        .bind(&req.description)
-        .bind(library_type)
-        .fetch_one(pool)
-        .await?;
+        let library = sqlx::query_as::<_, FocusLibrary>(FOCUS_LIBRARY_CREATE)
+            .bind(user_id)
+            .bind(&req.name)
+            .bind(&req.description)
+            .bind(library_type)
+            .fetch_one(pool)
+            .await?;
 
         Ok(library)
     }
@@ -516,19 +578,17 @@ impl FocusLibraryRepo {
         library_id: Uuid,
     ) -> Result<(), AppError> {
         // First delete all tracks
-        sqlx::query("DELETE FROM focus_library_tracks WHERE library_id = $1")
+        sqlx::query(FOCUS_LIBRARY_DELETE_DELETE_FOCUS_LIBRARY_TRACKS)
             .bind(library_id)
             .execute(pool)
             .await?;
 
         // Then delete library
-        let result = sqlx::query(
-            "DELETE FROM focus_libraries WHERE id = $1 AND user_id = $2",
-        )
-        .bind(library_id)
-        .bind(user_id)
-        .execute(pool)
-        .await?;
+        let result = sqlx::query(FOCUS_LIBRARY_DELETE_DELETE_FOCUS_LIBRARIES)
+            .bind(library_id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
 
         if result.rows_affected() == 0 {
             return Err(AppError::NotFound("Library not found".into()));
@@ -543,17 +603,12 @@ impl FocusLibraryRepo {
         user_id: Uuid,
         library_id: Uuid,
     ) -> Result<FocusLibrary, AppError> {
-        let library = sqlx::query_as::<_, FocusLibrary>(
-            "UPDATE focus_libraries 
-             SET is_favorite = NOT is_favorite, updated_at = NOW()
-             WHERE id = $1 AND user_id = $2
-             RETURNING *",
-        )
-        .bind(library_id)
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?
-        .ok_or(AppError::NotFound("Library not found".into()))?;
+        let library = sqlx::query_as::<_, FocusLibrary>(FOCUS_LIBRARY_TOGGLE_FAVORITE)
+            .bind(library_id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(AppError::NotFound("Library not found".into()))?;
 
         Ok(library)
     }
@@ -17,6 +17,26 @@ use crate::error::AppError;
 
 pub struct FrameManifestRepo;
 
+pub const FRAME_MANIFEST_CREATE: &str = r#"
+    INSERT INTO analysis_frame_manifests (
+        analysis_id, hop_ms, frame_count, duration_ms, sample_rate,
+        bands, bytes_per_frame, frame_layout, fingerprint, analyzer_version,
+        chunk_size_frames, total_chunks
+    )
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+    RETURNING *
+"#;
+
+pub const FRAME_MANIFEST_GET_BY_ANALYSIS: &str =
+    "SELECT * FROM analysis_frame_manifests WHERE analysis_id = $1";
+
+pub const FRAME_MANIFEST_GET_BY_ID: &str = "SELECT * FROM analysis_frame_manifests WHERE id = $1";
+
+pub const FRAME_MANIFEST_UPDATE_EVENTS: &str =
+    "UPDATE analysis_frame_manifests SET events = $2 WHERE id = $1";
+
+pub const FRAME_MANIFEST_DELETE: &str = "DELETE FROM analysis_frame_manifests WHERE id = $1";
+
 impl FrameManifestRepo {
     /// Create a new frame manifest
     pub async fn create(
@@ -34,36 +54,26 @@ impl FrameManifestRepo {
         let layout_json =
             serde_json::to_value(&frame_layout).map_err(|e| AppError::Internal(e.to_string()))?;
 
-        let manifest = sqlx::query_as::<_, AnalysisFrameManifest>(
-            r#"
-            INSERT INTO analysis_frame_manifests (
-                analysis_id, hop_ms, frame_count, duration_ms, sample_rate,
-                bands, bytes_per_frame, frame_layout, fingerprint, analyzer_version,
-                chunk_size_frames, total_chunks
+        let manifest = sqlx::query_as::<_, AnalysisFrameManifest>(FRAME_MANIFEST_CREATE)
+            .bind(analysis_id)
+            .bind(input.hop_ms)
+            .bind(input.frame_count)
+            .bind(input.duration_ms)
+            .bind(input.sample_rate.unwrap_or(44100))
+            .bind(&bands_json)
+            .bind(bytes_per_frame)
+            .bind(&layout_json)
+            .bind(&input.fingerprint)
+            .bind(
+                input
+                    .analyzer_version
+                    .unwrap_or_else(|| "1.0.0".to_string()),
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
-            RETURNING *
-            "#,
-        )
-        .bind(analysis_id)
-        .bind(input.hop_ms)
-        .bind(input.frame_count)
-        .bind(input.duration_ms)
-        .bind(input.sample_rate.unwrap_or(44100))
-        .bind(&bands_json)
-        .bind(bytes_per_frame)
-        .bind(&layout_json)
-        .bind(&input.fingerprint)
-        .bind(
-            input
-                .analyzer_version
-                .unwrap_or_else(|| "1.0.0".to_string()),
-        )
-        .bind(chunk_size)
-        .bind(total_chunks)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+            .bind(chunk_size)
+            .bind(total_chunks)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(manifest)
     }
@@ -73,13 +83,11 @@ impl FrameManifestRepo {
         pool: &PgPool,
         analysis_id: Uuid,
     ) -> Result<Option<AnalysisFrameManifest>, AppError> {
-        let manifest = sqlx::query_as::<_, AnalysisFrameManifest>(
-            "SELECT * FROM analysis_frame_manifests WHERE analysis_id = $1",
-        )
-        .bind(analysis_id)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        let manifest = sqlx::query_as::<_, AnalysisFrameManifest>(FRAME_MANIFEST_GET_BY_ANALYSIS)
+            .bind(analysis_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(manifest)
     }
@@ -89,13 +97,11 @@ impl FrameManifestRepo {
         pool: &PgPool,
         id: Uuid,
     ) -> Result<Option<AnalysisFrameManifest>, AppError> {
-        let manifest = sqlx::query_as::<_, AnalysisFrameManifest>(
-            "SELECT * FROM analysis_frame_manifests WHERE id = $1",
-        )
-        .bind(id)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        let manifest = sqlx::query_as::<_, AnalysisFrameManifest>(FRAME_MANIFEST_GET_BY_ID)
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(manifest)
     }
@@ -109,7 +115,7 @@ impl FrameManifestRepo {
         let events_json =
             serde_json::to_value(&events).map_err(|e| AppError::Internal(e.to_string()))?;
 
-        sqlx::query("UPDATE analysis_frame_manifests SET events = $2 WHERE id = $1")
+        sqlx::query(FRAME_MANIFEST_UPDATE_EVENTS)
             .bind(manifest_id)
             .bind(&events_json)
             .execute(pool)
@@ -121,7 +127,7 @@ impl FrameManifestRepo {
 
     /// Delete manifest (cascades to frame data)
     pub async fn delete(pool: &PgPool, id: Uuid) -> Result<bool, AppError> {
-        let result = sqlx::query("DELETE FROM analysis_frame_manifests WHERE id = $1")
+        let result = sqlx::query(FRAME_MANIFEST_DELETE)
             .bind(id)
             .execute(pool)
             .await
@@ -137,6 +143,30 @@ impl FrameManifestRepo {
 
 pub struct FrameDataRepo;
 
+pub const FRAME_DATA_CREATE_CHUNK: &str = r#"
+    INSERT INTO analysis_frame_data (
+        manifest_id, chunk_index, start_frame, end_frame,
+        start_time_ms, end_time_ms, frame_data, frame_count,
+        compressed, compression_type
+    )
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+    RETURNING *
+"#;
+
+pub const FRAME_DATA_GET_CHUNKS_FOR_RANGE: &str = r#"
+    SELECT * FROM analysis_frame_data
+    WHERE manifest_id = $1
+      AND end_time_ms > $2
+      AND start_time_ms < $3
+    ORDER BY chunk_index
+"#;
+
+pub const FRAME_DATA_GET_ALL_CHUNKS: &str =
+    "SELECT * FROM analysis_frame_data WHERE manifest_id = $1 ORDER BY chunk_index";
+
+pub const FRAME_DATA_GET_CHUNK_BY_INDEX: &str =
+    "SELECT * FROM analysis_frame_data WHERE manifest_id = $1 AND chunk_index = $2";
+
 impl FrameDataRepo {
     /// Create a frame data chunk
     pub async fn create_chunk(
@@ -144,30 +174,20 @@ impl FrameDataRepo {
         manifest_id: Uuid,
         input: CreateFrameDataInput,
     ) -> Result<AnalysisFrameData, AppError> {
-        let chunk = sqlx::query_as::<_, AnalysisFrameData>(
-            r#"
-            INSERT INTO analysis_frame_data (
-                manifest_id, chunk_index, start_frame, end_frame,
-                start_time_ms, end_time_ms, frame_data, frame_count,
-                compressed, compression_type
-            )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-            RETURNING *
-            "#,
-        )
-        .bind(manifest_id)
-        .bind(input.chunk_index)
-        .bind(input.start_frame)
-        .bind(input.end_frame)
-        .bind(input.start_time_ms)
-        .bind(input.end_time_ms)
-        .bind(&input.frame_data)
-        .bind(input.frame_count)
-        .bind(input.compressed)
-        .bind(&input.compression_type)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        let chunk = sqlx::query_as::<_, AnalysisFrameData>(FRAME_DATA_CREATE_CHUNK)
+            .bind(manifest_id)
+            .bind(input.chunk_index)
+            .bind(input.start_frame)
+            .bind(input.end_frame)
+            .bind(input.start_time_ms)
+            .bind(input.end_time_ms)
+            .bind(&input.frame_data)
+            .bind(input.frame_count)
+            .bind(input.compressed)
+            .bind(&input.compression_type)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(chunk)
     }
@@ -179,21 +199,13 @@ impl FrameDataRepo {
         from_ms: i32,
         to_ms: i32,
     ) -> Result<Vec<AnalysisFrameData>, AppError> {
-        let chunks = sqlx::query_as::<_, AnalysisFrameData>(
-            r#"
-            SELECT * FROM analysis_frame_data
-            WHERE manifest_id = $1
-              AND end_time_ms > $2
-              AND start_time_ms < $3
-            ORDER BY chunk_index
-            "#,
-        )
-        .bind(manifest_id)
-        .bind(from_ms)
-        .bind(to_ms)
-        .fetch_all(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        let chunks = sqlx::query_as::<_, AnalysisFrameData>(FRAME_DATA_GET_CHUNKS_FOR_RANGE)
+            .bind(manifest_id)
+            .bind(from_ms)
+            .bind(to_ms)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(chunks)
     }
@@ -203,13 +215,11 @@ impl FrameDataRepo {
         pool: &PgPool,
         manifest_id: Uuid,
     ) -> Result<Vec<AnalysisFrameData>, AppError> {
-        let chunks = sqlx::query_as::<_, AnalysisFrameData>(
-            "SELECT * FROM analysis_frame_data WHERE manifest_id = $1 ORDER BY chunk_index",
-        )
-        .bind(manifest_id)
-        .fetch_all(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        let chunks = sqlx::query_as::<_, AnalysisFrameData>(FRAME_DATA_GET_ALL_CHUNKS)
+            .bind(manifest_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(chunks)
     }
@@ -220,14 +230,12 @@ impl FrameDataRepo {
         manifest_id: Uuid,
         chunk_index: i32,
     ) -> Result<Option<AnalysisFrameData>, AppError> {
-        let chunk = sqlx::query_as::<_, AnalysisFrameData>(
-            "SELECT * FROM analysis_frame_data WHERE manifest_id = $1 AND chunk_index = $2",
-        )
-        .bind(manifest_id)
-        .bind(chunk_index)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        let chunk = sqlx::query_as::<_, AnalysisFrameData>(FRAME_DATA_GET_CHUNK_BY_INDEX)
+            .bind(manifest_id)
+            .bind(chunk_index)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(chunk)
     }
@@ -258,6 +266,30 @@ impl FrameDataRepo {
 
 pub struct AnalysisEventsRepo;
 
+pub const ANALYSIS_EVENTS_CREATE: &str = r#"
+    INSERT INTO analysis_events (
+        analysis_id, time_ms, duration_ms, event_type, event_data, confidence
+    )
+    VALUES ($1, $2, $3, $4, $5, $6)
+    RETURNING *
+"#;
+
+pub const ANALYSIS_EVENTS_CREATE_BATCH: &str = r#"
+    INSERT INTO analysis_events (
+        analysis_id, time_ms, duration_ms, event_type, event_data, confidence
+    )
+    VALUES ($1, $2, $3, $4, $5, $6)
+"#;
+
+pub const ANALYSIS_EVENTS_GET_FOR_ANALYSIS: &str = r#"
+    SELECT * FROM analysis_events
+    WHERE analysis_id = $1
+      AND ($2::int IS NULL OR time_ms >= $2)
+      AND ($3::int IS NULL OR time_ms <= $3)
+      AND ($4::text IS NULL OR event_type = $4)
+    ORDER BY time_ms
+"#;
+
 impl AnalysisEventsRepo {
     /// Create an event
     pub async fn create(
@@ -267,24 +299,16 @@ impl AnalysisEventsRepo {
     ) -> Result<AnalysisEventRow, AppError> {
         let event_data = input.event_data.unwrap_or(serde_json::json!({}));
 
-        let event = sqlx::query_as::<_, AnalysisEventRow>(
-            r#"
-            INSERT INTO analysis_events (
-                analysis_id, time_ms, duration_ms, event_type, event_data, confidence
-            )
-            VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING *
-            "#,
-        )
-        .bind(analysis_id)
-        .bind(input.time_ms)
-        .bind(input.duration_ms)
-        .bind(&input.event_type)
-        .bind(&event_data)
-        .bind(input.confidence)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        let event = sqlx::query_as::<_, AnalysisEventRow>(ANALYSIS_EVENTS_CREATE)
+            .bind(analysis_id)
+            .bind(input.time_ms)
+            .bind(input.duration_ms)
+            .bind(&input.event_type)
+            .bind(&event_data)
+            .bind(input.confidence)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(event)
     }
@@ -300,23 +324,16 @@ impl AnalysisEventsRepo {
         for input in inputs {
             let event_data = input.event_data.unwrap_or(serde_json::json!({}));
 
-            sqlx::query(
-                r#"
-                INSERT INTO analysis_events (
-                    analysis_id, time_ms, duration_ms, event_type, event_data, confidence
-                )
-                VALUES ($1, $2, $3, $4, $5, $6)
-                "#,
-            )
-            .bind(analysis_id)
-            .bind(input.time_ms)
-            .bind(input.duration_ms)
-            .bind(&input.event_type)
-            .bind(&event_data)
-            .bind(input.confidence)
-            .execute(pool)
-            .await
-            .map_err(|e| AppError::Database(e.to_string()))?;
+            sqlx::query(ANALYSIS_EVENTS_CREATE_BATCH)
+                .bind(analysis_id)
+                .bind(input.time_ms)
+                .bind(input.duration_ms)
+                .bind(&input.event_type)
+                .bind(&event_data)
+                .bind(input.confidence)
+                .execute(pool)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?;
 
             count += 1;
         }
@@ -332,84 +349,15 @@ impl AnalysisEventsRepo {
         to_ms: Option<i32>,
         event_type: Option<String>,
     ) -> Result<Vec<AnalysisEventRow>, AppError> {
-        let events = match (&from_ms, &to_ms, &event_type) {
-            (Some(from), Some(to), Some(etype)) => {
-                sqlx::query_as::<_, AnalysisEventRow>(
-                    "SELECT * FROM analysis_events WHERE analysis_id = $1 AND time_ms >= $2 AND time_ms <= $3 AND event_type = $4 ORDER BY time_ms"
-                )
-                .bind(analysis_id)
-                .bind(from)
-                .bind(to)
-                .bind(etype)
-                .fetch_all(pool)
-                .await
-            }
-            (Some(from), Some(to), None) => {
-                sqlx::query_as::<_, AnalysisEventRow>(
-                    "SELECT * FROM analysis_events WHERE analysis_id = $1 AND time_ms >= $2 AND time_ms <= $3 ORDER BY time_ms"
-                )
-                .bind(analysis_id)
-                .bind(from)
-                .bind(to)
-                .fetch_all(pool)
-                .await
-            }
-            (Some(from), None, Some(etype)) => {
-                sqlx::query_as::<_, AnalysisEventRow>(
-                    "SELECT * FROM analysis_events WHERE analysis_id = $1 AND time_ms >= $2 AND event_type = $3 ORDER BY time_ms"
-                )
-                .bind(analysis_id)
-                .bind(from)
-                .bind(etype)
-                .fetch_all(pool)
-                .await
-            }
-            (None, Some(to), Some(etype)) => {
-                sqlx::query_as::<_, AnalysisEventRow>(
-                    "SELECT * FROM analysis_events WHERE analysis_id = $1 AND time_ms <= $2 AND event_type = $3 ORDER BY time_ms"
-                )
-                .bind(analysis_id)
-                .bind(to)
-                .bind(etype)
-                .fetch_all(pool)
-                .await
-            }
-            (Some(from), None, None) => {
-                sqlx::query_as::<_, AnalysisEventRow>(
-                    "SELECT * FROM analysis_events WHERE analysis_id = $1 AND time_ms >= $2 ORDER BY time_ms"
-                )
-                .bind(analysis_id)
-                .bind(from)
-                .fetch_all(pool)
-                .await
-            }
-            (None, Some(to), None) => {
-                sqlx::query_as::<_, AnalysisEventRow>(
-                    "SELECT * FROM analysis_events WHERE analysis_id = $1 AND time_ms <= $2 ORDER BY time_ms"
-                )
-                .bind(analysis_id)
-                .bind(to)
-                .fetch_all(pool)
-                .await
-            }
-            (None, None, Some(etype)) => {
-                sqlx::query_as::<_, AnalysisEventRow>(
-                    "SELECT * FROM analysis_events WHERE analysis_id = $1 AND event_type = $2 ORDER BY time_ms"
-                )
-                .bind(analysis_id)
-                .bind(etype)
-                .fetch_all(pool)
-                .await
-            }
-            (None, None, None) => {
-                sqlx::query_as::<_, AnalysisEventRow>(
-                    "SELECT * FROM analysis_events WHERE analysis_id = $1 ORDER BY time_ms"
-                )
-                .bind(analysis_id)
-                .fetch_all(pool)
-                .await
-            }
-        }.map_err(|e| AppError::Database(e.to_string()))?;
+        // Unset filters match everything
+        let events = sqlx::query_as::<_, AnalysisEventRow>(ANALYSIS_EVENTS_GET_FOR_ANALYSIS)
+            .bind(analysis_id)
+            .bind(from_ms)
+            .bind(to_ms)
+            .bind(&event_type)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(events)
     }
@@ -211,6 +211,9 @@ pub struct AwardPointsInput {
     pub event_id: Option<Uuid>,
     pub reason: Option<String>,
     pub idempotency_key: Option<String>,
+    /// Amount of activity behind the event (e.g. minutes), for quest progress
+    #[serde(default)]
+    pub quantity: Option<i32>,
 }
 
 /// Input for spending coins
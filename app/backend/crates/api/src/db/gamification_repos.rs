@@ -8,6 +8,8 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use super::gamification_models::*;
+use super::quests_models::QuestEvent;
+use super::quests_repos::UniversalQuestRepo;
 use crate::error::AppError;
 
 // ============================================================================
@@ -25,33 +27,52 @@ fn xp_for_level(level: i32) -> i32 {
 
 pub struct UserProgressRepo;
 
+pub const USER_PROGRESS_GET_OR_CREATE_USER_PROGRESS: &str = r#"
+    SELECT id, user_id, total_xp, current_level, xp_to_next_level,
+           total_skill_stars, created_at, updated_at
+    FROM user_progress WHERE user_id = $1
+"#;
+
+pub const USER_PROGRESS_GET_OR_CREATE_PROGRESS: &str = r#"
+    INSERT INTO user_progress (user_id, total_xp, current_level, xp_to_next_level, total_skill_stars)
+    VALUES ($1, 0, 1, 100, 0)
+    RETURNING id, user_id, total_xp, current_level, xp_to_next_level,
+              total_skill_stars, created_at, updated_at
+"#;
+
+// XP and coins for one award share a key, so each check only counts its own currency
+pub const USER_PROGRESS_AWARD_XP_POINTS_LEDGER: &str =
+    "SELECT COUNT(*) FROM points_ledger WHERE idempotency_key = $1 AND xp <> 0";
+
+pub const USER_PROGRESS_AWARD_XP_UPDATE_USER_PROGRESS: &str = r#"
+    UPDATE user_progress
+    SET total_xp = $1, current_level = $2, xp_to_next_level = $3, updated_at = NOW()
+    WHERE user_id = $4
+"#;
+
+pub const USER_PROGRESS_AWARD_XP_INSERT_POINTS_LEDGER: &str = r#"
+    INSERT INTO points_ledger (user_id, event_type, event_id, xp, reason, idempotency_key)
+    VALUES ($1, $2, $3, $4, $5, $6)
+"#;
+
 impl UserProgressRepo {
     /// Get or create user progress
     pub async fn get_or_create(pool: &PgPool, user_id: Uuid) -> Result<UserProgress, AppError> {
         // Try to get existing
-        let existing = sqlx::query_as::<_, UserProgress>(
-            r#"SELECT id, user_id, total_xp, current_level, xp_to_next_level,
-                      total_skill_stars, created_at, updated_at
-               FROM user_progress WHERE user_id = $1"#,
-        )
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let existing = sqlx::query_as::<_, UserProgress>(USER_PROGRESS_GET_OR_CREATE_USER_PROGRESS)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
 
         if let Some(progress) = existing {
             return Ok(progress);
         }
 
         // Create new
-        let progress = sqlx::query_as::<_, UserProgress>(
-            r#"INSERT INTO user_progress (user_id, total_xp, current_level, xp_to_next_level, total_skill_stars)
-               VALUES ($1, 0, 1, 100, 0)
-               RETURNING id, user_id, total_xp, current_level, xp_to_next_level,
-                         total_skill_stars, created_at, updated_at"#,
-        )
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
+        let progress = sqlx::query_as::<_, UserProgress>(USER_PROGRESS_GET_OR_CREATE_PROGRESS)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
 
         Ok(progress)
     }
@@ -68,12 +89,10 @@ impl UserProgressRepo {
     ) -> Result<AwardResult, AppError> {
         // Check idempotency
         if let Some(key) = idempotency_key {
-            let existing = sqlx::query_scalar::<_, i64>(
-                "SELECT COUNT(*) FROM points_ledger WHERE idempotency_key = $1",
-            )
-            .bind(key)
-            .fetch_one(pool)
-            .await?;
+            let existing = sqlx::query_scalar::<_, i64>(USER_PROGRESS_AWARD_XP_POINTS_LEDGER)
+                .bind(key)
+                .fetch_one(pool)
+                .await?;
 
             if existing > 0 {
                 let progress = Self::get_or_create(pool, user_id).await?;
@@ -110,31 +129,24 @@ impl UserProgressRepo {
         let new_xp_to_next = xp_for_level(new_level);
 
         // Update progress
-        sqlx::query(
-            r#"UPDATE user_progress
-               SET total_xp = $1, current_level = $2, xp_to_next_level = $3, updated_at = NOW()
-               WHERE user_id = $4"#,
-        )
-        .bind(new_xp)
-        .bind(new_level)
-        .bind(new_xp_to_next)
-        .bind(user_id)
-        .execute(pool)
-        .await?;
+        sqlx::query(USER_PROGRESS_AWARD_XP_UPDATE_USER_PROGRESS)
+            .bind(new_xp)
+            .bind(new_level)
+            .bind(new_xp_to_next)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
 
         // Record in ledger
-        sqlx::query(
-            r#"INSERT INTO points_ledger (user_id, event_type, event_id, xp, reason, idempotency_key)
-               VALUES ($1, $2, $3, $4, $5, $6)"#,
-        )
-        .bind(user_id)
-        .bind(event_type)
-        .bind(event_id)
-        .bind(xp)
-        .bind(reason)
-        .bind(idempotency_key)
-        .execute(pool)
-        .await?;
+        sqlx::query(USER_PROGRESS_AWARD_XP_INSERT_POINTS_LEDGER)
+            .bind(user_id)
+            .bind(event_type)
+            .bind(event_id)
+            .bind(xp)
+            .bind(reason)
+            .bind(idempotency_key)
+            .execute(pool)
+            .await?;
 
         Ok(AwardResult {
             success: true,
@@ -152,31 +164,64 @@ impl UserProgressRepo {
 
 pub struct UserWalletRepo;
 
+pub const USER_WALLET_GET_OR_CREATE_USER_WALLET: &str = r#"
+    SELECT id, user_id, coins, total_earned, total_spent, created_at, updated_at
+    FROM user_wallet WHERE user_id = $1
+"#;
+
+pub const USER_WALLET_GET_OR_CREATE_WALLET: &str = r#"
+    INSERT INTO user_wallet (user_id, coins, total_earned, total_spent)
+    VALUES ($1, 0, 0, 0)
+    RETURNING id, user_id, coins, total_earned, total_spent, created_at, updated_at
+"#;
+
+pub const USER_WALLET_AWARD_COINS_POINTS_LEDGER: &str =
+    "SELECT COUNT(*) FROM points_ledger WHERE idempotency_key = $1 AND coins <> 0";
+
+pub const USER_WALLET_AWARD_COINS_NEW_BALANCE: &str = r#"
+    UPDATE user_wallet
+    SET coins = coins + $1,
+        total_earned = CASE WHEN $1 > 0 THEN total_earned + $1 ELSE total_earned END,
+        updated_at = NOW()
+    WHERE user_id = $2
+    RETURNING coins::bigint
+"#;
+
+pub const USER_WALLET_AWARD_COINS_INSERT_POINTS_LEDGER: &str = r#"
+    INSERT INTO points_ledger (user_id, event_type, event_id, coins, reason, idempotency_key)
+    VALUES ($1, $2, $3, $4, $5, $6)
+"#;
+
+pub const USER_WALLET_SPEND_COINS_NEW_BALANCE: &str = r#"
+    UPDATE user_wallet
+    SET coins = coins - $1, total_spent = total_spent + $1, updated_at = NOW()
+    WHERE user_id = $2
+    RETURNING coins::bigint
+"#;
+
+pub const USER_WALLET_SPEND_COINS_INSERT_POINTS_LEDGER: &str = r#"
+    INSERT INTO points_ledger (user_id, event_type, event_id, coins, reason)
+    VALUES ($1, 'spend', $2, $3, $4)
+"#;
+
 impl UserWalletRepo {
     /// Get or create user wallet
     pub async fn get_or_create(pool: &PgPool, user_id: Uuid) -> Result<UserWallet, AppError> {
         // Try to get existing
-        let existing = sqlx::query_as::<_, UserWallet>(
-            r#"SELECT id, user_id, coins, total_earned, total_spent, created_at, updated_at
-               FROM user_wallet WHERE user_id = $1"#,
-        )
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let existing = sqlx::query_as::<_, UserWallet>(USER_WALLET_GET_OR_CREATE_USER_WALLET)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
 
         if let Some(wallet) = existing {
             return Ok(wallet);
         }
 
         // Create new
-        let wallet = sqlx::query_as::<_, UserWallet>(
-            r#"INSERT INTO user_wallet (user_id, coins, total_earned, total_spent)
-               VALUES ($1, 0, 0, 0)
-               RETURNING id, user_id, coins, total_earned, total_spent, created_at, updated_at"#,
-        )
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
+        let wallet = sqlx::query_as::<_, UserWallet>(USER_WALLET_GET_OR_CREATE_WALLET)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
 
         Ok(wallet)
     }
@@ -193,12 +238,10 @@ impl UserWalletRepo {
     ) -> Result<AwardResult, AppError> {
         // Check idempotency
         if let Some(key) = idempotency_key {
-            let existing = sqlx::query_scalar::<_, i64>(
-                "SELECT COUNT(*) FROM points_ledger WHERE idempotency_key = $1",
-            )
-            .bind(key)
-            .fetch_one(pool)
-            .await?;
+            let existing = sqlx::query_scalar::<_, i64>(USER_WALLET_AWARD_COINS_POINTS_LEDGER)
+                .bind(key)
+                .fetch_one(pool)
+                .await?;
 
             if existing > 0 {
                 let wallet = Self::get_or_create(pool, user_id).await?;
@@ -216,32 +259,22 @@ impl UserWalletRepo {
         Self::get_or_create(pool, user_id).await?;
 
         // Update wallet
-        let new_balance = sqlx::query_scalar::<_, i64>(
-            r#"UPDATE user_wallet
-               SET coins = coins + $1,
-                   total_earned = CASE WHEN $1 > 0 THEN total_earned + $1 ELSE total_earned END,
-                   updated_at = NOW()
-               WHERE user_id = $2
-               RETURNING coins"#,
-        )
-        .bind(coins as i64)
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
+        let new_balance = sqlx::query_scalar::<_, i64>(USER_WALLET_AWARD_COINS_NEW_BALANCE)
+            .bind(coins as i64)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
 
         // Record in ledger
-        sqlx::query(
-            r#"INSERT INTO points_ledger (user_id, event_type, event_id, coins, reason, idempotency_key)
-               VALUES ($1, $2, $3, $4, $5, $6)"#,
-        )
-        .bind(user_id)
-        .bind(event_type)
-        .bind(event_id)
-        .bind(coins)
-        .bind(reason)
-        .bind(idempotency_key)
-        .execute(pool)
-        .await?;
+        sqlx::query(USER_WALLET_AWARD_COINS_INSERT_POINTS_LEDGER)
+            .bind(user_id)
+            .bind(event_type)
+            .bind(event_id)
+            .bind(coins)
+            .bind(reason)
+            .bind(idempotency_key)
+            .execute(pool)
+            .await?;
 
         Ok(AwardResult {
             success: true,
@@ -272,28 +305,20 @@ impl UserWalletRepo {
         }
 
         // Deduct coins
-        let new_balance = sqlx::query_scalar::<_, i64>(
-            r#"UPDATE user_wallet
-               SET coins = coins - $1, total_spent = total_spent + $1, updated_at = NOW()
-               WHERE user_id = $2
-               RETURNING coins"#,
-        )
-        .bind(amount as i64)
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
+        let new_balance = sqlx::query_scalar::<_, i64>(USER_WALLET_SPEND_COINS_NEW_BALANCE)
+            .bind(amount as i64)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
 
         // Record in ledger (negative amount)
-        sqlx::query(
-            r#"INSERT INTO points_ledger (user_id, event_type, event_id, coins, reason)
-               VALUES ($1, 'spend', $2, $3, $4)"#,
-        )
-        .bind(user_id)
-        .bind(purchase_id)
-        .bind(-amount)
-        .bind(reason)
-        .execute(pool)
-        .await?;
+        sqlx::query(USER_WALLET_SPEND_COINS_INSERT_POINTS_LEDGER)
+            .bind(user_id)
+            .bind(purchase_id)
+            .bind(-amount)
+            .bind(reason)
+            .execute(pool)
+            .await?;
 
         Ok(SpendResult {
             success: true,
@@ -309,6 +334,29 @@ impl UserWalletRepo {
 
 pub struct StreaksRepo;
 
+pub const STREAKS_GET_STREAK: &str = r#"
+    SELECT id, user_id, streak_type, current_streak, longest_streak,
+           last_activity_date, created_at, updated_at
+    FROM user_streaks WHERE user_id = $1 AND streak_type = $2
+"#;
+
+pub const STREAKS_UPDATE_STREAK_INSERT_USER_STREAKS: &str = r#"
+    INSERT INTO user_streaks (user_id, streak_type, current_streak, longest_streak, last_activity_date)
+    VALUES ($1, $2, 1, 1, $3)
+"#;
+
+pub const STREAKS_UPDATE_STREAK_UPDATE_USER_STREAKS: &str = r#"
+    UPDATE user_streaks
+    SET current_streak = $1, longest_streak = $2, last_activity_date = $3, updated_at = NOW()
+    WHERE user_id = $4 AND streak_type = $5
+"#;
+
+pub const STREAKS_GET_MAX_CURRENT_STREAK: &str =
+    "SELECT MAX(current_streak) FROM user_streaks WHERE user_id = $1";
+
+pub const STREAKS_GET_MAX_LONGEST_STREAK: &str =
+    "SELECT MAX(longest_streak) FROM user_streaks WHERE user_id = $1";
+
 impl StreaksRepo {
     /// Get user streak
     pub async fn get_streak(
@@ -316,15 +364,11 @@ impl StreaksRepo {
         user_id: Uuid,
         streak_type: &str,
     ) -> Result<Option<UserStreak>, AppError> {
-        let streak = sqlx::query_as::<_, UserStreak>(
-            r#"SELECT id, user_id, streak_type, current_streak, longest_streak,
-                      last_activity_date, created_at, updated_at
-               FROM user_streaks WHERE user_id = $1 AND streak_type = $2"#,
-        )
-        .bind(user_id)
-        .bind(streak_type)
-        .fetch_optional(pool)
-        .await?;
+        let streak = sqlx::query_as::<_, UserStreak>(STREAKS_GET_STREAK)
+            .bind(user_id)
+            .bind(streak_type)
+            .fetch_optional(pool)
+            .await?;
 
         Ok(streak)
     }
@@ -343,15 +387,12 @@ impl StreaksRepo {
         match existing {
             None => {
                 // Create new streak
-                sqlx::query(
-                    r#"INSERT INTO user_streaks (user_id, streak_type, current_streak, longest_streak, last_activity_date)
-                       VALUES ($1, $2, 1, 1, $3)"#,
-                )
-                .bind(user_id)
-                .bind(streak_type)
-                .bind(today)
-                .execute(pool)
-                .await?;
+                sqlx::query(STREAKS_UPDATE_STREAK_INSERT_USER_STREAKS)
+                    .bind(user_id)
+                    .bind(streak_type)
+                    .bind(today)
+                    .execute(pool)
+                    .await?;
 
                 Ok(StreakUpdateResult {
                     current_streak: 1,
@@ -379,18 +420,14 @@ impl StreaksRepo {
 
                 let new_longest = std::cmp::max(streak.longest_streak, new_streak);
 
-                sqlx::query(
-                    r#"UPDATE user_streaks
-                       SET current_streak = $1, longest_streak = $2, last_activity_date = $3, updated_at = NOW()
-                       WHERE user_id = $4 AND streak_type = $5"#,
-                )
-                .bind(new_streak)
-                .bind(new_longest)
-                .bind(today)
-                .bind(user_id)
-                .bind(streak_type)
-                .execute(pool)
-                .await?;
+                sqlx::query(STREAKS_UPDATE_STREAK_UPDATE_USER_STREAKS)
+                    .bind(new_streak)
+                    .bind(new_longest)
+                    .bind(today)
+                    .bind(user_id)
+                    .bind(streak_type)
+                    .execute(pool)
+                    .await?;
 
                 Ok(StreakUpdateResult {
                     current_streak: new_streak,
@@ -403,24 +440,20 @@ impl StreaksRepo {
 
     /// Get max current streak for user
     pub async fn get_max_current_streak(pool: &PgPool, user_id: Uuid) -> Result<i32, AppError> {
-        let max_streak = sqlx::query_scalar::<_, Option<i32>>(
-            "SELECT MAX(current_streak) FROM user_streaks WHERE user_id = $1",
-        )
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
+        let max_streak = sqlx::query_scalar::<_, Option<i32>>(STREAKS_GET_MAX_CURRENT_STREAK)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
 
         Ok(max_streak.unwrap_or(0))
     }
 
     /// Get max longest streak for user
     pub async fn get_max_longest_streak(pool: &PgPool, user_id: Uuid) -> Result<i32, AppError> {
-        let max_streak = sqlx::query_scalar::<_, Option<i32>>(
-            "SELECT MAX(longest_streak) FROM user_streaks WHERE user_id = $1",
-        )
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
+        let max_streak = sqlx::query_scalar::<_, Option<i32>>(STREAKS_GET_MAX_LONGEST_STREAK)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
 
         Ok(max_streak.unwrap_or(0))
     }
@@ -432,16 +465,34 @@ impl StreaksRepo {
 
 pub struct AchievementsRepo;
 
+pub const ACHIEVEMENTS_GET_DEFINITIONS: &str = r#"
+    SELECT id, key, name, description, category, icon, trigger_type, trigger_config,
+           reward_coins, reward_xp, is_hidden, sort_order, created_at
+    FROM achievement_definitions ORDER BY category, sort_order
+"#;
+
+pub const ACHIEVEMENTS_GET_USER_ACHIEVEMENTS: &str = r#"
+    SELECT id, user_id, achievement_key, earned_at, notified
+    FROM user_achievements WHERE user_id = $1 ORDER BY earned_at DESC
+"#;
+
+pub const ACHIEVEMENTS_HAS_ACHIEVEMENT: &str =
+    "SELECT COUNT(*) FROM user_achievements WHERE user_id = $1 AND achievement_key = $2";
+
+pub const ACHIEVEMENTS_UNLOCK_ACHIEVEMENT: &str = r#"
+    INSERT INTO user_achievements (user_id, achievement_key, earned_at, notified)
+    VALUES ($1, $2, NOW(), false)
+"#;
+
+pub const ACHIEVEMENTS_GET_ACHIEVEMENT_COUNT: &str =
+    "SELECT COUNT(*) FROM user_achievements WHERE user_id = $1";
+
 impl AchievementsRepo {
     /// Get all achievement definitions
     pub async fn get_definitions(pool: &PgPool) -> Result<Vec<AchievementDefinition>, AppError> {
-        let achievements = sqlx::query_as::<_, AchievementDefinition>(
-            r#"SELECT id, key, name, description, category, icon, trigger_type, trigger_config,
-                      reward_coins, reward_xp, is_hidden, sort_order, created_at
-               FROM achievement_definitions ORDER BY category, sort_order"#,
-        )
-        .fetch_all(pool)
-        .await?;
+        let achievements = sqlx::query_as::<_, AchievementDefinition>(ACHIEVEMENTS_GET_DEFINITIONS)
+            .fetch_all(pool)
+            .await?;
 
         Ok(achievements)
     }
@@ -451,13 +502,10 @@ impl AchievementsRepo {
         pool: &PgPool,
         user_id: Uuid,
     ) -> Result<Vec<UserAchievement>, AppError> {
-        let achievements = sqlx::query_as::<_, UserAchievement>(
-            r#"SELECT id, user_id, achievement_key, earned_at, notified
-               FROM user_achievements WHERE user_id = $1 ORDER BY earned_at DESC"#,
-        )
-        .bind(user_id)
-        .fetch_all(pool)
-        .await?;
+        let achievements = sqlx::query_as::<_, UserAchievement>(ACHIEVEMENTS_GET_USER_ACHIEVEMENTS)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
 
         Ok(achievements)
     }
@@ -468,13 +516,11 @@ impl AchievementsRepo {
         user_id: Uuid,
         achievement_key: &str,
     ) -> Result<bool, AppError> {
-        let count = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM user_achievements WHERE user_id = $1 AND achievement_key = $2",
-        )
-        .bind(user_id)
-        .bind(achievement_key)
-        .fetch_one(pool)
-        .await?;
+        let count = sqlx::query_scalar::<_, i64>(ACHIEVEMENTS_HAS_ACHIEVEMENT)
+            .bind(user_id)
+            .bind(achievement_key)
+            .fetch_one(pool)
+            .await?;
 
         Ok(count > 0)
     }
@@ -491,26 +537,21 @@ impl AchievementsRepo {
         }
 
         // Insert
-        sqlx::query(
-            r#"INSERT INTO user_achievements (user_id, achievement_key, earned_at, notified)
-               VALUES ($1, $2, NOW(), false)"#,
-        )
-        .bind(user_id)
-        .bind(achievement_key)
-        .execute(pool)
-        .await?;
+        sqlx::query(ACHIEVEMENTS_UNLOCK_ACHIEVEMENT)
+            .bind(user_id)
+            .bind(achievement_key)
+            .execute(pool)
+            .await?;
 
         Ok(true)
     }
 
     /// Get achievement count for user
     pub async fn get_achievement_count(pool: &PgPool, user_id: Uuid) -> Result<i64, AppError> {
-        let count = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM user_achievements WHERE user_id = $1",
-        )
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
+        let count = sqlx::query_scalar::<_, i64>(ACHIEVEMENTS_GET_ACHIEVEMENT_COUNT)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
 
         Ok(count)
     }
@@ -522,6 +563,24 @@ impl AchievementsRepo {
 
 pub struct GamificationRepo;
 
+pub const GAMIFICATION_GET_ACHIEVEMENT_TEASER_ACHIEVEMENTS: &str = r#"
+    SELECT id, key, name, description, category, icon, trigger_type, trigger_config,
+           reward_coins, reward_xp, is_hidden, sort_order, created_at
+    FROM achievement_definitions
+    WHERE is_hidden = false
+    ORDER BY reward_coins ASC
+"#;
+
+pub const GAMIFICATION_GET_ACHIEVEMENT_TEASER_FOCUS_COUNT: &str = r#"
+    SELECT COUNT(*) FROM points_ledger
+    WHERE user_id = $1 AND event_type = 'focus_complete'
+"#;
+
+pub const GAMIFICATION_GET_ACHIEVEMENT_TEASER_QUEST_COUNT: &str = r#"
+    SELECT COUNT(*) FROM points_ledger
+    WHERE user_id = $1 AND event_type = 'quest_complete'
+"#;
+
 impl GamificationRepo {
     /// Get complete gamification summary for a user
     pub async fn get_summary(
@@ -562,11 +621,7 @@ impl GamificationRepo {
     ) -> Result<Option<AchievementTeaser>, AppError> {
         // Get all non-hidden achievements
         let achievements = sqlx::query_as::<_, AchievementDefinition>(
-            r#"SELECT id, key, name, description, category, icon, trigger_type, trigger_config,
-                      reward_coins, reward_xp, is_hidden, sort_order, created_at
-               FROM achievement_definitions
-               WHERE is_hidden = false
-               ORDER BY reward_coins ASC"#,
+            GAMIFICATION_GET_ACHIEVEMENT_TEASER_ACHIEVEMENTS,
         )
         .fetch_all(pool)
         .await?;
@@ -582,23 +637,19 @@ impl GamificationRepo {
         let summary = Self::get_summary(pool, user_id).await?;
 
         // Count various activities
-        let focus_count = sqlx::query_scalar::<_, i64>(
-            r#"SELECT COUNT(*) FROM points_ledger
-               WHERE user_id = $1 AND event_type = 'focus_complete'"#,
-        )
-        .bind(user_id)
-        .fetch_one(pool)
-        .await
-        .unwrap_or(0);
-
-        let quest_count = sqlx::query_scalar::<_, i64>(
-            r#"SELECT COUNT(*) FROM points_ledger
-               WHERE user_id = $1 AND event_type = 'quest_complete'"#,
-        )
-        .bind(user_id)
-        .fetch_one(pool)
-        .await
-        .unwrap_or(0);
+        let focus_count =
+            sqlx::query_scalar::<_, i64>(GAMIFICATION_GET_ACHIEVEMENT_TEASER_FOCUS_COUNT)
+                .bind(user_id)
+                .fetch_one(pool)
+                .await
+                .unwrap_or(0);
+
+        let quest_count =
+            sqlx::query_scalar::<_, i64>(GAMIFICATION_GET_ACHIEVEMENT_TEASER_QUEST_COUNT)
+                .bind(user_id)
+                .fetch_one(pool)
+                .await
+                .unwrap_or(0);
 
         // Find first unachieved achievement with progress
         for achievement in achievements {
@@ -710,6 +761,8 @@ impl GamificationRepo {
             leveled_up: None,
             new_level: None,
         };
+        let mut awarded_xp = 0;
+        let mut awarded_coins = 0;
 
         // Award XP if specified
         if let Some(xp) = input.xp {
@@ -726,6 +779,9 @@ impl GamificationRepo {
                 .await?;
 
                 result.already_awarded = xp_result.already_awarded;
+                if !xp_result.already_awarded {
+                    awarded_xp = xp;
+                }
                 result.leveled_up = xp_result.leveled_up;
                 result.new_level = xp_result.new_level;
             }
@@ -748,6 +804,8 @@ impl GamificationRepo {
                 result.new_balance = coins_result.new_balance;
                 if coins_result.already_awarded {
                     result.already_awarded = true;
+                } else {
+                    awarded_coins = coins;
                 }
             }
         }
@@ -755,6 +813,19 @@ impl GamificationRepo {
         // Update daily activity streak
         let _ = StreaksRepo::update_streak(pool, user_id, "daily_activity").await;
 
+        // Advance universal quests the first time an event is recorded
+        if awarded_xp > 0 || awarded_coins > 0 {
+            let event = QuestEvent {
+                event_type: input.event_type.clone(),
+                xp: awarded_xp,
+                coins: awarded_coins,
+                quantity: input.quantity,
+            };
+            if let Err(e) = UniversalQuestRepo::record_event(pool, user_id, &event).await {
+                tracing::warn!("Failed to advance quests for {}: {}", input.event_type, e);
+            }
+        }
+
         Ok(result)
     }
 }
@@ -1,5 +1,5 @@
-// GENERATED FROM schema.json v2.0.0 - DO NOT EDIT
-// Generated: 2026-01-10
+// GENERATED FROM schema.json v2.1.0 - DO NOT EDIT
+// Generated: 2026-10-19
 //
 // Source of truth for database types. Import from here.
 //
@@ -51,6 +51,22 @@ pub struct Accounts {
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// Database model for `api_tokens` table
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ApiTokens {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub token_hash: String,
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_used_ip: Option<String>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
 /// Database model for `authenticators` table
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct Authenticators {
@@ -64,6 +80,8 @@ pub struct Authenticators {
     pub credential_backed_up: bool,
     pub transports: Vec<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    pub name: Option<String>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 /// Database model for `entitlements` table
@@ -77,6 +95,18 @@ pub struct Entitlements {
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// Database model for `oauth_states` table
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct OauthStates {
+    pub state_key: String,
+    pub pkce_verifier: String,
+    pub redirect_uri: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub provider: Option<String>,
+    pub nonce: Option<String>,
+}
+
 /// Database model for `role_entitlements` table
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct RoleEntitlements {
@@ -148,6 +178,16 @@ pub struct VerificationTokens {
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// Database model for `webauthn_challenges` table
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct WebauthnChallenges {
+    pub challenge: String,
+    pub ceremony: String,
+    pub user_id: Option<Uuid>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
 // =============================================================================
 // GAMIFICATION & PROGRESS
 // =============================================================================
@@ -196,6 +236,11 @@ pub struct SkillDefinitions {
     pub stars_per_level: i32,
     pub sort_order: i32,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    pub color: String,
+    pub xp_scaling_base: i32,
+    pub xp_scaling_multiplier: f64,
+    pub is_active: bool,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
 /// Database model for `universal_quests` table
@@ -216,6 +261,11 @@ pub struct UniversalQuests {
     pub sort_order: i32,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub key: Option<String>,
+    pub category: Option<String>,
+    pub skill_star_reward: i32,
+    pub is_recurring: bool,
+    pub recurrence_period: Option<String>,
 }
 
 /// Database model for `user_achievements` table
@@ -233,7 +283,7 @@ pub struct UserAchievements {
 pub struct UserProgress {
     pub id: Uuid,
     pub user_id: Uuid,
-    pub total_xp: i32,
+    pub total_xp: i64,
     pub current_level: i32,
     pub xp_to_next_level: i32,
     pub total_skill_stars: i32,
@@ -286,9 +336,9 @@ pub struct UserSkills {
 pub struct UserWallet {
     pub id: Uuid,
     pub user_id: Uuid,
-    pub coins: i32,
-    pub total_earned: i32,
-    pub total_spent: i32,
+    pub coins: i64,
+    pub total_earned: i64,
+    pub total_spent: i64,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -337,6 +387,19 @@ pub struct FocusSessions {
 // HABITS & GOALS
 // =============================================================================
 
+/// Database model for `goal_links` table
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct GoalLinks {
+    pub id: Uuid,
+    pub goal_id: Uuid,
+    pub user_id: Uuid,
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub weight: i32,
+    pub target: Option<i32>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
 /// Database model for `goal_milestones` table
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct GoalMilestones {
@@ -347,6 +410,7 @@ pub struct GoalMilestones {
     pub is_completed: bool,
     pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
     pub sort_order: i32,
+    pub weight: i32,
 }
 
 /// Database model for `goals` table
@@ -366,6 +430,8 @@ pub struct Goals {
     pub sort_order: i32,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub at_risk: bool,
+    pub archived_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 /// Database model for `habit_completions` table
@@ -493,6 +559,38 @@ pub struct WorkoutSessions {
 // LEARNING & COURSES
 // =============================================================================
 
+/// Database model for `drill_session_answers` table
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DrillSessionAnswers {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub question_index: i32,
+    pub answer: String,
+    pub is_correct: bool,
+    pub response_ms: i32,
+    pub answered_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Database model for `drill_sessions` table
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DrillSessions {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub drill_id: Uuid,
+    pub token: String,
+    pub drill_type: String,
+    pub config_json: serde_json::Value,
+    pub seed: i64,
+    pub difficulty_level: i32,
+    pub question_count: i32,
+    pub status: String,
+    pub score: Option<i32>,
+    pub correct_count: Option<i32>,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
 /// Database model for `learn_lessons` table
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct LearnLessons {
@@ -700,6 +798,68 @@ pub struct FeatureFlags {
     pub metadata: Option<serde_json::Value>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub rollout_percentage: i32,
+    pub target_user_ids: Vec<Uuid>,
+    pub target_roles: Vec<String>,
+    pub target_entitlements: Vec<String>,
+}
+
+/// Database model for `notification_deliveries` table
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct NotificationDeliveries {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub ref_id: Option<Uuid>,
+    pub dedupe_key: String,
+    pub channel: String,
+    pub title: String,
+    pub body: Option<String>,
+    pub action_url: Option<String>,
+    pub due_at: chrono::DateTime<chrono::Utc>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: chrono::DateTime<chrono::Utc>,
+    pub last_error: Option<String>,
+    pub sent_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Database model for `notification_settings` table
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    pub user_id: Uuid,
+    pub notifications_enabled: bool,
+    pub email_notifications: bool,
+    pub push_notifications: bool,
+    pub timezone: String,
+    pub daily_reminder_time: Option<chrono::NaiveTime>,
+    pub quiet_hours_start: Option<chrono::NaiveTime>,
+    pub quiet_hours_end: Option<chrono::NaiveTime>,
+}
+
+/// Database model for `push_subscriptions` table
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PushSubscriptions {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub endpoint: String,
+    pub p256dh: String,
+    pub auth: String,
+    pub user_agent: Option<String>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Database model for `sync_events` table
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct SyncEvents {
+    pub id: i64,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub ref_id: Option<Uuid>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
 /// Database model for `user_settings` table
@@ -717,6 +877,39 @@ pub struct UserSettings {
 // CONTENT & REFERENCES
 // =============================================================================
 
+/// Database model for `content_revisions` table
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ContentRevisions {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub entity_type: String,
+    pub entity_id: Uuid,
+    pub version: i32,
+    pub title: String,
+    pub content: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub author_id: Option<Uuid>,
+    pub content_hash: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Database model for `idea_attachments` table
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct IdeaAttachments {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub idea_id: Uuid,
+    pub kind: String,
+    pub r2_key: String,
+    pub filename: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub duration_seconds: Option<f32>,
+    pub reference_track_id: Option<Uuid>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
 /// Database model for `ideas` table
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct Ideas {
@@ -729,6 +922,23 @@ pub struct Ideas {
     pub is_pinned: bool,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub version: i32,
+    pub key: Option<String>,
+    pub bpm: Option<i32>,
+    pub mood: Option<String>,
+    pub genre: Option<String>,
+    pub time_signature: Option<String>,
+}
+
+/// Database model for `inbox_conversions` table
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct InboxConversions {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub inbox_item_id: Uuid,
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
 /// Database model for `inbox_items` table
@@ -744,6 +954,26 @@ pub struct InboxItems {
     pub processed_at: Option<chrono::DateTime<chrono::Utc>>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub body: Option<String>,
+    pub action_url: Option<String>,
+    pub action_data: Option<serde_json::Value>,
+    pub priority: i32,
+    pub is_read: bool,
+    pub is_archived: bool,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Database model for `infobase_links` table
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct InfobaseLinks {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub source_id: Uuid,
+    pub target_type: String,
+    pub target_id: Option<Uuid>,
+    pub target_title: Option<String>,
+    pub raw: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
 // =============================================================================
@@ -760,6 +990,8 @@ pub struct OnboardingFlows {
     pub total_steps: i32,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub version: i32,
+    pub published_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 /// Database model for `onboarding_steps` table
@@ -781,12 +1013,60 @@ pub struct OnboardingSteps {
     pub action_config: Option<serde_json::Value>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub show_if: Option<serde_json::Value>,
 }
 
 // =============================================================================
 // ADMIN & PLATFORM
 // =============================================================================
 
+/// Database model for `account_archive_jobs` table
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct AccountArchiveJobs {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub status: String,
+    pub storage_key: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub summary: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub lease_owner: Option<Uuid>,
+    pub leased_until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Database model for `account_deletions` table
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct AccountDeletions {
+    pub user_id: Uuid,
+    pub status: String,
+    pub requested_by: Option<Uuid>,
+    pub requested_at: chrono::DateTime<chrono::Utc>,
+    pub purge_after: chrono::DateTime<chrono::Utc>,
+    pub attempts: i32,
+    pub leased_until: Option<chrono::DateTime<chrono::Utc>>,
+    pub objects_removed: i32,
+    pub rows_removed: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub cancelled_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub lease_owner: Option<Uuid>,
+}
+
+/// Database model for `admin_saved_queries` table
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct AdminSavedQueries {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub sql: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
 /// Database model for `audit_log` table
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct AuditLog {
@@ -820,6 +1100,16 @@ pub struct Feedback {
     pub metadata: Option<serde_json::Value>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub resolved_by: Option<Uuid>,
+}
+
+/// Database model for `rate_limit_buckets` table
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct RateLimitBuckets {
+    pub key: String,
+    pub tokens: f64,
+    pub allowed: bool,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
 // =============================================================================
@@ -899,6 +1189,7 @@ pub struct InfobaseEntries {
     pub is_pinned: bool,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub version: i32,
 }
 
 /// Database model for `learn_drills` table
@@ -976,16 +1267,6 @@ pub struct MarketTransactions {
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
-/// Database model for `oauth_states` table
-#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
-pub struct OauthStates {
-    pub state_key: String,
-    pub pkce_verifier: String,
-    pub redirect_uri: Option<String>,
-    pub created_at: chrono::DateTime<chrono::Utc>,
-    pub expires_at: chrono::DateTime<chrono::Utc>,
-}
-
 /// Database model for `plan_templates` table
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct PlanTemplates {
@@ -1044,7 +1325,7 @@ pub struct ProgramWorkouts {
 pub struct ReferenceTracks {
     pub id: Uuid,
     pub user_id: Uuid,
-    pub name: String,
+    pub title: String,
     pub description: Option<String>,
     pub r2_key: String,
     pub file_size_bytes: i64,
@@ -1054,12 +1335,23 @@ pub struct ReferenceTracks {
     pub album: Option<String>,
     pub genre: Option<String>,
     pub bpm: Option<f32>,
-    pub key_signature: Option<String>,
+    pub key: Option<String>,
     pub tags: Option<Vec<String>>,
     pub status: String,
     pub error_message: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub waveform_r2_key: Option<String>,
+    pub thumbnail_r2_key: Option<String>,
+    pub file_format: Option<String>,
+    pub sample_rate: Option<i32>,
+    pub bit_depth: Option<i32>,
+    pub channels: Option<i32>,
+    pub is_reference: bool,
+    pub is_user_upload: bool,
+    pub source: Option<String>,
+    pub source_url: Option<String>,
+    pub metadata: Option<serde_json::Value>,
 }
 
 /// Database model for `track_analyses` table
@@ -1077,6 +1369,8 @@ pub struct TrackAnalyses {
     pub manifest: Option<serde_json::Value>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub parameters: Option<serde_json::Value>,
+    pub results: Option<serde_json::Value>,
 }
 
 /// Database model for `track_annotations` table
@@ -1085,15 +1379,16 @@ pub struct TrackAnnotations {
     pub id: Uuid,
     pub track_id: Uuid,
     pub user_id: Uuid,
-    pub start_time_ms: i32,
-    pub end_time_ms: Option<i32>,
-    pub title: String,
+    pub title: Option<String>,
     pub content: Option<String>,
-    pub category: Option<String>,
+    pub annotation_type: String,
     pub color: Option<String>,
     pub is_private: bool,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub start_time_seconds: f32,
+    pub end_time_seconds: Option<f32>,
+    pub tags: Option<Vec<String>>,
 }
 
 /// Database model for `track_regions` table
@@ -1102,15 +1397,17 @@ pub struct TrackRegions {
     pub id: Uuid,
     pub track_id: Uuid,
     pub user_id: Uuid,
-    pub start_time_ms: i32,
-    pub end_time_ms: i32,
     pub name: String,
-    pub description: Option<String>,
-    pub section_type: Option<String>,
+    pub notes: Option<String>,
+    pub region_type: Option<String>,
     pub color: Option<String>,
     pub display_order: i32,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub start_time_seconds: f32,
+    pub end_time_seconds: f32,
+    pub loop_count: i32,
+    pub is_favorite: bool,
 }
 
 /// Database model for `training_programs` table
@@ -1274,15 +1571,22 @@ pub struct Workouts {
 // TYPE ALIASES
 // =============================================================================
 
+pub type AccountArchiveJob = AccountArchiveJobs;
+pub type AccountDeletion = AccountDeletions;
 pub type Account = Accounts;
 pub type AchievementDefinition = AchievementDefinitions;
 pub type ActivityEvent = ActivityEvents;
+pub type AdminSavedQuery = AdminSavedQueries;
 pub type AnalysisEvent = AnalysisEvents;
 pub type AnalysisFrameManifest = AnalysisFrameManifests;
+pub type ApiToken = ApiTokens;
 pub type Authenticator = Authenticators;
 pub type Book = Books;
 pub type CalendarEvent = CalendarEvents;
+pub type ContentRevision = ContentRevisions;
 pub type DailyPlan = DailyPlans;
+pub type DrillSessionAnswer = DrillSessionAnswers;
+pub type DrillSession = DrillSessions;
 pub type Entitlement = Entitlements;
 pub type ExerciseSet = ExerciseSets;
 pub type Exercise = Exercises;
@@ -1290,13 +1594,17 @@ pub type FeatureFlag = FeatureFlags;
 pub type FocusLibrary = FocusLibraries;
 pub type FocusLibraryTrack = FocusLibraryTracks;
 pub type FocusSession = FocusSessions;
+pub type GoalLink = GoalLinks;
 pub type GoalMileston = GoalMilestones;
 pub type Goal = Goals;
 pub type HabitCompletion = HabitCompletions;
 pub type Habit = Habits;
+pub type IdeaAttachment = IdeaAttachments;
 pub type Idea = Ideas;
+pub type InboxConversion = InboxConversions;
 pub type InboxItem = InboxItems;
 pub type InfobaseEntry = InfobaseEntries;
+pub type InfobaseLink = InfobaseLinks;
 pub type LearnDrill = LearnDrills;
 pub type LearnLesson = LearnLessons;
 pub type LearnTopic = LearnTopics;
@@ -1305,6 +1613,8 @@ pub type ListeningPromptTemplat = ListeningPromptTemplates;
 pub type MarketItem = MarketItems;
 pub type MarketRecommendation = MarketRecommendations;
 pub type MarketTransaction = MarketTransactions;
+pub type NotificationDelivery = NotificationDeliveries;
+pub type NotificationSetting = NotificationSettings;
 pub type OauthStat = OauthStates;
 pub type OnboardingFlow = OnboardingFlows;
 pub type OnboardingStep = OnboardingSteps;
@@ -1312,12 +1622,15 @@ pub type PersonalRecord = PersonalRecords;
 pub type PlanTemplat = PlanTemplates;
 pub type ProgramWeek = ProgramWeeks;
 pub type ProgramWorkout = ProgramWorkouts;
+pub type PushSubscription = PushSubscriptions;
+pub type RateLimitBucket = RateLimitBuckets;
 pub type ReadingSession = ReadingSessions;
 pub type ReferenceTrack = ReferenceTracks;
 pub type RoleEntitlement = RoleEntitlements;
 pub type Rol = Roles;
 pub type Session = Sessions;
 pub type SkillDefinition = SkillDefinitions;
+pub type SyncEvent = SyncEvents;
 pub type TrackAnalyse = TrackAnalyses;
 pub type TrackAnnotation = TrackAnnotations;
 pub type TrackRegion = TrackRegions;
@@ -1337,10 +1650,11 @@ pub type UserSkill = UserSkills;
 pub type UserStreak = UserStreaks;
 pub type User = Users;
 pub type VerificationToken = VerificationTokens;
+pub type WebauthnChalleng = WebauthnChallenges;
 pub type WorkoutExercise = WorkoutExercises;
 pub type WorkoutSection = WorkoutSections;
 pub type WorkoutSession = WorkoutSessions;
 pub type Workout = Workouts;
 
 /// Schema version
-pub const SCHEMA_VERSION: &str = "2.0.0";
+pub const SCHEMA_VERSION: &str = "2.1.0";
@@ -2,11 +2,15 @@
 //!
 //! Models for habit tracking and goal management.
 
-use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::shared::serde_helpers::double_option;
+
 // ============================================================================
 // HABITS
 // ============================================================================
@@ -65,6 +69,185 @@ fn default_target() -> i32 {
     1
 }
 
+/// Habit frequencies
+///
+/// - `daily`: `target_count` completions every day
+/// - `custom`: `target_count` completions on the weekdays in `custom_days`
+/// - `weekly`: completions on `target_count` different days each week
+pub const HABIT_FREQUENCIES: &[&str] = &["daily", "custom", "weekly"];
+
+/// Most completions a day can ask for
+pub const MAX_HABIT_TARGET: i32 = 20;
+
+/// Longest range the history endpoint returns, and its default
+pub const MAX_HISTORY_DAYS: i64 = 366;
+pub const DEFAULT_HISTORY_DAYS: i64 = 365;
+
+/// When a habit is due and how much counts as done
+///
+/// Weekdays are numbered from 0 for Sunday through 6 for Saturday, and
+/// weeks run Monday to Sunday.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HabitSchedule {
+    /// `times` completions every day
+    Daily { times: i64 },
+    /// `times` completions on each of `days`
+    Weekdays { days: Vec<u32>, times: i64 },
+    /// Completions on `days` different days a week
+    Weekly { days: i64 },
+}
+
+impl HabitSchedule {
+    /// Any unknown frequency is treated as daily
+    pub fn new(frequency: &str, target_count: i32, custom_days: Option<&[i32]>) -> Self {
+        let target = i64::from(target_count.max(1));
+        match frequency {
+            "weekly" => HabitSchedule::Weekly {
+                days: target.min(7),
+            },
+            "custom" => HabitSchedule::Weekdays {
+                days: custom_days
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|d| u32::try_from(*d).ok())
+                    .filter(|d| *d < 7)
+                    .collect(),
+                times: target,
+            },
+            _ => HabitSchedule::Daily { times: target },
+        }
+    }
+
+    pub fn of(habit: &Habit) -> Self {
+        Self::new(
+            &habit.frequency,
+            habit.target_count,
+            habit.custom_days.as_deref(),
+        )
+    }
+
+    /// Whether `date` asks for anything; every day can count toward a week
+    pub fn is_due(&self, date: NaiveDate) -> bool {
+        match self {
+            HabitSchedule::Weekdays { days, .. } => {
+                days.contains(&date.weekday().num_days_from_sunday())
+            }
+            _ => true,
+        }
+    }
+
+    /// Completions that count on one day; more are refused
+    pub fn daily_cap(&self) -> i64 {
+        match self {
+            HabitSchedule::Daily { times } | HabitSchedule::Weekdays { times, .. } => *times,
+            HabitSchedule::Weekly { .. } => 1,
+        }
+    }
+
+    /// Completions still wanted on `date`; 0 when it is not due
+    pub fn remaining_on(&self, date: NaiveDate, done_today: i64, done_this_week: i64) -> i64 {
+        match self {
+            _ if !self.is_due(date) => 0,
+            HabitSchedule::Weekly { .. } if done_today > 0 => 0,
+            HabitSchedule::Weekly { days } => (days - done_this_week).max(0),
+            _ => (self.daily_cap() - done_today).max(0),
+        }
+    }
+
+    /// Streaks in due days met, or weeks met for a weekly habit
+    ///
+    /// `counts` holds completions per date. The current day or week only
+    /// adds to the streak once it is met; until then it doesn't break it.
+    pub fn streaks(&self, counts: &BTreeMap<NaiveDate, i64>, today: NaiveDate) -> HabitStreaks {
+        let Some(first) = counts.keys().next().copied().filter(|d| *d <= today) else {
+            return HabitStreaks::default();
+        };
+        let count = |date: NaiveDate| counts.get(&date).copied().unwrap_or(0);
+
+        let mut periods = Vec::new();
+        match self {
+            HabitSchedule::Weekly { days } => {
+                let mut monday = week_start(first);
+                while monday <= today {
+                    let done_days =
+                        monday.iter_days().take(7).filter(|d| count(*d) > 0).count() as i64;
+                    let next = monday + Duration::days(7);
+                    periods.push((done_days >= *days, today < next));
+                    monday = next;
+                }
+            }
+            _ => {
+                for date in first.iter_days().take_while(|d| *d <= today) {
+                    if self.is_due(date) {
+                        periods.push((count(date) >= self.daily_cap(), date == today));
+                    }
+                }
+            }
+        }
+
+        let mut streaks = HabitStreaks::default();
+        for (met, in_progress) in periods {
+            if met {
+                streaks.current += 1;
+                streaks.longest = streaks.longest.max(streaks.current);
+            } else if !in_progress {
+                streaks.current = 0;
+            }
+        }
+        streaks
+    }
+
+    /// Each day from `from` to `to` with its completions
+    pub fn history(
+        &self,
+        counts: &BTreeMap<NaiveDate, i64>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Vec<HabitHistoryDay> {
+        from.iter_days()
+            .take_while(|d| *d <= to)
+            .map(|date| {
+                let count = counts.get(&date).copied().unwrap_or(0);
+                HabitHistoryDay {
+                    date,
+                    count,
+                    due: self.is_due(date),
+                    done: count >= self.daily_cap(),
+                }
+            })
+            .collect()
+    }
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(i64::from(date.weekday().num_days_from_monday()))
+}
+
+/// Current and longest streak of a habit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HabitStreaks {
+    pub current: i32,
+    pub longest: i32,
+}
+
+/// Update habit request; omitted fields are left unchanged
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateHabitRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub frequency: Option<String>,
+    pub target_count: Option<i32>,
+    pub custom_days: Option<Vec<i32>>,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+}
+
+/// Reorder habits request; habits are sorted in the order given
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReorderHabitsRequest {
+    pub ids: Vec<Uuid>,
+}
+
 /// Habit response with today's status
 #[derive(Debug, Clone, Serialize)]
 pub struct HabitResponse {
@@ -73,16 +256,44 @@ pub struct HabitResponse {
     pub description: Option<String>,
     pub frequency: String,
     pub target_count: i32,
+    pub custom_days: Option<Vec<i32>>,
     pub icon: Option<String>,
     pub color: Option<String>,
     pub is_active: bool,
     pub current_streak: i32,
     pub longest_streak: i32,
     pub last_completed_at: Option<DateTime<Utc>>,
+    /// Whether today has all the completions it can take
     pub completed_today: bool,
+    pub completions_today: i64,
+    pub due_today: bool,
     pub sort_order: i32,
 }
 
+impl HabitResponse {
+    pub fn new(habit: Habit, completions_today: i64, today: NaiveDate) -> Self {
+        let schedule = HabitSchedule::of(&habit);
+        Self {
+            completed_today: completions_today >= schedule.daily_cap(),
+            due_today: schedule.is_due(today),
+            completions_today,
+            id: habit.id,
+            name: habit.name,
+            description: habit.description,
+            frequency: habit.frequency,
+            target_count: habit.target_count,
+            custom_days: habit.custom_days,
+            icon: habit.icon,
+            color: habit.color,
+            is_active: habit.is_active,
+            current_streak: habit.current_streak,
+            longest_streak: habit.longest_streak,
+            last_completed_at: habit.last_completed_at,
+            sort_order: habit.sort_order,
+        }
+    }
+}
+
 /// Complete habit result
 #[derive(Debug, Clone, Serialize)]
 pub struct CompleteHabitResult {
@@ -98,6 +309,28 @@ pub struct HabitsListResponse {
     pub habits: Vec<HabitResponse>,
 }
 
+/// A day in a habit's history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HabitHistoryDay {
+    pub date: NaiveDate,
+    pub count: i64,
+    pub due: bool,
+    /// Whether the day had all the completions it can take
+    pub done: bool,
+}
+
+/// Habit history response, one entry per day for a heatmap
+#[derive(Debug, Clone, Serialize)]
+pub struct HabitHistoryResponse {
+    pub habit_id: Uuid,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub current_streak: i32,
+    pub longest_streak: i32,
+    pub total_completions: i64,
+    pub days: Vec<HabitHistoryDay>,
+}
+
 // ============================================================================
 // GOALS
 // ============================================================================
@@ -138,6 +371,8 @@ pub struct Goal {
     pub progress: i32,
     pub priority: i32,
     pub sort_order: i32,
+    pub at_risk: bool,
+    pub archived_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -152,6 +387,86 @@ pub struct GoalMilestone {
     pub is_completed: bool,
     pub completed_at: Option<DateTime<Utc>>,
     pub sort_order: i32,
+    pub weight: i32,
+}
+
+/// What a goal can be linked to
+///
+/// - `habit`: completions since the goal started, out of the link's `target`
+/// - `quest`: the quest's own progress toward its target
+/// - `book`: pages read
+pub const GOAL_LINK_TYPES: &[&str] = &["habit", "quest", "book"];
+
+/// Heaviest a milestone or link can weigh
+pub const MAX_GOAL_WEIGHT: i32 = 100;
+
+/// A habit, quest or book counting toward a goal
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct GoalLink {
+    pub id: Uuid,
+    pub goal_id: Uuid,
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub weight: i32,
+    pub target: Option<i32>,
+    /// `None` once the linked item is gone
+    pub title: Option<String>,
+    /// Percent done, 0 to 100
+    pub progress: i32,
+}
+
+/// Progress from a goal's milestones and links, averaged by weight; `None`
+/// when it has neither and progress is set by hand. Links to items that are
+/// gone don't count.
+pub fn derived_progress(milestones: &[GoalMilestone], links: &[GoalLink]) -> Option<i32> {
+    let parts = milestones
+        .iter()
+        .map(|m| (m.weight, if m.is_completed { 100 } else { 0 }))
+        .chain(
+            links
+                .iter()
+                .filter(|l| l.title.is_some())
+                .map(|l| (l.weight, l.progress.clamp(0, 100))),
+        );
+    let (weight, done) = parts.fold((0i64, 0i64), |(weight, done), (w, p)| {
+        (weight + i64::from(w), done + i64::from(w) * i64::from(p))
+    });
+    (weight > 0).then(|| (done / weight) as i32)
+}
+
+/// Status once progress moves from `previous` to `progress`: an active goal
+/// completes when it reaches 100%, and a completed one that drops back below
+/// is active again
+pub fn status_for_progress(status: &str, previous: i32, progress: i32) -> &str {
+    match status {
+        "active" if previous < 100 && progress >= 100 => "completed",
+        "completed" if previous >= 100 && progress < 100 => "active",
+        _ => status,
+    }
+}
+
+/// Whether an active goal is falling behind its target date: it's overdue,
+/// or at its pace since it started it would finish late. A goal with no
+/// progress yet is at risk once a quarter of its time has gone.
+pub fn goal_at_risk(goal: &Goal, status: &str, progress: i32, today: NaiveDate) -> bool {
+    let Some(target_date) = goal.target_date else {
+        return false;
+    };
+    if status != "active" || goal.archived_at.is_some() || progress >= 100 {
+        return false;
+    }
+    if today > target_date {
+        return true;
+    }
+
+    let started = goal.started_at.unwrap_or(goal.created_at).date_naive();
+    let total = (target_date - started).num_days().max(1);
+    let elapsed = (today - started).num_days().max(0);
+    if progress <= 0 {
+        return elapsed * 4 >= total;
+    }
+    // At this pace the goal takes elapsed * 100 / progress days
+    elapsed * 100 > total * i64::from(progress)
 }
 
 /// Create goal request
@@ -164,14 +479,54 @@ pub struct CreateGoalRequest {
     pub priority: Option<i32>,
 }
 
+/// Update goal request; omitted fields are left unchanged and a `null`
+/// `target_date` clears it
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateGoalRequest {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub target_date: Option<Option<NaiveDate>>,
+    pub priority: Option<i32>,
+    pub status: Option<GoalStatus>,
+    /// Only for goals without milestones or links
+    pub progress: Option<i32>,
+}
+
+/// Reorder goals request; goals are sorted in the order given
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReorderGoalsRequest {
+    pub ids: Vec<Uuid>,
+}
+
 /// Create milestone request
 #[derive(Debug, Clone, Deserialize)]
 pub struct CreateMilestoneRequest {
     pub title: String,
     pub description: Option<String>,
+    pub weight: Option<i32>,
 }
 
-/// Goal response with milestones
+/// Update milestone request; omitted fields are left unchanged
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateMilestoneRequest {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub weight: Option<i32>,
+}
+
+/// Link a habit, quest or book to a goal
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateGoalLinkRequest {
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub weight: Option<i32>,
+    /// Completions a linked habit counts toward; required for habits only
+    pub target: Option<i32>,
+}
+
+/// Goal response with milestones and links
 #[derive(Debug, Clone, Serialize)]
 pub struct GoalResponse {
     pub id: Uuid,
@@ -183,10 +538,41 @@ pub struct GoalResponse {
     pub completed_at: Option<DateTime<Utc>>,
     pub status: String,
     pub progress: i32,
+    /// Whether progress comes from milestones and links rather than by hand
+    pub auto_progress: bool,
+    pub at_risk: bool,
     pub priority: i32,
+    pub sort_order: i32,
+    pub archived_at: Option<DateTime<Utc>>,
     pub milestones: Vec<GoalMilestone>,
     pub total_milestones: i32,
     pub completed_milestones: i32,
+    pub links: Vec<GoalLink>,
+}
+
+impl GoalResponse {
+    pub fn new(goal: Goal, milestones: Vec<GoalMilestone>, links: Vec<GoalLink>) -> Self {
+        Self {
+            auto_progress: derived_progress(&milestones, &links).is_some(),
+            total_milestones: milestones.len() as i32,
+            completed_milestones: milestones.iter().filter(|m| m.is_completed).count() as i32,
+            id: goal.id,
+            title: goal.title,
+            description: goal.description,
+            category: goal.category,
+            target_date: goal.target_date,
+            started_at: goal.started_at,
+            completed_at: goal.completed_at,
+            status: goal.status,
+            progress: goal.progress,
+            at_risk: goal.at_risk,
+            priority: goal.priority,
+            sort_order: goal.sort_order,
+            archived_at: goal.archived_at,
+            milestones,
+            links,
+        }
+    }
 }
 
 /// Goals list response
@@ -203,3 +589,230 @@ pub struct CompleteMilestoneResult {
     pub goal_progress: i32,
     pub goal_completed: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap()
+    }
+
+    fn counts(days: &[(u32, i64)]) -> BTreeMap<NaiveDate, i64> {
+        days.iter().map(|(d, n)| (date(*d), *n)).collect()
+    }
+
+    #[test]
+    fn test_remaining_on() {
+        // 2026-03-11 is a Wednesday
+        let wednesday = date(11);
+        let daily = HabitSchedule::new("daily", 3, None);
+        assert_eq!(daily.remaining_on(wednesday, 1, 4), 2);
+        let daily = HabitSchedule::new("daily", 1, None);
+        assert_eq!(daily.remaining_on(wednesday, 2, 2), 0);
+
+        let mwf = HabitSchedule::new("custom", 1, Some(&[1, 3, 5]));
+        assert_eq!(mwf.remaining_on(wednesday, 0, 0), 1);
+        assert_eq!(mwf.remaining_on(date(12), 0, 1), 0);
+        let never = HabitSchedule::new("custom", 1, None);
+        assert_eq!(never.remaining_on(wednesday, 0, 0), 0);
+
+        let weekly = HabitSchedule::new("weekly", 3, None);
+        assert_eq!(weekly.remaining_on(wednesday, 0, 1), 2);
+        assert_eq!(weekly.remaining_on(wednesday, 1, 2), 0);
+        assert_eq!(weekly.remaining_on(wednesday, 0, 3), 0);
+        assert_eq!(weekly.daily_cap(), 1);
+    }
+
+    #[test]
+    fn test_weekday_streak_skips_days_off() {
+        // Mon/Wed/Fri, done on each since Monday the 2nd, Wednesday the 11th
+        // still to do
+        let mwf = HabitSchedule::new("custom", 1, Some(&[1, 3, 5]));
+        let done = counts(&[(2, 1), (4, 1), (6, 1), (9, 1)]);
+        let streaks = mwf.streaks(&done, date(11));
+        assert_eq!(
+            streaks,
+            HabitStreaks {
+                current: 4,
+                longest: 4
+            }
+        );
+
+        // Missing Wednesday breaks it once Thursday comes
+        let streaks = mwf.streaks(&done, date(12));
+        assert_eq!(
+            streaks,
+            HabitStreaks {
+                current: 0,
+                longest: 4
+            }
+        );
+
+        // A day off doesn't count even with a completion
+        let done = counts(&[(2, 1), (3, 1), (4, 1)]);
+        assert_eq!(mwf.streaks(&done, date(4)).current, 2);
+    }
+
+    #[test]
+    fn test_times_per_day_streak() {
+        let twice = HabitSchedule::new("daily", 2, None);
+        let done = counts(&[(9, 2), (10, 1), (11, 2), (12, 2), (13, 1)]);
+        let streaks = twice.streaks(&done, date(13));
+        assert_eq!(
+            streaks,
+            HabitStreaks {
+                current: 2,
+                longest: 2
+            }
+        );
+        assert_eq!(twice.streaks(&done, date(14)).current, 0);
+        assert_eq!(
+            twice.streaks(&BTreeMap::new(), date(14)),
+            HabitStreaks::default()
+        );
+    }
+
+    #[test]
+    fn test_weekly_streak_counts_weeks() {
+        // Three days a week: met the weeks of the 2nd and 9th, the week of
+        // the 16th under way
+        let weekly = HabitSchedule::new("weekly", 3, None);
+        let done = counts(&[(2, 1), (3, 2), (8, 1), (9, 1), (11, 1), (13, 1), (16, 1)]);
+        assert_eq!(
+            weekly.streaks(&done, date(18)),
+            HabitStreaks {
+                current: 2,
+                longest: 2
+            }
+        );
+        // The week of the 16th ended short
+        assert_eq!(weekly.streaks(&done, date(23)).current, 0);
+    }
+
+    #[test]
+    fn test_history() {
+        let mwf = HabitSchedule::new("custom", 2, Some(&[1, 3, 5]));
+        let days = mwf.history(&counts(&[(9, 2), (10, 1)]), date(9), date(11));
+        assert_eq!(
+            days,
+            vec![
+                HabitHistoryDay {
+                    date: date(9),
+                    count: 2,
+                    due: true,
+                    done: true
+                },
+                HabitHistoryDay {
+                    date: date(10),
+                    count: 1,
+                    due: false,
+                    done: false
+                },
+                HabitHistoryDay {
+                    date: date(11),
+                    count: 0,
+                    due: true,
+                    done: false
+                },
+            ]
+        );
+    }
+
+    fn milestone(weight: i32, is_completed: bool) -> GoalMilestone {
+        GoalMilestone {
+            id: Uuid::new_v4(),
+            goal_id: Uuid::nil(),
+            title: "Milestone".to_string(),
+            description: None,
+            is_completed,
+            completed_at: None,
+            sort_order: 0,
+            weight,
+        }
+    }
+
+    fn link(weight: i32, progress: i32, title: Option<&str>) -> GoalLink {
+        GoalLink {
+            id: Uuid::new_v4(),
+            goal_id: Uuid::nil(),
+            target_type: "book".to_string(),
+            target_id: Uuid::new_v4(),
+            weight,
+            target: None,
+            title: title.map(str::to_string),
+            progress,
+        }
+    }
+
+    fn goal(started: u32, target: Option<u32>) -> Goal {
+        let started_at = date(started).and_hms_opt(9, 0, 0).unwrap().and_utc();
+        Goal {
+            id: Uuid::nil(),
+            user_id: Uuid::nil(),
+            title: "Goal".to_string(),
+            description: None,
+            category: None,
+            target_date: target.map(date),
+            started_at: Some(started_at),
+            completed_at: None,
+            status: "active".to_string(),
+            progress: 0,
+            priority: 0,
+            sort_order: 0,
+            at_risk: false,
+            archived_at: None,
+            created_at: started_at,
+            updated_at: started_at,
+        }
+    }
+
+    #[test]
+    fn test_derived_progress_is_weighted() {
+        assert_eq!(derived_progress(&[], &[]), None);
+
+        // 3 of 4 parts by weight
+        let milestones = [milestone(3, true), milestone(1, false)];
+        assert_eq!(derived_progress(&milestones, &[]), Some(75));
+
+        // A half-read book weighing as much as both milestones
+        let links = [link(4, 50, Some("Dune"))];
+        assert_eq!(derived_progress(&milestones, &links), Some(62));
+
+        // Links to deleted items are left out, and 100 needs everything done
+        let links = [link(4, 100, Some("Dune")), link(10, 0, None)];
+        assert_eq!(derived_progress(&milestones, &links), Some(87));
+        assert_eq!(derived_progress(&[], &[link(1, 0, None)]), None);
+    }
+
+    #[test]
+    fn test_status_for_progress() {
+        assert_eq!(status_for_progress("active", 80, 100), "completed");
+        assert_eq!(status_for_progress("completed", 100, 90), "active");
+        // Reopened by hand at 100% stays open until progress changes again
+        assert_eq!(status_for_progress("active", 100, 100), "active");
+        // Completed by hand short of 100% stays completed
+        assert_eq!(status_for_progress("completed", 60, 60), "completed");
+        assert_eq!(status_for_progress("paused", 90, 100), "paused");
+    }
+
+    #[test]
+    fn test_goal_at_risk() {
+        // 20 days from the 1st to the 21st
+        let g = goal(1, Some(21));
+        assert!(!goal_at_risk(&goal(1, None), "active", 0, date(30)));
+
+        // No progress: fine for the first quarter of the time
+        assert!(!goal_at_risk(&g, "active", 0, date(5)));
+        assert!(goal_at_risk(&g, "active", 0, date(6)));
+
+        // Half the time gone: on pace at half done, behind at 40%
+        assert!(!goal_at_risk(&g, "active", 50, date(11)));
+        assert!(goal_at_risk(&g, "active", 40, date(11)));
+
+        // Overdue, unless done or not active
+        assert!(goal_at_risk(&g, "active", 95, date(22)));
+        assert!(!goal_at_risk(&g, "active", 100, date(22)));
+        assert!(!goal_at_risk(&g, "paused", 10, date(22)));
+    }
+}
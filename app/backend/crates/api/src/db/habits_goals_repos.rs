@@ -2,8 +2,10 @@
 //!
 //! Database operations for habit tracking and goal management.
 
+use std::collections::{BTreeMap, HashMap};
+
 use chrono::{NaiveDate, Utc};
-use sqlx::PgPool;
+use sqlx::{Acquire, PgConnection, PgExecutor, PgPool, Postgres};
 use uuid::Uuid;
 
 use super::gamification_models::AwardPointsInput;
@@ -17,30 +19,184 @@ use crate::error::AppError;
 
 pub struct HabitsRepo;
 
+pub const HABITS_CREATE: &str = r#"
+    INSERT INTO habits (user_id, name, description, frequency, target_count, custom_days, icon, color,
+                        is_active, current_streak, longest_streak, sort_order)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, true, 0, 0,
+            (SELECT COALESCE(MAX(sort_order) + 1, 0) FROM habits WHERE user_id = $1))
+    RETURNING id, user_id, name, description, frequency, target_count, custom_days,
+              icon, color, is_active, current_streak, longest_streak,
+              last_completed_at, sort_order, created_at, updated_at
+"#;
+
+pub const HABITS_GET_BY_ID: &str = r#"
+    SELECT id, user_id, name, description, frequency, target_count, custom_days,
+           icon, color, is_active, current_streak, longest_streak,
+           last_completed_at, sort_order, created_at, updated_at
+    FROM habits WHERE id = $1 AND user_id = $2
+"#;
+
+pub const HABITS_GET_FOR_UPDATE: &str = r#"
+    SELECT id, user_id, name, description, frequency, target_count, custom_days,
+           icon, color, is_active, current_streak, longest_streak,
+           last_completed_at, sort_order, created_at, updated_at
+    FROM habits WHERE id = $1 AND user_id = $2
+    FOR UPDATE
+"#;
+
+pub const HABITS_LIST: &str = r#"
+    SELECT id, user_id, name, description, frequency, target_count, custom_days,
+           icon, color, is_active, current_streak, longest_streak,
+           last_completed_at, sort_order, created_at, updated_at
+    FROM habits
+    WHERE user_id = $1 AND is_active = $2
+    ORDER BY sort_order, name
+"#;
+
+pub const HABITS_COMPLETIONS_ON: &str = r#"
+    SELECT habit_id, COUNT(*) FROM habit_completions
+    WHERE user_id = $1 AND completed_date = $2
+    GROUP BY habit_id
+"#;
+
+pub const HABITS_COMPLETION_COUNTS: &str = r#"
+    SELECT completed_date, COUNT(*) FROM habit_completions
+    WHERE habit_id = $1
+    GROUP BY completed_date
+    ORDER BY completed_date
+"#;
+
+pub const HABITS_COMPLETE_HABIT_INSERT_HABIT_COMPLETIONS: &str = r#"
+    INSERT INTO habit_completions (habit_id, user_id, completed_date, notes)
+    VALUES ($1, $2, $3, $4)
+"#;
+
+/// Remove the latest completion on a date
+pub const HABITS_UNCOMPLETE: &str = r#"
+    DELETE FROM habit_completions
+    WHERE id = (
+        SELECT id FROM habit_completions
+        WHERE habit_id = $1 AND completed_date = $2
+        ORDER BY completed_at DESC, id DESC
+        LIMIT 1
+    )
+"#;
+
+pub const HABITS_UPDATE_STREAKS: &str = r#"
+    UPDATE habits
+    SET current_streak = $2,
+        longest_streak = $3,
+        last_completed_at = (SELECT MAX(completed_at) FROM habit_completions WHERE habit_id = $1),
+        updated_at = NOW()
+    WHERE id = $1
+    RETURNING id, user_id, name, description, frequency, target_count, custom_days,
+              icon, color, is_active, current_streak, longest_streak,
+              last_completed_at, sort_order, created_at, updated_at
+"#;
+
+pub const HABITS_UPDATE: &str = r#"
+    UPDATE habits
+    SET name = $3, description = $4, frequency = $5, target_count = $6,
+        custom_days = $7, icon = $8, color = $9, updated_at = NOW()
+    WHERE id = $1 AND user_id = $2
+"#;
+
+pub const HABITS_SET_ACTIVE: &str = r#"
+    UPDATE habits SET is_active = $3, updated_at = NOW()
+    WHERE id = $1 AND user_id = $2
+    RETURNING id, user_id, name, description, frequency, target_count, custom_days,
+              icon, color, is_active, current_streak, longest_streak,
+              last_completed_at, sort_order, created_at, updated_at
+"#;
+
+pub const HABITS_DELETE_COMPLETIONS: &str =
+    "DELETE FROM habit_completions WHERE habit_id = $1 AND user_id = $2";
+
+pub const HABITS_DELETE_UNLINK_EVENTS: &str =
+    "UPDATE calendar_events SET habit_id = NULL WHERE habit_id = $1 AND user_id = $2";
+
+pub const HABITS_DELETE: &str = "DELETE FROM habits WHERE id = $1 AND user_id = $2";
+
+/// Sort the given habits in the order given; returns how many were the user's
+pub const HABITS_REORDER: &str = r#"
+    UPDATE habits h
+    SET sort_order = o.position - 1, updated_at = NOW()
+    FROM unnest($2::uuid[]) WITH ORDINALITY AS o(id, position)
+    WHERE h.id = o.id AND h.user_id = $1
+"#;
+
+/// Streak lengths that earn bonus XP when reached
+const STREAK_MILESTONES: &[i32] = &[7, 14, 30, 60, 100, 365];
+
+/// Validate a habit's name; creates from other features go through this too
+fn checked_name(name: &str) -> Result<(), AppError> {
+    if name.trim().is_empty() {
+        return Err(AppError::Validation("Habit name is required".into()));
+    }
+    Ok(())
+}
+
+/// Validate a habit's schedule, returning its weekdays sorted; only custom
+/// habits keep them
+fn checked_schedule(
+    frequency: &str,
+    target_count: i32,
+    custom_days: Option<&[i32]>,
+) -> Result<Option<Vec<i32>>, AppError> {
+    if !HABIT_FREQUENCIES.contains(&frequency) {
+        return Err(AppError::Validation(format!(
+            "Frequency must be one of {}",
+            HABIT_FREQUENCIES.join(", ")
+        )));
+    }
+    let max_target = if frequency == "weekly" {
+        7
+    } else {
+        MAX_HABIT_TARGET
+    };
+    if !(1..=max_target).contains(&target_count) {
+        return Err(AppError::Validation(format!(
+            "target_count must be between 1 and {} for a {} habit",
+            max_target, frequency
+        )));
+    }
+    if frequency != "custom" {
+        return Ok(None);
+    }
+
+    let mut days = custom_days.unwrap_or_default().to_vec();
+    if days.is_empty() || days.iter().any(|d| !(0..=6).contains(d)) {
+        return Err(AppError::Validation(
+            "custom_days needs weekdays from 0 (Sunday) to 6 (Saturday)".into(),
+        ));
+    }
+    days.sort_unstable();
+    days.dedup();
+    Ok(Some(days))
+}
+
 impl HabitsRepo {
     /// Create a new habit
     pub async fn create(
-        pool: &PgPool,
+        db: impl PgExecutor<'_>,
         user_id: Uuid,
         req: &CreateHabitRequest,
     ) -> Result<Habit, AppError> {
-        let habit = sqlx::query_as::<_, Habit>(
-            r#"INSERT INTO habits (user_id, name, description, frequency, target_count, custom_days, icon, color)
-               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-               RETURNING id, user_id, name, description, frequency, target_count, custom_days,
-                         icon, color, is_active, current_streak, longest_streak,
-                         last_completed_at, sort_order, created_at, updated_at"#,
-        )
-        .bind(user_id)
-        .bind(&req.name)
-        .bind(&req.description)
-        .bind(&req.frequency)
-        .bind(req.target_count)
-        .bind(&req.custom_days)
-        .bind(&req.icon)
-        .bind(&req.color)
-        .fetch_one(pool)
-        .await?;
+        checked_name(&req.name)?;
+        let custom_days =
+            checked_schedule(&req.frequency, req.target_count, req.custom_days.as_deref())?;
+
+        let habit = sqlx::query_as::<_, Habit>(HABITS_CREATE)
+            .bind(user_id)
+            .bind(&req.name)
+            .bind(&req.description)
+            .bind(&req.frequency)
+            .bind(req.target_count)
+            .bind(&custom_days)
+            .bind(&req.icon)
+            .bind(&req.color)
+            .fetch_one(db)
+            .await?;
 
         Ok(habit)
     }
@@ -51,171 +207,171 @@ impl HabitsRepo {
         habit_id: Uuid,
         user_id: Uuid,
     ) -> Result<Option<Habit>, AppError> {
-        let habit = sqlx::query_as::<_, Habit>(
-            r#"SELECT id, user_id, name, description, frequency, target_count, custom_days,
-                      icon, color, is_active, current_streak, longest_streak,
-                      last_completed_at, sort_order, created_at, updated_at
-               FROM habits WHERE id = $1 AND user_id = $2"#,
-        )
-        .bind(habit_id)
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let habit = sqlx::query_as::<_, Habit>(HABITS_GET_BY_ID)
+            .bind(habit_id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
 
         Ok(habit)
     }
 
+    /// Get a habit with today's status
+    pub async fn get(
+        pool: &PgPool,
+        habit_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<HabitResponse, AppError> {
+        let habit = Self::get_by_id(pool, habit_id, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Habit not found".to_string()))?;
+        let today = Utc::now().date_naive();
+        let counts = Self::completion_counts(pool, habit_id).await?;
+
+        let done_today = counts.get(&today).copied().unwrap_or(0);
+        Ok(HabitResponse::new(habit, done_today, today))
+    }
+
     /// List active habits with today's completion status
     pub async fn list_active(pool: &PgPool, user_id: Uuid) -> Result<HabitsListResponse, AppError> {
+        Self::list(pool, user_id, true).await
+    }
+
+    /// List archived habits
+    pub async fn list_archived(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<HabitsListResponse, AppError> {
+        Self::list(pool, user_id, false).await
+    }
+
+    async fn list(
+        pool: &PgPool,
+        user_id: Uuid,
+        active: bool,
+    ) -> Result<HabitsListResponse, AppError> {
         let today = Utc::now().date_naive();
 
-        // Get habits with today's completion status
-        let habits = sqlx::query_as::<_, Habit>(
-            r#"SELECT id, user_id, name, description, frequency, target_count, custom_days,
-                      icon, color, is_active, current_streak, longest_streak,
-                      last_completed_at, sort_order, created_at, updated_at
-               FROM habits
-               WHERE user_id = $1 AND is_active = true
-               ORDER BY sort_order, name"#,
-        )
-        .bind(user_id)
-        .fetch_all(pool)
-        .await?;
+        let habits = sqlx::query_as::<_, Habit>(HABITS_LIST)
+            .bind(user_id)
+            .bind(active)
+            .fetch_all(pool)
+            .await?;
 
         // Get today's completions
-        let completions = sqlx::query_scalar::<_, Uuid>(
-            r#"SELECT habit_id FROM habit_completions
-               WHERE user_id = $1 AND completed_date = $2"#,
-        )
-        .bind(user_id)
-        .bind(today)
-        .fetch_all(pool)
-        .await?;
-
-        let completed_ids: std::collections::HashSet<_> = completions.into_iter().collect();
+        let completions: HashMap<Uuid, i64> =
+            sqlx::query_as::<_, (Uuid, i64)>(HABITS_COMPLETIONS_ON)
+                .bind(user_id)
+                .bind(today)
+                .fetch_all(pool)
+                .await?
+                .into_iter()
+                .collect();
 
         let responses = habits
             .into_iter()
-            .map(|h| HabitResponse {
-                completed_today: completed_ids.contains(&h.id),
-                id: h.id,
-                name: h.name,
-                description: h.description,
-                frequency: h.frequency,
-                target_count: h.target_count,
-                icon: h.icon,
-                color: h.color,
-                is_active: h.is_active,
-                current_streak: h.current_streak,
-                longest_streak: h.longest_streak,
-                last_completed_at: h.last_completed_at,
-                sort_order: h.sort_order,
+            .map(|h| {
+                let done_today = completions.get(&h.id).copied().unwrap_or(0);
+                HabitResponse::new(h, done_today, today)
             })
             .collect();
 
         Ok(HabitsListResponse { habits: responses })
     }
 
+    /// Completions per date over a habit's whole history
+    async fn completion_counts(
+        db: impl PgExecutor<'_>,
+        habit_id: Uuid,
+    ) -> Result<BTreeMap<NaiveDate, i64>, AppError> {
+        let counts = sqlx::query_as::<_, (NaiveDate, i64)>(HABITS_COMPLETION_COUNTS)
+            .bind(habit_id)
+            .fetch_all(db)
+            .await?;
+
+        Ok(counts.into_iter().collect())
+    }
+
+    /// Recompute a habit's streaks from its completions
+    async fn refresh_streaks(
+        conn: &mut PgConnection,
+        habit: &Habit,
+        today: NaiveDate,
+    ) -> Result<(Habit, i64), AppError> {
+        let counts = Self::completion_counts(&mut *conn, habit.id).await?;
+        let streaks = HabitSchedule::of(habit).streaks(&counts, today);
+
+        let updated = sqlx::query_as::<_, Habit>(HABITS_UPDATE_STREAKS)
+            .bind(habit.id)
+            .bind(streaks.current)
+            .bind(streaks.longest)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        let done_today = counts.get(&today).copied().unwrap_or(0);
+        Ok((updated, done_today))
+    }
+
     /// Complete a habit for today
+    ///
+    /// A day takes completions up to the habit's target for it (once for a
+    /// weekly habit); beyond that the current state comes back unchanged.
     pub async fn complete_habit(
         pool: &PgPool,
         habit_id: Uuid,
         user_id: Uuid,
         notes: Option<&str>,
     ) -> Result<CompleteHabitResult, AppError> {
-        let habit = Self::get_by_id(pool, habit_id, user_id).await?;
-        let habit = habit.ok_or_else(|| AppError::NotFound("Habit not found".to_string()))?;
-
         let today = Utc::now().date_naive();
+        let mut tx = pool.begin().await?;
 
-        // Check if already completed today
-        let already_completed = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM habit_completions WHERE habit_id = $1 AND completed_date = $2",
-        )
-        .bind(habit_id)
-        .bind(today)
-        .fetch_one(pool)
-        .await?;
+        let habit = sqlx::query_as::<_, Habit>(HABITS_GET_FOR_UPDATE)
+            .bind(habit_id)
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Habit not found".to_string()))?;
+        let previous_streak = habit.current_streak;
+
+        let counts = Self::completion_counts(&mut *tx, habit_id).await?;
+        let done_today = counts.get(&today).copied().unwrap_or(0);
 
-        if already_completed > 0 {
-            // Already completed, return current state
+        if done_today >= HabitSchedule::of(&habit).daily_cap() {
+            // Already done for today, return current state
             return Ok(CompleteHabitResult {
-                habit: HabitResponse {
-                    id: habit.id,
-                    name: habit.name,
-                    description: habit.description,
-                    frequency: habit.frequency,
-                    target_count: habit.target_count,
-                    icon: habit.icon,
-                    color: habit.color,
-                    is_active: habit.is_active,
-                    current_streak: habit.current_streak,
-                    longest_streak: habit.longest_streak,
-                    last_completed_at: habit.last_completed_at,
-                    completed_today: true,
-                    sort_order: habit.sort_order,
-                },
-                new_streak: habit.current_streak,
+                habit: HabitResponse::new(habit, done_today, today),
+                new_streak: previous_streak,
                 xp_awarded: 0,
                 streak_bonus: false,
             });
         }
 
-        // Get last completion date
-        let last_date = sqlx::query_scalar::<_, NaiveDate>(
-            r#"SELECT completed_date FROM habit_completions
-               WHERE habit_id = $1 ORDER BY completed_date DESC LIMIT 1"#,
-        )
-        .bind(habit_id)
-        .fetch_optional(pool)
-        .await?;
+        sqlx::query(HABITS_COMPLETE_HABIT_INSERT_HABIT_COMPLETIONS)
+            .bind(habit_id)
+            .bind(user_id)
+            .bind(today)
+            .bind(notes)
+            .execute(&mut *tx)
+            .await?;
 
-        // Calculate new streak
-        let yesterday = today.pred_opt().unwrap_or(today);
-        let new_streak = match last_date {
-            None => 1,
-            Some(last) if last == yesterday => habit.current_streak + 1,
-            Some(_) => 1, // Streak broken
-        };
+        let (updated, done_today) = Self::refresh_streaks(&mut tx, &habit, today).await?;
+        GoalsRepo::refresh_linked(&mut tx, user_id, "habit", habit_id).await?;
+        tx.commit().await?;
 
         // Calculate XP and streak bonus
+        let new_streak = updated.current_streak;
         let mut xp = 5;
-        let streak_bonus = matches!(new_streak, 7 | 14 | 30 | 60 | 100 | 365);
+        let streak_bonus = new_streak > previous_streak && STREAK_MILESTONES.contains(&new_streak);
         if streak_bonus {
             xp += new_streak;
         }
 
-        // Insert log
-        sqlx::query(
-            r#"INSERT INTO habit_completions (habit_id, user_id, completed_date, notes)
-               VALUES ($1, $2, $3, $4)"#,
-        )
-        .bind(habit_id)
-        .bind(user_id)
-        .bind(today)
-        .bind(notes)
-        .execute(pool)
-        .await?;
-
-        // Update habit
-        let updated = sqlx::query_as::<_, Habit>(
-            r#"UPDATE habits
-               SET current_streak = $1,
-                   longest_streak = GREATEST(longest_streak, $1),
-                   last_completed_at = NOW()
-               WHERE id = $2
-               RETURNING id, user_id, name, description, frequency, target_count, custom_days,
-                         icon, color, is_active, current_streak, longest_streak,
-                         last_completed_at, sort_order, created_at, updated_at"#,
-        )
-        .bind(new_streak)
-        .bind(habit_id)
-        .fetch_one(pool)
-        .await?;
-
-        // Award XP
-        let idempotency_key = format!("habit_complete_{}_{}", habit_id, today);
-        GamificationRepo::award_points(
+        // One award per completion slot, so undoing and redoing earns nothing
+        let idempotency_key = match done_today {
+            1 => format!("habit_complete_{}_{}", habit_id, today),
+            n => format!("habit_complete_{}_{}_{}", habit_id, today, n),
+        };
+        let award = GamificationRepo::award_points(
             pool,
             user_id,
             &AwardPointsInput {
@@ -227,29 +383,209 @@ impl HabitsRepo {
                 event_id: Some(habit_id),
                 reason: Some(format!("Completed habit: {}", updated.name)),
                 idempotency_key: Some(idempotency_key),
+                quantity: None,
             },
         )
         .await?;
 
         Ok(CompleteHabitResult {
-            habit: HabitResponse {
-                id: updated.id,
-                name: updated.name,
-                description: updated.description,
-                frequency: updated.frequency,
-                target_count: updated.target_count,
-                icon: updated.icon,
-                color: updated.color,
-                is_active: updated.is_active,
-                current_streak: updated.current_streak,
-                longest_streak: updated.longest_streak,
-                last_completed_at: updated.last_completed_at,
-                completed_today: true,
-                sort_order: updated.sort_order,
-            },
+            habit: HabitResponse::new(updated, done_today, today),
             new_streak,
-            xp_awarded: xp,
-            streak_bonus,
+            xp_awarded: if award.already_awarded { 0 } else { xp },
+            streak_bonus: streak_bonus && !award.already_awarded,
+        })
+    }
+
+    /// Undo the latest completion on `date` and recompute streaks
+    ///
+    /// XP already awarded is kept; completing again doesn't award it twice.
+    pub async fn uncomplete_habit(
+        pool: &PgPool,
+        habit_id: Uuid,
+        user_id: Uuid,
+        date: NaiveDate,
+    ) -> Result<HabitResponse, AppError> {
+        let today = Utc::now().date_naive();
+        let mut tx = pool.begin().await?;
+
+        let habit = sqlx::query_as::<_, Habit>(HABITS_GET_FOR_UPDATE)
+            .bind(habit_id)
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Habit not found".to_string()))?;
+
+        let removed = sqlx::query(HABITS_UNCOMPLETE)
+            .bind(habit_id)
+            .bind(date)
+            .execute(&mut *tx)
+            .await?;
+        if removed.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "No completion on {} to undo",
+                date
+            )));
+        }
+
+        let (updated, done_today) = Self::refresh_streaks(&mut tx, &habit, today).await?;
+        GoalsRepo::refresh_linked(&mut tx, user_id, "habit", habit_id).await?;
+        tx.commit().await?;
+
+        Ok(HabitResponse::new(updated, done_today, today))
+    }
+
+    /// Update a habit; a new schedule recomputes its streaks
+    pub async fn update(
+        pool: &PgPool,
+        habit_id: Uuid,
+        user_id: Uuid,
+        req: &UpdateHabitRequest,
+    ) -> Result<HabitResponse, AppError> {
+        if let Some(name) = &req.name {
+            checked_name(name)?;
+        }
+        let today = Utc::now().date_naive();
+        let mut tx = pool.begin().await?;
+
+        let existing = sqlx::query_as::<_, Habit>(HABITS_GET_FOR_UPDATE)
+            .bind(habit_id)
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Habit not found".to_string()))?;
+
+        let frequency = req.frequency.clone().unwrap_or(existing.frequency.clone());
+        let target_count = req.target_count.unwrap_or(existing.target_count);
+        let custom_days = checked_schedule(
+            &frequency,
+            target_count,
+            req.custom_days
+                .as_deref()
+                .or(existing.custom_days.as_deref()),
+        )?;
+        let habit = Habit {
+            name: req.name.clone().unwrap_or(existing.name),
+            description: req.description.clone().or(existing.description),
+            frequency,
+            target_count,
+            custom_days,
+            icon: req.icon.clone().or(existing.icon),
+            color: req.color.clone().or(existing.color),
+            ..existing
+        };
+
+        sqlx::query(HABITS_UPDATE)
+            .bind(habit_id)
+            .bind(user_id)
+            .bind(&habit.name)
+            .bind(&habit.description)
+            .bind(&habit.frequency)
+            .bind(habit.target_count)
+            .bind(&habit.custom_days)
+            .bind(&habit.icon)
+            .bind(&habit.color)
+            .execute(&mut *tx)
+            .await?;
+
+        let (updated, done_today) = Self::refresh_streaks(&mut tx, &habit, today).await?;
+        tx.commit().await?;
+
+        Ok(HabitResponse::new(updated, done_today, today))
+    }
+
+    /// Archive or restore a habit; archived habits keep their history
+    pub async fn set_active(
+        pool: &PgPool,
+        habit_id: Uuid,
+        user_id: Uuid,
+        active: bool,
+    ) -> Result<HabitResponse, AppError> {
+        let habit = sqlx::query_as::<_, Habit>(HABITS_SET_ACTIVE)
+            .bind(habit_id)
+            .bind(user_id)
+            .bind(active)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Habit not found".to_string()))?;
+        let today = Utc::now().date_naive();
+        let counts = Self::completion_counts(pool, habit_id).await?;
+
+        let done_today = counts.get(&today).copied().unwrap_or(0);
+        Ok(HabitResponse::new(habit, done_today, today))
+    }
+
+    /// Delete a habit with its completions; goals linking to it stop counting it
+    pub async fn delete(pool: &PgPool, habit_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(HABITS_DELETE_COMPLETIONS)
+            .bind(habit_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(HABITS_DELETE_UNLINK_EVENTS)
+            .bind(habit_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query(HABITS_DELETE)
+            .bind(habit_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Habit not found".to_string()));
+        }
+        GoalsRepo::refresh_linked(&mut tx, user_id, "habit", habit_id).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Sort habits in the order given
+    pub async fn reorder(
+        pool: &PgPool,
+        user_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<HabitsListResponse, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let result = sqlx::query(HABITS_REORDER)
+            .bind(user_id)
+            .bind(ids)
+            .execute(&mut *tx)
+            .await?;
+
+        if result.rows_affected() != ids.len() as u64 {
+            return Err(AppError::NotFound("Habit not found".to_string()));
+        }
+        tx.commit().await?;
+
+        Self::list_active(pool, user_id).await
+    }
+
+    /// Completions per day from `from` to `to`, with the habit's streaks
+    pub async fn history(
+        pool: &PgPool,
+        habit_id: Uuid,
+        user_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<HabitHistoryResponse, AppError> {
+        let habit = Self::get_by_id(pool, habit_id, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Habit not found".to_string()))?;
+        let counts = Self::completion_counts(pool, habit_id).await?;
+        let days = HabitSchedule::of(&habit).history(&counts, from, to);
+
+        Ok(HabitHistoryResponse {
+            habit_id,
+            from,
+            to,
+            current_streak: habit.current_streak,
+            longest_streak: habit.longest_streak,
+            total_completions: days.iter().map(|d| d.count).sum(),
+            days,
         })
     }
 }
@@ -260,6 +596,230 @@ impl HabitsRepo {
 
 pub struct GoalsRepo;
 
+pub const GOALS_CREATE: &str = r#"
+    INSERT INTO goals (user_id, title, description, category, target_date, priority, started_at,
+                       sort_order)
+    VALUES ($1, $2, $3, $4, $5, $6, NOW(),
+            (SELECT COALESCE(MAX(sort_order) + 1, 0) FROM goals WHERE user_id = $1))
+    RETURNING id, user_id, title, description, category, target_date, started_at,
+              completed_at, status, progress, priority, sort_order, at_risk, archived_at,
+              created_at, updated_at
+"#;
+
+pub const GOALS_GET_BY_ID_GOAL: &str = r#"
+    SELECT id, user_id, title, description, category, target_date, started_at,
+           completed_at, status, progress, priority, sort_order, at_risk, archived_at,
+           created_at, updated_at
+    FROM goals WHERE id = $1 AND user_id = $2
+"#;
+
+pub const GOALS_GET_FOR_UPDATE: &str = r#"
+    SELECT id, user_id, title, description, category, target_date, started_at,
+           completed_at, status, progress, priority, sort_order, at_risk, archived_at,
+           created_at, updated_at
+    FROM goals WHERE id = $1 AND user_id = $2
+    FOR UPDATE
+"#;
+
+/// Goals linking to an item, locked in id order
+pub const GOALS_LIST_LINKED_FOR_UPDATE: &str = r#"
+    SELECT g.id, g.user_id, g.title, g.description, g.category, g.target_date, g.started_at,
+           g.completed_at, g.status, g.progress, g.priority, g.sort_order, g.at_risk,
+           g.archived_at, g.created_at, g.updated_at
+    FROM goals g
+    WHERE g.user_id = $1
+      AND EXISTS (
+          SELECT 1 FROM goal_links l
+          WHERE l.goal_id = g.id AND l.target_type = $2 AND l.target_id = $3
+      )
+    ORDER BY g.id
+    FOR UPDATE
+"#;
+
+/// $2 picks archived or current goals, $3 optionally filters by status
+pub const GOALS_LIST: &str = r#"
+    SELECT id, user_id, title, description, category, target_date, started_at,
+           completed_at, status, progress, priority, sort_order, at_risk, archived_at,
+           created_at, updated_at
+    FROM goals
+    WHERE user_id = $1
+      AND (archived_at IS NOT NULL) = $2
+      AND ($3::text IS NULL OR status = $3)
+    ORDER BY sort_order, priority DESC, title
+"#;
+
+pub const GOALS_LIST_GOAL_MILESTONES: &str = r#"
+    SELECT id, goal_id, title, description, is_completed, completed_at, sort_order, weight
+    FROM goal_milestones WHERE goal_id = ANY($1) ORDER BY sort_order, id
+"#;
+
+/// Links with how far along each linked item is, in percent. Habits count
+/// their completions since the goal started; items that are gone have no
+/// title.
+pub const GOALS_LIST_GOAL_LINKS: &str = r#"
+    SELECT l.id, l.goal_id, l.target_type, l.target_id, l.weight, l.target,
+           COALESCE(h.name, q.title, b.title) AS title,
+           LEAST(100, GREATEST(0, COALESCE(CASE l.target_type
+               WHEN 'habit' THEN (
+                   SELECT COUNT(*) * 100 / GREATEST(l.target, 1)
+                   FROM habit_completions c
+                   WHERE c.habit_id = h.id
+                     AND c.completed_date >= COALESCE(g.started_at, g.created_at)::date)
+               WHEN 'quest' THEN
+                   CASE WHEN q.completed_at IS NOT NULL THEN 100
+                        ELSE q.progress * 100 / GREATEST(q.target, 1) END
+               WHEN 'book' THEN
+                   CASE WHEN b.completed_at IS NOT NULL OR b.status = 'completed' THEN 100
+                        ELSE b.current_page * 100 / NULLIF(b.total_pages, 0) END
+           END, 0)))::int AS progress
+    FROM goal_links l
+    JOIN goals g ON g.id = l.goal_id
+    LEFT JOIN habits h
+        ON l.target_type = 'habit' AND h.id = l.target_id AND h.user_id = l.user_id
+    LEFT JOIN user_quests q
+        ON l.target_type = 'quest' AND q.id = l.target_id AND q.user_id = l.user_id
+    LEFT JOIN books b
+        ON l.target_type = 'book' AND b.id = l.target_id AND b.user_id = l.user_id
+    WHERE l.goal_id = ANY($1)
+    ORDER BY l.created_at, l.id
+"#;
+
+/// Save recomputed progress; the at-risk flag alone doesn't count as an update
+pub const GOALS_REFRESH: &str = r#"
+    UPDATE goals
+    SET progress = $2, status = $3, at_risk = $4,
+        completed_at = CASE WHEN $3 = 'completed' THEN COALESCE(completed_at, NOW()) END,
+        updated_at = CASE WHEN progress <> $2 OR status <> $3 THEN NOW() ELSE updated_at END
+    WHERE id = $1
+    RETURNING id, user_id, title, description, category, target_date, started_at,
+              completed_at, status, progress, priority, sort_order, at_risk, archived_at,
+              created_at, updated_at
+"#;
+
+pub const GOALS_UPDATE: &str = r#"
+    UPDATE goals
+    SET title = $3, description = $4, category = $5, target_date = $6, priority = $7,
+        status = $8, progress = $9,
+        completed_at = CASE WHEN $8 = 'completed' THEN COALESCE(completed_at, NOW()) END,
+        updated_at = NOW()
+    WHERE id = $1 AND user_id = $2
+    RETURNING id, user_id, title, description, category, target_date, started_at,
+              completed_at, status, progress, priority, sort_order, at_risk, archived_at,
+              created_at, updated_at
+"#;
+
+pub const GOALS_SET_ARCHIVED: &str = r#"
+    UPDATE goals
+    SET archived_at = CASE WHEN $3 THEN COALESCE(archived_at, NOW()) END, updated_at = NOW()
+    WHERE id = $1 AND user_id = $2
+    RETURNING id, user_id, title, description, category, target_date, started_at,
+              completed_at, status, progress, priority, sort_order, at_risk, archived_at,
+              created_at, updated_at
+"#;
+
+pub const GOALS_DELETE_MILESTONES: &str = r#"
+    DELETE FROM goal_milestones
+    WHERE goal_id = (SELECT id FROM goals WHERE id = $1 AND user_id = $2)
+"#;
+
+pub const GOALS_DELETE_LINKS: &str = "DELETE FROM goal_links WHERE goal_id = $1 AND user_id = $2";
+
+pub const GOALS_DELETE_UNLINK_EVENTS: &str =
+    "UPDATE calendar_events SET goal_id = NULL WHERE goal_id = $1 AND user_id = $2";
+
+pub const GOALS_DELETE: &str = "DELETE FROM goals WHERE id = $1 AND user_id = $2";
+
+/// Sort the given goals in the order given; returns how many were the user's
+pub const GOALS_REORDER: &str = r#"
+    UPDATE goals g
+    SET sort_order = o.position - 1, updated_at = NOW()
+    FROM unnest($2::uuid[]) WITH ORDINALITY AS o(id, position)
+    WHERE g.id = o.id AND g.user_id = $1
+"#;
+
+pub const GOALS_ADD_MILESTONE: &str = r#"
+    INSERT INTO goal_milestones (goal_id, title, description, weight, sort_order)
+    VALUES ($1, $2, $3, $4,
+            (SELECT COALESCE(MAX(sort_order) + 1, 0) FROM goal_milestones WHERE goal_id = $1))
+    RETURNING id, goal_id, title, description, is_completed, completed_at, sort_order, weight
+"#;
+
+pub const GOALS_GET_MILESTONE_FOR_UPDATE: &str = r#"
+    SELECT gm.id, gm.goal_id, gm.title, gm.description, gm.is_completed, gm.completed_at,
+           gm.sort_order, gm.weight
+    FROM goal_milestones gm
+    JOIN goals g ON gm.goal_id = g.id
+    WHERE gm.id = $1 AND g.user_id = $2
+    FOR UPDATE OF gm
+"#;
+
+pub const GOALS_COMPLETE_MILESTONE_UPDATE_GOAL_MILESTONES: &str = r#"
+    UPDATE goal_milestones
+    SET is_completed = true, completed_at = NOW()
+    WHERE id = $1
+    RETURNING id, goal_id, title, description, is_completed, completed_at, sort_order, weight
+"#;
+
+pub const GOALS_UPDATE_MILESTONE: &str = r#"
+    UPDATE goal_milestones
+    SET title = $2, description = $3, weight = $4
+    WHERE id = $1
+"#;
+
+pub const GOALS_DELETE_MILESTONE: &str = "DELETE FROM goal_milestones WHERE id = $1";
+
+/// Title of the user's habit, quest or book, if it exists
+pub const GOALS_LINK_TARGET_TITLE: &str = r#"
+    SELECT CASE $1
+        WHEN 'habit' THEN (SELECT name FROM habits WHERE id = $2 AND user_id = $3)
+        WHEN 'quest' THEN (SELECT title FROM user_quests WHERE id = $2 AND user_id = $3)
+        WHEN 'book' THEN (SELECT title FROM books WHERE id = $2 AND user_id = $3)
+    END
+"#;
+
+pub const GOALS_ADD_LINK: &str = r#"
+    INSERT INTO goal_links (goal_id, user_id, target_type, target_id, weight, target)
+    VALUES ($1, $2, $3, $4, $5, $6)
+    ON CONFLICT (goal_id, target_type, target_id) DO NOTHING
+    RETURNING id
+"#;
+
+pub const GOALS_DELETE_LINK: &str =
+    "DELETE FROM goal_links WHERE id = $1 AND goal_id = $2 AND user_id = $3";
+
+/// Validate a milestone or link weight, defaulting to 1
+fn checked_weight(weight: Option<i32>) -> Result<i32, AppError> {
+    let weight = weight.unwrap_or(1);
+    if !(1..=MAX_GOAL_WEIGHT).contains(&weight) {
+        return Err(AppError::Validation(format!(
+            "weight must be between 1 and {}",
+            MAX_GOAL_WEIGHT
+        )));
+    }
+    Ok(weight)
+}
+
+/// Validate a link's type and target; only habit links have a target, the
+/// number of completions they count toward
+fn checked_link_target(req: &CreateGoalLinkRequest) -> Result<Option<i32>, AppError> {
+    if !GOAL_LINK_TYPES.contains(&req.target_type.as_str()) {
+        return Err(AppError::Validation(format!(
+            "target_type must be one of {}",
+            GOAL_LINK_TYPES.join(", ")
+        )));
+    }
+    match (req.target_type == "habit", req.target) {
+        (true, Some(target)) if target >= 1 => Ok(Some(target)),
+        (true, _) => Err(AppError::Validation(
+            "A habit link needs a target of at least 1 completion".into(),
+        )),
+        (false, Some(_)) => Err(AppError::Validation(
+            "Only habit links take a target".into(),
+        )),
+        (false, None) => Ok(None),
+    }
+}
+
 impl GoalsRepo {
     /// Create a new goal
     pub async fn create(
@@ -267,246 +827,434 @@ impl GoalsRepo {
         user_id: Uuid,
         req: &CreateGoalRequest,
     ) -> Result<Goal, AppError> {
-        let goal = sqlx::query_as::<_, Goal>(
-            r#"INSERT INTO goals (user_id, title, description, category, target_date, priority, started_at)
-               VALUES ($1, $2, $3, $4, $5, $6, NOW())
-               RETURNING id, user_id, title, description, category, target_date, started_at,
-                         completed_at, status, progress, priority, sort_order, created_at, updated_at"#,
-        )
-        .bind(user_id)
-        .bind(&req.title)
-        .bind(&req.description)
-        .bind(&req.category)
-        .bind(req.target_date)
-        .bind(req.priority.unwrap_or(0))
-        .fetch_one(pool)
-        .await?;
+        let goal = sqlx::query_as::<_, Goal>(GOALS_CREATE)
+            .bind(user_id)
+            .bind(&req.title)
+            .bind(&req.description)
+            .bind(&req.category)
+            .bind(req.target_date)
+            .bind(req.priority.unwrap_or(0))
+            .fetch_one(pool)
+            .await?;
 
         Ok(goal)
     }
 
-    /// Get a goal by ID with milestones
+    /// Load the milestones and links, with each link's progress, of goals
+    async fn goal_parts(
+        conn: &mut PgConnection,
+        goals: &[Goal],
+    ) -> Result<
+        (
+            HashMap<Uuid, Vec<GoalMilestone>>,
+            HashMap<Uuid, Vec<GoalLink>>,
+        ),
+        AppError,
+    > {
+        let goal_ids: Vec<Uuid> = goals.iter().map(|g| g.id).collect();
+
+        let mut milestones: HashMap<Uuid, Vec<GoalMilestone>> = HashMap::new();
+        let mut links: HashMap<Uuid, Vec<GoalLink>> = HashMap::new();
+        if !goal_ids.is_empty() {
+            let rows = sqlx::query_as::<_, GoalMilestone>(GOALS_LIST_GOAL_MILESTONES)
+                .bind(&goal_ids)
+                .fetch_all(&mut *conn)
+                .await?;
+            for m in rows {
+                milestones.entry(m.goal_id).or_default().push(m);
+            }
+            let rows = sqlx::query_as::<_, GoalLink>(GOALS_LIST_GOAL_LINKS)
+                .bind(&goal_ids)
+                .fetch_all(&mut *conn)
+                .await?;
+            for l in rows {
+                links.entry(l.goal_id).or_default().push(l);
+            }
+        }
+        Ok((milestones, links))
+    }
+
+    /// Goals as stored, with milestones and links. Progress is kept current by
+    /// the writes that change it; only the at-risk flag depends on the day.
+    async fn responses(
+        conn: &mut PgConnection,
+        goals: Vec<Goal>,
+    ) -> Result<Vec<GoalResponse>, AppError> {
+        let today = Utc::now().date_naive();
+        let (mut milestones, mut links) = Self::goal_parts(conn, &goals).await?;
+
+        Ok(goals
+            .into_iter()
+            .map(|goal| {
+                let ms = milestones.remove(&goal.id).unwrap_or_default();
+                let ls = links.remove(&goal.id).unwrap_or_default();
+                let at_risk = goal_at_risk(&goal, &goal.status, goal.progress, today);
+                GoalResponse::new(Goal { at_risk, ..goal }, ms, ls)
+            })
+            .collect())
+    }
+
+    /// Recompute locked goals' progress from their milestones and links,
+    /// completing or reopening them and flagging the ones at risk; saves what
+    /// changed
+    async fn refresh(
+        conn: &mut PgConnection,
+        goals: Vec<Goal>,
+    ) -> Result<Vec<GoalResponse>, AppError> {
+        let today = Utc::now().date_naive();
+        let (mut milestones, mut links) = Self::goal_parts(conn, &goals).await?;
+
+        let mut responses = Vec::with_capacity(goals.len());
+        for goal in goals {
+            let ms = milestones.remove(&goal.id).unwrap_or_default();
+            let ls = links.remove(&goal.id).unwrap_or_default();
+
+            let progress = derived_progress(&ms, &ls).unwrap_or(goal.progress);
+            let status = status_for_progress(&goal.status, goal.progress, progress).to_string();
+            let at_risk = goal_at_risk(&goal, &status, progress, today);
+
+            let goal =
+                if progress != goal.progress || status != goal.status || at_risk != goal.at_risk {
+                    sqlx::query_as::<_, Goal>(GOALS_REFRESH)
+                        .bind(goal.id)
+                        .bind(progress)
+                        .bind(&status)
+                        .bind(at_risk)
+                        .fetch_one(&mut *conn)
+                        .await?
+                } else {
+                    goal
+                };
+            responses.push(GoalResponse::new(goal, ms, ls));
+        }
+        Ok(responses)
+    }
+
+    /// Recompute the goals linked to a habit, quest or book
+    /// after it changed, in the same transaction
+    pub async fn refresh_linked(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        target_type: &str,
+        target_id: Uuid,
+    ) -> Result<(), AppError> {
+        let goals = sqlx::query_as::<_, Goal>(GOALS_LIST_LINKED_FOR_UPDATE)
+            .bind(user_id)
+            .bind(target_type)
+            .bind(target_id)
+            .fetch_all(&mut *conn)
+            .await?;
+        Self::refresh(conn, goals).await?;
+        Ok(())
+    }
+
+    async fn refresh_one(conn: &mut PgConnection, goal: Goal) -> Result<GoalResponse, AppError> {
+        let mut responses = Self::refresh(conn, vec![goal]).await?;
+        Ok(responses.remove(0))
+    }
+
+    /// Lock one of the user's goals for a change
+    async fn get_for_update(
+        conn: &mut PgConnection,
+        goal_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Goal, AppError> {
+        sqlx::query_as::<_, Goal>(GOALS_GET_FOR_UPDATE)
+            .bind(goal_id)
+            .bind(user_id)
+            .fetch_optional(conn)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Goal not found".to_string()))
+    }
+
+    /// Get a goal by ID with milestones and links
     pub async fn get_by_id(
         pool: &PgPool,
         goal_id: Uuid,
         user_id: Uuid,
     ) -> Result<Option<GoalResponse>, AppError> {
-        let goal = sqlx::query_as::<_, Goal>(
-            r#"SELECT id, user_id, title, description, category, target_date, started_at,
-                      completed_at, status, progress, priority, sort_order, created_at, updated_at
-               FROM goals WHERE id = $1 AND user_id = $2"#,
-        )
-        .bind(goal_id)
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let mut conn = pool.acquire().await?;
+
+        let goal = sqlx::query_as::<_, Goal>(GOALS_GET_BY_ID_GOAL)
+            .bind(goal_id)
+            .bind(user_id)
+            .fetch_optional(&mut *conn)
+            .await?;
 
         match goal {
             None => Ok(None),
-            Some(goal) => {
-                let milestones = sqlx::query_as::<_, GoalMilestone>(
-                    r#"SELECT id, goal_id, title, description, is_completed, completed_at, sort_order
-                       FROM goal_milestones WHERE goal_id = $1 ORDER BY sort_order"#,
-                )
-                .bind(goal_id)
-                .fetch_all(pool)
-                .await?;
-
-                let total = milestones.len() as i32;
-                let completed = milestones.iter().filter(|m| m.is_completed).count() as i32;
-
-                Ok(Some(GoalResponse {
-                    id: goal.id,
-                    title: goal.title,
-                    description: goal.description,
-                    category: goal.category,
-                    target_date: goal.target_date,
-                    started_at: goal.started_at,
-                    completed_at: goal.completed_at,
-                    status: goal.status,
-                    progress: goal.progress,
-                    priority: goal.priority,
-                    milestones,
-                    total_milestones: total,
-                    completed_milestones: completed,
-                }))
-            }
+            Some(goal) => Ok(Self::responses(&mut conn, vec![goal]).await?.pop()),
         }
     }
 
-    /// List goals for user
+    async fn list_where(
+        pool: &PgPool,
+        user_id: Uuid,
+        archived: bool,
+        status_filter: Option<&str>,
+    ) -> Result<GoalsListResponse, AppError> {
+        let mut conn = pool.acquire().await?;
+
+        let goals = sqlx::query_as::<_, Goal>(GOALS_LIST)
+            .bind(user_id)
+            .bind(archived)
+            .bind(status_filter)
+            .fetch_all(&mut *conn)
+            .await?;
+
+        let goals = Self::responses(&mut conn, goals).await?;
+        Ok(GoalsListResponse {
+            total: goals.len() as i64,
+            goals,
+        })
+    }
+
+    /// List the user's goals that aren't archived
     pub async fn list(
         pool: &PgPool,
         user_id: Uuid,
         status_filter: Option<&str>,
     ) -> Result<GoalsListResponse, AppError> {
-        let goals = if let Some(status) = status_filter {
-            sqlx::query_as::<_, Goal>(
-                r#"SELECT id, user_id, title, description, category, target_date, started_at,
-                          completed_at, status, progress, priority, sort_order, created_at, updated_at
-                   FROM goals WHERE user_id = $1 AND status = $2
-                   ORDER BY priority DESC, sort_order, title"#,
-            )
+        Self::list_where(pool, user_id, false, status_filter).await
+    }
+
+    /// List the user's archived goals
+    pub async fn list_archived(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<GoalsListResponse, AppError> {
+        Self::list_where(pool, user_id, true, None).await
+    }
+
+    /// Update a goal. Progress can only be set on goals without milestones
+    /// or links; reaching 100% completes the goal.
+    pub async fn update(
+        pool: &PgPool,
+        goal_id: Uuid,
+        user_id: Uuid,
+        req: &UpdateGoalRequest,
+    ) -> Result<GoalResponse, AppError> {
+        if let Some(progress) = req.progress {
+            if !(0..=100).contains(&progress) {
+                return Err(AppError::Validation(
+                    "progress must be between 0 and 100".into(),
+                ));
+            }
+        }
+        let mut tx = pool.begin().await?;
+
+        let existing = Self::get_for_update(&mut tx, goal_id, user_id).await?;
+        let progress = req.progress.unwrap_or(existing.progress);
+        let status = match req.status {
+            Some(status) => status.as_str(),
+            None => status_for_progress(&existing.status, existing.progress, progress),
+        };
+
+        let goal = sqlx::query_as::<_, Goal>(GOALS_UPDATE)
+            .bind(goal_id)
             .bind(user_id)
+            .bind(req.title.as_ref().unwrap_or(&existing.title))
+            .bind(req.description.as_ref().or(existing.description.as_ref()))
+            .bind(req.category.as_ref().or(existing.category.as_ref()))
+            .bind(req.target_date.unwrap_or(existing.target_date))
+            .bind(req.priority.unwrap_or(existing.priority))
             .bind(status)
-            .fetch_all(pool)
-            .await?
-        } else {
-            sqlx::query_as::<_, Goal>(
-                r#"SELECT id, user_id, title, description, category, target_date, started_at,
-                          completed_at, status, progress, priority, sort_order, created_at, updated_at
-                   FROM goals WHERE user_id = $1
-                   ORDER BY priority DESC, sort_order, title"#,
-            )
+            .bind(progress)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let response = Self::refresh_one(&mut tx, goal).await?;
+        if req.progress.is_some() && response.auto_progress {
+            return Err(AppError::Validation(
+                "This goal's progress comes from its milestones and links".into(),
+            ));
+        }
+        tx.commit().await?;
+
+        Ok(response)
+    }
+
+    /// Archive or restore a goal; archived goals are left out of the list
+    pub async fn set_archived(
+        pool: &PgPool,
+        goal_id: Uuid,
+        user_id: Uuid,
+        archived: bool,
+    ) -> Result<GoalResponse, AppError> {
+        let mut conn = pool.acquire().await?;
+
+        let goal = sqlx::query_as::<_, Goal>(GOALS_SET_ARCHIVED)
+            .bind(goal_id)
             .bind(user_id)
-            .fetch_all(pool)
+            .bind(archived)
+            .fetch_optional(&mut *conn)
             .await?
-        };
+            .ok_or_else(|| AppError::NotFound("Goal not found".to_string()))?;
 
-        let total = goals.len() as i64;
+        Self::refresh_one(&mut conn, goal).await
+    }
 
-        // Get milestones for all goals
-        let goal_ids: Vec<Uuid> = goals.iter().map(|g| g.id).collect();
+    /// Delete a goal with its milestones and links
+    pub async fn delete(pool: &PgPool, goal_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
 
-        let milestones = if !goal_ids.is_empty() {
-            sqlx::query_as::<_, GoalMilestone>(
-                r#"SELECT id, goal_id, title, description, is_completed, completed_at, sort_order
-                   FROM goal_milestones WHERE goal_id = ANY($1) ORDER BY sort_order"#,
-            )
-            .bind(&goal_ids)
-            .fetch_all(pool)
-            .await?
-        } else {
-            vec![]
-        };
+        for query in [
+            GOALS_DELETE_MILESTONES,
+            GOALS_DELETE_LINKS,
+            GOALS_DELETE_UNLINK_EVENTS,
+        ] {
+            sqlx::query(query)
+                .bind(goal_id)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        let result = sqlx::query(GOALS_DELETE)
+            .bind(goal_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
 
-        // Group milestones by goal
-        let mut milestone_map: std::collections::HashMap<Uuid, Vec<GoalMilestone>> =
-            std::collections::HashMap::new();
-        for m in milestones {
-            milestone_map.entry(m.goal_id).or_default().push(m);
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Goal not found".to_string()));
         }
+        tx.commit().await?;
+        Ok(())
+    }
 
-        let responses = goals
-            .into_iter()
-            .map(|g| {
-                let ms = milestone_map.get(&g.id).cloned().unwrap_or_default();
-                let total_m = ms.len() as i32;
-                let completed_m = ms.iter().filter(|m| m.is_completed).count() as i32;
-
-                GoalResponse {
-                    id: g.id,
-                    title: g.title,
-                    description: g.description,
-                    category: g.category,
-                    target_date: g.target_date,
-                    started_at: g.started_at,
-                    completed_at: g.completed_at,
-                    status: g.status,
-                    progress: g.progress,
-                    priority: g.priority,
-                    milestones: ms,
-                    total_milestones: total_m,
-                    completed_milestones: completed_m,
-                }
-            })
-            .collect();
+    /// Sort goals in the order given
+    pub async fn reorder(
+        pool: &PgPool,
+        user_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<GoalsListResponse, AppError> {
+        let mut tx = pool.begin().await?;
 
-        Ok(GoalsListResponse {
-            goals: responses,
-            total,
-        })
+        let result = sqlx::query(GOALS_REORDER)
+            .bind(user_id)
+            .bind(ids)
+            .execute(&mut *tx)
+            .await?;
+
+        if result.rows_affected() != ids.len() as u64 {
+            return Err(AppError::NotFound("Goal not found".to_string()));
+        }
+        tx.commit().await?;
+
+        Self::list(pool, user_id, None).await
     }
 
     /// Add milestone to goal
-    pub async fn add_milestone(
-        pool: &PgPool,
+    pub async fn add_milestone<'c>(
+        db: impl Acquire<'c, Database = Postgres>,
         goal_id: Uuid,
         user_id: Uuid,
         req: &CreateMilestoneRequest,
     ) -> Result<GoalMilestone, AppError> {
-        // Verify goal belongs to user
-        let goal = Self::get_by_id(pool, goal_id, user_id).await?;
-        if goal.is_none() {
-            return Err(AppError::NotFound("Goal not found".to_string()));
-        }
+        let weight = checked_weight(req.weight)?;
+        let mut tx = db.begin().await?;
 
-        let milestone = sqlx::query_as::<_, GoalMilestone>(
-            r#"INSERT INTO goal_milestones (goal_id, title, description)
-               VALUES ($1, $2, $3)
-               RETURNING id, goal_id, title, description, is_completed, completed_at, sort_order"#,
-        )
-        .bind(goal_id)
-        .bind(&req.title)
-        .bind(&req.description)
-        .fetch_one(pool)
-        .await?;
+        let goal = Self::get_for_update(&mut tx, goal_id, user_id).await?;
+
+        let milestone = sqlx::query_as::<_, GoalMilestone>(GOALS_ADD_MILESTONE)
+            .bind(goal_id)
+            .bind(&req.title)
+            .bind(&req.description)
+            .bind(weight)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        Self::refresh_one(&mut tx, goal).await?;
+        tx.commit().await?;
 
         Ok(milestone)
     }
 
+    /// Lock one of the user's milestones and its goal for a change
+    async fn get_milestone_for_update(
+        conn: &mut PgConnection,
+        milestone_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(GoalMilestone, Goal), AppError> {
+        let milestone = sqlx::query_as::<_, GoalMilestone>(GOALS_GET_MILESTONE_FOR_UPDATE)
+            .bind(milestone_id)
+            .bind(user_id)
+            .fetch_optional(&mut *conn)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Milestone not found".to_string()))?;
+        let goal = Self::get_for_update(conn, milestone.goal_id, user_id).await?;
+
+        Ok((milestone, goal))
+    }
+
+    /// Update a milestone's title, description or weight
+    pub async fn update_milestone(
+        pool: &PgPool,
+        milestone_id: Uuid,
+        user_id: Uuid,
+        req: &UpdateMilestoneRequest,
+    ) -> Result<GoalResponse, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let (milestone, goal) =
+            Self::get_milestone_for_update(&mut tx, milestone_id, user_id).await?;
+        let weight = checked_weight(req.weight.or(Some(milestone.weight)))?;
+
+        sqlx::query(GOALS_UPDATE_MILESTONE)
+            .bind(milestone_id)
+            .bind(req.title.as_ref().unwrap_or(&milestone.title))
+            .bind(req.description.as_ref().or(milestone.description.as_ref()))
+            .bind(weight)
+            .execute(&mut *tx)
+            .await?;
+
+        let response = Self::refresh_one(&mut tx, goal).await?;
+        tx.commit().await?;
+
+        Ok(response)
+    }
+
+    /// Delete a milestone
+    pub async fn delete_milestone(
+        pool: &PgPool,
+        milestone_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<GoalResponse, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let (_, goal) = Self::get_milestone_for_update(&mut tx, milestone_id, user_id).await?;
+        sqlx::query(GOALS_DELETE_MILESTONE)
+            .bind(milestone_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let response = Self::refresh_one(&mut tx, goal).await?;
+        tx.commit().await?;
+
+        Ok(response)
+    }
+
     /// Complete milestone
     pub async fn complete_milestone(
         pool: &PgPool,
         milestone_id: Uuid,
         user_id: Uuid,
     ) -> Result<CompleteMilestoneResult, AppError> {
-        // Get milestone and verify ownership
-        let milestone = sqlx::query_as::<_, GoalMilestone>(
-            r#"SELECT gm.id, gm.goal_id, gm.title, gm.description, gm.is_completed, gm.completed_at, gm.sort_order
-               FROM goal_milestones gm
-               JOIN goals g ON gm.goal_id = g.id
-               WHERE gm.id = $1 AND g.user_id = $2"#,
-        )
-        .bind(milestone_id)
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
-
-        let milestone =
-            milestone.ok_or_else(|| AppError::NotFound("Milestone not found".to_string()))?;
-
-        // Update milestone
-        let updated = sqlx::query_as::<_, GoalMilestone>(
-            r#"UPDATE goal_milestones
-               SET is_completed = true, completed_at = NOW()
-               WHERE id = $1
-               RETURNING id, goal_id, title, description, is_completed, completed_at, sort_order"#,
-        )
-        .bind(milestone_id)
-        .fetch_one(pool)
-        .await?;
+        let mut tx = pool.begin().await?;
 
-        // Calculate new goal progress
-        let (total, completed): (i64, i64) = sqlx::query_as(
-            r#"SELECT COUNT(*), COUNT(*) FILTER (WHERE is_completed)
-               FROM goal_milestones WHERE goal_id = $1"#,
-        )
-        .bind(milestone.goal_id)
-        .fetch_one(pool)
-        .await?;
+        let (_, goal) = Self::get_milestone_for_update(&mut tx, milestone_id, user_id).await?;
+        let was_completed = goal.status == "completed";
 
-        let progress = if total > 0 {
-            ((completed * 100) / total) as i32
-        } else {
-            0
-        };
+        let updated =
+            sqlx::query_as::<_, GoalMilestone>(GOALS_COMPLETE_MILESTONE_UPDATE_GOAL_MILESTONES)
+                .bind(milestone_id)
+                .fetch_one(&mut *tx)
+                .await?;
 
-        let goal_completed = progress == 100;
+        let goal = Self::refresh_one(&mut tx, goal).await?;
+        tx.commit().await?;
 
-        // Update goal
-        sqlx::query(
-            r#"UPDATE goals
-               SET progress = $1,
-                   status = CASE WHEN $2 THEN 'completed' ELSE status END,
-                   completed_at = CASE WHEN $2 THEN NOW() ELSE completed_at END
-               WHERE id = $3"#,
-        )
-        .bind(progress)
-        .bind(goal_completed)
-        .bind(milestone.goal_id)
-        .execute(pool)
-        .await?;
+        let goal_completed = goal.status == "completed";
 
         // Award XP for milestone completion
         let idempotency_key = format!("milestone_complete_{}", milestone_id);
@@ -515,21 +1263,100 @@ impl GoalsRepo {
             user_id,
             &AwardPointsInput {
                 xp: Some(10),
-                coins: if goal_completed { Some(20) } else { None },
+                coins: if goal_completed && !was_completed {
+                    Some(20)
+                } else {
+                    None
+                },
                 skill_stars: None,
                 skill_key: None,
                 event_type: "milestone_complete".to_string(),
                 event_id: Some(milestone_id),
                 reason: Some(format!("Completed milestone: {}", updated.title)),
                 idempotency_key: Some(idempotency_key),
+                quantity: None,
             },
         )
         .await?;
 
         Ok(CompleteMilestoneResult {
             milestone: updated,
-            goal_progress: progress,
+            goal_progress: goal.progress,
             goal_completed,
         })
     }
+
+    /// Link a habit, quest or book to a goal
+    pub async fn add_link(
+        pool: &PgPool,
+        goal_id: Uuid,
+        user_id: Uuid,
+        req: &CreateGoalLinkRequest,
+    ) -> Result<GoalResponse, AppError> {
+        let target = checked_link_target(req)?;
+        let weight = checked_weight(req.weight)?;
+        let mut tx = pool.begin().await?;
+
+        let goal = Self::get_for_update(&mut tx, goal_id, user_id).await?;
+
+        let title: Option<String> = sqlx::query_scalar(GOALS_LINK_TARGET_TITLE)
+            .bind(&req.target_type)
+            .bind(req.target_id)
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        if title.is_none() {
+            return Err(AppError::NotFound(format!(
+                "No {} to link with that id",
+                req.target_type.replace('_', " ")
+            )));
+        }
+
+        let added: Option<Uuid> = sqlx::query_scalar(GOALS_ADD_LINK)
+            .bind(goal_id)
+            .bind(user_id)
+            .bind(&req.target_type)
+            .bind(req.target_id)
+            .bind(weight)
+            .bind(target)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if added.is_none() {
+            return Err(AppError::Conflict {
+                message: "That item is already linked to this goal".to_string(),
+                details: None,
+            });
+        }
+
+        let response = Self::refresh_one(&mut tx, goal).await?;
+        tx.commit().await?;
+
+        Ok(response)
+    }
+
+    /// Remove a link from a goal
+    pub async fn remove_link(
+        pool: &PgPool,
+        goal_id: Uuid,
+        link_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<GoalResponse, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let goal = Self::get_for_update(&mut tx, goal_id, user_id).await?;
+        let result = sqlx::query(GOALS_DELETE_LINK)
+            .bind(link_id)
+            .bind(goal_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Goal link not found".to_string()));
+        }
+
+        let response = Self::refresh_one(&mut tx, goal).await?;
+        tx.commit().await?;
+
+        Ok(response)
+    }
 }
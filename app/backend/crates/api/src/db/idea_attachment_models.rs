@@ -0,0 +1,79 @@
+//! Idea Attachment Models
+//!
+//! Audio attached to ideas: voice memos, loops and stems.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// What an attachment holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentKind {
+    VoiceMemo,
+    Loop,
+    Stem,
+    Other,
+}
+
+impl AttachmentKind {
+    /// `kind` column value
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttachmentKind::VoiceMemo => "voice_memo",
+            AttachmentKind::Loop => "loop",
+            AttachmentKind::Stem => "stem",
+            AttachmentKind::Other => "other",
+        }
+    }
+}
+
+impl std::str::FromStr for AttachmentKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "voice_memo" => Ok(AttachmentKind::VoiceMemo),
+            "loop" => Ok(AttachmentKind::Loop),
+            "stem" => Ok(AttachmentKind::Stem),
+            "other" => Ok(AttachmentKind::Other),
+            _ => Err(format!("Unknown attachment kind: {}", s)),
+        }
+    }
+}
+
+/// Audio file attached to an idea
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct IdeaAttachment {
+    pub id: Uuid,
+    pub idea_id: Uuid,
+    pub kind: String,
+    pub r2_key: String,
+    pub filename: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    /// Read from the file's headers on upload; unknown for some formats
+    pub duration_seconds: Option<f32>,
+    /// Reference track the audio was promoted to
+    pub reference_track_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Fields of a newly uploaded attachment
+#[derive(Debug, Clone)]
+pub struct NewAttachment<'a> {
+    pub kind: AttachmentKind,
+    pub r2_key: &'a str,
+    pub filename: &'a str,
+    pub mime_type: &'a str,
+    pub size_bytes: i64,
+    pub duration_seconds: Option<f32>,
+}
+
+/// Promote attachment request; the idea's title by default
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PromoteAttachmentRequest {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+}
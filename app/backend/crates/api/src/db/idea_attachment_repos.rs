@@ -0,0 +1,208 @@
+//! Idea Attachment Repository
+//!
+//! Rows for audio attached to ideas. The files themselves live in storage;
+//! routes upload them before a row is written and remove them after a row is
+//! deleted, so a failure leaves at worst an unreferenced object.
+
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::idea_attachment_models::*;
+use super::platform_models::Idea;
+use super::platform_repos::IDEAS_GET;
+use super::reference_models::{CreateTrackInput, ReferenceTrack};
+use super::reference_repos::ReferenceTrackRepo;
+use crate::error::AppError;
+
+pub struct IdeaAttachmentRepo;
+
+// Written only for an idea the user owns
+pub const IDEA_ATTACHMENT_CREATE: &str = r#"
+    INSERT INTO idea_attachments
+        (user_id, idea_id, kind, r2_key, filename, mime_type, size_bytes, duration_seconds)
+    SELECT $1, $2, $3, $4, $5, $6, $7, $8
+    WHERE EXISTS (SELECT 1 FROM ideas WHERE id = $2 AND user_id = $1)
+    RETURNING id, idea_id, kind, r2_key, filename, mime_type, size_bytes, duration_seconds,
+              reference_track_id, created_at
+"#;
+
+pub const IDEA_ATTACHMENT_LIST: &str = r#"
+    SELECT id, idea_id, kind, r2_key, filename, mime_type, size_bytes, duration_seconds,
+           reference_track_id, created_at
+    FROM idea_attachments
+    WHERE user_id = $1 AND idea_id = $2
+    ORDER BY created_at
+"#;
+
+pub const IDEA_ATTACHMENT_GET: &str = r#"
+    SELECT id, idea_id, kind, r2_key, filename, mime_type, size_bytes, duration_seconds,
+           reference_track_id, created_at
+    FROM idea_attachments
+    WHERE user_id = $1 AND idea_id = $2 AND id = $3
+"#;
+
+pub const IDEA_ATTACHMENT_GET_FOR_UPDATE: &str = r#"
+    SELECT id, idea_id, kind, r2_key, filename, mime_type, size_bytes, duration_seconds,
+           reference_track_id, created_at
+    FROM idea_attachments
+    WHERE user_id = $1 AND idea_id = $2 AND id = $3
+    FOR UPDATE
+"#;
+
+pub const IDEA_ATTACHMENT_SET_TRACK: &str =
+    "UPDATE idea_attachments SET reference_track_id = $2 WHERE id = $1";
+
+pub const IDEA_ATTACHMENT_DELETE: &str = r#"
+    DELETE FROM idea_attachments
+    WHERE user_id = $1 AND idea_id = $2 AND id = $3
+    RETURNING id, idea_id, kind, r2_key, filename, mime_type, size_bytes, duration_seconds,
+              reference_track_id, created_at
+"#;
+
+fn attachment_not_found() -> AppError {
+    AppError::NotFound("Attachment not found".into())
+}
+
+impl IdeaAttachmentRepo {
+    /// Record an uploaded file against an idea
+    pub async fn create(
+        pool: &PgPool,
+        user_id: Uuid,
+        idea_id: Uuid,
+        new: &NewAttachment<'_>,
+    ) -> Result<IdeaAttachment, AppError> {
+        sqlx::query_as::<_, IdeaAttachment>(IDEA_ATTACHMENT_CREATE)
+            .bind(user_id)
+            .bind(idea_id)
+            .bind(new.kind.as_str())
+            .bind(new.r2_key)
+            .bind(new.filename)
+            .bind(new.mime_type)
+            .bind(new.size_bytes)
+            .bind(new.duration_seconds)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Idea not found".into()))
+    }
+
+    /// Attachments of an idea, oldest first
+    pub async fn list(
+        pool: &PgPool,
+        user_id: Uuid,
+        idea_id: Uuid,
+    ) -> Result<Vec<IdeaAttachment>, AppError> {
+        let attachments = sqlx::query_as::<_, IdeaAttachment>(IDEA_ATTACHMENT_LIST)
+            .bind(user_id)
+            .bind(idea_id)
+            .fetch_all(pool)
+            .await?;
+        Ok(attachments)
+    }
+
+    /// A single attachment
+    pub async fn get(
+        pool: &PgPool,
+        user_id: Uuid,
+        idea_id: Uuid,
+        id: Uuid,
+    ) -> Result<IdeaAttachment, AppError> {
+        sqlx::query_as::<_, IdeaAttachment>(IDEA_ATTACHMENT_GET)
+            .bind(user_id)
+            .bind(idea_id)
+            .bind(id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(attachment_not_found)
+    }
+
+    /// Delete the row; the caller removes the stored file
+    pub async fn delete(
+        pool: &PgPool,
+        user_id: Uuid,
+        idea_id: Uuid,
+        id: Uuid,
+    ) -> Result<IdeaAttachment, AppError> {
+        sqlx::query_as::<_, IdeaAttachment>(IDEA_ATTACHMENT_DELETE)
+            .bind(user_id)
+            .bind(idea_id)
+            .bind(id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(attachment_not_found)
+    }
+
+    /// Create a reference track from an attachment
+    ///
+    /// `track_key` is the track's own copy of the audio, so deleting either
+    /// the track or the attachment leaves the other playable. The track takes
+    /// the idea's key, BPM and genre.
+    pub async fn promote(
+        pool: &PgPool,
+        user_id: Uuid,
+        idea_id: Uuid,
+        id: Uuid,
+        track_key: &str,
+        req: &PromoteAttachmentRequest,
+    ) -> Result<ReferenceTrack, AppError> {
+        let mut tx = pool.begin().await?;
+        let attachment = sqlx::query_as::<_, IdeaAttachment>(IDEA_ATTACHMENT_GET_FOR_UPDATE)
+            .bind(user_id)
+            .bind(idea_id)
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(attachment_not_found)?;
+        if let Some(track_id) = attachment.reference_track_id {
+            return Err(already_promoted(track_id));
+        }
+        let idea = sqlx::query_as::<_, Idea>(IDEAS_GET)
+            .bind(idea_id)
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let input = CreateTrackInput {
+            title: req.title.clone().unwrap_or(idea.title),
+            r2_key: track_key.to_string(),
+            artist: req.artist.clone(),
+            album: None,
+            genre: idea.music.genre,
+            bpm: idea.music.bpm.map(|bpm| bpm as f32),
+            key: idea.music.key,
+            duration_seconds: attachment.duration_seconds,
+            waveform_r2_key: None,
+            thumbnail_r2_key: None,
+            file_format: Some(attachment.mime_type),
+            sample_rate: None,
+            bit_depth: None,
+            channels: None,
+            is_reference: Some(true),
+            is_user_upload: Some(true),
+            source: Some("idea".to_string()),
+            source_url: None,
+            metadata: Some(json!({
+                "idea_id": idea_id,
+                "attachment_id": id,
+                "time_signature": idea.music.time_signature,
+            })),
+        };
+        let track = ReferenceTrackRepo::create(&mut *tx, user_id, input).await?;
+        sqlx::query(IDEA_ATTACHMENT_SET_TRACK)
+            .bind(id)
+            .bind(track.id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(track)
+    }
+}
+
+/// 409 for promoting an attachment twice
+pub fn already_promoted(track_id: Uuid) -> AppError {
+    AppError::Conflict {
+        message: "Attachment was already promoted to a reference track".into(),
+        details: Some(json!({ "reference_track_id": track_id })),
+    }
+}
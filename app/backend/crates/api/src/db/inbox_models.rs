@@ -24,10 +24,22 @@ pub struct InboxItem {
     pub priority: i32,
     pub is_read: bool,
     pub is_archived: bool,
+    pub is_processed: bool,
+    pub processed_at: Option<DateTime<Utc>>,
     pub expires_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
 }
 
+/// Record of an inbox item converted into another item
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct InboxConversion {
+    pub id: Uuid,
+    pub inbox_item_id: Uuid,
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
 // ============================================================================
 // REQUEST/RESPONSE TYPES
 // ============================================================================
@@ -68,6 +80,8 @@ pub struct InboxResponse {
     pub priority: i32,
     pub is_read: bool,
     pub is_archived: bool,
+    pub is_processed: bool,
+    pub processed_at: Option<DateTime<Utc>>,
     pub expires_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
 }
@@ -84,6 +98,8 @@ impl From<InboxItem> for InboxResponse {
             priority: item.priority,
             is_read: item.is_read,
             is_archived: item.is_archived,
+            is_processed: item.is_processed,
+            processed_at: item.processed_at,
             expires_at: item.expires_at,
             created_at: item.created_at,
         }
@@ -106,3 +122,99 @@ pub struct DeleteInboxResponse {
     pub success: bool,
     pub id: Uuid,
 }
+
+// ============================================================================
+// TRIAGE
+// ============================================================================
+
+/// Most items one bulk triage request may touch
+pub const MAX_TRIAGE_ITEMS: usize = 200;
+
+/// What an inbox item is converted into, with what the target needs beyond
+/// the item's title and body
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "target", rename_all = "snake_case")]
+pub enum ConvertTarget {
+    Quest {
+        category: Option<String>,
+        difficulty: Option<String>,
+    },
+    Habit {
+        frequency: Option<String>,
+    },
+    CalendarEvent {
+        start_time: DateTime<Utc>,
+        end_time: Option<DateTime<Utc>>,
+        #[serde(default)]
+        all_day: bool,
+    },
+    GoalMilestone {
+        goal_id: Uuid,
+    },
+    Idea {
+        category: Option<String>,
+    },
+    InfobaseEntry {
+        category: Option<String>,
+    },
+}
+
+impl ConvertTarget {
+    /// `target_type` column value
+    pub fn target_type(&self) -> &'static str {
+        match self {
+            ConvertTarget::Quest { .. } => "quest",
+            ConvertTarget::Habit { .. } => "habit",
+            ConvertTarget::CalendarEvent { .. } => "calendar_event",
+            ConvertTarget::GoalMilestone { .. } => "goal_milestone",
+            ConvertTarget::Idea { .. } => "idea",
+            ConvertTarget::InfobaseEntry { .. } => "infobase_entry",
+        }
+    }
+}
+
+/// Convert inbox item request
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConvertInboxRequest {
+    /// Title for the new item; the inbox item's title by default
+    pub title: Option<String>,
+    #[serde(flatten)]
+    pub target: ConvertTarget,
+}
+
+/// Convert inbox item response
+#[derive(Debug, Serialize)]
+pub struct ConvertInboxResponse {
+    pub item: InboxResponse,
+    pub conversion: InboxConversion,
+    /// The created quest, habit, event, milestone, idea or entry
+    pub target: serde_json::Value,
+}
+
+/// Bulk triage action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TriageAction {
+    Read,
+    Unread,
+    Archive,
+    Unarchive,
+    /// Handled without converting
+    Process,
+    Delete,
+}
+
+/// Bulk triage request
+#[derive(Debug, Deserialize)]
+pub struct TriageInboxRequest {
+    pub ids: Vec<Uuid>,
+    pub action: TriageAction,
+}
+
+/// Bulk triage response
+#[derive(Debug, Serialize)]
+pub struct TriageInboxResponse {
+    pub action: TriageAction,
+    /// Items the action changed
+    pub affected: i64,
+}
@@ -2,14 +2,105 @@
 //!
 //! Database operations for inbox items (notifications, action items).
 
+use serde::Serialize;
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 
+use crate::db::habits_goals_models::{CreateHabitRequest, CreateMilestoneRequest};
+use crate::db::habits_goals_repos::{GoalsRepo, HabitsRepo};
 use crate::db::inbox_models::*;
+use crate::db::platform_models::{
+    CreateCalendarEventRequest, CreateIdeaRequest, CreateInfobaseEntryRequest, IdeaMusic,
+};
+use crate::db::platform_repos::{CalendarRepo, IdeasRepo, InfobaseRepo};
+use crate::db::quests_models::{CreateQuestRequest, QuestResponse};
+use crate::db::quests_repos::QuestsRepo;
 use crate::error::AppError;
 
 pub struct InboxRepo;
 
+pub const INBOX_LIST_WITH_ARCHIVED: &str =
+    "SELECT * FROM inbox_items WHERE user_id = $1 ORDER BY priority DESC, created_at DESC LIMIT $2 OFFSET $3";
+
+pub const INBOX_LIST: &str =
+    "SELECT * FROM inbox_items WHERE user_id = $1 AND is_archived = false ORDER BY priority DESC, created_at DESC LIMIT $2 OFFSET $3";
+
+pub const INBOX_LIST_TOTAL: &str = "SELECT COUNT(*) FROM inbox_items WHERE user_id = $1";
+
+pub const INBOX_LIST_UNREAD: &str =
+    "SELECT COUNT(*) FROM inbox_items WHERE user_id = $1 AND is_read = false AND is_archived = false";
+
+pub const INBOX_GET: &str = "SELECT * FROM inbox_items WHERE id = $1 AND user_id = $2";
+
+pub const INBOX_CREATE: &str = r#"
+    INSERT INTO inbox_items (user_id, item_type, title, body, action_url, action_data, priority, expires_at)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+    RETURNING *
+"#;
+
+pub const INBOX_UPDATE: &str = r#"
+    UPDATE inbox_items
+    SET is_read = COALESCE($1, is_read),
+        is_archived = COALESCE($2, is_archived)
+    WHERE id = $3 AND user_id = $4
+    RETURNING *
+"#;
+
+pub const INBOX_MARK_ALL_READ: &str =
+    "UPDATE inbox_items SET is_read = true WHERE user_id = $1 AND is_read = false";
+
+pub const INBOX_DELETE: &str = "DELETE FROM inbox_items WHERE id = $1 AND user_id = $2";
+
+pub const INBOX_UNREAD_COUNT: &str =
+    "SELECT COUNT(*) FROM inbox_items WHERE user_id = $1 AND is_read = false AND is_archived = false";
+
+pub const INBOX_GET_FOR_UPDATE: &str =
+    "SELECT * FROM inbox_items WHERE id = $1 AND user_id = $2 FOR UPDATE";
+
+pub const INBOX_MARK_PROCESSED: &str = r#"
+    UPDATE inbox_items
+    SET is_processed = true, processed_at = NOW(), is_read = true, updated_at = NOW()
+    WHERE id = $1 AND user_id = $2
+    RETURNING *
+"#;
+
+pub const INBOX_CONVERSION_CREATE: &str = r#"
+    INSERT INTO inbox_conversions (user_id, inbox_item_id, target_type, target_id)
+    VALUES ($1, $2, $3, $4)
+    RETURNING id, inbox_item_id, target_type, target_id, created_at
+"#;
+
+pub const INBOX_SOURCE_FOR_TARGET: &str = r#"
+    SELECT i.*
+    FROM inbox_items i
+    JOIN inbox_conversions c ON c.inbox_item_id = i.id
+    WHERE c.user_id = $1 AND c.target_type = $2 AND c.target_id = $3
+"#;
+
+pub const INBOX_TRIAGE_READ: &str = r#"
+    UPDATE inbox_items SET is_read = $3, updated_at = NOW()
+    WHERE user_id = $1 AND id = ANY($2) AND is_read <> $3
+"#;
+
+pub const INBOX_TRIAGE_ARCHIVE: &str = r#"
+    UPDATE inbox_items SET is_archived = $3, updated_at = NOW()
+    WHERE user_id = $1 AND id = ANY($2) AND is_archived <> $3
+"#;
+
+pub const INBOX_TRIAGE_PROCESS: &str = r#"
+    UPDATE inbox_items
+    SET is_processed = true, processed_at = NOW(), is_read = true, updated_at = NOW()
+    WHERE user_id = $1 AND id = ANY($2) AND NOT is_processed
+"#;
+
+pub const INBOX_TRIAGE_DELETE: &str =
+    "DELETE FROM inbox_items WHERE user_id = $1 AND id = ANY($2)";
+
+pub const INBOX_EXPIRE: &str = r#"
+    UPDATE inbox_items SET is_archived = true, updated_at = NOW()
+    WHERE expires_at <= NOW() AND NOT is_processed AND NOT is_archived
+"#;
+
 impl InboxRepo {
     /// List inbox items for user
     pub async fn list(
@@ -22,38 +113,30 @@ impl InboxRepo {
         let offset = (page - 1) * page_size;
 
         let items = if include_archived {
-            sqlx::query_as::<_, InboxItem>(
-                "SELECT * FROM inbox_items WHERE user_id = $1 ORDER BY priority DESC, created_at DESC LIMIT $2 OFFSET $3",
-            )
-            .bind(user_id)
-            .bind(page_size)
-            .bind(offset)
-            .fetch_all(db)
-            .await?
+            sqlx::query_as::<_, InboxItem>(INBOX_LIST_WITH_ARCHIVED)
+                .bind(user_id)
+                .bind(page_size)
+                .bind(offset)
+                .fetch_all(db)
+                .await?
         } else {
-            sqlx::query_as::<_, InboxItem>(
-                "SELECT * FROM inbox_items WHERE user_id = $1 AND is_archived = false ORDER BY priority DESC, created_at DESC LIMIT $2 OFFSET $3",
-            )
-            .bind(user_id)
-            .bind(page_size)
-            .bind(offset)
-            .fetch_all(db)
-            .await?
+            sqlx::query_as::<_, InboxItem>(INBOX_LIST)
+                .bind(user_id)
+                .bind(page_size)
+                .bind(offset)
+                .fetch_all(db)
+                .await?
         };
 
-        let total: (i64,) = sqlx::query_as(
-            "SELECT COUNT(*) FROM inbox_items WHERE user_id = $1",
-        )
-        .bind(user_id)
-        .fetch_one(db)
-        .await?;
+        let total: (i64,) = sqlx::query_as(INBOX_LIST_TOTAL)
+            .bind(user_id)
+            .fetch_one(db)
+            .await?;
 
-        let unread: (i64,) = sqlx::query_as(
-            "SELECT COUNT(*) FROM inbox_items WHERE user_id = $1 AND is_read = false AND is_archived = false",
-        )
-        .bind(user_id)
-        .fetch_one(db)
-        .await?;
+        let unread: (i64,) = sqlx::query_as(INBOX_LIST_UNREAD)
+            .bind(user_id)
+            .fetch_one(db)
+            .await?;
 
         Ok(InboxListResponse {
             items: items.into_iter().map(InboxResponse::from).collect(),
@@ -70,14 +153,12 @@ impl InboxRepo {
         user_id: Uuid,
         item_id: Uuid,
     ) -> Result<InboxItem, AppError> {
-        let item = sqlx::query_as::<_, InboxItem>(
-            "SELECT * FROM inbox_items WHERE id = $1 AND user_id = $2",
-        )
-        .bind(item_id)
-        .bind(user_id)
-        .fetch_optional(db)
-        .await?
-        .ok_or(AppError::NotFound("Inbox item not found".into()))?;
+        let item = sqlx::query_as::<_, InboxItem>(INBOX_GET)
+            .bind(item_id)
+            .bind(user_id)
+            .fetch_optional(db)
+            .await?
+            .ok_or(AppError::NotFound("Inbox item not found".into()))?;
 
         Ok(item)
     }
@@ -88,21 +169,17 @@ impl InboxRepo {
         user_id: Uuid,
         req: &CreateInboxRequest,
     ) -> Result<InboxItem, AppError> {
-        let item = sqlx::query_as::<_, InboxItem>(
-            "INSERT INTO inbox_items (user_id, item_type, title, body, action_url, action_data, priority, expires_at)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-             RETURNING *",
-        )
-        .bind(user_id)
-        .bind(&req.item_type)
-        .bind(&req.title)
-        .bind(&req.body)
-        .bind(&req.action_url)
-        .bind(&req.action_data)
-        .bind(req.priority.unwrap_or(0))
-        .bind(&req.expires_at)
-        .fetch_one(db)
-        .await?;
+        let item = sqlx::query_as::<_, InboxItem>(INBOX_CREATE)
+            .bind(user_id)
+            .bind(&req.item_type)
+            .bind(&req.title)
+            .bind(&req.body)
+            .bind(&req.action_url)
+            .bind(&req.action_data)
+            .bind(req.priority.unwrap_or(0))
+            .bind(&req.expires_at)
+            .fetch_one(db)
+            .await?;
 
         Ok(item)
     }
@@ -117,19 +194,13 @@ impl InboxRepo {
         // Check item exists
         let _ = Self::get(db, user_id, item_id).await?;
 
-        let item = sqlx::query_as::<_, InboxItem>(
-            "UPDATE inbox_items 
-             SET is_read = COALESCE($1, is_read),
-                 is_archived = COALESCE($2, is_archived)
-             WHERE id = $3 AND user_id = $4
-             RETURNING *",
-        )
-        .bind(&req.is_read)
-        .bind(&req.is_archived)
-        .bind(item_id)
-        .bind(user_id)
-        .fetch_one(db)
-        .await?;
+        let item = sqlx::query_as::<_, InboxItem>(INBOX_UPDATE)
+            .bind(&req.is_read)
+            .bind(&req.is_archived)
+            .bind(item_id)
+            .bind(user_id)
+            .fetch_one(db)
+            .await?;
 
         Ok(item)
     }
@@ -139,12 +210,10 @@ impl InboxRepo {
         db: &Pool<Postgres>,
         user_id: Uuid,
     ) -> Result<i64, AppError> {
-        let result = sqlx::query(
-            "UPDATE inbox_items SET is_read = true WHERE user_id = $1 AND is_read = false",
-        )
-        .bind(user_id)
-        .execute(db)
-        .await?;
+        let result = sqlx::query(INBOX_MARK_ALL_READ)
+            .bind(user_id)
+            .execute(db)
+            .await?;
 
         Ok(result.rows_affected() as i64)
     }
@@ -155,13 +224,11 @@ impl InboxRepo {
         user_id: Uuid,
         item_id: Uuid,
     ) -> Result<(), AppError> {
-        let result = sqlx::query(
-            "DELETE FROM inbox_items WHERE id = $1 AND user_id = $2",
-        )
-        .bind(item_id)
-        .bind(user_id)
-        .execute(db)
-        .await?;
+        let result = sqlx::query(INBOX_DELETE)
+            .bind(item_id)
+            .bind(user_id)
+            .execute(db)
+            .await?;
 
         if result.rows_affected() == 0 {
             return Err(AppError::NotFound("Inbox item not found".into()));
@@ -175,13 +242,229 @@ impl InboxRepo {
         db: &Pool<Postgres>,
         user_id: Uuid,
     ) -> Result<i64, AppError> {
-        let count: (i64,) = sqlx::query_as(
-            "SELECT COUNT(*) FROM inbox_items WHERE user_id = $1 AND is_read = false AND is_archived = false",
-        )
-        .bind(user_id)
-        .fetch_one(db)
-        .await?;
+        let count: (i64,) = sqlx::query_as(INBOX_UNREAD_COUNT)
+            .bind(user_id)
+            .fetch_one(db)
+            .await?;
 
         Ok(count.0)
     }
+
+    /// Convert an item into a quest, habit, event, milestone, idea or entry
+    ///
+    /// The target is created through its own repo, linked back to the item
+    /// and the item marked processed in one transaction, so a failure leaves
+    /// nothing half-converted.
+    pub async fn convert(
+        db: &Pool<Postgres>,
+        user_id: Uuid,
+        item_id: Uuid,
+        req: &ConvertInboxRequest,
+    ) -> Result<ConvertInboxResponse, AppError> {
+        let mut tx = db.begin().await?;
+        let item = sqlx::query_as::<_, InboxItem>(INBOX_GET_FOR_UPDATE)
+            .bind(item_id)
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound("Inbox item not found".into()))?;
+        if item.is_processed {
+            return Err(AppError::Conflict {
+                message: "Inbox item was already processed".into(),
+                details: None,
+            });
+        }
+
+        let title = req
+            .title
+            .clone()
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or(item.title);
+        let notes = item.body;
+
+        let (target_id, target) = match &req.target {
+            ConvertTarget::Quest { category, difficulty } => {
+                let quest = QuestsRepo::create(
+                    &mut *tx,
+                    user_id,
+                    &CreateQuestRequest {
+                        title,
+                        description: notes,
+                        category: category.clone().unwrap_or_else(|| "general".into()),
+                        difficulty: difficulty.clone().unwrap_or_else(|| "starter".into()),
+                        xp_reward: None,
+                        coin_reward: None,
+                        target: None,
+                        is_repeatable: None,
+                        repeat_frequency: None,
+                    },
+                )
+                .await?;
+                (quest.id, to_json(QuestResponse::from(quest))?)
+            }
+            ConvertTarget::Habit { frequency } => {
+                let habit = HabitsRepo::create(
+                    &mut *tx,
+                    user_id,
+                    &CreateHabitRequest {
+                        name: title,
+                        description: notes,
+                        frequency: frequency.clone().unwrap_or_else(|| "daily".into()),
+                        target_count: 1,
+                        custom_days: None,
+                        icon: None,
+                        color: None,
+                    },
+                )
+                .await?;
+                (habit.id, to_json(habit)?)
+            }
+            ConvertTarget::CalendarEvent { start_time, end_time, all_day } => {
+                let event = CalendarRepo::create(
+                    &mut *tx,
+                    user_id,
+                    &CreateCalendarEventRequest {
+                        title,
+                        description: notes,
+                        event_type: "general".into(),
+                        start_time: *start_time,
+                        end_time: *end_time,
+                        all_day: *all_day,
+                        timezone: None,
+                        location: None,
+                        workout_id: None,
+                        habit_id: None,
+                        goal_id: None,
+                        recurrence_rule: None,
+                        recurrence_end: None,
+                        parent_event_id: None,
+                        color: None,
+                        reminder_minutes: None,
+                        metadata: None,
+                    },
+                )
+                .await?;
+                (event.id, to_json(event)?)
+            }
+            ConvertTarget::GoalMilestone { goal_id } => {
+                let milestone = GoalsRepo::add_milestone(
+                    &mut *tx,
+                    *goal_id,
+                    user_id,
+                    &CreateMilestoneRequest {
+                        title,
+                        description: notes,
+                        weight: None,
+                    },
+                )
+                .await?;
+                (milestone.id, to_json(milestone)?)
+            }
+            ConvertTarget::Idea { category } => {
+                let idea = IdeasRepo::create(
+                    &mut *tx,
+                    user_id,
+                    &CreateIdeaRequest {
+                        title,
+                        content: notes,
+                        category: category.clone().unwrap_or_else(|| "general".into()),
+                        tags: None,
+                        music: IdeaMusic::default(),
+                    },
+                )
+                .await?;
+                (idea.id, to_json(idea)?)
+            }
+            ConvertTarget::InfobaseEntry { category } => {
+                let entry = InfobaseRepo::create(
+                    &mut *tx,
+                    user_id,
+                    &CreateInfobaseEntryRequest {
+                        title,
+                        content: notes.unwrap_or_default(),
+                        category: category.clone().unwrap_or_else(|| "Tips".into()),
+                        tags: None,
+                    },
+                )
+                .await?;
+                (entry.id, to_json(entry)?)
+            }
+        };
+
+        let conversion = sqlx::query_as::<_, InboxConversion>(INBOX_CONVERSION_CREATE)
+            .bind(user_id)
+            .bind(item_id)
+            .bind(req.target.target_type())
+            .bind(target_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        let item = sqlx::query_as::<_, InboxItem>(INBOX_MARK_PROCESSED)
+            .bind(item_id)
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(ConvertInboxResponse {
+            item: InboxResponse::from(item),
+            conversion,
+            target,
+        })
+    }
+
+    /// Inbox item a target was converted from
+    pub async fn source_for(
+        db: &Pool<Postgres>,
+        user_id: Uuid,
+        target_type: &str,
+        target_id: Uuid,
+    ) -> Result<InboxItem, AppError> {
+        let item = sqlx::query_as::<_, InboxItem>(INBOX_SOURCE_FOR_TARGET)
+            .bind(user_id)
+            .bind(target_type)
+            .bind(target_id)
+            .fetch_optional(db)
+            .await?
+            .ok_or(AppError::NotFound("No inbox item was converted into this".into()))?;
+
+        Ok(item)
+    }
+
+    /// Apply one action to many items; returns how many it changed
+    pub async fn triage(
+        db: &Pool<Postgres>,
+        user_id: Uuid,
+        req: &TriageInboxRequest,
+    ) -> Result<i64, AppError> {
+        let query = match req.action {
+            TriageAction::Read | TriageAction::Unread => sqlx::query(INBOX_TRIAGE_READ)
+                .bind(user_id)
+                .bind(&req.ids)
+                .bind(req.action == TriageAction::Read),
+            TriageAction::Archive | TriageAction::Unarchive => sqlx::query(INBOX_TRIAGE_ARCHIVE)
+                .bind(user_id)
+                .bind(&req.ids)
+                .bind(req.action == TriageAction::Archive),
+            TriageAction::Process => sqlx::query(INBOX_TRIAGE_PROCESS)
+                .bind(user_id)
+                .bind(&req.ids),
+            TriageAction::Delete => sqlx::query(INBOX_TRIAGE_DELETE)
+                .bind(user_id)
+                .bind(&req.ids),
+        };
+        let result = query.execute(db).await?;
+
+        Ok(result.rows_affected() as i64)
+    }
+
+    /// Archive every unprocessed item past its expiry; returns how many
+    pub async fn expire(db: &Pool<Postgres>) -> Result<u64, AppError> {
+        let result = sqlx::query(INBOX_EXPIRE).execute(db).await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+fn to_json<T: Serialize>(value: T) -> Result<serde_json::Value, AppError> {
+    serde_json::to_value(value).map_err(|e| AppError::Internal(e.to_string()))
 }
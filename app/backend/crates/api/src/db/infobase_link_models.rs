@@ -0,0 +1,79 @@
+//! Infobase Link Models
+//!
+//! Wiki-style links from infobase entries to other entries, ideas and tracks.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// What a link points at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkTarget {
+    /// `[[Entry Title]]`
+    Entry(String),
+    /// `[[idea:<uuid>]]`
+    Idea(Uuid),
+    /// `[[track:<uuid>]]`
+    Track(Uuid),
+}
+
+impl LinkTarget {
+    /// `target_type` column value
+    pub fn target_type(&self) -> &'static str {
+        match self {
+            LinkTarget::Entry(_) => "entry",
+            LinkTarget::Idea(_) => "idea",
+            LinkTarget::Track(_) => "track",
+        }
+    }
+}
+
+/// A link found in entry content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedLink {
+    /// Text between the brackets, trimmed
+    pub raw: String,
+    pub target: LinkTarget,
+}
+
+/// Entry linking to another entry
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Backlink {
+    pub id: Uuid,
+    pub title: String,
+    pub category: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Link whose target does not exist
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct BrokenLink {
+    pub source_id: Uuid,
+    pub source_title: String,
+    pub target_type: String,
+    pub raw: String,
+}
+
+/// Node of the knowledge graph
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct GraphNode {
+    pub id: Uuid,
+    /// `entry`, `idea` or `track`
+    pub node_type: String,
+    pub title: String,
+}
+
+/// Resolved link between two graph nodes
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct GraphEdge {
+    pub source: Uuid,
+    pub target: Uuid,
+}
+
+/// Knowledge graph response
+#[derive(Debug, Clone, Serialize)]
+pub struct InfobaseGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
@@ -0,0 +1,408 @@
+//! Infobase Link Repository
+//!
+//! Parses `[[...]]` links out of entry content and keeps `infobase_links` in
+//! step with it. `InfobaseRepo` calls the transactional helpers here whenever
+//! an entry is created, edited, renamed or deleted.
+
+use std::collections::HashSet;
+
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use super::infobase_link_models::*;
+use super::platform_models::InfobaseEntry;
+use super::revision_models::{NewRevision, RevisionEntity};
+use super::revision_repos::RevisionRepo;
+use crate::error::AppError;
+
+/// Longest title a link may name
+const MAX_LINK_TITLE_LEN: usize = 200;
+
+/// Spans of `[[...]]` links in `content`: (start, end, inner text)
+///
+/// A link cannot span lines or contain brackets; `[[[Title]]` links
+/// `Title`.
+fn link_spans(content: &str) -> Vec<(usize, usize, &str)> {
+    let mut spans = Vec::new();
+    let mut from = 0;
+    while let Some(open) = content[from..].find("[[").map(|i| from + i) {
+        let inner_start = open + 2;
+        let Some(close) = content[inner_start..].find("]]").map(|i| inner_start + i) else {
+            break;
+        };
+        let inner = &content[inner_start..close];
+        if inner.contains(['[', ']', '\n']) {
+            from = open + 1;
+            continue;
+        }
+        spans.push((open, close + 2, inner));
+        from = close + 2;
+    }
+    spans
+}
+
+/// Target of the text between brackets
+fn link_target(raw: &str) -> Option<LinkTarget> {
+    if raw.is_empty() || raw.len() > MAX_LINK_TITLE_LEN {
+        return None;
+    }
+    if let Some((kind, id)) = raw.split_once(':') {
+        if let Ok(id) = Uuid::parse_str(id.trim()) {
+            if kind.eq_ignore_ascii_case("idea") {
+                return Some(LinkTarget::Idea(id));
+            }
+            if kind.eq_ignore_ascii_case("track") {
+                return Some(LinkTarget::Track(id));
+            }
+        }
+    }
+    Some(LinkTarget::Entry(raw.to_string()))
+}
+
+/// Every distinct link in `content`, in order of first appearance
+pub fn parse_links(content: &str) -> Vec<ParsedLink> {
+    let mut seen = HashSet::new();
+    link_spans(content)
+        .into_iter()
+        .filter_map(|(_, _, inner)| {
+            let raw = inner.trim();
+            let target = link_target(raw)?;
+            seen.insert((target.target_type(), raw.to_lowercase()))
+                .then(|| ParsedLink {
+                    raw: raw.to_string(),
+                    target,
+                })
+        })
+        .collect()
+}
+
+/// Point `[[old_title]]` links in `content` at `new_title`
+pub fn rewrite_links(content: &str, old_title: &str, new_title: &str) -> String {
+    let mut rewritten = String::with_capacity(content.len());
+    let mut last = 0;
+    for (start, end, inner) in link_spans(content) {
+        if inner.trim().to_lowercase() == old_title.trim().to_lowercase() {
+            rewritten.push_str(&content[last..start]);
+            rewritten.push_str("[[");
+            rewritten.push_str(new_title);
+            rewritten.push_str("]]");
+            last = end;
+        }
+    }
+    rewritten.push_str(&content[last..]);
+    rewritten
+}
+
+pub struct InfobaseLinkRepo;
+
+pub const INFOBASE_LINK_DELETE_FOR_SOURCE: &str = "DELETE FROM infobase_links WHERE source_id = $1";
+
+/// Entry titles resolve to the user's oldest entry of that title
+pub const INFOBASE_LINK_INSERT: &str = r#"
+    INSERT INTO infobase_links (user_id, source_id, target_type, target_id, target_title, raw)
+    SELECT $1, $2, $3,
+           CASE WHEN $3 = 'entry' THEN (
+               SELECT id FROM infobase_entries
+               WHERE user_id = $1 AND lower(title) = lower($5)
+               ORDER BY created_at, id
+               LIMIT 1
+           ) ELSE $4 END,
+           $5, $6
+    ON CONFLICT DO NOTHING
+"#;
+
+pub const INFOBASE_LINK_RESOLVE_TITLE: &str = r#"
+    UPDATE infobase_links
+    SET target_id = $2
+    WHERE user_id = $1 AND target_type = 'entry' AND target_id IS NULL
+      AND lower(target_title) = lower($3)
+"#;
+
+/// After an entry is deleted, its inbound links fall to the next entry with
+/// the same title, or become broken
+pub const INFOBASE_LINK_RETARGET_DELETED: &str = r#"
+    UPDATE infobase_links l
+    SET target_id = (
+        SELECT e.id FROM infobase_entries e
+        WHERE e.user_id = l.user_id AND lower(e.title) = lower(l.target_title)
+        ORDER BY e.created_at, e.id
+        LIMIT 1
+    )
+    WHERE l.target_type = 'entry' AND l.target_id = $1
+"#;
+
+/// Entries linking to `$1`, locked for the rewrite
+pub const INFOBASE_LINK_INBOUND_SOURCES: &str = r#"
+    SELECT id, user_id, title, content, category, tags, version, created_at, updated_at
+    FROM infobase_entries
+    WHERE id IN (
+        SELECT source_id FROM infobase_links
+        WHERE target_type = 'entry' AND target_id = $1 AND source_id <> $1
+    )
+    ORDER BY id
+    FOR UPDATE
+"#;
+
+/// Saves rewritten content as the entry's next version
+pub const INFOBASE_LINK_SET_CONTENT: &str = r#"
+    UPDATE infobase_entries
+    SET content = $2, version = version + 1, updated_at = NOW()
+    WHERE id = $1
+    RETURNING version
+"#;
+
+/// A source that already links the new title keeps that link; its link
+/// under the old title would otherwise collide with it on rename
+pub const INFOBASE_LINK_DROP_RENAME_DUPLICATES: &str = r#"
+    DELETE FROM infobase_links l
+    WHERE l.target_type = 'entry' AND l.target_id = $1 AND l.source_id <> $1
+      AND EXISTS (
+          SELECT 1 FROM infobase_links d
+          WHERE d.source_id = l.source_id AND d.target_type = 'entry'
+            AND lower(d.raw) = lower($2) AND d.id <> l.id
+      )
+"#;
+
+pub const INFOBASE_LINK_RENAME_TARGET: &str = r#"
+    UPDATE infobase_links
+    SET target_title = $2, raw = $2
+    WHERE target_type = 'entry' AND target_id = $1 AND source_id <> $1
+"#;
+
+pub const INFOBASE_LINK_BACKLINKS: &str = r#"
+    SELECT DISTINCT e.id, e.title, e.category, e.updated_at
+    FROM infobase_links l
+    JOIN infobase_entries e ON e.id = l.source_id
+    WHERE l.user_id = $1 AND l.target_type = 'entry' AND l.target_id = $2
+    ORDER BY e.updated_at DESC
+"#;
+
+pub const INFOBASE_LINK_BROKEN: &str = r#"
+    SELECT l.source_id, e.title AS source_title, l.target_type, l.raw
+    FROM infobase_links l
+    JOIN infobase_entries e ON e.id = l.source_id
+    WHERE l.user_id = $1
+      AND (l.target_id IS NULL
+           OR (l.target_type = 'idea' AND NOT EXISTS (
+                   SELECT 1 FROM ideas i WHERE i.id = l.target_id AND i.user_id = $1))
+           OR (l.target_type = 'track' AND NOT EXISTS (
+                   SELECT 1 FROM reference_tracks t WHERE t.id = l.target_id AND t.user_id = $1)))
+    ORDER BY e.title, l.raw
+"#;
+
+pub const INFOBASE_LINK_GRAPH_NODES: &str = r#"
+    SELECT id, 'entry' AS node_type, title FROM infobase_entries WHERE user_id = $1
+    UNION ALL
+    SELECT i.id, 'idea', i.title FROM ideas i
+    WHERE i.user_id = $1 AND EXISTS (
+        SELECT 1 FROM infobase_links l WHERE l.target_type = 'idea' AND l.target_id = i.id)
+    UNION ALL
+    SELECT t.id, 'track', t.title FROM reference_tracks t
+    WHERE t.user_id = $1 AND EXISTS (
+        SELECT 1 FROM infobase_links l WHERE l.target_type = 'track' AND l.target_id = t.id)
+"#;
+
+pub const INFOBASE_LINK_GRAPH_EDGES: &str = r#"
+    SELECT DISTINCT l.source_id AS source, l.target_id AS target
+    FROM infobase_links l
+    WHERE l.user_id = $1 AND l.target_id IS NOT NULL
+      AND (l.target_type = 'entry'
+           OR (l.target_type = 'idea' AND EXISTS (
+                   SELECT 1 FROM ideas i WHERE i.id = l.target_id AND i.user_id = $1))
+           OR (l.target_type = 'track' AND EXISTS (
+                   SELECT 1 FROM reference_tracks t WHERE t.id = l.target_id AND t.user_id = $1)))
+"#;
+
+impl InfobaseLinkRepo {
+    /// Replace an entry's outbound links with those in its content
+    pub async fn replace_for_source(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        source_id: Uuid,
+        content: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query(INFOBASE_LINK_DELETE_FOR_SOURCE)
+            .bind(source_id)
+            .execute(&mut *conn)
+            .await?;
+
+        for link in parse_links(content) {
+            let (target_id, target_title) = match &link.target {
+                LinkTarget::Entry(title) => (None, Some(title.as_str())),
+                LinkTarget::Idea(id) | LinkTarget::Track(id) => (Some(*id), None),
+            };
+            sqlx::query(INFOBASE_LINK_INSERT)
+                .bind(user_id)
+                .bind(source_id)
+                .bind(link.target.target_type())
+                .bind(target_id)
+                .bind(target_title)
+                .bind(&link.raw)
+                .execute(&mut *conn)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Point dangling links naming `title` at a new or renamed entry
+    pub async fn resolve_title(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        entry_id: Uuid,
+        title: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query(INFOBASE_LINK_RESOLVE_TITLE)
+            .bind(user_id)
+            .bind(entry_id)
+            .bind(title)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Rewrite other entries' links to a renamed entry
+    ///
+    /// Each rewritten entry gets a new version and revision by `author_id`,
+    /// so clients holding the old version see a conflict instead of
+    /// overwriting the rewrite.
+    pub async fn rename_target(
+        conn: &mut PgConnection,
+        author_id: Uuid,
+        entry_id: Uuid,
+        old_title: &str,
+        new_title: &str,
+    ) -> Result<(), AppError> {
+        let sources = sqlx::query_as::<_, InfobaseEntry>(INFOBASE_LINK_INBOUND_SOURCES)
+            .bind(entry_id)
+            .fetch_all(&mut *conn)
+            .await?;
+        for source in sources {
+            let content = rewrite_links(&source.content, old_title, new_title);
+            if content == source.content {
+                continue;
+            }
+            let version: i32 = sqlx::query_scalar(INFOBASE_LINK_SET_CONTENT)
+                .bind(source.id)
+                .bind(&content)
+                .fetch_one(&mut *conn)
+                .await?;
+            let tags = source.tags.unwrap_or_default();
+            let revision = NewRevision {
+                version,
+                title: &source.title,
+                content: Some(&content),
+                category: Some(&source.category),
+                tags: &tags,
+            };
+            RevisionRepo::record(
+                &mut *conn,
+                source.user_id,
+                RevisionEntity::Infobase,
+                source.id,
+                author_id,
+                &revision,
+            )
+            .await?;
+        }
+
+        sqlx::query(INFOBASE_LINK_DROP_RENAME_DUPLICATES)
+            .bind(entry_id)
+            .bind(new_title)
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query(INFOBASE_LINK_RENAME_TARGET)
+            .bind(entry_id)
+            .bind(new_title)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Move links to a deleted entry to its namesake, if any
+    pub async fn retarget_deleted(conn: &mut PgConnection, entry_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(INFOBASE_LINK_RETARGET_DELETED)
+            .bind(entry_id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Entries linking to an entry, most recently edited first
+    pub async fn backlinks(
+        pool: &PgPool,
+        user_id: Uuid,
+        entry_id: Uuid,
+    ) -> Result<Vec<Backlink>, AppError> {
+        let backlinks = sqlx::query_as::<_, Backlink>(INFOBASE_LINK_BACKLINKS)
+            .bind(user_id)
+            .bind(entry_id)
+            .fetch_all(pool)
+            .await?;
+        Ok(backlinks)
+    }
+
+    /// Links whose entry, idea or track does not exist
+    pub async fn broken(pool: &PgPool, user_id: Uuid) -> Result<Vec<BrokenLink>, AppError> {
+        let links = sqlx::query_as::<_, BrokenLink>(INFOBASE_LINK_BROKEN)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+        Ok(links)
+    }
+
+    /// Every entry, the ideas and tracks they link to, and the links
+    pub async fn graph(pool: &PgPool, user_id: Uuid) -> Result<InfobaseGraph, AppError> {
+        let nodes = sqlx::query_as::<_, GraphNode>(INFOBASE_LINK_GRAPH_NODES)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+        let edges = sqlx::query_as::<_, GraphEdge>(INFOBASE_LINK_GRAPH_EDGES)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+        Ok(InfobaseGraph { nodes, edges })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_links() {
+        let idea = Uuid::new_v4();
+        let content = format!(
+            "See [[ Mixing Basics ]], [[idea:{}]] and [[mixing basics]] again.\n\
+             Not links: [[]], [[a]b]], [[split\nline]], [[track:nope]]",
+            idea
+        );
+
+        assert_eq!(
+            parse_links(&content),
+            vec![
+                ParsedLink {
+                    raw: "Mixing Basics".to_string(),
+                    target: LinkTarget::Entry("Mixing Basics".to_string()),
+                },
+                ParsedLink {
+                    raw: format!("idea:{}", idea),
+                    target: LinkTarget::Idea(idea),
+                },
+                // Not a UUID, so an entry title
+                ParsedLink {
+                    raw: "track:nope".to_string(),
+                    target: LinkTarget::Entry("track:nope".to_string()),
+                },
+            ]
+        );
+        assert_eq!(parse_links("[[[Nested]]")[0].raw, "Nested");
+    }
+
+    #[test]
+    fn test_rewrite_links() {
+        assert_eq!(
+            rewrite_links("[[Old]] and [[ old ]] but not [[Older]]", "Old", "New"),
+            "[[New]] and [[New]] but not [[Older]]"
+        );
+    }
+}
@@ -123,6 +123,39 @@ pub struct UserDrillStats {
     pub total_time_seconds: i32,
 }
 
+/// Server-scored drill session
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DrillSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub drill_id: Uuid,
+    /// SHA-256 hex of the token, except as returned from `start`
+    pub token: String,
+    pub drill_type: String,
+    pub config_json: serde_json::Value,
+    pub seed: i64,
+    pub difficulty_level: i32,
+    pub question_count: i32,
+    pub status: String,
+    pub score: Option<i32>,
+    pub correct_count: Option<i32>,
+    pub started_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Recorded answer within a drill session
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DrillSessionAnswer {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub question_index: i32,
+    pub answer: String,
+    pub is_correct: bool,
+    pub response_ms: i32,
+    pub answered_at: DateTime<Utc>,
+}
+
 // ============================================================================
 // REQUEST MODELS
 // ============================================================================
@@ -150,6 +183,15 @@ pub struct SubmitDrillRequest {
     pub time_seconds: i32,
 }
 
+/// Answer a question in a drill session
+#[derive(Debug, Deserialize)]
+pub struct DrillAnswerRequest {
+    pub question_index: i32,
+    pub answer: String,
+    /// Client-measured time from audio start to answer
+    pub response_ms: i32,
+}
+
 // ============================================================================
 // RESPONSE MODELS
 // ============================================================================
@@ -263,6 +305,66 @@ pub struct DrillResultResponse {
     pub new_streak: i32,
 }
 
+/// Question as shown to the client (no answer, no audio parameters)
+#[derive(Serialize)]
+pub struct DrillQuestionView {
+    pub index: i32,
+    pub prompt: String,
+    pub choices: Vec<String>,
+    /// "choice" or "tempo"
+    pub input_type: String,
+    pub audio_url: String,
+    pub time_limit_ms: Option<u32>,
+}
+
+/// Started drill session
+#[derive(Serialize)]
+pub struct DrillSessionResponse {
+    pub session_token: String,
+    pub drill_id: Uuid,
+    pub drill_type: String,
+    pub difficulty_level: i32,
+    pub expires_at: DateTime<Utc>,
+    pub questions: Vec<DrillQuestionView>,
+}
+
+/// Result of answering one question
+#[derive(Serialize)]
+pub struct DrillAnswerResult {
+    pub question_index: i32,
+    pub correct: bool,
+    pub expected: String,
+    pub response_ms: i32,
+    pub answered_count: i64,
+    pub question_count: i32,
+}
+
+/// Per-question review in a completed session
+#[derive(Serialize)]
+pub struct DrillAnswerReview {
+    pub question_index: i32,
+    pub answer: Option<String>,
+    pub expected: String,
+    pub correct: bool,
+    pub response_ms: Option<i32>,
+}
+
+/// Completed drill session
+#[derive(Serialize)]
+pub struct DrillSessionResult {
+    pub drill_id: Uuid,
+    pub score: i32,
+    pub correct_count: i32,
+    pub total_count: i32,
+    pub time_seconds: i32,
+    pub difficulty_level: i32,
+    pub xp_awarded: i32,
+    pub is_new_best: bool,
+    pub streak_continued: bool,
+    pub new_streak: i32,
+    pub answers: Vec<DrillAnswerReview>,
+}
+
 /// Review items response
 #[derive(Serialize)]
 pub struct ReviewItemsResponse {
@@ -2,12 +2,19 @@
 //!
 //! Database operations for learning system.
 
+use chrono::{Duration, Utc};
+use rand::Rng;
 use sqlx::{FromRow, PgPool};
 use uuid::Uuid;
 
 use crate::error::AppError;
+use crate::services::drills::{self, DrillConfig, DrillKind, DrillQuestion};
+use crate::shared::auth::csrf::verify_token;
 
+use super::gamification_models::AwardPointsInput;
+use super::gamification_repos::GamificationRepo;
 use super::learn_models::*;
+use super::repos::{generate_session_token, hash_session_token};
 
 // ============================================================================
 // TOPIC REPOSITORY
@@ -15,6 +22,132 @@ use super::learn_models::*;
 
 pub struct LearnRepo;
 
+pub const LEARN_LIST_TOPICS: &str = r#"
+    SELECT t.id, t.key, t.name, t.description, t.category, t.icon,
+           COUNT(DISTINCT l.id) as lesson_count,
+           COUNT(DISTINCT CASE WHEN p.status = 'completed' THEN l.id END) as completed_count
+    FROM learn_topics t
+    LEFT JOIN learn_lessons l ON l.topic_id = t.id
+    LEFT JOIN user_lesson_progress p ON p.lesson_id = l.id AND p.user_id = $1
+    GROUP BY t.id, t.key, t.name, t.description, t.category, t.icon
+    ORDER BY t.sort_order
+"#;
+
+pub const LEARN_LIST_LESSONS: &str = r#"
+    SELECT l.id, l.topic_id, l.key, l.title, l.description,
+           l.duration_minutes, l.difficulty, l.xp_reward, l.coin_reward,
+           l.quiz_json IS NOT NULL as has_quiz,
+           l.audio_r2_key IS NOT NULL as has_audio,
+           COALESCE(p.status, 'not_started') as status
+    FROM learn_lessons l
+    LEFT JOIN user_lesson_progress p ON p.lesson_id = l.id AND p.user_id = $1
+    WHERE l.topic_id = $2
+    ORDER BY l.sort_order
+"#;
+
+pub const LEARN_GET_LESSON_CONTENT: &str = r#"
+    SELECT l.id, l.title, l.content_markdown, l.quiz_json, l.audio_r2_key,
+           p.status, p.started_at, p.completed_at, p.quiz_score, p.attempts
+    FROM learn_lessons l
+    LEFT JOIN user_lesson_progress p ON p.lesson_id = l.id AND p.user_id = $1
+    WHERE l.id = $2
+"#;
+
+pub const LEARN_START_LESSON_LEARN_LESSONS: &str = "SELECT id FROM learn_lessons WHERE id = $1";
+
+pub const LEARN_START_LESSON_PROGRESS: &str = r#"
+    INSERT INTO user_lesson_progress (user_id, lesson_id, status, started_at)
+    VALUES ($1, $2, 'in_progress', NOW())
+    ON CONFLICT (user_id, lesson_id)
+    DO UPDATE SET status = 'in_progress', started_at = COALESCE(user_lesson_progress.started_at, NOW())
+    RETURNING status, started_at, completed_at, quiz_score, attempts
+"#;
+
+pub const LEARN_COMPLETE_LESSON_LESSON: &str =
+    "SELECT id, xp_reward, coin_reward FROM learn_lessons WHERE id = $1";
+
+pub const LEARN_COMPLETE_LESSON_USER_LESSON_PROGRESS: &str =
+    "SELECT completed_at FROM user_lesson_progress WHERE user_id = $1 AND lesson_id = $2";
+
+pub const LEARN_COMPLETE_LESSON_INSERT_USER_LESSON_PROGRESS: &str = r#"
+    INSERT INTO user_lesson_progress (user_id, lesson_id, status, started_at, completed_at, quiz_score, attempts)
+    VALUES ($1, $2, 'completed', NOW(), NOW(), $3, 1)
+    ON CONFLICT (user_id, lesson_id)
+    DO UPDATE SET
+        status = 'completed',
+        completed_at = COALESCE(user_lesson_progress.completed_at, NOW()),
+        quiz_score = COALESCE($3, user_lesson_progress.quiz_score),
+        attempts = user_lesson_progress.attempts + 1
+"#;
+
+pub const LEARN_LIST_DRILLS: &str = r#"
+    SELECT d.id, d.topic_id, d.key, d.title, d.description,
+           d.drill_type, d.difficulty, d.duration_seconds, d.xp_reward,
+           s.best_score, s.current_streak
+    FROM learn_drills d
+    LEFT JOIN user_drill_stats s ON s.drill_id = d.id AND s.user_id = $1
+    WHERE d.topic_id = $2
+    ORDER BY d.sort_order
+"#;
+
+pub const LEARN_SUBMIT_DRILL_DRILL: &str =
+    "SELECT drill_type, xp_reward FROM learn_drills WHERE id = $1";
+
+pub const LEARN_SUBMIT_DRILL_USER_DRILL_STATS: &str =
+    "SELECT best_score, current_streak FROM user_drill_stats WHERE user_id = $1 AND drill_id = $2";
+
+pub const LEARN_SUBMIT_DRILL_INSERT_USER_DRILL_STATS: &str = r#"
+    INSERT INTO user_drill_stats (user_id, drill_id, total_attempts, correct_answers, best_score,
+                                  current_streak, last_attempt_at, total_time_seconds)
+    VALUES ($1, $2, 1, $3, $4, $5, NOW(), $6)
+    ON CONFLICT (user_id, drill_id)
+    DO UPDATE SET
+        total_attempts = user_drill_stats.total_attempts + 1,
+        correct_answers = user_drill_stats.correct_answers + $3,
+        best_score = GREATEST(user_drill_stats.best_score, $4),
+        current_streak = $5,
+        last_attempt_at = NOW(),
+        total_time_seconds = user_drill_stats.total_time_seconds + $6
+"#;
+
+pub const LEARN_GET_REVIEW_ITEMS_LESSONS_DUE: &str = r#"
+    SELECT l.id, l.topic_id, l.key, l.title, l.description,
+           l.duration_minutes, l.difficulty, l.xp_reward, l.coin_reward,
+           l.quiz_json IS NOT NULL as has_quiz,
+           l.audio_r2_key IS NOT NULL as has_audio
+    FROM learn_lessons l
+    JOIN user_lesson_progress p ON p.lesson_id = l.id AND p.user_id = $1
+    WHERE p.status = 'completed'
+      AND p.completed_at < NOW() - INTERVAL '7 days'
+    ORDER BY p.completed_at
+    LIMIT 5
+"#;
+
+pub const LEARN_GET_REVIEW_ITEMS_DRILLS_DUE: &str = r#"
+    SELECT d.id, d.topic_id, d.key, d.title, d.description,
+           d.drill_type, d.difficulty, d.duration_seconds, d.xp_reward,
+           s.best_score, s.current_streak
+    FROM learn_drills d
+    JOIN user_drill_stats s ON s.drill_id = d.id AND s.user_id = $1
+    WHERE s.last_attempt_at < NOW() - INTERVAL '3 days'
+    ORDER BY s.last_attempt_at
+    LIMIT 5
+"#;
+
+pub const LEARN_GET_PROGRESS_SUMMARY_STATS: &str = r#"
+    SELECT
+        COUNT(DISTINCT l.topic_id) as topics_started,
+        COUNT(*) FILTER (WHERE p.status = 'completed') as lessons_completed
+    FROM user_lesson_progress p
+    JOIN learn_lessons l ON p.lesson_id = l.id
+    WHERE p.user_id = $1
+"#;
+
+pub const LEARN_GET_PROGRESS_SUMMARY_TOTAL_LESSONS: &str = "SELECT COUNT(*) FROM learn_lessons";
+
+pub const LEARN_GET_PROGRESS_SUMMARY_DRILLS_PRACTICED: &str =
+    "SELECT COUNT(DISTINCT drill_id) FROM user_drill_stats WHERE user_id = $1";
+
 impl LearnRepo {
     /// List all topics with user progress
     pub async fn list_topics(pool: &PgPool, user_id: Uuid) -> Result<TopicsListResponse, AppError> {
@@ -30,21 +163,10 @@ impl LearnRepo {
             completed_count: Option<i64>,
         }
 
-        let topics = sqlx::query_as::<_, TopicRow>(
-            r#"
-            SELECT t.id, t.key, t.name, t.description, t.category, t.icon,
-                   COUNT(DISTINCT l.id) as lesson_count,
-                   COUNT(DISTINCT CASE WHEN p.status = 'completed' THEN l.id END) as completed_count
-            FROM learn_topics t
-            LEFT JOIN learn_lessons l ON l.topic_id = t.id
-            LEFT JOIN user_lesson_progress p ON p.lesson_id = l.id AND p.user_id = $1
-            GROUP BY t.id, t.key, t.name, t.description, t.category, t.icon
-            ORDER BY t.sort_order
-            "#,
-        )
-        .bind(user_id)
-        .fetch_all(pool)
-        .await?;
+        let topics = sqlx::query_as::<_, TopicRow>(LEARN_LIST_TOPICS)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
 
         let total = topics.len() as i64;
 
@@ -88,23 +210,11 @@ impl LearnRepo {
             status: Option<String>,
         }
 
-        let lessons = sqlx::query_as::<_, LessonRow>(
-            r#"
-            SELECT l.id, l.topic_id, l.key, l.title, l.description,
-                   l.duration_minutes, l.difficulty, l.xp_reward, l.coin_reward,
-                   l.quiz_json IS NOT NULL as has_quiz,
-                   l.audio_r2_key IS NOT NULL as has_audio,
-                   COALESCE(p.status, 'not_started') as status
-            FROM learn_lessons l
-            LEFT JOIN user_lesson_progress p ON p.lesson_id = l.id AND p.user_id = $1
-            WHERE l.topic_id = $2
-            ORDER BY l.sort_order
-            "#,
-        )
-        .bind(user_id)
-        .bind(topic_id)
-        .fetch_all(pool)
-        .await?;
+        let lessons = sqlx::query_as::<_, LessonRow>(LEARN_LIST_LESSONS)
+            .bind(user_id)
+            .bind(topic_id)
+            .fetch_all(pool)
+            .await?;
 
         let total = lessons.len() as i64;
 
@@ -150,19 +260,11 @@ impl LearnRepo {
             attempts: Option<i32>,
         }
 
-        let lesson = sqlx::query_as::<_, LessonContentRow>(
-            r#"
-            SELECT l.id, l.title, l.content_markdown, l.quiz_json, l.audio_r2_key,
-                   p.status, p.started_at, p.completed_at, p.quiz_score, p.attempts
-            FROM learn_lessons l
-            LEFT JOIN user_lesson_progress p ON p.lesson_id = l.id AND p.user_id = $1
-            WHERE l.id = $2
-            "#,
-        )
-        .bind(user_id)
-        .bind(lesson_id)
-        .fetch_optional(pool)
-        .await?;
+        let lesson = sqlx::query_as::<_, LessonContentRow>(LEARN_GET_LESSON_CONTENT)
+            .bind(user_id)
+            .bind(lesson_id)
+            .fetch_optional(pool)
+            .await?;
 
         Ok(lesson.map(|l| LessonContentResponse {
             id: l.id,
@@ -189,7 +291,7 @@ impl LearnRepo {
         lesson_id: Uuid,
     ) -> Result<LessonProgressInfo, AppError> {
         // Check if lesson exists
-        let exists: Option<Uuid> = sqlx::query_scalar("SELECT id FROM learn_lessons WHERE id = $1")
+        let exists: Option<Uuid> = sqlx::query_scalar(LEARN_START_LESSON_LEARN_LESSONS)
             .bind(lesson_id)
             .fetch_optional(pool)
             .await?;
@@ -208,19 +310,11 @@ impl LearnRepo {
             attempts: i32,
         }
 
-        let progress = sqlx::query_as::<_, ProgressRow>(
-            r#"
-            INSERT INTO user_lesson_progress (user_id, lesson_id, status, started_at)
-            VALUES ($1, $2, 'in_progress', NOW())
-            ON CONFLICT (user_id, lesson_id)
-            DO UPDATE SET status = 'in_progress', started_at = COALESCE(user_lesson_progress.started_at, NOW())
-            RETURNING status, started_at, completed_at, quiz_score, attempts
-            "#,
-        )
-        .bind(user_id)
-        .bind(lesson_id)
-        .fetch_one(pool)
-        .await?;
+        let progress = sqlx::query_as::<_, ProgressRow>(LEARN_START_LESSON_PROGRESS)
+            .bind(user_id)
+            .bind(lesson_id)
+            .fetch_one(pool)
+            .await?;
 
         Ok(LessonProgressInfo {
             status: progress.status,
@@ -245,12 +339,10 @@ impl LearnRepo {
             coin_reward: i32,
         }
 
-        let lesson = sqlx::query_as::<_, LessonRewards>(
-            "SELECT id, xp_reward, coin_reward FROM learn_lessons WHERE id = $1",
-        )
-        .bind(req.lesson_id)
-        .fetch_optional(pool)
-        .await?;
+        let lesson = sqlx::query_as::<_, LessonRewards>(LEARN_COMPLETE_LESSON_LESSON)
+            .bind(req.lesson_id)
+            .fetch_optional(pool)
+            .await?;
 
         let lesson = lesson.ok_or_else(|| AppError::NotFound("Lesson not found".to_string()))?;
 
@@ -260,34 +352,22 @@ impl LearnRepo {
             completed_at: Option<chrono::DateTime<chrono::Utc>>,
         }
 
-        let existing = sqlx::query_as::<_, CompletedCheck>(
-            "SELECT completed_at FROM user_lesson_progress WHERE user_id = $1 AND lesson_id = $2",
-        )
-        .bind(user_id)
-        .bind(req.lesson_id)
-        .fetch_optional(pool)
-        .await?;
+        let existing =
+            sqlx::query_as::<_, CompletedCheck>(LEARN_COMPLETE_LESSON_USER_LESSON_PROGRESS)
+                .bind(user_id)
+                .bind(req.lesson_id)
+                .fetch_optional(pool)
+                .await?;
 
         let is_first_completion = existing.map_or(true, |e| e.completed_at.is_none());
 
         // Upsert progress
-        sqlx::query(
-            r#"
-            INSERT INTO user_lesson_progress (user_id, lesson_id, status, started_at, completed_at, quiz_score, attempts)
-            VALUES ($1, $2, 'completed', NOW(), NOW(), $3, 1)
-            ON CONFLICT (user_id, lesson_id)
-            DO UPDATE SET
-                status = 'completed',
-                completed_at = COALESCE(user_lesson_progress.completed_at, NOW()),
-                quiz_score = COALESCE($3, user_lesson_progress.quiz_score),
-                attempts = user_lesson_progress.attempts + 1
-            "#,
-        )
-        .bind(user_id)
-        .bind(req.lesson_id)
-        .bind(req.quiz_score)
-        .execute(pool)
-        .await?;
+        sqlx::query(LEARN_COMPLETE_LESSON_INSERT_USER_LESSON_PROGRESS)
+            .bind(user_id)
+            .bind(req.lesson_id)
+            .bind(req.quiz_score)
+            .execute(pool)
+            .await?;
 
         // Award XP/coins only on first completion
         let (xp_awarded, coins_awarded) = if is_first_completion {
@@ -326,21 +406,11 @@ impl LearnRepo {
             current_streak: Option<i32>,
         }
 
-        let drills = sqlx::query_as::<_, DrillRow>(
-            r#"
-            SELECT d.id, d.topic_id, d.key, d.title, d.description,
-                   d.drill_type, d.difficulty, d.duration_seconds, d.xp_reward,
-                   s.best_score, s.current_streak
-            FROM learn_drills d
-            LEFT JOIN user_drill_stats s ON s.drill_id = d.id AND s.user_id = $1
-            WHERE d.topic_id = $2
-            ORDER BY d.sort_order
-            "#,
-        )
-        .bind(user_id)
-        .bind(topic_id)
-        .fetch_all(pool)
-        .await?;
+        let drills = sqlx::query_as::<_, DrillRow>(LEARN_LIST_DRILLS)
+            .bind(user_id)
+            .bind(topic_id)
+            .fetch_all(pool)
+            .await?;
 
         let total = drills.len() as i64;
 
@@ -374,18 +444,25 @@ impl LearnRepo {
         // Get drill
         #[derive(FromRow)]
         struct DrillInfo {
-            id: Uuid,
+            drill_type: String,
             xp_reward: i32,
         }
 
-        let drill =
-            sqlx::query_as::<_, DrillInfo>("SELECT id, xp_reward FROM learn_drills WHERE id = $1")
-                .bind(req.drill_id)
-                .fetch_optional(pool)
-                .await?;
+        let drill = sqlx::query_as::<_, DrillInfo>(LEARN_SUBMIT_DRILL_DRILL)
+            .bind(req.drill_id)
+            .fetch_optional(pool)
+            .await?;
 
         let drill = drill.ok_or_else(|| AppError::NotFound("Drill not found".to_string()))?;
 
+        // Server-scored drills must go through a drill session
+        if DrillKind::from_drill_type(&drill.drill_type).is_some() {
+            return Err(AppError::BadRequest(format!(
+                "Drills of type '{}' are scored by the server; start a drill session instead",
+                drill.drill_type
+            )));
+        }
+
         // Get existing stats
         #[derive(FromRow)]
         struct StatsRow {
@@ -393,13 +470,11 @@ impl LearnRepo {
             current_streak: Option<i32>,
         }
 
-        let existing = sqlx::query_as::<_, StatsRow>(
-            "SELECT best_score, current_streak FROM user_drill_stats WHERE user_id = $1 AND drill_id = $2",
-        )
-        .bind(user_id)
-        .bind(req.drill_id)
-        .fetch_optional(pool)
-        .await?;
+        let existing = sqlx::query_as::<_, StatsRow>(LEARN_SUBMIT_DRILL_USER_DRILL_STATS)
+            .bind(user_id)
+            .bind(req.drill_id)
+            .fetch_optional(pool)
+            .await?;
 
         let is_new_best = existing
             .as_ref()
@@ -417,29 +492,15 @@ impl LearnRepo {
         };
 
         // Upsert stats
-        sqlx::query(
-            r#"
-            INSERT INTO user_drill_stats (user_id, drill_id, total_attempts, correct_answers, best_score,
-                                          current_streak, last_attempt_at, total_time_seconds)
-            VALUES ($1, $2, 1, $3, $4, $5, NOW(), $6)
-            ON CONFLICT (user_id, drill_id)
-            DO UPDATE SET
-                total_attempts = user_drill_stats.total_attempts + 1,
-                correct_answers = user_drill_stats.correct_answers + $3,
-                best_score = GREATEST(user_drill_stats.best_score, $4),
-                current_streak = $5,
-                last_attempt_at = NOW(),
-                total_time_seconds = user_drill_stats.total_time_seconds + $6
-            "#,
-        )
-        .bind(user_id)
-        .bind(req.drill_id)
-        .bind(req.correct_count)
-        .bind(req.score)
-        .bind(new_streak)
-        .bind(req.time_seconds)
-        .execute(pool)
-        .await?;
+        sqlx::query(LEARN_SUBMIT_DRILL_INSERT_USER_DRILL_STATS)
+            .bind(user_id)
+            .bind(req.drill_id)
+            .bind(req.correct_count)
+            .bind(req.score)
+            .bind(new_streak)
+            .bind(req.time_seconds)
+            .execute(pool)
+            .await?;
 
         // Award XP based on performance
         let xp_awarded = if req.score >= 80 {
@@ -481,23 +542,10 @@ impl LearnRepo {
             has_audio: Option<bool>,
         }
 
-        let lessons_due = sqlx::query_as::<_, LessonDueRow>(
-            r#"
-            SELECT l.id, l.topic_id, l.key, l.title, l.description,
-                   l.duration_minutes, l.difficulty, l.xp_reward, l.coin_reward,
-                   l.quiz_json IS NOT NULL as has_quiz,
-                   l.audio_r2_key IS NOT NULL as has_audio
-            FROM learn_lessons l
-            JOIN user_lesson_progress p ON p.lesson_id = l.id AND p.user_id = $1
-            WHERE p.status = 'completed'
-              AND p.completed_at < NOW() - INTERVAL '7 days'
-            ORDER BY p.completed_at
-            LIMIT 5
-            "#,
-        )
-        .bind(user_id)
-        .fetch_all(pool)
-        .await?;
+        let lessons_due = sqlx::query_as::<_, LessonDueRow>(LEARN_GET_REVIEW_ITEMS_LESSONS_DUE)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
 
         #[derive(FromRow)]
         struct DrillDueRow {
@@ -514,21 +562,10 @@ impl LearnRepo {
             current_streak: Option<i32>,
         }
 
-        let drills_due = sqlx::query_as::<_, DrillDueRow>(
-            r#"
-            SELECT d.id, d.topic_id, d.key, d.title, d.description,
-                   d.drill_type, d.difficulty, d.duration_seconds, d.xp_reward,
-                   s.best_score, s.current_streak
-            FROM learn_drills d
-            JOIN user_drill_stats s ON s.drill_id = d.id AND s.user_id = $1
-            WHERE s.last_attempt_at < NOW() - INTERVAL '3 days'
-            ORDER BY s.last_attempt_at
-            LIMIT 5
-            "#,
-        )
-        .bind(user_id)
-        .fetch_all(pool)
-        .await?;
+        let drills_due = sqlx::query_as::<_, DrillDueRow>(LEARN_GET_REVIEW_ITEMS_DRILLS_DUE)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
 
         let total_due = (lessons_due.len() + drills_due.len()) as i64;
 
@@ -581,30 +618,21 @@ impl LearnRepo {
             lessons_completed: Option<i64>,
         }
 
-        let stats = sqlx::query_as::<_, StatsRow>(
-            r#"
-            SELECT
-                COUNT(DISTINCT l.topic_id) as topics_started,
-                COUNT(*) FILTER (WHERE p.status = 'completed') as lessons_completed
-            FROM user_lesson_progress p
-            JOIN learn_lessons l ON p.lesson_id = l.id
-            WHERE p.user_id = $1
-            "#,
-        )
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
-
-        let total_lessons: Option<i64> = sqlx::query_scalar("SELECT COUNT(*) FROM learn_lessons")
+        let stats = sqlx::query_as::<_, StatsRow>(LEARN_GET_PROGRESS_SUMMARY_STATS)
+            .bind(user_id)
             .fetch_one(pool)
             .await?;
 
-        let drills_practiced: Option<i64> = sqlx::query_scalar(
-            "SELECT COUNT(DISTINCT drill_id) FROM user_drill_stats WHERE user_id = $1",
-        )
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
+        let total_lessons: Option<i64> =
+            sqlx::query_scalar(LEARN_GET_PROGRESS_SUMMARY_TOTAL_LESSONS)
+                .fetch_one(pool)
+                .await?;
+
+        let drills_practiced: Option<i64> =
+            sqlx::query_scalar(LEARN_GET_PROGRESS_SUMMARY_DRILLS_PRACTICED)
+                .bind(user_id)
+                .fetch_one(pool)
+                .await?;
 
         Ok(LearnProgressSummary {
             topics_started: stats.topics_started.unwrap_or(0),
@@ -617,6 +645,395 @@ impl LearnRepo {
     }
 }
 
+// ============================================================================
+// DRILL SESSION REPOSITORY
+// ============================================================================
+
+/// How long a drill session stays open for answers
+const DRILL_SESSION_TTL_MINUTES: i64 = 30;
+
+pub struct DrillSessionRepo;
+
+pub const DRILL_SESSION_START_DRILL: &str = r#"
+    SELECT id, topic_id, key, title, description, drill_type, config_json, difficulty,
+           COALESCE(duration_seconds, 0) as duration_seconds, xp_reward, sort_order, created_at
+    FROM learn_drills
+    WHERE id = $1 AND is_active = true
+"#;
+
+pub const DRILL_SESSION_START_SESSION: &str = r#"
+    INSERT INTO drill_sessions (user_id, drill_id, token, drill_type, config_json, seed,
+                                difficulty_level, question_count, expires_at)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+    RETURNING id, user_id, drill_id, token, drill_type, config_json, seed, difficulty_level,
+              question_count, status, score, correct_count, started_at, expires_at, completed_at
+"#;
+
+pub const DRILL_SESSION_LOAD: &str = r#"
+    SELECT id, user_id, drill_id, token, drill_type, config_json, seed, difficulty_level,
+           question_count, status, score, correct_count, started_at, expires_at, completed_at
+    FROM drill_sessions
+    WHERE token = $1 AND user_id = $2
+"#;
+
+pub const DRILL_SESSION_ANSWER_LAST_ANSWER_AT: &str =
+    "SELECT MAX(answered_at) FROM drill_session_answers WHERE session_id = $1";
+
+pub const DRILL_SESSION_ANSWER_INSERT_DRILL_SESSION_ANSWERS: &str = r#"
+    INSERT INTO drill_session_answers (session_id, question_index, answer, is_correct, response_ms)
+    VALUES ($1, $2, $3, $4, $5)
+    ON CONFLICT (session_id, question_index) DO NOTHING
+"#;
+
+pub const DRILL_SESSION_ANSWER_ANSWERED_COUNT: &str =
+    "SELECT COUNT(*) FROM drill_session_answers WHERE session_id = $1";
+
+pub const DRILL_SESSION_COMPLETE_ANSWERS: &str = r#"
+    SELECT id, session_id, question_index, answer, is_correct, response_ms, answered_at
+    FROM drill_session_answers
+    WHERE session_id = $1
+    ORDER BY question_index
+"#;
+
+pub const DRILL_SESSION_COMPLETE_UPDATE_DRILL_SESSIONS: &str = r#"
+    UPDATE drill_sessions
+    SET status = 'completed', score = $2, correct_count = $3, completed_at = NOW()
+    WHERE id = $1 AND status = 'active'
+"#;
+
+pub const DRILL_SESSION_COMPLETE_INSERT_USER_DRILL_STATS: &str = r#"
+    INSERT INTO user_drill_stats (user_id, drill_id, total_attempts, correct_answers, best_score,
+                                  average_score, current_streak, best_streak, last_attempt_at,
+                                  total_time_seconds)
+    VALUES ($1, $2, 1, $3, $4::int, $4, $5, $5, NOW(), $6)
+    ON CONFLICT (user_id, drill_id)
+    DO UPDATE SET
+        total_attempts = user_drill_stats.total_attempts + 1,
+        correct_answers = user_drill_stats.correct_answers + $3,
+        best_score = GREATEST(user_drill_stats.best_score, $4),
+        average_score = (user_drill_stats.average_score * user_drill_stats.total_attempts + $4)
+                        / (user_drill_stats.total_attempts + 1),
+        current_streak = $5,
+        best_streak = GREATEST(user_drill_stats.best_streak, $5),
+        last_attempt_at = NOW(),
+        total_time_seconds = user_drill_stats.total_time_seconds + $6
+"#;
+
+pub const DRILL_SESSION_COMPLETE_XP_REWARD: &str =
+    "SELECT xp_reward FROM learn_drills WHERE id = $1";
+
+pub const DRILL_SESSION_GET_STATS: &str = r#"
+    SELECT id, user_id, drill_id, total_attempts, correct_answers, best_score,
+           average_score::float8 as average_score, current_streak, best_streak,
+           last_attempt_at, total_time_seconds
+    FROM user_drill_stats
+    WHERE user_id = $1 AND drill_id = $2
+"#;
+
+impl DrillSessionRepo {
+    /// Start a server-scored session for a drill
+    ///
+    /// Difficulty adapts from the user's `user_drill_stats` row; the config is
+    /// snapshotted so later edits to the drill don't change an open session.
+    /// As with `SessionRepo::create`, the returned `DrillSession.token` is the
+    /// raw token; only its hash is stored.
+    pub async fn start(
+        pool: &PgPool,
+        user_id: Uuid,
+        drill_id: Uuid,
+    ) -> Result<(DrillSession, Vec<DrillQuestion>), AppError> {
+        let drill = sqlx::query_as::<_, LearnDrill>(DRILL_SESSION_START_DRILL)
+            .bind(drill_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Drill not found".to_string()))?;
+
+        let kind = DrillKind::from_drill_type(&drill.drill_type).ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "Drills of type '{}' are not server-scored",
+                drill.drill_type
+            ))
+        })?;
+        let config = DrillConfig::from_json(&drill.config_json)?;
+
+        let stats = Self::get_stats(pool, user_id, drill_id).await?;
+        let level = drills::adaptive_level(&drill.difficulty, stats.as_ref());
+        let seed: i64 = rand::thread_rng().gen();
+        let questions = drills::generate_questions(kind, &config, seed as u64, level);
+
+        let token = generate_session_token();
+        let session = sqlx::query_as::<_, DrillSession>(DRILL_SESSION_START_SESSION)
+            .bind(user_id)
+            .bind(drill_id)
+            .bind(hash_session_token(&token))
+            .bind(&drill.drill_type)
+            .bind(&drill.config_json)
+            .bind(seed)
+            .bind(level as i32)
+            .bind(questions.len() as i32)
+            .bind(Utc::now() + Duration::minutes(DRILL_SESSION_TTL_MINUTES))
+            .fetch_one(pool)
+            .await?;
+
+        Ok((DrillSession { token, ..session }, questions))
+    }
+
+    /// Load a session owned by the user and regenerate its questions
+    pub async fn load(
+        pool: &PgPool,
+        user_id: Uuid,
+        token: &str,
+    ) -> Result<(DrillSession, Vec<DrillQuestion>), AppError> {
+        let token_hash = hash_session_token(token);
+        let session = sqlx::query_as::<_, DrillSession>(DRILL_SESSION_LOAD)
+            .bind(&token_hash)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?
+            .filter(|s| verify_token(&s.token, &token_hash))
+            .ok_or_else(|| AppError::NotFound("Drill session not found".to_string()))?;
+
+        let questions = Self::questions(&session)?;
+        Ok((session, questions))
+    }
+
+    /// Regenerate the question set for a session
+    pub fn questions(session: &DrillSession) -> Result<Vec<DrillQuestion>, AppError> {
+        let kind = DrillKind::from_drill_type(&session.drill_type).ok_or_else(|| {
+            AppError::Internal(format!("Unknown drill type '{}'", session.drill_type))
+        })?;
+        let config = DrillConfig::from_json(&session.config_json)?;
+        Ok(drills::generate_questions(
+            kind,
+            &config,
+            session.seed as u64,
+            session.difficulty_level as u8,
+        ))
+    }
+
+    /// Record and grade an answer
+    ///
+    /// The client's `response_ms` is clamped to the time the server has seen pass
+    /// since the previous answer (or session start), so it can't be inflated.
+    pub async fn answer(
+        pool: &PgPool,
+        user_id: Uuid,
+        token: &str,
+        req: &DrillAnswerRequest,
+    ) -> Result<DrillAnswerResult, AppError> {
+        let (session, questions) = Self::load(pool, user_id, token).await?;
+        Self::ensure_open(&session)?;
+
+        let question = usize::try_from(req.question_index)
+            .ok()
+            .and_then(|i| questions.get(i))
+            .ok_or_else(|| AppError::Validation("Invalid question index".to_string()))?;
+
+        let last_answer_at: Option<chrono::DateTime<Utc>> =
+            sqlx::query_scalar(DRILL_SESSION_ANSWER_LAST_ANSWER_AT)
+                .bind(session.id)
+                .fetch_one(pool)
+                .await?;
+        let since = last_answer_at.unwrap_or(session.started_at);
+        let server_elapsed_ms = (Utc::now() - since).num_milliseconds().max(0);
+        let response_ms = (req.response_ms.max(0) as i64).min(server_elapsed_ms) as i32;
+
+        let is_correct = question.grade(&req.answer, response_ms as u32);
+
+        let inserted = sqlx::query(DRILL_SESSION_ANSWER_INSERT_DRILL_SESSION_ANSWERS)
+            .bind(session.id)
+            .bind(req.question_index)
+            .bind(req.answer.trim())
+            .bind(is_correct)
+            .bind(response_ms)
+            .execute(pool)
+            .await?;
+
+        if inserted.rows_affected() == 0 {
+            return Err(AppError::BadRequest(
+                "Question has already been answered".to_string(),
+            ));
+        }
+
+        let answered_count: i64 = sqlx::query_scalar(DRILL_SESSION_ANSWER_ANSWERED_COUNT)
+            .bind(session.id)
+            .fetch_one(pool)
+            .await?;
+
+        Ok(DrillAnswerResult {
+            question_index: req.question_index,
+            correct: is_correct,
+            expected: question.expected_label(),
+            response_ms,
+            answered_count,
+            question_count: session.question_count,
+        })
+    }
+
+    /// Score a session from its recorded answers, update stats and award XP
+    ///
+    /// Unanswered questions count as wrong. Completion is guarded on
+    /// `status = 'active'`, so a session can only be scored once.
+    pub async fn complete(
+        pool: &PgPool,
+        user_id: Uuid,
+        token: &str,
+    ) -> Result<DrillSessionResult, AppError> {
+        let (session, questions) = Self::load(pool, user_id, token).await?;
+        if session.status != "active" {
+            return Err(AppError::BadRequest(
+                "Drill session is already complete".to_string(),
+            ));
+        }
+
+        let answers = sqlx::query_as::<_, DrillSessionAnswer>(DRILL_SESSION_COMPLETE_ANSWERS)
+            .bind(session.id)
+            .fetch_all(pool)
+            .await?;
+
+        let correct_count = answers.iter().filter(|a| a.is_correct).count();
+        let score = drills::score(correct_count, questions.len());
+        let time_seconds =
+            (answers.iter().map(|a| a.response_ms as i64).sum::<i64>() / 1000) as i32;
+
+        let existing = Self::get_stats(pool, user_id, session.drill_id).await?;
+        let is_new_best = existing
+            .as_ref()
+            .is_none_or(|e| e.best_score.is_none_or(|b| score > b));
+
+        // Streak continues when the drill was last practiced today or yesterday
+        let today = Utc::now().date_naive();
+        let streak_continued = existing
+            .as_ref()
+            .and_then(|e| e.last_attempt_at)
+            .map(|last| (today - last.date_naive()).num_days() <= 1)
+            .unwrap_or(false);
+        let new_streak = match (&existing, streak_continued) {
+            (Some(e), true) => e.current_streak + 1,
+            _ => 1,
+        };
+
+        let mut tx = pool.begin().await?;
+
+        let updated = sqlx::query(DRILL_SESSION_COMPLETE_UPDATE_DRILL_SESSIONS)
+            .bind(session.id)
+            .bind(score)
+            .bind(correct_count as i32)
+            .execute(&mut *tx)
+            .await?;
+
+        if updated.rows_affected() == 0 {
+            return Err(AppError::BadRequest(
+                "Drill session is already complete".to_string(),
+            ));
+        }
+
+        sqlx::query(DRILL_SESSION_COMPLETE_INSERT_USER_DRILL_STATS)
+            .bind(user_id)
+            .bind(session.drill_id)
+            .bind(correct_count as i32)
+            .bind(score)
+            .bind(new_streak)
+            .bind(time_seconds)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        let xp_reward: i32 = sqlx::query_scalar(DRILL_SESSION_COMPLETE_XP_REWARD)
+            .bind(session.drill_id)
+            .fetch_optional(pool)
+            .await?
+            .unwrap_or(0);
+
+        // Same reward curve as self-reported drills
+        let xp_awarded = if score >= 80 {
+            xp_reward
+        } else if score >= 60 {
+            xp_reward / 2
+        } else {
+            1
+        };
+
+        GamificationRepo::award_points(
+            pool,
+            user_id,
+            &AwardPointsInput {
+                xp: Some(xp_awarded),
+                coins: None,
+                skill_stars: None,
+                skill_key: None,
+                event_type: "drill_complete".to_string(),
+                event_id: Some(session.drill_id),
+                reason: Some(format!("Drill session scored {}%", score)),
+                idempotency_key: Some(format!("drill_session_{}", session.id)),
+                quantity: None,
+            },
+        )
+        .await?;
+
+        let answers_review = questions
+            .iter()
+            .map(|q| {
+                let recorded = answers
+                    .iter()
+                    .find(|a| a.question_index as usize == q.index);
+                DrillAnswerReview {
+                    question_index: q.index as i32,
+                    answer: recorded.map(|a| a.answer.clone()),
+                    expected: q.expected_label(),
+                    correct: recorded.is_some_and(|a| a.is_correct),
+                    response_ms: recorded.map(|a| a.response_ms),
+                }
+            })
+            .collect();
+
+        Ok(DrillSessionResult {
+            drill_id: session.drill_id,
+            score,
+            correct_count: correct_count as i32,
+            total_count: questions.len() as i32,
+            time_seconds,
+            difficulty_level: session.difficulty_level,
+            xp_awarded,
+            is_new_best,
+            streak_continued,
+            new_streak,
+            answers: answers_review,
+        })
+    }
+
+    /// Reject answers for completed or expired sessions
+    fn ensure_open(session: &DrillSession) -> Result<(), AppError> {
+        if session.status != "active" {
+            return Err(AppError::BadRequest(
+                "Drill session is already complete".to_string(),
+            ));
+        }
+        if session.expires_at <= Utc::now() {
+            return Err(AppError::BadRequest(
+                "Drill session has expired".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Get a user's stats for a drill
+    async fn get_stats(
+        pool: &PgPool,
+        user_id: Uuid,
+        drill_id: Uuid,
+    ) -> Result<Option<UserDrillStats>, AppError> {
+        let stats = sqlx::query_as::<_, UserDrillStats>(DRILL_SESSION_GET_STATS)
+            .bind(user_id)
+            .bind(drill_id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(stats)
+    }
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -15,6 +15,118 @@ use super::market_models::*;
 
 pub struct MarketRepo;
 
+pub const MARKET_LIST_ITEMS_BY_CATEGORY: &str = r#"
+    SELECT id, key, name, description, category, cost_coins,
+           icon, image_url, is_global, is_available, is_active,
+           is_consumable, uses_per_purchase, total_stock,
+           remaining_stock, created_by_user_id, sort_order,
+           created_at, updated_at
+    FROM market_items
+    WHERE is_active = true AND is_available = true
+      AND is_global = true AND category = $1
+    ORDER BY sort_order, name
+"#;
+
+pub const MARKET_LIST_ITEMS: &str = r#"
+    SELECT id, key, name, description, category, cost_coins,
+           icon, image_url, is_global, is_available, is_active,
+           is_consumable, uses_per_purchase, total_stock,
+           remaining_stock, created_by_user_id, sort_order,
+           created_at, updated_at
+    FROM market_items
+    WHERE is_active = true AND is_available = true AND is_global = true
+    ORDER BY sort_order, name
+"#;
+
+pub const MARKET_GET_ITEM_BY_KEY: &str = r#"
+    SELECT id, key, name, description, category, cost_coins,
+           icon, image_url, is_global, is_available, is_active,
+           is_consumable, uses_per_purchase, total_stock,
+           remaining_stock, created_by_user_id, sort_order,
+           created_at, updated_at
+    FROM market_items
+    WHERE key = $1
+"#;
+
+pub const MARKET_PURCHASE_ITEM: &str = r#"
+    SELECT id, key, name, description, category, cost_coins,
+           icon, image_url, is_global, is_available, is_active,
+           is_consumable, uses_per_purchase, total_stock,
+           remaining_stock, created_by_user_id, sort_order,
+           created_at, updated_at
+    FROM market_items
+    WHERE key = $1
+    FOR UPDATE
+"#;
+
+pub const MARKET_PURCHASE_WALLET_ROW: &str =
+    "SELECT coins::bigint AS coins FROM user_wallet WHERE user_id = $1 FOR UPDATE";
+
+pub const MARKET_PURCHASE_NEW_BALANCE: &str = r#"
+    UPDATE user_wallet
+    SET coins = coins - $2, total_spent = total_spent + $2, updated_at = NOW()
+    WHERE user_id = $1
+    RETURNING coins::bigint
+"#;
+
+pub const MARKET_PURCHASE_UPDATE_MARKET_ITEMS: &str =
+    "UPDATE market_items SET remaining_stock = remaining_stock - $2 WHERE id = $1";
+
+pub const MARKET_PURCHASE_PURCHASE: &str = r#"
+    INSERT INTO user_purchases (user_id, item_id, cost_coins, quantity, uses_remaining)
+    VALUES ($1, $2, $3, $4, $5)
+    RETURNING id, purchased_at, status
+"#;
+
+pub const MARKET_REDEEM_PURCHASE: &str = r#"
+    SELECT id, user_id, item_id, cost_coins, quantity, purchased_at,
+           redeemed_at, uses_remaining, status, refunded_at, refund_reason
+    FROM user_purchases
+    WHERE id = $1 AND user_id = $2
+    FOR UPDATE
+"#;
+
+pub const MARKET_REDEEM_ITEM: &str =
+    "SELECT key, name, is_consumable, uses_per_purchase FROM market_items WHERE id = $1";
+
+pub const MARKET_REDEEM_UPDATE_USER_PURCHASES: &str = r#"
+    UPDATE user_purchases
+    SET uses_remaining = $3, status = $4,
+        redeemed_at = CASE WHEN $4 = 'redeemed' THEN NOW() ELSE redeemed_at END
+    WHERE id = $1 AND user_id = $2
+"#;
+
+pub const MARKET_GET_PURCHASE_HISTORY: &str = r#"
+    SELECT p.id, p.cost_coins, p.quantity, p.purchased_at,
+           p.status, p.uses_remaining,
+           i.key as item_key, i.name as item_name
+    FROM user_purchases p
+    JOIN market_items i ON p.item_id = i.id
+    WHERE p.user_id = $1
+    ORDER BY p.purchased_at DESC
+    LIMIT $2
+"#;
+
+pub const MARKET_GET_WALLET: &str = r#"
+    SELECT coins::bigint AS coins,
+           total_earned::bigint AS lifetime_earned,
+           total_spent::bigint AS lifetime_spent
+    FROM user_wallet
+    WHERE user_id = $1
+"#;
+
+pub const MARKET_CREATE_ITEM: &str = r#"
+    INSERT INTO market_items (key, name, description, category, cost_coins,
+                              icon, image_url, is_consumable, uses_per_purchase,
+                              total_stock, remaining_stock, created_by_user_id)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $10, $11)
+    RETURNING id, key, name, description, category, cost_coins,
+              icon, image_url, is_global, is_available, is_active,
+              is_consumable, uses_per_purchase, total_stock,
+              remaining_stock, created_by_user_id, sort_order,
+              created_at, updated_at
+"#;
+
 impl MarketRepo {
     /// List available market items
     pub async fn list_items(
@@ -22,37 +134,14 @@ impl MarketRepo {
         category: Option<&str>,
     ) -> Result<MarketListResponse, AppError> {
         let items = if let Some(cat) = category {
-            sqlx::query_as::<_, MarketItem>(
-                r#"
-                SELECT id, key, name, description, category, cost_coins,
-                       icon, image_url, is_global, is_available, is_active,
-                       is_consumable, uses_per_purchase, total_stock,
-                       remaining_stock, created_by_user_id, sort_order,
-                       created_at, updated_at
-                FROM market_items
-                WHERE is_active = true AND is_available = true
-                  AND is_global = true AND category = $1
-                ORDER BY sort_order, name
-                "#,
-            )
-            .bind(cat)
-            .fetch_all(pool)
-            .await?
+            sqlx::query_as::<_, MarketItem>(MARKET_LIST_ITEMS_BY_CATEGORY)
+                .bind(cat)
+                .fetch_all(pool)
+                .await?
         } else {
-            sqlx::query_as::<_, MarketItem>(
-                r#"
-                SELECT id, key, name, description, category, cost_coins,
-                       icon, image_url, is_global, is_available, is_active,
-                       is_consumable, uses_per_purchase, total_stock,
-                       remaining_stock, created_by_user_id, sort_order,
-                       created_at, updated_at
-                FROM market_items
-                WHERE is_active = true AND is_available = true AND is_global = true
-                ORDER BY sort_order, name
-                "#,
-            )
-            .fetch_all(pool)
-            .await?
+            sqlx::query_as::<_, MarketItem>(MARKET_LIST_ITEMS)
+                .fetch_all(pool)
+                .await?
         };
 
         let total = items.len() as i64;
@@ -65,20 +154,10 @@ impl MarketRepo {
 
     /// Get item by key
     pub async fn get_item_by_key(pool: &PgPool, key: &str) -> Result<Option<MarketItem>, AppError> {
-        let item = sqlx::query_as::<_, MarketItem>(
-            r#"
-            SELECT id, key, name, description, category, cost_coins,
-                   icon, image_url, is_global, is_available, is_active,
-                   is_consumable, uses_per_purchase, total_stock,
-                   remaining_stock, created_by_user_id, sort_order,
-                   created_at, updated_at
-            FROM market_items
-            WHERE key = $1
-            "#,
-        )
-        .bind(key)
-        .fetch_optional(pool)
-        .await?;
+        let item = sqlx::query_as::<_, MarketItem>(MARKET_GET_ITEM_BY_KEY)
+            .bind(key)
+            .fetch_optional(pool)
+            .await?;
 
         Ok(item)
     }
@@ -97,21 +176,10 @@ impl MarketRepo {
         }
 
         // Get and lock item
-        let item = sqlx::query_as::<_, MarketItem>(
-            r#"
-            SELECT id, key, name, description, category, cost_coins,
-                   icon, image_url, is_global, is_available, is_active,
-                   is_consumable, uses_per_purchase, total_stock,
-                   remaining_stock, created_by_user_id, sort_order,
-                   created_at, updated_at
-            FROM market_items
-            WHERE key = $1
-            FOR UPDATE
-            "#,
-        )
-        .bind(&req.item_key)
-        .fetch_optional(pool)
-        .await?;
+        let item = sqlx::query_as::<_, MarketItem>(MARKET_PURCHASE_ITEM)
+            .bind(&req.item_key)
+            .fetch_optional(pool)
+            .await?;
 
         let item = item.ok_or_else(|| AppError::NotFound("Item not found".to_string()))?;
 
@@ -129,14 +197,10 @@ impl MarketRepo {
         let total_cost = item.cost_coins * quantity;
 
         // Get and update wallet
-        let wallet_row = sqlx::query(
-            r#"
-            SELECT coins FROM user_wallet WHERE user_id = $1 FOR UPDATE
-            "#,
-        )
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let wallet_row = sqlx::query(MARKET_PURCHASE_WALLET_ROW)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
 
         let current_coins: i64 = wallet_row.map(|r| r.get("coins")).unwrap_or(0);
 
@@ -148,28 +212,19 @@ impl MarketRepo {
         }
 
         // Deduct coins
-        let new_balance: i64 = sqlx::query_scalar(
-            r#"
-            UPDATE user_wallet
-            SET coins = coins - $2, lifetime_spent = lifetime_spent + $2
-            WHERE user_id = $1
-            RETURNING coins
-            "#,
-        )
-        .bind(user_id)
-        .bind(total_cost as i64)
-        .fetch_one(pool)
-        .await?;
+        let new_balance: i64 = sqlx::query_scalar(MARKET_PURCHASE_NEW_BALANCE)
+            .bind(user_id)
+            .bind(total_cost as i64)
+            .fetch_one(pool)
+            .await?;
 
         // Update stock if applicable
         if item.remaining_stock.is_some() {
-            sqlx::query(
-                "UPDATE market_items SET remaining_stock = remaining_stock - $2 WHERE id = $1",
-            )
-            .bind(item.id)
-            .bind(quantity)
-            .execute(pool)
-            .await?;
+            sqlx::query(MARKET_PURCHASE_UPDATE_MARKET_ITEMS)
+                .bind(item.id)
+                .bind(quantity)
+                .execute(pool)
+                .await?;
         }
 
         // Create purchase record
@@ -186,20 +241,14 @@ impl MarketRepo {
             status: String,
         }
 
-        let purchase = sqlx::query_as::<_, PurchaseRow>(
-            r#"
-            INSERT INTO user_purchases (user_id, item_id, cost_coins, quantity, uses_remaining)
-            VALUES ($1, $2, $3, $4, $5)
-            RETURNING id, purchased_at, status
-            "#,
-        )
-        .bind(user_id)
-        .bind(item.id)
-        .bind(total_cost)
-        .bind(quantity)
-        .bind(uses_remaining)
-        .fetch_one(pool)
-        .await?;
+        let purchase = sqlx::query_as::<_, PurchaseRow>(MARKET_PURCHASE_PURCHASE)
+            .bind(user_id)
+            .bind(item.id)
+            .bind(total_cost)
+            .bind(quantity)
+            .bind(uses_remaining)
+            .fetch_one(pool)
+            .await?;
 
         Ok(PurchaseResult {
             purchase: PurchaseResponse {
@@ -224,19 +273,11 @@ impl MarketRepo {
         purchase_id: Uuid,
     ) -> Result<RedeemResult, AppError> {
         // Get and lock purchase
-        let purchase = sqlx::query_as::<_, UserPurchase>(
-            r#"
-            SELECT id, user_id, item_id, cost_coins, quantity, purchased_at,
-                   redeemed_at, uses_remaining, status, refunded_at, refund_reason
-            FROM user_purchases
-            WHERE id = $1 AND user_id = $2
-            FOR UPDATE
-            "#,
-        )
-        .bind(purchase_id)
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let purchase = sqlx::query_as::<_, UserPurchase>(MARKET_REDEEM_PURCHASE)
+            .bind(purchase_id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
 
         let purchase =
             purchase.ok_or_else(|| AppError::NotFound("Purchase not found".to_string()))?;
@@ -257,12 +298,10 @@ impl MarketRepo {
             uses_per_purchase: Option<i32>,
         }
 
-        let item = sqlx::query_as::<_, ItemInfo>(
-            "SELECT key, name, is_consumable, uses_per_purchase FROM market_items WHERE id = $1",
-        )
-        .bind(purchase.item_id)
-        .fetch_one(pool)
-        .await?;
+        let item = sqlx::query_as::<_, ItemInfo>(MARKET_REDEEM_ITEM)
+            .bind(purchase.item_id)
+            .fetch_one(pool)
+            .await?;
 
         if !item.is_consumable {
             return Err(AppError::BadRequest("Item is not consumable".to_string()));
@@ -276,20 +315,13 @@ impl MarketRepo {
             "purchased"
         };
 
-        sqlx::query(
-            r#"
-            UPDATE user_purchases
-            SET uses_remaining = $3, status = $4,
-                redeemed_at = CASE WHEN $4 = 'redeemed' THEN NOW() ELSE redeemed_at END
-            WHERE id = $1 AND user_id = $2
-            "#,
-        )
-        .bind(purchase_id)
-        .bind(user_id)
-        .bind(new_uses)
-        .bind(new_status)
-        .execute(pool)
-        .await?;
+        sqlx::query(MARKET_REDEEM_UPDATE_USER_PURCHASES)
+            .bind(purchase_id)
+            .bind(user_id)
+            .bind(new_uses)
+            .bind(new_status)
+            .execute(pool)
+            .await?;
 
         let message = if new_status == "redeemed" {
             format!("Fully redeemed: {}", item.name)
@@ -334,22 +366,11 @@ impl MarketRepo {
             item_name: String,
         }
 
-        let purchases = sqlx::query_as::<_, PurchaseHistoryRow>(
-            r#"
-            SELECT p.id, p.cost_coins, p.quantity, p.purchased_at,
-                   p.status, p.uses_remaining,
-                   i.key as item_key, i.name as item_name
-            FROM user_purchases p
-            JOIN market_items i ON p.item_id = i.id
-            WHERE p.user_id = $1
-            ORDER BY p.purchased_at DESC
-            LIMIT $2
-            "#,
-        )
-        .bind(user_id)
-        .bind(limit)
-        .fetch_all(pool)
-        .await?;
+        let purchases = sqlx::query_as::<_, PurchaseHistoryRow>(MARKET_GET_PURCHASE_HISTORY)
+            .bind(user_id)
+            .bind(limit)
+            .fetch_all(pool)
+            .await?;
 
         let total = purchases.len() as i64;
 
@@ -380,16 +401,10 @@ impl MarketRepo {
             lifetime_spent: i64,
         }
 
-        let wallet = sqlx::query_as::<_, WalletRow>(
-            r#"
-            SELECT coins, lifetime_earned, lifetime_spent
-            FROM user_wallet
-            WHERE user_id = $1
-            "#,
-        )
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let wallet = sqlx::query_as::<_, WalletRow>(MARKET_GET_WALLET)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
 
         match wallet {
             Some(w) => Ok(WalletResponse {
@@ -411,32 +426,20 @@ impl MarketRepo {
         user_id: Uuid,
         req: &CreateItemRequest,
     ) -> Result<MarketItem, AppError> {
-        let item = sqlx::query_as::<_, MarketItem>(
-            r#"
-            INSERT INTO market_items (key, name, description, category, cost_coins,
-                                      icon, image_url, is_consumable, uses_per_purchase,
-                                      total_stock, remaining_stock, created_by_user_id)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $10, $11)
-            RETURNING id, key, name, description, category, cost_coins,
-                      icon, image_url, is_global, is_available, is_active,
-                      is_consumable, uses_per_purchase, total_stock,
-                      remaining_stock, created_by_user_id, sort_order,
-                      created_at, updated_at
-            "#,
-        )
-        .bind(&req.key)
-        .bind(&req.name)
-        .bind(&req.description)
-        .bind(&req.category)
-        .bind(req.cost_coins)
-        .bind(&req.icon)
-        .bind(&req.image_url)
-        .bind(req.is_consumable.unwrap_or(true))
-        .bind(req.uses_per_purchase)
-        .bind(req.total_stock)
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
+        let item = sqlx::query_as::<_, MarketItem>(MARKET_CREATE_ITEM)
+            .bind(&req.key)
+            .bind(&req.name)
+            .bind(&req.description)
+            .bind(&req.category)
+            .bind(req.cost_coins)
+            .bind(&req.icon)
+            .bind(&req.image_url)
+            .bind(req.is_consumable.unwrap_or(true))
+            .bind(req.uses_per_purchase)
+            .bind(req.total_stock)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
 
         Ok(item)
     }
@@ -5,16 +5,26 @@
 //! ## Architecture
 //! 
 //! - `core`: Centralized database utilities with observability
+//! - `catalog`: Named registry of every repository statement, checked against the schema
 //! - `*_models`: Database entity structs (FromRow)
 //! - `*_repos`: Repository pattern for CRUD operations
 
 pub mod core;  // Centralized DB utilities with observability
+pub mod account_deletion_models;
+pub mod account_deletion_repos;
 pub mod admin_models;
 pub mod admin_repos;
+pub mod api_token_models;
+pub mod api_token_repos;
+pub mod archive_models;
+pub mod archive_repos;
 pub mod books_models;
 pub mod books_repos;
+pub mod catalog;
 pub mod exercise_models;
 pub mod exercise_repos;
+pub mod feature_flag_models;
+pub mod feature_flag_repos;
 pub mod focus_models;
 pub mod focus_repos;
 pub mod frames_models;
@@ -25,24 +35,46 @@ pub mod gamification_repos;
 pub mod generated;  // Schema-generated types - source of truth
 pub mod habits_goals_models;
 pub mod habits_goals_repos;
+pub mod idea_attachment_models;
+pub mod idea_attachment_repos;
 pub mod inbox_models;
 pub mod inbox_repos;
+pub mod infobase_link_models;
+pub mod infobase_link_repos;
 pub mod learn_models;
 pub mod learn_repos;
 pub mod market_models;
 pub mod market_repos;
 pub mod models;
+pub mod notification_models;
+pub mod notification_repos;
 pub mod oauth_models;
 pub mod oauth_repos;
+pub mod onboarding_models;
+pub mod onboarding_repos;
+pub mod passkey_models;
+pub mod passkey_repos;
+pub mod personalization_models;
+pub mod personalization_repos;
+pub mod plan_models;
+pub mod plan_repos;
 pub mod platform_models;
 pub mod platform_repos;
 pub mod quests_models;
 pub mod quests_repos;
+pub mod rate_limit_models;
+pub mod rate_limit_repos;
 pub mod reference_models;
 pub mod reference_repos;
 pub mod references_models;
 pub mod references_repos;
 pub mod repos;
+pub mod revision_models;
+pub mod revision_repos;
+pub mod search_models;
+pub mod search_repos;
+pub mod sync_models;
+pub mod sync_repos;
 pub mod template_models;
 pub mod template_repos;
 
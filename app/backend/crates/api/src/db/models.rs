@@ -123,18 +123,21 @@ pub struct EntitlementCheck {
 }
 
 /// OAuth provider types
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum OAuthProvider {
     Google,
     Azure,
+    /// Config-driven OpenID Connect provider, by configured ID
+    Oidc(String),
 }
 
 impl OAuthProvider {
-    pub fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             OAuthProvider::Google => "google",
             OAuthProvider::Azure => "azure-ad",
+            OAuthProvider::Oidc(id) => id,
         }
     }
 }
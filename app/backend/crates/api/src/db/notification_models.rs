@@ -0,0 +1,138 @@
+//! Notification Models
+//!
+//! Reminder deliveries materialized by the notification scheduler and the
+//! browser push subscriptions they are sent to.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Calendar event with `reminder_minutes`, due that many minutes before it starts
+pub const NOTIFICATION_KIND_CALENDAR_EVENT: &str = "calendar_event";
+/// Daily habits still open at the user's `daily_reminder_time`
+pub const NOTIFICATION_KIND_HABIT_CHECKIN: &str = "habit_checkin";
+/// Accepted quest expiring within [`QUEST_EXPIRY_WARNING_HOURS`]
+pub const NOTIFICATION_KIND_QUEST_EXPIRING: &str = "quest_expiring";
+/// Running focus session reached its end
+pub const NOTIFICATION_KIND_FOCUS_END: &str = "focus_end";
+
+/// Inbox item (never held back by quiet hours)
+pub const NOTIFICATION_CHANNEL_INBOX: &str = "inbox";
+/// Email over SMTP
+pub const NOTIFICATION_CHANNEL_EMAIL: &str = "email";
+/// Web Push to every subscribed browser
+pub const NOTIFICATION_CHANNEL_PUSH: &str = "push";
+
+pub const DELIVERY_STATUS_PENDING: &str = "pending";
+pub const DELIVERY_STATUS_SENT: &str = "sent";
+pub const DELIVERY_STATUS_FAILED: &str = "failed";
+pub const DELIVERY_STATUS_SKIPPED: &str = "skipped";
+
+/// How long before expiry a quest reminder is sent
+pub const QUEST_EXPIRY_WARNING_HOURS: i32 = 24;
+
+/// How long finished deliveries are kept
+pub const DELIVERY_RETENTION_DAYS: i32 = 30;
+
+/// One reminder on one channel
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct NotificationDelivery {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub ref_id: Option<Uuid>,
+    pub dedupe_key: String,
+    pub channel: String,
+    pub title: String,
+    pub body: Option<String>,
+    pub action_url: Option<String>,
+    pub due_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Delivery claimed by the scheduler, with the recipient's address
+#[derive(Debug, Clone, FromRow)]
+pub struct DueNotification {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub ref_id: Option<Uuid>,
+    pub channel: String,
+    pub title: String,
+    pub body: Option<String>,
+    pub action_url: Option<String>,
+    /// Attempts including this one
+    pub attempts: i32,
+    pub email: String,
+}
+
+/// Browser push subscription
+#[derive(Debug, Clone, FromRow)]
+pub struct PushSubscription {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub endpoint: String,
+    /// Browser's P-256 public key (base64url)
+    pub p256dh: String,
+    /// Browser's auth secret (base64url)
+    pub auth: String,
+    pub user_agent: Option<String>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Keys from `PushSubscription.toJSON()`
+#[derive(Debug, Deserialize)]
+pub struct PushSubscriptionKeys {
+    pub p256dh: String,
+    pub auth: String,
+}
+
+/// Subscribe request, shaped like `PushSubscription.toJSON()`
+#[derive(Debug, Deserialize)]
+pub struct CreatePushSubscriptionRequest {
+    pub endpoint: String,
+    pub keys: PushSubscriptionKeys,
+}
+
+/// Unsubscribe request
+#[derive(Debug, Deserialize)]
+pub struct DeletePushSubscriptionRequest {
+    pub endpoint: String,
+}
+
+/// Push subscription as listed to its owner
+#[derive(Debug, Clone, Serialize)]
+pub struct PushSubscriptionResponse {
+    pub id: Uuid,
+    pub endpoint: String,
+    pub user_agent: Option<String>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<PushSubscription> for PushSubscriptionResponse {
+    fn from(s: PushSubscription) -> Self {
+        Self {
+            id: s.id,
+            endpoint: s.endpoint,
+            user_agent: s.user_agent,
+            last_used_at: s.last_used_at,
+            created_at: s.created_at,
+        }
+    }
+}
+
+/// Application server key for `pushManager.subscribe()`
+#[derive(Debug, Clone, Serialize)]
+pub struct VapidKeyResponse {
+    /// Uncompressed P-256 public key (base64url), or `None` when push is not configured
+    pub public_key: Option<String>,
+}
@@ -0,0 +1,455 @@
+//! Notification Repository
+//!
+//! Materializes due reminders into the delivery outbox, claims pending
+//! deliveries for the scheduler and records the outcome of each attempt.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::notification_models::*;
+use crate::error::AppError;
+
+// Column list for notification_deliveries - matches NotificationDelivery struct field order
+macro_rules! delivery_columns {
+    () => {
+        r#"id, user_id, kind, ref_id, dedupe_key, channel, title, body, action_url,
+    due_at, expires_at, status, attempts, next_attempt_at, last_error, sent_at, created_at"#
+    };
+}
+
+// Column list for push_subscriptions - matches PushSubscription struct field order
+macro_rules! push_subscription_columns {
+    () => {
+        r#"id, user_id, endpoint, p256dh, auth, user_agent, last_used_at, created_at"#
+    };
+}
+
+// Reminder sources are LATERAL subqueries over notification_preferences `p`
+// returning (kind, ref_id, dedupe_key, title, body, action_url, due_at,
+// expires_at). Each reminder fans out to the channels in $1 the user has
+// enabled; the dedupe key makes materializing idempotent.
+macro_rules! reminder_insert {
+    () => {
+        r#"
+    INSERT INTO notification_deliveries
+    (user_id, kind, ref_id, dedupe_key, channel, title, body, action_url,
+     due_at, expires_at, next_attempt_at)
+    SELECT p.user_id, r.kind, r.ref_id, r.dedupe_key, c.channel, r.title, r.body,
+           r.action_url, r.due_at, r.expires_at, r.due_at
+    FROM notification_preferences p
+    CROSS JOIN LATERAL ("#
+    };
+}
+
+macro_rules! reminder_fan_out {
+    () => {
+        r#") r
+    CROSS JOIN unnest($1::text[]) AS c(channel)
+    WHERE p.notifications_enabled
+      AND (c.channel = 'inbox'
+           OR (c.channel = 'email' AND p.email_notifications)
+           OR (c.channel = 'push' AND p.push_notifications))
+    ON CONFLICT (dedupe_key, channel) DO NOTHING
+"#
+    };
+}
+
+pub struct NotificationRepo;
+
+// A moved event gets a new reminder
+pub const NOTIFICATION_MATERIALIZE_CALENDAR_EVENTS: &str = concat!(
+    reminder_insert!(),
+    r#"
+        SELECT 'calendar_event' AS kind, e.id AS ref_id,
+               'calendar_event:' || e.id || ':' || extract(epoch FROM e.start_time)::bigint
+                   AS dedupe_key,
+               e.title,
+               CASE WHEN e.all_day THEN 'All day'
+                    ELSE 'Starts at ' || to_char(e.start_time AT TIME ZONE p.timezone, 'HH24:MI')
+               END AS body,
+               '/calendar' AS action_url,
+               e.start_time - make_interval(mins => e.reminder_minutes) AS due_at,
+               e.start_time + INTERVAL '5 minutes' AS expires_at
+        FROM calendar_events e
+        WHERE e.user_id = p.user_id
+          AND e.reminder_minutes IS NOT NULL
+          AND e.start_time - make_interval(mins => e.reminder_minutes) <= NOW()
+          AND e.start_time + INTERVAL '5 minutes' > NOW()
+    "#,
+    reminder_fan_out!()
+);
+
+// One check-in per local day, only within an hour of the reminder time.
+// A habit counts while it has completions left today, as in
+// `HabitSchedule::remaining_on`: custom habits only on their weekdays, weekly
+// habits on any day not yet done until the week (from Monday) is met.
+pub const NOTIFICATION_MATERIALIZE_HABIT_CHECKINS: &str = concat!(
+    reminder_insert!(),
+    r#"
+        SELECT 'habit_checkin' AS kind, NULL::uuid AS ref_id,
+               'habit_checkin:' || p.user_id || ':' || p.local_now::date AS dedupe_key,
+               'Habit check-in' AS title,
+               CASE WHEN open.remaining = 1 THEN '1 habit left today'
+                    ELSE open.remaining || ' habits left today'
+               END AS body,
+               '/habits' AS action_url,
+               (p.local_now::date + p.daily_reminder_time) AT TIME ZONE p.timezone AS due_at,
+               (p.local_now::date + 1)::timestamp AT TIME ZONE p.timezone AS expires_at
+        FROM (
+            SELECT COUNT(*) AS remaining
+            FROM (
+                SELECT h.frequency, h.custom_days, GREATEST(h.target_count, 1) AS target,
+                       COUNT(hc.id) FILTER (WHERE hc.completed_date = p.local_now::date)
+                           AS done_today,
+                       COUNT(hc.id) AS done_this_week
+                FROM habits h
+                LEFT JOIN habit_completions hc
+                    ON hc.habit_id = h.id
+                    AND hc.completed_date
+                        BETWEEN date_trunc('week', p.local_now)::date AND p.local_now::date
+                WHERE h.user_id = p.user_id AND h.is_active = true
+                GROUP BY h.id
+            ) h
+            WHERE CASE h.frequency
+                WHEN 'weekly' THEN h.done_today = 0 AND h.done_this_week < LEAST(h.target, 7)
+                WHEN 'custom' THEN extract(dow FROM p.local_now)::int = ANY(h.custom_days)
+                    AND h.done_today < h.target
+                ELSE h.done_today < h.target
+            END
+        ) open
+        WHERE p.daily_reminder_time IS NOT NULL
+          AND p.local_now::time >= p.daily_reminder_time
+          AND p.local_now - (p.local_now::date + p.daily_reminder_time) < INTERVAL '1 hour'
+          AND open.remaining > 0
+    "#,
+    reminder_fan_out!()
+);
+
+// $2 = warning hours; an extended quest gets a new reminder
+pub const NOTIFICATION_MATERIALIZE_EXPIRING_QUESTS: &str = concat!(
+    reminder_insert!(),
+    r#"
+        SELECT 'quest_expiring' AS kind, q.id AS ref_id,
+               'quest_expiring:' || q.id || ':' || extract(epoch FROM q.expires_at)::bigint
+                   AS dedupe_key,
+               'Quest ending soon' AS title,
+               q.title || ' expires ' || to_char(q.expires_at AT TIME ZONE p.timezone, 'Mon DD HH24:MI')
+                   AS body,
+               '/quests' AS action_url,
+               q.expires_at - make_interval(hours => $2) AS due_at,
+               q.expires_at
+        FROM user_quests q
+        WHERE q.user_id = p.user_id
+          AND q.status IN ('accepted', 'active')
+          AND q.expires_at > NOW()
+          AND q.expires_at - make_interval(hours => $2) <= NOW()
+    "#,
+    reminder_fan_out!()
+);
+
+// A resumed session ends at its new expiry; otherwise after its duration
+pub const NOTIFICATION_MATERIALIZE_FOCUS_ENDS: &str = concat!(
+    reminder_insert!(),
+    r#"
+        SELECT 'focus_end' AS kind, f.id AS ref_id,
+               'focus_end:' || f.id || ':' || extract(epoch FROM f.ends_at)::bigint AS dedupe_key,
+               CASE WHEN f.mode = 'focus' THEN 'Focus session complete'
+                    ELSE 'Break is over'
+               END AS title,
+               f.task_title AS body,
+               '/focus' AS action_url,
+               f.ends_at AS due_at,
+               f.ends_at + INTERVAL '15 minutes' AS expires_at
+        FROM (
+            SELECT fs.id, fs.mode, fs.task_title,
+                   CASE WHEN ps.resumed_at IS NOT NULL THEN fs.expires_at
+                        ELSE fs.started_at + make_interval(secs => fs.duration_seconds)
+                   END AS ends_at
+            FROM focus_sessions fs
+            LEFT JOIN focus_pause_state ps ON ps.session_id = fs.id
+            WHERE fs.user_id = p.user_id AND fs.status = 'active'
+        ) f
+        WHERE f.ends_at <= NOW() AND f.ends_at + INTERVAL '15 minutes' > NOW()
+    "#,
+    reminder_fan_out!()
+);
+
+pub const NOTIFICATION_SKIP_EXPIRED: &str = r#"
+    UPDATE notification_deliveries
+    SET status = 'skipped', last_error = 'Expired before delivery'
+    WHERE status = 'pending' AND expires_at < NOW()
+"#;
+
+// Claims a batch and leases it for $2 seconds, so another instance (or this
+// one after a crash) picks it up again only once the lease runs out. Email and
+// push wait out the user's quiet hours; inbox items are silent and go now.
+pub const NOTIFICATION_CLAIM_DUE: &str = r#"
+    WITH due AS (
+        SELECT d.id
+        FROM notification_deliveries d
+        JOIN notification_preferences p ON p.user_id = d.user_id
+        WHERE d.status = 'pending'
+          AND d.next_attempt_at <= NOW()
+          AND (d.channel = 'inbox' OR NOT p.in_quiet_hours)
+        ORDER BY d.next_attempt_at
+        LIMIT $1
+        FOR UPDATE OF d SKIP LOCKED
+    )
+    UPDATE notification_deliveries d
+    SET attempts = d.attempts + 1,
+        next_attempt_at = NOW() + make_interval(secs => $2)
+    FROM due, users u
+    WHERE d.id = due.id AND u.id = d.user_id
+    RETURNING d.id, d.user_id, d.kind, d.ref_id, d.channel, d.title, d.body,
+              d.action_url, d.attempts, u.email
+"#;
+
+pub const NOTIFICATION_MARK_SENT: &str = r#"
+    UPDATE notification_deliveries
+    SET status = 'sent', sent_at = NOW(), last_error = NULL
+    WHERE id = $1
+"#;
+
+pub const NOTIFICATION_SCHEDULE_RETRY: &str = r#"
+    UPDATE notification_deliveries
+    SET next_attempt_at = $2, last_error = $3
+    WHERE id = $1
+"#;
+
+pub const NOTIFICATION_MARK_FINISHED: &str = r#"
+    UPDATE notification_deliveries
+    SET status = $2, last_error = $3
+    WHERE id = $1
+"#;
+
+pub const NOTIFICATION_LIST_RECENT: &str = concat!(
+    "SELECT ",
+    delivery_columns!(),
+    r#"
+    FROM notification_deliveries
+    WHERE user_id = $1
+    ORDER BY created_at DESC
+    LIMIT $2
+"#
+);
+
+pub const NOTIFICATION_PRUNE: &str = r#"
+    DELETE FROM notification_deliveries
+    WHERE status <> 'pending' AND created_at < NOW() - make_interval(days => $1)
+"#;
+
+impl NotificationRepo {
+    /// Materialize due reminders of `kind` for the given channels
+    ///
+    /// Returns the number of new deliveries.
+    pub async fn materialize(
+        pool: &PgPool,
+        kind: &str,
+        channels: &[&str],
+    ) -> Result<u64, AppError> {
+        let query = match kind {
+            NOTIFICATION_KIND_CALENDAR_EVENT => {
+                sqlx::query(NOTIFICATION_MATERIALIZE_CALENDAR_EVENTS).bind(channels)
+            }
+            NOTIFICATION_KIND_HABIT_CHECKIN => {
+                sqlx::query(NOTIFICATION_MATERIALIZE_HABIT_CHECKINS).bind(channels)
+            }
+            NOTIFICATION_KIND_QUEST_EXPIRING => {
+                sqlx::query(NOTIFICATION_MATERIALIZE_EXPIRING_QUESTS)
+                    .bind(channels)
+                    .bind(QUEST_EXPIRY_WARNING_HOURS)
+            }
+            NOTIFICATION_KIND_FOCUS_END => {
+                sqlx::query(NOTIFICATION_MATERIALIZE_FOCUS_ENDS).bind(channels)
+            }
+            _ => {
+                return Err(AppError::Internal(format!(
+                    "Unknown notification kind: {}",
+                    kind
+                )))
+            }
+        };
+
+        let result = query.execute(pool).await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Skip pending deliveries that are no longer useful
+    pub async fn skip_expired(pool: &PgPool) -> Result<u64, AppError> {
+        let result = sqlx::query(NOTIFICATION_SKIP_EXPIRED).execute(pool).await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Claim up to `limit` due deliveries, counting this attempt
+    pub async fn claim_due(
+        pool: &PgPool,
+        limit: i64,
+        lease_seconds: f64,
+    ) -> Result<Vec<DueNotification>, AppError> {
+        let due = sqlx::query_as::<_, DueNotification>(NOTIFICATION_CLAIM_DUE)
+            .bind(limit)
+            .bind(lease_seconds)
+            .fetch_all(pool)
+            .await?;
+        Ok(due)
+    }
+
+    /// Record a successful delivery
+    pub async fn mark_sent(pool: &PgPool, id: Uuid) -> Result<(), AppError> {
+        sqlx::query(NOTIFICATION_MARK_SENT)
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Record a failed attempt to be retried at `next_attempt_at`
+    pub async fn schedule_retry(
+        pool: &PgPool,
+        id: Uuid,
+        next_attempt_at: DateTime<Utc>,
+        error: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query(NOTIFICATION_SCHEDULE_RETRY)
+            .bind(id)
+            .bind(next_attempt_at)
+            .bind(error)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Give up on a delivery (`DELIVERY_STATUS_FAILED` or `DELIVERY_STATUS_SKIPPED`)
+    pub async fn mark_finished(
+        pool: &PgPool,
+        id: Uuid,
+        status: &str,
+        reason: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query(NOTIFICATION_MARK_FINISHED)
+            .bind(id)
+            .bind(status)
+            .bind(reason)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Most recent deliveries for a user
+    pub async fn list_recent(
+        pool: &PgPool,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<NotificationDelivery>, AppError> {
+        let deliveries = sqlx::query_as::<_, NotificationDelivery>(NOTIFICATION_LIST_RECENT)
+            .bind(user_id)
+            .bind(limit)
+            .fetch_all(pool)
+            .await?;
+        Ok(deliveries)
+    }
+
+    /// Delete finished deliveries past the retention window
+    pub async fn prune(pool: &PgPool) -> Result<u64, AppError> {
+        let result = sqlx::query(NOTIFICATION_PRUNE)
+            .bind(DELIVERY_RETENTION_DAYS)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+// ============================================================================
+// PUSH SUBSCRIPTION REPOSITORY
+// ============================================================================
+
+pub struct PushSubscriptionRepo;
+
+// Browsers reuse an endpoint when resubscribing, possibly for another account
+pub const PUSH_SUBSCRIPTION_UPSERT: &str = concat!(
+    r#"
+    INSERT INTO push_subscriptions (user_id, endpoint, p256dh, auth, user_agent)
+    VALUES ($1, $2, $3, $4, $5)
+    ON CONFLICT (endpoint) DO UPDATE SET
+        user_id = EXCLUDED.user_id,
+        p256dh = EXCLUDED.p256dh,
+        auth = EXCLUDED.auth,
+        user_agent = EXCLUDED.user_agent
+    RETURNING "#,
+    push_subscription_columns!()
+);
+
+pub const PUSH_SUBSCRIPTION_LIST_FOR_USER: &str = concat!(
+    "SELECT ",
+    push_subscription_columns!(),
+    " FROM push_subscriptions WHERE user_id = $1 ORDER BY created_at"
+);
+
+pub const PUSH_SUBSCRIPTION_DELETE: &str =
+    "DELETE FROM push_subscriptions WHERE user_id = $1 AND endpoint = $2";
+
+pub const PUSH_SUBSCRIPTION_DELETE_BY_ID: &str = "DELETE FROM push_subscriptions WHERE id = $1";
+
+pub const PUSH_SUBSCRIPTION_TOUCH: &str =
+    "UPDATE push_subscriptions SET last_used_at = NOW() WHERE id = $1";
+
+impl PushSubscriptionRepo {
+    /// Save a subscription (replacing keys for a known endpoint)
+    pub async fn upsert(
+        pool: &PgPool,
+        user_id: Uuid,
+        req: &CreatePushSubscriptionRequest,
+        user_agent: Option<&str>,
+    ) -> Result<PushSubscription, AppError> {
+        let subscription = sqlx::query_as::<_, PushSubscription>(PUSH_SUBSCRIPTION_UPSERT)
+            .bind(user_id)
+            .bind(&req.endpoint)
+            .bind(&req.keys.p256dh)
+            .bind(&req.keys.auth)
+            .bind(user_agent)
+            .fetch_one(pool)
+            .await?;
+        Ok(subscription)
+    }
+
+    /// All subscriptions for a user
+    pub async fn list_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<PushSubscription>, AppError> {
+        let subscriptions = sqlx::query_as::<_, PushSubscription>(PUSH_SUBSCRIPTION_LIST_FOR_USER)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+        Ok(subscriptions)
+    }
+
+    /// Remove a user's subscription by endpoint
+    pub async fn delete(pool: &PgPool, user_id: Uuid, endpoint: &str) -> Result<bool, AppError> {
+        let result = sqlx::query(PUSH_SUBSCRIPTION_DELETE)
+            .bind(user_id)
+            .bind(endpoint)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove a subscription the push service reported as gone
+    pub async fn delete_by_id(pool: &PgPool, id: Uuid) -> Result<(), AppError> {
+        sqlx::query(PUSH_SUBSCRIPTION_DELETE_BY_ID)
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Record a successful push
+    pub async fn touch(pool: &PgPool, id: Uuid) -> Result<(), AppError> {
+        sqlx::query(PUSH_SUBSCRIPTION_TOUCH)
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+}
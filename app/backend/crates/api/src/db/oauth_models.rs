@@ -12,6 +12,10 @@ pub struct OAuthStateRow {
     pub state_key: String,
     pub pkce_verifier: String,
     pub redirect_uri: Option<String>,
+    /// Provider the flow was started for (checked on callback)
+    pub provider: Option<String>,
+    /// OIDC nonce expected in the ID token
+    pub nonce: Option<String>,
     pub created_at: DateTime<Utc>,
     pub expires_at: DateTime<Utc>,
 }
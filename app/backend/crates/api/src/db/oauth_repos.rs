@@ -11,6 +11,26 @@ use crate::error::AppResult;
 
 pub struct OAuthStateRepo;
 
+pub const OAUTH_STATE_INSERT: &str = r#"
+    INSERT INTO oauth_states (state_key, pkce_verifier, redirect_uri, provider, nonce, created_at, expires_at)
+    VALUES ($1, $2, $3, $4, $5, $6, $7)
+    ON CONFLICT (state_key) DO UPDATE SET
+        pkce_verifier = EXCLUDED.pkce_verifier,
+        redirect_uri = EXCLUDED.redirect_uri,
+        provider = EXCLUDED.provider,
+        nonce = EXCLUDED.nonce,
+        created_at = EXCLUDED.created_at,
+        expires_at = EXCLUDED.expires_at
+"#;
+
+pub const OAUTH_STATE_TAKE: &str = r#"
+    DELETE FROM oauth_states
+    WHERE state_key = $1 AND expires_at > NOW()
+    RETURNING state_key, pkce_verifier, redirect_uri, provider, nonce, created_at, expires_at
+"#;
+
+pub const OAUTH_STATE_CLEANUP_EXPIRED: &str = "DELETE FROM oauth_states WHERE expires_at < NOW()";
+
 impl OAuthStateRepo {
     /// Store OAuth state in database
     pub async fn insert(
@@ -18,51 +38,39 @@ impl OAuthStateRepo {
         state_key: &str,
         pkce_verifier: &str,
         redirect_uri: Option<&str>,
+        provider: Option<&str>,
+        nonce: Option<&str>,
     ) -> AppResult<()> {
         let expires_at = Utc::now() + Duration::minutes(10);
         let created_at = Utc::now();
         
-        sqlx::query(
-            r#"
-            INSERT INTO oauth_states (state_key, pkce_verifier, redirect_uri, created_at, expires_at)
-            VALUES ($1, $2, $3, $4, $5)
-            ON CONFLICT (state_key) DO UPDATE SET
-                pkce_verifier = EXCLUDED.pkce_verifier,
-                redirect_uri = EXCLUDED.redirect_uri,
-                created_at = EXCLUDED.created_at,
-                expires_at = EXCLUDED.expires_at
-            "#,
-        )
-        .bind(state_key)
-        .bind(pkce_verifier)
-        .bind(redirect_uri)
-        .bind(created_at)
-        .bind(expires_at)
-        .execute(pool)
-        .await?;
+        sqlx::query(OAUTH_STATE_INSERT)
+            .bind(state_key)
+            .bind(pkce_verifier)
+            .bind(redirect_uri)
+            .bind(provider)
+            .bind(nonce)
+            .bind(created_at)
+            .bind(expires_at)
+            .execute(pool)
+            .await?;
 
         Ok(())
     }
 
     /// Get and remove OAuth state (atomic operation)
     pub async fn take(pool: &PgPool, state_key: &str) -> AppResult<Option<OAuthStateRow>> {
-        let row = sqlx::query_as::<_, OAuthStateRow>(
-            r#"
-            DELETE FROM oauth_states
-            WHERE state_key = $1 AND expires_at > NOW()
-            RETURNING state_key, pkce_verifier, redirect_uri, created_at, expires_at
-            "#,
-        )
-        .bind(state_key)
-        .fetch_optional(pool)
-        .await?;
+        let row = sqlx::query_as::<_, OAuthStateRow>(OAUTH_STATE_TAKE)
+            .bind(state_key)
+            .fetch_optional(pool)
+            .await?;
 
         Ok(row)
     }
 
     /// Clean up expired OAuth states (call periodically)
     pub async fn cleanup_expired(pool: &PgPool) -> AppResult<u64> {
-        let result = sqlx::query("DELETE FROM oauth_states WHERE expires_at < NOW()")
+        let result = sqlx::query(OAUTH_STATE_CLEANUP_EXPIRED)
             .execute(pool)
             .await?;
 
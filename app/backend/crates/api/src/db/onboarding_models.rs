@@ -0,0 +1,306 @@
+//! Onboarding Flow Models
+//!
+//! Admin-authored flow versions, step conditions and completion funnels. The
+//! user-facing flow models live in `platform_models`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::platform_models::{OnboardingFlow, OnboardingStep};
+use crate::shared::serde_helpers::double_option;
+
+/// Longest flow name
+pub const MAX_FLOW_NAME_LEN: usize = 64;
+
+/// Most steps in one flow version
+pub const MAX_FLOW_STEPS: usize = 50;
+
+/// Step types the client knows how to render
+pub const STEP_TYPES: &[&str] = &["tour", "choice", "preference", "action", "explain"];
+
+// ============================================================================
+// CONDITIONS
+// ============================================================================
+
+/// When a step is shown, tested against the user's earlier responses
+///
+/// Responses are JSON objects; their fields are merged in step order, so a
+/// later step's answer to the same field wins. Serialized externally tagged:
+/// `{"includes": {"field": "interests", "value": "production"}}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum StepCondition {
+    All(Vec<StepCondition>),
+    Any(Vec<StepCondition>),
+    Not(Box<StepCondition>),
+    /// The field has a non-empty answer
+    Answered {
+        field: String,
+    },
+    Equals {
+        field: String,
+        value: Value,
+    },
+    /// A list answer contains the value, or a keyed answer has it as a key
+    Includes {
+        field: String,
+        value: Value,
+    },
+}
+
+/// Deepest nesting of `all`, `any` and `not`
+pub const MAX_CONDITION_DEPTH: usize = 8;
+
+impl StepCondition {
+    pub fn matches(&self, answers: &Map<String, Value>) -> bool {
+        match self {
+            StepCondition::All(conditions) => conditions.iter().all(|c| c.matches(answers)),
+            StepCondition::Any(conditions) => conditions.iter().any(|c| c.matches(answers)),
+            StepCondition::Not(condition) => !condition.matches(answers),
+            StepCondition::Answered { field } => match answers.get(field) {
+                None | Some(Value::Null) => false,
+                Some(Value::String(s)) => !s.is_empty(),
+                Some(Value::Array(items)) => !items.is_empty(),
+                Some(Value::Object(map)) => !map.is_empty(),
+                Some(_) => true,
+            },
+            StepCondition::Equals { field, value } => answers.get(field) == Some(value),
+            StepCondition::Includes { field, value } => match answers.get(field) {
+                Some(Value::Array(items)) => items.contains(value),
+                Some(Value::Object(map)) => value.as_str().is_some_and(|k| map.contains_key(k)),
+                Some(answer) => answer == value,
+                None => false,
+            },
+        }
+    }
+
+    /// Nesting depth; a leaf is 1
+    pub fn depth(&self) -> usize {
+        match self {
+            StepCondition::All(conditions) | StepCondition::Any(conditions) => {
+                1 + conditions.iter().map(Self::depth).max().unwrap_or(0)
+            }
+            StepCondition::Not(condition) => 1 + condition.depth(),
+            _ => 1,
+        }
+    }
+}
+
+/// Merge object responses in step order into one set of answers
+pub fn merge_answers<'a>(responses: impl IntoIterator<Item = &'a Value>) -> Map<String, Value> {
+    let mut answers = Map::new();
+    for response in responses {
+        if let Value::Object(fields) = response {
+            answers.extend(fields.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+    }
+    answers
+}
+
+/// Whether a step is shown given the answers so far
+///
+/// Conditions are checked when saved, so one that no longer parses only
+/// comes from a hand-edited row and leaves the step shown.
+pub fn step_visible(step: &OnboardingStep, answers: &Map<String, Value>) -> bool {
+    match &step.show_if {
+        None | Some(Value::Null) => true,
+        Some(raw) => match serde_json::from_value::<StepCondition>(raw.clone()) {
+            Ok(condition) => condition.matches(answers),
+            Err(e) => {
+                tracing::warn!(
+                    "Ignoring invalid show_if on onboarding step {}: {}",
+                    step.id,
+                    e
+                );
+                true
+            }
+        },
+    }
+}
+
+/// First shown step after `after_order` (from the start when `None`)
+///
+/// `steps` must be in step order.
+pub fn next_visible_step<'a>(
+    steps: &'a [OnboardingStep],
+    after_order: Option<i32>,
+    answers: &Map<String, Value>,
+) -> Option<&'a OnboardingStep> {
+    steps
+        .iter()
+        .filter(|s| after_order.is_none_or(|order| s.step_order > order))
+        .find(|s| step_visible(s, answers))
+}
+
+// ============================================================================
+// ADMIN REQUESTS
+// ============================================================================
+
+/// Create flow request; the flow starts as an unpublished version 1
+#[derive(Debug, Deserialize)]
+pub struct CreateOnboardingFlowRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Update flow request; the name is shared by every version
+#[derive(Debug, Deserialize)]
+pub struct UpdateOnboardingFlowRequest {
+    pub description: Option<String>,
+}
+
+/// Create step request; appended to the end unless `step_order` is given
+#[derive(Debug, Deserialize)]
+pub struct CreateOnboardingStepRequest {
+    pub step_order: Option<i32>,
+    pub step_type: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub target_selector: Option<String>,
+    pub target_route: Option<String>,
+    pub fallback_content: Option<String>,
+    pub options: Option<Value>,
+    #[serde(default)]
+    pub allows_multiple: bool,
+    #[serde(default)]
+    pub required: bool,
+    pub action_type: Option<String>,
+    pub action_config: Option<Value>,
+    pub show_if: Option<StepCondition>,
+}
+
+/// Update step request; omitted fields are left unchanged
+///
+/// `show_if: null` removes the condition, an omitted `show_if` keeps it.
+#[derive(Debug, Deserialize)]
+pub struct UpdateOnboardingStepRequest {
+    pub step_order: Option<i32>,
+    pub step_type: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub target_selector: Option<String>,
+    pub target_route: Option<String>,
+    pub fallback_content: Option<String>,
+    pub options: Option<Value>,
+    pub allows_multiple: Option<bool>,
+    pub required: Option<bool>,
+    pub action_type: Option<String>,
+    pub action_config: Option<Value>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub show_if: Option<Option<StepCondition>>,
+}
+
+// ============================================================================
+// ADMIN RESPONSES
+// ============================================================================
+
+/// A flow version with its steps
+#[derive(Debug, Clone, Serialize)]
+pub struct OnboardingFlowDetail {
+    #[serde(flatten)]
+    pub flow: OnboardingFlow,
+    pub steps: Vec<OnboardingStep>,
+}
+
+/// Users by where they are in a flow version
+#[derive(Debug, Clone, Default, FromRow, Serialize)]
+pub struct OnboardingFunnelTotals {
+    pub started: i64,
+    pub in_progress: i64,
+    pub completed: i64,
+    pub skipped: i64,
+}
+
+/// How far users got with one step
+///
+/// `reached` counts users who answered the step, are on it now or skipped
+/// onboarding from it; users a condition passed over never reach it.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct OnboardingStepFunnel {
+    pub step_id: Uuid,
+    pub step_order: i32,
+    pub title: String,
+    pub conditional: bool,
+    pub reached: i64,
+    pub completed: i64,
+    pub in_progress: i64,
+    pub skipped_here: i64,
+}
+
+/// Completion funnel of a flow version
+#[derive(Debug, Clone, Serialize)]
+pub struct OnboardingFunnel {
+    pub flow_id: Uuid,
+    pub name: String,
+    pub version: i32,
+    pub totals: OnboardingFunnelTotals,
+    pub steps: Vec<OnboardingStepFunnel>,
+    pub generated_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn answers(value: Value) -> Map<String, Value> {
+        value.as_object().cloned().unwrap()
+    }
+
+    fn condition(value: Value) -> StepCondition {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_includes_lists_and_keyed_answers() {
+        let production =
+            condition(json!({"includes": {"field": "interests", "value": "production"}}));
+        assert!(production.matches(&answers(json!({"interests": ["focus", "production"]}))));
+        assert!(!production.matches(&answers(json!({"interests": ["focus"]}))));
+        assert!(!production.matches(&Map::new()));
+
+        let quests = condition(json!({"includes": {"field": "modules", "value": "quests"}}));
+        assert!(quests.matches(&answers(json!({"modules": {"quests": 2}}))));
+        assert!(!quests.matches(&answers(json!({"modules": {"focus": 1}}))));
+    }
+
+    #[test]
+    fn test_combinators() {
+        let c = condition(json!({"all": [
+            {"answered": {"field": "nudge_intensity"}},
+            {"not": {"equals": {"field": "nudge_intensity", "value": "gentle"}}}
+        ]}));
+        assert!(c.matches(&answers(json!({"nudge_intensity": "energetic"}))));
+        assert!(!c.matches(&answers(json!({"nudge_intensity": "gentle"}))));
+        assert!(!c.matches(&answers(json!({"nudge_intensity": ""}))));
+        assert_eq!(c.depth(), 3);
+
+        assert!(!condition(json!({"any": []})).matches(&Map::new()));
+        assert!(condition(json!({"all": []})).matches(&Map::new()));
+    }
+
+    #[test]
+    fn test_unknown_operators_are_rejected() {
+        assert!(
+            serde_json::from_value::<StepCondition>(json!({"contains": {"field": "a"}})).is_err()
+        );
+        assert!(serde_json::from_value::<StepCondition>(
+            json!({"equals": {"field": "a", "value": 1, "extra": true}})
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_later_answers_win() {
+        let merged = merge_answers(&[
+            json!({"interests": ["focus"], "nudge": "gentle"}),
+            json!("not an object"),
+            json!({"interests": ["production"]}),
+        ]);
+        assert_eq!(merged["interests"], json!(["production"]));
+        assert_eq!(merged["nudge"], json!("gentle"));
+    }
+}
@@ -0,0 +1,542 @@
+//! Onboarding Flow Repository
+//!
+//! Admin editing of onboarding flow versions and their completion funnels.
+//! Users move through flows with `platform_repos::OnboardingRepo`.
+//!
+//! Only unpublished versions can be edited. Publishing a version makes it
+//! the active flow; to change a published flow, copy it into a new version.
+
+use chrono::Utc;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use super::onboarding_models::*;
+use super::platform_models::{OnboardingFlow, OnboardingStep};
+use super::platform_repos::{OnboardingRepo, ONBOARDING_GET_FLOW, ONBOARDING_GET_FLOW_STEPS};
+use crate::error::AppError;
+
+macro_rules! onboarding_flow_columns {
+    () => {
+        r#"id, name, description, is_active, total_steps, version, published_at,
+    created_at, updated_at"#
+    };
+}
+
+macro_rules! onboarding_step_columns {
+    () => {
+        r#"id, flow_id, step_order, step_type, title, description,
+    target_selector, target_route, fallback_content, options,
+    allows_multiple, required, action_type, action_config, show_if,
+    created_at, updated_at"#
+    };
+}
+
+pub struct OnboardingFlowRepo;
+
+pub const ONBOARDING_FLOW_LIST: &str = concat!(
+    "SELECT ",
+    onboarding_flow_columns!(),
+    " FROM onboarding_flows ORDER BY name, version DESC"
+);
+
+pub const ONBOARDING_FLOW_GET_FOR_UPDATE: &str = concat!(
+    "SELECT ",
+    onboarding_flow_columns!(),
+    " FROM onboarding_flows WHERE id = $1 FOR UPDATE"
+);
+
+pub const ONBOARDING_FLOW_CREATE: &str = concat!(
+    r#"
+    INSERT INTO onboarding_flows (name, description, is_active, total_steps, version)
+    SELECT $1, $2, false, 0, 1
+    WHERE NOT EXISTS (SELECT 1 FROM onboarding_flows WHERE name = $1)
+    ON CONFLICT (name, version) DO NOTHING
+    RETURNING "#,
+    onboarding_flow_columns!()
+);
+
+pub const ONBOARDING_FLOW_UPDATE: &str = concat!(
+    r#"
+    UPDATE onboarding_flows
+    SET description = COALESCE($2, description),
+        updated_at = NOW()
+    WHERE id = $1
+    RETURNING "#,
+    onboarding_flow_columns!()
+);
+
+pub const ONBOARDING_FLOW_IN_USE: &str =
+    "SELECT EXISTS (SELECT 1 FROM user_onboarding_state WHERE flow_id = $1)";
+
+pub const ONBOARDING_FLOW_DELETE: &str = "DELETE FROM onboarding_flows WHERE id = $1";
+
+pub const ONBOARDING_FLOW_HAS_DRAFT: &str = r#"
+    SELECT EXISTS (
+        SELECT 1 FROM onboarding_flows WHERE name = $1 AND published_at IS NULL
+    )
+"#;
+
+pub const ONBOARDING_FLOW_NEW_VERSION: &str = concat!(
+    r#"
+    INSERT INTO onboarding_flows (name, description, is_active, total_steps, version)
+    SELECT f.name, f.description, false, f.total_steps,
+           (SELECT MAX(version) + 1 FROM onboarding_flows WHERE name = f.name)
+    FROM onboarding_flows f
+    WHERE f.id = $1
+    RETURNING "#,
+    onboarding_flow_columns!()
+);
+
+pub const ONBOARDING_FLOW_COPY_STEPS: &str = r#"
+    INSERT INTO onboarding_steps
+        (flow_id, step_order, step_type, title, description, target_selector,
+         target_route, fallback_content, options, allows_multiple, required,
+         action_type, action_config, show_if)
+    SELECT $2, step_order, step_type, title, description, target_selector,
+           target_route, fallback_content, options, allows_multiple, required,
+           action_type, action_config, show_if
+    FROM onboarding_steps
+    WHERE flow_id = $1
+"#;
+
+pub const ONBOARDING_FLOW_DEACTIVATE_OTHERS: &str = r#"
+    UPDATE onboarding_flows
+    SET is_active = false, updated_at = NOW()
+    WHERE is_active AND id <> $1
+"#;
+
+pub const ONBOARDING_FLOW_PUBLISH: &str = concat!(
+    r#"
+    UPDATE onboarding_flows
+    SET is_active = true,
+        published_at = COALESCE(published_at, $2),
+        updated_at = $2
+    WHERE id = $1
+    RETURNING "#,
+    onboarding_flow_columns!()
+);
+
+pub const ONBOARDING_FLOW_COUNT_STEPS: &str = r#"
+    UPDATE onboarding_flows
+    SET total_steps = (SELECT COUNT(*) FROM onboarding_steps WHERE flow_id = $1),
+        updated_at = NOW()
+    WHERE id = $1
+    RETURNING total_steps
+"#;
+
+pub const ONBOARDING_STEP_MAKE_ROOM: &str = r#"
+    UPDATE onboarding_steps
+    SET step_order = step_order + 1, updated_at = NOW()
+    WHERE flow_id = $1 AND step_order >= $2 AND id IS DISTINCT FROM $3
+      AND EXISTS (
+          SELECT 1 FROM onboarding_steps
+          WHERE flow_id = $1 AND step_order = $2 AND id IS DISTINCT FROM $3
+      )
+"#;
+
+pub const ONBOARDING_STEP_CREATE: &str = concat!(
+    r#"
+    INSERT INTO onboarding_steps
+        (flow_id, step_order, step_type, title, description, target_selector,
+         target_route, fallback_content, options, allows_multiple, required,
+         action_type, action_config, show_if)
+    VALUES ($1,
+            COALESCE($2, (SELECT COALESCE(MAX(step_order), 0) + 1
+                          FROM onboarding_steps WHERE flow_id = $1)),
+            $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+    RETURNING "#,
+    onboarding_step_columns!()
+);
+
+pub const ONBOARDING_STEP_UPDATE: &str = concat!(
+    r#"
+    UPDATE onboarding_steps
+    SET step_order = COALESCE($3, step_order),
+        step_type = COALESCE($4, step_type),
+        title = COALESCE($5, title),
+        description = COALESCE($6, description),
+        target_selector = COALESCE($7, target_selector),
+        target_route = COALESCE($8, target_route),
+        fallback_content = COALESCE($9, fallback_content),
+        options = COALESCE($10, options),
+        allows_multiple = COALESCE($11, allows_multiple),
+        required = COALESCE($12, required),
+        action_type = COALESCE($13, action_type),
+        action_config = COALESCE($14, action_config),
+        show_if = CASE WHEN $15::BOOLEAN THEN $16 ELSE show_if END,
+        updated_at = NOW()
+    WHERE id = $1 AND flow_id = $2
+    RETURNING "#,
+    onboarding_step_columns!()
+);
+
+pub const ONBOARDING_STEP_DELETE: &str =
+    "DELETE FROM onboarding_steps WHERE id = $1 AND flow_id = $2";
+
+pub const ONBOARDING_FUNNEL_TOTALS: &str = r#"
+    SELECT COUNT(*) FILTER (WHERE started_at IS NOT NULL) AS started,
+           COUNT(*) FILTER (WHERE status = 'in_progress') AS in_progress,
+           COUNT(*) FILTER (WHERE status = 'completed') AS completed,
+           COUNT(*) FILTER (WHERE status = 'skipped') AS skipped
+    FROM user_onboarding_state
+    WHERE flow_id = $1
+"#;
+
+pub const ONBOARDING_FUNNEL_STEPS: &str = r#"
+    SELECT s.id AS step_id, s.step_order, s.title, s.show_if IS NOT NULL AS conditional,
+           r.completed + c.in_progress + c.skipped_here AS reached,
+           r.completed, c.in_progress, c.skipped_here
+    FROM onboarding_steps s
+    CROSS JOIN LATERAL (
+        SELECT COUNT(*) AS completed
+        FROM user_onboarding_responses
+        WHERE step_id = s.id
+    ) r
+    CROSS JOIN LATERAL (
+        SELECT COUNT(*) FILTER (WHERE st.status = 'in_progress') AS in_progress,
+               COUNT(*) FILTER (WHERE st.status = 'skipped') AS skipped_here
+        FROM user_onboarding_state st
+        WHERE st.current_step_id = s.id
+          AND NOT EXISTS (
+              SELECT 1 FROM user_onboarding_responses ur
+              WHERE ur.user_id = st.user_id AND ur.step_id = s.id
+          )
+    ) c
+    WHERE s.flow_id = $1
+    ORDER BY s.step_order ASC
+"#;
+
+/// The version locked for editing; published versions are refused
+async fn lock_draft(conn: &mut PgConnection, flow_id: Uuid) -> Result<OnboardingFlow, AppError> {
+    let flow = sqlx::query_as::<_, OnboardingFlow>(ONBOARDING_FLOW_GET_FOR_UPDATE)
+        .bind(flow_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Onboarding flow not found".into()))?;
+    if flow.published_at.is_some() {
+        return Err(AppError::Conflict {
+            message: format!(
+                "Version {} of {} is published; create a new version to change it",
+                flow.version, flow.name
+            ),
+            details: None,
+        });
+    }
+    Ok(flow)
+}
+
+/// Recount a version's steps after adding or removing one
+async fn count_steps(conn: &mut PgConnection, flow_id: Uuid) -> Result<i32, AppError> {
+    let total = sqlx::query_scalar::<_, i32>(ONBOARDING_FLOW_COUNT_STEPS)
+        .bind(flow_id)
+        .fetch_one(conn)
+        .await?;
+    Ok(total)
+}
+
+/// Shift later steps down when `step_order` is already taken
+async fn make_room(
+    conn: &mut PgConnection,
+    flow_id: Uuid,
+    step_order: i32,
+    moving: Option<Uuid>,
+) -> Result<(), AppError> {
+    sqlx::query(ONBOARDING_STEP_MAKE_ROOM)
+        .bind(flow_id)
+        .bind(step_order)
+        .bind(moving)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+impl OnboardingFlowRepo {
+    /// Every version of every flow, newest version first within a name
+    pub async fn list(pool: &PgPool) -> Result<Vec<OnboardingFlow>, AppError> {
+        let flows = sqlx::query_as::<_, OnboardingFlow>(ONBOARDING_FLOW_LIST)
+            .fetch_all(pool)
+            .await?;
+        Ok(flows)
+    }
+
+    /// One version with its steps
+    pub async fn get(pool: &PgPool, flow_id: Uuid) -> Result<OnboardingFlowDetail, AppError> {
+        let flow = sqlx::query_as::<_, OnboardingFlow>(ONBOARDING_GET_FLOW)
+            .bind(flow_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Onboarding flow not found".into()))?;
+        let steps = OnboardingRepo::get_flow_steps(pool, flow_id).await?;
+        Ok(OnboardingFlowDetail { flow, steps })
+    }
+
+    /// Create version 1 of a new flow; `None` if the name is taken
+    pub async fn create(
+        pool: &PgPool,
+        input: &CreateOnboardingFlowRequest,
+    ) -> Result<Option<OnboardingFlow>, AppError> {
+        let flow = sqlx::query_as::<_, OnboardingFlow>(ONBOARDING_FLOW_CREATE)
+            .bind(&input.name)
+            .bind(&input.description)
+            .fetch_optional(pool)
+            .await?;
+        Ok(flow)
+    }
+
+    /// Update an unpublished version's description
+    pub async fn update(
+        pool: &PgPool,
+        flow_id: Uuid,
+        input: &UpdateOnboardingFlowRequest,
+    ) -> Result<OnboardingFlow, AppError> {
+        let mut tx = pool.begin().await?;
+        lock_draft(&mut tx, flow_id).await?;
+        let flow = sqlx::query_as::<_, OnboardingFlow>(ONBOARDING_FLOW_UPDATE)
+            .bind(flow_id)
+            .bind(&input.description)
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(flow)
+    }
+
+    /// Delete a version nobody has started and that is not active
+    pub async fn delete(pool: &PgPool, flow_id: Uuid) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        let flow = sqlx::query_as::<_, OnboardingFlow>(ONBOARDING_FLOW_GET_FOR_UPDATE)
+            .bind(flow_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Onboarding flow not found".into()))?;
+        if flow.is_active {
+            return Err(AppError::Conflict {
+                message: "The active onboarding flow can't be deleted".into(),
+                details: None,
+            });
+        }
+        let in_use = sqlx::query_scalar::<_, bool>(ONBOARDING_FLOW_IN_USE)
+            .bind(flow_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        if in_use {
+            return Err(AppError::Conflict {
+                message: format!(
+                    "Users have started version {} of {}; it can't be deleted",
+                    flow.version, flow.name
+                ),
+                details: None,
+            });
+        }
+
+        sqlx::query(ONBOARDING_FLOW_DELETE)
+            .bind(flow_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Copy a version and its steps into a new unpublished version
+    ///
+    /// A flow has at most one unpublished version at a time.
+    pub async fn new_version(
+        pool: &PgPool,
+        flow_id: Uuid,
+    ) -> Result<OnboardingFlowDetail, AppError> {
+        let mut tx = pool.begin().await?;
+        let source = sqlx::query_as::<_, OnboardingFlow>(ONBOARDING_FLOW_GET_FOR_UPDATE)
+            .bind(flow_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Onboarding flow not found".into()))?;
+        let has_draft = sqlx::query_scalar::<_, bool>(ONBOARDING_FLOW_HAS_DRAFT)
+            .bind(&source.name)
+            .fetch_one(&mut *tx)
+            .await?;
+        if has_draft {
+            return Err(AppError::Conflict {
+                message: format!("{} already has an unpublished version", source.name),
+                details: None,
+            });
+        }
+
+        let flow = sqlx::query_as::<_, OnboardingFlow>(ONBOARDING_FLOW_NEW_VERSION)
+            .bind(flow_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        sqlx::query(ONBOARDING_FLOW_COPY_STEPS)
+            .bind(flow_id)
+            .bind(flow.id)
+            .execute(&mut *tx)
+            .await?;
+        let steps = sqlx::query_as::<_, OnboardingStep>(ONBOARDING_GET_FLOW_STEPS)
+            .bind(flow.id)
+            .fetch_all(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(OnboardingFlowDetail { flow, steps })
+    }
+
+    /// Make a version the active flow, publishing it if it is a draft
+    ///
+    /// New users start the active version; users part-way through finish
+    /// the one they started. Publishing an older version rolls back to it.
+    pub async fn publish(pool: &PgPool, flow_id: Uuid) -> Result<OnboardingFlow, AppError> {
+        let mut tx = pool.begin().await?;
+        let flow = sqlx::query_as::<_, OnboardingFlow>(ONBOARDING_FLOW_GET_FOR_UPDATE)
+            .bind(flow_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Onboarding flow not found".into()))?;
+        if count_steps(&mut tx, flow_id).await? == 0 {
+            return Err(AppError::Validation(format!(
+                "Version {} of {} has no steps",
+                flow.version, flow.name
+            )));
+        }
+
+        sqlx::query(ONBOARDING_FLOW_DEACTIVATE_OTHERS)
+            .bind(flow_id)
+            .execute(&mut *tx)
+            .await?;
+        let flow = sqlx::query_as::<_, OnboardingFlow>(ONBOARDING_FLOW_PUBLISH)
+            .bind(flow_id)
+            .bind(Utc::now())
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(flow)
+    }
+
+    /// Add a step to an unpublished version
+    pub async fn add_step(
+        pool: &PgPool,
+        flow_id: Uuid,
+        input: &CreateOnboardingStepRequest,
+    ) -> Result<OnboardingStep, AppError> {
+        let mut tx = pool.begin().await?;
+        let flow = lock_draft(&mut tx, flow_id).await?;
+        if flow.total_steps as usize >= MAX_FLOW_STEPS {
+            return Err(AppError::Validation(format!(
+                "A flow can have at most {} steps",
+                MAX_FLOW_STEPS
+            )));
+        }
+        if let Some(order) = input.step_order {
+            make_room(&mut tx, flow_id, order, None).await?;
+        }
+
+        let show_if = input
+            .show_if
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        let step = sqlx::query_as::<_, OnboardingStep>(ONBOARDING_STEP_CREATE)
+            .bind(flow_id)
+            .bind(input.step_order)
+            .bind(&input.step_type)
+            .bind(&input.title)
+            .bind(&input.description)
+            .bind(&input.target_selector)
+            .bind(&input.target_route)
+            .bind(&input.fallback_content)
+            .bind(&input.options)
+            .bind(input.allows_multiple)
+            .bind(input.required)
+            .bind(&input.action_type)
+            .bind(&input.action_config)
+            .bind(show_if)
+            .fetch_one(&mut *tx)
+            .await?;
+        count_steps(&mut tx, flow_id).await?;
+        tx.commit().await?;
+        Ok(step)
+    }
+
+    /// Change a step of an unpublished version
+    pub async fn update_step(
+        pool: &PgPool,
+        flow_id: Uuid,
+        step_id: Uuid,
+        input: &UpdateOnboardingStepRequest,
+    ) -> Result<OnboardingStep, AppError> {
+        let mut tx = pool.begin().await?;
+        lock_draft(&mut tx, flow_id).await?;
+        if let Some(order) = input.step_order {
+            make_room(&mut tx, flow_id, order, Some(step_id)).await?;
+        }
+
+        let show_if = input
+            .show_if
+            .as_ref()
+            .map(|c| c.as_ref().map(serde_json::to_value).transpose())
+            .transpose()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        let step = sqlx::query_as::<_, OnboardingStep>(ONBOARDING_STEP_UPDATE)
+            .bind(step_id)
+            .bind(flow_id)
+            .bind(input.step_order)
+            .bind(&input.step_type)
+            .bind(&input.title)
+            .bind(&input.description)
+            .bind(&input.target_selector)
+            .bind(&input.target_route)
+            .bind(&input.fallback_content)
+            .bind(&input.options)
+            .bind(input.allows_multiple)
+            .bind(input.required)
+            .bind(&input.action_type)
+            .bind(&input.action_config)
+            .bind(show_if.is_some())
+            .bind(show_if.flatten())
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Onboarding step not found".into()))?;
+        tx.commit().await?;
+        Ok(step)
+    }
+
+    /// Remove a step from an unpublished version
+    pub async fn delete_step(pool: &PgPool, flow_id: Uuid, step_id: Uuid) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        lock_draft(&mut tx, flow_id).await?;
+        let result = sqlx::query(ONBOARDING_STEP_DELETE)
+            .bind(step_id)
+            .bind(flow_id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Onboarding step not found".into()));
+        }
+        count_steps(&mut tx, flow_id).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// How far users got through a version, step by step
+    pub async fn funnel(pool: &PgPool, flow_id: Uuid) -> Result<OnboardingFunnel, AppError> {
+        let flow = sqlx::query_as::<_, OnboardingFlow>(ONBOARDING_GET_FLOW)
+            .bind(flow_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Onboarding flow not found".into()))?;
+        let totals = sqlx::query_as::<_, OnboardingFunnelTotals>(ONBOARDING_FUNNEL_TOTALS)
+            .bind(flow_id)
+            .fetch_one(pool)
+            .await?;
+        let steps = sqlx::query_as::<_, OnboardingStepFunnel>(ONBOARDING_FUNNEL_STEPS)
+            .bind(flow_id)
+            .fetch_all(pool)
+            .await?;
+
+        Ok(OnboardingFunnel {
+            flow_id,
+            name: flow.name,
+            version: flow.version,
+            totals,
+            steps,
+            generated_at: Utc::now(),
+        })
+    }
+}
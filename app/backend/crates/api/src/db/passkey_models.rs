@@ -0,0 +1,129 @@
+//! Passkey (WebAuthn) Models
+//!
+//! Database models and request/response types for passkey registration and sign-in.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Ceremony names stored with a challenge
+pub const CEREMONY_REGISTRATION: &str = "registration";
+pub const CEREMONY_AUTHENTICATION: &str = "authentication";
+
+/// Registered WebAuthn credential
+#[derive(Debug, Clone, FromRow)]
+#[allow(dead_code)]
+pub struct Authenticator {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Base64url credential ID
+    pub credential_id: String,
+    pub provider_account_id: String,
+    /// Base64url COSE_Key
+    pub credential_public_key: String,
+    pub counter: i64,
+    pub credential_device_type: String,
+    pub credential_backed_up: bool,
+    pub transports: Vec<String>,
+    pub name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Pending WebAuthn challenge stored in database for distributed access
+#[derive(Debug, Clone, FromRow)]
+#[allow(dead_code)]
+pub struct WebauthnChallengeRow {
+    pub challenge: String,
+    pub ceremony: String,
+    /// Set for registration (the signed-in user); None for sign-in
+    pub user_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Input for storing a verified credential
+#[derive(Debug, Clone)]
+pub struct CreateAuthenticatorInput {
+    pub user_id: Uuid,
+    pub credential_id: String,
+    pub public_key: String,
+    pub counter: i64,
+    pub device_type: String,
+    pub backed_up: bool,
+    pub transports: Vec<String>,
+    pub name: Option<String>,
+}
+
+// ============================================================================
+// REQUESTS
+// ============================================================================
+
+/// Registration ceremony result from `navigator.credentials.create()`
+#[derive(Debug, Deserialize)]
+pub struct PasskeyRegistrationRequest {
+    /// Base64url credential ID
+    pub id: String,
+    pub response: AttestationResponse,
+    /// Optional friendly name ("MacBook Touch ID")
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub attestation_object: String,
+    #[serde(default)]
+    pub transports: Vec<String>,
+}
+
+/// Assertion ceremony result from `navigator.credentials.get()`
+#[derive(Debug, Deserialize)]
+pub struct PasskeyAssertionRequest {
+    /// Base64url credential ID
+    pub id: String,
+    pub response: AssertionResponse,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssertionResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+    pub user_handle: Option<String>,
+}
+
+// ============================================================================
+// RESPONSES
+// ============================================================================
+
+/// Passkey as listed to its owner
+#[derive(Debug, Clone, Serialize)]
+pub struct PasskeyResponse {
+    pub id: Uuid,
+    pub name: Option<String>,
+    pub device_type: String,
+    pub backed_up: bool,
+    pub transports: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl From<Authenticator> for PasskeyResponse {
+    fn from(a: Authenticator) -> Self {
+        Self {
+            id: a.id,
+            name: a.name,
+            device_type: a.credential_device_type,
+            backed_up: a.credential_backed_up,
+            transports: a.transports,
+            created_at: a.created_at,
+            last_used_at: a.last_used_at,
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Passkey (WebAuthn) Repositories
+//!
+//! Database operations for WebAuthn challenges and registered authenticators.
+
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db::passkey_models::{Authenticator, CreateAuthenticatorInput, WebauthnChallengeRow};
+use crate::error::AppResult;
+use crate::services::webauthn::CHALLENGE_TTL_MINUTES;
+
+macro_rules! authenticator_columns {
+    () => {
+        r#"id, user_id, credential_id, provider_account_id,
+    credential_public_key, counter, credential_device_type, credential_backed_up,
+    transports, name, created_at, last_used_at"#
+    };
+}
+
+pub struct WebauthnChallengeRepo;
+
+pub const WEBAUTHN_CHALLENGE_INSERT: &str = r#"
+    INSERT INTO webauthn_challenges (challenge, ceremony, user_id, created_at, expires_at)
+    VALUES ($1, $2, $3, NOW(), $4)
+"#;
+
+// Deleted even when expired, so a challenge is gone once presented
+pub const WEBAUTHN_CHALLENGE_TAKE: &str = r#"
+    WITH taken AS (
+        DELETE FROM webauthn_challenges
+        WHERE challenge = $1 AND ceremony = $2
+        RETURNING challenge, ceremony, user_id, created_at, expires_at
+    )
+    SELECT challenge, ceremony, user_id, created_at, expires_at
+    FROM taken
+    WHERE expires_at > NOW()
+"#;
+
+pub const WEBAUTHN_CHALLENGE_CLEANUP_EXPIRED: &str =
+    "DELETE FROM webauthn_challenges WHERE expires_at < NOW()";
+
+impl WebauthnChallengeRepo {
+    /// Store a challenge in database
+    pub async fn insert(
+        pool: &PgPool,
+        challenge: &str,
+        ceremony: &str,
+        user_id: Option<Uuid>,
+    ) -> AppResult<()> {
+        let expires_at = Utc::now() + Duration::minutes(CHALLENGE_TTL_MINUTES);
+
+        sqlx::query(WEBAUTHN_CHALLENGE_INSERT)
+            .bind(challenge)
+            .bind(ceremony)
+            .bind(user_id)
+            .bind(expires_at)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Get and remove a challenge (atomic, single use)
+    pub async fn take(
+        pool: &PgPool,
+        challenge: &str,
+        ceremony: &str,
+    ) -> AppResult<Option<WebauthnChallengeRow>> {
+        let row = sqlx::query_as::<_, WebauthnChallengeRow>(WEBAUTHN_CHALLENGE_TAKE)
+            .bind(challenge)
+            .bind(ceremony)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(row)
+    }
+
+    /// Clean up expired challenges (call periodically)
+    pub async fn cleanup_expired(pool: &PgPool) -> AppResult<u64> {
+        let result = sqlx::query(WEBAUTHN_CHALLENGE_CLEANUP_EXPIRED)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+pub struct AuthenticatorRepo;
+
+pub const AUTHENTICATOR_FIND_BY_CREDENTIAL_ID: &str = concat!(
+    "SELECT ",
+    authenticator_columns!(),
+    " FROM authenticators WHERE credential_id = $1"
+);
+
+pub const AUTHENTICATOR_LIST_FOR_USER: &str = concat!(
+    "SELECT ",
+    authenticator_columns!(),
+    " FROM authenticators WHERE user_id = $1 ORDER BY created_at DESC"
+);
+
+pub const AUTHENTICATOR_CREATE: &str = concat!(
+    r#"
+    INSERT INTO authenticators
+        (user_id, credential_id, provider_account_id, credential_public_key, counter,
+         credential_device_type, credential_backed_up, transports, name, created_at)
+    VALUES ($1, $2, $2, $3, $4, $5, $6, $7, $8, NOW())
+    RETURNING "#,
+    authenticator_columns!()
+);
+
+pub const AUTHENTICATOR_RECORD_USE: &str = r#"
+    UPDATE authenticators
+    SET counter = $3, credential_backed_up = $4, last_used_at = NOW()
+    WHERE id = $1 AND counter = $2
+"#;
+
+pub const AUTHENTICATOR_DELETE_FOR_USER: &str =
+    "DELETE FROM authenticators WHERE id = $1 AND user_id = $2";
+
+impl AuthenticatorRepo {
+    /// Find an authenticator by its base64url credential ID
+    pub async fn find_by_credential_id(
+        pool: &PgPool,
+        credential_id: &str,
+    ) -> AppResult<Option<Authenticator>> {
+        let row = sqlx::query_as::<_, Authenticator>(AUTHENTICATOR_FIND_BY_CREDENTIAL_ID)
+            .bind(credential_id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(row)
+    }
+
+    /// List a user's authenticators, newest first
+    pub async fn list_for_user(pool: &PgPool, user_id: Uuid) -> AppResult<Vec<Authenticator>> {
+        let rows = sqlx::query_as::<_, Authenticator>(AUTHENTICATOR_LIST_FOR_USER)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+
+        Ok(rows)
+    }
+
+    /// Store a newly registered authenticator
+    pub async fn create(
+        pool: &PgPool,
+        input: CreateAuthenticatorInput,
+    ) -> AppResult<Authenticator> {
+        let row = sqlx::query_as::<_, Authenticator>(AUTHENTICATOR_CREATE)
+            .bind(input.user_id)
+            .bind(&input.credential_id)
+            .bind(&input.public_key)
+            .bind(input.counter)
+            .bind(&input.device_type)
+            .bind(input.backed_up)
+            .bind(&input.transports)
+            .bind(&input.name)
+            .fetch_one(pool)
+            .await?;
+
+        Ok(row)
+    }
+
+    /// Record a successful sign-in: store the new counter and backup state
+    ///
+    /// The update is guarded on the previous counter so two concurrent
+    /// assertions cannot both succeed with the same value.
+    pub async fn record_use(
+        pool: &PgPool,
+        id: Uuid,
+        previous_counter: i64,
+        counter: i64,
+        backed_up: bool,
+    ) -> AppResult<bool> {
+        let result = sqlx::query(AUTHENTICATOR_RECORD_USE)
+            .bind(id)
+            .bind(previous_counter)
+            .bind(counter)
+            .bind(backed_up)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete one of a user's authenticators
+    pub async fn delete_for_user(pool: &PgPool, user_id: Uuid, id: Uuid) -> AppResult<bool> {
+        let result = sqlx::query(AUTHENTICATOR_DELETE_FOR_USER)
+            .bind(id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
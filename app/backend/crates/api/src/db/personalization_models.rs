@@ -0,0 +1,638 @@
+//! Personalization Models
+//!
+//! Suggested next actions for the Today dashboard, ranked against what a
+//! user said they are interested in and what they have been doing lately.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Suggestions shown on the Today dashboard
+pub const MAX_SUGGESTIONS: usize = 5;
+
+/// At most this many suggestions of one kind
+pub const MAX_PER_KIND: usize = 2;
+
+/// Days of ledger activity that count as recent
+pub const ACTIVITY_WINDOW_DAYS: i64 = 14;
+
+/// Days without progress before an active goal is stalled
+pub const STALLED_GOAL_DAYS: i64 = 7;
+
+/// Tags each interest key reaches beyond itself
+///
+/// Keys are the ones offered in settings and onboarding; any other key only
+/// matches itself.
+const INTEREST_TAGS: &[(&str, &[&str])] = &[
+    ("focus", &["productivity", "mindfulness"]),
+    ("learning", &["reading", "theory"]),
+    (
+        "music",
+        &[
+            "music_theory",
+            "listening",
+            "production",
+            "mixing",
+            "arrangement",
+        ],
+    ),
+    ("fitness", &["fitness_basics"]),
+    ("creativity", &["music", "production", "arrangement"]),
+];
+
+/// Ledger events that show interest in a tag, with what to call them
+const ACTIVITY_TAGS: &[(&str, &str, &str)] = &[
+    ("focus_complete", "focus", "focus sessions"),
+    ("habit_complete", "habits", "habit check-ins"),
+    ("workout_complete", "fitness", "workouts"),
+    ("drill_complete", "listening", "ear training drills"),
+    ("reading_logged", "reading", "reading sessions"),
+    ("milestone_complete", "goals", "goal milestones"),
+];
+
+/// What a suggestion points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionKind {
+    Lesson,
+    ListeningPrompt,
+    Goal,
+    Book,
+}
+
+impl SuggestionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SuggestionKind::Lesson => "lesson",
+            SuggestionKind::ListeningPrompt => "listening_prompt",
+            SuggestionKind::Goal => "goal",
+            SuggestionKind::Book => "book",
+        }
+    }
+}
+
+/// A ranked next action with the reasons it was picked, strongest first
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Suggestion {
+    #[serde(rename = "type")]
+    pub kind: SuggestionKind,
+    pub id: Uuid,
+    pub title: String,
+    pub route: String,
+    pub reasons: Vec<String>,
+    pub score: f64,
+}
+
+/// How much a user cares about a tag, and why we think so
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterestSignal {
+    pub weight: f64,
+    pub reason: String,
+}
+
+/// Where an interest was stated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterestSource {
+    Profile,
+    Onboarding,
+}
+
+impl InterestSource {
+    fn weight(&self) -> f64 {
+        match self {
+            InterestSource::Profile => 1.0,
+            InterestSource::Onboarding => 0.8,
+        }
+    }
+}
+
+/// What a user cares about, keyed by tag
+///
+/// A tag keeps its strongest signal, so a stated interest outranks the same
+/// tag picked up from activity.
+#[derive(Debug, Clone, Default)]
+pub struct InterestProfile {
+    signals: BTreeMap<String, InterestSignal>,
+}
+
+impl InterestProfile {
+    /// An interest from the profile or from onboarding answers
+    pub fn add_stated(&mut self, key: &str, label: &str, source: InterestSource) {
+        let reason = match source {
+            InterestSource::Profile => format!("You're interested in {}", label),
+            InterestSource::Onboarding => format!("You picked {} during onboarding", label),
+        };
+        self.add(key, source.weight(), reason);
+    }
+
+    /// Ledger activity over the last `ACTIVITY_WINDOW_DAYS`; ten events of a
+    /// kind count as much as an onboarding answer
+    pub fn add_activity(&mut self, activity: &[RecentActivity]) {
+        for row in activity.iter().filter(|a| a.events > 0) {
+            let Some((_, tag, noun)) = ACTIVITY_TAGS.iter().find(|(e, _, _)| *e == row.event_type)
+            else {
+                continue;
+            };
+            let weight = InterestSource::Onboarding.weight() * (row.events.min(10) as f64 / 10.0);
+            let reason = format!(
+                "You've logged {} {} in the last two weeks",
+                row.events, noun
+            );
+            self.add(tag, weight, reason);
+        }
+    }
+
+    fn add(&mut self, key: &str, weight: f64, reason: String) {
+        let key = key.trim().to_lowercase();
+        if key.is_empty() {
+            return;
+        }
+        let aliases = INTEREST_TAGS
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, tags)| *tags)
+            .unwrap_or_default();
+        for tag in std::iter::once(key.as_str()).chain(aliases.iter().copied()) {
+            match self.signals.get(tag) {
+                Some(existing) if existing.weight >= weight => {}
+                _ => {
+                    self.signals.insert(
+                        tag.to_string(),
+                        InterestSignal {
+                            weight,
+                            reason: reason.clone(),
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    /// Strongest signal among the tags, matched case-insensitively
+    pub fn affinity<'a>(&self, tags: impl IntoIterator<Item = &'a str>) -> Option<&InterestSignal> {
+        tags.into_iter()
+            .filter_map(|t| self.signals.get(&t.to_lowercase()))
+            .fold(None, |best: Option<&InterestSignal>, s| match best {
+                Some(b) if b.weight >= s.weight => Some(b),
+                _ => Some(s),
+            })
+    }
+}
+
+/// Read interest keys out of a settings value or an onboarding answer
+///
+/// Accepts a list of keys or an object whose keys are the interests.
+pub fn interest_keys(value: &serde_json::Value) -> Vec<String> {
+    match value {
+        serde_json::Value::Array(items) => items
+            .iter()
+            .filter_map(|v| v.as_str().map(String::from))
+            .collect(),
+        serde_json::Value::Object(map) => map.keys().cloned().collect(),
+        serde_json::Value::String(s) => vec![s.clone()],
+        _ => Vec::new(),
+    }
+}
+
+// ============================================================================
+// CANDIDATE ROWS
+// ============================================================================
+
+/// First unfinished lesson of a topic
+#[derive(Debug, Clone, FromRow)]
+pub struct LessonCandidate {
+    pub id: Uuid,
+    pub title: String,
+    pub topic_key: String,
+    pub topic_name: String,
+    pub topic_category: String,
+    pub in_progress: bool,
+}
+
+/// Active listening prompt template
+#[derive(Debug, Clone, FromRow)]
+pub struct PromptCandidate {
+    pub id: Uuid,
+    pub name: String,
+    pub category: String,
+    pub tags: Option<Vec<String>>,
+}
+
+/// Active goal with the last time it moved
+#[derive(Debug, Clone, FromRow)]
+pub struct GoalCandidate {
+    pub id: Uuid,
+    pub title: String,
+    pub category: Option<String>,
+    pub progress: i32,
+    pub target_date: Option<NaiveDate>,
+    pub last_progress_at: DateTime<Utc>,
+}
+
+/// Book being read
+#[derive(Debug, Clone, FromRow)]
+pub struct BookCandidate {
+    pub id: Uuid,
+    pub title: String,
+    pub current_page: i32,
+    pub total_pages: Option<i32>,
+    pub last_read_at: DateTime<Utc>,
+}
+
+/// Ledger activity of one kind over the recent window
+#[derive(Debug, Clone, FromRow)]
+pub struct RecentActivity {
+    pub event_type: String,
+    pub events: i64,
+}
+
+/// Everything that could be suggested today
+#[derive(Debug, Clone, Default)]
+pub struct SuggestionCandidates {
+    pub lessons: Vec<LessonCandidate>,
+    pub prompts: Vec<PromptCandidate>,
+    pub goals: Vec<GoalCandidate>,
+    pub books: Vec<BookCandidate>,
+}
+
+// ============================================================================
+// RANKING
+// ============================================================================
+
+/// Stable tie-breaker in `[0, 0.5)` for one candidate on one day
+///
+/// Keeps the order fixed between polls while letting equally good
+/// suggestions take turns from one day to the next.
+pub fn daily_jitter(user_id: Uuid, date: NaiveDate, kind: SuggestionKind, id: Uuid) -> f64 {
+    let digest = Sha256::new()
+        .chain_update(user_id.as_bytes())
+        .chain_update(date.to_string().as_bytes())
+        .chain_update(kind.as_str().as_bytes())
+        .chain_update(id.as_bytes())
+        .finalize();
+    let bucket = u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]);
+    bucket as f64 / u32::MAX as f64 / 2.0
+}
+
+fn days_since(today: NaiveDate, at: DateTime<Utc>) -> i64 {
+    (today - at.date_naive()).num_days().max(0)
+}
+
+/// Rank candidates against a profile
+///
+/// Scores only depend on the profile, the candidates and `today`, so the
+/// same inputs give the same list all day. Lessons not yet started and
+/// listening prompts need a matching interest; goals, books and started
+/// lessons are the user's own and always qualify.
+pub fn rank_suggestions(
+    profile: &InterestProfile,
+    candidates: &SuggestionCandidates,
+    user_id: Uuid,
+    today: NaiveDate,
+    limit: usize,
+) -> Vec<Suggestion> {
+    let mut ranked = Vec::new();
+    let mut push = |kind: SuggestionKind,
+                    id: Uuid,
+                    title: &str,
+                    route: String,
+                    base: f64,
+                    interest: Option<&InterestSignal>,
+                    mut reasons: Vec<String>| {
+        if let Some(signal) = interest {
+            reasons.insert(0, signal.reason.clone());
+        }
+        let affinity = interest.map_or(0.0, |s| s.weight);
+        ranked.push(Suggestion {
+            kind,
+            id,
+            title: title.to_string(),
+            route,
+            reasons,
+            score: base + 3.0 * affinity + daily_jitter(user_id, today, kind, id),
+        });
+    };
+
+    for lesson in &candidates.lessons {
+        let interest =
+            profile.affinity([lesson.topic_key.as_str(), lesson.topic_category.as_str()]);
+        if interest.is_none() && !lesson.in_progress {
+            continue;
+        }
+        let (base, reason) = if lesson.in_progress {
+            (
+                2.0,
+                format!("You started this {} lesson", lesson.topic_name),
+            )
+        } else {
+            (1.0, format!("Next lesson in {}", lesson.topic_name))
+        };
+        push(
+            SuggestionKind::Lesson,
+            lesson.id,
+            &lesson.title,
+            format!("/learn?lesson={}", lesson.id),
+            base,
+            interest,
+            vec![reason],
+        );
+    }
+
+    for prompt in &candidates.prompts {
+        let tags = prompt.tags.iter().flatten().map(String::as_str);
+        let Some(interest) = profile.affinity(
+            ["listening", prompt.category.as_str()]
+                .into_iter()
+                .chain(tags),
+        ) else {
+            continue;
+        };
+        push(
+            SuggestionKind::ListeningPrompt,
+            prompt.id,
+            &prompt.name,
+            format!("/learn/ear-training?prompt={}", prompt.id),
+            0.5,
+            Some(interest),
+            vec![format!(
+                "A {} listening exercise",
+                prompt.category.replace('_', " ")
+            )],
+        );
+    }
+
+    for goal in &candidates.goals {
+        let idle = days_since(today, goal.last_progress_at);
+        let mut base = 1.5 + idle.min(30) as f64 / 30.0;
+        let mut reasons = vec![format!(
+            "No progress in {} days ({}% done)",
+            idle, goal.progress
+        )];
+        if let Some(target) = goal.target_date {
+            let left = (target - today).num_days();
+            if left < 0 {
+                base += 1.5;
+                reasons.push(format!("Overdue by {} days", -left));
+            } else if left <= 14 {
+                base += 1.5 * (14 - left) as f64 / 14.0;
+                reasons.push(match left {
+                    0 => "Due today".to_string(),
+                    1 => "Due tomorrow".to_string(),
+                    _ => format!("Due in {} days", left),
+                });
+            }
+        }
+        let interest = goal.category.as_deref().and_then(|c| profile.affinity([c]));
+        push(
+            SuggestionKind::Goal,
+            goal.id,
+            &goal.title,
+            format!("/goals?goal={}", goal.id),
+            base,
+            interest,
+            reasons,
+        );
+    }
+
+    for book in &candidates.books {
+        let idle = days_since(today, book.last_read_at);
+        let mut base = 1.0 + idle.min(30) as f64 / 60.0;
+        let mut reasons = Vec::new();
+        match book.total_pages.filter(|&t| t > 0) {
+            Some(total) => {
+                let done = book.current_page as f64 / total as f64;
+                if done >= 0.5 {
+                    base += 0.5;
+                }
+                reasons.push(format!("You're on page {} of {}", book.current_page, total));
+            }
+            None => reasons.push(format!("You're on page {}", book.current_page)),
+        }
+        if idle > 0 {
+            reasons.push(match idle {
+                1 => "Last read yesterday".to_string(),
+                _ => format!("Last read {} days ago", idle),
+            });
+        }
+        push(
+            SuggestionKind::Book,
+            book.id,
+            &book.title,
+            format!("/books?book={}", book.id),
+            base,
+            profile.affinity(["reading"]),
+            reasons,
+        );
+    }
+
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.id.cmp(&b.id)));
+
+    let mut per_kind: HashMap<SuggestionKind, usize> = HashMap::new();
+    ranked
+        .into_iter()
+        .filter(|s| {
+            let count = per_kind.entry(s.kind).or_default();
+            *count += 1;
+            *count <= MAX_PER_KIND
+        })
+        .take(limit)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, 10).unwrap()
+    }
+
+    fn days_ago(days: i64) -> DateTime<Utc> {
+        let date = today() - chrono::Duration::days(days);
+        Utc.from_utc_datetime(&date.and_hms_opt(12, 0, 0).unwrap())
+    }
+
+    fn lesson(topic_key: &str, in_progress: bool) -> LessonCandidate {
+        LessonCandidate {
+            id: Uuid::new_v4(),
+            title: format!("{} 1", topic_key),
+            topic_key: topic_key.to_string(),
+            topic_name: topic_key.to_string(),
+            topic_category: "theory".to_string(),
+            in_progress,
+        }
+    }
+
+    fn goal(idle: i64, target_in: Option<i64>) -> GoalCandidate {
+        GoalCandidate {
+            id: Uuid::new_v4(),
+            title: "Finish EP".to_string(),
+            category: None,
+            progress: 40,
+            target_date: target_in.map(|d| today() + chrono::Duration::days(d)),
+            last_progress_at: days_ago(idle),
+        }
+    }
+
+    #[test]
+    fn test_stated_interests_expand_and_keep_the_strongest_reason() {
+        let mut profile = InterestProfile::default();
+        profile.add_activity(&[RecentActivity {
+            event_type: "drill_complete".to_string(),
+            events: 3,
+        }]);
+        profile.add_stated("music", "Music", InterestSource::Profile);
+
+        let signal = profile.affinity(["listening"]).unwrap();
+        assert_eq!(signal.weight, 1.0);
+        assert_eq!(signal.reason, "You're interested in Music");
+        assert!(profile.affinity(["MUSIC_THEORY"]).is_some());
+        assert!(profile.affinity(["fitness"]).is_none());
+
+        profile.add_activity(&[RecentActivity {
+            event_type: "workout_complete".to_string(),
+            events: 25,
+        }]);
+        let signal = profile.affinity(["fitness_basics"]).unwrap();
+        assert_eq!(signal.weight, 0.8);
+        assert_eq!(
+            signal.reason,
+            "You've logged 25 workouts in the last two weeks"
+        );
+    }
+
+    #[test]
+    fn test_interest_keys_from_lists_and_objects() {
+        assert_eq!(
+            interest_keys(&serde_json::json!(["music", 3, "focus"])),
+            vec!["music", "focus"]
+        );
+        assert_eq!(
+            interest_keys(&serde_json::json!({"fitness": true})),
+            vec!["fitness"]
+        );
+        assert!(interest_keys(&serde_json::json!(null)).is_empty());
+    }
+
+    #[test]
+    fn test_unmatched_lessons_and_prompts_are_left_out() {
+        let mut profile = InterestProfile::default();
+        profile.add_stated("music", "Music", InterestSource::Onboarding);
+        let started = lesson("fitness_basics", true);
+        let candidates = SuggestionCandidates {
+            lessons: vec![
+                lesson("music_theory", false),
+                lesson("habits", false),
+                started.clone(),
+            ],
+            prompts: vec![PromptCandidate {
+                id: Uuid::new_v4(),
+                name: "Kick and bass".to_string(),
+                category: "mixing".to_string(),
+                tags: None,
+            }],
+            ..Default::default()
+        };
+
+        let ranked = rank_suggestions(&profile, &candidates, Uuid::new_v4(), today(), 10);
+        let titles: Vec<_> = ranked.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(ranked.len(), 3);
+        assert!(titles.contains(&"music_theory 1"));
+        assert!(titles.contains(&"Kick and bass"));
+        assert!(!titles.contains(&"habits 1"));
+
+        let started = ranked.iter().find(|s| s.id == started.id).unwrap();
+        assert_eq!(
+            started.reasons,
+            vec!["You started this fitness_basics lesson"]
+        );
+        let theory = ranked.iter().find(|s| s.title == "music_theory 1").unwrap();
+        assert_eq!(theory.reasons[0], "You picked Music during onboarding");
+    }
+
+    #[test]
+    fn test_goals_near_their_deadline_rank_first() {
+        let relaxed = goal(8, None);
+        let due = goal(8, Some(2));
+        let candidates = SuggestionCandidates {
+            goals: vec![relaxed.clone(), due.clone()],
+            ..Default::default()
+        };
+        let ranked = rank_suggestions(
+            &InterestProfile::default(),
+            &candidates,
+            Uuid::new_v4(),
+            today(),
+            10,
+        );
+        assert_eq!(ranked[0].id, due.id);
+        assert_eq!(
+            ranked[0].reasons,
+            vec!["No progress in 8 days (40% done)", "Due in 2 days"]
+        );
+        assert_eq!(ranked[1].id, relaxed.id);
+    }
+
+    #[test]
+    fn test_ranking_is_stable_within_a_day_and_capped_per_kind() {
+        let candidates = SuggestionCandidates {
+            goals: (0..6).map(|_| goal(10, None)).collect(),
+            books: vec![BookCandidate {
+                id: Uuid::new_v4(),
+                title: "Mixing Secrets".to_string(),
+                current_page: 120,
+                total_pages: Some(200),
+                last_read_at: days_ago(1),
+            }],
+            ..Default::default()
+        };
+        let user_id = Uuid::new_v4();
+        let profile = InterestProfile::default();
+
+        let first = rank_suggestions(&profile, &candidates, user_id, today(), MAX_SUGGESTIONS);
+        let again = rank_suggestions(&profile, &candidates, user_id, today(), MAX_SUGGESTIONS);
+        let ids = |s: &[Suggestion]| s.iter().map(|s| s.id).collect::<Vec<_>>();
+        assert_eq!(ids(&first), ids(&again));
+        assert_eq!(first.len(), 3);
+        assert_eq!(
+            first
+                .iter()
+                .filter(|s| s.kind == SuggestionKind::Goal)
+                .count(),
+            MAX_PER_KIND
+        );
+        let book = first
+            .iter()
+            .find(|s| s.kind == SuggestionKind::Book)
+            .unwrap();
+        assert_eq!(
+            book.reasons,
+            vec!["You're on page 120 of 200", "Last read yesterday"]
+        );
+    }
+
+    #[test]
+    fn test_daily_jitter_changes_by_day() {
+        let (user_id, id) = (Uuid::new_v4(), Uuid::new_v4());
+        let a = daily_jitter(user_id, today(), SuggestionKind::Goal, id);
+        assert_eq!(a, daily_jitter(user_id, today(), SuggestionKind::Goal, id));
+        assert!((0.0..0.5).contains(&a));
+        let later: Vec<_> = (1..8)
+            .map(|d| {
+                daily_jitter(
+                    user_id,
+                    today() + chrono::Duration::days(d),
+                    SuggestionKind::Goal,
+                    id,
+                )
+            })
+            .collect();
+        assert!(later.iter().any(|j| *j != a));
+    }
+}
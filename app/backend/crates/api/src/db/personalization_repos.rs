@@ -0,0 +1,190 @@
+//! Personalization Repository
+//!
+//! Loads interests, recent activity and candidate actions, then ranks them
+//! with `personalization_models::rank_suggestions`.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::personalization_models::*;
+use crate::error::AppError;
+
+pub struct PersonalizationRepo;
+
+pub const PERSONALIZATION_PROFILE_INTERESTS: &str = r#"
+    SELECT interest_key, interest_label
+    FROM user_interests
+    WHERE user_id = $1
+    ORDER BY created_at, interest_key
+"#;
+
+pub const PERSONALIZATION_SETTINGS_INTERESTS: &str =
+    "SELECT value FROM user_settings WHERE user_id = $1 AND key = 'interests'";
+
+pub const PERSONALIZATION_ONBOARDING_RESPONSES: &str = r#"
+    SELECT r.response
+    FROM user_onboarding_responses r
+    JOIN onboarding_steps s ON s.id = r.step_id
+    WHERE r.user_id = $1 AND r.response IS NOT NULL
+    ORDER BY r.created_at, s.step_order
+"#;
+
+/// Activity before today only, so the profile holds still during the day
+pub const PERSONALIZATION_RECENT_ACTIVITY: &str = r#"
+    SELECT event_type, COUNT(*) AS events
+    FROM points_ledger
+    WHERE user_id = $1 AND created_at >= $2 AND created_at < $3
+    GROUP BY event_type
+    ORDER BY event_type
+"#;
+
+/// First unfinished lesson of each topic, preferring one already started
+pub const PERSONALIZATION_LESSONS: &str = r#"
+    SELECT DISTINCT ON (t.id)
+        l.id, l.title, t.key AS topic_key, t.name AS topic_name,
+        t.category AS topic_category,
+        COALESCE(p.status = 'in_progress', false) AS in_progress
+    FROM learn_topics t
+    JOIN learn_lessons l ON l.topic_id = t.id AND l.is_active = true
+    LEFT JOIN user_lesson_progress p ON p.lesson_id = l.id AND p.user_id = $1
+    WHERE t.is_active = true AND (p.status IS NULL OR p.status <> 'completed')
+    ORDER BY t.id, COALESCE(p.status = 'in_progress', false) DESC, l.sort_order, l.id
+"#;
+
+pub const PERSONALIZATION_PROMPTS: &str = r#"
+    SELECT id, name, category, tags
+    FROM listening_prompt_templates
+    WHERE is_active = true
+    ORDER BY display_order, id
+    LIMIT 100
+"#;
+
+/// Active goals whose last update or milestone is older than `$2`
+pub const PERSONALIZATION_STALLED_GOALS: &str = r#"
+    SELECT g.id, g.title, g.category, g.progress, g.target_date,
+           GREATEST(g.updated_at, MAX(m.completed_at)) AS last_progress_at
+    FROM goals g
+    LEFT JOIN goal_milestones m ON m.goal_id = g.id
+    WHERE g.user_id = $1 AND g.status = 'active' AND g.archived_at IS NULL
+    GROUP BY g.id
+    HAVING GREATEST(g.updated_at, MAX(m.completed_at)) < $2
+    ORDER BY last_progress_at, g.id
+    LIMIT 20
+"#;
+
+pub const PERSONALIZATION_UNFINISHED_BOOKS: &str = r#"
+    SELECT id, title, current_page, total_pages, updated_at AS last_read_at
+    FROM books
+    WHERE user_id = $1 AND status = 'reading'
+      AND (total_pages IS NULL OR current_page < total_pages)
+    ORDER BY updated_at DESC, id
+    LIMIT 20
+"#;
+
+fn start_of(date: NaiveDate) -> DateTime<Utc> {
+    date.and_hms_opt(0, 0, 0)
+        .expect("midnight is a valid time")
+        .and_utc()
+}
+
+impl PersonalizationRepo {
+    /// What the user said they like and what they have been doing lately
+    pub async fn interest_profile(
+        pool: &PgPool,
+        user_id: Uuid,
+        today: NaiveDate,
+    ) -> Result<InterestProfile, AppError> {
+        let mut profile = InterestProfile::default();
+
+        let interests = sqlx::query_as::<_, (String, String)>(PERSONALIZATION_PROFILE_INTERESTS)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+        for (key, label) in &interests {
+            profile.add_stated(key, label, InterestSource::Profile);
+        }
+
+        let settings =
+            sqlx::query_scalar::<_, serde_json::Value>(PERSONALIZATION_SETTINGS_INTERESTS)
+                .bind(user_id)
+                .fetch_optional(pool)
+                .await?;
+        for key in settings.iter().flat_map(interest_keys) {
+            profile.add_stated(&key, &key, InterestSource::Profile);
+        }
+
+        let responses =
+            sqlx::query_scalar::<_, serde_json::Value>(PERSONALIZATION_ONBOARDING_RESPONSES)
+                .bind(user_id)
+                .fetch_all(pool)
+                .await?;
+        let answers = super::onboarding_models::merge_answers(&responses);
+        for key in answers.get("interests").into_iter().flat_map(interest_keys) {
+            profile.add_stated(&key, &key, InterestSource::Onboarding);
+        }
+
+        let today_start = start_of(today);
+        let activity = sqlx::query_as::<_, RecentActivity>(PERSONALIZATION_RECENT_ACTIVITY)
+            .bind(user_id)
+            .bind(today_start - Duration::days(ACTIVITY_WINDOW_DAYS))
+            .bind(today_start)
+            .fetch_all(pool)
+            .await?;
+        profile.add_activity(&activity);
+
+        Ok(profile)
+    }
+
+    /// Lessons, listening prompts, stalled goals and unfinished books
+    pub async fn candidates(
+        pool: &PgPool,
+        user_id: Uuid,
+        today: NaiveDate,
+    ) -> Result<SuggestionCandidates, AppError> {
+        let lessons = sqlx::query_as::<_, LessonCandidate>(PERSONALIZATION_LESSONS)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+
+        let prompts = sqlx::query_as::<_, PromptCandidate>(PERSONALIZATION_PROMPTS)
+            .fetch_all(pool)
+            .await?;
+
+        let goals = sqlx::query_as::<_, GoalCandidate>(PERSONALIZATION_STALLED_GOALS)
+            .bind(user_id)
+            .bind(start_of(today) - Duration::days(STALLED_GOAL_DAYS - 1))
+            .fetch_all(pool)
+            .await?;
+
+        let books = sqlx::query_as::<_, BookCandidate>(PERSONALIZATION_UNFINISHED_BOOKS)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+
+        Ok(SuggestionCandidates {
+            lessons,
+            prompts,
+            goals,
+            books,
+        })
+    }
+
+    /// Ranked next actions for `today`, strongest first
+    pub async fn suggestions(
+        pool: &PgPool,
+        user_id: Uuid,
+        today: NaiveDate,
+        limit: usize,
+    ) -> Result<Vec<Suggestion>, AppError> {
+        let profile = Self::interest_profile(pool, user_id, today).await?;
+        let candidates = Self::candidates(pool, user_id, today).await?;
+        Ok(rank_suggestions(
+            &profile,
+            &candidates,
+            user_id,
+            today,
+            limit,
+        ))
+    }
+}
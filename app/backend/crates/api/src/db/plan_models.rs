@@ -0,0 +1,795 @@
+//! Daily Plan Models
+//!
+//! What plan generation reads, how it schedules a day, and reusable plan
+//! templates. The stored plan and its items live in `platform_models`.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, DurationRound, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::habits_goals_models::HabitSchedule;
+use super::platform_models::PlanItem;
+
+/// Focus blocks fitted into a generated day
+pub const FOCUS_BLOCKS: usize = 2;
+
+/// Break left after a focus block before the next one starts
+pub const FOCUS_BREAK_MINUTES: i64 = 10;
+
+/// How long a calendar event without an end time blocks
+pub const UNTIMED_EVENT_MINUTES: i64 = 30;
+
+/// Goals due within this many days are planned
+pub const GOAL_HORIZON_DAYS: i64 = 14;
+
+/// Most goals and quests in one generated plan
+pub const MAX_GOAL_ITEMS: usize = 3;
+pub const MAX_QUEST_ITEMS: usize = 2;
+
+/// Item types a plan can hold
+pub const PLAN_ITEM_TYPES: &[&str] = &[
+    "focus", "event", "goal", "habit", "quest", "workout", "learning", "task",
+];
+
+/// Item types produced fresh for each day and never carried over
+const DAY_BOUND_TYPES: &[&str] = &["focus", "event", "habit"];
+
+/// Prefix of item ids produced by generation
+pub const GENERATED_ID_PREFIX: &str = "plan_";
+
+/// Longest template name
+pub const MAX_TEMPLATE_NAME_LEN: usize = 100;
+
+/// Most items in one template
+pub const MAX_TEMPLATE_ITEMS: usize = 50;
+
+// ============================================================================
+// GENERATION INPUTS
+// ============================================================================
+
+/// The day being planned, resolved in the user's timezone
+#[derive(Debug, Clone, FromRow)]
+pub struct PlanDay {
+    /// Local midnight to midnight
+    pub day_start: DateTime<Utc>,
+    pub day_end: DateTime<Utc>,
+    /// Local workday, where focus blocks go
+    pub work_start: DateTime<Utc>,
+    pub work_end: DateTime<Utc>,
+    pub focus_minutes: i32,
+}
+
+/// Calendar event on the day
+#[derive(Debug, Clone, FromRow)]
+pub struct PlanEvent {
+    pub id: Uuid,
+    pub title: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: Option<DateTime<Utc>>,
+    pub all_day: bool,
+}
+
+/// Active habit with its completions so far
+#[derive(Debug, Clone, FromRow)]
+pub struct PlanHabit {
+    pub id: Uuid,
+    pub name: String,
+    pub frequency: String,
+    pub target_count: i32,
+    pub custom_days: Option<Vec<i32>>,
+    pub done_today: i64,
+    pub done_this_week: i64,
+}
+
+/// Active goal due soon or overdue
+#[derive(Debug, Clone, FromRow)]
+pub struct PlanGoal {
+    pub id: Uuid,
+    pub title: String,
+    pub target_date: NaiveDate,
+    pub progress: i32,
+}
+
+/// Open universal quest
+#[derive(Debug, Clone, FromRow)]
+pub struct PlanQuest {
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+}
+
+/// Everything generation reads for one day
+#[derive(Debug, Clone)]
+pub struct PlanInputs {
+    pub date: NaiveDate,
+    pub day: PlanDay,
+    pub events: Vec<PlanEvent>,
+    pub habits: Vec<PlanHabit>,
+    pub goals: Vec<PlanGoal>,
+    pub quests: Vec<PlanQuest>,
+    /// Items of the day before
+    pub previous: Vec<PlanItem>,
+    /// Items already planned for the day
+    pub existing: Vec<PlanItem>,
+}
+
+// ============================================================================
+// SCHEDULING
+// ============================================================================
+
+/// A stretch of time, start inclusive and end exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeSlot {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Gaps in `window` left free by `busy`
+pub fn free_slots(window: TimeSlot, busy: &[TimeSlot]) -> Vec<TimeSlot> {
+    let mut busy: Vec<TimeSlot> = busy
+        .iter()
+        .filter(|b| b.end > window.start && b.start < window.end)
+        .copied()
+        .collect();
+    busy.sort_by_key(|b| b.start);
+
+    let mut free = Vec::new();
+    let mut cursor = window.start;
+    for b in busy {
+        if b.start > cursor {
+            free.push(TimeSlot {
+                start: cursor,
+                end: b.start,
+            });
+        }
+        cursor = cursor.max(b.end);
+    }
+    if cursor < window.end {
+        free.push(TimeSlot {
+            start: cursor,
+            end: window.end,
+        });
+    }
+    free
+}
+
+/// Place up to `count` blocks of `minutes` in the free slots, earliest first,
+/// with a break after each
+pub fn place_blocks(free: &[TimeSlot], minutes: i64, count: usize) -> Vec<TimeSlot> {
+    let length = Duration::minutes(minutes);
+    let mut blocks = Vec::new();
+    for slot in free {
+        let mut start = slot.start;
+        while blocks.len() < count && start + length <= slot.end {
+            blocks.push(TimeSlot {
+                start,
+                end: start + length,
+            });
+            start += length + Duration::minutes(FOCUS_BREAK_MINUTES);
+        }
+    }
+    blocks
+}
+
+fn deadline_note(target_date: NaiveDate, date: NaiveDate, progress: i32) -> String {
+    let days = (target_date - date).num_days();
+    let due = match days {
+        d if d < -1 => format!("Overdue by {} days", -d),
+        -1 => "Overdue by 1 day".to_string(),
+        0 => "Due today".to_string(),
+        1 => "Due tomorrow".to_string(),
+        d => format!("Due in {} days", d),
+    };
+    format!("{} · {}% done", due, progress)
+}
+
+fn item(id: String, item_type: &str, title: String, action_url: &str) -> PlanItem {
+    PlanItem {
+        id,
+        item_type: item_type.to_string(),
+        title,
+        description: None,
+        duration: None,
+        action_url: action_url.to_string(),
+        completed: false,
+        priority: 0,
+        starts_at: None,
+        ends_at: None,
+        carried_over_from: None,
+    }
+}
+
+/// Build a day's plan
+///
+/// Order, which is also priority: the day's schedule (all-day events, then
+/// timed events and focus blocks by start), goals by deadline, items
+/// carried over from the day before, due habits, then quests. Focus blocks
+/// only use workday time that is free and, for today, still ahead of `now`.
+/// Regenerating keeps what was already checked off and anything added by
+/// hand or from a template.
+pub fn build_plan(inputs: &PlanInputs, now: DateTime<Utc>) -> Vec<PlanItem> {
+    let date = inputs.date;
+    let day = &inputs.day;
+    let mut items = Vec::new();
+
+    // Schedule
+    let mut timed = Vec::new();
+    let mut busy = Vec::new();
+    for event in &inputs.events {
+        let mut entry = item(
+            format!("plan_event_{}", event.id),
+            "event",
+            event.title.clone(),
+            "/planner",
+        );
+        if event.all_day {
+            entry.description = Some("All day".to_string());
+            items.push(entry);
+            continue;
+        }
+        let end = event
+            .end_time
+            .filter(|end| *end > event.start_time)
+            .unwrap_or(event.start_time + Duration::minutes(UNTIMED_EVENT_MINUTES));
+        entry.starts_at = Some(event.start_time);
+        entry.ends_at = Some(end);
+        entry.duration = Some((end - event.start_time).num_minutes() as i32);
+        busy.push(TimeSlot {
+            start: event.start_time,
+            end,
+        });
+        timed.push(entry);
+    }
+
+    // Blocks start on a five-minute mark and never in the past
+    let earliest = now
+        .duration_trunc(Duration::minutes(5))
+        .map(|t| if t < now { t + Duration::minutes(5) } else { t })
+        .unwrap_or(now);
+    let window = TimeSlot {
+        start: day.work_start.max(earliest),
+        end: day.work_end,
+    };
+    let focus_minutes = i64::from(day.focus_minutes.clamp(5, 180));
+    let blocks = if window.start < window.end {
+        place_blocks(&free_slots(window, &busy), focus_minutes, FOCUS_BLOCKS)
+    } else {
+        Vec::new()
+    };
+    let focus_goal = inputs.goals.first();
+    for (n, block) in blocks.iter().enumerate() {
+        let (title, description) = match focus_goal {
+            Some(goal) if n == 0 => (
+                format!("Focus: {}", goal.title),
+                deadline_note(goal.target_date, date, goal.progress),
+            ),
+            _ => (
+                "Focus Session".to_string(),
+                format!("Complete a {}-minute focus session", focus_minutes),
+            ),
+        };
+        let mut entry = item(
+            format!("plan_focus_{}_{}", date, n + 1),
+            "focus",
+            title,
+            "/focus",
+        );
+        entry.description = Some(description);
+        entry.duration = Some(focus_minutes as i32);
+        entry.starts_at = Some(block.start);
+        entry.ends_at = Some(block.end);
+        timed.push(entry);
+    }
+    timed.sort_by_key(|i| i.starts_at);
+    items.extend(timed);
+
+    // Goals by deadline
+    for goal in inputs.goals.iter().take(MAX_GOAL_ITEMS) {
+        let mut entry = item(
+            format!("plan_goal_{}", goal.id),
+            "goal",
+            goal.title.clone(),
+            "/goals",
+        );
+        entry.description = Some(deadline_note(goal.target_date, date, goal.progress));
+        items.push(entry);
+    }
+
+    // Unfinished items from the day before
+    let previous_date = date.pred_opt();
+    for prev in &inputs.previous {
+        if prev.completed || DAY_BOUND_TYPES.contains(&prev.item_type.as_str()) {
+            continue;
+        }
+        let mut entry = prev.clone();
+        entry.carried_over_from = prev.carried_over_from.or(previous_date);
+        entry.starts_at = None;
+        entry.ends_at = None;
+        items.push(entry);
+    }
+
+    // Habits due today
+    for habit in &inputs.habits {
+        let schedule = HabitSchedule::new(
+            &habit.frequency,
+            habit.target_count,
+            habit.custom_days.as_deref(),
+        );
+        let remaining = schedule.remaining_on(date, habit.done_today, habit.done_this_week);
+        if remaining == 0 {
+            continue;
+        }
+        let mut entry = item(
+            format!("plan_habit_{}", habit.id),
+            "habit",
+            habit.name.clone(),
+            "/habits",
+        );
+        let target = habit.target_count.max(1);
+        entry.description = match habit.frequency.as_str() {
+            "weekly" => Some(format!("{} of {} this week", remaining, target)),
+            _ if target > 1 => Some(format!("{} of {} today", remaining, target)),
+            _ => None,
+        };
+        items.push(entry);
+    }
+
+    // Quests
+    for quest in inputs.quests.iter().take(MAX_QUEST_ITEMS) {
+        let mut entry = item(
+            format!("plan_quest_{}", quest.id),
+            "quest",
+            quest.title.clone(),
+            "/quests",
+        );
+        entry.description = quest.description.clone();
+        items.push(entry);
+    }
+
+    // Merge with what is already planned
+    let mut seen = HashSet::new();
+    items.retain(|i| seen.insert(i.id.clone()));
+    for existing in &inputs.existing {
+        if let Some(generated) = items.iter_mut().find(|i| i.id == existing.id) {
+            generated.completed = existing.completed;
+        } else if existing.completed || !existing.id.starts_with(GENERATED_ID_PREFIX) {
+            items.push(existing.clone());
+        }
+    }
+
+    for (priority, entry) in items.iter_mut().enumerate() {
+        entry.priority = priority as i32;
+    }
+    items
+}
+
+// ============================================================================
+// TEMPLATES
+// ============================================================================
+
+/// Plan template database model
+#[derive(Debug, Clone, FromRow)]
+pub struct PlanTemplate {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub items: serde_json::Value,
+    pub is_public: bool,
+    pub category: Option<String>,
+    pub use_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An item a template adds to a plan; times and completion belong to a day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanTemplateItem {
+    #[serde(rename = "type")]
+    pub item_type: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub duration: Option<i32>,
+    pub action_url: String,
+}
+
+impl From<&PlanItem> for PlanTemplateItem {
+    fn from(item: &PlanItem) -> Self {
+        Self {
+            item_type: item.item_type.clone(),
+            title: item.title.clone(),
+            description: item.description.clone(),
+            duration: item.duration,
+            action_url: item.action_url.clone(),
+        }
+    }
+}
+
+/// Items of a plan worth keeping in a template; calendar events belong to
+/// their day
+pub fn template_items_from_plan(items: &[PlanItem]) -> Vec<PlanTemplateItem> {
+    items
+        .iter()
+        .filter(|i| i.item_type != "event")
+        .map(PlanTemplateItem::from)
+        .collect()
+}
+
+/// Add a template's items to a day's items
+///
+/// Replacing keeps only the items already checked off. Template items get
+/// ids derived from the template, so applying it twice adds nothing new.
+pub fn apply_template_items(
+    existing: Vec<PlanItem>,
+    template_id: Uuid,
+    template_items: &[PlanTemplateItem],
+    replace: bool,
+) -> Vec<PlanItem> {
+    let mut items: Vec<PlanItem> = existing
+        .into_iter()
+        .filter(|i| !replace || i.completed)
+        .collect();
+    for (n, t) in template_items.iter().enumerate() {
+        let id = format!("tpl_{}_{}", template_id, n + 1);
+        if items.iter().any(|i| i.id == id) {
+            continue;
+        }
+        let mut entry = item(id, &t.item_type, t.title.clone(), &t.action_url);
+        entry.description = t.description.clone();
+        entry.duration = t.duration;
+        items.push(entry);
+    }
+    for (priority, entry) in items.iter_mut().enumerate() {
+        entry.priority = priority as i32;
+    }
+    items
+}
+
+/// Save template request; `items` or `from_date` (a plan to copy) is required
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreatePlanTemplateRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    #[serde(default)]
+    pub is_public: bool,
+    pub items: Option<Vec<PlanTemplateItem>>,
+    pub from_date: Option<NaiveDate>,
+}
+
+/// Update template request; omitted fields are left unchanged
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdatePlanTemplateRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub is_public: Option<bool>,
+    pub items: Option<Vec<PlanTemplateItem>>,
+}
+
+/// Apply template request; the date defaults to today
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ApplyPlanTemplateRequest {
+    pub date: Option<NaiveDate>,
+    /// Replace the unfinished items instead of adding to them
+    #[serde(default)]
+    pub replace: bool,
+}
+
+/// Plan template response
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanTemplateResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub is_public: bool,
+    pub use_count: i32,
+    pub items: Vec<PlanTemplateItem>,
+    /// Whether the requesting user owns it and may change it
+    pub is_owner: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PlanTemplateResponse {
+    pub fn for_user(t: PlanTemplate, user_id: Uuid) -> Self {
+        Self {
+            id: t.id,
+            name: t.name,
+            description: t.description,
+            category: t.category,
+            is_public: t.is_public,
+            use_count: t.use_count,
+            items: serde_json::from_value(t.items).unwrap_or_default(),
+            is_owner: t.user_id == user_id,
+            created_at: t.created_at,
+            updated_at: t.updated_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, 11).unwrap()
+    }
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.from_utc_datetime(&date().and_hms_opt(hour, minute, 0).unwrap())
+    }
+
+    fn slot(start: (u32, u32), end: (u32, u32)) -> TimeSlot {
+        TimeSlot {
+            start: at(start.0, start.1),
+            end: at(end.0, end.1),
+        }
+    }
+
+    fn inputs() -> PlanInputs {
+        PlanInputs {
+            date: date(),
+            day: PlanDay {
+                day_start: at(0, 0),
+                day_end: at(0, 0) + Duration::days(1),
+                work_start: at(9, 0),
+                work_end: at(18, 0),
+                focus_minutes: 50,
+            },
+            events: Vec::new(),
+            habits: Vec::new(),
+            goals: Vec::new(),
+            quests: Vec::new(),
+            previous: Vec::new(),
+            existing: Vec::new(),
+        }
+    }
+
+    fn early() -> DateTime<Utc> {
+        at(7, 0)
+    }
+
+    #[test]
+    fn test_free_slots_skip_overlapping_events() {
+        let free = free_slots(
+            slot((9, 0), (18, 0)),
+            &[
+                slot((8, 0), (9, 30)),
+                slot((11, 0), (12, 0)),
+                slot((11, 30), (13, 0)),
+                slot((17, 30), (19, 0)),
+            ],
+        );
+        assert_eq!(free, vec![slot((9, 30), (11, 0)), slot((13, 0), (17, 30))]);
+    }
+
+    #[test]
+    fn test_blocks_fit_free_time_with_breaks() {
+        let free = [slot((9, 0), (9, 40)), slot((10, 0), (12, 0))];
+        assert_eq!(
+            place_blocks(&free, 50, 3),
+            vec![slot((10, 0), (10, 50)), slot((11, 0), (11, 50))]
+        );
+        assert!(place_blocks(&[slot((9, 0), (9, 20))], 25, 2).is_empty());
+    }
+
+    #[test]
+    fn test_focus_blocks_work_around_the_calendar() {
+        let mut inputs = inputs();
+        let meeting = Uuid::new_v4();
+        inputs.events = vec![
+            PlanEvent {
+                id: meeting,
+                title: "Standup".to_string(),
+                start_time: at(9, 0),
+                end_time: Some(at(10, 0)),
+                all_day: false,
+            },
+            PlanEvent {
+                id: Uuid::new_v4(),
+                title: "Birthday".to_string(),
+                start_time: at(0, 0),
+                end_time: None,
+                all_day: true,
+            },
+        ];
+        inputs.goals = vec![PlanGoal {
+            id: Uuid::new_v4(),
+            title: "Finish EP".to_string(),
+            target_date: date() + Duration::days(3),
+            progress: 60,
+        }];
+
+        let plan = build_plan(&inputs, early());
+        let summary: Vec<_> = plan
+            .iter()
+            .map(|i| (i.item_type.as_str(), i.title.as_str(), i.starts_at))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("event", "Birthday", None),
+                ("event", "Standup", Some(at(9, 0))),
+                ("focus", "Focus: Finish EP", Some(at(10, 0))),
+                ("focus", "Focus Session", Some(at(11, 0))),
+                ("goal", "Finish EP", None),
+            ]
+        );
+        assert_eq!(
+            plan[4].description.as_deref(),
+            Some("Due in 3 days · 60% done")
+        );
+        assert_eq!(
+            plan.iter().map(|i| i.priority).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4]
+        );
+
+        // Late in the day only what is left of the workday is used
+        let plan = build_plan(&inputs, at(16, 52));
+        let focus: Vec<_> = plan.iter().filter(|i| i.item_type == "focus").collect();
+        assert_eq!(focus.len(), 1);
+        assert_eq!(focus[0].starts_at, Some(at(16, 55)));
+    }
+
+    #[test]
+    fn test_habits_due_today_only() {
+        let mut inputs = inputs();
+        let habit = |name: &str, frequency: &str, custom_days: Option<Vec<i32>>| PlanHabit {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            frequency: frequency.to_string(),
+            target_count: 1,
+            custom_days,
+            done_today: 0,
+            done_this_week: 0,
+        };
+        // 2026-03-11 is a Wednesday
+        inputs.habits = vec![
+            habit("Stretch", "daily", None),
+            habit("Gym", "custom", Some(vec![1, 3, 5])),
+            habit("Long run", "custom", Some(vec![0, 6])),
+            PlanHabit {
+                target_count: 3,
+                done_this_week: 1,
+                ..habit("Practice scales", "weekly", None)
+            },
+        ];
+        let plan = build_plan(&inputs, early());
+        let habits: Vec<_> = plan
+            .iter()
+            .filter(|i| i.item_type == "habit")
+            .map(|i| (i.title.as_str(), i.description.as_deref()))
+            .collect();
+        assert_eq!(
+            habits,
+            vec![
+                ("Stretch", None),
+                ("Gym", None),
+                ("Practice scales", Some("2 of 3 this week")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unfinished_items_carry_over_once() {
+        let mut inputs = inputs();
+        let task = |id: &str, item_type: &str, completed: bool| PlanItem {
+            completed,
+            ..item(id.to_string(), item_type, id.to_string(), "/planner")
+        };
+        let yesterday = date().pred_opt().unwrap();
+        let older = yesterday.pred_opt().unwrap();
+        inputs.previous = vec![
+            task("call_label", "task", false),
+            task("mix_notes", "task", true),
+            task("plan_habit_x", "habit", false),
+            PlanItem {
+                carried_over_from: Some(older),
+                ..task("tpl_a_1", "learning", false)
+            },
+        ];
+        // Already checked off today after an earlier generation
+        inputs.existing = vec![task("call_label", "task", true)];
+
+        let plan = build_plan(&inputs, early());
+        let carried: Vec<_> = plan
+            .iter()
+            .filter(|i| i.item_type != "focus")
+            .map(|i| (i.id.as_str(), i.completed, i.carried_over_from))
+            .collect();
+        assert_eq!(
+            carried,
+            vec![
+                ("call_label", true, Some(yesterday)),
+                ("tpl_a_1", false, Some(older)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_regenerating_keeps_manual_and_completed_items() {
+        let mut inputs = inputs();
+        let quest = Uuid::new_v4();
+        inputs.quests = vec![PlanQuest {
+            id: quest,
+            title: "Daily listening".to_string(),
+            description: None,
+        }];
+        inputs.existing = vec![
+            PlanItem {
+                completed: true,
+                ..item(
+                    format!("plan_quest_{}", quest),
+                    "quest",
+                    "Old".into(),
+                    "/quests",
+                )
+            },
+            item("manual_1".into(), "task", "Buy strings".into(), "/planner"),
+            item(
+                "plan_quest_gone".into(),
+                "quest",
+                "Expired".into(),
+                "/quests",
+            ),
+            PlanItem {
+                completed: true,
+                ..item("plan_habit_gone".into(), "habit", "Done".into(), "/habits")
+            },
+        ];
+        let plan = build_plan(&inputs, early());
+        let ids: Vec<_> = plan
+            .iter()
+            .filter(|i| i.item_type != "focus")
+            .map(|i| (i.title.as_str(), i.completed))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                ("Daily listening", true),
+                ("Buy strings", false),
+                ("Done", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_template_merges_or_replaces() {
+        let template_id = Uuid::new_v4();
+        let template = vec![PlanTemplateItem {
+            item_type: "learning".to_string(),
+            title: "Ear training".to_string(),
+            description: None,
+            duration: Some(15),
+            action_url: "/learn/ear-training".to_string(),
+        }];
+        let existing = vec![
+            item("a".into(), "task", "Open".into(), "/planner"),
+            PlanItem {
+                completed: true,
+                ..item("b".into(), "task", "Done".into(), "/planner")
+            },
+        ];
+
+        let merged = apply_template_items(existing.clone(), template_id, &template, false);
+        assert_eq!(merged.len(), 3);
+        let again = apply_template_items(merged.clone(), template_id, &template, false);
+        assert_eq!(again.len(), 3);
+
+        let replaced = apply_template_items(existing, template_id, &template, true);
+        assert_eq!(
+            replaced
+                .iter()
+                .map(|i| i.title.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Done", "Ear training"]
+        );
+        assert_eq!(replaced[1].priority, 1);
+    }
+}
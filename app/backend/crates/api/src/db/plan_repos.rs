@@ -0,0 +1,355 @@
+//! Daily Plan Repository
+//!
+//! Loads what plan generation needs for a day, and stores plan templates.
+//! Plans themselves are read and written by `platform_repos::DailyPlanRepo`.
+
+use chrono::{Duration, NaiveDate, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::plan_models::*;
+use super::platform_models::DailyPlanResponse;
+use super::platform_repos::DailyPlanRepo;
+use crate::error::AppError;
+
+pub struct PlanRepo;
+
+/// The day's bounds in the user's timezone, with their workday and focus length
+pub const PLAN_DAY: &str = r#"
+    WITH settings AS (
+        SELECT jsonb_object_agg(key, value) AS s
+        FROM user_settings
+        WHERE user_id = $1
+    ),
+    prefs AS (
+        SELECT
+            p.timezone,
+            CASE WHEN settings.s->>'workday_start' ~ '^([01]?[0-9]|2[0-3]):[0-5][0-9]$'
+                THEN (settings.s->>'workday_start')::time END AS work_start,
+            CASE WHEN settings.s->>'workday_end' ~ '^([01]?[0-9]|2[0-3]):[0-5][0-9]$'
+                THEN (settings.s->>'workday_end')::time END AS work_end,
+            CASE WHEN jsonb_typeof(settings.s->'focus_duration') = 'number'
+                THEN round((settings.s->>'focus_duration')::numeric)::int END AS focus_minutes
+        FROM notification_preferences p
+        CROSS JOIN settings
+        WHERE p.user_id = $1
+    )
+    SELECT
+        $2::date::timestamp AT TIME ZONE timezone AS day_start,
+        ($2::date + 1)::timestamp AT TIME ZONE timezone AS day_end,
+        ($2::date + COALESCE(work_start, TIME '09:00')) AT TIME ZONE timezone AS work_start,
+        ($2::date + COALESCE(work_end, TIME '18:00')) AT TIME ZONE timezone AS work_end,
+        COALESCE(focus_minutes, 25) AS focus_minutes
+    FROM prefs
+"#;
+
+pub const PLAN_EVENTS: &str = r#"
+    SELECT id, title, start_time, end_time, all_day
+    FROM calendar_events
+    WHERE user_id = $1 AND start_time < $3 AND COALESCE(end_time, start_time) >= $2
+    ORDER BY start_time, id
+"#;
+
+/// Active habits with completions on `$2` and in its week (from Monday)
+pub const PLAN_HABITS: &str = r#"
+    SELECT h.id, h.name, h.frequency, h.target_count, h.custom_days,
+           COUNT(c.id) FILTER (WHERE c.completed_date = $2) AS done_today,
+           COUNT(c.id) AS done_this_week
+    FROM habits h
+    LEFT JOIN habit_completions c
+        ON c.habit_id = h.id
+        AND c.completed_date BETWEEN date_trunc('week', $2::date)::date AND $2
+    WHERE h.user_id = $1 AND h.is_active = true
+    GROUP BY h.id
+    ORDER BY h.sort_order, h.created_at, h.id
+"#;
+
+/// Active goals due by `$3`, overdue ones included, soonest first
+pub const PLAN_GOALS: &str = r#"
+    SELECT id, title, target_date, progress
+    FROM goals
+    WHERE user_id = $1 AND status = 'active' AND archived_at IS NULL
+      AND target_date IS NOT NULL AND target_date <= $3
+    ORDER BY target_date, priority DESC, id
+    LIMIT $2
+"#;
+
+/// Open quests, those already under way first
+pub const PLAN_QUESTS: &str = r#"
+    SELECT q.id, q.title, q.description
+    FROM universal_quests q
+    LEFT JOIN user_quest_progress p ON q.id = p.quest_id AND p.user_id = $1
+    WHERE q.is_active = true AND p.completed_at IS NULL
+    ORDER BY (p.id IS NOT NULL) DESC, q.sort_order, q.id
+    LIMIT $2
+"#;
+
+macro_rules! plan_template_columns {
+    () => {
+        r#"id, user_id, name, description, items, is_public, category, use_count,
+    created_at, updated_at"#
+    };
+}
+
+/// The user's own templates, then public ones by popularity
+pub const PLAN_TEMPLATE_LIST: &str = concat!(
+    "SELECT ",
+    plan_template_columns!(),
+    r#" FROM plan_templates
+    WHERE user_id = $1 OR is_public = true
+    ORDER BY (user_id = $1) DESC, use_count DESC, lower(name), id"#
+);
+
+pub const PLAN_TEMPLATE_CREATE: &str = concat!(
+    r#"
+    INSERT INTO plan_templates (user_id, name, description, items, is_public, category, use_count)
+    VALUES ($1, $2, $3, $4, $5, $6, 0)
+    ON CONFLICT (user_id, lower(name)) DO NOTHING
+    RETURNING "#,
+    plan_template_columns!()
+);
+
+pub const PLAN_TEMPLATE_GET_OWNED: &str = concat!(
+    "SELECT ",
+    plan_template_columns!(),
+    " FROM plan_templates WHERE id = $1 AND user_id = $2"
+);
+
+pub const PLAN_TEMPLATE_NAME_TAKEN: &str = r#"
+    SELECT EXISTS(
+        SELECT 1 FROM plan_templates
+        WHERE user_id = $1 AND lower(name) = lower($2) AND id <> $3
+    )
+"#;
+
+pub const PLAN_TEMPLATE_UPDATE: &str = concat!(
+    r#"
+    UPDATE plan_templates
+    SET name = $3, description = $4, items = $5, is_public = $6, category = $7,
+        updated_at = NOW()
+    WHERE id = $1 AND user_id = $2
+    RETURNING "#,
+    plan_template_columns!()
+);
+
+pub const PLAN_TEMPLATE_DELETE: &str = "DELETE FROM plan_templates WHERE id = $1 AND user_id = $2";
+
+/// Count a use of a template the user can see
+pub const PLAN_TEMPLATE_USE: &str = concat!(
+    r#"
+    UPDATE plan_templates
+    SET use_count = use_count + 1
+    WHERE id = $1 AND (user_id = $2 OR is_public = true)
+    RETURNING "#,
+    plan_template_columns!()
+);
+
+impl PlanRepo {
+    /// Everything generation reads for `date`
+    pub async fn inputs(
+        pool: &PgPool,
+        user_id: Uuid,
+        date: NaiveDate,
+    ) -> Result<PlanInputs, AppError> {
+        let day = sqlx::query_as::<_, PlanDay>(PLAN_DAY)
+            .bind(user_id)
+            .bind(date)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".into()))?;
+
+        let events = sqlx::query_as::<_, PlanEvent>(PLAN_EVENTS)
+            .bind(user_id)
+            .bind(day.day_start)
+            .bind(day.day_end)
+            .fetch_all(pool)
+            .await?;
+
+        let habits = sqlx::query_as::<_, PlanHabit>(PLAN_HABITS)
+            .bind(user_id)
+            .bind(date)
+            .fetch_all(pool)
+            .await?;
+
+        let goals = sqlx::query_as::<_, PlanGoal>(PLAN_GOALS)
+            .bind(user_id)
+            .bind(MAX_GOAL_ITEMS as i64)
+            .bind(date + Duration::days(GOAL_HORIZON_DAYS))
+            .fetch_all(pool)
+            .await?;
+
+        let quests = sqlx::query_as::<_, PlanQuest>(PLAN_QUESTS)
+            .bind(user_id)
+            .bind(MAX_QUEST_ITEMS as i64)
+            .fetch_all(pool)
+            .await?;
+
+        let previous = match date.pred_opt() {
+            Some(yesterday) => DailyPlanRepo::get_for_date(pool, user_id, yesterday)
+                .await?
+                .map(|p| p.items)
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+        let existing = DailyPlanRepo::get_for_date(pool, user_id, date)
+            .await?
+            .map(|p| p.items)
+            .unwrap_or_default();
+
+        Ok(PlanInputs {
+            date,
+            day,
+            events,
+            habits,
+            goals,
+            quests,
+            previous,
+            existing,
+        })
+    }
+}
+
+// ============================================================================
+// TEMPLATES
+// ============================================================================
+
+pub struct PlanTemplateRepo;
+
+fn items_json(items: &[PlanTemplateItem]) -> Result<serde_json::Value, AppError> {
+    serde_json::to_value(items).map_err(|e| AppError::Internal(e.to_string()))
+}
+
+fn name_taken(name: &str) -> AppError {
+    AppError::Conflict {
+        message: format!("A plan template named {} already exists", name),
+        details: None,
+    }
+}
+
+impl PlanTemplateRepo {
+    /// The user's templates and everyone's public ones
+    pub async fn list(pool: &PgPool, user_id: Uuid) -> Result<Vec<PlanTemplateResponse>, AppError> {
+        let templates = sqlx::query_as::<_, PlanTemplate>(PLAN_TEMPLATE_LIST)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+
+        Ok(templates
+            .into_iter()
+            .map(|t| PlanTemplateResponse::for_user(t, user_id))
+            .collect())
+    }
+
+    /// Save a template; `items` has already been resolved from the request
+    pub async fn create(
+        pool: &PgPool,
+        user_id: Uuid,
+        req: &CreatePlanTemplateRequest,
+        items: &[PlanTemplateItem],
+    ) -> Result<PlanTemplateResponse, AppError> {
+        let name = req.name.trim();
+        let template = sqlx::query_as::<_, PlanTemplate>(PLAN_TEMPLATE_CREATE)
+            .bind(user_id)
+            .bind(name)
+            .bind(&req.description)
+            .bind(items_json(items)?)
+            .bind(req.is_public)
+            .bind(&req.category)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| name_taken(name))?;
+
+        Ok(PlanTemplateResponse::for_user(template, user_id))
+    }
+
+    /// Update one of the user's templates
+    pub async fn update(
+        pool: &PgPool,
+        user_id: Uuid,
+        id: Uuid,
+        req: &UpdatePlanTemplateRequest,
+    ) -> Result<PlanTemplateResponse, AppError> {
+        let existing = sqlx::query_as::<_, PlanTemplate>(PLAN_TEMPLATE_GET_OWNED)
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Plan template not found".into()))?;
+
+        let name = req
+            .name
+            .as_deref()
+            .map(str::trim)
+            .unwrap_or(&existing.name)
+            .to_string();
+        let taken: bool = sqlx::query_scalar(PLAN_TEMPLATE_NAME_TAKEN)
+            .bind(user_id)
+            .bind(&name)
+            .bind(id)
+            .fetch_one(pool)
+            .await?;
+        if taken {
+            return Err(name_taken(&name));
+        }
+
+        let items = match &req.items {
+            Some(items) => items_json(items)?,
+            None => existing.items,
+        };
+        let template = sqlx::query_as::<_, PlanTemplate>(PLAN_TEMPLATE_UPDATE)
+            .bind(id)
+            .bind(user_id)
+            .bind(&name)
+            .bind(req.description.clone().or(existing.description))
+            .bind(items)
+            .bind(req.is_public.unwrap_or(existing.is_public))
+            .bind(req.category.clone().or(existing.category))
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Plan template not found".into()))?;
+
+        Ok(PlanTemplateResponse::for_user(template, user_id))
+    }
+
+    /// Delete one of the user's templates
+    pub async fn delete(pool: &PgPool, user_id: Uuid, id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query(PLAN_TEMPLATE_DELETE)
+            .bind(id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Plan template not found".into()));
+        }
+        Ok(())
+    }
+
+    /// Add a template's items to the plan for `date` and count the use
+    pub async fn apply(
+        pool: &PgPool,
+        user_id: Uuid,
+        id: Uuid,
+        req: &ApplyPlanTemplateRequest,
+    ) -> Result<DailyPlanResponse, AppError> {
+        let date = req.date.unwrap_or_else(|| Utc::now().date_naive());
+        let mut tx = pool.begin().await?;
+
+        let template = sqlx::query_as::<_, PlanTemplate>(PLAN_TEMPLATE_USE)
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Plan template not found".into()))?;
+        let template_items: Vec<PlanTemplateItem> =
+            serde_json::from_value(template.items).unwrap_or_default();
+
+        let plan = DailyPlanRepo::get_for_date(&mut *tx, user_id, date).await?;
+        let (existing, notes) = plan.map(|p| (p.items, p.notes)).unwrap_or_default();
+        let items = apply_template_items(existing, template.id, &template_items, req.replace);
+
+        let plan = DailyPlanRepo::save(&mut *tx, user_id, date, &items, notes.as_deref()).await?;
+        tx.commit().await?;
+        Ok(plan)
+    }
+}
@@ -133,6 +133,14 @@ pub struct PlanItem {
     pub action_url: String,
     pub completed: bool,
     pub priority: i32,
+    /// Scheduled time, for calendar events and focus blocks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub starts_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ends_at: Option<DateTime<Utc>>,
+    /// Plan date this unfinished item was first carried over from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub carried_over_from: Option<NaiveDate>,
 }
 
 /// Create/update daily plan request
@@ -234,7 +242,8 @@ pub struct InfobaseEntry {
     pub title: String,
     pub content: String,
     pub category: String,
-    pub tags: Option<serde_json::Value>,
+    pub tags: Option<Vec<String>>,
+    pub version: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -254,7 +263,7 @@ fn default_category() -> String {
 }
 
 /// Update infobase entry request
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateInfobaseEntryRequest {
     pub title: Option<String>,
     pub content: Option<String>,
@@ -270,6 +279,8 @@ pub struct InfobaseEntryResponse {
     pub content: String,
     pub category: String,
     pub tags: Vec<String>,
+    /// Send back as `If-Match` when saving
+    pub version: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -280,6 +291,24 @@ pub struct InfobaseListResponse {
     pub entries: Vec<InfobaseEntryResponse>,
 }
 
+/// Entry in a batch sync; an `id` updates that entry if it is still at
+/// `version`, otherwise a new entry is created
+#[derive(Debug, Clone, Deserialize)]
+pub struct SyncInfobaseEntryRequest {
+    pub id: Option<Uuid>,
+    pub version: Option<i32>,
+    #[serde(flatten)]
+    pub entry: CreateInfobaseEntryRequest,
+}
+
+/// Batch sync result
+#[derive(Debug, Clone, Serialize)]
+pub struct InfobaseSyncResponse {
+    pub synced: i32,
+    /// Entries changed since the client's version, left as stored
+    pub conflicts: Vec<InfobaseEntryResponse>,
+}
+
 // ============================================================================
 // IDEAS
 // ============================================================================
@@ -292,12 +321,29 @@ pub struct Idea {
     pub title: String,
     pub content: Option<String>,
     pub category: String,
-    pub tags: Option<serde_json::Value>,
+    pub tags: Option<Vec<String>>,
     pub is_pinned: bool,
+    pub version: i32,
+    #[sqlx(flatten)]
+    pub music: IdeaMusic,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Musical metadata of an idea
+///
+/// Not part of the versioned content: restoring a revision leaves it as is.
+#[derive(Debug, Clone, Default, PartialEq, FromRow, Serialize, Deserialize)]
+pub struct IdeaMusic {
+    /// One spelling per pitch class, e.g. "A minor" or "Eb major"
+    pub key: Option<String>,
+    pub bpm: Option<i32>,
+    pub mood: Option<String>,
+    pub genre: Option<String>,
+    /// e.g. "4/4" or "6/8"
+    pub time_signature: Option<String>,
+}
+
 /// Create idea request
 #[derive(Debug, Clone, Deserialize)]
 pub struct CreateIdeaRequest {
@@ -306,10 +352,8 @@ pub struct CreateIdeaRequest {
     #[serde(default = "default_idea_category")]
     pub category: String,
     pub tags: Option<Vec<String>>,
-    // Music-specific fields that get folded into content
-    pub key: Option<String>,
-    pub bpm: Option<i32>,
-    pub mood: Option<String>,
+    #[serde(flatten)]
+    pub music: IdeaMusic,
 }
 
 fn default_idea_category() -> String {
@@ -317,13 +361,27 @@ fn default_idea_category() -> String {
 }
 
 /// Update idea request
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateIdeaRequest {
     pub title: Option<String>,
     pub content: Option<String>,
     pub category: Option<String>,
     pub tags: Option<Vec<String>>,
     pub is_pinned: Option<bool>,
+    /// Fields left out are unchanged
+    #[serde(flatten)]
+    pub music: IdeaMusic,
+}
+
+/// Idea list filters, e.g. `?key=A minor&bpm_min=120&bpm_max=128`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct IdeaFilter {
+    pub key: Option<String>,
+    pub bpm_min: Option<i32>,
+    pub bpm_max: Option<i32>,
+    pub mood: Option<String>,
+    pub genre: Option<String>,
+    pub time_signature: Option<String>,
 }
 
 /// Idea response
@@ -335,6 +393,10 @@ pub struct IdeaResponse {
     pub category: String,
     pub tags: Vec<String>,
     pub is_pinned: bool,
+    /// Send back as `If-Match` when saving
+    pub version: i32,
+    #[serde(flatten)]
+    pub music: IdeaMusic,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -357,6 +419,10 @@ pub struct OnboardingFlow {
     pub description: Option<String>,
     pub is_active: bool,
     pub total_steps: i32,
+    /// Versions of a flow share its name
+    pub version: i32,
+    /// Published versions can no longer be edited
+    pub published_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -378,6 +444,8 @@ pub struct OnboardingStep {
     pub required: bool,
     pub action_type: Option<String>,
     pub action_config: Option<serde_json::Value>,
+    /// `StepCondition` the user's earlier responses must meet to see the step
+    pub show_if: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -431,6 +499,7 @@ pub struct OnboardingStateResponse {
 pub struct OnboardingFlowResponse {
     pub id: Uuid,
     pub name: String,
+    pub version: i32,
     pub total_steps: i32,
 }
 
@@ -497,25 +566,6 @@ pub struct SkipOnboardingResponse {
 // USER SETTINGS
 // ============================================================================
 
-/// User settings database model
-#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
-pub struct UserSettings {
-    pub id: Uuid,
-    pub user_id: Uuid,
-    pub notifications_enabled: bool,
-    pub email_notifications: bool,
-    pub push_notifications: bool,
-    pub theme: String,
-    pub timezone: Option<String>,
-    pub locale: String,
-    pub profile_public: bool,
-    pub show_activity: bool,
-    pub soft_landing_until: Option<DateTime<Utc>>,
-    pub daily_reminder_time: Option<String>,
-    pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
-}
-
 /// Update settings request
 #[derive(Debug, Clone, Deserialize)]
 pub struct UpdateUserSettingsRequest {
@@ -528,10 +578,18 @@ pub struct UpdateUserSettingsRequest {
     pub profile_public: Option<bool>,
     pub show_activity: Option<bool>,
     pub daily_reminder_time: Option<String>,
+    /// Start of the quiet window ("HH:MM", local time); empty string clears it
+    pub quiet_hours_start: Option<String>,
+    /// End of the quiet window ("HH:MM", local time); empty string clears it
+    pub quiet_hours_end: Option<String>,
+    /// Start of the workday ("HH:MM", local time) that generated plans fill
+    pub workday_start: Option<String>,
+    /// End of the workday ("HH:MM", local time)
+    pub workday_end: Option<String>,
 }
 
 /// User settings response
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserSettingsResponse {
     pub notifications_enabled: bool,
     pub email_notifications: bool,
@@ -542,6 +600,32 @@ pub struct UserSettingsResponse {
     pub profile_public: bool,
     pub show_activity: bool,
     pub daily_reminder_time: Option<String>,
+    /// Email and push reminders are held back during quiet hours
+    pub quiet_hours_start: Option<String>,
+    pub quiet_hours_end: Option<String>,
+    /// Generated daily plans fit focus blocks into the workday
+    pub workday_start: String,
+    pub workday_end: String,
+}
+
+impl Default for UserSettingsResponse {
+    fn default() -> Self {
+        Self {
+            notifications_enabled: true,
+            email_notifications: true,
+            push_notifications: false,
+            theme: "system".to_string(),
+            timezone: None,
+            locale: "en".to_string(),
+            profile_public: false,
+            show_activity: true,
+            daily_reminder_time: None,
+            quiet_hours_start: None,
+            quiet_hours_end: None,
+            workday_start: "09:00".to_string(),
+            workday_end: "18:00".to_string(),
+        }
+    }
 }
 
 // ============================================================================
@@ -580,6 +664,8 @@ pub struct UserInterestsResponse {
 pub struct DeleteAccountResponse {
     pub success: bool,
     pub message: String,
+    /// When the account and its data are purged, unless cancelled first
+    pub purge_after: DateTime<Utc>,
 }
 
 /// Export data response is a full JSON object containing all user data
@@ -590,3 +676,87 @@ pub struct ExportDataResponse {
     pub email: Option<String>,
     pub data: serde_json::Value,
 }
+
+// ============================================================================
+// USER SESSIONS
+// ============================================================================
+
+/// A signed-in device as shown to the user
+#[derive(Debug, Clone, Serialize)]
+pub struct UserSessionResponse {
+    pub id: Uuid,
+    pub device: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_activity_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub current: bool,
+}
+
+impl UserSessionResponse {
+    /// Build the response for a session; the token hash is never exposed
+    pub fn from_session(session: super::models::Session, current_session_id: Uuid) -> Self {
+        Self {
+            id: session.id,
+            device: describe_device(session.user_agent.as_deref()),
+            current: session.id == current_session_id,
+            user_agent: session.user_agent,
+            ip_address: session.ip_address,
+            created_at: session.created_at,
+            last_activity_at: session.last_activity_at,
+            expires_at: session.expires_at,
+        }
+    }
+}
+
+/// Revoke other sessions response
+#[derive(Debug, Clone, Serialize)]
+pub struct RevokeSessionsResponse {
+    pub revoked: u64,
+}
+
+/// Summarize a user agent as "Browser on OS"
+pub fn describe_device(user_agent: Option<&str>) -> String {
+    let Some(ua) = user_agent.filter(|ua| !ua.trim().is_empty()) else {
+        return "Unknown device".to_string();
+    };
+
+    // Order matters: Edge and Opera include "Chrome", Chrome includes "Safari"
+    let browser = if ua.contains("Edg/") {
+        Some("Edge")
+    } else if ua.contains("OPR/") || ua.contains("Opera") {
+        Some("Opera")
+    } else if ua.contains("Firefox/") || ua.contains("FxiOS/") {
+        Some("Firefox")
+    } else if ua.contains("Chrome/") || ua.contains("CriOS/") {
+        Some("Chrome")
+    } else if ua.contains("Safari/") {
+        Some("Safari")
+    } else {
+        None
+    };
+
+    let os = if ua.contains("iPhone") || ua.contains("iPad") {
+        Some("iOS")
+    } else if ua.contains("Android") {
+        Some("Android")
+    } else if ua.contains("Windows") {
+        Some("Windows")
+    } else if ua.contains("Mac OS X") || ua.contains("Macintosh") {
+        Some("macOS")
+    } else if ua.contains("CrOS") {
+        Some("ChromeOS")
+    } else if ua.contains("Linux") {
+        Some("Linux")
+    } else {
+        None
+    };
+
+    match (browser, os) {
+        (Some(b), Some(o)) => format!("{} on {}", b, o),
+        (Some(b), None) => b.to_string(),
+        (None, Some(o)) => o.to_string(),
+        (None, None) => "Unknown device".to_string(),
+    }
+}
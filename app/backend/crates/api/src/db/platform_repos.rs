@@ -4,10 +4,17 @@
 //! Onboarding, and User settings.
 
 use chrono::{NaiveDate, Utc};
-use sqlx::{FromRow, PgPool};
+use sqlx::{Acquire, PgExecutor, PgPool, Postgres};
 use uuid::Uuid;
 
+use super::infobase_link_repos::{rewrite_links, InfobaseLinkRepo};
+use super::onboarding_models::{merge_answers, next_visible_step, step_visible};
+use super::plan_models::build_plan;
+use super::plan_repos::PlanRepo;
 use super::platform_models::*;
+use super::revision_models::{NewRevision, RevisionEntity};
+use super::revision_repos::{version_conflict, RevisionRepo};
+use super::search_repos::search_tsquery;
 use crate::error::AppError;
 
 // ============================================================================
@@ -16,27 +23,74 @@ use crate::error::AppError;
 
 pub struct CalendarRepo;
 
+pub const CALENDAR_LIST: &str = r#"
+    SELECT id, user_id, title, description, event_type,
+           start_time, end_time, all_day, timezone, location,
+           workout_id, habit_id, goal_id, recurrence_rule,
+           recurrence_end, parent_event_id, color, reminder_minutes,
+           metadata, created_at, updated_at
+    FROM calendar_events
+    WHERE user_id = $1
+    ORDER BY start_time ASC
+"#;
+
+pub const CALENDAR_LIST_IN_RANGE: &str = r#"
+    SELECT id, user_id, title, description, event_type,
+           start_time, end_time, all_day, timezone, location,
+           workout_id, habit_id, goal_id, recurrence_rule,
+           recurrence_end, parent_event_id, color, reminder_minutes,
+           metadata, created_at, updated_at
+    FROM calendar_events
+    WHERE user_id = $1
+      AND start_time >= $2
+      AND start_time <= $3
+    ORDER BY start_time ASC
+"#;
+
+pub const CALENDAR_GET: &str = r#"
+    SELECT id, user_id, title, description, event_type,
+           start_time, end_time, all_day, timezone, location,
+           workout_id, habit_id, goal_id, recurrence_rule,
+           recurrence_end, parent_event_id, color, reminder_minutes,
+           metadata, created_at, updated_at
+    FROM calendar_events
+    WHERE id = $1 AND user_id = $2
+"#;
+
+pub const CALENDAR_CREATE: &str = r#"
+    INSERT INTO calendar_events (
+        id, user_id, title, description, event_type,
+        start_time, end_time, all_day, timezone, location,
+        workout_id, habit_id, goal_id, recurrence_rule,
+        recurrence_end, parent_event_id, color, reminder_minutes,
+        metadata, created_at, updated_at
+    ) VALUES (
+        $1, $2, $3, $4, $5, $6, $7, $8, $9, $10,
+        $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21
+    )
+"#;
+
+pub const CALENDAR_UPDATE: &str = r#"
+    UPDATE calendar_events
+    SET title = $1, description = $2, event_type = $3,
+        start_time = $4, end_time = $5, all_day = $6,
+        timezone = $7, location = $8, color = $9,
+        reminder_minutes = $10, metadata = $11, updated_at = $12
+    WHERE id = $13 AND user_id = $14
+"#;
+
+pub const CALENDAR_DELETE: &str = "DELETE FROM calendar_events WHERE id = $1 AND user_id = $2";
+
 impl CalendarRepo {
     /// List all events for a user
     pub async fn list(
         pool: &PgPool,
         user_id: Uuid,
     ) -> Result<CalendarEventsListResponse, AppError> {
-        let events = sqlx::query_as::<_, CalendarEvent>(
-            r#"
-            SELECT id, user_id, title, description, event_type,
-                   start_time, end_time, all_day, timezone, location,
-                   workout_id, habit_id, goal_id, recurrence_rule,
-                   recurrence_end, parent_event_id, color, reminder_minutes,
-                   metadata, created_at, updated_at
-            FROM calendar_events
-            WHERE user_id = $1
-            ORDER BY start_time ASC
-            "#,
-        )
-        .bind(user_id)
-        .fetch_all(pool)
-        .await?;
+        let events = sqlx::query_as::<_, CalendarEvent>(CALENDAR_LIST)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
 
         Ok(CalendarEventsListResponse {
             events: events.into_iter().map(Self::to_response).collect(),
@@ -50,25 +104,12 @@ impl CalendarRepo {
         start: chrono::DateTime<Utc>,
         end: chrono::DateTime<Utc>,
     ) -> Result<CalendarEventsListResponse, AppError> {
-        let events = sqlx::query_as::<_, CalendarEvent>(
-            r#"
-            SELECT id, user_id, title, description, event_type,
-                   start_time, end_time, all_day, timezone, location,
-                   workout_id, habit_id, goal_id, recurrence_rule,
-                   recurrence_end, parent_event_id, color, reminder_minutes,
-                   metadata, created_at, updated_at
-            FROM calendar_events
-            WHERE user_id = $1
-              AND start_time >= $2
-              AND start_time <= $3
-            ORDER BY start_time ASC
-            "#,
-        )
-        .bind(user_id)
-        .bind(start)
-        .bind(end)
-        .fetch_all(pool)
-        .await?;
+        let events = sqlx::query_as::<_, CalendarEvent>(CALENDAR_LIST_IN_RANGE)
+            .bind(user_id)
+            .bind(start)
+            .bind(end)
+            .fetch_all(pool)
+            .await?;
 
         Ok(CalendarEventsListResponse {
             events: events.into_iter().map(Self::to_response).collect(),
@@ -81,72 +122,49 @@ impl CalendarRepo {
         id: Uuid,
         user_id: Uuid,
     ) -> Result<CalendarEventResponse, AppError> {
-        let event = sqlx::query_as::<_, CalendarEvent>(
-            r#"
-            SELECT id, user_id, title, description, event_type,
-                   start_time, end_time, all_day, timezone, location,
-                   workout_id, habit_id, goal_id, recurrence_rule,
-                   recurrence_end, parent_event_id, color, reminder_minutes,
-                   metadata, created_at, updated_at
-            FROM calendar_events
-            WHERE id = $1 AND user_id = $2
-            "#,
-        )
-        .bind(id)
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Calendar event not found".into()))?;
+        let event = sqlx::query_as::<_, CalendarEvent>(CALENDAR_GET)
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Calendar event not found".into()))?;
 
         Ok(Self::to_response(event))
     }
 
     /// Create a new event
     pub async fn create(
-        pool: &PgPool,
+        db: impl PgExecutor<'_>,
         user_id: Uuid,
         req: &CreateCalendarEventRequest,
     ) -> Result<CalendarEventResponse, AppError> {
         let id = Uuid::new_v4();
         let now = Utc::now();
 
-        sqlx::query(
-            r#"
-            INSERT INTO calendar_events (
-                id, user_id, title, description, event_type,
-                start_time, end_time, all_day, timezone, location,
-                workout_id, habit_id, goal_id, recurrence_rule,
-                recurrence_end, parent_event_id, color, reminder_minutes,
-                metadata, created_at, updated_at
-            ) VALUES (
-                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10,
-                $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21
-            )
-            "#,
-        )
-        .bind(id)
-        .bind(user_id)
-        .bind(&req.title)
-        .bind(&req.description)
-        .bind(&req.event_type)
-        .bind(req.start_time)
-        .bind(req.end_time)
-        .bind(req.all_day)
-        .bind(&req.timezone)
-        .bind(&req.location)
-        .bind(req.workout_id)
-        .bind(req.habit_id)
-        .bind(req.goal_id)
-        .bind(&req.recurrence_rule)
-        .bind(req.recurrence_end)
-        .bind(req.parent_event_id)
-        .bind(&req.color)
-        .bind(req.reminder_minutes)
-        .bind(&req.metadata)
-        .bind(now)
-        .bind(now)
-        .execute(pool)
-        .await?;
+        sqlx::query(CALENDAR_CREATE)
+            .bind(id)
+            .bind(user_id)
+            .bind(&req.title)
+            .bind(&req.description)
+            .bind(&req.event_type)
+            .bind(req.start_time)
+            .bind(req.end_time)
+            .bind(req.all_day)
+            .bind(&req.timezone)
+            .bind(&req.location)
+            .bind(req.workout_id)
+            .bind(req.habit_id)
+            .bind(req.goal_id)
+            .bind(&req.recurrence_rule)
+            .bind(req.recurrence_end)
+            .bind(req.parent_event_id)
+            .bind(&req.color)
+            .bind(req.reminder_minutes)
+            .bind(&req.metadata)
+            .bind(now)
+            .bind(now)
+            .execute(db)
+            .await?;
 
         Ok(CalendarEventResponse {
             id,
@@ -187,32 +205,23 @@ impl CalendarRepo {
         let color = req.color.clone().or(existing.color);
         let reminder_minutes = req.reminder_minutes.or(existing.reminder_minutes);
 
-        sqlx::query(
-            r#"
-            UPDATE calendar_events
-            SET title = $1, description = $2, event_type = $3,
-                start_time = $4, end_time = $5, all_day = $6,
-                timezone = $7, location = $8, color = $9,
-                reminder_minutes = $10, metadata = $11, updated_at = $12
-            WHERE id = $13 AND user_id = $14
-            "#,
-        )
-        .bind(title)
-        .bind(&description)
-        .bind(event_type)
-        .bind(start_time)
-        .bind(end_time)
-        .bind(all_day)
-        .bind(&timezone)
-        .bind(&location)
-        .bind(&color)
-        .bind(reminder_minutes)
-        .bind(&req.metadata)
-        .bind(now)
-        .bind(id)
-        .bind(user_id)
-        .execute(pool)
-        .await?;
+        sqlx::query(CALENDAR_UPDATE)
+            .bind(title)
+            .bind(&description)
+            .bind(event_type)
+            .bind(start_time)
+            .bind(end_time)
+            .bind(all_day)
+            .bind(&timezone)
+            .bind(&location)
+            .bind(&color)
+            .bind(reminder_minutes)
+            .bind(&req.metadata)
+            .bind(now)
+            .bind(id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
 
         Ok(CalendarEventResponse {
             id,
@@ -233,7 +242,7 @@ impl CalendarRepo {
 
     /// Delete an event
     pub async fn delete(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
-        let result = sqlx::query("DELETE FROM calendar_events WHERE id = $1 AND user_id = $2")
+        let result = sqlx::query(CALENDAR_DELETE)
             .bind(id)
             .bind(user_id)
             .execute(pool)
@@ -271,24 +280,32 @@ impl CalendarRepo {
 
 pub struct DailyPlanRepo;
 
+pub const DAILY_PLAN_GET_FOR_DATE: &str = r#"
+    SELECT id, user_id, date, items, notes, created_at, updated_at
+    FROM daily_plans
+    WHERE user_id = $1 AND date = $2
+"#;
+
+pub const DAILY_PLAN_UPSERT: &str = r#"
+    INSERT INTO daily_plans (user_id, date, items, notes, created_at, updated_at)
+    VALUES ($1, $2, $3, $4, NOW(), NOW())
+    ON CONFLICT (user_id, date)
+    DO UPDATE SET items = EXCLUDED.items, notes = EXCLUDED.notes, updated_at = NOW()
+    RETURNING id, user_id, date, items, notes, created_at, updated_at
+"#;
+
 impl DailyPlanRepo {
     /// Get plan for a specific date
     pub async fn get_for_date(
-        pool: &PgPool,
+        db: impl PgExecutor<'_>,
         user_id: Uuid,
         date: NaiveDate,
     ) -> Result<Option<DailyPlanResponse>, AppError> {
-        let plan = sqlx::query_as::<_, DailyPlan>(
-            r#"
-            SELECT id, user_id, date, items, notes, created_at, updated_at
-            FROM daily_plans
-            WHERE user_id = $1 AND date = $2
-            "#,
-        )
-        .bind(user_id)
-        .bind(date)
-        .fetch_optional(pool)
-        .await?;
+        let plan = sqlx::query_as::<_, DailyPlan>(DAILY_PLAN_GET_FOR_DATE)
+            .bind(user_id)
+            .bind(date)
+            .fetch_optional(db)
+            .await?;
 
         Ok(plan.map(Self::to_response))
     }
@@ -299,61 +316,30 @@ impl DailyPlanRepo {
         user_id: Uuid,
         req: &UpsertDailyPlanRequest,
     ) -> Result<DailyPlanResponse, AppError> {
-        let now = Utc::now();
-        let items_json = serde_json::to_value(&req.items.clone().unwrap_or_default())
-            .map_err(|e| AppError::Internal(e.to_string()))?;
+        let items = req.items.clone().unwrap_or_default();
+        Self::save(pool, user_id, req.date, &items, req.notes.as_deref()).await
+    }
 
-        let items_count = req.items.as_ref().map(|v| v.len()).unwrap_or(0) as i32;
-        let completed_count = req
-            .items
-            .as_ref()
-            .map(|items| items.iter().filter(|i| i.completed).count())
-            .unwrap_or(0) as i32;
-
-        // Try update first
-        let result = sqlx::query(
-            r#"
-            UPDATE daily_plans
-            SET items = $1, notes = $2, updated_at = $3
-            WHERE user_id = $4 AND date = $5
-            "#,
-        )
-        .bind(&items_json)
-        .bind(&req.notes)
-        .bind(now)
-        .bind(user_id)
-        .bind(req.date)
-        .execute(pool)
-        .await?;
+    /// Write the plan for a day, replacing any existing one
+    pub async fn save(
+        db: impl PgExecutor<'_>,
+        user_id: Uuid,
+        date: NaiveDate,
+        items: &[PlanItem],
+        notes: Option<&str>,
+    ) -> Result<DailyPlanResponse, AppError> {
+        let items_json =
+            serde_json::to_value(items).map_err(|e| AppError::Internal(e.to_string()))?;
 
-        if result.rows_affected() == 0 {
-            // Insert new
-            let id = Uuid::new_v4();
-            sqlx::query(
-                r#"
-                INSERT INTO daily_plans (id, user_id, date, items, notes, created_at, updated_at)
-                VALUES ($1, $2, $3, $4, $5, $6, $7)
-                "#,
-            )
-            .bind(id)
+        let plan = sqlx::query_as::<_, DailyPlan>(DAILY_PLAN_UPSERT)
             .bind(user_id)
-            .bind(req.date)
+            .bind(date)
             .bind(&items_json)
-            .bind(&req.notes)
-            .bind(now)
-            .bind(now)
-            .execute(pool)
+            .bind(notes)
+            .fetch_one(db)
             .await?;
-        }
 
-        Ok(DailyPlanResponse {
-            id: Uuid::new_v4(), // Will be overwritten if fetched
-            date: req.date,
-            items: req.items.clone().unwrap_or_default(),
-            notes: req.notes.clone(),
-            completed_count,
-            total_count: items_count,
-        })
+        Ok(Self::to_response(plan))
     }
 
     /// Complete or uncomplete a plan item
@@ -382,113 +368,26 @@ impl DailyPlanRepo {
             return Err(AppError::NotFound("Plan item not found".into()));
         }
 
-        let req = UpsertDailyPlanRequest {
-            date,
-            items: Some(items),
-            notes: plan.notes,
-        };
-
-        Self::upsert(pool, user_id, &req).await
+        Self::save(pool, user_id, date, &items, plan.notes.as_deref()).await
     }
 
-    /// Generate a daily plan from user's active items
+    /// Generate the day's plan around the calendar, due habits, near goals
+    /// and yesterday's unfinished items.
+    ///
+    /// Regenerating keeps completed and hand-added items and the plan's notes.
     pub async fn generate(
         pool: &PgPool,
         user_id: Uuid,
         date: NaiveDate,
     ) -> Result<DailyPlanResponse, AppError> {
-        let mut items: Vec<PlanItem> = vec![];
-        let mut priority = 0;
-
-        // Add a focus session suggestion
-        items.push(PlanItem {
-            id: format!("plan_focus_{}", Utc::now().timestamp_millis()),
-            item_type: "focus".to_string(),
-            title: "Focus Session".to_string(),
-            description: Some("Complete a 25-minute focus session".to_string()),
-            duration: Some(25),
-            action_url: "/focus".to_string(),
-            completed: false,
-            priority: priority,
-        });
-        priority += 1;
-
-        // Get active habits
-        #[derive(FromRow)]
-        struct HabitRow {
-            id: Uuid,
-            name: String,
-        }
-
-        let habits = sqlx::query_as::<_, HabitRow>(
-            r#"
-            SELECT id, name FROM habits
-            WHERE user_id = $1 AND is_active = true
-            LIMIT 5
-            "#,
-        )
-        .bind(user_id)
-        .fetch_all(pool)
-        .await
-        .unwrap_or_default();
-
-        for habit in habits {
-            items.push(PlanItem {
-                id: format!("plan_habit_{}", habit.id),
-                item_type: "habit".to_string(),
-                title: habit.name,
-                description: None,
-                duration: None,
-                action_url: "/habits".to_string(),
-                completed: false,
-                priority,
-            });
-            priority += 1;
-        }
-
-        // Get active quests
-        #[derive(FromRow)]
-        struct QuestRow {
-            id: Uuid,
-            name: String,
-            description: Option<String>,
-        }
+        let inputs = PlanRepo::inputs(pool, user_id, date).await?;
+        let items = build_plan(&inputs, Utc::now());
 
-        let quests = sqlx::query_as::<_, QuestRow>(
-            r#"
-            SELECT q.id, q.name, q.description
-            FROM universal_quests q
-            LEFT JOIN user_quest_progress p ON q.id = p.quest_id AND p.user_id = $1
-            WHERE q.is_active = true AND (p.completed IS NULL OR p.completed = false)
-            LIMIT 3
-            "#,
-        )
-        .bind(user_id)
-        .fetch_all(pool)
-        .await
-        .unwrap_or_default();
-
-        for quest in quests {
-            items.push(PlanItem {
-                id: format!("plan_quest_{}", quest.id),
-                item_type: "quest".to_string(),
-                title: quest.name,
-                description: quest.description,
-                duration: None,
-                action_url: "/quests".to_string(),
-                completed: false,
-                priority,
-            });
-            priority += 1;
-        }
-
-        let req = UpsertDailyPlanRequest {
-            date,
-            items: Some(items.clone()),
-            notes: None,
-        };
+        let notes = Self::get_for_date(pool, user_id, date)
+            .await?
+            .and_then(|p| p.notes);
 
-        Self::upsert(pool, user_id, &req).await
+        Self::save(pool, user_id, date, &items, notes.as_deref()).await
     }
 
     fn to_response(p: DailyPlan) -> DailyPlanResponse {
@@ -513,22 +412,27 @@ impl DailyPlanRepo {
 
 pub struct FeedbackRepo;
 
+pub const FEEDBACK_LIST: &str = r#"
+    SELECT id, user_id, feedback_type, title, description,
+           status, priority, admin_response, resolved_at,
+           metadata, created_at, updated_at
+    FROM feedback
+    WHERE user_id = $1
+    ORDER BY created_at DESC
+"#;
+
+pub const FEEDBACK_CREATE: &str = r#"
+    INSERT INTO feedback (id, user_id, feedback_type, title, description, priority, created_at, updated_at)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+"#;
+
 impl FeedbackRepo {
     /// List user's feedback
     pub async fn list(pool: &PgPool, user_id: Uuid) -> Result<FeedbackListResponse, AppError> {
-        let feedback = sqlx::query_as::<_, Feedback>(
-            r#"
-            SELECT id, user_id, feedback_type, title, description,
-                   status, priority, admin_response, resolved_at,
-                   metadata, created_at, updated_at
-            FROM feedback
-            WHERE user_id = $1
-            ORDER BY created_at DESC
-            "#,
-        )
-        .bind(user_id)
-        .fetch_all(pool)
-        .await?;
+        let feedback = sqlx::query_as::<_, Feedback>(FEEDBACK_LIST)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
 
         Ok(FeedbackListResponse {
             feedback: feedback.into_iter().map(Self::to_response).collect(),
@@ -549,22 +453,17 @@ impl FeedbackRepo {
         let id = Uuid::new_v4();
         let now = Utc::now();
 
-        sqlx::query(
-            r#"
-            INSERT INTO feedback (id, user_id, feedback_type, title, description, priority, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            "#
-        )
-        .bind(id)
-        .bind(user_id)
-        .bind(&req.feedback_type)
-        .bind(&req.title)
-        .bind(&req.description)
-        .bind(&req.priority)
-        .bind(now)
-        .bind(now)
-        .execute(pool)
-        .await?;
+        sqlx::query(FEEDBACK_CREATE)
+            .bind(id)
+            .bind(user_id)
+            .bind(&req.feedback_type)
+            .bind(&req.title)
+            .bind(&req.description)
+            .bind(&req.priority)
+            .bind(now)
+            .bind(now)
+            .execute(pool)
+            .await?;
 
         Ok(FeedbackResponse {
             id,
@@ -598,6 +497,53 @@ impl FeedbackRepo {
 
 pub struct InfobaseRepo;
 
+pub const INFOBASE_LIST_SEARCH: &str = r#"
+    SELECT id, user_id, title, content, category, tags, version, created_at, updated_at
+    FROM infobase_entries
+    WHERE user_id = $1 AND search_vector @@ to_tsquery('english', $2)
+    ORDER BY ts_rank(search_vector, to_tsquery('english', $2)) DESC, updated_at DESC
+"#;
+
+pub const INFOBASE_LIST_BY_CATEGORY: &str = r#"
+    SELECT id, user_id, title, content, category, tags, version, created_at, updated_at
+    FROM infobase_entries
+    WHERE user_id = $1 AND category = $2
+    ORDER BY updated_at DESC
+"#;
+
+pub const INFOBASE_LIST: &str = r#"
+    SELECT id, user_id, title, content, category, tags, version, created_at, updated_at
+    FROM infobase_entries
+    WHERE user_id = $1
+    ORDER BY updated_at DESC
+"#;
+
+pub const INFOBASE_GET: &str = r#"
+    SELECT id, user_id, title, content, category, tags, version, created_at, updated_at
+    FROM infobase_entries
+    WHERE id = $1 AND user_id = $2
+"#;
+
+pub const INFOBASE_GET_FOR_UPDATE: &str = r#"
+    SELECT id, user_id, title, content, category, tags, version, created_at, updated_at
+    FROM infobase_entries
+    WHERE id = $1 AND user_id = $2
+    FOR UPDATE
+"#;
+
+pub const INFOBASE_CREATE: &str = r#"
+    INSERT INTO infobase_entries (id, user_id, title, content, category, tags, is_pinned, version, created_at, updated_at)
+    VALUES ($1, $2, $3, $4, $5, $6, false, 1, $7, $8)
+"#;
+
+pub const INFOBASE_UPDATE: &str = r#"
+    UPDATE infobase_entries
+    SET title = $1, content = $2, category = $3, tags = $4, version = $5, updated_at = $6
+    WHERE id = $7 AND user_id = $8
+"#;
+
+pub const INFOBASE_DELETE: &str = "DELETE FROM infobase_entries WHERE id = $1 AND user_id = $2";
+
 impl InfobaseRepo {
     /// List entries, optionally filtered
     pub async fn list(
@@ -606,59 +552,23 @@ impl InfobaseRepo {
         category: Option<&str>,
         search: Option<&str>,
     ) -> Result<InfobaseListResponse, AppError> {
-        let entries = if let Some(search_term) = search {
-            let pattern = format!("%{}%", search_term);
-            sqlx::query_as::<_, InfobaseEntry>(
-                r#"
-                SELECT id, user_id, title, content, category, tags, created_at, updated_at
-                FROM infobase_entries
-                WHERE user_id = $1 AND (title ILIKE $2 OR content ILIKE $2)
-                ORDER BY updated_at DESC
-                "#,
-            )
-            .bind(user_id)
-            .bind(&pattern)
-            .fetch_all(pool)
-            .await?
-        } else if let Some(cat) = category {
-            if cat != "All Entries" {
-                sqlx::query_as::<_, InfobaseEntry>(
-                    r#"
-                    SELECT id, user_id, title, content, category, tags, created_at, updated_at
-                    FROM infobase_entries
-                    WHERE user_id = $1 AND category = $2
-                    ORDER BY updated_at DESC
-                    "#,
-                )
+        let entries = if let Some(query) = search.and_then(search_tsquery) {
+            sqlx::query_as::<_, InfobaseEntry>(INFOBASE_LIST_SEARCH)
                 .bind(user_id)
-                .bind(cat)
+                .bind(&query)
                 .fetch_all(pool)
                 .await?
-            } else {
-                sqlx::query_as::<_, InfobaseEntry>(
-                    r#"
-                    SELECT id, user_id, title, content, category, tags, created_at, updated_at
-                    FROM infobase_entries
-                    WHERE user_id = $1
-                    ORDER BY updated_at DESC
-                    "#,
-                )
+        } else if let Some(cat) = category.filter(|c| *c != "All Entries") {
+            sqlx::query_as::<_, InfobaseEntry>(INFOBASE_LIST_BY_CATEGORY)
                 .bind(user_id)
+                .bind(cat)
                 .fetch_all(pool)
                 .await?
-            }
         } else {
-            sqlx::query_as::<_, InfobaseEntry>(
-                r#"
-                SELECT id, user_id, title, content, category, tags, created_at, updated_at
-                FROM infobase_entries
-                WHERE user_id = $1
-                ORDER BY updated_at DESC
-                "#,
-            )
-            .bind(user_id)
-            .fetch_all(pool)
-            .await?
+            sqlx::query_as::<_, InfobaseEntry>(INFOBASE_LIST)
+                .bind(user_id)
+                .fetch_all(pool)
+                .await?
         };
 
         Ok(InfobaseListResponse {
@@ -672,149 +582,249 @@ impl InfobaseRepo {
         id: Uuid,
         user_id: Uuid,
     ) -> Result<InfobaseEntryResponse, AppError> {
-        let entry = sqlx::query_as::<_, InfobaseEntry>(
-            r#"
-            SELECT id, user_id, title, content, category, tags, created_at, updated_at
-            FROM infobase_entries
-            WHERE id = $1 AND user_id = $2
-            "#,
-        )
-        .bind(id)
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Infobase entry not found".into()))?;
+        let entry = sqlx::query_as::<_, InfobaseEntry>(INFOBASE_GET)
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Infobase entry not found".into()))?;
 
         Ok(Self::to_response(entry))
     }
 
     /// Create entry
-    pub async fn create(
-        pool: &PgPool,
+    ///
+    /// Its links are recorded, and links elsewhere naming its title resolve
+    /// to it. The content is saved as revision 1.
+    pub async fn create<'c>(
+        db: impl Acquire<'c, Database = Postgres>,
         user_id: Uuid,
         req: &CreateInfobaseEntryRequest,
     ) -> Result<InfobaseEntryResponse, AppError> {
         let id = Uuid::new_v4();
         let now = Utc::now();
-        let tags_json = req
-            .tags
-            .as_ref()
-            .map(|t| serde_json::to_value(t).ok())
-            .flatten();
-
-        sqlx::query(
-            r#"
-            INSERT INTO infobase_entries (id, user_id, title, content, category, tags, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            "#
+        let tags = req.tags.clone().unwrap_or_default();
+
+        let mut tx = db.begin().await?;
+        sqlx::query(INFOBASE_CREATE)
+            .bind(id)
+            .bind(user_id)
+            .bind(&req.title)
+            .bind(&req.content)
+            .bind(&req.category)
+            .bind(&tags)
+            .bind(now)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        InfobaseLinkRepo::replace_for_source(&mut tx, user_id, id, &req.content).await?;
+        InfobaseLinkRepo::resolve_title(&mut tx, user_id, id, &req.title).await?;
+        let revision = NewRevision {
+            version: 1,
+            title: &req.title,
+            content: Some(&req.content),
+            category: Some(&req.category),
+            tags: &tags,
+        };
+        RevisionRepo::record(
+            &mut tx,
+            user_id,
+            RevisionEntity::Infobase,
+            id,
+            user_id,
+            &revision,
         )
-        .bind(id)
-        .bind(user_id)
-        .bind(&req.title)
-        .bind(&req.content)
-        .bind(&req.category)
-        .bind(&tags_json)
-        .bind(now)
-        .bind(now)
-        .execute(pool)
         .await?;
+        tx.commit().await?;
 
         Ok(InfobaseEntryResponse {
             id,
             title: req.title.clone(),
             content: req.content.clone(),
             category: req.category.clone(),
-            tags: req.tags.clone().unwrap_or_default(),
+            tags,
+            version: 1,
             created_at: now,
             updated_at: now,
         })
     }
 
     /// Update entry
+    ///
+    /// Links are re-read from the content. A new title is written into every
+    /// entry linking here, so those links keep pointing at this entry.
+    /// With `expected_version` (the client's `If-Match`), an entry saved
+    /// since then is left alone and the update fails with a conflict.
     pub async fn update(
         pool: &PgPool,
         id: Uuid,
         user_id: Uuid,
         req: &UpdateInfobaseEntryRequest,
+        expected_version: Option<i32>,
     ) -> Result<InfobaseEntryResponse, AppError> {
-        let existing = Self::get(pool, id, user_id).await?;
+        let mut tx = pool.begin().await?;
+        let existing = sqlx::query_as::<_, InfobaseEntry>(INFOBASE_GET_FOR_UPDATE)
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Infobase entry not found".into()))?;
+        if let Some(expected) = expected_version.filter(|v| *v != existing.version) {
+            let current = Self::to_response(existing);
+            return Err(version_conflict(&current, current.version, expected, req));
+        }
         let now = Utc::now();
+        let version = existing.version + 1;
 
         let title = req.title.as_ref().unwrap_or(&existing.title);
-        let content = req.content.as_ref().unwrap_or(&existing.content);
+        let mut content = req.content.clone().unwrap_or(existing.content.clone());
         let category = req.category.as_ref().unwrap_or(&existing.category);
-        let tags = req.tags.clone().unwrap_or(existing.tags.clone());
-        let tags_json = serde_json::to_value(&tags).ok();
-
-        sqlx::query(
-            r#"
-            UPDATE infobase_entries
-            SET title = $1, content = $2, category = $3, tags = $4, updated_at = $5
-            WHERE id = $6 AND user_id = $7
-            "#,
+        let tags = req
+            .tags
+            .clone()
+            .unwrap_or(existing.tags.clone().unwrap_or_default());
+        let renamed = title.trim().to_lowercase() != existing.title.trim().to_lowercase();
+
+        if renamed {
+            InfobaseLinkRepo::rename_target(&mut tx, user_id, id, &existing.title, title).await?;
+            content = rewrite_links(&content, &existing.title, title);
+        }
+        sqlx::query(INFOBASE_UPDATE)
+            .bind(title)
+            .bind(&content)
+            .bind(category)
+            .bind(&tags)
+            .bind(version)
+            .bind(now)
+            .bind(id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        InfobaseLinkRepo::replace_for_source(&mut tx, user_id, id, &content).await?;
+        if renamed {
+            InfobaseLinkRepo::resolve_title(&mut tx, user_id, id, title).await?;
+        }
+        let revision = NewRevision {
+            version,
+            title,
+            content: Some(&content),
+            category: Some(category),
+            tags: &tags,
+        };
+        RevisionRepo::record(
+            &mut tx,
+            user_id,
+            RevisionEntity::Infobase,
+            id,
+            user_id,
+            &revision,
         )
-        .bind(title)
-        .bind(content)
-        .bind(category)
-        .bind(&tags_json)
-        .bind(now)
-        .bind(id)
-        .bind(user_id)
-        .execute(pool)
         .await?;
+        tx.commit().await?;
 
         Ok(InfobaseEntryResponse {
             id,
             title: title.clone(),
-            content: content.clone(),
+            content,
             category: category.clone(),
             tags,
+            version,
             created_at: existing.created_at,
             updated_at: now,
         })
     }
 
-    /// Delete entry
+    /// Save an earlier revision's content as a new version
+    pub async fn restore(
+        pool: &PgPool,
+        id: Uuid,
+        user_id: Uuid,
+        version: i32,
+        expected_version: Option<i32>,
+    ) -> Result<InfobaseEntryResponse, AppError> {
+        let revision =
+            RevisionRepo::get(pool, user_id, RevisionEntity::Infobase, id, version).await?;
+        let req = UpdateInfobaseEntryRequest {
+            title: Some(revision.title),
+            content: Some(revision.content.unwrap_or_default()),
+            category: revision.category,
+            tags: Some(revision.tags),
+        };
+        Self::update(pool, id, user_id, &req, expected_version).await
+    }
+
+    /// Delete entry and its history; links to it move to another entry of
+    /// the same title or become broken
     pub async fn delete(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
-        let result = sqlx::query("DELETE FROM infobase_entries WHERE id = $1 AND user_id = $2")
+        let mut tx = pool.begin().await?;
+        let result = sqlx::query(INFOBASE_DELETE)
             .bind(id)
             .bind(user_id)
-            .execute(pool)
+            .execute(&mut *tx)
             .await?;
 
         if result.rows_affected() == 0 {
             return Err(AppError::NotFound("Infobase entry not found".into()));
         }
 
+        InfobaseLinkRepo::retarget_deleted(&mut tx, id).await?;
+        RevisionRepo::delete_for_entity(&mut tx, RevisionEntity::Infobase, id).await?;
+        tx.commit().await?;
         Ok(())
     }
 
     /// Sync multiple entries (batch upsert)
+    ///
+    /// Entries saved since the version the client sent are not overwritten;
+    /// they come back as conflicts for the client to reconcile.
     pub async fn sync(
         pool: &PgPool,
         user_id: Uuid,
-        entries: Vec<CreateInfobaseEntryRequest>,
-    ) -> Result<i32, AppError> {
-        let mut count = 0;
-        for entry in entries {
-            Self::create(pool, user_id, &entry).await?;
-            count += 1;
+        entries: Vec<SyncInfobaseEntryRequest>,
+    ) -> Result<InfobaseSyncResponse, AppError> {
+        let mut response = InfobaseSyncResponse {
+            synced: 0,
+            conflicts: Vec::new(),
+        };
+        for item in entries {
+            match item.id {
+                None => {
+                    Self::create(pool, user_id, &item.entry).await?;
+                }
+                Some(id) => {
+                    let version = item.version.ok_or_else(|| {
+                        AppError::Validation("Synced entries with an id need a version".into())
+                    })?;
+                    let req = UpdateInfobaseEntryRequest {
+                        title: Some(item.entry.title),
+                        content: Some(item.entry.content),
+                        category: Some(item.entry.category),
+                        tags: item.entry.tags,
+                    };
+                    match Self::update(pool, id, user_id, &req, Some(version)).await {
+                        Err(AppError::Conflict { .. }) => {
+                            response.conflicts.push(Self::get(pool, id, user_id).await?);
+                            continue;
+                        }
+                        result => {
+                            result?;
+                        }
+                    }
+                }
+            }
+            response.synced += 1;
         }
-        Ok(count)
+        Ok(response)
     }
 
     fn to_response(e: InfobaseEntry) -> InfobaseEntryResponse {
-        let tags: Vec<String> = e
-            .tags
-            .and_then(|t| serde_json::from_value(t).ok())
-            .unwrap_or_default();
-
         InfobaseEntryResponse {
             id: e.id,
             title: e.title,
             content: e.content,
             category: e.category,
-            tags,
+            tags: e.tags.unwrap_or_default(),
+            version: e.version,
             created_at: e.created_at,
             updated_at: e.updated_at,
         }
@@ -827,20 +837,71 @@ impl InfobaseRepo {
 
 pub struct IdeasRepo;
 
+pub const IDEAS_LIST: &str = r#"
+    SELECT id, user_id, title, content, category, tags, is_pinned, version, key, bpm, mood, genre,
+           time_signature, created_at, updated_at
+    FROM ideas
+    WHERE user_id = $1
+      AND ($2::TEXT IS NULL OR key = $2)
+      AND ($3::INTEGER IS NULL OR bpm >= $3)
+      AND ($4::INTEGER IS NULL OR bpm <= $4)
+      AND ($5::TEXT IS NULL OR lower(mood) = lower($5))
+      AND ($6::TEXT IS NULL OR lower(genre) = lower($6))
+      AND ($7::TEXT IS NULL OR time_signature = $7)
+    ORDER BY is_pinned DESC, created_at DESC
+"#;
+
+pub const IDEAS_GET: &str = r#"
+    SELECT id, user_id, title, content, category, tags, is_pinned, version, key, bpm, mood, genre,
+           time_signature, created_at, updated_at
+    FROM ideas
+    WHERE id = $1 AND user_id = $2
+"#;
+
+pub const IDEAS_GET_FOR_UPDATE: &str = r#"
+    SELECT id, user_id, title, content, category, tags, is_pinned, version, key, bpm, mood, genre,
+           time_signature, created_at, updated_at
+    FROM ideas
+    WHERE id = $1 AND user_id = $2
+    FOR UPDATE
+"#;
+
+pub const IDEAS_CREATE: &str = r#"
+    INSERT INTO ideas (id, user_id, title, content, category, tags, is_pinned, version, key, bpm,
+                       mood, genre, time_signature, created_at, updated_at)
+    VALUES ($1, $2, $3, $4, $5, $6, false, 1, $7, $8, $9, $10, $11, $12, $13)
+"#;
+
+pub const IDEAS_UPDATE: &str = r#"
+    UPDATE ideas
+    SET title = $1, content = $2, category = $3, tags = $4, is_pinned = $5, version = $6,
+        key = $7, bpm = $8, mood = $9, genre = $10, time_signature = $11, updated_at = $12
+    WHERE id = $13 AND user_id = $14
+"#;
+
+pub const IDEAS_DELETE: &str = "DELETE FROM ideas WHERE id = $1 AND user_id = $2";
+
 impl IdeasRepo {
-    /// List ideas
-    pub async fn list(pool: &PgPool, user_id: Uuid) -> Result<IdeasListResponse, AppError> {
-        let ideas = sqlx::query_as::<_, Idea>(
-            r#"
-            SELECT id, user_id, title, content, category, tags, is_pinned, created_at, updated_at
-            FROM ideas
-            WHERE user_id = $1
-            ORDER BY is_pinned DESC, created_at DESC
-            "#,
-        )
-        .bind(user_id)
-        .fetch_all(pool)
-        .await?;
+    /// List ideas matching the filters
+    pub async fn list(
+        pool: &PgPool,
+        user_id: Uuid,
+        filter: &IdeaFilter,
+    ) -> Result<IdeasListResponse, AppError> {
+        let key = match &filter.key {
+            Some(key) => Some(normalize_key(key).ok_or_else(|| unknown_key(key))?),
+            None => None,
+        };
+        let ideas = sqlx::query_as::<_, Idea>(IDEAS_LIST)
+            .bind(user_id)
+            .bind(key)
+            .bind(filter.bpm_min)
+            .bind(filter.bpm_max)
+            .bind(&filter.mood)
+            .bind(&filter.genre)
+            .bind(&filter.time_signature)
+            .fetch_all(pool)
+            .await?;
 
         Ok(IdeasListResponse {
             ideas: ideas.into_iter().map(Self::to_response).collect(),
@@ -849,76 +910,67 @@ impl IdeasRepo {
 
     /// Get a single idea
     pub async fn get(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<IdeaResponse, AppError> {
-        let idea = sqlx::query_as::<_, Idea>(
-            r#"
-            SELECT id, user_id, title, content, category, tags, is_pinned, created_at, updated_at
-            FROM ideas
-            WHERE id = $1 AND user_id = $2
-            "#,
-        )
-        .bind(id)
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Idea not found".into()))?;
+        let idea = sqlx::query_as::<_, Idea>(IDEAS_GET)
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Idea not found".into()))?;
 
         Ok(Self::to_response(idea))
     }
 
-    /// Create idea
-    pub async fn create(
-        pool: &PgPool,
+    /// Create idea, saved as revision 1
+    pub async fn create<'c>(
+        db: impl Acquire<'c, Database = Postgres>,
         user_id: Uuid,
         req: &CreateIdeaRequest,
     ) -> Result<IdeaResponse, AppError> {
         let id = Uuid::new_v4();
         let now = Utc::now();
-
-        // Build content from various inputs
-        let mut content = req.content.clone().unwrap_or_default();
-        if let Some(key) = &req.key {
-            content.push_str(&format!("\nKey: {}", key));
-        }
-        if let Some(bpm) = req.bpm {
-            content.push_str(&format!("\nBPM: {}", bpm));
-        }
-        if let Some(mood) = &req.mood {
-            content.push_str(&format!("\nMood: {}", mood));
-        }
-        let content = if content.trim().is_empty() {
-            None
-        } else {
-            Some(content.trim().to_string())
-        };
-
-        let mut tags = req.tags.clone().unwrap_or_default();
-        if let Some(mood) = &req.mood {
-            if !tags.contains(mood) {
-                tags.push(mood.clone());
-            }
-        }
-        let tags_json = if tags.is_empty() {
-            None
-        } else {
-            serde_json::to_value(&tags).ok()
+        let music = checked_music(&req.music)?;
+        let content = req
+            .content
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(str::to_string);
+        let tags = req.tags.clone().unwrap_or_default();
+
+        let mut tx = db.begin().await?;
+        sqlx::query(IDEAS_CREATE)
+            .bind(id)
+            .bind(user_id)
+            .bind(&req.title)
+            .bind(&content)
+            .bind(&req.category)
+            .bind(&tags)
+            .bind(&music.key)
+            .bind(music.bpm)
+            .bind(&music.mood)
+            .bind(&music.genre)
+            .bind(&music.time_signature)
+            .bind(now)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        let revision = NewRevision {
+            version: 1,
+            title: &req.title,
+            content: content.as_deref(),
+            category: Some(&req.category),
+            tags: &tags,
         };
-
-        sqlx::query(
-            r#"
-            INSERT INTO ideas (id, user_id, title, content, category, tags, is_pinned, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, false, $7, $8)
-            "#
+        RevisionRepo::record(
+            &mut tx,
+            user_id,
+            RevisionEntity::Idea,
+            id,
+            user_id,
+            &revision,
         )
-        .bind(id)
-        .bind(user_id)
-        .bind(&req.title)
-        .bind(&content)
-        .bind(&req.category)
-        .bind(&tags_json)
-        .bind(now)
-        .bind(now)
-        .execute(pool)
         .await?;
+        tx.commit().await?;
 
         Ok(IdeaResponse {
             id,
@@ -927,49 +979,91 @@ impl IdeasRepo {
             category: req.category.clone(),
             tags,
             is_pinned: false,
+            version: 1,
+            music,
             created_at: now,
             updated_at: now,
         })
     }
 
     /// Update idea
+    ///
+    /// With `expected_version` (the client's `If-Match`), an idea saved
+    /// since then is left alone and the update fails with a conflict.
     pub async fn update(
         pool: &PgPool,
         id: Uuid,
         user_id: Uuid,
         req: &UpdateIdeaRequest,
+        expected_version: Option<i32>,
     ) -> Result<IdeaResponse, AppError> {
-        let existing = Self::get(pool, id, user_id).await?;
+        let mut tx = pool.begin().await?;
+        let existing = sqlx::query_as::<_, Idea>(IDEAS_GET_FOR_UPDATE)
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Idea not found".into()))?;
+        if let Some(expected) = expected_version.filter(|v| *v != existing.version) {
+            let current = Self::to_response(existing);
+            return Err(version_conflict(&current, current.version, expected, req));
+        }
         let now = Utc::now();
+        let version = existing.version + 1;
 
         let title = req.title.as_ref().unwrap_or(&existing.title);
-        let content = req.content.clone().or(existing.content);
+        let content = req.content.clone().or(existing.content.clone());
         let category = req.category.as_ref().unwrap_or(&existing.category);
         let is_pinned = req.is_pinned.unwrap_or(existing.is_pinned);
-        let tags = req.tags.clone().unwrap_or(existing.tags.clone());
-        let tags_json = if tags.is_empty() {
-            None
-        } else {
-            serde_json::to_value(&tags).ok()
+        let tags = req
+            .tags
+            .clone()
+            .unwrap_or(existing.tags.clone().unwrap_or_default());
+        let changes = checked_music(&req.music)?;
+        let music = IdeaMusic {
+            key: changes.key.or(existing.music.key.clone()),
+            bpm: changes.bpm.or(existing.music.bpm),
+            mood: changes.mood.or(existing.music.mood.clone()),
+            genre: changes.genre.or(existing.music.genre.clone()),
+            time_signature: changes
+                .time_signature
+                .or(existing.music.time_signature.clone()),
         };
 
-        sqlx::query(
-            r#"
-            UPDATE ideas
-            SET title = $1, content = $2, category = $3, tags = $4, is_pinned = $5, updated_at = $6
-            WHERE id = $7 AND user_id = $8
-            "#,
+        sqlx::query(IDEAS_UPDATE)
+            .bind(title)
+            .bind(&content)
+            .bind(category)
+            .bind(&tags)
+            .bind(is_pinned)
+            .bind(version)
+            .bind(&music.key)
+            .bind(music.bpm)
+            .bind(&music.mood)
+            .bind(&music.genre)
+            .bind(&music.time_signature)
+            .bind(now)
+            .bind(id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        let revision = NewRevision {
+            version,
+            title,
+            content: content.as_deref(),
+            category: Some(category),
+            tags: &tags,
+        };
+        RevisionRepo::record(
+            &mut tx,
+            user_id,
+            RevisionEntity::Idea,
+            id,
+            user_id,
+            &revision,
         )
-        .bind(title)
-        .bind(&content)
-        .bind(category)
-        .bind(&tags_json)
-        .bind(is_pinned)
-        .bind(now)
-        .bind(id)
-        .bind(user_id)
-        .execute(pool)
         .await?;
+        tx.commit().await?;
 
         Ok(IdeaResponse {
             id,
@@ -978,87 +1072,312 @@ impl IdeasRepo {
             category: category.clone(),
             tags,
             is_pinned,
+            version,
+            music,
             created_at: existing.created_at,
             updated_at: now,
         })
     }
 
-    /// Delete idea
+    /// Save an earlier revision's content as a new version
+    pub async fn restore(
+        pool: &PgPool,
+        id: Uuid,
+        user_id: Uuid,
+        version: i32,
+        expected_version: Option<i32>,
+    ) -> Result<IdeaResponse, AppError> {
+        let revision = RevisionRepo::get(pool, user_id, RevisionEntity::Idea, id, version).await?;
+        let req = UpdateIdeaRequest {
+            title: Some(revision.title),
+            content: Some(revision.content.unwrap_or_default()),
+            category: revision.category,
+            tags: Some(revision.tags),
+            is_pinned: None,
+            music: IdeaMusic::default(),
+        };
+        Self::update(pool, id, user_id, &req, expected_version).await
+    }
+
+    /// Delete idea and its history
     pub async fn delete(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
-        let result = sqlx::query("DELETE FROM ideas WHERE id = $1 AND user_id = $2")
+        let mut tx = pool.begin().await?;
+        let result = sqlx::query(IDEAS_DELETE)
             .bind(id)
             .bind(user_id)
-            .execute(pool)
+            .execute(&mut *tx)
             .await?;
 
         if result.rows_affected() == 0 {
             return Err(AppError::NotFound("Idea not found".into()));
         }
 
+        RevisionRepo::delete_for_entity(&mut tx, RevisionEntity::Idea, id).await?;
+        tx.commit().await?;
         Ok(())
     }
 
     fn to_response(i: Idea) -> IdeaResponse {
-        let tags: Vec<String> = i
-            .tags
-            .and_then(|t| serde_json::from_value(t).ok())
-            .unwrap_or_default();
-
         IdeaResponse {
             id: i.id,
             title: i.title,
             content: i.content,
             category: i.category,
-            tags,
+            tags: i.tags.unwrap_or_default(),
             is_pinned: i.is_pinned,
+            version: i.version,
+            music: i.music,
             created_at: i.created_at,
             updated_at: i.updated_at,
         }
     }
 }
 
+/// Pitch class names keys are stored with
+const KEY_NAMES: [&str; 12] = [
+    "C", "Db", "D", "Eb", "E", "F", "F#", "G", "Ab", "A", "Bb", "B",
+];
+
+/// Spell a musical key the way it is stored
+///
+/// Accepts a note with an optional sharp or flat and a mode, e.g. "Am",
+/// "a# min", "Bb major" or "C"; uppercase "M" alone means major. Enharmonic
+/// spellings collapse to one ("A#m" and "Bbm" are both "Bb minor").
+pub fn normalize_key(input: &str) -> Option<String> {
+    let mut chars = input.trim().chars();
+    let semitone: i32 = match chars.next()?.to_ascii_uppercase() {
+        'C' => 0,
+        'D' => 2,
+        'E' => 4,
+        'F' => 5,
+        'G' => 7,
+        'A' => 9,
+        'B' => 11,
+        _ => return None,
+    };
+    let rest = chars.as_str();
+    let (shift, rest) = match rest.chars().next() {
+        Some(c @ ('#' | '♯')) => (1, &rest[c.len_utf8()..]),
+        Some(c @ ('b' | '♭')) => (-1, &rest[c.len_utf8()..]),
+        _ => (0, rest),
+    };
+    let mode = rest.trim();
+    let mode = if mode == "M" {
+        "major"
+    } else {
+        match mode.to_lowercase().as_str() {
+            "" | "maj" | "major" => "major",
+            "m" | "min" | "minor" => "minor",
+            _ => return None,
+        }
+    };
+
+    let name = KEY_NAMES[(semitone + shift).rem_euclid(12) as usize];
+    Some(format!("{} {}", name, mode))
+}
+
+fn unknown_key(key: &str) -> AppError {
+    AppError::Validation(format!(
+        "Unrecognised key '{}'; use a note and mode like 'A minor'",
+        key
+    ))
+}
+
+/// Normalize and validate music metadata; blank text counts as not given
+fn checked_music(music: &IdeaMusic) -> Result<IdeaMusic, AppError> {
+    fn text(value: &Option<String>) -> Option<String> {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    }
+
+    let key = match text(&music.key) {
+        Some(key) => Some(normalize_key(&key).ok_or_else(|| unknown_key(&key))?),
+        None => None,
+    };
+    if let Some(bpm) = music.bpm.filter(|bpm| !(20..=400).contains(bpm)) {
+        return Err(AppError::Validation(format!(
+            "BPM must be between 20 and 400, got {}",
+            bpm
+        )));
+    }
+    let time_signature = text(&music.time_signature);
+    if let Some(sig) = &time_signature {
+        let valid = sig.split_once('/').is_some_and(|(beats, unit)| {
+            beats.parse::<u8>().is_ok_and(|b| (1..=32).contains(&b))
+                && unit
+                    .parse::<u8>()
+                    .is_ok_and(|u| [1, 2, 4, 8, 16, 32].contains(&u))
+        });
+        if !valid {
+            return Err(AppError::Validation(format!(
+                "Time signature '{}' should look like '4/4' or '6/8'",
+                sig
+            )));
+        }
+    }
+
+    Ok(IdeaMusic {
+        key,
+        bpm: music.bpm,
+        mood: text(&music.mood),
+        genre: text(&music.genre),
+        time_signature,
+    })
+}
+
 // ============================================================================
 // ONBOARDING REPOSITORY
 // ============================================================================
 
 pub struct OnboardingRepo;
 
+pub const ONBOARDING_GET_ACTIVE_FLOW: &str = r#"
+    SELECT id, name, description, is_active, total_steps, version, published_at,
+           created_at, updated_at
+    FROM onboarding_flows
+    WHERE is_active = true
+    LIMIT 1
+"#;
+
+pub const ONBOARDING_GET_FLOW: &str = r#"
+    SELECT id, name, description, is_active, total_steps, version, published_at,
+           created_at, updated_at
+    FROM onboarding_flows
+    WHERE id = $1
+"#;
+
+pub const ONBOARDING_GET_FLOW_STEPS: &str = r#"
+    SELECT id, flow_id, step_order, step_type, title, description,
+           target_selector, target_route, fallback_content, options,
+           allows_multiple, required, action_type, action_config, show_if,
+           created_at, updated_at
+    FROM onboarding_steps
+    WHERE flow_id = $1
+    ORDER BY step_order ASC
+"#;
+
+pub const ONBOARDING_GET_STEP: &str = r#"
+    SELECT id, flow_id, step_order, step_type, title, description,
+           target_selector, target_route, fallback_content, options,
+           allows_multiple, required, action_type, action_config, show_if,
+           created_at, updated_at
+    FROM onboarding_steps
+    WHERE id = $1
+"#;
+
+pub const ONBOARDING_GET_USER_STATE: &str = r#"
+    SELECT id, user_id, flow_id, current_step_id, status,
+           can_resume, started_at, completed_at, skipped_at,
+           created_at, updated_at
+    FROM user_onboarding_state
+    WHERE user_id = $1
+"#;
+
+pub const ONBOARDING_GET_RESPONSES: &str = r#"
+    SELECT r.step_id, r.response
+    FROM user_onboarding_responses r
+    JOIN onboarding_steps s ON s.id = r.step_id
+    WHERE r.user_id = $1 AND s.flow_id = $2
+    ORDER BY s.step_order ASC, s.id ASC
+"#;
+
+pub const ONBOARDING_START: &str = r#"
+    INSERT INTO user_onboarding_state (id, user_id, flow_id, current_step_id, status, can_resume, started_at, created_at, updated_at)
+    VALUES ($1, $2, $3, $4, 'in_progress', true, $5, $5, $5)
+    ON CONFLICT (user_id) DO UPDATE
+    SET flow_id = $3, status = 'in_progress', current_step_id = $4, can_resume = true, started_at = COALESCE(user_onboarding_state.started_at, $5), updated_at = $5
+"#;
+
+pub const ONBOARDING_COMPLETE_STEP_INSERT_USER_ONBOARDING_RESPONSES: &str = r#"
+    INSERT INTO user_onboarding_responses (id, user_id, step_id, response, created_at)
+    VALUES ($1, $2, $3, $4, $5)
+    ON CONFLICT (user_id, step_id) DO UPDATE SET response = $4
+"#;
+
+pub const ONBOARDING_COMPLETE_STEP_FINISH: &str = r#"
+    UPDATE user_onboarding_state
+    SET status = 'completed', completed_at = $1, current_step_id = NULL, updated_at = $1
+    WHERE user_id = $2
+"#;
+
+pub const ONBOARDING_COMPLETE_STEP_ADVANCE: &str = r#"
+    UPDATE user_onboarding_state
+    SET current_step_id = $1, updated_at = $2
+    WHERE user_id = $3
+"#;
+
+pub const ONBOARDING_SKIP_UPDATE_USER_ONBOARDING_STATE: &str = r#"
+    UPDATE user_onboarding_state
+    SET status = 'skipped', skipped_at = $1, updated_at = $1
+    WHERE user_id = $2
+"#;
+
+pub const ONBOARDING_SKIP_INSERT_USER_SETTINGS: &str = r#"
+    INSERT INTO user_settings (user_id, key, value, created_at, updated_at)
+    VALUES ($1, 'soft_landing_until', to_jsonb($2::timestamptz), $3, $3)
+    ON CONFLICT (user_id, key) DO UPDATE SET value = EXCLUDED.value, updated_at = $3
+"#;
+
+pub const ONBOARDING_RESET_DELETE_USER_ONBOARDING_RESPONSES: &str =
+    "DELETE FROM user_onboarding_responses WHERE user_id = $1";
+
+pub const ONBOARDING_RESET_INSERT_USER_ONBOARDING_STATE: &str = r#"
+    INSERT INTO user_onboarding_state (id, user_id, flow_id, status, can_resume, created_at, updated_at)
+    VALUES ($1, $2, $3, 'not_started', true, $4, $4)
+    ON CONFLICT (user_id) DO UPDATE
+    SET status = 'not_started', current_step_id = NULL, started_at = NULL,
+        completed_at = NULL, skipped_at = NULL, can_resume = true, updated_at = $4
+"#;
+
 impl OnboardingRepo {
     /// Get active flow
     pub async fn get_active_flow(pool: &PgPool) -> Result<Option<OnboardingFlow>, AppError> {
-        let flow = sqlx::query_as::<_, OnboardingFlow>(
-            r#"
-            SELECT id, name, description, is_active, total_steps, created_at, updated_at
-            FROM onboarding_flows
-            WHERE is_active = true
-            LIMIT 1
-            "#,
-        )
-        .fetch_optional(pool)
-        .await?;
+        let flow = sqlx::query_as::<_, OnboardingFlow>(ONBOARDING_GET_ACTIVE_FLOW)
+            .fetch_optional(pool)
+            .await?;
 
         Ok(flow)
     }
 
+    /// Get a flow version by ID
+    pub async fn get_flow(
+        pool: &PgPool,
+        flow_id: Uuid,
+    ) -> Result<Option<OnboardingFlow>, AppError> {
+        let flow = sqlx::query_as::<_, OnboardingFlow>(ONBOARDING_GET_FLOW)
+            .bind(flow_id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(flow)
+    }
+
+    /// Flow version a user is on: the one they started, or the active one
+    /// if they have not started
+    async fn flow_for(
+        pool: &PgPool,
+        state: Option<&UserOnboardingState>,
+    ) -> Result<Option<OnboardingFlow>, AppError> {
+        if let Some(s) = state.filter(|s| s.status != "not_started") {
+            if let Some(flow) = Self::get_flow(pool, s.flow_id).await? {
+                return Ok(Some(flow));
+            }
+        }
+        Self::get_active_flow(pool).await
+    }
+
     /// Get flow steps
     pub async fn get_flow_steps(
         pool: &PgPool,
         flow_id: Uuid,
     ) -> Result<Vec<OnboardingStep>, AppError> {
-        let steps = sqlx::query_as::<_, OnboardingStep>(
-            r#"
-            SELECT id, flow_id, step_order, step_type, title, description,
-                   target_selector, target_route, fallback_content, options,
-                   allows_multiple, required, action_type, action_config,
-                   created_at, updated_at
-            FROM onboarding_steps
-            WHERE flow_id = $1
-            ORDER BY step_order ASC
-            "#,
-        )
-        .bind(flow_id)
-        .fetch_all(pool)
-        .await?;
+        let steps = sqlx::query_as::<_, OnboardingStep>(ONBOARDING_GET_FLOW_STEPS)
+            .bind(flow_id)
+            .fetch_all(pool)
+            .await?;
 
         Ok(steps)
     }
@@ -1068,19 +1387,10 @@ impl OnboardingRepo {
         pool: &PgPool,
         step_id: Uuid,
     ) -> Result<Option<OnboardingStep>, AppError> {
-        let step = sqlx::query_as::<_, OnboardingStep>(
-            r#"
-            SELECT id, flow_id, step_order, step_type, title, description,
-                   target_selector, target_route, fallback_content, options,
-                   allows_multiple, required, action_type, action_config,
-                   created_at, updated_at
-            FROM onboarding_steps
-            WHERE id = $1
-            "#,
-        )
-        .bind(step_id)
-        .fetch_optional(pool)
-        .await?;
+        let step = sqlx::query_as::<_, OnboardingStep>(ONBOARDING_GET_STEP)
+            .bind(step_id)
+            .fetch_optional(pool)
+            .await?;
 
         Ok(step)
     }
@@ -1090,22 +1400,29 @@ impl OnboardingRepo {
         pool: &PgPool,
         user_id: Uuid,
     ) -> Result<Option<UserOnboardingState>, AppError> {
-        let state = sqlx::query_as::<_, UserOnboardingState>(
-            r#"
-            SELECT id, user_id, flow_id, current_step_id, status,
-                   can_resume, started_at, completed_at, skipped_at,
-                   created_at, updated_at
-            FROM user_onboarding_state
-            WHERE user_id = $1
-            "#,
-        )
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let state = sqlx::query_as::<_, UserOnboardingState>(ONBOARDING_GET_USER_STATE)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
 
         Ok(state)
     }
 
+    /// A user's responses to the steps of a flow, in step order
+    pub async fn get_responses(
+        pool: &PgPool,
+        user_id: Uuid,
+        flow_id: Uuid,
+    ) -> Result<Vec<(Uuid, serde_json::Value)>, AppError> {
+        let responses = sqlx::query_as::<_, (Uuid, serde_json::Value)>(ONBOARDING_GET_RESPONSES)
+            .bind(user_id)
+            .bind(flow_id)
+            .fetch_all(pool)
+            .await?;
+
+        Ok(responses)
+    }
+
     /// Check if user needs onboarding
     pub async fn needs_onboarding(pool: &PgPool, user_id: Uuid) -> Result<bool, AppError> {
         let state = Self::get_user_state(pool, user_id).await?;
@@ -1117,13 +1434,16 @@ impl OnboardingRepo {
     }
 
     /// Get onboarding progress
+    ///
+    /// Counts the steps still ahead on the user's path given their answers
+    /// so far, so steps a condition passes over are not left outstanding.
     pub async fn get_progress(
         pool: &PgPool,
         user_id: Uuid,
     ) -> Result<OnboardingProgress, AppError> {
         let state = Self::get_user_state(pool, user_id).await?;
 
-        let Some(_state) = state else {
+        let Some(state) = state else {
             return Ok(OnboardingProgress {
                 completed_steps: 0,
                 total_steps: 0,
@@ -1131,24 +1451,36 @@ impl OnboardingRepo {
             });
         };
 
-        let flow = Self::get_active_flow(pool).await?;
-        let total = flow.map(|f| f.total_steps).unwrap_or(0);
+        let Some(flow) = Self::flow_for(pool, Some(&state)).await? else {
+            return Ok(OnboardingProgress {
+                completed_steps: 0,
+                total_steps: 0,
+                percent_complete: 0,
+            });
+        };
 
-        // Count completed steps
-        #[derive(FromRow)]
-        struct CountRow {
-            count: i64,
-        }
+        let steps = Self::get_flow_steps(pool, flow.id).await?;
+        let responses = Self::get_responses(pool, user_id, flow.id).await?;
+        let answers = merge_answers(responses.iter().map(|(_, r)| r));
+        let outstanding = |s: &&OnboardingStep| {
+            !responses.iter().any(|(id, _)| *id == s.id) && step_visible(s, &answers)
+        };
 
-        let completed = sqlx::query_as::<_, CountRow>(
-            "SELECT COUNT(*) as count FROM user_onboarding_responses WHERE user_id = $1",
-        )
-        .bind(user_id)
-        .fetch_one(pool)
-        .await
-        .map(|r| r.count as i32)
-        .unwrap_or(0);
+        let current = state
+            .current_step_id
+            .and_then(|id| steps.iter().find(|s| s.id == id));
+        let remaining = match current {
+            Some(current) => steps
+                .iter()
+                .filter(|s| s.step_order >= current.step_order)
+                .filter(outstanding)
+                .count(),
+            None if state.status == "completed" => 0,
+            None => steps.iter().filter(outstanding).count(),
+        };
 
+        let completed = responses.len() as i32;
+        let total = completed + remaining as i32;
         let percent = if total > 0 {
             (completed * 100) / total
         } else {
@@ -1162,49 +1494,66 @@ impl OnboardingRepo {
         })
     }
 
-    /// Start onboarding
+    /// Start or resume onboarding
+    ///
+    /// A user part-way through, or who skipped, resumes the flow version
+    /// they started, even if a newer version has been published since.
     pub async fn start(pool: &PgPool, user_id: Uuid) -> Result<StartOnboardingResponse, AppError> {
-        let flow = Self::get_active_flow(pool)
-            .await?
-            .ok_or_else(|| AppError::Internal("No active onboarding flow".into()))?;
+        let state = Self::get_user_state(pool, user_id).await?;
+        let resume_at = match &state {
+            Some(s) if s.status == "in_progress" || s.status == "skipped" => {
+                match s.current_step_id {
+                    Some(step_id) => Self::get_step(pool, step_id).await?,
+                    None => None,
+                }
+            }
+            _ => None,
+        };
 
-        let steps = Self::get_flow_steps(pool, flow.id).await?;
-        let first_step = steps.first().cloned();
+        let (flow_id, current_step) = match resume_at {
+            Some(step) => (step.flow_id, Some(step)),
+            None => {
+                let flow = Self::get_active_flow(pool)
+                    .await?
+                    .ok_or_else(|| AppError::Internal("No active onboarding flow".into()))?;
+                let steps = Self::get_flow_steps(pool, flow.id).await?;
+                let responses = Self::get_responses(pool, user_id, flow.id).await?;
+                let answers = merge_answers(responses.iter().map(|(_, r)| r));
+                (flow.id, next_visible_step(&steps, None, &answers).cloned())
+            }
+        };
 
         let now = Utc::now();
         let id = Uuid::new_v4();
 
         // Create or update state
-        sqlx::query(
-            r#"
-            INSERT INTO user_onboarding_state (id, user_id, flow_id, current_step_id, status, started_at, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, 'in_progress', $5, $5, $5)
-            ON CONFLICT (user_id) DO UPDATE
-            SET status = 'in_progress', current_step_id = $4, started_at = COALESCE(user_onboarding_state.started_at, $5), updated_at = $5
-            "#
-        )
-        .bind(id)
-        .bind(user_id)
-        .bind(flow.id)
-        .bind(first_step.as_ref().map(|s| s.id))
-        .bind(now)
-        .execute(pool)
-        .await?;
+        sqlx::query(ONBOARDING_START)
+            .bind(id)
+            .bind(user_id)
+            .bind(flow_id)
+            .bind(current_step.as_ref().map(|s| s.id))
+            .bind(now)
+            .execute(pool)
+            .await?;
 
         Ok(StartOnboardingResponse {
             success: true,
             state: OnboardingStateResponse {
                 status: "in_progress".to_string(),
-                started_at: Some(now),
+                started_at: state.and_then(|s| s.started_at).or(Some(now)),
                 completed_at: None,
                 skipped_at: None,
                 can_resume: true,
             },
-            current_step: first_step.map(Self::step_to_response),
+            current_step: current_step.map(Self::step_to_response),
         })
     }
 
     /// Complete a step
+    ///
+    /// Only the user's current step can be completed. The next step is the
+    /// first later one whose `show_if` holds against the user's responses,
+    /// including this one.
     pub async fn complete_step(
         pool: &PgPool,
         user_id: Uuid,
@@ -1213,76 +1562,55 @@ impl OnboardingRepo {
     ) -> Result<CompleteStepResponse, AppError> {
         let now = Utc::now();
 
+        let state = Self::get_user_state(pool, user_id)
+            .await?
+            .ok_or_else(|| AppError::BadRequest("Onboarding has not been started".into()))?;
+        let step = Self::get_step(pool, step_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Step not found".into()))?;
+        // Completing any other step would let a client jump past steps,
+        // replay finished ones and rewind the flow, or reach into another flow
+        if state.current_step_id != Some(step_id) {
+            return Err(AppError::BadRequest(
+                "Step is not your current onboarding step".into(),
+            ));
+        }
+
         // Store response
         let resp_id = Uuid::new_v4();
         let response_json = response.unwrap_or(serde_json::json!({}));
 
-        sqlx::query(
-            r#"
-            INSERT INTO user_onboarding_responses (id, user_id, step_id, response, created_at)
-            VALUES ($1, $2, $3, $4, $5)
-            ON CONFLICT (user_id, step_id) DO UPDATE SET response = $4
-            "#,
-        )
-        .bind(resp_id)
-        .bind(user_id)
-        .bind(step_id)
-        .bind(&response_json)
-        .bind(now)
-        .execute(pool)
-        .await?;
-
-        // Get current step to find next
-        let step = Self::get_step(pool, step_id)
-            .await?
-            .ok_or_else(|| AppError::NotFound("Step not found".into()))?;
+        sqlx::query(ONBOARDING_COMPLETE_STEP_INSERT_USER_ONBOARDING_RESPONSES)
+            .bind(resp_id)
+            .bind(user_id)
+            .bind(step_id)
+            .bind(&response_json)
+            .bind(now)
+            .execute(pool)
+            .await?;
 
         // Get next step
-        let next_step = sqlx::query_as::<_, OnboardingStep>(
-            r#"
-            SELECT id, flow_id, step_order, step_type, title, description,
-                   target_selector, target_route, fallback_content, options,
-                   allows_multiple, required, action_type, action_config,
-                   created_at, updated_at
-            FROM onboarding_steps
-            WHERE flow_id = $1 AND step_order > $2
-            ORDER BY step_order ASC
-            LIMIT 1
-            "#,
-        )
-        .bind(step.flow_id)
-        .bind(step.step_order)
-        .fetch_optional(pool)
-        .await?;
+        let steps = Self::get_flow_steps(pool, step.flow_id).await?;
+        let responses = Self::get_responses(pool, user_id, step.flow_id).await?;
+        let answers = merge_answers(responses.iter().map(|(_, r)| r));
+        let next_step = next_visible_step(&steps, Some(step.step_order), &answers).cloned();
 
         let completed = next_step.is_none();
 
         // Update state
         if completed {
-            sqlx::query(
-                r#"
-                UPDATE user_onboarding_state
-                SET status = 'completed', completed_at = $1, current_step_id = NULL, updated_at = $1
-                WHERE user_id = $2
-                "#,
-            )
-            .bind(now)
-            .bind(user_id)
-            .execute(pool)
-            .await?;
+            sqlx::query(ONBOARDING_COMPLETE_STEP_FINISH)
+                .bind(now)
+                .bind(user_id)
+                .execute(pool)
+                .await?;
         } else {
-            sqlx::query(
-                r#"
-                UPDATE user_onboarding_state
-                SET current_step_id = $1, updated_at = $2
-                WHERE user_id = $3
-                "#,
-            )
-            .bind(next_step.as_ref().map(|s| s.id))
-            .bind(now)
-            .bind(user_id)
-            .execute(pool)
-            .await?;
+            sqlx::query(ONBOARDING_COMPLETE_STEP_ADVANCE)
+                .bind(next_step.as_ref().map(|s| s.id))
+                .bind(now)
+                .bind(user_id)
+                .execute(pool)
+                .await?;
         }
 
         Ok(CompleteStepResponse {
@@ -1297,32 +1625,19 @@ impl OnboardingRepo {
         let now = Utc::now();
         let soft_landing_until = now + chrono::Duration::hours(1);
 
-        sqlx::query(
-            r#"
-            UPDATE user_onboarding_state
-            SET status = 'skipped', skipped_at = $1, updated_at = $1
-            WHERE user_id = $2
-            "#,
-        )
-        .bind(now)
-        .bind(user_id)
-        .execute(pool)
-        .await?;
+        sqlx::query(ONBOARDING_SKIP_UPDATE_USER_ONBOARDING_STATE)
+            .bind(now)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
 
         // Update user settings with soft landing
-        sqlx::query(
-            r#"
-            INSERT INTO user_settings (id, user_id, soft_landing_until, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $4)
-            ON CONFLICT (user_id) DO UPDATE SET soft_landing_until = $3, updated_at = $4
-            "#,
-        )
-        .bind(Uuid::new_v4())
-        .bind(user_id)
-        .bind(soft_landing_until)
-        .bind(now)
-        .execute(pool)
-        .await?;
+        sqlx::query(ONBOARDING_SKIP_INSERT_USER_SETTINGS)
+            .bind(user_id)
+            .bind(soft_landing_until)
+            .bind(now)
+            .execute(pool)
+            .await?;
 
         Ok(SkipOnboardingResponse {
             success: true,
@@ -1339,27 +1654,19 @@ impl OnboardingRepo {
             .ok_or_else(|| AppError::Internal("No active onboarding flow".into()))?;
 
         // Delete responses
-        sqlx::query("DELETE FROM user_onboarding_responses WHERE user_id = $1")
+        sqlx::query(ONBOARDING_RESET_DELETE_USER_ONBOARDING_RESPONSES)
             .bind(user_id)
             .execute(pool)
             .await?;
 
         // Reset state
-        sqlx::query(
-            r#"
-            INSERT INTO user_onboarding_state (id, user_id, flow_id, status, can_resume, created_at, updated_at)
-            VALUES ($1, $2, $3, 'not_started', true, $4, $4)
-            ON CONFLICT (user_id) DO UPDATE
-            SET status = 'not_started', current_step_id = NULL, started_at = NULL, 
-                completed_at = NULL, skipped_at = NULL, can_resume = true, updated_at = $4
-            "#
-        )
-        .bind(Uuid::new_v4())
-        .bind(user_id)
-        .bind(flow.id)
-        .bind(now)
-        .execute(pool)
-        .await?;
+        sqlx::query(ONBOARDING_RESET_INSERT_USER_ONBOARDING_STATE)
+            .bind(Uuid::new_v4())
+            .bind(user_id)
+            .bind(flow.id)
+            .bind(now)
+            .execute(pool)
+            .await?;
 
         Ok(())
     }
@@ -1373,9 +1680,14 @@ impl OnboardingRepo {
         let needs = Self::needs_onboarding(pool, user_id).await?;
         let progress = Self::get_progress(pool, user_id).await?;
 
-        let flow = Self::get_active_flow(pool).await?;
+        // Steps on the user's path so far; later answers can reveal more
+        let flow = Self::flow_for(pool, state.as_ref()).await?;
         let steps = if let Some(ref f) = flow {
-            Self::get_flow_steps(pool, f.id).await?
+            let responses = Self::get_responses(pool, user_id, f.id).await?;
+            let answers = merge_answers(responses.iter().map(|(_, r)| r));
+            let mut steps = Self::get_flow_steps(pool, f.id).await?;
+            steps.retain(|s| step_visible(s, &answers));
+            steps
         } else {
             vec![]
         };
@@ -1403,6 +1715,7 @@ impl OnboardingRepo {
             flow: flow.map(|f| OnboardingFlowResponse {
                 id: f.id,
                 name: f.name,
+                version: f.version,
                 total_steps: f.total_steps,
             }),
             current_step: current_step.map(Self::step_to_response),
@@ -1443,37 +1756,51 @@ impl OnboardingRepo {
 
 pub struct UserSettingsRepo;
 
+pub const USER_SETTINGS_GET: &str = r#"
+    SELECT COALESCE(jsonb_object_agg(key, value), '{}'::jsonb)
+    FROM user_settings
+    WHERE user_id = $1
+"#;
+
+pub const USER_SETTINGS_UPDATE: &str = r#"
+    INSERT INTO user_settings (user_id, key, value, created_at, updated_at)
+    SELECT $1, s.key, s.value, $3, $3
+    FROM jsonb_each($2) AS s(key, value)
+    ON CONFLICT (user_id, key) DO UPDATE SET value = EXCLUDED.value, updated_at = $3
+"#;
+
+pub const USER_SETTINGS_TIMEZONE_EXISTS: &str =
+    "SELECT EXISTS(SELECT 1 FROM pg_timezone_names WHERE name = $1)";
+
 impl UserSettingsRepo {
-    /// Get user settings
+    /// Whether `timezone` is an IANA zone name the database knows
+    pub async fn timezone_exists(pool: &PgPool, timezone: &str) -> Result<bool, AppError> {
+        let exists = sqlx::query_scalar(USER_SETTINGS_TIMEZONE_EXISTS)
+            .bind(timezone)
+            .fetch_one(pool)
+            .await?;
+        Ok(exists)
+    }
+
+    /// Get user settings, filling unset keys with defaults
     pub async fn get(pool: &PgPool, user_id: Uuid) -> Result<UserSettingsResponse, AppError> {
-        let settings = sqlx::query_as::<_, UserSettings>(
-            r#"
-            SELECT id, user_id, notifications_enabled, email_notifications,
-                   push_notifications, theme, timezone, locale, profile_public,
-                   show_activity, soft_landing_until, daily_reminder_time,
-                   created_at, updated_at
-            FROM user_settings
-            WHERE user_id = $1
-            "#,
-        )
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?;
+        let stored: serde_json::Value = sqlx::query_scalar(USER_SETTINGS_GET)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
 
-        match settings {
-            Some(s) => Ok(Self::to_response(s)),
-            None => Ok(UserSettingsResponse {
-                notifications_enabled: true,
-                email_notifications: true,
-                push_notifications: false,
-                theme: "system".to_string(),
-                timezone: None,
-                locale: "en".to_string(),
-                profile_public: false,
-                show_activity: true,
-                daily_reminder_time: None,
-            }),
+        let mut settings = serde_json::to_value(UserSettingsResponse::default())
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        if let (Some(settings), Some(stored)) = (settings.as_object_mut(), stored.as_object()) {
+            for (key, value) in settings.iter_mut() {
+                if let Some(v) = stored.get(key) {
+                    *value = v.clone();
+                }
+            }
         }
+
+        // A malformed stored value falls back to defaults rather than failing the request
+        Ok(serde_json::from_value(settings).unwrap_or_default())
     }
 
     /// Update settings
@@ -1485,79 +1812,51 @@ impl UserSettingsRepo {
         let existing = Self::get(pool, user_id).await?;
         let now = Utc::now();
 
-        let notifications_enabled = req
-            .notifications_enabled
-            .unwrap_or(existing.notifications_enabled);
-        let email_notifications = req
-            .email_notifications
-            .unwrap_or(existing.email_notifications);
-        let push_notifications = req
-            .push_notifications
-            .unwrap_or(existing.push_notifications);
-        let theme = req.theme.as_ref().unwrap_or(&existing.theme);
-        let timezone = req.timezone.clone().or(existing.timezone);
-        let locale = req.locale.as_ref().unwrap_or(&existing.locale);
-        let profile_public = req.profile_public.unwrap_or(existing.profile_public);
-        let show_activity = req.show_activity.unwrap_or(existing.show_activity);
-        let daily_reminder_time = req
-            .daily_reminder_time
-            .clone()
-            .or(existing.daily_reminder_time);
-
-        sqlx::query(
-            r#"
-            INSERT INTO user_settings (
-                id, user_id, notifications_enabled, email_notifications,
-                push_notifications, theme, timezone, locale, profile_public,
-                show_activity, daily_reminder_time, created_at, updated_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $12)
-            ON CONFLICT (user_id) DO UPDATE SET
-                notifications_enabled = $3, email_notifications = $4,
-                push_notifications = $5, theme = $6, timezone = $7, locale = $8,
-                profile_public = $9, show_activity = $10, daily_reminder_time = $11,
-                updated_at = $12
-            "#,
-        )
-        .bind(Uuid::new_v4())
-        .bind(user_id)
-        .bind(notifications_enabled)
-        .bind(email_notifications)
-        .bind(push_notifications)
-        .bind(theme)
-        .bind(&timezone)
-        .bind(locale)
-        .bind(profile_public)
-        .bind(show_activity)
-        .bind(&daily_reminder_time)
-        .bind(now)
-        .execute(pool)
-        .await?;
+        let settings = UserSettingsResponse {
+            notifications_enabled: req
+                .notifications_enabled
+                .unwrap_or(existing.notifications_enabled),
+            email_notifications: req
+                .email_notifications
+                .unwrap_or(existing.email_notifications),
+            push_notifications: req
+                .push_notifications
+                .unwrap_or(existing.push_notifications),
+            theme: req.theme.clone().unwrap_or(existing.theme),
+            timezone: req.timezone.clone().or(existing.timezone),
+            locale: req.locale.clone().unwrap_or(existing.locale),
+            profile_public: req.profile_public.unwrap_or(existing.profile_public),
+            show_activity: req.show_activity.unwrap_or(existing.show_activity),
+            daily_reminder_time: req
+                .daily_reminder_time
+                .clone()
+                .or(existing.daily_reminder_time),
+            quiet_hours_start: match &req.quiet_hours_start {
+                Some(t) if t.is_empty() => None,
+                Some(t) => Some(t.clone()),
+                None => existing.quiet_hours_start,
+            },
+            quiet_hours_end: match &req.quiet_hours_end {
+                Some(t) if t.is_empty() => None,
+                Some(t) => Some(t.clone()),
+                None => existing.quiet_hours_end,
+            },
+            workday_start: req.workday_start.clone().unwrap_or(existing.workday_start),
+            workday_end: req.workday_end.clone().unwrap_or(existing.workday_end),
+        };
 
-        Ok(UserSettingsResponse {
-            notifications_enabled,
-            email_notifications,
-            push_notifications,
-            theme: theme.clone(),
-            timezone,
-            locale: locale.clone(),
-            profile_public,
-            show_activity,
-            daily_reminder_time,
-        })
-    }
+        let values =
+            serde_json::to_value(&settings).map_err(|e| AppError::Internal(e.to_string()))?;
 
-    fn to_response(s: UserSettings) -> UserSettingsResponse {
-        UserSettingsResponse {
-            notifications_enabled: s.notifications_enabled,
-            email_notifications: s.email_notifications,
-            push_notifications: s.push_notifications,
-            theme: s.theme,
-            timezone: s.timezone,
-            locale: s.locale,
-            profile_public: s.profile_public,
-            show_activity: s.show_activity,
-            daily_reminder_time: s.daily_reminder_time,
-        }
+        // One row per setting key
+        sqlx::query(USER_SETTINGS_UPDATE)
+            .bind(user_id)
+            .bind(&values)
+            .bind(now)
+            .execute(pool)
+            .await?;
+
+        Ok(settings)
     }
 }
 
@@ -1567,25 +1866,26 @@ impl UserSettingsRepo {
 
 pub struct UserAccountRepo;
 
-impl UserAccountRepo {
-    /// Delete all user data
-    pub async fn delete_account(
-        pool: &PgPool,
-        user_id: Uuid,
-    ) -> Result<DeleteAccountResponse, AppError> {
-        // Delete from all tables in order (respecting foreign keys)
-        // The users table has ON DELETE CASCADE on most child tables
-        sqlx::query("DELETE FROM users WHERE id = $1")
-            .bind(user_id)
-            .execute(pool)
-            .await?;
-
-        Ok(DeleteAccountResponse {
-            success: true,
-            message: "Account and all associated data deleted successfully".to_string(),
-        })
-    }
+/// Tables included in a user data export, each keyed by `user_id`
+pub const EXPORT_TABLES: &[&str] = &[
+    "calendar_events",
+    "daily_plans",
+    "feedback",
+    "infobase_entries",
+    "ideas",
+    "habits",
+    "goals",
+    "focus_sessions",
+    "user_settings",
+    "user_interests",
+];
+
+/// Export query for one of `EXPORT_TABLES`
+pub fn export_table_sql(table: &str) -> String {
+    format!("SELECT row_to_json(t) FROM {} t WHERE user_id = $1", table)
+}
 
+impl UserAccountRepo {
     /// Export all user data
     pub async fn export_data(
         pool: &PgPool,
@@ -1595,63 +1895,15 @@ impl UserAccountRepo {
         let now = Utc::now();
         let mut data = serde_json::Map::new();
 
-        // Helper macro for fetching table data
-        macro_rules! fetch_table {
-            ($pool:expr, $user_id:expr, $table:expr) => {{
-                let result: Result<Vec<serde_json::Value>, _> = sqlx::query_scalar(&format!(
-                    "SELECT row_to_json(t) FROM {} t WHERE user_id = $1",
-                    $table
-                ))
-                .bind($user_id)
-                .fetch_all($pool)
-                .await;
-                result.unwrap_or_default()
-            }};
-        }
-
         // Fetch data from each table
-        data.insert(
-            "calendar_events".to_string(),
-            serde_json::to_value(fetch_table!(pool, user_id, "calendar_events"))
-                .unwrap_or_default(),
-        );
-        data.insert(
-            "daily_plans".to_string(),
-            serde_json::to_value(fetch_table!(pool, user_id, "daily_plans")).unwrap_or_default(),
-        );
-        data.insert(
-            "feedback".to_string(),
-            serde_json::to_value(fetch_table!(pool, user_id, "feedback")).unwrap_or_default(),
-        );
-        data.insert(
-            "infobase_entries".to_string(),
-            serde_json::to_value(fetch_table!(pool, user_id, "infobase_entries"))
-                .unwrap_or_default(),
-        );
-        data.insert(
-            "ideas".to_string(),
-            serde_json::to_value(fetch_table!(pool, user_id, "ideas")).unwrap_or_default(),
-        );
-        data.insert(
-            "habits".to_string(),
-            serde_json::to_value(fetch_table!(pool, user_id, "habits")).unwrap_or_default(),
-        );
-        data.insert(
-            "goals".to_string(),
-            serde_json::to_value(fetch_table!(pool, user_id, "goals")).unwrap_or_default(),
-        );
-        data.insert(
-            "focus_sessions".to_string(),
-            serde_json::to_value(fetch_table!(pool, user_id, "focus_sessions")).unwrap_or_default(),
-        );
-        data.insert(
-            "user_settings".to_string(),
-            serde_json::to_value(fetch_table!(pool, user_id, "user_settings")).unwrap_or_default(),
-        );
-        data.insert(
-            "user_interests".to_string(),
-            serde_json::to_value(fetch_table!(pool, user_id, "user_interests")).unwrap_or_default(),
-        );
+        for table in EXPORT_TABLES {
+            let rows: Vec<serde_json::Value> = sqlx::query_scalar(&export_table_sql(table))
+                .bind(user_id)
+                .fetch_all(pool)
+                .await
+                .unwrap_or_default();
+            data.insert(table.to_string(), serde_json::Value::Array(rows));
+        }
 
         Ok(ExportDataResponse {
             exported_at: now,
@@ -1661,3 +1913,57 @@ impl UserAccountRepo {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_key_spellings() {
+        assert_eq!(normalize_key("Am").as_deref(), Some("A minor"));
+        assert_eq!(normalize_key(" a minor ").as_deref(), Some("A minor"));
+        assert_eq!(normalize_key("C").as_deref(), Some("C major"));
+        assert_eq!(normalize_key("CM").as_deref(), Some("C major"));
+        assert_eq!(normalize_key("F# maj").as_deref(), Some("F# major"));
+        assert_eq!(normalize_key("Gb").as_deref(), Some("F# major"));
+        assert_eq!(normalize_key("A#m").as_deref(), Some("Bb minor"));
+        assert_eq!(normalize_key("B♭ min").as_deref(), Some("Bb minor"));
+        assert_eq!(normalize_key("Cb").as_deref(), Some("B major"));
+        assert_eq!(normalize_key("bm").as_deref(), Some("B minor"));
+        assert_eq!(normalize_key("H"), None);
+        assert_eq!(normalize_key("A dorian"), None);
+        assert_eq!(normalize_key(""), None);
+    }
+
+    #[test]
+    fn test_checked_music() {
+        let music = checked_music(&IdeaMusic {
+            key: Some("ebm".into()),
+            bpm: Some(124),
+            mood: Some("  ".into()),
+            genre: Some(" house ".into()),
+            time_signature: Some("6/8".into()),
+        })
+        .unwrap();
+        assert_eq!(music.key.as_deref(), Some("Eb minor"));
+        assert_eq!(music.mood, None);
+        assert_eq!(music.genre.as_deref(), Some("house"));
+
+        for bad in [
+            IdeaMusic {
+                bpm: Some(900),
+                ..Default::default()
+            },
+            IdeaMusic {
+                time_signature: Some("4/3".into()),
+                ..Default::default()
+            },
+            IdeaMusic {
+                key: Some("X".into()),
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(checked_music(&bad), Err(AppError::Validation(_))));
+        }
+    }
+}
@@ -61,6 +61,34 @@ impl QuestDifficulty {
     }
 }
 
+// Universal quest target types: what a quest counts toward its target.
+// Everything except QUEST_TARGET_MANUAL advances from points ledger events.
+
+/// Only completed by hand
+pub const QUEST_TARGET_MANUAL: &str = "manual";
+/// Minutes of completed focus sessions
+pub const QUEST_TARGET_FOCUS_MINUTES: &str = "focus_minutes";
+/// Completed focus sessions
+pub const QUEST_TARGET_FOCUS_SESSIONS: &str = "focus_sessions";
+/// Habit check-ins
+pub const QUEST_TARGET_HABITS_COMPLETED: &str = "habits_completed";
+/// Completed workouts
+pub const QUEST_TARGET_WORKOUTS_COMPLETED: &str = "workouts_completed";
+/// Minutes of completed workouts
+pub const QUEST_TARGET_EXERCISE_MINUTES: &str = "exercise_minutes";
+/// Minutes of logged reading
+pub const QUEST_TARGET_READING_MINUTES: &str = "reading_minutes";
+/// Scored drill sessions
+pub const QUEST_TARGET_DRILLS_COMPLETED: &str = "drills_completed";
+/// Completed personal quests
+pub const QUEST_TARGET_QUESTS_COMPLETED: &str = "quests_completed";
+/// XP earned from any source
+pub const QUEST_TARGET_XP_EARNED: &str = "xp_earned";
+/// Coins earned from any source
+pub const QUEST_TARGET_COINS_EARNED: &str = "coins_earned";
+/// Events whose type is `target_config.event_type`
+pub const QUEST_TARGET_EVENT_COUNT: &str = "event_count";
+
 // ============================================================================
 // DATABASE MODELS
 // ============================================================================
@@ -74,7 +102,9 @@ pub struct UniversalQuest {
     pub description: Option<String>,
     pub quest_type: String,
     pub category: Option<String>,
-    pub requirements: Option<serde_json::Value>,
+    pub target: i32,
+    pub target_type: String,
+    pub target_config: Option<serde_json::Value>,
     pub xp_reward: i32,
     pub coin_reward: i32,
     pub skill_key: Option<String>,
@@ -104,6 +134,16 @@ pub struct UserQuestProgress {
     pub updated_at: DateTime<Utc>,
 }
 
+/// A points ledger event, as seen by quest progress
+#[derive(Debug, Clone)]
+pub struct QuestEvent {
+    pub event_type: String,
+    pub xp: i32,
+    pub coins: i32,
+    /// Amount of activity behind the event (e.g. minutes focused)
+    pub quantity: Option<i32>,
+}
+
 /// User quest (personal quests)
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct Quest {
@@ -222,3 +262,15 @@ pub struct UniversalQuestWithProgress {
     pub is_available: bool,
     pub is_completed: bool,
 }
+
+/// Result of claiming a universal quest reward
+#[derive(Debug, Clone, Serialize)]
+pub struct ClaimQuestResult {
+    pub progress: UserQuestProgress,
+    /// The reward had already been claimed; nothing was awarded this time
+    pub already_claimed: bool,
+    pub xp_awarded: i32,
+    pub coins_awarded: i32,
+    pub leveled_up: bool,
+    pub new_level: Option<i32>,
+}
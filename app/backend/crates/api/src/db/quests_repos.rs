@@ -2,20 +2,27 @@
 //!
 //! Database operations for quest system.
 
+use std::collections::HashMap;
+
 use chrono::Utc;
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool};
 use uuid::Uuid;
 
 use super::gamification_models::AwardPointsInput;
 use super::gamification_repos::GamificationRepo;
+use super::habits_goals_repos::GoalsRepo;
 use super::quests_models::*;
 use crate::error::AppError;
 
 // Column list for user_quests table - matches Quest struct field order exactly
-const QUEST_COLUMNS: &str = r#"id, user_id, source_quest_id, title, description, category, difficulty,
+macro_rules! quest_columns {
+    () => {
+        r#"id, user_id, source_quest_id, title, description, category, difficulty,
     xp_reward, coin_reward, status, progress, target, is_active, is_repeatable,
     repeat_frequency, accepted_at, completed_at, claimed_at, expires_at,
-    last_completed_date, streak_count, created_at, updated_at"#;
+    last_completed_date, streak_count, created_at, updated_at"#
+    };
+}
 
 // ============================================================================
 // QUESTS REPOSITORY
@@ -23,10 +30,74 @@ const QUEST_COLUMNS: &str = r#"id, user_id, source_quest_id, title, description,
 
 pub struct QuestsRepo;
 
+pub const QUESTS_CREATE: &str = concat!(
+    r#"
+    INSERT INTO user_quests
+    (user_id, title, description, category, difficulty, xp_reward, coin_reward,
+     target, is_repeatable, repeat_frequency, status, progress, is_active, streak_count)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, 'active', 0, true, 0)
+    RETURNING "#,
+    quest_columns!()
+);
+
+pub const QUESTS_GET_BY_ID: &str = concat!(
+    "SELECT ",
+    quest_columns!(),
+    " FROM user_quests WHERE id = $1 AND user_id = $2"
+);
+
+pub const QUESTS_LIST_BY_STATUS: &str = concat!(
+    "SELECT ",
+    quest_columns!(),
+    r#"
+    FROM user_quests
+    WHERE user_id = $1 AND status = $2 AND is_active = true
+    ORDER BY created_at DESC
+"#
+);
+
+pub const QUESTS_LIST: &str = concat!(
+    "SELECT ",
+    quest_columns!(),
+    r#"
+    FROM user_quests
+    WHERE user_id = $1 AND is_active = true
+    ORDER BY created_at DESC
+"#
+);
+
+pub const QUESTS_ACCEPT_QUEST: &str = concat!(
+    r#"
+    UPDATE user_quests
+    SET status = 'accepted'
+    WHERE id = $1 AND user_id = $2
+    RETURNING "#,
+    quest_columns!()
+);
+
+pub const QUESTS_COMPLETE_QUEST: &str = concat!(
+    r#"
+    UPDATE user_quests
+    SET status = 'completed', completed_at = NOW(),
+        last_completed_date = $1, streak_count = $2
+    WHERE id = $3 AND user_id = $4
+    RETURNING "#,
+    quest_columns!()
+);
+
+pub const QUESTS_ABANDON_QUEST: &str = concat!(
+    r#"
+    UPDATE user_quests
+    SET status = 'abandoned'
+    WHERE id = $1 AND user_id = $2
+    RETURNING "#,
+    quest_columns!()
+);
+
 impl QuestsRepo {
     /// Create a new user quest
     pub async fn create(
-        pool: &PgPool,
+        db: impl PgExecutor<'_>,
         user_id: Uuid,
         req: &CreateQuestRequest,
     ) -> Result<Quest, AppError> {
@@ -44,16 +115,7 @@ impl QuestsRepo {
         let coins = req.coin_reward.unwrap_or(default_coins);
         let target = req.target.unwrap_or(1);
 
-        let query = format!(
-            r#"INSERT INTO user_quests
-               (user_id, title, description, category, difficulty, xp_reward, coin_reward,
-                target, is_repeatable, repeat_frequency, status)
-               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, 'active')
-               RETURNING {}"#,
-            QUEST_COLUMNS
-        );
-
-        let quest = sqlx::query_as::<_, Quest>(&query)
+        let quest = sqlx::query_as::<_, Quest>(QUESTS_CREATE)
             .bind(user_id)
             .bind(&req.title)
             .bind(&req.description)
@@ -64,7 +126,7 @@ impl QuestsRepo {
             .bind(target)
             .bind(req.is_repeatable.unwrap_or(false))
             .bind(&req.repeat_frequency)
-            .fetch_one(pool)
+            .fetch_one(db)
             .await?;
 
         Ok(quest)
@@ -76,12 +138,7 @@ impl QuestsRepo {
         quest_id: Uuid,
         user_id: Uuid,
     ) -> Result<Option<Quest>, AppError> {
-        let query = format!(
-            r#"SELECT {} FROM user_quests WHERE id = $1 AND user_id = $2"#,
-            QUEST_COLUMNS
-        );
-
-        let quest = sqlx::query_as::<_, Quest>(&query)
+        let quest = sqlx::query_as::<_, Quest>(QUESTS_GET_BY_ID)
             .bind(quest_id)
             .bind(user_id)
             .fetch_optional(pool)
@@ -97,25 +154,13 @@ impl QuestsRepo {
         status_filter: Option<&str>,
     ) -> Result<QuestsListResponse, AppError> {
         let quests = if let Some(status) = status_filter {
-            let query = format!(
-                r#"SELECT {} FROM user_quests
-                   WHERE user_id = $1 AND status = $2 AND is_active = true
-                   ORDER BY created_at DESC"#,
-                QUEST_COLUMNS
-            );
-            sqlx::query_as::<_, Quest>(&query)
+            sqlx::query_as::<_, Quest>(QUESTS_LIST_BY_STATUS)
                 .bind(user_id)
                 .bind(status)
                 .fetch_all(pool)
                 .await?
         } else {
-            let query = format!(
-                r#"SELECT {} FROM user_quests
-                   WHERE user_id = $1 AND is_active = true
-                   ORDER BY created_at DESC"#,
-                QUEST_COLUMNS
-            );
-            sqlx::query_as::<_, Quest>(&query)
+            sqlx::query_as::<_, Quest>(QUESTS_LIST)
                 .bind(user_id)
                 .fetch_all(pool)
                 .await?
@@ -145,15 +190,7 @@ impl QuestsRepo {
             )));
         }
 
-        let query = format!(
-            r#"UPDATE user_quests
-               SET status = 'accepted'
-               WHERE id = $1 AND user_id = $2
-               RETURNING {}"#,
-            QUEST_COLUMNS
-        );
-
-        let updated = sqlx::query_as::<_, Quest>(&query)
+        let updated = sqlx::query_as::<_, Quest>(QUESTS_ACCEPT_QUEST)
             .bind(quest_id)
             .bind(user_id)
             .fetch_one(pool)
@@ -192,23 +229,17 @@ impl QuestsRepo {
             0
         };
 
-        // Update quest
-        let query = format!(
-            r#"UPDATE user_quests
-               SET status = 'completed', completed_at = NOW(),
-                   last_completed_date = $1, streak_count = $2
-               WHERE id = $3 AND user_id = $4
-               RETURNING {}"#,
-            QUEST_COLUMNS
-        );
-
-        let updated = sqlx::query_as::<_, Quest>(&query)
+        // Update quest and the goals it counts towards
+        let mut tx = pool.begin().await?;
+        let updated = sqlx::query_as::<_, Quest>(QUESTS_COMPLETE_QUEST)
             .bind(today)
             .bind(new_streak)
             .bind(quest_id)
             .bind(user_id)
-            .fetch_one(pool)
+            .fetch_one(&mut *tx)
             .await?;
+        GoalsRepo::refresh_linked(&mut tx, user_id, "quest", quest_id).await?;
+        tx.commit().await?;
 
         // Award points with idempotency
         let idempotency_key = format!("quest_complete_{}_{}", quest_id, today);
@@ -224,6 +255,7 @@ impl QuestsRepo {
                 event_id: Some(quest_id),
                 reason: Some(format!("Completed quest: {}", quest.title)),
                 idempotency_key: Some(idempotency_key),
+                quantity: None,
             },
         )
         .await?;
@@ -253,15 +285,7 @@ impl QuestsRepo {
             )));
         }
 
-        let query = format!(
-            r#"UPDATE user_quests
-               SET status = 'abandoned'
-               WHERE id = $1 AND user_id = $2
-               RETURNING {}"#,
-            QUEST_COLUMNS
-        );
-
-        let updated = sqlx::query_as::<_, Quest>(&query)
+        let updated = sqlx::query_as::<_, Quest>(QUESTS_ABANDON_QUEST)
             .bind(quest_id)
             .bind(user_id)
             .fetch_one(pool)
@@ -270,3 +294,432 @@ impl QuestsRepo {
         Ok(updated)
     }
 }
+
+// ============================================================================
+// UNIVERSAL QUESTS REPOSITORY
+// ============================================================================
+
+// Column list for universal_quests - matches UniversalQuest struct field order
+macro_rules! universal_quest_columns {
+    () => {
+        r#"q.id, q.key, q.title AS name, q.description, q.type AS quest_type, q.category,
+    q.target, q.target_type, q.target_config, q.xp_reward, q.coin_reward, q.skill_key,
+    q.skill_star_reward, q.is_recurring, q.recurrence_period, q.is_active, q.sort_order,
+    q.created_at, q.updated_at"#
+    };
+}
+
+// Column list for user_quest_progress - matches UserQuestProgress struct field order
+macro_rules! quest_progress_columns {
+    () => {
+        r#"id, user_id, quest_id, status, progress, accepted_at, completed_at,
+    claimed_at, last_reset_at, times_completed, created_at, updated_at"#
+    };
+}
+
+/// How far `event` moves a quest with this target (0 when it doesn't count)
+pub fn quest_increment(
+    target_type: &str,
+    target_config: Option<&serde_json::Value>,
+    event: &QuestEvent,
+) -> i32 {
+    let minutes = event.quantity.unwrap_or(0);
+    let amount = match (target_type, event.event_type.as_str()) {
+        (QUEST_TARGET_FOCUS_MINUTES, "focus_complete") => minutes,
+        // Only focus-mode sessions carry minutes; breaks are not counted
+        (QUEST_TARGET_FOCUS_SESSIONS, "focus_complete") => i32::from(event.quantity.is_some()),
+        (QUEST_TARGET_HABITS_COMPLETED, "habit_complete") => 1,
+        (QUEST_TARGET_WORKOUTS_COMPLETED, "workout_complete") => 1,
+        (QUEST_TARGET_EXERCISE_MINUTES, "workout_complete") => minutes,
+        (QUEST_TARGET_READING_MINUTES, "reading_logged") => minutes,
+        (QUEST_TARGET_DRILLS_COMPLETED, "drill_complete") => 1,
+        (QUEST_TARGET_QUESTS_COMPLETED, "quest_complete") => 1,
+        (QUEST_TARGET_XP_EARNED, _) => event.xp,
+        (QUEST_TARGET_COINS_EARNED, _) => event.coins,
+        (QUEST_TARGET_EVENT_COUNT, event_type) => {
+            let wanted = target_config
+                .and_then(|c| c.get("event_type"))
+                .and_then(|v| v.as_str());
+            i32::from(wanted == Some(event_type))
+        }
+        _ => 0,
+    };
+    amount.max(0)
+}
+
+pub struct UniversalQuestRepo;
+
+pub const UNIVERSAL_QUEST_LIST_ACTIVE: &str = concat!(
+    "SELECT ",
+    universal_quest_columns!(),
+    r#"
+    FROM universal_quests q
+    WHERE q.is_active = true
+    ORDER BY q.sort_order, q.title
+"#
+);
+
+pub const UNIVERSAL_QUEST_LIST_AUTOMATIC: &str = concat!(
+    "SELECT ",
+    universal_quest_columns!(),
+    r#"
+    FROM universal_quests q
+    WHERE q.is_active = true AND q.target_type <> 'manual'
+"#
+);
+
+pub const UNIVERSAL_QUEST_GET_BY_ID: &str = concat!(
+    "SELECT ",
+    universal_quest_columns!(),
+    " FROM universal_quests q WHERE q.id = $1 AND q.is_active = true"
+);
+
+pub const QUEST_PROGRESS_LIST_FOR_USER: &str = concat!(
+    "SELECT ",
+    quest_progress_columns!(),
+    " FROM user_quest_progress WHERE user_id = $1"
+);
+
+pub const QUEST_PROGRESS_GET: &str = concat!(
+    "SELECT ",
+    quest_progress_columns!(),
+    " FROM user_quest_progress WHERE user_id = $1 AND quest_id = $2"
+);
+
+// Recurring quests start over once their period (UTC) has rolled over. A
+// completion left unclaimed lapses with its period, like the quest itself.
+pub const QUEST_PROGRESS_RESET_EXPIRED: &str = r#"
+    UPDATE user_quest_progress p
+    SET progress = 0, status = 'in_progress', completed_at = NULL, claimed_at = NULL,
+        last_reset_at = NOW(), updated_at = NOW()
+    FROM universal_quests q
+    WHERE p.quest_id = q.id
+      AND p.user_id = $1
+      AND q.is_recurring = true
+      AND q.recurrence_period IN ('daily', 'weekly', 'monthly')
+      AND COALESCE(p.last_reset_at, p.created_at) < date_trunc(
+            CASE q.recurrence_period WHEN 'daily' THEN 'day' WHEN 'weekly' THEN 'week' ELSE 'month' END,
+            NOW(), 'UTC')
+"#;
+
+// $3 = amount, $4 = quest target; progress is capped at the target and rows
+// that are already completed or claimed are left alone
+pub const QUEST_PROGRESS_ADVANCE: &str = concat!(
+    r#"
+    INSERT INTO user_quest_progress
+    (user_id, quest_id, status, progress, completed_at, last_reset_at, times_completed)
+    VALUES (
+        $1, $2,
+        CASE WHEN $3::int >= $4::int THEN 'completed' ELSE 'in_progress' END,
+        LEAST($3, $4),
+        CASE WHEN $3 >= $4 THEN NOW() END,
+        NOW(),
+        CASE WHEN $3 >= $4 THEN 1 ELSE 0 END
+    )
+    ON CONFLICT (user_id, quest_id) DO UPDATE SET
+        progress = LEAST(user_quest_progress.progress + $3, $4),
+        status = CASE WHEN user_quest_progress.progress + $3 >= $4
+                      THEN 'completed' ELSE 'in_progress' END,
+        completed_at = CASE WHEN user_quest_progress.progress + $3 >= $4 THEN NOW() END,
+        times_completed = user_quest_progress.times_completed
+            + CASE WHEN user_quest_progress.progress + $3 >= $4 THEN 1 ELSE 0 END,
+        updated_at = NOW()
+    WHERE user_quest_progress.status = 'in_progress'
+    RETURNING "#,
+    quest_progress_columns!()
+);
+
+pub const QUEST_PROGRESS_CLAIM: &str = concat!(
+    r#"
+    UPDATE user_quest_progress
+    SET status = 'claimed', claimed_at = NOW(), updated_at = NOW()
+    WHERE user_id = $1 AND quest_id = $2 AND status = 'completed'
+    RETURNING "#,
+    quest_progress_columns!()
+);
+
+pub const QUEST_PROGRESS_UNDO_CLAIM: &str = r#"
+    UPDATE user_quest_progress
+    SET status = 'completed', claimed_at = NULL, updated_at = NOW()
+    WHERE id = $1 AND status = 'claimed'
+"#;
+
+impl UniversalQuestRepo {
+    /// Active universal quests with the user's progress in the current period
+    pub async fn list_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<UniversalQuestWithProgress>, AppError> {
+        Self::reset_expired(pool, user_id).await?;
+
+        let quests = sqlx::query_as::<_, UniversalQuest>(UNIVERSAL_QUEST_LIST_ACTIVE)
+            .fetch_all(pool)
+            .await?;
+
+        let mut progress: HashMap<Uuid, UserQuestProgress> =
+            sqlx::query_as::<_, UserQuestProgress>(QUEST_PROGRESS_LIST_FOR_USER)
+                .bind(user_id)
+                .fetch_all(pool)
+                .await?
+                .into_iter()
+                .map(|p| (p.quest_id, p))
+                .collect();
+
+        Ok(quests
+            .into_iter()
+            .map(|quest| {
+                let progress = progress.remove(&quest.id);
+                let status = progress.as_ref().map(|p| p.status.as_str());
+                UniversalQuestWithProgress {
+                    is_available: status != Some(QuestStatus::Claimed.as_str()),
+                    is_completed: matches!(status, Some("completed") | Some("claimed")),
+                    quest,
+                    progress,
+                }
+            })
+            .collect())
+    }
+
+    /// Advance every automatic quest that counts this ledger event
+    ///
+    /// Returns the progress rows that moved.
+    pub async fn record_event(
+        pool: &PgPool,
+        user_id: Uuid,
+        event: &QuestEvent,
+    ) -> Result<Vec<UserQuestProgress>, AppError> {
+        let quests = sqlx::query_as::<_, UniversalQuest>(UNIVERSAL_QUEST_LIST_AUTOMATIC)
+            .fetch_all(pool)
+            .await?;
+
+        let advances: Vec<(Uuid, i32, i32)> = quests
+            .iter()
+            .filter_map(|q| {
+                let amount = quest_increment(&q.target_type, q.target_config.as_ref(), event);
+                (amount > 0).then_some((q.id, amount, q.target))
+            })
+            .collect();
+
+        if advances.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Self::reset_expired(pool, user_id).await?;
+
+        let mut updated = Vec::with_capacity(advances.len());
+        for (quest_id, amount, target) in advances {
+            let row = sqlx::query_as::<_, UserQuestProgress>(QUEST_PROGRESS_ADVANCE)
+                .bind(user_id)
+                .bind(quest_id)
+                .bind(amount)
+                .bind(target)
+                .fetch_optional(pool)
+                .await?;
+            updated.extend(row);
+        }
+
+        Ok(updated)
+    }
+
+    /// Claim the reward for a completed quest
+    ///
+    /// Claiming again returns the claimed progress without awarding anything.
+    pub async fn claim(
+        pool: &PgPool,
+        user_id: Uuid,
+        quest_id: Uuid,
+    ) -> Result<ClaimQuestResult, AppError> {
+        let quest = sqlx::query_as::<_, UniversalQuest>(UNIVERSAL_QUEST_GET_BY_ID)
+            .bind(quest_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Quest not found".to_string()))?;
+
+        Self::reset_expired(pool, user_id).await?;
+
+        // Only one request can move the row from completed to claimed
+        let claimed = sqlx::query_as::<_, UserQuestProgress>(QUEST_PROGRESS_CLAIM)
+            .bind(user_id)
+            .bind(quest_id)
+            .fetch_optional(pool)
+            .await?;
+
+        let Some(progress) = claimed else {
+            let current = sqlx::query_as::<_, UserQuestProgress>(QUEST_PROGRESS_GET)
+                .bind(user_id)
+                .bind(quest_id)
+                .fetch_optional(pool)
+                .await?;
+
+            return match current {
+                Some(progress) if progress.status == QuestStatus::Claimed.as_str() => {
+                    Ok(ClaimQuestResult {
+                        progress,
+                        already_claimed: true,
+                        xp_awarded: 0,
+                        coins_awarded: 0,
+                        leveled_up: false,
+                        new_level: None,
+                    })
+                }
+                _ => Err(AppError::BadRequest("Quest target not reached".to_string())),
+            };
+        };
+
+        // One key per completion, so a recurring quest pays out once per period
+        let idempotency_key = format!(
+            "universal_quest_claim_{}_{}",
+            progress.id, progress.times_completed
+        );
+        let award_result = GamificationRepo::award_points(
+            pool,
+            user_id,
+            &AwardPointsInput {
+                xp: Some(quest.xp_reward),
+                coins: Some(quest.coin_reward),
+                skill_stars: Some(quest.skill_star_reward),
+                skill_key: quest.skill_key.clone(),
+                event_type: "universal_quest_claim".to_string(),
+                event_id: Some(quest.id),
+                reason: Some(format!("Claimed quest: {}", quest.name)),
+                idempotency_key: Some(idempotency_key),
+                quantity: None,
+            },
+        )
+        .await;
+
+        let award_result = match award_result {
+            Ok(result) => result,
+            Err(e) => {
+                // Leave the reward claimable
+                let _ = sqlx::query(QUEST_PROGRESS_UNDO_CLAIM)
+                    .bind(progress.id)
+                    .execute(pool)
+                    .await;
+                return Err(e);
+            }
+        };
+
+        Ok(ClaimQuestResult {
+            progress,
+            already_claimed: false,
+            xp_awarded: quest.xp_reward,
+            coins_awarded: quest.coin_reward,
+            leveled_up: award_result.leveled_up.unwrap_or(false),
+            new_level: award_result.new_level,
+        })
+    }
+
+    /// Start recurring quests over when their period has rolled over
+    async fn reset_expired(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(QUEST_PROGRESS_RESET_EXPIRED)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event_type: &str, xp: i32, coins: i32, quantity: Option<i32>) -> QuestEvent {
+        QuestEvent {
+            event_type: event_type.to_string(),
+            xp,
+            coins,
+            quantity,
+        }
+    }
+
+    #[test]
+    fn test_quest_increment_counts_matching_events() {
+        let focus = event("focus_complete", 25, 5, Some(25));
+
+        assert_eq!(
+            quest_increment(QUEST_TARGET_FOCUS_MINUTES, None, &focus),
+            25
+        );
+        assert_eq!(
+            quest_increment(QUEST_TARGET_FOCUS_SESSIONS, None, &focus),
+            1
+        );
+        assert_eq!(
+            quest_increment(QUEST_TARGET_WORKOUTS_COMPLETED, None, &focus),
+            0
+        );
+        assert_eq!(quest_increment(QUEST_TARGET_MANUAL, None, &focus), 0);
+
+        let short_break = event("focus_complete", 2, 0, None);
+        assert_eq!(
+            quest_increment(QUEST_TARGET_FOCUS_SESSIONS, None, &short_break),
+            0
+        );
+
+        let workout = event("workout_complete", 40, 2, Some(40));
+        assert_eq!(
+            quest_increment(QUEST_TARGET_WORKOUTS_COMPLETED, None, &workout),
+            1
+        );
+        assert_eq!(
+            quest_increment(QUEST_TARGET_EXERCISE_MINUTES, None, &workout),
+            40
+        );
+    }
+
+    #[test]
+    fn test_quest_increment_totals_rewards() {
+        let habit = event("habit_complete", 10, 3, None);
+
+        assert_eq!(quest_increment(QUEST_TARGET_XP_EARNED, None, &habit), 10);
+        assert_eq!(quest_increment(QUEST_TARGET_COINS_EARNED, None, &habit), 3);
+        // Minutes-based targets need a quantity
+        assert_eq!(
+            quest_increment(
+                QUEST_TARGET_READING_MINUTES,
+                None,
+                &event("reading_logged", 1, 0, None)
+            ),
+            0
+        );
+    }
+
+    #[test]
+    fn test_quest_increment_event_count_uses_config() {
+        let config = serde_json::json!({ "event_type": "milestone_complete" });
+
+        assert_eq!(
+            quest_increment(
+                QUEST_TARGET_EVENT_COUNT,
+                Some(&config),
+                &event("milestone_complete", 10, 0, None)
+            ),
+            1
+        );
+        assert_eq!(
+            quest_increment(
+                QUEST_TARGET_EVENT_COUNT,
+                Some(&config),
+                &event("habit_complete", 10, 0, None)
+            ),
+            0
+        );
+        assert_eq!(
+            quest_increment(
+                QUEST_TARGET_EVENT_COUNT,
+                None,
+                &event("habit_complete", 10, 0, None)
+            ),
+            0
+        );
+    }
+
+    #[test]
+    fn test_quest_increment_never_negative() {
+        let refund = event("purchase", -50, -20, Some(-5));
+
+        assert_eq!(quest_increment(QUEST_TARGET_XP_EARNED, None, &refund), 0);
+        assert_eq!(quest_increment(QUEST_TARGET_COINS_EARNED, None, &refund), 0);
+    }
+}
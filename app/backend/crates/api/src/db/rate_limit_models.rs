@@ -0,0 +1,14 @@
+//! Rate Limit Models
+//!
+//! Token buckets shared between instances (see `services::rate_limit`).
+
+use sqlx::FromRow;
+
+/// A bucket after taking a token from it
+#[derive(Debug, Clone, Copy, FromRow)]
+pub struct RateLimitBucket {
+    /// Tokens left, possibly fractional
+    pub tokens: f64,
+    /// Whether a token was available
+    pub allowed: bool,
+}
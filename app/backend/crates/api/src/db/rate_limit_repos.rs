@@ -0,0 +1,63 @@
+//! Rate Limit Repository
+//!
+//! Postgres-backed token buckets for the rate limiter's `postgres` backend.
+
+use sqlx::PgPool;
+
+use super::rate_limit_models::*;
+use crate::error::AppError;
+
+pub struct RateLimitRepo;
+
+/// Refill a bucket for the time since it was last used, then take a token if
+/// a whole one is there. New buckets start full.
+pub const RATE_LIMIT_TAKE: &str = r#"
+    INSERT INTO rate_limit_buckets (key, tokens, allowed, updated_at)
+    VALUES ($1, $2::float8 - 1, TRUE, NOW())
+    ON CONFLICT (key) DO UPDATE
+    SET tokens = CASE
+            WHEN LEAST($2::float8, rate_limit_buckets.tokens
+                + EXTRACT(EPOCH FROM NOW() - rate_limit_buckets.updated_at)::float8 * $3::float8) >= 1
+            THEN LEAST($2::float8, rate_limit_buckets.tokens
+                + EXTRACT(EPOCH FROM NOW() - rate_limit_buckets.updated_at)::float8 * $3::float8) - 1
+            ELSE LEAST($2::float8, rate_limit_buckets.tokens
+                + EXTRACT(EPOCH FROM NOW() - rate_limit_buckets.updated_at)::float8 * $3::float8)
+        END,
+        allowed = LEAST($2::float8, rate_limit_buckets.tokens
+            + EXTRACT(EPOCH FROM NOW() - rate_limit_buckets.updated_at)::float8 * $3::float8) >= 1,
+        updated_at = NOW()
+    RETURNING tokens, allowed
+"#;
+
+pub const RATE_LIMIT_PRUNE: &str = r#"
+    DELETE FROM rate_limit_buckets
+    WHERE updated_at < NOW() - make_interval(secs => $1::float8)
+"#;
+
+impl RateLimitRepo {
+    /// Take a token from a bucket holding at most `capacity` that refills at
+    /// `refill_per_second`
+    pub async fn take(
+        pool: &PgPool,
+        key: &str,
+        capacity: f64,
+        refill_per_second: f64,
+    ) -> Result<RateLimitBucket, AppError> {
+        let bucket = sqlx::query_as::<_, RateLimitBucket>(RATE_LIMIT_TAKE)
+            .bind(key)
+            .bind(capacity)
+            .bind(refill_per_second)
+            .fetch_one(pool)
+            .await?;
+        Ok(bucket)
+    }
+
+    /// Delete buckets idle for longer than `idle_seconds`
+    pub async fn prune(pool: &PgPool, idle_seconds: f64) -> Result<u64, AppError> {
+        let result = sqlx::query(RATE_LIMIT_PRUNE)
+            .bind(idle_seconds)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
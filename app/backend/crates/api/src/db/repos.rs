@@ -6,6 +6,7 @@ use uuid::Uuid;
 
 use super::models::*;
 use crate::error::AppError;
+use crate::shared::auth::csrf::verify_token;
 
 /// Generate a secure random token for sessions
 pub fn generate_session_token() -> String {
@@ -17,40 +18,79 @@ pub fn generate_session_token() -> String {
     base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
 }
 
+/// Hash a raw session token for storage and lookup
+///
+/// Only the lowercase hex SHA-256 digest is persisted in `sessions.token`;
+/// the raw token lives solely in the client's cookie.
+pub fn hash_session_token(token: &str) -> String {
+    use sha2::{Digest, Sha256};
+    use std::fmt::Write;
+
+    let digest = Sha256::digest(token.as_bytes());
+    let mut hex = String::with_capacity(digest.len() * 2);
+    for byte in digest {
+        let _ = write!(hex, "{:02x}", byte);
+    }
+    hex
+}
+
 /// User repository operations
 pub struct UserRepo;
 
+pub const USER_FIND_BY_ID: &str = r#"
+    SELECT
+        id, name, email, email_verified, image, role,
+        approved, age_verified, tos_accepted, tos_accepted_at,
+        tos_version, last_activity_at, created_at, updated_at
+    FROM users WHERE id = $1
+"#;
+
+pub const USER_FIND_BY_EMAIL: &str = r#"
+    SELECT
+        id, name, email, email_verified, image, role,
+        approved, age_verified, tos_accepted, tos_accepted_at,
+        tos_version, last_activity_at, created_at, updated_at
+    FROM users WHERE email = $1
+"#;
+
+pub const USER_CREATE: &str = r#"
+    INSERT INTO users (id, email, name, image, email_verified, role, approved, age_verified, tos_accepted, is_admin, created_at, updated_at)
+    VALUES ($1, $2, $3, $4, $5, 'user', false, false, false, false, NOW(), NOW())
+    RETURNING
+        id, name, email, email_verified, image, role,
+        approved, age_verified, tos_accepted, tos_accepted_at,
+        tos_version, last_activity_at, created_at, updated_at
+"#;
+
+pub const USER_UPDATE_LAST_ACTIVITY: &str =
+    "UPDATE users SET last_activity_at = NOW() WHERE id = $1";
+
+pub const USER_VERIFY_AGE: &str = "UPDATE users SET age_verified = true WHERE id = $1";
+
+pub const USER_ACCEPT_TOS: &str =
+    "UPDATE users SET tos_accepted = true, tos_accepted_at = NOW(), tos_version = $2 WHERE id = $1";
+
+pub const USER_UPDATE_ROLE: &str = "UPDATE users SET role = $2 WHERE id = $1";
+
 impl UserRepo {
     /// Find user by ID
     pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<User>, AppError> {
-        let user = sqlx::query_as::<_, User>(
-            r#"SELECT
-                id, name, email, email_verified, image, role,
-                approved, age_verified, tos_accepted, tos_accepted_at,
-                tos_version, last_activity_at, created_at, updated_at
-            FROM users WHERE id = $1"#,
-        )
-        .bind(id)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        let user = sqlx::query_as::<_, User>(USER_FIND_BY_ID)
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(user)
     }
 
     /// Find user by email
     pub async fn find_by_email(pool: &PgPool, email: &str) -> Result<Option<User>, AppError> {
-        let user = sqlx::query_as::<_, User>(
-            r#"SELECT
-                id, name, email, email_verified, image, role,
-                approved, age_verified, tos_accepted, tos_accepted_at,
-                tos_version, last_activity_at, created_at, updated_at
-            FROM users WHERE email = $1"#,
-        )
-        .bind(email)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        let user = sqlx::query_as::<_, User>(USER_FIND_BY_EMAIL)
+            .bind(email)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(user)
     }
@@ -58,29 +98,22 @@ impl UserRepo {
     /// Create a new user
     pub async fn create(pool: &PgPool, input: CreateUserInput) -> Result<User, AppError> {
         let id = Uuid::new_v4();
-        let user = sqlx::query_as::<_, User>(
-            r#"INSERT INTO users (id, email, name, image, email_verified, role, approved, age_verified, tos_accepted, is_admin, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, 'user', false, false, false, false, NOW(), NOW())
-            RETURNING
-                id, name, email, email_verified, image, role,
-                approved, age_verified, tos_accepted, tos_accepted_at,
-                tos_version, last_activity_at, created_at, updated_at"#,
-        )
-        .bind(id)
-        .bind(&input.email)
-        .bind(&input.name)
-        .bind(&input.image)
-        .bind(input.email_verified)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        let user = sqlx::query_as::<_, User>(USER_CREATE)
+            .bind(id)
+            .bind(&input.email)
+            .bind(&input.name)
+            .bind(&input.image)
+            .bind(input.email_verified)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(user)
     }
 
     /// Update user's last activity
     pub async fn update_last_activity(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
-        sqlx::query("UPDATE users SET last_activity_at = NOW() WHERE id = $1")
+        sqlx::query(USER_UPDATE_LAST_ACTIVITY)
             .bind(user_id)
             .execute(pool)
             .await
@@ -91,7 +124,7 @@ impl UserRepo {
 
     /// Verify user's age (COPPA compliance)
     pub async fn verify_age(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
-        sqlx::query("UPDATE users SET age_verified = true WHERE id = $1")
+        sqlx::query(USER_VERIFY_AGE)
             .bind(user_id)
             .execute(pool)
             .await
@@ -106,7 +139,7 @@ impl UserRepo {
         user_id: Uuid,
         tos_version: &str,
     ) -> Result<(), AppError> {
-        sqlx::query("UPDATE users SET tos_accepted = true, tos_accepted_at = NOW(), tos_version = $2 WHERE id = $1")
+        sqlx::query(USER_ACCEPT_TOS)
             .bind(user_id)
             .bind(tos_version)
             .execute(pool)
@@ -119,7 +152,7 @@ impl UserRepo {
     /// Update user role
     #[allow(dead_code)]
     pub async fn update_role(pool: &PgPool, user_id: Uuid, role: UserRole) -> Result<(), AppError> {
-        sqlx::query("UPDATE users SET role = $2 WHERE id = $1")
+        sqlx::query(USER_UPDATE_ROLE)
             .bind(user_id)
             .bind(role.as_str())
             .execute(pool)
@@ -133,6 +166,40 @@ impl UserRepo {
 /// Account (OAuth) repository operations
 pub struct AccountRepo;
 
+pub const ACCOUNT_FIND_BY_PROVIDER: &str = r#"
+    SELECT
+        id, user_id, type, provider, provider_account_id,
+        refresh_token, access_token, expires_at, token_type, scope,
+        id_token, session_state, created_at, updated_at
+    FROM accounts
+    WHERE provider = $1 AND provider_account_id = $2
+"#;
+
+pub const ACCOUNT_FIND_BY_USER_ID: &str = r#"
+    SELECT
+        id, user_id, type, provider, provider_account_id,
+        refresh_token, access_token, expires_at, token_type, scope,
+        id_token, session_state, created_at, updated_at
+    FROM accounts
+    WHERE user_id = $1
+"#;
+
+pub const ACCOUNT_UPSERT: &str = r#"
+    INSERT INTO accounts (id, user_id, type, provider, provider_account_id, access_token, refresh_token, expires_at, id_token, created_at, updated_at)
+    VALUES ($1, $2, 'oauth', $3, $4, $5, $6, $7, $8, NOW(), NOW())
+    ON CONFLICT (provider, provider_account_id)
+    DO UPDATE SET
+        access_token = EXCLUDED.access_token,
+        refresh_token = COALESCE(EXCLUDED.refresh_token, accounts.refresh_token),
+        expires_at = EXCLUDED.expires_at,
+        id_token = EXCLUDED.id_token,
+        updated_at = NOW()
+    RETURNING
+        id, user_id, type, provider, provider_account_id,
+        refresh_token, access_token, expires_at, token_type, scope,
+        id_token, session_state, created_at, updated_at
+"#;
+
 impl AccountRepo {
     /// Find account by provider and provider account ID
     pub async fn find_by_provider(
@@ -140,19 +207,12 @@ impl AccountRepo {
         provider: &str,
         provider_account_id: &str,
     ) -> Result<Option<Account>, AppError> {
-        let account = sqlx::query_as::<_, Account>(
-            r#"SELECT
-                id, user_id, type, provider, provider_account_id,
-                refresh_token, access_token, expires_at, token_type, scope,
-                id_token, session_state, created_at, updated_at
-            FROM accounts
-            WHERE provider = $1 AND provider_account_id = $2"#,
-        )
-        .bind(provider)
-        .bind(provider_account_id)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        let account = sqlx::query_as::<_, Account>(ACCOUNT_FIND_BY_PROVIDER)
+            .bind(provider)
+            .bind(provider_account_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(account)
     }
@@ -160,18 +220,11 @@ impl AccountRepo {
     /// Find all accounts for a user
     #[allow(dead_code)]
     pub async fn find_by_user_id(pool: &PgPool, user_id: Uuid) -> Result<Vec<Account>, AppError> {
-        let accounts = sqlx::query_as::<_, Account>(
-            r#"SELECT
-                id, user_id, type, provider, provider_account_id,
-                refresh_token, access_token, expires_at, token_type, scope,
-                id_token, session_state, created_at, updated_at
-            FROM accounts
-            WHERE user_id = $1"#,
-        )
-        .bind(user_id)
-        .fetch_all(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        let accounts = sqlx::query_as::<_, Account>(ACCOUNT_FIND_BY_USER_ID)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(accounts)
     }
@@ -189,32 +242,18 @@ impl AccountRepo {
         id_token: Option<&str>,
     ) -> Result<Account, AppError> {
         let id = Uuid::new_v4();
-        let account = sqlx::query_as::<_, Account>(
-            r#"INSERT INTO accounts (id, user_id, type, provider, provider_account_id, access_token, refresh_token, expires_at, id_token, created_at, updated_at)
-            VALUES ($1, $2, 'oauth', $3, $4, $5, $6, $7, $8, NOW(), NOW())
-            ON CONFLICT (provider, provider_account_id)
-            DO UPDATE SET
-                access_token = EXCLUDED.access_token,
-                refresh_token = COALESCE(EXCLUDED.refresh_token, accounts.refresh_token),
-                expires_at = EXCLUDED.expires_at,
-                id_token = EXCLUDED.id_token,
-                updated_at = NOW()
-            RETURNING
-                id, user_id, type, provider, provider_account_id,
-                refresh_token, access_token, expires_at, token_type, scope,
-                id_token, session_state, created_at, updated_at"#
-        )
-        .bind(id)
-        .bind(user_id)
-        .bind(provider)
-        .bind(provider_account_id)
-        .bind(access_token)
-        .bind(refresh_token)
-        .bind(expires_at)
-        .bind(id_token)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        let account = sqlx::query_as::<_, Account>(ACCOUNT_UPSERT)
+            .bind(id)
+            .bind(user_id)
+            .bind(provider)
+            .bind(provider_account_id)
+            .bind(access_token)
+            .bind(refresh_token)
+            .bind(expires_at)
+            .bind(id_token)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(account)
     }
@@ -223,25 +262,77 @@ impl AccountRepo {
 /// Session repository operations
 pub struct SessionRepo;
 
+pub const SESSION_FIND_BY_TOKEN: &str = r#"
+    SELECT
+        id, user_id, token, expires_at, created_at, last_activity_at,
+        user_agent, ip_address::text, rotated_from
+    FROM sessions
+    WHERE token = $1 AND expires_at > NOW()
+"#;
+
+pub const SESSION_CREATE: &str = r#"
+    INSERT INTO sessions (id, user_id, token, expires_at, user_agent, ip_address, created_at, last_activity_at)
+    VALUES ($1, $2, $3, $4, $5, $6::inet, NOW(), NOW())
+    RETURNING
+        id, user_id, token, expires_at, created_at, last_activity_at,
+        user_agent, ip_address::text, rotated_from
+"#;
+
+pub const SESSION_ROTATE: &str = r#"
+    UPDATE sessions
+    SET token = $2, rotated_from = id, created_at = NOW()
+    WHERE id = $1
+    RETURNING
+        id, user_id, token, expires_at, created_at, last_activity_at,
+        user_agent, ip_address::text, rotated_from
+"#;
+
+pub const SESSION_TOUCH: &str = "UPDATE sessions SET last_activity_at = NOW() WHERE id = $1";
+
+pub const SESSION_DELETE: &str = "DELETE FROM sessions WHERE id = $1";
+
+pub const SESSION_DELETE_BY_TOKEN: &str = "DELETE FROM sessions WHERE token = $1";
+
+pub const SESSION_LIST_FOR_USER: &str = r#"
+    SELECT
+        id, user_id, token, expires_at, created_at,
+        COALESCE(last_activity_at, created_at) AS last_activity_at,
+        user_agent, ip_address::text, rotated_from
+    FROM sessions
+    WHERE user_id = $1 AND expires_at > NOW()
+    ORDER BY last_activity_at DESC NULLS LAST, created_at DESC
+"#;
+
+pub const SESSION_DELETE_FOR_USER: &str = "DELETE FROM sessions WHERE id = $1 AND user_id = $2";
+
+pub const SESSION_DELETE_OTHERS_FOR_USER: &str =
+    "DELETE FROM sessions WHERE user_id = $1 AND id <> $2";
+
+pub const SESSION_DELETE_ALL_FOR_USER: &str = "DELETE FROM sessions WHERE user_id = $1";
+
+pub const SESSION_CLEANUP_EXPIRED: &str = "DELETE FROM sessions WHERE expires_at < NOW()";
+
 impl SessionRepo {
-    /// Find session by token
+    /// Find session by raw token
+    ///
+    /// The token is hashed before lookup and the stored digest is compared in
+    /// constant time, so the raw value never reaches the database.
     pub async fn find_by_token(pool: &PgPool, token: &str) -> Result<Option<Session>, AppError> {
-        let session = sqlx::query_as::<_, Session>(
-            r#"SELECT
-                id, user_id, token, expires_at, created_at, last_activity_at,
-                user_agent, ip_address::text, rotated_from
-            FROM sessions
-            WHERE token = $1 AND expires_at > NOW()"#,
-        )
-        .bind(token)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
-
-        Ok(session)
+        let token_hash = hash_session_token(token);
+
+        let session = sqlx::query_as::<_, Session>(SESSION_FIND_BY_TOKEN)
+            .bind(&token_hash)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(session.filter(|s| verify_token(&s.token, &token_hash)))
     }
 
     /// Create a new session
+    ///
+    /// The returned `Session.token` holds the raw token for the cookie; this is
+    /// the only time it is available, as only its hash is stored.
     pub async fn create(
         pool: &PgPool,
         input: CreateSessionInput,
@@ -251,50 +342,42 @@ impl SessionRepo {
         let token = generate_session_token();
         let expires_at = Utc::now() + Duration::days(ttl_days);
 
-        let session = sqlx::query_as::<_, Session>(
-            r#"INSERT INTO sessions (id, user_id, token, expires_at, user_agent, ip_address, created_at, last_activity_at)
-            VALUES ($1, $2, $3, $4, $5, $6::inet, NOW(), NOW())
-            RETURNING
-                id, user_id, token, expires_at, created_at, last_activity_at,
-                user_agent, ip_address::text, rotated_from"#,
-        )
-        .bind(id)
-        .bind(input.user_id)
-        .bind(&token)
-        .bind(expires_at)
-        .bind(&input.user_agent)
-        .bind(&input.ip_address)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
-
-        Ok(session)
+        let session = sqlx::query_as::<_, Session>(SESSION_CREATE)
+            .bind(id)
+            .bind(input.user_id)
+            .bind(hash_session_token(&token))
+            .bind(expires_at)
+            .bind(&input.user_agent)
+            .bind(&input.ip_address)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(Session { token, ..session })
     }
 
     /// Rotate session token (for session fixation prevention)
+    ///
+    /// Like `create`, the returned `Session.token` is the new raw token.
     pub async fn rotate(pool: &PgPool, session_id: Uuid) -> Result<Session, AppError> {
         let new_token = generate_session_token();
 
-        let session = sqlx::query_as::<_, Session>(
-            r#"UPDATE sessions
-            SET token = $2, rotated_from = id, created_at = NOW()
-            WHERE id = $1
-            RETURNING
-                id, user_id, token, expires_at, created_at, last_activity_at,
-                user_agent, ip_address::text, rotated_from"#,
-        )
-        .bind(session_id)
-        .bind(&new_token)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
-
-        Ok(session)
+        let session = sqlx::query_as::<_, Session>(SESSION_ROTATE)
+            .bind(session_id)
+            .bind(hash_session_token(&new_token))
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(Session {
+            token: new_token,
+            ..session
+        })
     }
 
     /// Update session last activity
     pub async fn touch(pool: &PgPool, session_id: Uuid) -> Result<(), AppError> {
-        sqlx::query("UPDATE sessions SET last_activity_at = NOW() WHERE id = $1")
+        sqlx::query(SESSION_TOUCH)
             .bind(session_id)
             .execute(pool)
             .await
@@ -305,7 +388,7 @@ impl SessionRepo {
 
     /// Delete session (logout)
     pub async fn delete(pool: &PgPool, session_id: Uuid) -> Result<(), AppError> {
-        sqlx::query("DELETE FROM sessions WHERE id = $1")
+        sqlx::query(SESSION_DELETE)
             .bind(session_id)
             .execute(pool)
             .await
@@ -317,8 +400,8 @@ impl SessionRepo {
     /// Delete session by token
     #[allow(dead_code)]
     pub async fn delete_by_token(pool: &PgPool, token: &str) -> Result<(), AppError> {
-        sqlx::query("DELETE FROM sessions WHERE token = $1")
-            .bind(token)
+        sqlx::query(SESSION_DELETE_BY_TOKEN)
+            .bind(hash_session_token(token))
             .execute(pool)
             .await
             .map_err(|e| AppError::Database(e.to_string()))?;
@@ -326,10 +409,53 @@ impl SessionRepo {
         Ok(())
     }
 
+    /// List active sessions for a user, most recently used first
+    pub async fn list_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<Session>, AppError> {
+        let sessions = sqlx::query_as::<_, Session>(SESSION_LIST_FOR_USER)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(sessions)
+    }
+
+    /// Delete one of a user's sessions; returns false if it does not belong to them
+    pub async fn delete_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+        session_id: Uuid,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query(SESSION_DELETE_FOR_USER)
+            .bind(session_id)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete every session for a user except the given one
+    pub async fn delete_others_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+        keep_session_id: Uuid,
+    ) -> Result<u64, AppError> {
+        let result = sqlx::query(SESSION_DELETE_OTHERS_FOR_USER)
+            .bind(user_id)
+            .bind(keep_session_id)
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+
     /// Delete all sessions for a user
     #[allow(dead_code)]
     pub async fn delete_all_for_user(pool: &PgPool, user_id: Uuid) -> Result<u64, AppError> {
-        let result = sqlx::query("DELETE FROM sessions WHERE user_id = $1")
+        let result = sqlx::query(SESSION_DELETE_ALL_FOR_USER)
             .bind(user_id)
             .execute(pool)
             .await
@@ -341,7 +467,7 @@ impl SessionRepo {
     /// Cleanup expired sessions
     #[allow(dead_code)]
     pub async fn cleanup_expired(pool: &PgPool) -> Result<u64, AppError> {
-        let result = sqlx::query("DELETE FROM sessions WHERE expires_at < NOW()")
+        let result = sqlx::query(SESSION_CLEANUP_EXPIRED)
             .execute(pool)
             .await
             .map_err(|e| AppError::Database(e.to_string()))?;
@@ -353,29 +479,31 @@ impl SessionRepo {
 /// Audit log repository
 pub struct AuditLogRepo;
 
+pub const AUDIT_LOG_LOG: &str = r#"
+    INSERT INTO audit_log
+        (user_id, session_id, event_type, resource_type, resource_id,
+         action, status, details, ip_address, user_agent, request_id)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9::inet, $10, $11)
+"#;
+
 impl AuditLogRepo {
     /// Log a security event
     pub async fn log(pool: &PgPool, entry: AuditLogEntry) -> Result<(), AppError> {
-        sqlx::query(
-            r#"INSERT INTO audit_log
-                (user_id, session_id, event_type, resource_type, resource_id,
-                 action, status, details, ip_address, user_agent, request_id)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9::inet, $10, $11)"#,
-        )
-        .bind(entry.user_id)
-        .bind(entry.session_id)
-        .bind(&entry.event_type)
-        .bind(&entry.resource_type)
-        .bind(&entry.resource_id)
-        .bind(&entry.action)
-        .bind(&entry.status)
-        .bind(&entry.details)
-        .bind(&entry.ip_address)
-        .bind(&entry.user_agent)
-        .bind(&entry.request_id)
-        .execute(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        sqlx::query(AUDIT_LOG_LOG)
+            .bind(entry.user_id)
+            .bind(entry.session_id)
+            .bind(&entry.event_type)
+            .bind(&entry.resource_type)
+            .bind(&entry.resource_id)
+            .bind(&entry.action)
+            .bind(&entry.status)
+            .bind(&entry.details)
+            .bind(&entry.ip_address)
+            .bind(&entry.user_agent)
+            .bind(&entry.request_id)
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(())
     }
@@ -384,6 +512,31 @@ impl AuditLogRepo {
 /// RBAC repository for entitlement checks
 pub struct RbacRepo;
 
+pub const RBAC_HAS_ENTITLEMENT: &str = r#"
+    SELECT EXISTS(
+        SELECT 1 FROM user_roles ur
+        JOIN roles r ON ur.role_id = r.id
+        WHERE ur.user_id = $1
+          AND r.name = $2
+          AND (ur.expires_at IS NULL OR ur.expires_at > NOW())
+    )
+"#;
+
+pub const RBAC_GET_ENTITLEMENTS: &str = r#"
+    SELECT DISTINCT r.name
+    FROM user_roles ur
+    JOIN roles r ON ur.role_id = r.id
+    WHERE ur.user_id = $1
+      AND (ur.expires_at IS NULL OR ur.expires_at > NOW())
+"#;
+
+pub const RBAC_ASSIGN_ROLE: &str = r#"
+    INSERT INTO user_roles (user_id, role_id, granted_by, granted_at)
+    SELECT $1, r.id, $3, NOW()
+    FROM roles r WHERE r.name = $2
+    ON CONFLICT (user_id, role_id) DO NOTHING
+"#;
+
 impl RbacRepo {
     /// Check if user has a specific entitlement (now checks role names)
     /// NOTE: Full entitlement system removed (DEC-003). Checks role names instead.
@@ -395,20 +548,12 @@ impl RbacRepo {
     ) -> Result<bool, AppError> {
         // Entitlements/role_entitlements tables were removed per DEC-003
         // Check role names instead
-        let result: (bool,) = sqlx::query_as(
-            r#"SELECT EXISTS(
-                SELECT 1 FROM user_roles ur
-                JOIN roles r ON ur.role_id = r.id
-                WHERE ur.user_id = $1
-                  AND r.name = $2
-                  AND (ur.expires_at IS NULL OR ur.expires_at > NOW())
-            )"#,
-        )
-        .bind(user_id)
-        .bind(entitlement)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        let result: (bool,) = sqlx::query_as(RBAC_HAS_ENTITLEMENT)
+            .bind(user_id)
+            .bind(entitlement)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(result.0)
     }
@@ -418,17 +563,11 @@ impl RbacRepo {
     pub async fn get_entitlements(pool: &PgPool, user_id: Uuid) -> Result<Vec<String>, AppError> {
         // Entitlements/role_entitlements tables were removed per DEC-003
         // Return role names instead for basic RBAC
-        let rows: Vec<(String,)> = sqlx::query_as(
-            r#"SELECT DISTINCT r.name
-            FROM user_roles ur
-            JOIN roles r ON ur.role_id = r.id
-            WHERE ur.user_id = $1
-              AND (ur.expires_at IS NULL OR ur.expires_at > NOW())"#,
-        )
-        .bind(user_id)
-        .fetch_all(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows: Vec<(String,)> = sqlx::query_as(RBAC_GET_ENTITLEMENTS)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(rows.into_iter().map(|(name,)| name).collect())
     }
@@ -440,18 +579,13 @@ impl RbacRepo {
         role_name: &str,
         granted_by: Option<Uuid>,
     ) -> Result<(), AppError> {
-        sqlx::query(
-            r#"INSERT INTO user_roles (user_id, role_id, granted_by, granted_at)
-            SELECT $1, r.id, $3, NOW()
-            FROM roles r WHERE r.name = $2
-            ON CONFLICT (user_id, role_id) DO NOTHING"#,
-        )
-        .bind(user_id)
-        .bind(role_name)
-        .bind(granted_by)
-        .execute(pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        sqlx::query(RBAC_ASSIGN_ROLE)
+            .bind(user_id)
+            .bind(role_name)
+            .bind(granted_by)
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(())
     }
@@ -0,0 +1,92 @@
+//! Revision Models
+//!
+//! Saved versions of infobase entries and ideas.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// What a revision belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevisionEntity {
+    Infobase,
+    Idea,
+}
+
+impl RevisionEntity {
+    /// `entity_type` column value
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RevisionEntity::Infobase => "infobase",
+            RevisionEntity::Idea => "idea",
+        }
+    }
+}
+
+/// One saved version
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct ContentRevision {
+    pub id: Uuid,
+    pub entity_id: Uuid,
+    pub version: i32,
+    pub title: String,
+    pub content: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub author_id: Option<Uuid>,
+    pub content_hash: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Revision list item, without the content
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct RevisionSummary {
+    pub version: i32,
+    pub title: String,
+    pub author_id: Option<Uuid>,
+    pub content_hash: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Fields written to a new revision
+#[derive(Debug, Clone)]
+pub struct NewRevision<'a> {
+    pub version: i32,
+    pub title: &'a str,
+    pub content: Option<&'a str>,
+    pub category: Option<&'a str>,
+    pub tags: &'a [String],
+}
+
+/// Query for diffing two revisions
+#[derive(Debug, Clone, Deserialize)]
+pub struct RevisionDiffQuery {
+    pub from: i32,
+    pub to: i32,
+}
+
+/// Line-based unified diff between two revisions
+#[derive(Debug, Clone, Serialize)]
+pub struct RevisionDiff {
+    pub from: i32,
+    pub to: i32,
+    pub title_changed: bool,
+    /// Unified diff of the content, empty when it is unchanged
+    pub diff: String,
+}
+
+/// Body of a 409 for a write based on an older version
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionConflict<C: Serialize, S: Serialize> {
+    /// Version stored now, with its content
+    pub current: C,
+    /// Version the client edited, with the change it sent
+    pub submitted: SubmittedVersion<S>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubmittedVersion<S: Serialize> {
+    pub version: i32,
+    pub changes: S,
+}
@@ -0,0 +1,388 @@
+//! Revision Repository
+//!
+//! History of infobase entries and ideas. The owning repo appends a revision
+//! in the same transaction as every write (see migration 0019), so the newest
+//! revision always matches the row.
+
+use serde::Serialize;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use super::revision_models::*;
+use crate::error::AppError;
+
+pub struct RevisionRepo;
+
+/// Lines of unchanged context around each hunk
+const DIFF_CONTEXT: usize = 3;
+
+/// Edits beyond which a diff gives up and replaces the whole text
+const MAX_DIFF_EDITS: usize = 2000;
+
+pub const REVISION_INSERT: &str = r#"
+    INSERT INTO content_revisions
+        (user_id, entity_type, entity_id, version, title, content, category, tags,
+         author_id, content_hash)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+"#;
+
+pub const REVISION_LIST: &str = r#"
+    SELECT version, title, author_id, content_hash, created_at
+    FROM content_revisions
+    WHERE user_id = $1 AND entity_type = $2 AND entity_id = $3
+    ORDER BY version DESC
+"#;
+
+pub const REVISION_GET: &str = r#"
+    SELECT id, entity_id, version, title, content, category, tags, author_id,
+           content_hash, created_at
+    FROM content_revisions
+    WHERE user_id = $1 AND entity_type = $2 AND entity_id = $3 AND version = $4
+"#;
+
+pub const REVISION_DELETE_FOR_ENTITY: &str =
+    "DELETE FROM content_revisions WHERE entity_type = $1 AND entity_id = $2";
+
+/// Lowercase hex SHA-256 of revision content; missing content hashes as empty
+pub fn content_hash(content: Option<&str>) -> String {
+    use sha2::{Digest, Sha256};
+    use std::fmt::Write;
+
+    let digest = Sha256::digest(content.unwrap_or_default().as_bytes());
+    let mut hex = String::with_capacity(digest.len() * 2);
+    for byte in digest {
+        let _ = write!(hex, "{:02x}", byte);
+    }
+    hex
+}
+
+/// 409 for a write based on `expected` when `current` is stored
+pub fn version_conflict<C: Serialize, S: Serialize>(
+    current: C,
+    current_version: i32,
+    expected: i32,
+    changes: S,
+) -> AppError {
+    let body = VersionConflict {
+        current,
+        submitted: SubmittedVersion {
+            version: expected,
+            changes,
+        },
+    };
+    AppError::Conflict {
+        message: format!(
+            "Version {} was edited from {}; reload before saving",
+            current_version, expected
+        ),
+        details: serde_json::to_value(body).ok(),
+    }
+}
+
+impl RevisionRepo {
+    /// Append a revision
+    pub async fn record(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        entity: RevisionEntity,
+        entity_id: Uuid,
+        author_id: Uuid,
+        revision: &NewRevision<'_>,
+    ) -> Result<(), AppError> {
+        sqlx::query(REVISION_INSERT)
+            .bind(user_id)
+            .bind(entity.as_str())
+            .bind(entity_id)
+            .bind(revision.version)
+            .bind(revision.title)
+            .bind(revision.content)
+            .bind(revision.category)
+            .bind(revision.tags)
+            .bind(author_id)
+            .bind(content_hash(revision.content))
+            .execute(conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Revisions of an entity, newest first
+    pub async fn list(
+        pool: &PgPool,
+        user_id: Uuid,
+        entity: RevisionEntity,
+        entity_id: Uuid,
+    ) -> Result<Vec<RevisionSummary>, AppError> {
+        let revisions = sqlx::query_as::<_, RevisionSummary>(REVISION_LIST)
+            .bind(user_id)
+            .bind(entity.as_str())
+            .bind(entity_id)
+            .fetch_all(pool)
+            .await?;
+        Ok(revisions)
+    }
+
+    /// A single revision
+    pub async fn get(
+        pool: &PgPool,
+        user_id: Uuid,
+        entity: RevisionEntity,
+        entity_id: Uuid,
+        version: i32,
+    ) -> Result<ContentRevision, AppError> {
+        sqlx::query_as::<_, ContentRevision>(REVISION_GET)
+            .bind(user_id)
+            .bind(entity.as_str())
+            .bind(entity_id)
+            .bind(version)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Revision not found".into()))
+    }
+
+    /// Unified diff of the content of two revisions
+    pub async fn diff(
+        pool: &PgPool,
+        user_id: Uuid,
+        entity: RevisionEntity,
+        entity_id: Uuid,
+        from: i32,
+        to: i32,
+    ) -> Result<RevisionDiff, AppError> {
+        let old = Self::get(pool, user_id, entity, entity_id, from).await?;
+        let new = Self::get(pool, user_id, entity, entity_id, to).await?;
+
+        Ok(RevisionDiff {
+            from,
+            to,
+            title_changed: old.title != new.title,
+            diff: unified_diff(
+                old.content.as_deref().unwrap_or_default(),
+                new.content.as_deref().unwrap_or_default(),
+                &format!("v{}", from),
+                &format!("v{}", to),
+            ),
+        })
+    }
+
+    /// Drop the history of a deleted entity
+    pub async fn delete_for_entity(
+        conn: &mut PgConnection,
+        entity: RevisionEntity,
+        entity_id: Uuid,
+    ) -> Result<(), AppError> {
+        sqlx::query(REVISION_DELETE_FOR_ENTITY)
+            .bind(entity.as_str())
+            .bind(entity_id)
+            .execute(conn)
+            .await?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// DIFF
+// ============================================================================
+
+/// One line of an edit script, by index into the old or new lines
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Edit {
+    Keep(usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+impl Edit {
+    fn is_change(&self) -> bool {
+        !matches!(self, Edit::Keep(_))
+    }
+}
+
+/// Line-based unified diff (`diff -u` style), empty when nothing changed
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let edits = edit_script(&a, &b);
+    if !edits.iter().any(Edit::is_change) {
+        return String::new();
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", old_label, new_label);
+    let changes: Vec<usize> = (0..edits.len()).filter(|&i| edits[i].is_change()).collect();
+
+    let mut group_start = 0;
+    while group_start < changes.len() {
+        // Changes separated by little enough context share a hunk
+        let mut group_end = group_start;
+        while group_end + 1 < changes.len()
+            && changes[group_end + 1] - changes[group_end] <= 2 * DIFF_CONTEXT + 1
+        {
+            group_end += 1;
+        }
+        let start = changes[group_start].saturating_sub(DIFF_CONTEXT);
+        let end = (changes[group_end] + DIFF_CONTEXT + 1).min(edits.len());
+
+        let old_before = edits[..start]
+            .iter()
+            .filter(|e| !matches!(e, Edit::Insert(_)))
+            .count();
+        let new_before = edits[..start]
+            .iter()
+            .filter(|e| !matches!(e, Edit::Delete(_)))
+            .count();
+        let hunk = &edits[start..end];
+        let old_len = hunk
+            .iter()
+            .filter(|e| !matches!(e, Edit::Insert(_)))
+            .count();
+        let new_len = hunk
+            .iter()
+            .filter(|e| !matches!(e, Edit::Delete(_)))
+            .count();
+
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_before + usize::from(old_len > 0),
+            old_len,
+            new_before + usize::from(new_len > 0),
+            new_len
+        ));
+        for edit in hunk {
+            let line = match *edit {
+                Edit::Keep(i) => format!(" {}", a[i]),
+                Edit::Delete(i) => format!("-{}", a[i]),
+                Edit::Insert(j) => format!("+{}", b[j]),
+            };
+            out.push_str(&line);
+            out.push('\n');
+        }
+        group_start = group_end + 1;
+    }
+    out
+}
+
+/// Shortest edit script from `a` to `b` (Myers' algorithm)
+///
+/// Past `MAX_DIFF_EDITS` the texts share too little for a readable diff and
+/// the script deletes every old line and inserts every new one.
+fn edit_script(a: &[&str], b: &[&str]) -> Vec<Edit> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = (a.len() + b.len()).min(MAX_DIFF_EDITS) as isize;
+    let offset = max + 1;
+    let mut v = vec![0isize; 2 * max as usize + 3];
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    for d in 0..=max {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let idx = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+                v[idx + 1]
+            } else {
+                v[idx - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx] = x;
+            if x >= n && y >= m {
+                return backtrack(&trace, n, m, offset);
+            }
+        }
+    }
+
+    (0..a.len())
+        .map(Edit::Delete)
+        .chain((0..b.len()).map(Edit::Insert))
+        .collect()
+}
+
+/// Walk the saved frontiers back from the end to recover the edits
+fn backtrack(trace: &[Vec<isize>], n: isize, m: isize, offset: isize) -> Vec<Edit> {
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let idx = (k + offset) as usize;
+        let prev_k = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = v[(prev_k + offset) as usize];
+        let prev_y = prev_x - prev_k;
+
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            edits.push(Edit::Keep(x as usize));
+        }
+        if d > 0 {
+            if x == prev_x {
+                edits.push(Edit::Insert(prev_y as usize));
+            } else {
+                edits.push(Edit::Delete(prev_x as usize));
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+
+    edits.reverse();
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_texts_have_no_diff() {
+        assert_eq!(unified_diff("a\nb", "a\nb", "v1", "v2"), "");
+        assert_eq!(unified_diff("", "", "v1", "v2"), "");
+    }
+
+    #[test]
+    fn test_changed_line_with_context() {
+        let old = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight";
+        let new = "one\ntwo\nthree\nfour\nFIVE\nsix\nseven\neight\nnine";
+        assert_eq!(
+            unified_diff(old, new, "v1", "v2"),
+            "--- v1\n+++ v2\n@@ -2,7 +2,8 @@\n two\n three\n four\n-five\n+FIVE\n six\n seven\n eight\n+nine\n"
+        );
+    }
+
+    #[test]
+    fn test_distant_changes_get_separate_hunks() {
+        let old: Vec<String> = (1..=20).map(|i| i.to_string()).collect();
+        let mut new = old.clone();
+        new[0] = "first".to_string();
+        new[19] = "last".to_string();
+        let diff = unified_diff(&old.join("\n"), &new.join("\n"), "v1", "v2");
+
+        assert_eq!(diff.matches("@@ -").count(), 2);
+        assert!(diff.contains("@@ -1,4 +1,4 @@\n-1\n+first\n 2\n"));
+        assert!(diff.contains("@@ -17,4 +17,4 @@\n 17\n 18\n 19\n-20\n+last\n"));
+    }
+
+    #[test]
+    fn test_added_to_empty_text() {
+        assert_eq!(
+            unified_diff("", "new\nlines", "v1", "v2"),
+            "--- v1\n+++ v2\n@@ -0,0 +1,2 @@\n+new\n+lines\n"
+        );
+    }
+
+    #[test]
+    fn test_content_hash_is_hex_sha256() {
+        assert_eq!(
+            content_hash(None),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(content_hash(Some("")), content_hash(None));
+        assert_ne!(content_hash(Some("a")), content_hash(None));
+    }
+}
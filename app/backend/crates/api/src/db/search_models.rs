@@ -0,0 +1,50 @@
+//! Search Models
+//!
+//! Ranked full-text search results across a user's content.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Searchable content types, as used in `types=`
+pub const SEARCH_TYPES: [&str; 6] = [
+    "idea",
+    "infobase",
+    "reference",
+    "inbox",
+    "track",
+    "annotation",
+];
+
+/// Most words used from one query
+pub const MAX_SEARCH_TERMS: usize = 16;
+
+/// One search hit
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct SearchResult {
+    /// One of `SEARCH_TYPES`
+    pub entity_type: String,
+    pub id: Uuid,
+    /// Track an annotation belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<Uuid>,
+    pub title: String,
+    /// Body excerpt with matches wrapped in `<mark>`
+    pub snippet: String,
+    pub tags: Vec<String>,
+    pub rank: f32,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// `GET /api/search` query
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    /// Comma-separated `SEARCH_TYPES`; all when omitted
+    pub types: Option<String>,
+    /// Comma-separated tags every result must carry
+    pub tags: Option<String>,
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
@@ -0,0 +1,172 @@
+//! Search Repository
+//!
+//! Full-text search over the `search_vector` columns (kept current by
+//! triggers, see migration 0017). Results from every type are ranked together
+//! and paged by a keyset cursor on `(rank, entity_type, id)`.
+
+use base64::Engine;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::search_models::*;
+use crate::error::AppError;
+
+pub struct SearchRepo;
+
+/// Position after the last result of a page
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchCursor {
+    pub rank: f32,
+    pub entity_type: String,
+    pub id: Uuid,
+}
+
+impl SearchCursor {
+    pub fn encode(&self) -> String {
+        base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .encode(format!("{}:{}:{}", self.rank, self.entity_type, self.id))
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self, AppError> {
+        let invalid = || AppError::BadRequest("Invalid cursor".to_string());
+        let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(cursor)
+            .map_err(|_| invalid())?;
+        let text = String::from_utf8(bytes).map_err(|_| invalid())?;
+        let mut parts = text.splitn(3, ':');
+        let (Some(rank), Some(entity_type), Some(id)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        Ok(Self {
+            rank: rank.parse().map_err(|_| invalid())?,
+            entity_type: entity_type.to_string(),
+            id: id.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// Turn what a user typed into a `to_tsquery` expression
+///
+/// Every word must match and may be the start of a longer one; anything
+/// that is not a letter or digit separates words, so the result is always
+/// valid tsquery syntax. `None` when nothing searchable is left.
+pub fn search_tsquery(q: &str) -> Option<String> {
+    let terms: Vec<String> = q
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .take(MAX_SEARCH_TERMS)
+        .map(|t| format!("{}:*", t.to_lowercase()))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" & "))
+}
+
+/// `$1` user, `$2` tsquery, `$3` types, `$4` required tags, `$5`-`$7` cursor
+/// (rank, type, id; NULL for the first page), `$8` limit
+pub const SEARCH: &str = r#"
+    WITH q AS (SELECT to_tsquery('english', $2) AS query),
+    hits AS (
+        SELECT 'idea' AS entity_type, i.id, NULL::uuid AS parent_id, i.title,
+               i.content AS body, coalesce(i.tags, '{}') AS tags,
+               ts_rank(i.search_vector, q.query) AS rank, i.updated_at
+        FROM ideas i, q
+        WHERE i.user_id = $1 AND 'idea' = ANY($3) AND i.search_vector @@ q.query
+        UNION ALL
+        SELECT 'infobase', e.id, NULL, e.title, e.content, coalesce(e.tags, '{}'),
+               ts_rank(e.search_vector, q.query), e.updated_at
+        FROM infobase_entries e, q
+        WHERE e.user_id = $1 AND 'infobase' = ANY($3) AND e.search_vector @@ q.query
+        UNION ALL
+        SELECT 'reference', r.id, NULL, r.title, r.content, coalesce(r.tags, '{}'),
+               ts_rank(r.search_vector, q.query), r.updated_at
+        FROM user_references r, q
+        WHERE r.user_id = $1 AND 'reference' = ANY($3) AND r.search_vector @@ q.query
+        UNION ALL
+        SELECT 'inbox', n.id, NULL, n.title, concat_ws(' ', n.description, n.body),
+               coalesce(n.tags, '{}'), ts_rank(n.search_vector, q.query), n.updated_at
+        FROM inbox_items n, q
+        WHERE n.user_id = $1 AND 'inbox' = ANY($3) AND n.search_vector @@ q.query
+        UNION ALL
+        SELECT 'track', t.id, NULL, t.title,
+               concat_ws(' ', t.artist, t.album, t.description), coalesce(t.tags, '{}'),
+               ts_rank(t.search_vector, q.query), t.updated_at
+        FROM reference_tracks t, q
+        WHERE t.user_id = $1 AND 'track' = ANY($3) AND t.search_vector @@ q.query
+        UNION ALL
+        SELECT 'annotation', a.id, a.track_id, coalesce(a.title, a.annotation_type), a.content,
+               coalesce(a.tags, '{}'), ts_rank(a.search_vector, q.query), a.updated_at
+        FROM track_annotations a, q
+        WHERE a.user_id = $1 AND 'annotation' = ANY($3) AND a.search_vector @@ q.query
+    ),
+    page AS (
+        SELECT * FROM hits
+        WHERE tags @> $4::text[]
+          AND ($5::real IS NULL OR (rank, entity_type, id) < ($5::real, $6::text, $7::uuid))
+        ORDER BY rank DESC, entity_type DESC, id DESC
+        LIMIT $8
+    )
+    SELECT page.entity_type, page.id, page.parent_id, page.title,
+           ts_headline('english', coalesce(page.body, ''), q.query,
+                       'StartSel=<mark>, StopSel=</mark>, MaxWords=30, MinWords=10, MaxFragments=2')
+               AS snippet,
+           page.tags, page.rank, page.updated_at
+    FROM page, q
+    ORDER BY page.rank DESC, page.entity_type DESC, page.id DESC
+"#;
+
+impl SearchRepo {
+    /// One page of results, best first; fetches one extra row when there is
+    /// a next page
+    pub async fn search(
+        pool: &PgPool,
+        user_id: Uuid,
+        tsquery: &str,
+        types: &[String],
+        tags: &[String],
+        after: Option<&SearchCursor>,
+        limit: i64,
+    ) -> Result<Vec<SearchResult>, AppError> {
+        let results = sqlx::query_as::<_, SearchResult>(SEARCH)
+            .bind(user_id)
+            .bind(tsquery)
+            .bind(types)
+            .bind(tags)
+            .bind(after.map(|c| c.rank))
+            .bind(after.map(|c| c.entity_type.as_str()))
+            .bind(after.map(|c| c.id))
+            .bind(limit)
+            .fetch_all(pool)
+            .await?;
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tsquery_prefixes_every_word() {
+        assert_eq!(
+            search_tsquery("Mixing low-end").as_deref(),
+            Some("mixing:* & low:* & end:*")
+        );
+        // Operators and quotes cannot reach to_tsquery
+        assert_eq!(
+            search_tsquery("a & !b | 'c':*").as_deref(),
+            Some("a:* & b:* & c:*")
+        );
+        assert_eq!(search_tsquery(" -- "), None);
+    }
+
+    #[test]
+    fn test_cursor_round_trips() {
+        let cursor = SearchCursor {
+            rank: 0.0607927,
+            entity_type: "infobase".to_string(),
+            id: Uuid::new_v4(),
+        };
+        assert_eq!(SearchCursor::decode(&cursor.encode()).unwrap(), cursor);
+        assert!(SearchCursor::decode("not a cursor").is_err());
+    }
+}
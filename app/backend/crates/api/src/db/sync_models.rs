@@ -0,0 +1,37 @@
+//! Sync Event Models
+//!
+//! Change feed rows written by database triggers and pushed over `/api/sync/stream`.
+
+use serde::Deserialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Postgres NOTIFY channel carrying new sync events
+pub const SYNC_EVENTS_CHANNEL: &str = "sync_events";
+
+/// Focus session started, paused, resumed or ended
+pub const SYNC_KIND_FOCUS: &str = "focus";
+/// XP, level, coins or streak changed
+pub const SYNC_KIND_PROGRESS: &str = "progress";
+/// Inbox item created, read, archived or deleted
+pub const SYNC_KIND_INBOX: &str = "inbox";
+/// Quest or habit counts changed
+pub const SYNC_KIND_BADGES: &str = "badges";
+/// Daily plan created or progressed
+pub const SYNC_KIND_PLAN: &str = "plan";
+
+/// How long events are kept for resuming streams
+pub const SYNC_EVENT_RETENTION_HOURS: i32 = 24;
+
+/// One change to a user's synced state
+///
+/// Also the JSON payload of notifications on [`SYNC_EVENTS_CHANNEL`].
+#[derive(Debug, Clone, FromRow, Deserialize)]
+pub struct SyncEvent {
+    /// Monotonic id, used as the stream's resume cursor
+    pub id: i64,
+    pub user_id: Uuid,
+    pub kind: String,
+    /// The new row for inbox inserts
+    pub ref_id: Option<Uuid>,
+}
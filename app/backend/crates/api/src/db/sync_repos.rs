@@ -0,0 +1,88 @@
+//! Sync Event Repository
+//!
+//! Reads the change feed for resuming streams and the poll ETag.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::sync_models::{SyncEvent, SYNC_EVENT_RETENTION_HOURS};
+use crate::error::AppError;
+
+pub struct SyncEventRepo;
+
+// Also events at or before the cursor created within the grace window, which
+// may have committed after the cursor was issued
+pub const SYNC_EVENT_LIST_SINCE: &str = r#"
+    SELECT id, user_id, kind, ref_id
+    FROM sync_events
+    WHERE user_id = $1
+      AND (id > $2 OR created_at > NOW() - make_interval(secs => $3))
+    ORDER BY id
+    LIMIT $4
+"#;
+
+pub const SYNC_EVENT_LATEST_ID: &str =
+    "SELECT COALESCE(MAX(id), 0) FROM sync_events WHERE user_id = $1";
+
+// A cursor can be resumed when nothing after it has been pruned and it was
+// issued by this database (not ahead of the sequence)
+pub const SYNC_EVENT_CURSOR_IS_RESUMABLE: &str = r#"
+    SELECT $1 >= COALESCE((SELECT MIN(id) FROM sync_events), s.last_value + 1) - 1
+       AND $1 <= s.last_value
+    FROM sync_events_id_seq s
+"#;
+
+pub const SYNC_EVENT_PRUNE: &str =
+    "DELETE FROM sync_events WHERE created_at < NOW() - make_interval(hours => $1)";
+
+impl SyncEventRepo {
+    /// Events for a user after `cursor`, oldest first
+    ///
+    /// An event id is taken when its transaction inserts it, so one below the
+    /// cursor may only have committed after the cursor was handed out. Events
+    /// created within the last `grace_secs` are returned again; callers skip
+    /// the ones they already sent.
+    pub async fn list_since(
+        pool: &PgPool,
+        user_id: Uuid,
+        cursor: i64,
+        grace_secs: f64,
+        limit: i64,
+    ) -> Result<Vec<SyncEvent>, AppError> {
+        let events = sqlx::query_as::<_, SyncEvent>(SYNC_EVENT_LIST_SINCE)
+            .bind(user_id)
+            .bind(cursor)
+            .bind(grace_secs)
+            .bind(limit)
+            .fetch_all(pool)
+            .await?;
+        Ok(events)
+    }
+
+    /// Latest event id for a user (0 when there are none)
+    pub async fn latest_id(pool: &PgPool, user_id: Uuid) -> Result<i64, AppError> {
+        let id = sqlx::query_scalar::<_, i64>(SYNC_EVENT_LATEST_ID)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
+        Ok(id)
+    }
+
+    /// Whether every event after `cursor` is still stored
+    pub async fn cursor_is_resumable(pool: &PgPool, cursor: i64) -> Result<bool, AppError> {
+        let resumable = sqlx::query_scalar::<_, bool>(SYNC_EVENT_CURSOR_IS_RESUMABLE)
+            .bind(cursor)
+            .fetch_one(pool)
+            .await?;
+        Ok(resumable)
+    }
+
+    /// Delete events past the retention window
+    pub async fn prune(pool: &PgPool) -> Result<u64, AppError> {
+        let result = sqlx::query(SYNC_EVENT_PRUNE)
+            .bind(SYNC_EVENT_RETENTION_HOURS)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -41,6 +41,16 @@ pub enum AppError {
     #[error("Session expired")]
     SessionExpired,
 
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+
+    /// Write based on a stale version; `details` describes both versions
+    #[error("Conflict: {message}")]
+    Conflict {
+        message: String,
+        details: Option<serde_json::Value>,
+    },
+
     #[error("Database error: {0}")]
     Database(String),
 
@@ -91,10 +101,16 @@ pub struct ErrorResponse {
     pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        let details = match &self {
+            AppError::Conflict { details, .. } => details.clone(),
+            _ => None,
+        };
         let (status, error_type, message) = match &self {
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, "not_found", msg.clone()),
             AppError::Unauthorized => (
@@ -128,6 +144,12 @@ impl IntoResponse for AppError {
                 "session_expired",
                 "Session has expired".to_string(),
             ),
+            AppError::RateLimited(msg) => {
+                (StatusCode::TOO_MANY_REQUESTS, "rate_limited", msg.clone())
+            }
+            AppError::Conflict { message, .. } => {
+                (StatusCode::CONFLICT, "conflict", message.clone())
+            }
             AppError::Database(e) => {
                 tracing::error!(
                     error.type = "database",
@@ -205,6 +227,7 @@ impl IntoResponse for AppError {
             error: error_type.to_string(),
             message,
             code: None,
+            details,
         };
 
         (status, Json(body)).into_response()
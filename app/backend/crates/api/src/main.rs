@@ -5,8 +5,9 @@
 
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
-use axum::Router;
+use axum::{Extension, Router};
 use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
 use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -25,6 +26,10 @@ mod storage;
 mod tests;
 
 use config::AppConfig;
+use db::archive_repos::ArchiveJobRepo;
+use services::account_deletion::AccountPurger;
+use services::inbox_expiry::InboxExpirer;
+use services::notifications::NotificationScheduler;
 use state::AppState;
 
 #[tokio::main]
@@ -45,8 +50,35 @@ async fn main() -> anyhow::Result<()> {
     let config = AppConfig::load()?;
     tracing::info!("Configuration loaded");
 
+    // `ignition-api check-queries`: verify the query catalog and exit
+    if std::env::args().nth(1).as_deref() == Some("check-queries") {
+        return check_queries(&config).await;
+    }
+
     // Create application state
     let state = AppState::new(&config).await?;
+    state.sync.start(state.db.clone());
+    state.console.start();
+    if let Err(e) = state.flags.refresh(&state.db).await {
+        tracing::warn!("Failed to load feature flags: {}", e);
+    }
+    state.flags.start(state.db.clone());
+    state.rate_limits.start(state.db.clone());
+    InboxExpirer::start(state.db.clone());
+    let interrupted = ArchiveJobRepo::fail_expired(&state.db, None).await?;
+    if interrupted > 0 {
+        tracing::warn!("Marked {} abandoned archive jobs as failed", interrupted);
+    }
+    if config.notifications.scheduler_enabled {
+        NotificationScheduler::from_config(&config)?.start(state.db.clone());
+    }
+    if config.accounts.purger_enabled {
+        AccountPurger::new(
+            state.storage.clone(),
+            Duration::from_secs(config.accounts.purge_interval_seconds),
+        )
+        .start(state.db.clone());
+    }
     let state = Arc::new(state);
 
     // Build the router
@@ -65,6 +97,30 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Prepare every catalogued query against the configured database
+///
+/// Startup runs pending migrations first, so this checks the same schema the
+/// server would run against.
+async fn check_queries(config: &AppConfig) -> anyhow::Result<()> {
+    let state = AppState::new(config).await?;
+    let total = db::catalog::queries().len();
+    let failures = db::catalog::check(&state.db).await?;
+
+    for failure in &failures {
+        eprintln!("FAIL {}: {}", failure.name, failure.error);
+    }
+    if !failures.is_empty() {
+        anyhow::bail!(
+            "{} of {} catalogued queries failed to prepare",
+            failures.len(),
+            total
+        );
+    }
+
+    println!("All {} catalogued queries prepared", total);
+    Ok(())
+}
+
 fn build_router(state: Arc<AppState>) -> Router {
     // Create base router with state type
     let app: Router<Arc<AppState>> = Router::new()
@@ -73,17 +129,31 @@ fn build_router(state: Arc<AppState>) -> Router {
         // Auth routes (needs session extraction for /session endpoint, but no CSRF)
         .nest(
             "/auth",
-            routes::auth::router().layer(axum::middleware::from_fn_with_state(
-                state.clone(),
-                middleware::auth::extract_session,
-            )),
+            routes::auth::router()
+                .layer(axum::middleware::from_fn(middleware::auth::session_only))
+                .layer(axum::middleware::from_fn_with_state(
+                    state.clone(),
+                    middleware::rate_limit::rate_limit,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    state.clone(),
+                    middleware::auth::extract_session,
+                )),
         )
-        // API routes (requires auth + CSRF)
+        // API routes (requires auth + CSRF; API tokens are scope-checked and CSRF-exempt)
         .nest(
             "/api",
             routes::api::router()
                 .layer(axum::middleware::from_fn(middleware::auth::require_auth))
+                .layer(axum::middleware::from_fn_with_state(
+                    state.clone(),
+                    middleware::auth::require_token_scope,
+                ))
                 .layer(axum::middleware::from_fn(middleware::csrf::csrf_check))
+                .layer(axum::middleware::from_fn_with_state(
+                    state.clone(),
+                    middleware::rate_limit::rate_limit,
+                ))
                 .layer(axum::middleware::from_fn_with_state(
                     state.clone(),
                     middleware::auth::extract_session,
@@ -95,6 +165,10 @@ fn build_router(state: Arc<AppState>) -> Router {
             routes::reference::router()
                 .layer(axum::middleware::from_fn(middleware::auth::require_auth))
                 .layer(axum::middleware::from_fn(middleware::csrf::csrf_check))
+                .layer(axum::middleware::from_fn_with_state(
+                    state.clone(),
+                    middleware::rate_limit::rate_limit,
+                ))
                 .layer(axum::middleware::from_fn_with_state(
                     state.clone(),
                     middleware::auth::extract_session,
@@ -104,6 +178,9 @@ fn build_router(state: Arc<AppState>) -> Router {
         .nest(
             "/frames",
             routes::frames::router()
+                .route_layer(axum::middleware::from_fn(middleware::flags::require_flag(
+                    db::feature_flag_models::FLAG_MUSIC_ANALYSIS,
+                )))
                 .layer(axum::middleware::from_fn(middleware::auth::require_auth))
                 .layer(axum::middleware::from_fn_with_state(
                     state.clone(),
@@ -116,6 +193,10 @@ fn build_router(state: Arc<AppState>) -> Router {
             routes::blobs::router()
                 .layer(axum::middleware::from_fn(middleware::auth::require_auth))
                 .layer(axum::middleware::from_fn(middleware::csrf::csrf_check))
+                .layer(axum::middleware::from_fn_with_state(
+                    state.clone(),
+                    middleware::rate_limit::rate_limit,
+                ))
                 .layer(axum::middleware::from_fn_with_state(
                     state.clone(),
                     middleware::auth::extract_session,
@@ -126,6 +207,10 @@ fn build_router(state: Arc<AppState>) -> Router {
             "/admin-access",
             routes::admin::claiming_router()
                 .layer(axum::middleware::from_fn(middleware::auth::require_auth))
+                .layer(axum::middleware::from_fn_with_state(
+                    state.clone(),
+                    middleware::rate_limit::rate_limit,
+                ))
                 .layer(axum::middleware::from_fn_with_state(
                     state.clone(),
                     middleware::auth::extract_session,
@@ -145,6 +230,8 @@ fn build_router(state: Arc<AppState>) -> Router {
 
     // Apply state and middleware
     app.with_state(state.clone())
+        // Feature flags for `middleware::flags::require_flag`
+        .layer(Extension(state.flags.clone()))
         .layer(middleware::cors::cors_layer(&state.config))
         .layer(TraceLayer::new_for_http())
         .layer(PropagateRequestIdLayer::x_request_id())
@@ -1,6 +1,7 @@
 //! Authentication middleware
 //!
-//! Extracts and validates session from cookies.
+//! Extracts and validates session from cookies, or a personal API token from
+//! an `Authorization: Bearer` header.
 //! Per DEC-001=A: Force re-auth at cutover, no session migration.
 //! Per DEC-004=B: DB-backed roles for admin authorization.
 
@@ -8,15 +9,18 @@ use std::sync::Arc;
 
 use axum::{
     extract::{Request, State},
-    http::header,
+    http::{header, HeaderMap},
     middleware::Next,
     response::Response,
 };
 use uuid::Uuid;
 
+use crate::db::api_token_repos::{ApiTokenRepo, API_TOKEN_PREFIX};
 use crate::db::repos::{RbacRepo, SessionRepo, UserRepo};
 use crate::error::AppError;
 use crate::services::DevBypassAuth;
+use crate::shared::audit::{write_audit, AuditEventType};
+use crate::shared::auth::scopes;
 use crate::state::AppState;
 
 /// Extracted authentication context
@@ -37,14 +41,24 @@ pub struct AuthContext {
     pub entitlements: Vec<String>,
     /// Whether this is a dev bypass session
     pub is_dev_bypass: bool,
+    /// API token used for this request, if not a browser session
+    pub api_token_id: Option<Uuid>,
 }
 
 impl AuthContext {
-    /// Check if user has admin role
+    /// Check if user has admin role (never true for API tokens)
     pub fn is_admin(&self) -> bool {
+        if self.api_token_id.is_some() {
+            return false;
+        }
         self.role == "admin" || self.entitlements.contains(&"admin:access".to_string())
     }
 
+    /// Whether this request was authenticated with an API token
+    pub fn is_api_token(&self) -> bool {
+        self.api_token_id.is_some()
+    }
+
     /// Check if user has a specific entitlement
     #[allow(dead_code)]
     pub fn has_entitlement(&self, entitlement: &str) -> bool {
@@ -55,6 +69,10 @@ impl AuthContext {
 /// Session cookie name
 pub const SESSION_COOKIE_NAME: &str = "session";
 
+/// Marker set once an API token's scopes have been checked for the request
+#[derive(Debug, Clone, Copy)]
+pub struct TokenScopeChecked;
+
 /// Extract session from request and validate
 pub async fn extract_session(
     State(state): State<Arc<AppState>>,
@@ -83,27 +101,37 @@ pub async fn extract_session(
                 "admin:backup".to_string(),
             ],
             is_dev_bypass: true,
+            api_token_id: None,
         };
         req.extensions_mut().insert(auth_context);
         return Ok(next.run(req).await);
     }
 
+    // API tokens take precedence over cookies
+    if let Some(token) = extract_bearer_token(req.headers()) {
+        let ip = client_ip(req.headers());
+        if let Some(auth_context) = authenticate_api_token(&state, &token, ip).await? {
+            req.extensions_mut().insert(auth_context);
+        }
+        return Ok(next.run(req).await);
+    }
+
     // Extract session token from cookies
     let session_token = extract_session_token(&req);
 
     if let Some(token) = session_token {
         tracing::debug!(token_preview = %&token[..token.len().min(10)], "Looking up session in database");
-        
+
         // Look up session in database
         match SessionRepo::find_by_token(&state.db, &token).await {
             Ok(Some(session)) => {
                 tracing::debug!(session_id = %session.id, user_id = %session.user_id, "Session found in database");
-                
+
                 // Get user
                 match UserRepo::find_by_id(&state.db, session.user_id).await {
                     Ok(Some(user)) => {
                         tracing::debug!(user_id = %user.id, email = %user.email, "User found");
-                        
+
                         // Get entitlements from RBAC
                         let entitlements = RbacRepo::get_entitlements(&state.db, user.id).await?;
 
@@ -115,6 +143,7 @@ pub async fn extract_session(
                             session_id: session.id,
                             entitlements,
                             is_dev_bypass: false,
+                            api_token_id: None,
                         };
 
                         // Update last activity (fire and forget)
@@ -150,11 +179,109 @@ pub async fn extract_session(
     Ok(next.run(req).await)
 }
 
+/// Resolve an API token into an auth context
+///
+/// The context carries only the token's scopes as entitlements, never the
+/// owner's role entitlements.
+async fn authenticate_api_token(
+    state: &AppState,
+    token: &str,
+    ip: Option<String>,
+) -> Result<Option<AuthContext>, AppError> {
+    let Some(api_token) = ApiTokenRepo::find_active(&state.db, token).await? else {
+        tracing::debug!("API token not found, revoked or expired");
+        return Ok(None);
+    };
+
+    let Some(user) = UserRepo::find_by_id(&state.db, api_token.user_id).await? else {
+        tracing::warn!(token_id = %api_token.id, "User not found for API token");
+        return Ok(None);
+    };
+
+    // Last-used tracking (fire and forget)
+    let db = state.db.clone();
+    let token_id = api_token.id;
+    tokio::spawn(async move {
+        let _ = ApiTokenRepo::touch(&db, token_id, ip.as_deref()).await;
+    });
+
+    Ok(Some(AuthContext {
+        user_id: user.id,
+        email: user.email,
+        name: user.name,
+        role: user.role,
+        session_id: Uuid::nil(),
+        entitlements: api_token.scopes,
+        is_dev_bypass: false,
+        api_token_id: Some(api_token.id),
+    }))
+}
+
+/// Require API token requests to carry a matching scope
+///
+/// Session requests pass through untouched. Token requests outside the scope
+/// catalog (e.g. account management) are rejected.
+pub async fn require_token_scope(
+    State(state): State<Arc<AppState>>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let Some(auth) = req.extensions().get::<AuthContext>().cloned() else {
+        return Ok(next.run(req).await);
+    };
+    let Some(token_id) = auth.api_token_id else {
+        return Ok(next.run(req).await);
+    };
+
+    let allowed = scopes::policy_for_request(req.uri().path(), req.method())
+        .is_some_and(|policy| policy.check(&auth));
+    if !allowed {
+        tracing::debug!(token_id = %token_id, path = %req.uri().path(), "API token scope denied");
+        return Err(AppError::Forbidden);
+    }
+
+    if !req.method().is_safe() {
+        write_audit(
+            state.db.clone(),
+            AuditEventType::ApiTokenUsed,
+            Some(auth.user_id),
+            &format!("{} {}", req.method(), req.uri().path()),
+            Some("api_token"),
+            Some(token_id),
+        );
+    }
+
+    req.extensions_mut().insert(TokenScopeChecked);
+    Ok(next.run(req).await)
+}
+
+/// Treat API token requests as anonymous
+///
+/// Used on /auth, where everything is about the browser session.
+pub async fn session_only(mut req: Request, next: Next) -> Response {
+    if req
+        .extensions()
+        .get::<AuthContext>()
+        .is_some_and(|auth| auth.is_api_token())
+    {
+        req.extensions_mut().remove::<AuthContext>();
+    }
+    next.run(req).await
+}
+
 /// Require authenticated user
+///
+/// API tokens are only accepted where `require_token_scope` has run.
 pub async fn require_auth(req: Request, next: Next) -> Result<Response, AppError> {
     // Check if AuthContext is present in extensions
-    if req.extensions().get::<AuthContext>().is_none() {
-        return Err(AppError::Unauthorized);
+    match req.extensions().get::<AuthContext>() {
+        None => return Err(AppError::Unauthorized),
+        Some(auth) if auth.is_api_token() => {
+            if req.extensions().get::<TokenScopeChecked>().is_none() {
+                return Err(AppError::Forbidden);
+            }
+        }
+        Some(_) => {}
     }
 
     Ok(next.run(req).await)
@@ -193,10 +320,42 @@ pub fn require_entitlement(
     }
 }
 
+/// Extract a personal API token from the Authorization header
+fn extract_bearer_token(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    let token = token.trim();
+    if scheme.eq_ignore_ascii_case("bearer") && token.starts_with(API_TOKEN_PREFIX) {
+        Some(token.to_string())
+    } else {
+        None
+    }
+}
+
+/// Client IP as reported by the reverse proxy
+///
+/// Fly's proxy sets `Fly-Client-IP`, replacing any value the client sent.
+/// Otherwise the last `X-Forwarded-For` hop is used: it is the one our proxy
+/// appended, while earlier hops are whatever the client claimed.
+pub fn client_ip(headers: &HeaderMap) -> Option<String> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+
+    header("fly-client-ip")
+        .or_else(|| header("x-forwarded-for")?.rsplit(',').next())
+        .map(|ip| ip.trim().to_string())
+        .filter(|ip| !ip.is_empty())
+}
+
 /// Extract session token from cookie header
 fn extract_session_token(req: &Request) -> Option<String> {
     let cookie_header = req.headers().get(header::COOKIE);
-    
+
     // Debug log to trace cookie issues
     match cookie_header {
         Some(h) => {
@@ -207,28 +366,24 @@ fn extract_session_token(req: &Request) -> Option<String> {
             tracing::debug!("No cookie header in request");
         }
     }
-    
-    let token = cookie_header?
-        .to_str()
-        .ok()?
-        .split(';')
-        .find_map(|cookie| {
-            let cookie = cookie.trim();
-            if cookie.starts_with(SESSION_COOKIE_NAME) {
-                cookie
-                    .strip_prefix(&format!("{}=", SESSION_COOKIE_NAME))
-                    .map(|s| s.to_string())
-            } else {
-                None
-            }
-        });
-    
+
+    let token = cookie_header?.to_str().ok()?.split(';').find_map(|cookie| {
+        let cookie = cookie.trim();
+        if cookie.starts_with(SESSION_COOKIE_NAME) {
+            cookie
+                .strip_prefix(&format!("{}=", SESSION_COOKIE_NAME))
+                .map(|s| s.to_string())
+        } else {
+            None
+        }
+    });
+
     if token.is_some() {
         tracing::debug!("Session token extracted from cookie");
     } else {
         tracing::debug!("No session token found in cookies");
     }
-    
+
     token
 }
 
@@ -301,6 +456,18 @@ mod tests {
         std::env::remove_var("AUTH_DEV_BYPASS");
     }
 
+    #[test]
+    fn test_client_ip_ignores_client_supplied_hops() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "1.1.1.1, 203.0.113.7".parse().unwrap());
+        assert_eq!(client_ip(&headers).as_deref(), Some("203.0.113.7"));
+
+        headers.insert("fly-client-ip", "198.51.100.2".parse().unwrap());
+        assert_eq!(client_ip(&headers).as_deref(), Some("198.51.100.2"));
+
+        assert_eq!(client_ip(&HeaderMap::new()), None);
+    }
+
     #[test]
     fn test_auth_context_is_admin() {
         let ctx = AuthContext {
@@ -311,6 +478,7 @@ mod tests {
             session_id: Uuid::new_v4(),
             entitlements: vec![],
             is_dev_bypass: false,
+            api_token_id: None,
         };
         assert!(ctx.is_admin());
 
@@ -322,6 +490,7 @@ mod tests {
             session_id: Uuid::new_v4(),
             entitlements: vec!["admin:access".to_string()],
             is_dev_bypass: false,
+            api_token_id: None,
         };
         assert!(ctx2.is_admin());
 
@@ -333,6 +502,7 @@ mod tests {
             session_id: Uuid::new_v4(),
             entitlements: vec![],
             is_dev_bypass: false,
+            api_token_id: None,
         };
         assert!(!ctx3.is_admin());
     }
@@ -7,10 +7,12 @@
 //! - If Origin missing, fall back to Referer (same allowlist)
 //! - If neither exists or matches, reject with 403
 //! - For GET/HEAD/OPTIONS: No CSRF check required
+//! - Requests authenticated with an API token (no cookies) are exempt
 
 use axum::{extract::Request, http::Method, middleware::Next, response::Response};
 
 use crate::error::AppError;
+use crate::middleware::auth::AuthContext;
 
 /// Production allowed origins
 const PRODUCTION_ORIGINS: &[&str] = &[
@@ -35,6 +37,15 @@ pub async fn csrf_check(req: Request, next: Next) -> Result<Response, AppError>
         return Ok(next.run(req).await);
     }
 
+    // Bearer tokens are not sent automatically by browsers, so can't be forged cross-site
+    if req
+        .extensions()
+        .get::<AuthContext>()
+        .is_some_and(|auth| auth.is_api_token())
+    {
+        return Ok(next.run(req).await);
+    }
+
     // Determine environment from env var
     let is_production = std::env::var("NODE_ENV")
         .map(|v| v == "production")
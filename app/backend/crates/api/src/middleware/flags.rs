@@ -0,0 +1,37 @@
+//! Feature flag gate
+//!
+//! Hides routes whose flag is off for the caller. `main` puts the shared
+//! `FeatureFlags` into every request's extensions; routers gate themselves
+//! with `route_layer(from_fn(require_flag("name")))`.
+
+use axum::{extract::Request, middleware::Next, response::Response};
+
+use crate::error::AppError;
+use crate::middleware::auth::AuthContext;
+use crate::services::feature_flags::{FeatureFlags, FlagSubject};
+
+/// Respond 404 unless `flag` is on for the caller
+pub fn require_flag(
+    flag: &'static str,
+) -> impl Fn(
+    Request,
+    Next,
+)
+    -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Response, AppError>> + Send>>
+       + Clone
+       + Send
+       + 'static {
+    move |req: Request, next: Next| {
+        Box::pin(async move {
+            let enabled = req.extensions().get::<FeatureFlags>().is_some_and(|flags| {
+                let subject = req.extensions().get::<AuthContext>().map(FlagSubject::from);
+                flags.is_enabled(flag, subject)
+            });
+            if enabled {
+                Ok(next.run(req).await)
+            } else {
+                Err(AppError::NotFound("Not found".to_string()))
+            }
+        })
+    }
+}
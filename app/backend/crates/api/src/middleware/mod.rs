@@ -3,3 +3,5 @@
 pub mod auth;
 pub mod cors;
 pub mod csrf;
+pub mod flags;
+pub mod rate_limit;
@@ -0,0 +1,73 @@
+//! Rate limit middleware
+//!
+//! Applies `AppConfig::rate_limits` inside each router's session extraction,
+//! so user and session policies see the caller. Limited responses carry the
+//! `RateLimit-*` headers; refusals are 429 with `Retry-After`.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{OriginalUri, Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::error::AppError;
+use crate::middleware::auth::{client_ip, AuthContext};
+use crate::services::rate_limit::{Decision, RateLimitSubject};
+use crate::state::AppState;
+
+/// Check the request against its rate limit policy
+pub async fn rate_limit(State(state): State<Arc<AppState>>, req: Request, next: Next) -> Response {
+    // Nested routers see a stripped path; policies name full ones
+    let path = req
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|uri| uri.path().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+    let auth = req.extensions().get::<AuthContext>();
+    let subject = RateLimitSubject {
+        user_id: auth.map(|a| a.user_id),
+        session_id: auth.map(|a| a.session_id),
+        ip: client_ip(req.headers()),
+    };
+
+    let Some(decision) = state
+        .rate_limits
+        .check(&state.db, req.method().as_str(), &path, &subject)
+        .await
+    else {
+        return next.run(req).await;
+    };
+
+    let mut response = if decision.allowed {
+        next.run(req).await
+    } else {
+        AppError::RateLimited(format!(
+            "Too many requests; try again in {} seconds",
+            decision.retry_after.unwrap_or(1)
+        ))
+        .into_response()
+    };
+    set_headers(response.headers_mut(), &decision);
+    response
+}
+
+fn set_headers(headers: &mut HeaderMap, decision: &Decision) {
+    let mut set = |name: &'static str, value: String| {
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            headers.insert(HeaderName::from_static(name), value);
+        }
+    };
+    set("ratelimit-limit", decision.limit.to_string());
+    set("ratelimit-remaining", decision.remaining.to_string());
+    set("ratelimit-reset", decision.reset.to_string());
+    set(
+        "ratelimit-policy",
+        format!("{};w={}", decision.limit, decision.period_seconds),
+    );
+    if let Some(retry_after) = decision.retry_after {
+        set("retry-after", retry_after.to_string());
+    }
+}
@@ -18,13 +18,16 @@ use chrono::{DateTime, Utc};
 use serde::Serialize;
 use uuid::Uuid;
 
+use crate::db::account_deletion_models::AccountPurgeReport;
 use crate::db::admin_models::*;
 use crate::db::admin_repos::*;
 use crate::error::AppError;
 use crate::middleware::auth::{create_session_cookie, AuthContext};
 use crate::services::AuthService;
-use crate::shared::audit::{write_audit, AuditEventType};
+use crate::services::{account_deletion, backups};
+use crate::shared::audit::{write_audit, write_audit_failure, AuditEventType};
 use crate::state::AppState;
+use crate::storage::StorageClient;
 
 /// Generate and log a random claim key for admin bootstrap
 fn generate_claim_key() -> String {
@@ -79,8 +82,13 @@ pub fn router() -> Router<Arc<AppState>> {
         .nest("/sessions", sessions_routes())
         // Listening prompt templates (admin-curated)
         .nest("/templates", super::admin_templates::router())
+        // Feature flags
+        .nest("/flags", super::admin_flags::router())
+        // Onboarding flows
+        .nest("/onboarding", super::admin_onboarding::router())
         // Backup/restore
-        .route("/backup", get(get_backup).post(create_backup))
+        .route("/backup", get(list_backups).post(create_backup))
+        .route("/backup/prune", post(prune_backups))
         .route("/restore", post(restore_backup))
         // Database health
         .route("/db-health", get(db_health))
@@ -290,7 +298,8 @@ fn db_routes() -> Router<Arc<AppState>> {
         .route("/health", get(db_health))
         .route("/tables", get(list_tables))
         .route("/tables/{table}", get(get_table_data))
-        .route("/query", post(run_query))
+        // Read-only SQL console
+        .merge(super::admin_console::router())
 }
 
 // Session management routes
@@ -351,25 +360,32 @@ async fn delete_user(
     Ok(Json(result))
 }
 
-/// Cleanup a user's data (same as delete for now)
+/// Purge a user's stored objects and rows now, skipping any grace period,
+/// and report what was removed
 async fn cleanup_user(
     State(state): State<Arc<AppState>>,
     Extension(auth): Extension<AuthContext>,
     Path(id): Path<Uuid>,
-) -> Result<Json<DeleteUserResponse>, AppError> {
-    let result = AdminUserRepo::delete_user(&state.db, id).await?;
+) -> Result<Json<AccountPurgeReport>, AppError> {
+    let report =
+        account_deletion::purge_now(&state.db, state.storage.as_ref(), id, auth.user_id).await?;
 
     // Audit log: admin user cleanup
     write_audit(
         state.db.clone(),
         AuditEventType::AdminAction,
         Some(auth.user_id),
-        &format!("Admin cleaned up user data for {}", id),
+        &format!(
+            "Admin purged user {} ({} objects, {} tables)",
+            id,
+            report.objects_removed,
+            report.rows_removed.len()
+        ),
         Some("user"),
         Some(id),
     );
 
-    Ok(Json(result))
+    Ok(Json(report))
 }
 
 // ============================================
@@ -542,27 +558,144 @@ async fn db_health(State(state): State<Arc<AppState>>) -> Result<Json<DbHealthRe
     Ok(Json(health))
 }
 
-/// List available backups (stub - not implemented for Postgres)
-async fn get_backup() -> Json<serde_json::Value> {
-    Json(serde_json::json!({
-        "backups": [],
-        "message": "Backup listing not yet implemented for PostgreSQL"
+fn backup_storage(state: &AppState) -> Result<&StorageClient, AppError> {
+    state
+        .storage
+        .as_ref()
+        .ok_or_else(|| AppError::Config("Storage not configured".to_string()))
+}
+
+/// GET /admin/backup
+/// List backups, newest first
+async fn list_backups(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<BackupsListResponse>, AppError> {
+    let backups = backups::list_backups(backup_storage(&state)?).await?;
+    Ok(Json(BackupsListResponse {
+        backups,
+        schema_version: state.get_schema_version().await,
+        retention_count: state.config.backups.retention_count,
     }))
 }
 
-/// Create a backup (stub - not implemented for Postgres)
-async fn create_backup() -> Json<serde_json::Value> {
-    Json(serde_json::json!({
-        "success": false,
-        "message": "Backup creation not yet implemented for PostgreSQL. Use pg_dump externally."
+/// POST /admin/backup
+/// Snapshot every table to storage, then prune old backups
+async fn create_backup(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<CreateBackupResponse>, AppError> {
+    let storage = backup_storage(&state)?;
+    let schema_version = state.get_schema_version().await;
+    let manifest =
+        backups::create_backup(&state.db, storage, schema_version, Some(auth.user_id)).await?;
+
+    write_audit(
+        state.db.clone(),
+        AuditEventType::BackupCreated,
+        Some(auth.user_id),
+        &format!(
+            "Admin created backup {} ({} tables, schema {:?})",
+            manifest.backup_id,
+            manifest.tables.len(),
+            manifest.schema_version
+        ),
+        Some("backup"),
+        None,
+    );
+
+    // A failed prune leaves the new backup in place
+    let pruned =
+        match backups::prune_backups(storage, state.config.backups.retention_count).await {
+            Ok(pruned) => pruned,
+            Err(e) => {
+                tracing::warn!("Failed to prune backups: {}", e);
+                Vec::new()
+            }
+        };
+
+    Ok(Json(CreateBackupResponse {
+        success: true,
+        backup_id: manifest.backup_id.clone(),
+        message: format!("Backup {} created", manifest.backup_id),
+        manifest,
+        pruned,
     }))
 }
 
-/// Restore from backup (stub - not implemented for Postgres)
-async fn restore_backup() -> Json<serde_json::Value> {
-    Json(serde_json::json!({
-        "success": false,
-        "message": "Restore not yet implemented for PostgreSQL. Use pg_restore externally."
+/// POST /admin/backup/prune
+/// Remove backups beyond the retention count
+async fn prune_backups(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<PruneBackupsResponse>, AppError> {
+    let removed = backups::prune_backups(
+        backup_storage(&state)?,
+        state.config.backups.retention_count,
+    )
+    .await?;
+
+    if !removed.is_empty() {
+        write_audit(
+            state.db.clone(),
+            AuditEventType::AdminAction,
+            Some(auth.user_id),
+            &format!("Admin pruned backups: {}", removed.join(", ")),
+            Some("backup"),
+            None,
+        );
+    }
+
+    Ok(Json(PruneBackupsResponse { removed }))
+}
+
+/// POST /admin/restore
+/// Replace the database contents with a backup taken at the current schema
+/// version
+async fn restore_backup(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Json(req): Json<RestoreRequest>,
+) -> Result<Json<RestoreResponse>, AppError> {
+    let schema_version = state.get_schema_version().await;
+    let result = backups::restore_backup(
+        &state.db,
+        backup_storage(&state)?,
+        &req.backup_id,
+        schema_version,
+    )
+    .await;
+    let rows_restored = match result {
+        Ok(rows_restored) => rows_restored,
+        Err(e) => {
+            write_audit_failure(
+                state.db.clone(),
+                AuditEventType::BackupRestored,
+                Some(auth.user_id),
+                &format!("Admin failed to restore backup {}: {}", req.backup_id, e),
+                Some("backup"),
+                None,
+            );
+            return Err(e);
+        }
+    };
+
+    write_audit(
+        state.db.clone(),
+        AuditEventType::BackupRestored,
+        Some(auth.user_id),
+        &format!(
+            "Admin restored backup {} ({} rows)",
+            req.backup_id,
+            rows_restored.values().sum::<u64>()
+        ),
+        Some("backup"),
+        None,
+    );
+
+    Ok(Json(RestoreResponse {
+        success: true,
+        message: format!("Restored backup {}", req.backup_id),
+        rows_restored,
     }))
 }
 
@@ -601,26 +734,26 @@ struct TableInfo {
     size_bytes: Option<i64>,
 }
 
+pub const LIST_TABLES: &str = r#"
+    SELECT
+        tablename::text as name,
+        (xpath('/row/cnt/text()',
+            query_to_xml(format('SELECT COUNT(*) as cnt FROM %I.%I', schemaname, tablename), false, true, '')
+        ))[1]::text::bigint as row_count,
+        pg_table_size(quote_ident(schemaname) || '.' || quote_ident(tablename)) as size_bytes
+    FROM pg_tables
+    WHERE schemaname = 'public'
+    ORDER BY tablename
+"#;
+
 /// List all tables with row counts
 async fn list_tables(
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<Vec<TableInfo>>, AppError> {
-    let rows = sqlx::query_as::<_, (String, i64, Option<i64>)>(
-        r#"
-        SELECT 
-            tablename::text as name,
-            (xpath('/row/cnt/text()', 
-                query_to_xml(format('SELECT COUNT(*) as cnt FROM %I.%I', schemaname, tablename), false, true, '')
-            ))[1]::text::bigint as row_count,
-            pg_table_size(quote_ident(schemaname) || '.' || quote_ident(tablename)) as size_bytes
-        FROM pg_tables 
-        WHERE schemaname = 'public'
-        ORDER BY tablename
-        "#,
-    )
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| AppError::Internal(format!("Failed to list tables: {}", e)))?;
+    let rows = sqlx::query_as::<_, (String, i64, Option<i64>)>(LIST_TABLES)
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to list tables: {}", e)))?;
 
     let tables: Vec<TableInfo> = rows
         .into_iter()
@@ -637,6 +770,16 @@ struct TableDataQuery {
     offset: Option<i32>,
 }
 
+pub const GET_TABLE_DATA_VALID_TABLES: &str =
+    "SELECT tablename::text FROM pg_tables WHERE schemaname = 'public'";
+
+pub const GET_TABLE_DATA_COLUMNS: &str = r#"
+    SELECT column_name::text
+    FROM information_schema.columns
+    WHERE table_schema = 'public' AND table_name = $1
+    ORDER BY ordinal_position
+"#;
+
 /// Get data from a specific table
 async fn get_table_data(
     State(state): State<Arc<AppState>>,
@@ -644,12 +787,10 @@ async fn get_table_data(
     Query(query): Query<TableDataQuery>,
 ) -> Result<Json<serde_json::Value>, AppError> {
     // Validate table name to prevent SQL injection
-    let valid_tables: Vec<String> = sqlx::query_scalar(
-        "SELECT tablename::text FROM pg_tables WHERE schemaname = 'public'"
-    )
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| AppError::Internal(format!("Failed to validate table: {}", e)))?;
+    let valid_tables: Vec<String> = sqlx::query_scalar(GET_TABLE_DATA_VALID_TABLES)
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to validate table: {}", e)))?;
 
     if !valid_tables.contains(&table) {
         return Err(AppError::NotFound(format!("Table '{}' not found", table)));
@@ -659,18 +800,11 @@ async fn get_table_data(
     let offset = query.offset.unwrap_or(0);
 
     // Get column names first
-    let columns: Vec<String> = sqlx::query_scalar(
-        r#"
-        SELECT column_name::text
-        FROM information_schema.columns
-        WHERE table_schema = 'public' AND table_name = $1
-        ORDER BY ordinal_position
-        "#
-    )
-    .bind(&table)
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| AppError::Internal(format!("Failed to get columns: {}", e)))?;
+    let columns: Vec<String> = sqlx::query_scalar(GET_TABLE_DATA_COLUMNS)
+        .bind(&table)
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get columns: {}", e)))?;
 
     // Execute query and convert to JSON
     let sql = format!(
@@ -700,70 +834,6 @@ async fn get_table_data(
     })))
 }
 
-/// Request body for running queries
-#[derive(serde::Deserialize)]
-struct RunQueryRequest {
-    sql: String,
-}
-
-/// Run a read-only SQL query
-async fn run_query(
-    State(state): State<Arc<AppState>>,
-    Extension(auth): Extension<AuthContext>,
-    Json(request): Json<RunQueryRequest>,
-) -> Result<Json<serde_json::Value>, AppError> {
-    let sql = request.sql.trim();
-    
-    // Security: Only allow SELECT statements
-    let sql_upper = sql.to_uppercase();
-    if !sql_upper.starts_with("SELECT") && !sql_upper.starts_with("WITH") {
-        return Err(AppError::Validation("Only SELECT queries are allowed".to_string()));
-    }
-    
-    // Block dangerous keywords
-    let dangerous = ["DELETE", "UPDATE", "INSERT", "DROP", "TRUNCATE", "ALTER", "CREATE", "GRANT", "REVOKE"];
-    for keyword in dangerous {
-        if sql_upper.contains(keyword) {
-            return Err(AppError::Validation(format!("Query contains forbidden keyword: {}", keyword)));
-        }
-    }
-
-    // Limit results
-    let limited_sql = if !sql_upper.contains("LIMIT") {
-        format!("{} LIMIT 1000", sql)
-    } else {
-        sql.to_string()
-    };
-
-    // Execute and return as JSON
-    let start = std::time::Instant::now();
-    let rows: Vec<serde_json::Value> = sqlx::query_scalar(
-        &format!("SELECT row_to_json(t) FROM ({}) t", limited_sql)
-    )
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| AppError::Internal(format!("Query failed: {}", e)))?;
-
-    let duration_ms = start.elapsed().as_millis();
-
-    // Audit log: admin query
-    write_audit(
-        state.db.clone(),
-        AuditEventType::AdminAction,
-        Some(auth.user_id),
-        &format!("Admin ran query: {}", if sql.len() > 100 { &sql[..100] } else { sql }),
-        None,
-        None,
-    );
-
-    Ok(Json(serde_json::json!({
-        "rows": rows,
-        "count": rows.len(),
-        "duration_ms": duration_ms,
-        "sql": sql
-    })))
-}
-
 // ============================================
 // Session Management Handlers
 // ============================================
@@ -782,32 +852,32 @@ struct SessionInfo {
     ip_address: Option<String>,
 }
 
+pub const LIST_SESSIONS: &str = r#"
+    SELECT
+        s.id,
+        s.user_id,
+        u.email,
+        u.name,
+        s.created_at,
+        s.last_activity_at,
+        s.expires_at,
+        s.user_agent,
+        s.ip_address::text
+    FROM sessions s
+    JOIN users u ON s.user_id = u.id
+    WHERE s.expires_at > NOW()
+    ORDER BY s.last_activity_at DESC
+    LIMIT 500
+"#;
+
 /// List all active sessions
 async fn list_sessions(
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<Vec<SessionInfo>>, AppError> {
-    let rows = sqlx::query_as::<_, (Uuid, Uuid, Option<String>, Option<String>, DateTime<Utc>, DateTime<Utc>, DateTime<Utc>, Option<String>, Option<String>)>(
-        r#"
-        SELECT 
-            s.id,
-            s.user_id,
-            u.email,
-            u.name,
-            s.created_at,
-            s.last_activity_at,
-            s.expires_at,
-            s.user_agent,
-            s.ip_address::text
-        FROM sessions s
-        JOIN users u ON s.user_id = u.id
-        WHERE s.expires_at > NOW()
-        ORDER BY s.last_activity_at DESC
-        LIMIT 500
-        "#,
-    )
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| AppError::Internal(format!("Failed to list sessions: {}", e)))?;
+    let rows = sqlx::query_as::<_, (Uuid, Uuid, Option<String>, Option<String>, DateTime<Utc>, DateTime<Utc>, DateTime<Utc>, Option<String>, Option<String>)>(LIST_SESSIONS)
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to list sessions: {}", e)))?;
 
     let sessions: Vec<SessionInfo> = rows
         .into_iter()
@@ -829,13 +899,15 @@ async fn list_sessions(
     Ok(Json(sessions))
 }
 
+pub const DELETE_SESSION: &str = "DELETE FROM sessions WHERE id = $1";
+
 /// Delete a session (force logout)
 async fn delete_session(
     State(state): State<Arc<AppState>>,
     Extension(auth): Extension<AuthContext>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    sqlx::query("DELETE FROM sessions WHERE id = $1")
+    sqlx::query(DELETE_SESSION)
         .bind(id)
         .execute(&state.db)
         .await
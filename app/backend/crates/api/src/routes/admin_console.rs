@@ -0,0 +1,207 @@
+//! Admin SQL console routes
+//!
+//! Read-only queries against the live database, mounted under `/admin/db`.
+//! Results are paged through server-side cursors; see `services::sql_console`.
+
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Extension, Json, Router,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::db::admin_models::{
+    ConsoleExplainRequest, ConsoleExplainResponse, ConsolePage, ConsolePageQuery,
+    ConsoleQueryRequest, SaveQueryRequest, SavedQuery,
+};
+use crate::db::admin_repos::AdminConsoleRepo;
+use crate::error::AppError;
+use crate::middleware::auth::AuthContext;
+use crate::shared::audit::{write_audit, AuditEventType};
+use crate::state::AppState;
+
+/// Longest saved query name
+const MAX_SAVED_QUERY_NAME_LEN: usize = 100;
+
+/// Create SQL console routes
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/query", post(run_query))
+        .route("/query/explain", post(explain_query))
+        .route("/query/csv", post(export_query_csv))
+        .route("/query/{cursor}", get(next_page).delete(close_cursor))
+        .route("/saved-queries", get(list_saved_queries).post(save_query))
+        .route("/saved-queries/{id}", delete(delete_saved_query))
+}
+
+// ============================================
+// Response types
+// ============================================
+
+#[derive(Serialize)]
+struct SavedQueriesWrapper {
+    data: Vec<SavedQuery>,
+}
+
+#[derive(Serialize)]
+struct SavedQueryWrapper {
+    data: SavedQuery,
+}
+
+/// Record a console run in the audit log
+fn audit_query(state: &AppState, user_id: Uuid, verb: &str, sql: &str) {
+    let preview: String = sql.trim().chars().take(100).collect();
+    write_audit(
+        state.db.clone(),
+        AuditEventType::AdminAction,
+        Some(user_id),
+        &format!("Admin {} query: {}", verb, preview),
+        None,
+        None,
+    );
+}
+
+// ============================================
+// Query Handlers
+// ============================================
+
+/// POST /admin/db/query
+/// Run a read-only query and return its first page
+async fn run_query(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Json(request): Json<ConsoleQueryRequest>,
+) -> Result<Json<ConsolePage>, AppError> {
+    audit_query(&state, auth.user_id, "ran", &request.sql);
+    let page = state
+        .console
+        .run(auth.user_id, &request.sql, request.page_size)
+        .await?;
+    Ok(Json(page))
+}
+
+/// GET /admin/db/query/{cursor}
+/// Next page of an open result
+async fn next_page(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(cursor): Path<Uuid>,
+    Query(query): Query<ConsolePageQuery>,
+) -> Result<Json<ConsolePage>, AppError> {
+    let page = state
+        .console
+        .next_page(auth.user_id, cursor, query.page_size)
+        .await?;
+    Ok(Json(page))
+}
+
+/// DELETE /admin/db/query/{cursor}
+/// Close a result that will not be read to the end
+async fn close_cursor(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(cursor): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    if state.console.close(auth.user_id, cursor) {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(AppError::NotFound(
+            "Query cursor not found or expired".to_string(),
+        ))
+    }
+}
+
+/// POST /admin/db/query/explain
+/// Show a query's plan; with `analyze` the query is run for actual timings
+async fn explain_query(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Json(request): Json<ConsoleExplainRequest>,
+) -> Result<Json<ConsoleExplainResponse>, AppError> {
+    let verb = if request.analyze {
+        "analyzed"
+    } else {
+        "explained"
+    };
+    audit_query(&state, auth.user_id, verb, &request.sql);
+    let plan = state.console.explain(&request.sql, request.analyze).await?;
+    Ok(Json(plan))
+}
+
+/// POST /admin/db/query/csv
+/// Download a query's result as CSV
+async fn export_query_csv(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Json(request): Json<ConsoleQueryRequest>,
+) -> Result<Response, AppError> {
+    audit_query(&state, auth.user_id, "exported", &request.sql);
+    let csv = state.console.export_csv(&request.sql).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"query.csv\"",
+            ),
+        ],
+        Body::from_stream(csv),
+    )
+        .into_response())
+}
+
+// ============================================
+// Saved Query Handlers
+// ============================================
+
+/// GET /admin/db/saved-queries
+/// The admin's saved queries
+async fn list_saved_queries(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<SavedQueriesWrapper>, AppError> {
+    let queries = AdminConsoleRepo::list_saved(&state.db, auth.user_id).await?;
+    Ok(Json(SavedQueriesWrapper { data: queries }))
+}
+
+/// POST /admin/db/saved-queries
+/// Save a query under a name, replacing one saved under the same name
+async fn save_query(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Json(request): Json<SaveQueryRequest>,
+) -> Result<Json<SavedQueryWrapper>, AppError> {
+    let name = request.name.trim();
+    if name.is_empty() || name.len() > MAX_SAVED_QUERY_NAME_LEN {
+        return Err(AppError::Validation(format!(
+            "Name must be 1-{} characters",
+            MAX_SAVED_QUERY_NAME_LEN
+        )));
+    }
+    if request.sql.trim().is_empty() {
+        return Err(AppError::Validation("Query is empty".to_string()));
+    }
+
+    let query = AdminConsoleRepo::save(&state.db, auth.user_id, name, &request.sql).await?;
+    Ok(Json(SavedQueryWrapper { data: query }))
+}
+
+/// DELETE /admin/db/saved-queries/{id}
+/// Delete a saved query
+async fn delete_saved_query(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    if AdminConsoleRepo::delete_saved(&state.db, id, auth.user_id).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(AppError::NotFound("Saved query not found".to_string()))
+    }
+}
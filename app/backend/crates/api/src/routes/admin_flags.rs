@@ -0,0 +1,188 @@
+//! Admin routes for feature flags
+//!
+//! CRUD for runtime feature flags. Changes apply to this instance at once
+//! and to the others on their next refresh.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+    Extension, Json, Router,
+};
+use serde::Serialize;
+
+use crate::db::feature_flag_models::{
+    CreateFeatureFlagRequest, FeatureFlag, UpdateFeatureFlagRequest, MAX_FLAG_NAME_LEN,
+};
+use crate::db::feature_flag_repos::FeatureFlagRepo;
+use crate::error::AppError;
+use crate::middleware::auth::AuthContext;
+use crate::shared::audit::{write_audit, AuditEventType};
+use crate::state::AppState;
+
+/// Create feature flag admin routes
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_flags).post(create_flag))
+        .route(
+            "/{name}",
+            get(get_flag).put(update_flag).delete(delete_flag),
+        )
+}
+
+// ============================================
+// Response types
+// ============================================
+
+#[derive(Serialize)]
+struct FlagsWrapper {
+    data: Vec<FeatureFlag>,
+}
+
+#[derive(Serialize)]
+struct FlagWrapper {
+    data: FeatureFlag,
+}
+
+// ============================================
+// Helpers
+// ============================================
+
+/// Flag names are lowercase snake_case so code can refer to them safely
+fn validate_flag_name(name: &str) -> Result<(), AppError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_FLAG_NAME_LEN
+        && name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!(
+            "Flag name must be lowercase letters, digits and underscores (at most {})",
+            MAX_FLAG_NAME_LEN
+        )))
+    }
+}
+
+fn validate_rollout(rollout_percentage: Option<i32>) -> Result<(), AppError> {
+    match rollout_percentage {
+        Some(p) if !(0..=100).contains(&p) => Err(AppError::Validation(
+            "rollout_percentage must be between 0 and 100".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Apply a change here at once and record it
+async fn flag_changed(state: &AppState, auth: &AuthContext, action: &str) {
+    if let Err(e) = state.flags.refresh(&state.db).await {
+        tracing::warn!("Failed to refresh feature flags: {}", e);
+    }
+    write_audit(
+        state.db.clone(),
+        AuditEventType::AdminAction,
+        Some(auth.user_id),
+        action,
+        Some("feature_flag"),
+        None,
+    );
+}
+
+// ============================================
+// Handlers
+// ============================================
+
+/// GET /admin/flags
+/// List every flag
+async fn list_flags(State(state): State<Arc<AppState>>) -> Result<Json<FlagsWrapper>, AppError> {
+    let flags = FeatureFlagRepo::list(&state.db).await?;
+    Ok(Json(FlagsWrapper { data: flags }))
+}
+
+/// GET /admin/flags/{name}
+/// Get one flag
+async fn get_flag(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<Json<FlagWrapper>, AppError> {
+    let flag = FeatureFlagRepo::get(&state.db, &name)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Feature flag not found".to_string()))?;
+    Ok(Json(FlagWrapper { data: flag }))
+}
+
+/// POST /admin/flags
+/// Create a flag
+async fn create_flag(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Json(input): Json<CreateFeatureFlagRequest>,
+) -> Result<(StatusCode, Json<FlagWrapper>), AppError> {
+    validate_flag_name(&input.flag_name)?;
+    validate_rollout(input.rollout_percentage)?;
+
+    let flag = FeatureFlagRepo::create(&state.db, &input)
+        .await?
+        .ok_or_else(|| {
+            AppError::BadRequest(format!("Feature flag {} already exists", input.flag_name))
+        })?;
+
+    flag_changed(
+        &state,
+        &auth,
+        &format!("Created feature flag {}", flag.flag_name),
+    )
+    .await;
+    Ok((StatusCode::CREATED, Json(FlagWrapper { data: flag })))
+}
+
+/// PUT /admin/flags/{name}
+/// Change a flag's state, rollout or targeting
+async fn update_flag(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(name): Path<String>,
+    Json(input): Json<UpdateFeatureFlagRequest>,
+) -> Result<Json<FlagWrapper>, AppError> {
+    validate_rollout(input.rollout_percentage)?;
+
+    let flag = FeatureFlagRepo::update(&state.db, &name, &input)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Feature flag not found".to_string()))?;
+
+    flag_changed(&state, &auth, &format!("Updated feature flag {}", name)).await;
+    Ok(Json(FlagWrapper { data: flag }))
+}
+
+/// DELETE /admin/flags/{name}
+/// Delete a flag; routes it gates are no longer served
+async fn delete_flag(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, AppError> {
+    if !FeatureFlagRepo::delete(&state.db, &name).await? {
+        return Err(AppError::NotFound("Feature flag not found".to_string()));
+    }
+
+    flag_changed(&state, &auth, &format!("Deleted feature flag {}", name)).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flag_names() {
+        assert!(validate_flag_name("music_analysis").is_ok());
+        assert!(validate_flag_name("beta2").is_ok());
+        assert!(validate_flag_name("").is_err());
+        assert!(validate_flag_name("2fa").is_err());
+        assert!(validate_flag_name("New-Editor").is_err());
+    }
+}
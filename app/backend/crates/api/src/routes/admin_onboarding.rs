@@ -0,0 +1,363 @@
+//! Admin routes for onboarding flows
+//!
+//! Versions of onboarding flows, their steps and completion funnels. Steps
+//! can only be changed on an unpublished version; publishing one makes it
+//! the flow new users start.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post, put},
+    Extension, Json, Router,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::db::onboarding_models::*;
+use crate::db::onboarding_repos::OnboardingFlowRepo;
+use crate::db::platform_models::{OnboardingFlow, OnboardingStep};
+use crate::error::AppError;
+use crate::middleware::auth::AuthContext;
+use crate::shared::audit::{write_audit, AuditEventType};
+use crate::state::AppState;
+
+/// Create onboarding admin routes
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/flows", get(list_flows).post(create_flow))
+        .route(
+            "/flows/{id}",
+            get(get_flow).put(update_flow).delete(delete_flow),
+        )
+        .route("/flows/{id}/versions", post(new_version))
+        .route("/flows/{id}/publish", post(publish_flow))
+        .route("/flows/{id}/funnel", get(get_funnel))
+        .route("/flows/{id}/steps", post(add_step))
+        .route(
+            "/flows/{id}/steps/{step_id}",
+            put(update_step).delete(delete_step),
+        )
+}
+
+// ============================================
+// Response types
+// ============================================
+
+#[derive(Serialize)]
+struct FlowsWrapper {
+    data: Vec<OnboardingFlow>,
+}
+
+#[derive(Serialize)]
+struct FlowWrapper {
+    data: OnboardingFlow,
+}
+
+#[derive(Serialize)]
+struct FlowDetailWrapper {
+    data: OnboardingFlowDetail,
+}
+
+#[derive(Serialize)]
+struct StepWrapper {
+    data: OnboardingStep,
+}
+
+#[derive(Serialize)]
+struct FunnelWrapper {
+    data: OnboardingFunnel,
+}
+
+// ============================================
+// Helpers
+// ============================================
+
+/// Flow names are lowercase snake_case, like the seeded `welcome` flow
+fn validate_flow_name(name: &str) -> Result<(), AppError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_FLOW_NAME_LEN
+        && name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!(
+            "Flow name must be lowercase letters, digits and underscores (at most {})",
+            MAX_FLOW_NAME_LEN
+        )))
+    }
+}
+
+fn validate_step(
+    step_order: Option<i32>,
+    step_type: Option<&str>,
+    title: Option<&str>,
+    show_if: Option<&StepCondition>,
+) -> Result<(), AppError> {
+    if step_order.is_some_and(|o| o < 1) {
+        return Err(AppError::Validation("step_order must be at least 1".into()));
+    }
+    if let Some(step_type) = step_type {
+        if !STEP_TYPES.contains(&step_type) {
+            return Err(AppError::Validation(format!(
+                "step_type must be one of {}",
+                STEP_TYPES.join(", ")
+            )));
+        }
+    }
+    if title.is_some_and(|t| t.trim().is_empty()) {
+        return Err(AppError::Validation("Step title is required".into()));
+    }
+    if show_if.is_some_and(|c| c.depth() > MAX_CONDITION_DEPTH) {
+        return Err(AppError::Validation(format!(
+            "show_if can nest at most {} levels",
+            MAX_CONDITION_DEPTH
+        )));
+    }
+    Ok(())
+}
+
+fn audit(state: &AppState, auth: &AuthContext, action: String) {
+    write_audit(
+        state.db.clone(),
+        AuditEventType::AdminAction,
+        Some(auth.user_id),
+        &action,
+        Some("onboarding_flow"),
+        None,
+    );
+}
+
+// ============================================
+// Flow handlers
+// ============================================
+
+/// GET /admin/onboarding/flows
+/// List every version of every flow
+async fn list_flows(State(state): State<Arc<AppState>>) -> Result<Json<FlowsWrapper>, AppError> {
+    let flows = OnboardingFlowRepo::list(&state.db).await?;
+    Ok(Json(FlowsWrapper { data: flows }))
+}
+
+/// GET /admin/onboarding/flows/{id}
+/// Get a version with its steps
+async fn get_flow(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<FlowDetailWrapper>, AppError> {
+    let flow = OnboardingFlowRepo::get(&state.db, id).await?;
+    Ok(Json(FlowDetailWrapper { data: flow }))
+}
+
+/// POST /admin/onboarding/flows
+/// Create a flow as an unpublished version 1
+async fn create_flow(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Json(input): Json<CreateOnboardingFlowRequest>,
+) -> Result<(StatusCode, Json<FlowWrapper>), AppError> {
+    validate_flow_name(&input.name)?;
+
+    let flow = OnboardingFlowRepo::create(&state.db, &input)
+        .await?
+        .ok_or_else(|| {
+            AppError::BadRequest(format!("Onboarding flow {} already exists", input.name))
+        })?;
+
+    audit(
+        &state,
+        &auth,
+        format!("Created onboarding flow {}", flow.name),
+    );
+    Ok((StatusCode::CREATED, Json(FlowWrapper { data: flow })))
+}
+
+/// PUT /admin/onboarding/flows/{id}
+/// Change an unpublished version's description
+async fn update_flow(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+    Json(input): Json<UpdateOnboardingFlowRequest>,
+) -> Result<Json<FlowWrapper>, AppError> {
+    let flow = OnboardingFlowRepo::update(&state.db, id, &input).await?;
+
+    audit(
+        &state,
+        &auth,
+        format!("Updated onboarding flow {} v{}", flow.name, flow.version),
+    );
+    Ok(Json(FlowWrapper { data: flow }))
+}
+
+/// DELETE /admin/onboarding/flows/{id}
+/// Delete a version that is not active and nobody has started
+async fn delete_flow(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    OnboardingFlowRepo::delete(&state.db, id).await?;
+
+    audit(
+        &state,
+        &auth,
+        format!("Deleted onboarding flow version {}", id),
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /admin/onboarding/flows/{id}/versions
+/// Copy a version and its steps into a new unpublished version
+async fn new_version(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<FlowDetailWrapper>), AppError> {
+    let detail = OnboardingFlowRepo::new_version(&state.db, id).await?;
+
+    audit(
+        &state,
+        &auth,
+        format!(
+            "Created onboarding flow {} v{}",
+            detail.flow.name, detail.flow.version
+        ),
+    );
+    Ok((
+        StatusCode::CREATED,
+        Json(FlowDetailWrapper { data: detail }),
+    ))
+}
+
+/// POST /admin/onboarding/flows/{id}/publish
+/// Serve a version to new users; publishing an older version rolls back to it
+async fn publish_flow(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<FlowWrapper>, AppError> {
+    let flow = OnboardingFlowRepo::publish(&state.db, id).await?;
+
+    audit(
+        &state,
+        &auth,
+        format!("Published onboarding flow {} v{}", flow.name, flow.version),
+    );
+    Ok(Json(FlowWrapper { data: flow }))
+}
+
+/// GET /admin/onboarding/flows/{id}/funnel
+/// How many users reached, completed or left at each step of a version
+async fn get_funnel(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<FunnelWrapper>, AppError> {
+    let funnel = OnboardingFlowRepo::funnel(&state.db, id).await?;
+    Ok(Json(FunnelWrapper { data: funnel }))
+}
+
+// ============================================
+// Step handlers
+// ============================================
+
+/// POST /admin/onboarding/flows/{id}/steps
+/// Add a step to an unpublished version
+async fn add_step(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+    Json(input): Json<CreateOnboardingStepRequest>,
+) -> Result<(StatusCode, Json<StepWrapper>), AppError> {
+    validate_step(
+        input.step_order,
+        Some(input.step_type.as_str()),
+        Some(input.title.as_str()),
+        input.show_if.as_ref(),
+    )?;
+
+    let step = OnboardingFlowRepo::add_step(&state.db, id, &input).await?;
+
+    audit(
+        &state,
+        &auth,
+        format!("Added onboarding step {}", step.title),
+    );
+    Ok((StatusCode::CREATED, Json(StepWrapper { data: step })))
+}
+
+/// PUT /admin/onboarding/flows/{id}/steps/{step_id}
+/// Change a step of an unpublished version
+async fn update_step(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Path((id, step_id)): Path<(Uuid, Uuid)>,
+    Json(input): Json<UpdateOnboardingStepRequest>,
+) -> Result<Json<StepWrapper>, AppError> {
+    validate_step(
+        input.step_order,
+        input.step_type.as_deref(),
+        input.title.as_deref(),
+        input.show_if.as_ref().and_then(Option::as_ref),
+    )?;
+
+    let step = OnboardingFlowRepo::update_step(&state.db, id, step_id, &input).await?;
+
+    audit(
+        &state,
+        &auth,
+        format!("Updated onboarding step {}", step.title),
+    );
+    Ok(Json(StepWrapper { data: step }))
+}
+
+/// DELETE /admin/onboarding/flows/{id}/steps/{step_id}
+/// Remove a step from an unpublished version
+async fn delete_step(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Path((id, step_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    OnboardingFlowRepo::delete_step(&state.db, id, step_id).await?;
+
+    audit(
+        &state,
+        &auth,
+        format!("Deleted onboarding step {}", step_id),
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_flow_names() {
+        assert!(validate_flow_name("welcome").is_ok());
+        assert!(validate_flow_name("producer_v2").is_ok());
+        assert!(validate_flow_name("").is_err());
+        assert!(validate_flow_name("Welcome Flow").is_err());
+    }
+
+    #[test]
+    fn test_step_validation() {
+        assert!(validate_step(Some(1), Some("choice"), Some("Interests"), None).is_ok());
+        assert!(validate_step(None, None, None, None).is_ok());
+        assert!(validate_step(Some(0), None, None, None).is_err());
+        assert!(validate_step(None, Some("video"), None, None).is_err());
+        assert!(validate_step(None, None, Some("  "), None).is_err());
+
+        let mut deep = json!({"answered": {"field": "daw"}});
+        for _ in 0..MAX_CONDITION_DEPTH {
+            deep = json!({"not": deep});
+        }
+        let deep: StepCondition = serde_json::from_value(deep).unwrap();
+        assert!(validate_step(None, None, None, Some(&deep)).is_err());
+    }
+}
@@ -5,11 +5,14 @@
 use std::sync::Arc;
 
 use axum::{
+    middleware::from_fn,
     routing::get,
     Json, Router,
 };
 use serde::Serialize;
 
+use crate::db::feature_flag_models::FLAG_MUSIC_ANALYSIS;
+use crate::middleware::flags::require_flag;
 use crate::state::AppState;
 
 /// Create API routes
@@ -37,8 +40,11 @@ pub fn router() -> Router<Arc<AppState>> {
         .nest("/reference", super::reference::router())
         // References library module (stateless sync - 2026-01-10)
         .nest("/references", super::references_library::router())
-        // Frames module (Wave 5 - real implementation)
-        .nest("/frames", super::frames::router())
+        // Frames module (behind the music_analysis flag)
+        .nest(
+            "/frames",
+            super::frames::router().route_layer(from_fn(require_flag(FLAG_MUSIC_ANALYSIS))),
+        )
         // Learn module (Wave 3 - real implementation)
         .nest("/learn", super::learn::router())
         // User module (Wave 4 - real implementation)
@@ -51,8 +57,11 @@ pub fn router() -> Router<Arc<AppState>> {
         .nest("/ideas", super::ideas::router())
         // Feedback module (Wave 4 - real implementation)
         .nest("/feedback", super::feedback::router())
-        // Analysis module
-        .nest("/analysis", analysis_routes())
+        // Analysis module (behind the music_analysis flag)
+        .nest(
+            "/analysis",
+            analysis_routes().route_layer(from_fn(require_flag(FLAG_MUSIC_ANALYSIS))),
+        )
         // Books module (Wave 3 - real implementation)
         .nest("/books", super::books::router())
         // Programs are handled under /exercise/programs
@@ -66,6 +75,12 @@ pub fn router() -> Router<Arc<AppState>> {
         .nest("/settings", super::settings::router())
         // Today module - dashboard data aggregation (Wave 5)
         .nest("/today", super::today::router())
+        // Notifications module - push subscriptions and reminder deliveries
+        .nest("/notifications", super::notifications::router())
+        // Search module - full-text search across the user's content
+        .nest("/search", super::search::router())
+        // Inbox module - capture and triage (also under /user/inbox)
+        .nest("/inbox", super::inbox::router())
     // Apply middleware (CSRF and auth will be added at top level)
 }
 
@@ -101,6 +116,8 @@ async fn api_info() -> Json<ApiInfo> {
             "blobs".to_string(),
             "sync".to_string(),
             "settings".to_string(),
+            "search".to_string(),
+            "inbox".to_string(),
         ],
     })
 }
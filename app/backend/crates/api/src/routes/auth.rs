@@ -4,10 +4,11 @@
 //! Per DEC-001=A: Force re-auth at cutover, no session migration.
 //! Per DEC-002=A: CSRF via Origin/Referer verification.
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use axum::{
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::{header, StatusCode},
     response::{IntoResponse, Redirect, Response},
     routing::{get, post},
@@ -18,6 +19,8 @@ use serde::{Deserialize, Serialize};
 use crate::db::oauth_repos::OAuthStateRepo;
 use crate::error::{AppError, AppResult};
 use crate::middleware::auth::{create_logout_cookie, create_session_cookie, AuthContext};
+use crate::services::feature_flags::FlagSubject;
+use crate::services::oidc::{self, OidcProvider};
 use crate::services::{AuthService, OAuthService};
 use crate::state::AppState;
 
@@ -36,12 +39,17 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/signin/azure", get(signin_azure))
         .route("/callback/google", get(callback_google))
         .route("/callback/azure", get(callback_azure))
+        // Config-driven OIDC providers (the static routes above take precedence)
+        .route("/signin/{provider}", get(signin_oidc))
+        .route("/callback/{provider}", get(callback_oidc))
         // Session endpoints
         .route("/session", get(get_session))
         .route("/signout", post(signout))
         // Verification endpoints
         .route("/verify-age", post(verify_age))
         .route("/accept-tos", post(accept_tos))
+        // Passkey (WebAuthn) endpoints
+        .nest("/passkey", super::passkey::router())
 }
 
 /// OAuth provider info
@@ -73,6 +81,14 @@ async fn list_providers(State(state): State<Arc<AppState>>) -> Json<Vec<OAuthPro
         }
     }
 
+    for provider in oidc::configured_providers(&state.config) {
+        providers.push(OAuthProvider {
+            id: provider.id.clone(),
+            name: provider.name.clone(),
+            enabled: true,
+        });
+    }
+
     Json(providers)
 }
 
@@ -103,6 +119,8 @@ async fn signin_google(
         &oauth_state.csrf_token,
         &oauth_state.pkce_verifier,
         query.redirect_uri.as_deref(),
+        Some("google"),
+        None,
     )
     .await?;
 
@@ -124,7 +142,9 @@ async fn signin_azure(
             let error_url = format!(
                 "{}/auth/error?error=OAuthNotConfigured&provider=Azure&details={}",
                 state.config.server.frontend_url,
-                urlencoding::encode("Azure/Microsoft OAuth credentials are not configured on the server")
+                urlencoding::encode(
+                    "Azure/Microsoft OAuth credentials are not configured on the server"
+                )
             );
             return Ok(Redirect::temporary(&error_url).into_response());
         }
@@ -138,6 +158,8 @@ async fn signin_azure(
         &oauth_state.csrf_token,
         &oauth_state.pkce_verifier,
         query.redirect_uri.as_deref(),
+        Some("azure"),
+        None,
     )
     .await?;
 
@@ -177,7 +199,7 @@ async fn handle_google_callback(
     params: OAuthCallback,
 ) -> AppResult<Response> {
     tracing::debug!(state_key = %params.state, "Looking up OAuth state from database");
-    
+
     // Validate state and get stored OAuth state from database
     let oauth_state_row = OAuthStateRepo::take(&state.db, &params.state)
         .await?
@@ -204,7 +226,10 @@ async fn handle_google_callback(
 
     // Authenticate and create session
     let (user, session) = AuthService::authenticate_oauth(
-        &state.db, user_info, None, // TODO: Extract from request
+        &state.db,
+        user_info,
+        Some(&token_info),
+        None, // TODO: Extract from request
         None, // TODO: Extract from request
         30,   // 30 day session
     )
@@ -218,9 +243,10 @@ async fn handle_google_callback(
     );
 
     // Redirect to stored redirect_uri or default to /today
-    let redirect_url = oauth_state_row.redirect_uri
+    let redirect_url = oauth_state_row
+        .redirect_uri
         .unwrap_or_else(|| format!("{}/today", state.config.server.frontend_url));
-    
+
     tracing::info!(
         user_id = %user.id,
         email = %user.email,
@@ -265,7 +291,7 @@ async fn handle_azure_callback(
     params: OAuthCallback,
 ) -> AppResult<Response> {
     tracing::debug!(state_key = %params.state, "Looking up OAuth state from database");
-    
+
     // Validate state and get stored OAuth state from database
     let oauth_state_row = OAuthStateRepo::take(&state.db, &params.state)
         .await?
@@ -292,7 +318,12 @@ async fn handle_azure_callback(
 
     // Authenticate and create session
     let (user, session) = AuthService::authenticate_oauth(
-        &state.db, user_info, None, None, 30, // 30 day session
+        &state.db,
+        user_info,
+        Some(&token_info),
+        None,
+        None,
+        30, // 30 day session
     )
     .await?;
 
@@ -304,9 +335,10 @@ async fn handle_azure_callback(
     );
 
     // Redirect to stored redirect_uri or default to /today
-    let redirect_url = oauth_state_row.redirect_uri
+    let redirect_url = oauth_state_row
+        .redirect_uri
         .unwrap_or_else(|| format!("{}/today", state.config.server.frontend_url));
-    
+
     tracing::info!(
         user_id = %user.id,
         email = %user.email,
@@ -327,10 +359,131 @@ async fn handle_azure_callback(
     Ok(response)
 }
 
+/// Start an OAuth flow for a config-driven OIDC provider
+async fn signin_oidc(
+    State(state): State<Arc<AppState>>,
+    Path(provider_id): Path<String>,
+    Query(query): Query<SigninQuery>,
+) -> AppResult<Response> {
+    let Some(provider) = OidcProvider::from_config(&state.config, &provider_id) else {
+        let error_url = format!(
+            "{}/auth/error?error=OAuthNotConfigured&provider={}&details={}",
+            state.config.server.frontend_url,
+            urlencoding::encode(&provider_id),
+            urlencoding::encode("This sign-in provider is not configured on the server")
+        );
+        return Ok(Redirect::temporary(&error_url).into_response());
+    };
+
+    let (auth_url, oauth_state) = provider.authorization_url().await?;
+
+    OAuthStateRepo::insert(
+        &state.db,
+        &oauth_state.csrf_token,
+        &oauth_state.pkce_verifier,
+        query.redirect_uri.as_deref(),
+        Some(&provider_id),
+        Some(&oauth_state.nonce),
+    )
+    .await?;
+
+    tracing::debug!(provider = %provider_id, state = %oauth_state.csrf_token, "Stored OIDC state in database");
+
+    Ok(Redirect::temporary(&auth_url).into_response())
+}
+
+/// OIDC provider callback
+async fn callback_oidc(
+    State(state): State<Arc<AppState>>,
+    Path(provider_id): Path<String>,
+    Query(params): Query<OAuthCallback>,
+) -> Response {
+    match handle_oidc_callback(&state, &provider_id, params).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!(provider = %provider_id, "OIDC callback error: {}", e);
+            let error_url = format!(
+                "{}/auth/error?error=OAuthCallback&provider={}&details={}",
+                state.config.server.frontend_url,
+                urlencoding::encode(&provider_id),
+                urlencoding::encode(&e.to_string())
+            );
+            Redirect::temporary(&error_url).into_response()
+        }
+    }
+}
+
+async fn handle_oidc_callback(
+    state: &Arc<AppState>,
+    provider_id: &str,
+    params: OAuthCallback,
+) -> AppResult<Response> {
+    let oauth_state_row = OAuthStateRepo::take(&state.db, &params.state)
+        .await?
+        .ok_or_else(|| {
+            tracing::warn!(state_key = %params.state, "OAuth state not found in database");
+            AppError::OAuthError("Invalid state parameter".to_string())
+        })?;
+
+    // The state must have been issued for this provider
+    if oauth_state_row.provider.as_deref() != Some(provider_id) {
+        return Err(AppError::OAuthError("State was issued for a different provider".to_string()));
+    }
+
+    let provider = OidcProvider::from_config(&state.config, provider_id)
+        .ok_or_else(|| AppError::Config(format!("OIDC provider {} not configured", provider_id)))?;
+
+    let token_info = provider
+        .exchange_code(&params.code, &oauth_state_row.pkce_verifier)
+        .await?;
+
+    let user_info = provider
+        .get_user_info(&token_info, oauth_state_row.nonce.as_deref())
+        .await?;
+
+    let (user, session) = AuthService::authenticate_oauth(
+        &state.db,
+        user_info,
+        Some(&token_info),
+        None,
+        None,
+        30, // 30 day session
+    )
+    .await?;
+
+    let cookie = create_session_cookie(
+        &session.token,
+        &state.config.auth.cookie_domain,
+        state.config.auth.session_ttl_seconds,
+    );
+
+    let redirect_url = oauth_state_row.redirect_uri
+        .unwrap_or_else(|| format!("{}/today", state.config.server.frontend_url));
+
+    tracing::info!(
+        user_id = %user.id,
+        email = %user.email,
+        provider = %provider.name(),
+        redirect_url = %redirect_url,
+        "User authenticated via OIDC, redirecting with cookie"
+    );
+
+    let response = Response::builder()
+        .status(StatusCode::FOUND)
+        .header(header::LOCATION, redirect_url)
+        .header(header::SET_COOKIE, cookie)
+        .body(axum::body::Body::empty())
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(response)
+}
+
 /// Session response
 #[derive(Serialize)]
 pub struct SessionResponse {
     pub user: Option<SessionUser>,
+    /// Feature flags as evaluated for this user (or for anonymous visitors)
+    pub flags: BTreeMap<String, bool>,
 }
 
 #[derive(Serialize)]
@@ -350,11 +503,8 @@ async fn get_session(
     State(state): State<Arc<AppState>>,
     auth: Option<Extension<AuthContext>>,
 ) -> Json<SessionResponse> {
-    tracing::debug!(
-        has_auth = auth.is_some(),
-        "get_session called"
-    );
-    
+    tracing::debug!(has_auth = auth.is_some(), "get_session called");
+
     if let Some(Extension(auth_context)) = auth {
         tracing::debug!(
             user_id = %auth_context.user_id,
@@ -365,6 +515,9 @@ async fn get_session(
         if let Ok(Some(user)) =
             crate::db::repos::UserRepo::find_by_id(&state.db, auth_context.user_id).await
         {
+            let flags = state
+                .flags
+                .evaluate_all(Some(FlagSubject::from(&auth_context)));
             return Json(SessionResponse {
                 user: Some(SessionUser {
                     id: user.id.to_string(),
@@ -376,12 +529,16 @@ async fn get_session(
                     age_verified: user.age_verified,
                     tos_accepted: user.tos_accepted,
                 }),
+                flags,
             });
         }
     }
 
     tracing::debug!("No valid session, returning null user");
-    Json(SessionResponse { user: None })
+    Json(SessionResponse {
+        user: None,
+        flags: state.flags.evaluate_all(None),
+    })
 }
 
 /// Sign out (destroy session)
@@ -398,7 +555,8 @@ async fn signout(
                 auth_context.user_id,
                 None,
             )
-            .await {
+            .await
+            {
                 // Log error but continue - cookie will be cleared regardless
                 tracing::warn!(
                     error = %e,
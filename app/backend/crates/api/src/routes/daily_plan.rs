@@ -1,18 +1,22 @@
 //! Daily Plan routes
 //!
-//! Routes for daily planning.
+//! Routes for daily planning and reusable plan templates.
 
 use std::sync::Arc;
 
 use axum::{
-    extract::{Extension, Query, State},
-    routing::get,
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    routing::{get, post, put},
     Json, Router,
 };
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::db::models::User;
+use crate::db::plan_models::*;
+use crate::db::plan_repos::PlanTemplateRepo;
 use crate::db::platform_models::*;
 use crate::db::platform_repos::DailyPlanRepo;
 use crate::error::AppError;
@@ -20,7 +24,14 @@ use crate::state::AppState;
 
 /// Create daily plan routes
 pub fn router() -> Router<Arc<AppState>> {
-    Router::new().route("/", get(get_plan).post(handle_action))
+    Router::new()
+        .route("/", get(get_plan).post(handle_action))
+        .route("/templates", get(list_templates).post(create_template))
+        .route(
+            "/templates/{id}",
+            put(update_template).delete(delete_template),
+        )
+        .route("/templates/{id}/apply", post(apply_template))
 }
 
 // ============================================================================
@@ -55,6 +66,58 @@ struct PlanWrapper {
     plan: Option<DailyPlanResponse>,
 }
 
+#[derive(Serialize)]
+struct TemplatesWrapper {
+    templates: Vec<PlanTemplateResponse>,
+}
+
+#[derive(Serialize)]
+struct TemplateWrapper {
+    template: PlanTemplateResponse,
+}
+
+// ============================================================================
+// VALIDATION
+// ============================================================================
+
+fn validate_template_name(name: &str) -> Result<(), AppError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_TEMPLATE_NAME_LEN {
+        return Err(AppError::Validation(format!(
+            "Template name must be 1 to {} characters",
+            MAX_TEMPLATE_NAME_LEN
+        )));
+    }
+    Ok(())
+}
+
+/// Templates hold things to do, so calendar events can't be part of one
+fn validate_template_items(items: &[PlanTemplateItem]) -> Result<(), AppError> {
+    if items.is_empty() || items.len() > MAX_TEMPLATE_ITEMS {
+        return Err(AppError::Validation(format!(
+            "A template needs 1 to {} items",
+            MAX_TEMPLATE_ITEMS
+        )));
+    }
+    for item in items {
+        if item.item_type == "event" || !PLAN_ITEM_TYPES.contains(&item.item_type.as_str()) {
+            return Err(AppError::Validation(format!(
+                "Invalid template item type: {}",
+                item.item_type
+            )));
+        }
+        if item.title.trim().is_empty() {
+            return Err(AppError::Validation("Template items need a title".into()));
+        }
+        if !item.action_url.starts_with('/') {
+            return Err(AppError::Validation(
+                "Template item action_url must be an app path".into(),
+            ));
+        }
+    }
+    Ok(())
+}
+
 // ============================================================================
 // HANDLERS
 // ============================================================================
@@ -119,3 +182,82 @@ async fn handle_action(
 
     Ok(Json(PlanWrapper { plan }))
 }
+
+/// GET /daily-plan/templates
+/// List the user's templates and public ones
+async fn list_templates(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<Json<TemplatesWrapper>, AppError> {
+    let templates = PlanTemplateRepo::list(&state.db, user.id).await?;
+    Ok(Json(TemplatesWrapper { templates }))
+}
+
+/// POST /daily-plan/templates
+/// Save a template from a list of items or from the plan on `from_date`
+async fn create_template(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(req): Json<CreatePlanTemplateRequest>,
+) -> Result<(StatusCode, Json<TemplateWrapper>), AppError> {
+    validate_template_name(&req.name)?;
+
+    let items = match (&req.items, req.from_date) {
+        (Some(items), _) => items.clone(),
+        (None, Some(date)) => {
+            let plan = DailyPlanRepo::get_for_date(&state.db, user.id, date)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Daily plan not found".into()))?;
+            template_items_from_plan(&plan.items)
+        }
+        (None, None) => {
+            return Err(AppError::Validation("items or from_date required".into()));
+        }
+    };
+    validate_template_items(&items)?;
+
+    let template = PlanTemplateRepo::create(&state.db, user.id, &req, &items).await?;
+    Ok((StatusCode::CREATED, Json(TemplateWrapper { template })))
+}
+
+/// PUT /daily-plan/templates/{id}
+/// Update one of the user's templates
+async fn update_template(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdatePlanTemplateRequest>,
+) -> Result<Json<TemplateWrapper>, AppError> {
+    if let Some(name) = &req.name {
+        validate_template_name(name)?;
+    }
+    if let Some(items) = &req.items {
+        validate_template_items(items)?;
+    }
+
+    let template = PlanTemplateRepo::update(&state.db, user.id, id, &req).await?;
+    Ok(Json(TemplateWrapper { template }))
+}
+
+/// DELETE /daily-plan/templates/{id}
+/// Delete one of the user's templates
+async fn delete_template(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    PlanTemplateRepo::delete(&state.db, user.id, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /daily-plan/templates/{id}/apply
+/// Add a template's items to a day's plan
+async fn apply_template(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<ApplyPlanTemplateRequest>,
+) -> Result<Json<PlanWrapper>, AppError> {
+    let plan = PlanTemplateRepo::apply(&state.db, user.id, id, &req).await?;
+    Ok(Json(PlanWrapper { plan: Some(plan) }))
+}
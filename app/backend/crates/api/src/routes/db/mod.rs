@@ -3,7 +3,5 @@
 //! These modules contain database access code that is closely tied
 //! to specific routes and wasn't migrated to the main db/ folder.
 
-pub mod market_models;
-pub mod market_repos;
 pub mod user_settings_models;
 pub mod user_settings_repos;
@@ -10,23 +10,49 @@ use super::user_settings_models::{UserSetting, UpdateSettingRequest};
 
 pub struct UserSettingsRepo;
 
+pub const USER_SETTINGS_GET_ALL: &str = r#"
+    SELECT id, user_id, key, value, updated_at, created_at
+    FROM user_settings
+    WHERE user_id = $1
+    ORDER BY key ASC
+"#;
+
+pub const USER_SETTINGS_GET_ONE: &str = r#"
+    SELECT id, user_id, key, value, updated_at, created_at
+    FROM user_settings
+    WHERE user_id = $1 AND key = $2
+"#;
+
+pub const USER_SETTINGS_UPSERT: &str = r#"
+    INSERT INTO user_settings (id, user_id, key, value, updated_at, created_at)
+    VALUES (
+      gen_random_uuid(),
+      $1,
+      $2,
+      $3,
+      $4,
+      $4
+    )
+    ON CONFLICT (user_id, key) DO UPDATE
+    SET value = $3, updated_at = $4
+    RETURNING id, user_id, key, value, updated_at, created_at
+"#;
+
+pub const USER_SETTINGS_DELETE: &str = r#"
+    DELETE FROM user_settings
+    WHERE user_id = $1 AND key = $2
+"#;
+
 impl UserSettingsRepo {
     /// Get all settings for a user
     pub async fn get_all(
         pool: &PgPool,
         user_id: Uuid,
     ) -> Result<Vec<UserSetting>, SqlxError> {
-        sqlx::query_as::<_, UserSetting>(
-            r#"
-            SELECT id, user_id, key, value, updated_at, created_at
-            FROM user_settings
-            WHERE user_id = $1
-            ORDER BY key ASC
-            "#,
-        )
-        .bind(user_id)
-        .fetch_all(pool)
-        .await
+        sqlx::query_as::<_, UserSetting>(USER_SETTINGS_GET_ALL)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await
     }
 
     /// Get a single setting by key
@@ -35,17 +61,11 @@ impl UserSettingsRepo {
         user_id: Uuid,
         key: &str,
     ) -> Result<Option<UserSetting>, SqlxError> {
-        sqlx::query_as::<_, UserSetting>(
-            r#"
-            SELECT id, user_id, key, value, updated_at, created_at
-            FROM user_settings
-            WHERE user_id = $1 AND key = $2
-            "#,
-        )
-        .bind(user_id)
-        .bind(key)
-        .fetch_optional(pool)
-        .await
+        sqlx::query_as::<_, UserSetting>(USER_SETTINGS_GET_ONE)
+            .bind(user_id)
+            .bind(key)
+            .fetch_optional(pool)
+            .await
     }
 
     /// Upsert a setting (insert or update)
@@ -56,28 +76,13 @@ impl UserSettingsRepo {
     ) -> Result<UserSetting, SqlxError> {
         let now = chrono::Utc::now();
         
-        sqlx::query_as::<_, UserSetting>(
-            r#"
-            INSERT INTO user_settings (id, user_id, key, value, updated_at, created_at)
-            VALUES (
-              gen_random_uuid(),
-              $1,
-              $2,
-              $3,
-              $4,
-              $4
-            )
-            ON CONFLICT (user_id, key) DO UPDATE
-            SET value = $3, updated_at = $4
-            RETURNING id, user_id, key, value, updated_at, created_at
-            "#,
-        )
-        .bind(user_id)
-        .bind(&req.key)
-        .bind(&req.value)
-        .bind(now)
-        .fetch_one(pool)
-        .await
+        sqlx::query_as::<_, UserSetting>(USER_SETTINGS_UPSERT)
+            .bind(user_id)
+            .bind(&req.key)
+            .bind(&req.value)
+            .bind(now)
+            .fetch_one(pool)
+            .await
     }
 
     /// Delete a setting
@@ -86,16 +91,11 @@ impl UserSettingsRepo {
         user_id: Uuid,
         key: &str,
     ) -> Result<bool, SqlxError> {
-        let result = sqlx::query(
-            r#"
-            DELETE FROM user_settings
-            WHERE user_id = $1 AND key = $2
-            "#,
-        )
-        .bind(user_id)
-        .bind(key)
-        .execute(pool)
-        .await?;
+        let result = sqlx::query(USER_SETTINGS_DELETE)
+            .bind(user_id)
+            .bind(key)
+            .execute(pool)
+            .await?;
 
         Ok(result.rows_affected() > 0)
     }
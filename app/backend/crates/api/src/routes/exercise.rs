@@ -279,6 +279,8 @@ async fn list_sessions(
     Ok(Json(SessionsListWrapper { data: result }))
 }
 
+pub const START_SESSION: &str = "SELECT name FROM workouts WHERE id = $1";
+
 /// POST /exercise/sessions
 /// Start a workout session
 async fn start_session(
@@ -298,7 +300,7 @@ async fn start_session(
 
     // Get workout name if provided
     let workout_name: Option<String> = if let Some(wid) = session.workout_id {
-        sqlx::query_scalar("SELECT name FROM workouts WHERE id = $1")
+        sqlx::query_scalar(START_SESSION)
             .bind(wid)
             .fetch_optional(&state.db)
             .await?
@@ -323,6 +325,11 @@ async fn start_session(
     }))
 }
 
+pub const GET_ACTIVE_SESSION_WORKOUTS: &str = "SELECT name FROM workouts WHERE id = $1";
+
+pub const GET_ACTIVE_SESSION_SETS_LOGGED: &str =
+    "SELECT COUNT(*) FROM exercise_sets WHERE session_id = $1";
+
 /// GET /exercise/sessions/active
 /// Get active workout session
 async fn get_active_session(
@@ -335,7 +342,7 @@ async fn get_active_session(
         Some(s) => {
             // Get workout name
             let workout_name: Option<String> = if let Some(wid) = s.workout_id {
-                sqlx::query_scalar("SELECT name FROM workouts WHERE id = $1")
+                sqlx::query_scalar(GET_ACTIVE_SESSION_WORKOUTS)
                     .bind(wid)
                     .fetch_optional(&state.db)
                     .await?
@@ -344,11 +351,10 @@ async fn get_active_session(
             };
 
             // Get sets count
-            let sets_logged: Option<i64> =
-                sqlx::query_scalar("SELECT COUNT(*) FROM exercise_sets WHERE session_id = $1")
-                    .bind(s.id)
-                    .fetch_one(&state.db)
-                    .await?;
+            let sets_logged: Option<i64> = sqlx::query_scalar(GET_ACTIVE_SESSION_SETS_LOGGED)
+                .bind(s.id)
+                .fetch_one(&state.db)
+                .await?;
 
             Ok(Json(serde_json::json!({
                 "data": {
@@ -1,12 +1,15 @@
 //! Goals routes
 //!
-//! Routes for goal management.
+//! Routes for goal management: goals with weighted milestones and linked
+//! habits, quests and books that their progress rolls up from.
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use axum::{
     extract::{Extension, Path, Query, State},
-    routing::{get, post},
+    http::StatusCode,
+    routing::{delete, get, post, put},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
@@ -22,8 +25,17 @@ use crate::state::AppState;
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(list_goals).post(create_goal))
-        .route("/{id}", get(get_goal))
+        .route("/reorder", post(reorder_goals))
+        .route("/{id}", get(get_goal).put(update_goal).delete(delete_goal))
+        .route("/{id}/archive", post(archive_goal))
+        .route("/{id}/restore", post(restore_goal))
         .route("/{id}/milestones", post(add_milestone))
+        .route("/{id}/links", post(add_link))
+        .route("/{id}/links/{link_id}", delete(remove_link))
+        .route(
+            "/milestones/{id}",
+            put(update_milestone).delete(delete_milestone),
+        )
         .route("/milestones/{id}/complete", post(complete_milestone))
 }
 
@@ -34,6 +46,8 @@ pub fn router() -> Router<Arc<AppState>> {
 #[derive(Debug, Deserialize)]
 pub struct ListGoalsQuery {
     pub status: Option<String>,
+    #[serde(default)]
+    pub archived: bool,
 }
 
 // ============================================================================
@@ -60,18 +74,33 @@ struct CompleteMilestoneWrapper {
     data: CompleteMilestoneResult,
 }
 
+// ============================================================================
+// VALIDATION
+// ============================================================================
+
+fn validate_title(title: &str) -> Result<(), AppError> {
+    if title.trim().is_empty() {
+        return Err(AppError::Validation("Title is required".into()));
+    }
+    Ok(())
+}
+
 // ============================================================================
 // HANDLERS
 // ============================================================================
 
 /// GET /goals
-/// List goals for user
+/// List goals for user, or the archived ones
 async fn list_goals(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Query(query): Query<ListGoalsQuery>,
 ) -> Result<Json<GoalsListWrapper>, AppError> {
-    let result = GoalsRepo::list(&state.db, user.id, query.status.as_deref()).await?;
+    let result = if query.archived {
+        GoalsRepo::list_archived(&state.db, user.id).await?
+    } else {
+        GoalsRepo::list(&state.db, user.id, query.status.as_deref()).await?
+    };
 
     Ok(Json(GoalsListWrapper { data: result }))
 }
@@ -83,29 +112,16 @@ async fn create_goal(
     Extension(user): Extension<User>,
     Json(req): Json<CreateGoalRequest>,
 ) -> Result<Json<GoalResponseWrapper>, AppError> {
+    validate_title(&req.title)?;
     let goal = GoalsRepo::create(&state.db, user.id, &req).await?;
 
     Ok(Json(GoalResponseWrapper {
-        data: GoalResponse {
-            id: goal.id,
-            title: goal.title,
-            description: goal.description,
-            category: goal.category,
-            target_date: goal.target_date,
-            started_at: goal.started_at,
-            completed_at: goal.completed_at,
-            status: goal.status,
-            progress: goal.progress,
-            priority: goal.priority,
-            milestones: vec![],
-            total_milestones: 0,
-            completed_milestones: 0,
-        },
+        data: GoalResponse::new(goal, vec![], vec![]),
     }))
 }
 
 /// GET /goals/:id
-/// Get a goal with milestones
+/// Get a goal with milestones and links
 async fn get_goal(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
@@ -117,6 +133,74 @@ async fn get_goal(
     Ok(Json(GoalResponseWrapper { data: goal }))
 }
 
+/// PUT /goals/:id
+/// Update a goal
+async fn update_goal(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateGoalRequest>,
+) -> Result<Json<GoalResponseWrapper>, AppError> {
+    if let Some(title) = &req.title {
+        validate_title(title)?;
+    }
+    let goal = GoalsRepo::update(&state.db, id, user.id, &req).await?;
+
+    Ok(Json(GoalResponseWrapper { data: goal }))
+}
+
+/// DELETE /goals/:id
+/// Delete a goal with its milestones and links
+async fn delete_goal(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    GoalsRepo::delete(&state.db, id, user.id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /goals/:id/archive
+/// Archive a goal, keeping its milestones and links
+async fn archive_goal(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<GoalResponseWrapper>, AppError> {
+    let goal = GoalsRepo::set_archived(&state.db, id, user.id, true).await?;
+
+    Ok(Json(GoalResponseWrapper { data: goal }))
+}
+
+/// POST /goals/:id/restore
+/// Bring back an archived goal
+async fn restore_goal(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<GoalResponseWrapper>, AppError> {
+    let goal = GoalsRepo::set_archived(&state.db, id, user.id, false).await?;
+
+    Ok(Json(GoalResponseWrapper { data: goal }))
+}
+
+/// POST /goals/reorder
+/// Sort goals in the order given
+async fn reorder_goals(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(req): Json<ReorderGoalsRequest>,
+) -> Result<Json<GoalsListWrapper>, AppError> {
+    let unique: HashSet<&Uuid> = req.ids.iter().collect();
+    if req.ids.is_empty() || unique.len() != req.ids.len() {
+        return Err(AppError::Validation("ids must list each goal once".into()));
+    }
+    let result = GoalsRepo::reorder(&state.db, user.id, &req.ids).await?;
+
+    Ok(Json(GoalsListWrapper { data: result }))
+}
+
 /// POST /goals/:id/milestones
 /// Add milestone to goal
 async fn add_milestone(
@@ -125,11 +209,40 @@ async fn add_milestone(
     Path(id): Path<Uuid>,
     Json(req): Json<CreateMilestoneRequest>,
 ) -> Result<Json<MilestoneWrapper>, AppError> {
+    validate_title(&req.title)?;
     let milestone = GoalsRepo::add_milestone(&state.db, id, user.id, &req).await?;
 
     Ok(Json(MilestoneWrapper { data: milestone }))
 }
 
+/// PUT /goals/milestones/:id
+/// Update a milestone; returns its goal with the new progress
+async fn update_milestone(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateMilestoneRequest>,
+) -> Result<Json<GoalResponseWrapper>, AppError> {
+    if let Some(title) = &req.title {
+        validate_title(title)?;
+    }
+    let goal = GoalsRepo::update_milestone(&state.db, id, user.id, &req).await?;
+
+    Ok(Json(GoalResponseWrapper { data: goal }))
+}
+
+/// DELETE /goals/milestones/:id
+/// Delete a milestone; returns its goal with the new progress
+async fn delete_milestone(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<GoalResponseWrapper>, AppError> {
+    let goal = GoalsRepo::delete_milestone(&state.db, id, user.id).await?;
+
+    Ok(Json(GoalResponseWrapper { data: goal }))
+}
+
 /// POST /goals/milestones/:id/complete
 /// Complete a milestone
 async fn complete_milestone(
@@ -141,3 +254,31 @@ async fn complete_milestone(
 
     Ok(Json(CompleteMilestoneWrapper { data: result }))
 }
+
+/// POST /goals/:id/links
+/// Count a habit, quest or book toward a goal
+async fn add_link(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<CreateGoalLinkRequest>,
+) -> Result<(StatusCode, Json<GoalResponseWrapper>), AppError> {
+    let goal = GoalsRepo::add_link(&state.db, id, user.id, &req).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(GoalResponseWrapper { data: goal }),
+    ))
+}
+
+/// DELETE /goals/:id/links/:link_id
+/// Unlink an item from a goal
+async fn remove_link(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((id, link_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<GoalResponseWrapper>, AppError> {
+    let goal = GoalsRepo::remove_link(&state.db, id, link_id, user.id).await?;
+
+    Ok(Json(GoalResponseWrapper { data: goal }))
+}
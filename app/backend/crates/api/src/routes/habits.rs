@@ -1,14 +1,18 @@
 //! Habits routes
 //!
-//! Routes for habit tracking.
+//! Routes for habit tracking: habits on daily, weekday or weekly schedules,
+//! their completions and history.
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use axum::{
-    extract::{Extension, Path, State},
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
     routing::{get, post},
     Json, Router,
 };
+use chrono::{Duration, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -22,7 +26,39 @@ use crate::state::AppState;
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(list_habits).post(create_habit))
-        .route("/{id}/complete", post(complete_habit))
+        .route("/reorder", post(reorder_habits))
+        .route(
+            "/{id}",
+            get(get_habit).put(update_habit).delete(delete_habit),
+        )
+        .route(
+            "/{id}/complete",
+            post(complete_habit).delete(uncomplete_habit),
+        )
+        .route("/{id}/archive", post(archive_habit))
+        .route("/{id}/restore", post(restore_habit))
+        .route("/{id}/history", get(habit_history))
+}
+
+// ============================================================================
+// QUERY PARAMS
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct ListHabitsQuery {
+    #[serde(default)]
+    archived: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct UncompleteQuery {
+    date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoryQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
 }
 
 // ============================================================================
@@ -44,17 +80,27 @@ struct CompleteResultWrapper {
     data: CompleteHabitResult,
 }
 
+#[derive(Serialize)]
+struct HistoryWrapper {
+    data: HabitHistoryResponse,
+}
+
 // ============================================================================
 // HANDLERS
 // ============================================================================
 
 /// GET /habits
-/// List active habits with today's completion status
+/// List active habits with today's completion status, or archived ones
 async fn list_habits(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
+    Query(query): Query<ListHabitsQuery>,
 ) -> Result<Json<HabitsListWrapper>, AppError> {
-    let result = HabitsRepo::list_active(&state.db, user.id).await?;
+    let result = if query.archived {
+        HabitsRepo::list_archived(&state.db, user.id).await?
+    } else {
+        HabitsRepo::list_active(&state.db, user.id).await?
+    };
 
     Ok(Json(HabitsListWrapper { data: result }))
 }
@@ -68,25 +114,89 @@ async fn create_habit(
 ) -> Result<Json<HabitResponseWrapper>, AppError> {
     let habit = HabitsRepo::create(&state.db, user.id, &req).await?;
 
+    let today = Utc::now().date_naive();
     Ok(Json(HabitResponseWrapper {
-        data: HabitResponse {
-            id: habit.id,
-            name: habit.name,
-            description: habit.description,
-            frequency: habit.frequency,
-            target_count: habit.target_count,
-            icon: habit.icon,
-            color: habit.color,
-            is_active: habit.is_active,
-            current_streak: habit.current_streak,
-            longest_streak: habit.longest_streak,
-            last_completed_at: habit.last_completed_at,
-            completed_today: false,
-            sort_order: habit.sort_order,
-        },
+        data: HabitResponse::new(habit, 0, today),
     }))
 }
 
+/// GET /habits/:id
+/// Get a habit with today's completion status
+async fn get_habit(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<HabitResponseWrapper>, AppError> {
+    let habit = HabitsRepo::get(&state.db, id, user.id).await?;
+
+    Ok(Json(HabitResponseWrapper { data: habit }))
+}
+
+/// PUT /habits/:id
+/// Update a habit
+async fn update_habit(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateHabitRequest>,
+) -> Result<Json<HabitResponseWrapper>, AppError> {
+    let habit = HabitsRepo::update(&state.db, id, user.id, &req).await?;
+
+    Ok(Json(HabitResponseWrapper { data: habit }))
+}
+
+/// DELETE /habits/:id
+/// Delete a habit and its history
+async fn delete_habit(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    HabitsRepo::delete(&state.db, id, user.id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /habits/:id/archive
+/// Archive a habit, keeping its history
+async fn archive_habit(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<HabitResponseWrapper>, AppError> {
+    let habit = HabitsRepo::set_active(&state.db, id, user.id, false).await?;
+
+    Ok(Json(HabitResponseWrapper { data: habit }))
+}
+
+/// POST /habits/:id/restore
+/// Bring back an archived habit
+async fn restore_habit(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<HabitResponseWrapper>, AppError> {
+    let habit = HabitsRepo::set_active(&state.db, id, user.id, true).await?;
+
+    Ok(Json(HabitResponseWrapper { data: habit }))
+}
+
+/// POST /habits/reorder
+/// Sort active habits in the order given
+async fn reorder_habits(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(req): Json<ReorderHabitsRequest>,
+) -> Result<Json<HabitsListWrapper>, AppError> {
+    let unique: HashSet<&Uuid> = req.ids.iter().collect();
+    if req.ids.is_empty() || unique.len() != req.ids.len() {
+        return Err(AppError::Validation("ids must list each habit once".into()));
+    }
+    let result = HabitsRepo::reorder(&state.db, user.id, &req.ids).await?;
+
+    Ok(Json(HabitsListWrapper { data: result }))
+}
+
 #[derive(Debug, Deserialize)]
 struct CompleteHabitBody {
     notes: Option<String>,
@@ -105,3 +215,41 @@ async fn complete_habit(
 
     Ok(Json(CompleteResultWrapper { data: result }))
 }
+
+/// DELETE /habits/:id/complete
+/// Undo the latest completion, today's unless a date is given
+async fn uncomplete_habit(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<UncompleteQuery>,
+) -> Result<Json<HabitResponseWrapper>, AppError> {
+    let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+    let habit = HabitsRepo::uncomplete_habit(&state.db, id, user.id, date).await?;
+
+    Ok(Json(HabitResponseWrapper { data: habit }))
+}
+
+/// GET /habits/:id/history
+/// Completions per day for a heatmap; the last year by default
+async fn habit_history(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<HistoryWrapper>, AppError> {
+    let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = query
+        .from
+        .unwrap_or(to - Duration::days(DEFAULT_HISTORY_DAYS - 1));
+    let days = (to - from).num_days() + 1;
+    if !(1..=MAX_HISTORY_DAYS).contains(&days) {
+        return Err(AppError::Validation(format!(
+            "History covers 1 to {} days, from on or before to",
+            MAX_HISTORY_DAYS
+        )));
+    }
+    let history = HabitsRepo::history(&state.db, id, user.id, from, to).await?;
+
+    Ok(Json(HistoryWrapper { data: history }))
+}
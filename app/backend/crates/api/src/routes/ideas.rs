@@ -5,24 +5,57 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Extension, Path, State},
-    routing::get,
+    extract::{Extension, Multipart, Path, Query, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    routing::{get, post},
     Json, Router,
 };
 use serde::Serialize;
 use uuid::Uuid;
 
+use crate::db::idea_attachment_models::*;
+use crate::db::idea_attachment_repos::{already_promoted, IdeaAttachmentRepo};
 use crate::db::models::User;
 use crate::db::platform_models::*;
 use crate::db::platform_repos::IdeasRepo;
+use crate::db::reference_models::ReferenceTrack;
+use crate::db::revision_models::*;
+use crate::db::revision_repos::RevisionRepo;
 use crate::error::AppError;
+use crate::shared::http::conditional::{if_match_version, version_etag};
 use crate::state::AppState;
+use crate::storage::audio::probe_duration;
+use crate::storage::{
+    generate_blob_key, get_extension_from_mime, BlobCategory, SignedUrlResponse, StorageClient,
+    UploadRequest,
+};
 
 /// Create ideas routes
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(list_ideas).post(create_idea))
         .route("/{id}", get(get_idea).put(update_idea).delete(delete_idea))
+        .route("/{id}/revisions", get(list_revisions))
+        .route("/{id}/revisions/diff", get(diff_revisions))
+        .route("/{id}/revisions/{version}", get(get_revision))
+        .route("/{id}/revisions/{version}/restore", post(restore_revision))
+        .route(
+            "/{id}/attachments",
+            get(list_attachments).post(upload_attachment),
+        )
+        .route(
+            "/{id}/attachments/{attachment_id}",
+            get(get_attachment).delete(delete_attachment),
+        )
+        .route(
+            "/{id}/attachments/{attachment_id}/url",
+            get(get_attachment_url),
+        )
+        .route(
+            "/{id}/attachments/{attachment_id}/promote",
+            post(promote_attachment),
+        )
 }
 
 // ============================================================================
@@ -39,6 +72,41 @@ struct IdeasListWrapper {
     data: IdeasListResponse,
 }
 
+#[derive(Serialize)]
+struct RevisionsWrapper {
+    data: Vec<RevisionSummary>,
+}
+
+#[derive(Serialize)]
+struct RevisionWrapper {
+    data: ContentRevision,
+}
+
+#[derive(Serialize)]
+struct RevisionDiffWrapper {
+    data: RevisionDiff,
+}
+
+#[derive(Serialize)]
+struct AttachmentWrapper {
+    data: IdeaAttachment,
+}
+
+#[derive(Serialize)]
+struct AttachmentsWrapper {
+    data: Vec<IdeaAttachment>,
+}
+
+#[derive(Serialize)]
+struct SignedUrlWrapper {
+    data: SignedUrlResponse,
+}
+
+#[derive(Serialize)]
+struct TrackWrapper {
+    data: ReferenceTrack,
+}
+
 #[derive(Serialize)]
 struct DeleteSuccessWrapper {
     data: DeleteSuccess,
@@ -53,25 +121,26 @@ struct DeleteSuccess {
 // HANDLERS
 // ============================================================================
 
-/// GET /ideas
-/// List all ideas
+/// GET /ideas?key=&bpm_min=&bpm_max=&mood=&genre=&time_signature=
+/// List ideas, optionally filtered by their music metadata
 async fn list_ideas(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
+    Query(filter): Query<IdeaFilter>,
 ) -> Result<Json<IdeasListWrapper>, AppError> {
-    let result = IdeasRepo::list(&state.db, user.id).await?;
+    let result = IdeasRepo::list(&state.db, user.id, &filter).await?;
     Ok(Json(IdeasListWrapper { data: result }))
 }
 
 /// GET /ideas/:id
-/// Get a single idea
+/// Get a single idea, with its version as the ETag
 async fn get_idea(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(id): Path<Uuid>,
-) -> Result<Json<IdeaWrapper>, AppError> {
+) -> Result<impl IntoResponse, AppError> {
     let idea = IdeasRepo::get(&state.db, id, user.id).await?;
-    Ok(Json(IdeaWrapper { data: idea }))
+    Ok((version_etag(idea.version), Json(IdeaWrapper { data: idea })))
 }
 
 /// POST /ideas
@@ -90,26 +159,277 @@ async fn create_idea(
 }
 
 /// PUT /ideas/:id
-/// Update an idea
+/// Update an idea; 409 when `If-Match` names an older version
 async fn update_idea(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
     Json(req): Json<UpdateIdeaRequest>,
-) -> Result<Json<IdeaWrapper>, AppError> {
-    let idea = IdeasRepo::update(&state.db, id, user.id, &req).await?;
-    Ok(Json(IdeaWrapper { data: idea }))
+) -> Result<impl IntoResponse, AppError> {
+    let expected = if_match_version(&headers)?;
+    let idea = IdeasRepo::update(&state.db, id, user.id, &req, expected).await?;
+    Ok((version_etag(idea.version), Json(IdeaWrapper { data: idea })))
 }
 
 /// DELETE /ideas/:id
-/// Delete an idea
+/// Delete an idea and its attached audio
 async fn delete_idea(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<DeleteSuccessWrapper>, AppError> {
+    let attachments = IdeaAttachmentRepo::list(&state.db, user.id, id).await?;
     IdeasRepo::delete(&state.db, id, user.id).await?;
+    if let Some(storage) = &state.storage {
+        for attachment in &attachments {
+            remove_stored(storage, &attachment.r2_key).await;
+        }
+    }
     Ok(Json(DeleteSuccessWrapper {
         data: DeleteSuccess { success: true },
     }))
 }
+
+/// GET /ideas/:id/revisions
+/// Saved versions, newest first
+async fn list_revisions(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RevisionsWrapper>, AppError> {
+    // 404 for ideas that are not the user's
+    IdeasRepo::get(&state.db, id, user.id).await?;
+    let revisions = RevisionRepo::list(&state.db, user.id, RevisionEntity::Idea, id).await?;
+    Ok(Json(RevisionsWrapper { data: revisions }))
+}
+
+/// GET /ideas/:id/revisions/:version
+/// A saved version with its content
+async fn get_revision(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((id, version)): Path<(Uuid, i32)>,
+) -> Result<Json<RevisionWrapper>, AppError> {
+    let revision = RevisionRepo::get(&state.db, user.id, RevisionEntity::Idea, id, version).await?;
+    Ok(Json(RevisionWrapper { data: revision }))
+}
+
+/// GET /ideas/:id/revisions/diff?from=&to=
+/// Unified diff of the content of two versions
+async fn diff_revisions(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<RevisionDiffQuery>,
+) -> Result<Json<RevisionDiffWrapper>, AppError> {
+    let diff = RevisionRepo::diff(
+        &state.db,
+        user.id,
+        RevisionEntity::Idea,
+        id,
+        query.from,
+        query.to,
+    )
+    .await?;
+    Ok(Json(RevisionDiffWrapper { data: diff }))
+}
+
+/// POST /ideas/:id/revisions/:version/restore
+/// Save a version's content again as the newest version
+async fn restore_revision(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((id, version)): Path<(Uuid, i32)>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    let expected = if_match_version(&headers)?;
+    let idea = IdeasRepo::restore(&state.db, id, user.id, version, expected).await?;
+    Ok((version_etag(idea.version), Json(IdeaWrapper { data: idea })))
+}
+
+/// GET /ideas/:id/attachments
+/// Audio attached to an idea
+async fn list_attachments(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<AttachmentsWrapper>, AppError> {
+    IdeasRepo::get(&state.db, id, user.id).await?;
+    let attachments = IdeaAttachmentRepo::list(&state.db, user.id, id).await?;
+    Ok(Json(AttachmentsWrapper { data: attachments }))
+}
+
+/// POST /ideas/:id/attachments
+/// Attach audio (multipart `file`, optional `kind`); its duration is read
+/// from the file
+async fn upload_attachment(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> Result<Json<AttachmentWrapper>, AppError> {
+    let storage = storage(&state)?;
+    IdeasRepo::get(&state.db, id, user.id).await?;
+
+    let mut file_data: Option<Vec<u8>> = None;
+    let mut filename: Option<String> = None;
+    let mut mime_type: Option<String> = None;
+    let mut kind = AttachmentKind::VoiceMemo;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Multipart error: {}", e)))?
+    {
+        match field.name().unwrap_or("") {
+            "file" => {
+                filename = field.file_name().map(|s| s.to_string());
+                mime_type = field.content_type().map(|s| s.to_string());
+                file_data = Some(
+                    field
+                        .bytes()
+                        .await
+                        .map_err(|e| AppError::BadRequest(format!("File read error: {}", e)))?
+                        .to_vec(),
+                );
+            }
+            "kind" => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|e| AppError::BadRequest(format!("Read error: {}", e)))?;
+                kind = text.trim().parse().map_err(AppError::Validation)?;
+            }
+            _ => {}
+        }
+    }
+
+    let data = file_data.ok_or_else(|| AppError::BadRequest("No file provided".to_string()))?;
+    let filename = filename.unwrap_or_else(|| "memo".to_string());
+    let mime_type = mime_type.unwrap_or_default();
+    if !mime_type.starts_with("audio/") {
+        return Err(AppError::Validation(
+            "Only audio files can be attached to ideas".to_string(),
+        ));
+    }
+    let duration_seconds = probe_duration(&data);
+
+    let upload = storage
+        .upload(UploadRequest {
+            user_id: user.id,
+            filename: filename.clone(),
+            mime_type: mime_type.clone(),
+            data,
+            metadata: None,
+        })
+        .await?;
+    let new = NewAttachment {
+        kind,
+        r2_key: &upload.key,
+        filename: &filename,
+        mime_type: &mime_type,
+        size_bytes: upload.size_bytes as i64,
+        duration_seconds,
+    };
+    match IdeaAttachmentRepo::create(&state.db, user.id, id, &new).await {
+        Ok(attachment) => Ok(Json(AttachmentWrapper { data: attachment })),
+        Err(e) => {
+            remove_stored(storage, &upload.key).await;
+            Err(e)
+        }
+    }
+}
+
+/// GET /ideas/:id/attachments/:attachment_id
+/// A single attachment
+async fn get_attachment(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((id, attachment_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<AttachmentWrapper>, AppError> {
+    let attachment = IdeaAttachmentRepo::get(&state.db, user.id, id, attachment_id).await?;
+    Ok(Json(AttachmentWrapper { data: attachment }))
+}
+
+/// GET /ideas/:id/attachments/:attachment_id/url
+/// Signed URL to play or download an attachment
+async fn get_attachment_url(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((id, attachment_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<SignedUrlWrapper>, AppError> {
+    let storage = storage(&state)?;
+    let attachment = IdeaAttachmentRepo::get(&state.db, user.id, id, attachment_id).await?;
+    let url = storage
+        .generate_signed_download_url(&attachment.r2_key)
+        .await?;
+    Ok(Json(SignedUrlWrapper { data: url }))
+}
+
+/// DELETE /ideas/:id/attachments/:attachment_id
+/// Remove an attachment and its file; a track promoted from it is kept
+async fn delete_attachment(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((id, attachment_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<DeleteSuccessWrapper>, AppError> {
+    let attachment = IdeaAttachmentRepo::delete(&state.db, user.id, id, attachment_id).await?;
+    if let Some(storage) = &state.storage {
+        remove_stored(storage, &attachment.r2_key).await;
+    }
+    Ok(Json(DeleteSuccessWrapper {
+        data: DeleteSuccess { success: true },
+    }))
+}
+
+/// POST /ideas/:id/attachments/:attachment_id/promote
+/// Turn an attachment into a reference track with a copy of its audio
+async fn promote_attachment(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((id, attachment_id)): Path<(Uuid, Uuid)>,
+    req: Option<Json<PromoteAttachmentRequest>>,
+) -> Result<Json<TrackWrapper>, AppError> {
+    let storage = storage(&state)?;
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    let attachment = IdeaAttachmentRepo::get(&state.db, user.id, id, attachment_id).await?;
+    // Checked again under a lock; this avoids copying audio for nothing
+    if let Some(track_id) = attachment.reference_track_id {
+        return Err(already_promoted(track_id));
+    }
+
+    let data = storage.get_by_key(&attachment.r2_key).await?;
+    let extension = get_extension_from_mime(&attachment.mime_type);
+    let (_, track_key) = generate_blob_key(&user.id, BlobCategory::Audio, extension);
+    storage
+        .put_by_key(&track_key, &data, &attachment.mime_type)
+        .await?;
+
+    match IdeaAttachmentRepo::promote(&state.db, user.id, id, attachment_id, &track_key, &req).await
+    {
+        Ok(track) => Ok(Json(TrackWrapper { data: track })),
+        Err(e) => {
+            remove_stored(storage, &track_key).await;
+            Err(e)
+        }
+    }
+}
+
+// ============================================================================
+// HELPERS
+// ============================================================================
+
+fn storage(state: &AppState) -> Result<&StorageClient, AppError> {
+    state
+        .storage
+        .as_ref()
+        .ok_or_else(|| AppError::Config("Storage not configured".to_string()))
+}
+
+/// Best-effort removal of a file no row refers to any more
+async fn remove_stored(storage: &StorageClient, key: &str) {
+    if let Err(e) = storage.delete_by_key(key).await {
+        tracing::warn!("Failed to delete R2 object {}: {}", key, e);
+    }
+}
@@ -6,7 +6,7 @@ use std::sync::Arc;
 
 use axum::{
     extract::{Extension, Path, Query, State},
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
 use serde::Deserialize;
@@ -22,7 +22,10 @@ use crate::state::AppState;
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(list_inbox).post(create_inbox_item))
+        .route("/triage", post(triage_inbox))
+        .route("/source", get(get_conversion_source))
         .route("/{id}", get(get_inbox_item).put(update_inbox_item).delete(delete_inbox_item))
+        .route("/{id}/convert", post(convert_inbox_item))
 }
 
 // ============================================================================
@@ -47,6 +50,12 @@ fn default_page_size() -> i64 {
     50
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SourceQuery {
+    pub target_type: String,
+    pub target_id: Uuid,
+}
+
 // ============================================================================
 // RESPONSE WRAPPERS
 // ============================================================================
@@ -66,6 +75,16 @@ struct DeleteWrapper {
     data: DeleteInboxResponse,
 }
 
+#[derive(serde::Serialize)]
+struct ConvertWrapper {
+    data: ConvertInboxResponse,
+}
+
+#[derive(serde::Serialize)]
+struct TriageWrapper {
+    data: TriageInboxResponse,
+}
+
 // ============================================================================
 // HANDLERS
 // ============================================================================
@@ -143,3 +162,54 @@ async fn delete_inbox_item(
         },
     }))
 }
+
+/// POST /user/inbox/:id/convert
+/// Convert inbox item into a quest, habit, event, milestone, idea or entry
+async fn convert_inbox_item(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<ConvertInboxRequest>,
+) -> Result<Json<ConvertWrapper>, AppError> {
+    let response = InboxRepo::convert(&state.db, user.id, id, &req).await?;
+    Ok(Json(ConvertWrapper { data: response }))
+}
+
+/// POST /user/inbox/triage
+/// Apply one action to many inbox items
+async fn triage_inbox(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(req): Json<TriageInboxRequest>,
+) -> Result<Json<TriageWrapper>, AppError> {
+    if req.ids.is_empty() {
+        return Err(AppError::Validation("No inbox items given".into()));
+    }
+    if req.ids.len() > MAX_TRIAGE_ITEMS {
+        return Err(AppError::Validation(format!(
+            "At most {} inbox items can be triaged at once",
+            MAX_TRIAGE_ITEMS
+        )));
+    }
+
+    let affected = InboxRepo::triage(&state.db, user.id, &req).await?;
+    Ok(Json(TriageWrapper {
+        data: TriageInboxResponse {
+            action: req.action,
+            affected,
+        },
+    }))
+}
+
+/// GET /user/inbox/source
+/// Inbox item a quest, habit, event, milestone, idea or entry came from
+async fn get_conversion_source(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Query(query): Query<SourceQuery>,
+) -> Result<Json<ItemWrapper>, AppError> {
+    let item = InboxRepo::source_for(&state.db, user.id, &query.target_type, query.target_id).await?;
+    Ok(Json(ItemWrapper {
+        data: InboxResponse::from(item),
+    }))
+}
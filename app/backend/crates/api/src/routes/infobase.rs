@@ -6,26 +6,40 @@ use std::sync::Arc;
 
 use axum::{
     extract::{Extension, Path, Query, State},
-    routing::get,
+    http::HeaderMap,
+    response::IntoResponse,
+    routing::{get, post},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::db::infobase_link_models::*;
+use crate::db::infobase_link_repos::InfobaseLinkRepo;
 use crate::db::models::User;
 use crate::db::platform_models::*;
 use crate::db::platform_repos::InfobaseRepo;
+use crate::db::revision_models::*;
+use crate::db::revision_repos::RevisionRepo;
 use crate::error::AppError;
+use crate::shared::http::conditional::{if_match_version, version_etag};
 use crate::state::AppState;
 
 /// Create infobase routes
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(list_entries).post(create_entry))
+        .route("/graph", get(get_graph))
+        .route("/links/broken", get(list_broken_links))
         .route(
             "/{id}",
             get(get_entry).put(update_entry).delete(delete_entry),
         )
+        .route("/{id}/backlinks", get(list_backlinks))
+        .route("/{id}/revisions", get(list_revisions))
+        .route("/{id}/revisions/diff", get(diff_revisions))
+        .route("/{id}/revisions/{version}", get(get_revision))
+        .route("/{id}/revisions/{version}/restore", post(restore_revision))
 }
 
 // ============================================================================
@@ -52,6 +66,36 @@ struct EntriesListWrapper {
     data: InfobaseListResponse,
 }
 
+#[derive(Serialize)]
+struct BacklinksWrapper {
+    data: Vec<Backlink>,
+}
+
+#[derive(Serialize)]
+struct BrokenLinksWrapper {
+    data: Vec<BrokenLink>,
+}
+
+#[derive(Serialize)]
+struct GraphWrapper {
+    data: InfobaseGraph,
+}
+
+#[derive(Serialize)]
+struct RevisionsWrapper {
+    data: Vec<RevisionSummary>,
+}
+
+#[derive(Serialize)]
+struct RevisionWrapper {
+    data: ContentRevision,
+}
+
+#[derive(Serialize)]
+struct RevisionDiffWrapper {
+    data: RevisionDiff,
+}
+
 #[derive(Serialize)]
 struct DeleteSuccessWrapper {
     data: DeleteSuccess,
@@ -85,14 +129,17 @@ async fn list_entries(
 }
 
 /// GET /infobase/:id
-/// Get a single entry
+/// Get a single entry, with its version as the ETag
 async fn get_entry(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(id): Path<Uuid>,
-) -> Result<Json<EntryWrapper>, AppError> {
+) -> Result<impl IntoResponse, AppError> {
     let entry = InfobaseRepo::get(&state.db, id, user.id).await?;
-    Ok(Json(EntryWrapper { data: entry }))
+    Ok((
+        version_etag(entry.version),
+        Json(EntryWrapper { data: entry }),
+    ))
 }
 
 /// POST /infobase
@@ -114,15 +161,20 @@ async fn create_entry(
 }
 
 /// PUT /infobase/:id
-/// Update entry
+/// Update entry; 409 when `If-Match` names an older version
 async fn update_entry(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
     Json(req): Json<UpdateInfobaseEntryRequest>,
-) -> Result<Json<EntryWrapper>, AppError> {
-    let entry = InfobaseRepo::update(&state.db, id, user.id, &req).await?;
-    Ok(Json(EntryWrapper { data: entry }))
+) -> Result<impl IntoResponse, AppError> {
+    let expected = if_match_version(&headers)?;
+    let entry = InfobaseRepo::update(&state.db, id, user.id, &req, expected).await?;
+    Ok((
+        version_etag(entry.version),
+        Json(EntryWrapper { data: entry }),
+    ))
 }
 
 /// DELETE /infobase/:id
@@ -137,3 +189,97 @@ async fn delete_entry(
         data: DeleteSuccess { success: true },
     }))
 }
+
+/// GET /infobase/:id/backlinks
+/// Entries linking to this entry
+async fn list_backlinks(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<BacklinksWrapper>, AppError> {
+    // 404 for entries that are not the user's
+    InfobaseRepo::get(&state.db, id, user.id).await?;
+    let backlinks = InfobaseLinkRepo::backlinks(&state.db, user.id, id).await?;
+    Ok(Json(BacklinksWrapper { data: backlinks }))
+}
+
+/// GET /infobase/graph
+/// Every entry and linked idea or track, with the links between them
+async fn get_graph(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<Json<GraphWrapper>, AppError> {
+    let graph = InfobaseLinkRepo::graph(&state.db, user.id).await?;
+    Ok(Json(GraphWrapper { data: graph }))
+}
+
+/// GET /infobase/links/broken
+/// Links naming an entry, idea or track that does not exist
+async fn list_broken_links(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<Json<BrokenLinksWrapper>, AppError> {
+    let links = InfobaseLinkRepo::broken(&state.db, user.id).await?;
+    Ok(Json(BrokenLinksWrapper { data: links }))
+}
+
+/// GET /infobase/:id/revisions
+/// Saved versions, newest first
+async fn list_revisions(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RevisionsWrapper>, AppError> {
+    // 404 for entries that are not the user's
+    InfobaseRepo::get(&state.db, id, user.id).await?;
+    let revisions = RevisionRepo::list(&state.db, user.id, RevisionEntity::Infobase, id).await?;
+    Ok(Json(RevisionsWrapper { data: revisions }))
+}
+
+/// GET /infobase/:id/revisions/:version
+/// A saved version with its content
+async fn get_revision(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((id, version)): Path<(Uuid, i32)>,
+) -> Result<Json<RevisionWrapper>, AppError> {
+    let revision =
+        RevisionRepo::get(&state.db, user.id, RevisionEntity::Infobase, id, version).await?;
+    Ok(Json(RevisionWrapper { data: revision }))
+}
+
+/// GET /infobase/:id/revisions/diff?from=&to=
+/// Unified diff of the content of two versions
+async fn diff_revisions(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<RevisionDiffQuery>,
+) -> Result<Json<RevisionDiffWrapper>, AppError> {
+    let diff = RevisionRepo::diff(
+        &state.db,
+        user.id,
+        RevisionEntity::Infobase,
+        id,
+        query.from,
+        query.to,
+    )
+    .await?;
+    Ok(Json(RevisionDiffWrapper { data: diff }))
+}
+
+/// POST /infobase/:id/revisions/:version/restore
+/// Save a version's content again as the newest version
+async fn restore_revision(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((id, version)): Path<(Uuid, i32)>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    let expected = if_match_version(&headers)?;
+    let entry = InfobaseRepo::restore(&state.db, id, user.id, version, expected).await?;
+    Ok((
+        version_etag(entry.version),
+        Json(EntryWrapper { data: entry }),
+    ))
+}
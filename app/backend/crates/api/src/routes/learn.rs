@@ -5,7 +5,10 @@
 use std::sync::Arc;
 
 use axum::{
+    body::Body,
     extract::{Extension, Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Redirect, Response},
     routing::{get, post},
     Json, Router,
 };
@@ -13,9 +16,10 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::db::learn_models::*;
-use crate::db::learn_repos::LearnRepo;
+use crate::db::learn_repos::{DrillSessionRepo, LearnRepo};
 use crate::db::models::User;
 use crate::error::AppError;
+use crate::services::drills::{self, AudioSource};
 use crate::state::AppState;
 
 /// Create learn routes
@@ -29,6 +33,19 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/lessons/{id}/start", post(start_lesson))
         .route("/lessons/{id}/complete", post(complete_lesson))
         .route("/drills/{id}/submit", post(submit_drill))
+        .route("/drills/{id}/sessions", post(start_drill_session))
+        .route(
+            "/drill-sessions/{token}/answers",
+            post(answer_drill_question),
+        )
+        .route(
+            "/drill-sessions/{token}/complete",
+            post(complete_drill_session),
+        )
+        .route(
+            "/drill-sessions/{token}/questions/{index}/audio",
+            get(get_drill_question_audio),
+        )
         .route("/review", get(get_review_items))
         .route("/progress", get(get_progress))
 }
@@ -72,6 +89,21 @@ struct DrillResultWrapper {
     data: DrillResultResponse,
 }
 
+#[derive(Serialize)]
+struct DrillSessionWrapper {
+    data: DrillSessionResponse,
+}
+
+#[derive(Serialize)]
+struct DrillAnswerWrapper {
+    data: DrillAnswerResult,
+}
+
+#[derive(Serialize)]
+struct DrillSessionResultWrapper {
+    data: DrillSessionResult,
+}
+
 #[derive(Serialize)]
 struct ReviewWrapper {
     data: ReviewItemsResponse,
@@ -220,6 +252,100 @@ struct SubmitRequest {
     time_seconds: i32,
 }
 
+/// POST /learn/drills/:id/sessions
+/// Start a server-scored drill session
+async fn start_drill_session(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(drill_id): Path<Uuid>,
+) -> Result<Json<DrillSessionWrapper>, AppError> {
+    let (session, questions) = DrillSessionRepo::start(&state.db, user.id, drill_id).await?;
+
+    let questions = questions
+        .iter()
+        .map(|q| DrillQuestionView {
+            index: q.index as i32,
+            prompt: q.prompt.clone(),
+            choices: q.choices.clone(),
+            input_type: q.input_type().to_string(),
+            audio_url: format!(
+                "/api/learn/drill-sessions/{}/questions/{}/audio",
+                session.token, q.index
+            ),
+            time_limit_ms: q.time_limit_ms,
+        })
+        .collect();
+
+    Ok(Json(DrillSessionWrapper {
+        data: DrillSessionResponse {
+            session_token: session.token,
+            drill_id: session.drill_id,
+            drill_type: session.drill_type,
+            difficulty_level: session.difficulty_level,
+            expires_at: session.expires_at,
+            questions,
+        },
+    }))
+}
+
+/// POST /learn/drill-sessions/:token/answers
+/// Answer one question; the server grades it
+async fn answer_drill_question(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(token): Path<String>,
+    Json(req): Json<DrillAnswerRequest>,
+) -> Result<Json<DrillAnswerWrapper>, AppError> {
+    let result = DrillSessionRepo::answer(&state.db, user.id, &token, &req).await?;
+    Ok(Json(DrillAnswerWrapper { data: result }))
+}
+
+/// POST /learn/drill-sessions/:token/complete
+/// Score the session from recorded answers
+async fn complete_drill_session(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(token): Path<String>,
+) -> Result<Json<DrillSessionResultWrapper>, AppError> {
+    let result = DrillSessionRepo::complete(&state.db, user.id, &token).await?;
+    Ok(Json(DrillSessionResultWrapper { data: result }))
+}
+
+/// GET /learn/drill-sessions/:token/questions/:index/audio
+/// Synthesized WAV for a question, or a redirect to a stored clip
+async fn get_drill_question_audio(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((token, index)): Path<(String, usize)>,
+) -> Result<Response, AppError> {
+    let (session, questions) = DrillSessionRepo::load(&state.db, user.id, &token).await?;
+    let question = questions
+        .get(index)
+        .ok_or_else(|| AppError::NotFound("Question not found".to_string()))?;
+
+    if let AudioSource::Clip { r2_key } = &question.audio {
+        let storage = state
+            .storage
+            .as_ref()
+            .ok_or_else(|| AppError::Config("Storage not configured".to_string()))?;
+        let signed = storage.generate_signed_download_url(r2_key).await?;
+        return Ok(Redirect::temporary(&signed.url).into_response());
+    }
+
+    let wav = drills::render_wav(
+        &question.audio,
+        drills::question_seed(session.seed as u64, index),
+    )
+    .ok_or_else(|| AppError::Internal("No audio for question".to_string()))?;
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "audio/wav")
+        .header(header::CACHE_CONTROL, "private, max-age=3600")
+        .body(Body::from(wav))
+        .unwrap())
+}
+
 /// GET /learn/review
 /// Get items due for review
 async fn get_review_items(
@@ -1,6 +1,9 @@
 //! Route modules
 
 pub mod admin;
+pub mod admin_console;
+pub mod admin_flags;
+pub mod admin_onboarding;
 pub mod admin_templates;
 pub mod api;
 pub mod auth;
@@ -22,10 +25,13 @@ pub mod inbox;
 pub mod infobase;
 pub mod learn;
 pub mod market;
+pub mod notifications;
 pub mod onboarding;
+pub mod passkey;
 pub mod quests;
 pub mod reference;
 pub mod references_library;
+pub mod search;
 pub mod settings;
 pub mod sync;
 pub mod today;
@@ -0,0 +1,166 @@
+//! Notifications routes
+//!
+//! Web Push subscriptions and the user's recent reminder deliveries.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db::models::User;
+use crate::db::notification_models::*;
+use crate::db::notification_repos::{NotificationRepo, PushSubscriptionRepo};
+use crate::error::AppError;
+use crate::services::web_push::VapidKeys;
+use crate::services::webauthn::b64url_decode;
+use crate::state::AppState;
+
+/// Create notifications routes
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/vapid-key", get(get_vapid_key))
+        .route(
+            "/push/subscriptions",
+            get(list_push_subscriptions)
+                .post(create_push_subscription)
+                .delete(delete_push_subscription),
+        )
+        .route("/deliveries", get(list_deliveries))
+}
+
+// ============================================================================
+// QUERY PARAMS
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ListDeliveriesQuery {
+    pub limit: Option<i64>,
+}
+
+// ============================================================================
+// RESPONSE WRAPPERS
+// ============================================================================
+
+#[derive(Serialize)]
+struct VapidKeyWrapper {
+    data: VapidKeyResponse,
+}
+
+#[derive(Serialize)]
+struct PushSubscriptionWrapper {
+    data: PushSubscriptionResponse,
+}
+
+#[derive(Serialize)]
+struct PushSubscriptionsListWrapper {
+    data: Vec<PushSubscriptionResponse>,
+}
+
+#[derive(Serialize)]
+struct DeliveriesListWrapper {
+    data: Vec<NotificationDelivery>,
+}
+
+// ============================================================================
+// HANDLERS
+// ============================================================================
+
+/// GET /notifications/vapid-key
+/// Application server key for subscribing to push
+async fn get_vapid_key(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<VapidKeyWrapper>, AppError> {
+    let public_key = match &state.config.notifications.vapid {
+        Some(vapid) => Some(VapidKeys::from_config(vapid)?.public_key().to_string()),
+        None => None,
+    };
+
+    Ok(Json(VapidKeyWrapper {
+        data: VapidKeyResponse { public_key },
+    }))
+}
+
+/// GET /notifications/push/subscriptions
+/// List the user's push subscriptions
+async fn list_push_subscriptions(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<Json<PushSubscriptionsListWrapper>, AppError> {
+    let subscriptions = PushSubscriptionRepo::list_for_user(&state.db, user.id).await?;
+
+    Ok(Json(PushSubscriptionsListWrapper {
+        data: subscriptions.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// POST /notifications/push/subscriptions
+/// Save a browser push subscription
+async fn create_push_subscription(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    headers: HeaderMap,
+    Json(req): Json<CreatePushSubscriptionRequest>,
+) -> Result<(StatusCode, Json<PushSubscriptionWrapper>), AppError> {
+    if state.config.notifications.vapid.is_none() {
+        return Err(AppError::BadRequest(
+            "Push notifications are not configured".to_string(),
+        ));
+    }
+    if !req.endpoint.starts_with("https://") || req.endpoint.len() > 2048 {
+        return Err(AppError::BadRequest(
+            "Push endpoint must be an https URL".to_string(),
+        ));
+    }
+    if b64url_decode(&req.keys.p256dh).ok().map(|k| k.len()) != Some(65) {
+        return Err(AppError::BadRequest("Invalid p256dh key".to_string()));
+    }
+    if b64url_decode(&req.keys.auth).ok().map(|k| k.len()) != Some(16) {
+        return Err(AppError::BadRequest("Invalid auth secret".to_string()));
+    }
+
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok());
+
+    let subscription = PushSubscriptionRepo::upsert(&state.db, user.id, &req, user_agent).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(PushSubscriptionWrapper {
+            data: subscription.into(),
+        }),
+    ))
+}
+
+/// DELETE /notifications/push/subscriptions
+/// Remove a browser push subscription
+async fn delete_push_subscription(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(req): Json<DeletePushSubscriptionRequest>,
+) -> Result<StatusCode, AppError> {
+    if !PushSubscriptionRepo::delete(&state.db, user.id, &req.endpoint).await? {
+        return Err(AppError::NotFound(
+            "Push subscription not found".to_string(),
+        ));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /notifications/deliveries
+/// Recent reminder deliveries and their status
+async fn list_deliveries(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Query(query): Query<ListDeliveriesQuery>,
+) -> Result<Json<DeliveriesListWrapper>, AppError> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    let deliveries = NotificationRepo::list_recent(&state.db, user.id, limit).await?;
+
+    Ok(Json(DeliveriesListWrapper { data: deliveries }))
+}
@@ -0,0 +1,358 @@
+//! Passkey routes
+//!
+//! WebAuthn registration and sign-in under /auth/passkey.
+//! Registration links a passkey to the signed-in account, so users can still
+//! sign in when their OAuth provider is unavailable.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::Response,
+    routing::{delete, get, post},
+    Extension, Json, Router,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::auth::{SessionResponse, SessionUser};
+use crate::db::passkey_models::*;
+use crate::db::passkey_repos::{AuthenticatorRepo, WebauthnChallengeRepo};
+use crate::db::repos::RbacRepo;
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::{create_session_cookie, AuthContext};
+use crate::services::feature_flags::FlagSubject;
+use crate::services::webauthn::{self, RelyingParty};
+use crate::services::AuthService;
+use crate::shared::audit::{write_audit, AuditEventType};
+use crate::state::AppState;
+
+/// Create passkey routes
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/register/options", post(registration_options))
+        .route("/register", post(register))
+        .route("/login/options", post(login_options))
+        .route("/login", post(login))
+        .route("/credentials", get(list_credentials))
+        .route("/credentials/{id}", delete(delete_credential))
+}
+
+#[derive(Serialize)]
+struct CredentialsWrapper {
+    data: Vec<PasskeyResponse>,
+}
+
+#[derive(Serialize)]
+struct CredentialWrapper {
+    data: PasskeyResponse,
+}
+
+/// Signed-in, non-bypass user required for managing passkeys
+fn require_user(auth: Option<Extension<AuthContext>>) -> AppResult<AuthContext> {
+    let auth = auth.ok_or(AppError::Unauthorized)?.0;
+    if auth.is_dev_bypass {
+        return Err(AppError::Forbidden);
+    }
+    Ok(auth)
+}
+
+/// POST /auth/passkey/register/options
+/// Start registering a passkey for the signed-in user
+async fn registration_options(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+) -> AppResult<Json<serde_json::Value>> {
+    let auth = require_user(auth)?;
+    let rp = RelyingParty::from_config(&state.config);
+
+    let challenge = webauthn::generate_challenge();
+    WebauthnChallengeRepo::insert(
+        &state.db,
+        &challenge,
+        CEREMONY_REGISTRATION,
+        Some(auth.user_id),
+    )
+    .await?;
+
+    // Prevent registering the same authenticator twice
+    let existing = AuthenticatorRepo::list_for_user(&state.db, auth.user_id).await?;
+    let exclude: Vec<_> = existing
+        .iter()
+        .map(|a| {
+            serde_json::json!({
+                "type": "public-key",
+                "id": a.credential_id,
+                "transports": a.transports,
+            })
+        })
+        .collect();
+
+    let params: Vec<_> = webauthn::SUPPORTED_ALGORITHMS
+        .iter()
+        .map(|alg| serde_json::json!({ "type": "public-key", "alg": alg }))
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "challenge": challenge,
+        "rp": { "id": rp.id, "name": rp.name },
+        "user": {
+            "id": webauthn::b64url_encode(auth.user_id.as_bytes()),
+            "name": auth.email,
+            "displayName": auth.name,
+        },
+        "pubKeyCredParams": params,
+        "timeout": webauthn::CEREMONY_TIMEOUT_MS,
+        "attestation": "none",
+        "excludeCredentials": exclude,
+        "authenticatorSelection": {
+            "residentKey": "required",
+            "requireResidentKey": true,
+            "userVerification": "preferred",
+        },
+    })))
+}
+
+/// POST /auth/passkey/register
+/// Verify a registration response and store the passkey
+async fn register(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Json(req): Json<PasskeyRegistrationRequest>,
+) -> AppResult<Json<CredentialWrapper>> {
+    let auth = require_user(auth)?;
+    let rp = RelyingParty::from_config(&state.config);
+
+    let client_data_json = webauthn::b64url_decode(&req.response.client_data_json)?;
+    let client_data = webauthn::verify_client_data(&rp, &client_data_json, "webauthn.create")?;
+
+    let challenge =
+        WebauthnChallengeRepo::take(&state.db, &client_data.challenge, CEREMONY_REGISTRATION)
+            .await?
+            .ok_or_else(|| AppError::BadRequest("Unknown or expired challenge".to_string()))?;
+    if challenge.user_id != Some(auth.user_id) {
+        return Err(AppError::Forbidden);
+    }
+
+    let attestation_object = webauthn::b64url_decode(&req.response.attestation_object)?;
+    let verified = webauthn::verify_registration(&rp, &attestation_object)?;
+
+    let credential_id = webauthn::b64url_encode(&verified.credential_id);
+    if credential_id != req.id.trim_end_matches('=') {
+        return Err(AppError::BadRequest("Credential ID mismatch".to_string()));
+    }
+    if AuthenticatorRepo::find_by_credential_id(&state.db, &credential_id)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::BadRequest(
+            "Passkey is already registered".to_string(),
+        ));
+    }
+
+    let name = req
+        .name
+        .map(|n| n.trim().chars().take(100).collect::<String>())
+        .filter(|n| !n.is_empty());
+
+    let authenticator = AuthenticatorRepo::create(
+        &state.db,
+        CreateAuthenticatorInput {
+            user_id: auth.user_id,
+            credential_id,
+            public_key: webauthn::b64url_encode(&verified.public_key),
+            counter: verified.sign_count as i64,
+            device_type: verified.device_type.to_string(),
+            backed_up: verified.backed_up,
+            transports: req.response.transports,
+            name,
+        },
+    )
+    .await?;
+
+    write_audit(
+        state.db.clone(),
+        AuditEventType::ResourceCreated,
+        Some(auth.user_id),
+        "Registered passkey",
+        Some("authenticator"),
+        Some(authenticator.id),
+    );
+
+    Ok(Json(CredentialWrapper {
+        data: authenticator.into(),
+    }))
+}
+
+/// POST /auth/passkey/login/options
+/// Start a passkey sign-in (discoverable credentials, no username needed)
+async fn login_options(State(state): State<Arc<AppState>>) -> AppResult<Json<serde_json::Value>> {
+    let rp = RelyingParty::from_config(&state.config);
+
+    let challenge = webauthn::generate_challenge();
+    WebauthnChallengeRepo::insert(&state.db, &challenge, CEREMONY_AUTHENTICATION, None).await?;
+
+    Ok(Json(serde_json::json!({
+        "challenge": challenge,
+        "rpId": rp.id,
+        "timeout": webauthn::CEREMONY_TIMEOUT_MS,
+        "allowCredentials": [],
+        "userVerification": "preferred",
+    })))
+}
+
+/// POST /auth/passkey/login
+/// Verify an assertion and create a session
+async fn login(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<PasskeyAssertionRequest>,
+) -> AppResult<Response> {
+    let rp = RelyingParty::from_config(&state.config);
+
+    let client_data_json = webauthn::b64url_decode(&req.response.client_data_json)?;
+    let client_data = webauthn::verify_client_data(&rp, &client_data_json, "webauthn.get")?;
+
+    WebauthnChallengeRepo::take(&state.db, &client_data.challenge, CEREMONY_AUTHENTICATION)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Unknown or expired challenge".to_string()))?;
+
+    let authenticator =
+        AuthenticatorRepo::find_by_credential_id(&state.db, req.id.trim_end_matches('='))
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+    if let Some(ref handle) = req.response.user_handle {
+        if webauthn::b64url_decode(handle)? != authenticator.user_id.as_bytes() {
+            return Err(AppError::Unauthorized);
+        }
+    }
+
+    let public_key = webauthn::b64url_decode(&authenticator.credential_public_key)?;
+    let authenticator_data = webauthn::b64url_decode(&req.response.authenticator_data)?;
+    let signature = webauthn::b64url_decode(&req.response.signature)?;
+
+    let verified = webauthn::verify_assertion(
+        &rp,
+        &public_key,
+        authenticator.counter as u32,
+        &client_data_json,
+        &authenticator_data,
+        &signature,
+    )
+    .inspect_err(|e| {
+        tracing::warn!(
+            authenticator_id = %authenticator.id,
+            user_id = %authenticator.user_id,
+            error = %e,
+            "Passkey assertion rejected"
+        );
+    })?;
+
+    let recorded = AuthenticatorRepo::record_use(
+        &state.db,
+        authenticator.id,
+        authenticator.counter,
+        verified.sign_count as i64,
+        verified.backed_up,
+    )
+    .await?;
+    if !recorded {
+        return Err(AppError::Unauthorized);
+    }
+
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string());
+
+    let (user, session) = AuthService::authenticate_passkey(
+        &state.db,
+        authenticator.user_id,
+        authenticator.id,
+        user_agent,
+        None,
+        30, // 30 day session
+    )
+    .await?;
+
+    let entitlements = RbacRepo::get_entitlements(&state.db, user.id).await?;
+
+    let cookie = create_session_cookie(
+        &session.token,
+        &state.config.auth.cookie_domain,
+        state.config.auth.session_ttl_seconds,
+    );
+
+    tracing::info!(
+        user_id = %user.id,
+        authenticator_id = %authenticator.id,
+        "User authenticated via passkey"
+    );
+
+    let flags = state.flags.evaluate_all(Some(FlagSubject {
+        user_id: user.id,
+        role: &user.role,
+        entitlements: &entitlements,
+    }));
+    let body = SessionResponse {
+        user: Some(SessionUser {
+            id: user.id.to_string(),
+            email: user.email,
+            name: user.name,
+            image: user.image,
+            role: user.role,
+            entitlements,
+            age_verified: user.age_verified,
+            tos_accepted: user.tos_accepted,
+        }),
+        flags,
+    };
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::SET_COOKIE, cookie)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(axum::body::Body::from(
+            serde_json::to_string(&body).map_err(|e| AppError::Internal(e.to_string()))?,
+        ))
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// GET /auth/passkey/credentials
+/// List the signed-in user's passkeys
+async fn list_credentials(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+) -> AppResult<Json<CredentialsWrapper>> {
+    let auth = require_user(auth)?;
+    let rows = AuthenticatorRepo::list_for_user(&state.db, auth.user_id).await?;
+    Ok(Json(CredentialsWrapper {
+        data: rows.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// DELETE /auth/passkey/credentials/{id}
+/// Remove one of the signed-in user's passkeys
+async fn delete_credential(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    let auth = require_user(auth)?;
+    if !AuthenticatorRepo::delete_for_user(&state.db, auth.user_id, id).await? {
+        return Err(AppError::NotFound("Passkey not found".to_string()));
+    }
+
+    write_audit(
+        state.db.clone(),
+        AuditEventType::ResourceDeleted,
+        Some(auth.user_id),
+        "Removed passkey",
+        Some("authenticator"),
+        Some(id),
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
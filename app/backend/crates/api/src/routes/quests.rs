@@ -14,7 +14,7 @@ use uuid::Uuid;
 
 use crate::db::models::User;
 use crate::db::quests_models::*;
-use crate::db::quests_repos::QuestsRepo;
+use crate::db::quests_repos::{QuestsRepo, UniversalQuestRepo};
 use crate::error::AppError;
 use crate::state::AppState;
 
@@ -22,6 +22,8 @@ use crate::state::AppState;
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(list_quests).post(create_quest))
+        .route("/universal", get(list_universal_quests))
+        .route("/universal/{id}/claim", post(claim_universal_quest))
         .route("/{id}", get(get_quest))
         .route("/{id}/accept", post(accept_quest))
         .route("/{id}/complete", post(complete_quest))
@@ -56,6 +58,16 @@ struct CompleteQuestWrapper {
     data: CompleteQuestResult,
 }
 
+#[derive(Serialize)]
+struct UniversalQuestsWrapper {
+    data: Vec<UniversalQuestWithProgress>,
+}
+
+#[derive(Serialize)]
+struct ClaimQuestWrapper {
+    data: ClaimQuestResult,
+}
+
 // ============================================================================
 // HANDLERS
 // ============================================================================
@@ -132,3 +144,26 @@ async fn abandon_quest(
 
     Ok(Json(QuestResponseWrapper { data: quest.into() }))
 }
+
+/// GET /quests/universal
+/// List universal quests with the user's progress this period
+async fn list_universal_quests(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<Json<UniversalQuestsWrapper>, AppError> {
+    let quests = UniversalQuestRepo::list_for_user(&state.db, user.id).await?;
+
+    Ok(Json(UniversalQuestsWrapper { data: quests }))
+}
+
+/// POST /quests/universal/:id/claim
+/// Claim the reward for a completed universal quest
+async fn claim_universal_quest(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ClaimQuestWrapper>, AppError> {
+    let result = UniversalQuestRepo::claim(&state.db, user.id, id).await?;
+
+    Ok(Json(ClaimQuestWrapper { data: result }))
+}
@@ -0,0 +1,136 @@
+//! Search routes
+//!
+//! Full-text search across ideas, infobase entries, references, inbox items,
+//! reference tracks and track annotations.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+
+use crate::db::models::User;
+use crate::db::search_models::*;
+use crate::db::search_repos::{search_tsquery, SearchCursor, SearchRepo};
+use crate::error::AppError;
+use crate::shared::db::pagination::{CursorPaginated, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::state::AppState;
+
+/// Create search routes
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/", get(search))
+}
+
+// ============================================================================
+// RESPONSE WRAPPERS
+// ============================================================================
+
+#[derive(Serialize)]
+struct SearchWrapper {
+    data: CursorPaginated<SearchResult>,
+}
+
+// ============================================================================
+// HELPERS
+// ============================================================================
+
+/// Split a comma-separated parameter
+fn split_list(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Requested types, all when none are given
+fn parse_types(types: Option<&str>) -> Result<Vec<String>, AppError> {
+    let types = split_list(types);
+    if let Some(unknown) = types.iter().find(|t| !SEARCH_TYPES.contains(&t.as_str())) {
+        return Err(AppError::Validation(format!(
+            "Unknown search type {}; expected one of {}",
+            unknown,
+            SEARCH_TYPES.join(", ")
+        )));
+    }
+    if types.is_empty() {
+        Ok(SEARCH_TYPES.iter().map(|t| t.to_string()).collect())
+    } else {
+        Ok(types)
+    }
+}
+
+// ============================================================================
+// HANDLERS
+// ============================================================================
+
+/// GET /search?q=&types=&tags=&cursor=&limit=
+/// Ranked results with highlighted snippets; words match as prefixes
+async fn search(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<SearchWrapper>, AppError> {
+    let tsquery = search_tsquery(&query.q)
+        .ok_or_else(|| AppError::Validation("Search query is required".into()))?;
+    let types = parse_types(query.types.as_deref())?;
+    let tags = split_list(query.tags.as_deref());
+    let after = query
+        .cursor
+        .as_deref()
+        .map(SearchCursor::decode)
+        .transpose()?;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let mut results = SearchRepo::search(
+        &state.db,
+        user.id,
+        &tsquery,
+        &types,
+        &tags,
+        after.as_ref(),
+        limit + 1,
+    )
+    .await?;
+
+    let next_cursor = if results.len() as i64 > limit {
+        results.truncate(limit as usize);
+        results.last().map(|last| {
+            SearchCursor {
+                rank: last.rank,
+                entity_type: last.entity_type.clone(),
+                id: last.id,
+            }
+            .encode()
+        })
+    } else {
+        None
+    };
+
+    Ok(Json(SearchWrapper {
+        data: CursorPaginated::new(results, next_cursor, None),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_types() {
+        assert_eq!(parse_types(None).unwrap().len(), SEARCH_TYPES.len());
+        assert_eq!(
+            parse_types(Some("idea, track,")).unwrap(),
+            vec!["idea".to_string(), "track".to_string()]
+        );
+        assert!(parse_types(Some("idea,tweets")).is_err());
+    }
+}
@@ -1,7 +1,8 @@
-//! Sync Routes - Lightweight polling endpoints
+//! Sync Routes - Lightweight polling endpoints and live event stream
 //!
-//! These endpoints return minimal data optimized for 30-second polling.
-//! Designed to be fast (<50ms) and bandwidth-efficient.
+//! The poll endpoints return minimal data optimized for 30-second polling.
+//! Designed to be fast (<50ms) and bandwidth-efficient. `/stream` pushes the
+//! same data as server-sent events when it changes, with polling as fallback.
 //!
 //! Use cases:
 //! - UI badge counts (inbox, quests)
@@ -12,24 +13,40 @@
 //! DESIGN PRINCIPLES:
 //! 1. Minimal response size - only what's needed for UI indicators
 //! 2. Fast queries - indexed lookups, no joins where possible
-//! 3. Cache-friendly - includes ETag/Last-Modified for conditional requests
+//! 3. Cache-friendly - ETag/If-None-Match answered from the change feed, before any data query
 //! 4. Single endpoint - one request to get all poll data
 
+use std::collections::VecDeque;
+use std::convert::Infallible;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use axum::{
-    extract::State,
-    http::{header, StatusCode},
-    response::Response,
+    extract::{Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Response,
+    },
     routing::get,
-    Extension, Json, Router,
+    Extension, Router,
 };
-use serde::Serialize;
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use tokio::sync::broadcast::{
+    self,
+    error::{RecvError, TryRecvError},
+};
 use uuid::Uuid;
 
+use crate::db::inbox_models::InboxResponse;
+use crate::db::inbox_repos::InboxRepo;
+use crate::db::sync_models::*;
+use crate::db::sync_repos::SyncEventRepo;
 use crate::error::AppError;
 use crate::middleware::auth::AuthContext;
+use crate::services::sync_hub::SyncSignal;
 use crate::state::AppState;
 
 /// Create sync routes
@@ -42,8 +59,30 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/badges", get(get_badges))
         .route("/focus-status", get(get_focus_status))
         .route("/plan-status", get(get_plan_status))
+        // Server-sent events replacing polling
+        .route("/stream", get(stream))
 }
 
+/// Time-derived fields (overdue quests, expired focus sessions, today's
+/// habits) change without a write, so ETags also roll over on this period
+const ETAG_WINDOW_SECONDS: i64 = 300;
+
+/// Delay that lets a burst of changes (one transaction touching several tables) settle
+const STREAM_COALESCE_DELAY: Duration = Duration::from_millis(250);
+
+/// Most missed events replayed on reconnect before sending a snapshot instead
+const STREAM_REPLAY_LIMIT: i64 = 500;
+
+/// Keep-alive comment interval, below common proxy idle timeouts
+const STREAM_KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// How long a writing transaction may hold a sync event before committing
+///
+/// Event ids are taken at insert, so an event can become visible after ones
+/// with higher ids were already sent. Replays re-read this window behind the
+/// cursor, and streams remember what they sent within it.
+const STREAM_COMMIT_GRACE: Duration = Duration::from_secs(30);
+
 // ============================================
 // Combined Poll Response
 // ============================================
@@ -63,6 +102,8 @@ pub struct PollResponse {
     pub server_time: String,
     /// ETag for conditional polling
     pub etag: String,
+    /// Latest change seen; pass to /stream as `cursor` to pick up from here
+    pub cursor: i64,
 }
 
 /// Gamification progress for HUD
@@ -103,56 +144,37 @@ pub struct PlanStatusData {
     pub percent_complete: f32,
 }
 
+/// Query parameters for the event stream
+#[derive(Deserialize)]
+struct StreamQuery {
+    /// Resume after this event id (`Last-Event-ID` takes precedence)
+    cursor: Option<i64>,
+}
+
 // ============================================
 // Combined Poll Endpoint
 // ============================================
 
 /// GET /api/sync/poll
-/// 
+///
 /// Single endpoint that returns all data needed for UI polling.
 /// Designed for 30-second interval polling.
-/// 
+///
 /// Response includes ETag header for conditional requests.
-/// Clients should use If-None-Match to avoid redundant data transfer.
+/// Clients should use If-None-Match; an unchanged version gets 304 without
+/// running the data queries.
 async fn poll_all(
     State(state): State<Arc<AppState>>,
     Extension(auth): Extension<AuthContext>,
+    headers: HeaderMap,
 ) -> Result<Response, AppError> {
-    let user_id = auth.user_id;
-    
-    // Fetch all data in parallel
-    let (progress, badges, focus, plan) = tokio::try_join!(
-        fetch_progress(&state.db, user_id),
-        fetch_badges(&state.db, user_id),
-        fetch_focus_status(&state.db, user_id),
-        fetch_plan_status(&state.db, user_id),
-    )?;
-    
-    let server_time = chrono::Utc::now().to_rfc3339();
-    
-    // Generate ETag from content hash
-    let etag = generate_etag(&progress, &badges, &focus, &plan);
-    
-    let response = PollResponse {
-        progress,
-        badges,
-        focus,
-        plan,
-        server_time,
-        etag: etag.clone(),
-    };
-    
-    // Build response with cache headers
-    let body = serde_json::to_string(&response)
-        .map_err(|e| AppError::Internal(e.to_string()))?;
-    
-    Ok(Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, "application/json")
-        .header(header::ETAG, format!("\"{}\"", etag))
-        .header(header::CACHE_CONTROL, "private, max-age=10")
-        .body(axum::body::Body::from(body))
-        .map_err(|e| AppError::Internal(e.to_string()))?)
+    let version = SyncVersion::load(&state.db, auth.user_id).await?;
+    if version.matches(&headers) {
+        return version.not_modified();
+    }
+
+    let response = fetch_poll(&state.db, auth.user_id, &version).await?;
+    version.respond(&response)
 }
 
 // ============================================
@@ -163,63 +185,369 @@ async fn poll_all(
 async fn get_progress(
     State(state): State<Arc<AppState>>,
     Extension(auth): Extension<AuthContext>,
-) -> Result<Json<ProgressData>, AppError> {
-    let data = fetch_progress(&state.db, auth.user_id).await?;
-    Ok(Json(data))
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let version = SyncVersion::load(&state.db, auth.user_id).await?;
+    if version.matches(&headers) {
+        return version.not_modified();
+    }
+    version.respond(&fetch_progress(&state.db, auth.user_id).await?)
 }
 
 /// GET /api/sync/badges
 async fn get_badges(
     State(state): State<Arc<AppState>>,
     Extension(auth): Extension<AuthContext>,
-) -> Result<Json<BadgeData>, AppError> {
-    let data = fetch_badges(&state.db, auth.user_id).await?;
-    Ok(Json(data))
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let version = SyncVersion::load(&state.db, auth.user_id).await?;
+    if version.matches(&headers) {
+        return version.not_modified();
+    }
+    version.respond(&fetch_badges(&state.db, auth.user_id).await?)
 }
 
 /// GET /api/sync/focus-status
 async fn get_focus_status(
     State(state): State<Arc<AppState>>,
     Extension(auth): Extension<AuthContext>,
-) -> Result<Json<FocusStatusData>, AppError> {
-    let data = fetch_focus_status(&state.db, auth.user_id).await?;
-    Ok(Json(data))
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let version = SyncVersion::load(&state.db, auth.user_id).await?;
+    if version.matches(&headers) {
+        return version.not_modified();
+    }
+    version.respond(&fetch_focus_status(&state.db, auth.user_id).await?)
 }
 
 /// GET /api/sync/plan-status
 async fn get_plan_status(
     State(state): State<Arc<AppState>>,
     Extension(auth): Extension<AuthContext>,
-) -> Result<Json<PlanStatusData>, AppError> {
-    let data = fetch_plan_status(&state.db, auth.user_id).await?;
-    Ok(Json(data))
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let version = SyncVersion::load(&state.db, auth.user_id).await?;
+    if version.matches(&headers) {
+        return version.not_modified();
+    }
+    version.respond(&fetch_plan_status(&state.db, auth.user_id).await?)
+}
+
+// ============================================
+// Event Stream
+// ============================================
+
+/// GET /api/sync/stream
+///
+/// Server-sent events for the signed-in user. Opens with a `snapshot` event
+/// (the /poll payload), then sends `progress`, `badges`, `focus` and `plan`
+/// sections as they change, and an `inbox` event for each new inbox item.
+///
+/// Every event id is a cursor: reconnecting with `Last-Event-ID` (sent by
+/// EventSource automatically) or `?cursor=` replays only what changed since,
+/// falling back to a fresh snapshot once those changes have been pruned.
+async fn stream(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    headers: HeaderMap,
+    Query(query): Query<StreamQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let cursor = headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+        .or(query.cursor);
+
+    // Subscribe before the first read so no change falls in between
+    let session = StreamSession {
+        db: state.db.clone(),
+        user_id: auth.user_id,
+        receiver: state.sync.subscribe(),
+        cursor,
+        sent: VecDeque::new(),
+        started: false,
+    };
+
+    let events = stream::unfold(session, StreamSession::next_batch)
+        .flat_map(|batch| stream::iter(batch.into_iter().map(Ok)));
+
+    Sse::new(events).keep_alive(KeepAlive::new().interval(STREAM_KEEP_ALIVE))
+}
+
+/// One open stream connection
+struct StreamSession {
+    db: PgPool,
+    user_id: Uuid,
+    receiver: broadcast::Receiver<SyncSignal>,
+    /// Highest event id sent (or requested on reconnect)
+    cursor: Option<i64>,
+    /// Ids sent within the last `STREAM_COMMIT_GRACE`, oldest first
+    sent: VecDeque<(Instant, i64)>,
+    started: bool,
+}
+
+impl StreamSession {
+    /// Wait for the next events to send; `None` ends the stream
+    ///
+    /// On a database error the stream ends and the client reconnects with
+    /// its last event id, so nothing is lost.
+    async fn next_batch(mut self) -> Option<(Vec<Event>, Self)> {
+        if !self.started {
+            self.started = true;
+            return match self.resume().await {
+                Ok(events) => Some((events, self)),
+                Err(e) => {
+                    tracing::warn!(user_id = %self.user_id, "Sync stream failed to start: {}", e);
+                    None
+                }
+            };
+        }
+
+        loop {
+            let mut signals = vec![match self.receiver.recv().await {
+                Ok(signal) => signal,
+                Err(RecvError::Lagged(_)) => SyncSignal::Resync,
+                Err(RecvError::Closed) => return None,
+            }];
+
+            tokio::time::sleep(STREAM_COALESCE_DELAY).await;
+            loop {
+                match self.receiver.try_recv() {
+                    Ok(signal) => signals.push(signal),
+                    Err(TryRecvError::Lagged(_)) => signals.push(SyncSignal::Resync),
+                    Err(_) => break,
+                }
+            }
+
+            let result = if signals.iter().any(|s| matches!(s, SyncSignal::Resync)) {
+                self.resume().await
+            } else {
+                let user_id = self.user_id;
+                let events = signals
+                    .into_iter()
+                    .filter_map(|signal| match signal {
+                        SyncSignal::Event(event) if event.user_id == user_id => Some(event),
+                        _ => None,
+                    })
+                    .collect();
+                self.deliver(events).await
+            };
+
+            match result {
+                Ok(events) if events.is_empty() => continue,
+                Ok(events) => return Some((events, self)),
+                Err(e) => {
+                    tracing::warn!(user_id = %self.user_id, "Sync stream failed: {}", e);
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// Replay changes after the cursor, or send a snapshot when it can't be resumed
+    async fn resume(&mut self) -> Result<Vec<Event>, AppError> {
+        if let Some(cursor) = self.cursor {
+            if SyncEventRepo::cursor_is_resumable(&self.db, cursor).await? {
+                let missed = SyncEventRepo::list_since(
+                    &self.db,
+                    self.user_id,
+                    cursor,
+                    STREAM_COMMIT_GRACE.as_secs_f64(),
+                    STREAM_REPLAY_LIMIT,
+                )
+                .await?;
+                if (missed.len() as i64) < STREAM_REPLAY_LIMIT {
+                    return self.deliver(missed).await;
+                }
+            }
+        }
+        self.snapshot().await
+    }
+
+    /// Full state, as returned by /poll
+    async fn snapshot(&mut self) -> Result<Vec<Event>, AppError> {
+        // The cursor is read first, so changes made during the reads are sent again rather than lost
+        let version = SyncVersion::load(&self.db, self.user_id).await?;
+        let poll = fetch_poll(&self.db, self.user_id, &version).await?;
+        self.cursor = Some(version.cursor);
+        Ok(vec![sse_event("snapshot", version.cursor, &poll)?])
+    }
+
+    /// Send the sections touched by `events`, with current data
+    ///
+    /// Events below the cursor are still sent unless they already were: they
+    /// committed late, or were re-read from the grace window.
+    async fn deliver(&mut self, events: Vec<SyncEvent>) -> Result<Vec<Event>, AppError> {
+        let now = Instant::now();
+        while self
+            .sent
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) > STREAM_COMMIT_GRACE)
+        {
+            self.sent.pop_front();
+        }
+
+        let mut fresh = Vec::new();
+        for event in events {
+            if !self.sent.iter().any(|(_, id)| *id == event.id) {
+                self.sent.push_back((now, event.id));
+                fresh.push(event);
+            }
+        }
+        let events = fresh;
+        let Some(newest) = events.iter().map(|e| e.id).max() else {
+            return Ok(Vec::new());
+        };
+        // A late event never moves the cursor back
+        let latest = self.cursor.map_or(newest, |cursor| cursor.max(newest));
+        let changed = |kind: &str| events.iter().any(|e| e.kind == kind);
+
+        let mut out = Vec::new();
+        let new_items = events
+            .iter()
+            .filter(|e| e.kind == SYNC_KIND_INBOX)
+            .filter_map(|e| e.ref_id);
+        for item_id in new_items {
+            match InboxRepo::get(&self.db, self.user_id, item_id).await {
+                Ok(item) => out.push(sse_event(
+                    SYNC_KIND_INBOX,
+                    latest,
+                    &InboxResponse::from(item),
+                )?),
+                // Already deleted again
+                Err(AppError::NotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if changed(SYNC_KIND_PROGRESS) {
+            let data = fetch_progress(&self.db, self.user_id).await?;
+            out.push(sse_event(SYNC_KIND_PROGRESS, latest, &data)?);
+        }
+        // Inbox changes move the unread badge
+        if changed(SYNC_KIND_BADGES) || changed(SYNC_KIND_INBOX) {
+            let data = fetch_badges(&self.db, self.user_id).await?;
+            out.push(sse_event(SYNC_KIND_BADGES, latest, &data)?);
+        }
+        if changed(SYNC_KIND_FOCUS) {
+            let data = fetch_focus_status(&self.db, self.user_id).await?;
+            out.push(sse_event(SYNC_KIND_FOCUS, latest, &data)?);
+        }
+        if changed(SYNC_KIND_PLAN) {
+            let data = fetch_plan_status(&self.db, self.user_id).await?;
+            out.push(sse_event(SYNC_KIND_PLAN, latest, &data)?);
+        }
+
+        self.cursor = Some(latest);
+        Ok(out)
+    }
+}
+
+/// Build a named SSE event carrying `cursor` as its id
+fn sse_event<T: Serialize>(kind: &str, cursor: i64, data: &T) -> Result<Event, AppError> {
+    Event::default()
+        .event(kind)
+        .id(cursor.to_string())
+        .json_data(data)
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+// ============================================
+// Conditional Requests
+// ============================================
+
+/// Version of a user's synced state, shared by every poll endpoint as its ETag
+struct SyncVersion {
+    cursor: i64,
+    etag: String,
+}
+
+impl SyncVersion {
+    /// Current version: one indexed lookup on the change feed
+    async fn load(pool: &PgPool, user_id: Uuid) -> Result<Self, AppError> {
+        let cursor = SyncEventRepo::latest_id(pool, user_id).await?;
+        Ok(Self {
+            cursor,
+            etag: version_etag(cursor, chrono::Utc::now().timestamp()),
+        })
+    }
+
+    /// Whether the request's If-None-Match already names this version
+    fn matches(&self, headers: &HeaderMap) -> bool {
+        etag_matches(headers, &self.etag)
+    }
+
+    fn not_modified(&self) -> Result<Response, AppError> {
+        Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .header(header::ETAG, format!("\"{}\"", self.etag))
+            .header(header::CACHE_CONTROL, "private, max-age=10")
+            .body(axum::body::Body::empty())
+            .map_err(|e| AppError::Internal(e.to_string()))
+    }
+
+    /// JSON response tagged with this version
+    fn respond<T: Serialize>(&self, data: &T) -> Result<Response, AppError> {
+        let body = serde_json::to_string(data).map_err(|e| AppError::Internal(e.to_string()))?;
+
+        Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::ETAG, format!("\"{}\"", self.etag))
+            .header(header::CACHE_CONTROL, "private, max-age=10")
+            .body(axum::body::Body::from(body))
+            .map_err(|e| AppError::Internal(e.to_string()))
+    }
 }
 
 // ============================================
 // Data Fetchers (optimized queries)
 // ============================================
 
+async fn fetch_poll(
+    pool: &PgPool,
+    user_id: Uuid,
+    version: &SyncVersion,
+) -> Result<PollResponse, AppError> {
+    // Fetch all data in parallel
+    let (progress, badges, focus, plan) = tokio::try_join!(
+        fetch_progress(pool, user_id),
+        fetch_badges(pool, user_id),
+        fetch_focus_status(pool, user_id),
+        fetch_plan_status(pool, user_id),
+    )?;
+
+    Ok(PollResponse {
+        progress,
+        badges,
+        focus,
+        plan,
+        server_time: chrono::Utc::now().to_rfc3339(),
+        etag: version.etag.clone(),
+        cursor: version.cursor,
+    })
+}
+
+pub const FETCH_PROGRESS: &str = r#"
+    SELECT
+        COALESCE(up.current_level, 1) as level,
+        COALESCE(up.total_xp, 0) as total_xp,
+        COALESCE(uw.coins, 0) as coins,
+        COALESCE(us.current_streak, 0) as streak_days
+    FROM users u
+    LEFT JOIN user_progress up ON u.id = up.user_id
+    LEFT JOIN user_wallet uw ON u.id = uw.user_id
+    LEFT JOIN user_streaks us ON u.id = us.user_id AND us.streak_type = 'daily'
+    WHERE u.id = $1
+"#;
+
 async fn fetch_progress(pool: &PgPool, user_id: Uuid) -> Result<ProgressData, AppError> {
     // Single query to get user progress
-    let row = sqlx::query_as::<_, (i32, i64, i32, i32)>(
-        r#"
-        SELECT 
-            COALESCE(up.current_level, 1) as level,
-            COALESCE(up.total_xp, 0) as total_xp,
-            COALESCE(uw.coins, 0) as coins,
-            COALESCE(us.current_streak, 0) as streak_days
-        FROM users u
-        LEFT JOIN user_progress up ON u.id = up.user_id
-        LEFT JOIN user_wallet uw ON u.id = uw.user_id
-        LEFT JOIN user_streaks us ON u.id = us.user_id AND us.streak_type = 'daily'
-        WHERE u.id = $1
-        "#
-    )
-    .bind(user_id)
-    .fetch_optional(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?
-    .unwrap_or((1, 0, 0, 0));
+    let row = sqlx::query_as::<_, (i32, i64, i64, i32)>(FETCH_PROGRESS)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .unwrap_or((1, 0, 0, 0));
     
     let (level, total_xp, coins, streak_days) = row;
     
@@ -239,7 +567,7 @@ async fn fetch_progress(pool: &PgPool, user_id: Uuid) -> Result<ProgressData, Ap
         current_xp: xp_in_current_level,
         xp_to_next_level: xp_needed_for_level - xp_in_current_level,
         xp_progress_percent,
-        coins: coins.into(),
+        coins,
         streak_days,
     })
 }
@@ -261,26 +589,26 @@ async fn fetch_badges(pool: &PgPool, user_id: Uuid) -> Result<BadgeData, AppErro
     })
 }
 
+pub const FETCH_FOCUS_STATUS: &str = r#"
+    SELECT
+        mode,
+        expires_at::text,
+        EXTRACT(EPOCH FROM (expires_at - NOW()))::int as time_remaining
+    FROM focus_sessions
+    WHERE user_id = $1
+      AND status = 'active'
+      AND (expires_at IS NULL OR expires_at > NOW())
+    ORDER BY started_at DESC
+    LIMIT 1
+"#;
+
 async fn fetch_focus_status(pool: &PgPool, user_id: Uuid) -> Result<FocusStatusData, AppError> {
     // Check for active focus session
-    let session = sqlx::query_as::<_, (String, Option<String>, Option<i32>)>(
-        r#"
-        SELECT 
-            mode,
-            expires_at::text,
-            EXTRACT(EPOCH FROM (expires_at - NOW()))::int as time_remaining
-        FROM focus_sessions
-        WHERE user_id = $1 
-          AND status = 'active'
-          AND (expires_at IS NULL OR expires_at > NOW())
-        ORDER BY started_at DESC
-        LIMIT 1
-        "#
-    )
-    .bind(user_id)
-    .fetch_optional(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    let session = sqlx::query_as::<_, (String, Option<String>, Option<i32>)>(FETCH_FOCUS_STATUS)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
     
     match session {
         Some((mode, expires_at, time_remaining)) => Ok(FocusStatusData {
@@ -298,24 +626,25 @@ async fn fetch_focus_status(pool: &PgPool, user_id: Uuid) -> Result<FocusStatusD
     }
 }
 
+pub const FETCH_PLAN_STATUS: &str = r#"
+    SELECT
+        (SELECT COUNT(*)::int FROM jsonb_array_elements(items) AS item
+         WHERE (item->>'completed')::boolean) as completed,
+        jsonb_array_length(items) as total
+    FROM daily_plans
+    WHERE user_id = $1 AND date = $2
+"#;
+
 async fn fetch_plan_status(pool: &PgPool, user_id: Uuid) -> Result<PlanStatusData, AppError> {
     // Get today's plan completion status
-    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let today = chrono::Utc::now().date_naive();
     
-    let plan = sqlx::query_as::<_, (i32, i32)>(
-        r#"
-        SELECT 
-            COALESCE(completed_count, 0) as completed,
-            COALESCE(total_count, 0) as total
-        FROM daily_plans
-        WHERE user_id = $1 AND date = $2
-        "#
-    )
-    .bind(user_id)
-    .bind(&today)
-    .fetch_optional(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    let plan = sqlx::query_as::<_, (i32, i32)>(FETCH_PLAN_STATUS)
+        .bind(user_id)
+        .bind(today)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
     
     match plan {
         Some((completed, total)) => {
@@ -344,75 +673,77 @@ async fn fetch_plan_status(pool: &PgPool, user_id: Uuid) -> Result<PlanStatusDat
 // Helper Queries
 // ============================================
 
+pub const FETCH_UNREAD_INBOX_COUNT: &str =
+    "SELECT COUNT(*) FROM inbox_items WHERE user_id = $1 AND is_read = false";
+
 async fn fetch_unread_inbox_count(pool: &PgPool, user_id: Uuid) -> Result<i32, AppError> {
-    let count = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM inbox_items WHERE user_id = $1 AND is_read = false"
-    )
-    .bind(user_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    let count = sqlx::query_scalar::<_, i64>(FETCH_UNREAD_INBOX_COUNT)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
     
     Ok(count as i32)
 }
 
+pub const FETCH_ACTIVE_QUESTS_COUNT: &str =
+    "SELECT COUNT(*) FROM user_quests WHERE user_id = $1 AND status = 'accepted'";
+
 async fn fetch_active_quests_count(pool: &PgPool, user_id: Uuid) -> Result<i32, AppError> {
-    let count = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM user_quests WHERE user_id = $1 AND status = 'accepted'"
-    )
-    .bind(user_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    let count = sqlx::query_scalar::<_, i64>(FETCH_ACTIVE_QUESTS_COUNT)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
     
     Ok(count as i32)
 }
 
+pub const FETCH_PENDING_HABITS_COUNT: &str = r#"
+    SELECT COUNT(*)
+    FROM habits h
+    WHERE h.user_id = $1
+      AND h.is_active = true
+      AND NOT EXISTS (
+        SELECT 1 FROM habit_completions hc
+        WHERE hc.habit_id = h.id
+          AND hc.completed_date = $2
+      )
+"#;
+
 async fn fetch_pending_habits_count(pool: &PgPool, user_id: Uuid) -> Result<i32, AppError> {
-    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let today = chrono::Utc::now().date_naive();
     
     // Count habits that haven't been completed today
-    let count = sqlx::query_scalar::<_, i64>(
-        r#"
-        SELECT COUNT(*) 
-        FROM habits h
-        WHERE h.user_id = $1 
-          AND h.is_active = true
-          AND NOT EXISTS (
-            SELECT 1 FROM habit_completions hc 
-            WHERE hc.habit_id = h.id 
-              AND hc.completed_date = $2
-          )
-        "#
-    )
-    .bind(user_id)
-    .bind(&today)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    let count = sqlx::query_scalar::<_, i64>(FETCH_PENDING_HABITS_COUNT)
+        .bind(user_id)
+        .bind(today)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
     
     Ok(count as i32)
 }
 
+pub const FETCH_OVERDUE_ITEMS_COUNT: &str = r#"
+    SELECT COUNT(*)
+    FROM user_quests
+    WHERE user_id = $1
+      AND status = 'accepted'
+      AND expires_at IS NOT NULL
+      AND expires_at < $2
+"#;
+
 async fn fetch_overdue_items_count(pool: &PgPool, user_id: Uuid) -> Result<i32, AppError> {
     let now = chrono::Utc::now();
     
     // Count quests that are past their deadline
-    let count = sqlx::query_scalar::<_, i64>(
-        r#"
-        SELECT COUNT(*) 
-        FROM user_quests 
-        WHERE user_id = $1 
-          AND status = 'accepted'
-          AND expires_at IS NOT NULL 
-          AND expires_at < $2
-        "#
-    )
-    .bind(user_id)
-    .bind(now)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    let count = sqlx::query_scalar::<_, i64>(FETCH_OVERDUE_ITEMS_COUNT)
+        .bind(user_id)
+        .bind(now)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
     
     Ok(count as i32)
 }
@@ -427,27 +758,61 @@ fn calculate_xp_for_level(level: i32) -> i64 {
     (100.0 * (level as f64).powf(1.5)) as i64
 }
 
-/// Generate ETag from response data
-fn generate_etag(
-    progress: &ProgressData,
-    badges: &BadgeData,
-    focus: &FocusStatusData,
-    plan: &PlanStatusData,
-) -> String {
-    use std::hash::{Hash, Hasher};
-    use std::collections::hash_map::DefaultHasher;
-    
-    let mut hasher = DefaultHasher::new();
-    
-    // Hash the key values that would indicate a change
-    progress.level.hash(&mut hasher);
-    progress.coins.hash(&mut hasher);
-    badges.unread_inbox.hash(&mut hasher);
-    badges.active_quests.hash(&mut hasher);
-    badges.pending_habits.hash(&mut hasher);
-    focus.has_active_session.hash(&mut hasher);
-    plan.completed.hash(&mut hasher);
-    plan.total.hash(&mut hasher);
-    
-    format!("{:x}", hasher.finish())
+/// ETag for a change-feed cursor at `now` (unix seconds)
+fn version_etag(cursor: i64, now: i64) -> String {
+    format!("{:x}-{:x}", cursor, now.div_euclid(ETAG_WINDOW_SECONDS))
+}
+
+/// Whether any If-None-Match entity tag (weak or strong, or `*`) matches `etag`
+fn etag_matches(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/").trim_matches('"') == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn if_none_match(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_version_etag_changes_with_cursor_and_window() {
+        let now = 1_700_000_000;
+        assert_eq!(version_etag(5, now), version_etag(5, now + 1));
+        assert_ne!(version_etag(5, now), version_etag(6, now));
+        assert_ne!(
+            version_etag(5, now),
+            version_etag(5, now + ETAG_WINDOW_SECONDS)
+        );
+    }
+
+    #[test]
+    fn test_etag_matches() {
+        let etag = version_etag(42, 1_700_000_000);
+
+        assert!(etag_matches(
+            &if_none_match(&format!("\"{}\"", etag)),
+            &etag
+        ));
+        assert!(etag_matches(
+            &if_none_match(&format!("W/\"{}\"", etag)),
+            &etag
+        ));
+        assert!(etag_matches(
+            &if_none_match(&format!("\"stale\", \"{}\"", etag)),
+            &etag
+        ));
+        assert!(etag_matches(&if_none_match("*"), &etag));
+        assert!(!etag_matches(&if_none_match("\"stale\""), &etag));
+        assert!(!etag_matches(&HeaderMap::new(), &etag));
+    }
 }
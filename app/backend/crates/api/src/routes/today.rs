@@ -2,6 +2,7 @@
 //!
 //! Provides the Today page with all data in a single request.
 //! Aggregates from: daily_plans, focus_sessions, habits, quests, user_settings, onboarding
+//! Suggested next actions come from `PersonalizationRepo`
 //!
 //! DESIGN:
 //! - Single endpoint to reduce client round-trips
@@ -19,6 +20,8 @@ use serde::Serialize;
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::db::personalization_models::{Suggestion, SuggestionKind, MAX_SUGGESTIONS};
+use crate::db::personalization_repos::PersonalizationRepo;
 use crate::error::AppError;
 use crate::middleware::auth::AuthContext;
 use crate::state::AppState;
@@ -78,6 +81,7 @@ pub struct InterestPrimer {
     pub primer_type: String,
     pub route: String,
     pub label: String,
+    pub reason: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -86,6 +90,8 @@ pub struct DynamicUIData {
     pub quick_picks: Vec<QuickPick>,
     pub resume_last: Option<ResumeLast>,
     pub interest_primer: Option<InterestPrimer>,
+    /// Ranked next actions, stable for the day
+    pub suggestions: Vec<Suggestion>,
 }
 
 #[derive(Serialize)]
@@ -154,82 +160,82 @@ async fn get_today_data(
 // Data Fetchers
 // ============================================
 
+pub const FETCH_USER_STATE_PLAN_ROW: &str = r#"
+    SELECT
+        COUNT(*) as plan_count,
+        COALESCE(SUM(CASE WHEN (item->>'completed')::boolean THEN 1 ELSE 0 END), 0)::int as completed,
+        COUNT(item)::int as total
+    FROM daily_plans dp
+    LEFT JOIN LATERAL jsonb_array_elements(dp.items) AS item ON true
+    WHERE dp.user_id = $1 AND dp.date = $2
+"#;
+
+pub const FETCH_USER_STATE_FOCUS_ACTIVE: &str = r#"
+    SELECT COUNT(*) FROM focus_sessions
+    WHERE user_id = $1 AND status = 'active'
+    AND (expires_at IS NULL OR expires_at > NOW())
+"#;
+
+pub const FETCH_USER_STATE_ACTIVE_STREAK: &str = r#"
+    SELECT COUNT(*) FROM user_streaks
+    WHERE user_id = $1 AND current_streak > 0
+"#;
+
+pub const FETCH_USER_STATE_FIRST_DAY: &str = r#"
+    SELECT COUNT(*) FROM users
+    WHERE id = $1 AND created_at::date = CURRENT_DATE
+"#;
+
+pub const FETCH_USER_STATE_RETURNING_AFTER_GAP: &str = r#"
+    SELECT COUNT(*) FROM users
+    WHERE id = $1
+    AND last_activity_at IS NOT NULL
+    AND last_activity_at < NOW() - INTERVAL '3 days'
+"#;
+
 async fn fetch_user_state(pool: &PgPool, user_id: Uuid) -> Result<UserState, AppError> {
-    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let today = chrono::Utc::now().date_naive();
     
     // Check if plan exists for today
-    let plan_row = sqlx::query_as::<_, (i64, i32, i32)>(
-        r#"
-        SELECT 
-            COUNT(*) as plan_count,
-            COALESCE(SUM(CASE WHEN completed THEN 1 ELSE 0 END), 0)::int as completed,
-            COALESCE(COUNT(*), 0)::int as total
-        FROM daily_plan_items dpi
-        JOIN daily_plans dp ON dp.id = dpi.plan_id
-        WHERE dp.user_id = $1 AND dp.date = $2
-        "#
-    )
-    .bind(user_id)
-    .bind(&today)
-    .fetch_optional(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?
-    .unwrap_or((0, 0, 0));
+    let plan_row = sqlx::query_as::<_, (i64, i32, i32)>(FETCH_USER_STATE_PLAN_ROW)
+        .bind(user_id)
+        .bind(today)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .unwrap_or((0, 0, 0));
     
     let (plan_count, completed, total) = plan_row;
     let plan_exists = plan_count > 0;
     let has_incomplete = total > completed;
     
     // Check for active focus session
-    let focus_active = sqlx::query_scalar::<_, i64>(
-        r#"
-        SELECT COUNT(*) FROM focus_sessions 
-        WHERE user_id = $1 AND status = 'active' 
-        AND (expires_at IS NULL OR expires_at > NOW())
-        "#
-    )
-    .bind(user_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))? > 0;
+    let focus_active = sqlx::query_scalar::<_, i64>(FETCH_USER_STATE_FOCUS_ACTIVE)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))? > 0;
     
     // Check for active streak (user has logged activity in the last 2 days)
-    let active_streak = sqlx::query_scalar::<_, i64>(
-        r#"
-        SELECT COUNT(*) FROM user_progress 
-        WHERE user_id = $1 AND streak_days > 0
-        "#
-    )
-    .bind(user_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))? > 0;
+    let active_streak = sqlx::query_scalar::<_, i64>(FETCH_USER_STATE_ACTIVE_STREAK)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))? > 0;
     
     // Check if first day (user created today)
-    let first_day = sqlx::query_scalar::<_, i64>(
-        r#"
-        SELECT COUNT(*) FROM users 
-        WHERE id = $1 AND created_at::date = CURRENT_DATE
-        "#
-    )
-    .bind(user_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))? > 0;
+    let first_day = sqlx::query_scalar::<_, i64>(FETCH_USER_STATE_FIRST_DAY)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))? > 0;
     
     // Check for returning after gap (last activity > 3 days ago)
-    let returning_after_gap = sqlx::query_scalar::<_, i64>(
-        r#"
-        SELECT COUNT(*) FROM users 
-        WHERE id = $1 
-        AND last_activity_at IS NOT NULL 
-        AND last_activity_at < NOW() - INTERVAL '3 days'
-        "#
-    )
-    .bind(user_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))? > 0;
+    let returning_after_gap = sqlx::query_scalar::<_, i64>(FETCH_USER_STATE_RETURNING_AFTER_GAP)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))? > 0;
     
     Ok(UserState {
         plan_exists,
@@ -241,29 +247,29 @@ async fn fetch_user_state(pool: &PgPool, user_id: Uuid) -> Result<UserState, App
     })
 }
 
+pub const FETCH_PLAN_SUMMARY: &str = r#"
+    SELECT
+        item->>'id',
+        item->>'title',
+        COALESCE((item->>'priority')::int, 0),
+        item->>'item_type',
+        COALESCE((item->>'completed')::boolean, false)
+    FROM daily_plans dp
+    CROSS JOIN LATERAL jsonb_array_elements(dp.items) WITH ORDINALITY AS i(item, position)
+    WHERE dp.user_id = $1 AND dp.date = $2
+    ORDER BY 3 ASC, i.position ASC
+"#;
+
 async fn fetch_plan_summary(pool: &PgPool, user_id: Uuid) -> Result<DailyPlanSummary, AppError> {
-    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let today = chrono::Utc::now().date_naive();
     
     // Get plan items for today
-    let items = sqlx::query_as::<_, (String, String, i32, String, bool)>(
-        r#"
-        SELECT 
-            dpi.id::text,
-            dpi.title,
-            dpi.priority,
-            dpi.item_type,
-            dpi.completed
-        FROM daily_plan_items dpi
-        JOIN daily_plans dp ON dp.id = dpi.plan_id
-        WHERE dp.user_id = $1 AND dp.date = $2
-        ORDER BY dpi.priority ASC, dpi.created_at ASC
-        "#
-    )
-    .bind(user_id)
-    .bind(&today)
-    .fetch_all(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    let items = sqlx::query_as::<_, (String, String, i32, String, bool)>(FETCH_PLAN_SUMMARY)
+        .bind(user_id)
+        .bind(today)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
     
     let total_count = items.len() as i32;
     let completed_count = items.iter().filter(|i| i.4).count() as i32;
@@ -300,39 +306,39 @@ async fn fetch_plan_summary(pool: &PgPool, user_id: Uuid) -> Result<DailyPlanSum
     })
 }
 
+pub const FETCH_PERSONALIZATION_SETTINGS: &str = r#"
+    SELECT key, value FROM user_settings
+    WHERE user_id = $1 AND key IN (
+        'interests', 'module_weights', 'nudge_intensity',
+        'focus_duration', 'gamification_visible'
+    )
+"#;
+
+pub const FETCH_PERSONALIZATION_ONBOARDING: &str = r#"
+    SELECT
+        status NOT IN ('completed', 'skipped') as active,
+        current_step_id::text
+    FROM user_onboarding_state
+    WHERE user_id = $1
+"#;
+
 async fn fetch_personalization(pool: &PgPool, user_id: Uuid) -> Result<UserPersonalization, AppError> {
     // Get user settings
-    let settings = sqlx::query_as::<_, (String, serde_json::Value)>(
-        r#"
-        SELECT key, value FROM user_settings 
-        WHERE user_id = $1 AND key IN (
-            'interests', 'module_weights', 'nudge_intensity', 
-            'focus_duration', 'gamification_visible'
-        )
-        "#
-    )
-    .bind(user_id)
-    .fetch_all(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    let settings = sqlx::query_as::<_, (String, serde_json::Value)>(FETCH_PERSONALIZATION_SETTINGS)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
     
     let settings_map: std::collections::HashMap<String, serde_json::Value> = 
         settings.into_iter().collect();
     
     // Check onboarding status
-    let onboarding = sqlx::query_as::<_, (bool, Option<String>)>(
-        r#"
-        SELECT 
-            NOT completed as active,
-            current_step
-        FROM user_onboarding_state
-        WHERE user_id = $1
-        "#
-    )
-    .bind(user_id)
-    .fetch_optional(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    let onboarding = sqlx::query_as::<_, (bool, Option<String>)>(FETCH_PERSONALIZATION_ONBOARDING)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
     
     let (onboarding_active, current_step) = onboarding.unwrap_or((false, None));
     let onboarding_route = if onboarding_active {
@@ -364,26 +370,47 @@ async fn fetch_personalization(pool: &PgPool, user_id: Uuid) -> Result<UserPerso
     })
 }
 
+pub const FETCH_DYNAMIC_UI_PENDING_HABITS: &str = r#"
+    SELECT COUNT(*) FROM habits h
+    WHERE h.user_id = $1 AND h.is_active = true
+    AND NOT EXISTS (
+        SELECT 1 FROM habit_completions hl
+        WHERE hl.habit_id = h.id
+        AND hl.completed_date = CURRENT_DATE
+    )
+"#;
+
+pub const FETCH_DYNAMIC_UI_ACTIVE_QUESTS: &str = r#"
+    SELECT COUNT(*) FROM user_quest_progress uqp
+    JOIN universal_quests uq ON uq.id = uqp.quest_id
+    WHERE uqp.user_id = $1 AND uqp.completed_at IS NULL
+    AND uq.is_active = true
+"#;
+
+pub const FETCH_DYNAMIC_UI_UNREAD_INBOX: &str =
+    "SELECT COUNT(*) FROM inbox_items WHERE user_id = $1 AND is_read = false";
+
+pub const FETCH_DYNAMIC_UI_RESUME_LAST: &str = r#"
+    SELECT
+        'focus' as module,
+        '/focus' as route,
+        mode as label
+    FROM focus_sessions
+    WHERE user_id = $1 AND status = 'completed'
+    ORDER BY completed_at DESC
+    LIMIT 1
+"#;
+
 async fn fetch_dynamic_ui(pool: &PgPool, user_id: Uuid) -> Result<DynamicUIData, AppError> {
     // Build quick picks from user activity
     let mut quick_picks = Vec::new();
     
     // Check pending habits
-    let pending_habits = sqlx::query_scalar::<_, i64>(
-        r#"
-        SELECT COUNT(*) FROM habits h
-        WHERE h.user_id = $1 AND h.archived = false
-        AND NOT EXISTS (
-            SELECT 1 FROM habit_completions hl 
-            WHERE hl.habit_id = h.id 
-            AND hl.completed_at::date = CURRENT_DATE
-        )
-        "#
-    )
-    .bind(user_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    let pending_habits = sqlx::query_scalar::<_, i64>(FETCH_DYNAMIC_UI_PENDING_HABITS)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
     
     if pending_habits > 0 {
         quick_picks.push(QuickPick {
@@ -395,18 +422,11 @@ async fn fetch_dynamic_ui(pool: &PgPool, user_id: Uuid) -> Result<DynamicUIData,
     }
     
     // Check active quests
-    let active_quests = sqlx::query_scalar::<_, i64>(
-        r#"
-        SELECT COUNT(*) FROM user_quest_progress uqp
-        JOIN universal_quests uq ON uq.id = uqp.quest_id
-        WHERE uqp.user_id = $1 AND uqp.completed = false
-        AND (uq.expires_at IS NULL OR uq.expires_at > NOW())
-        "#
-    )
-    .bind(user_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    let active_quests = sqlx::query_scalar::<_, i64>(FETCH_DYNAMIC_UI_ACTIVE_QUESTS)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
     
     if active_quests > 0 {
         quick_picks.push(QuickPick {
@@ -418,13 +438,11 @@ async fn fetch_dynamic_ui(pool: &PgPool, user_id: Uuid) -> Result<DynamicUIData,
     }
     
     // Check unread inbox
-    let unread_inbox = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM inbox_items WHERE user_id = $1 AND is_read = false"
-    )
-    .bind(user_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    let unread_inbox = sqlx::query_scalar::<_, i64>(FETCH_DYNAMIC_UI_UNREAD_INBOX)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
     
     if unread_inbox > 0 {
         quick_picks.push(QuickPick {
@@ -436,32 +454,36 @@ async fn fetch_dynamic_ui(pool: &PgPool, user_id: Uuid) -> Result<DynamicUIData,
     }
     
     // Get resume last (most recent activity)
-    let resume_last = sqlx::query_as::<_, (String, String, String)>(
-        r#"
-        SELECT 
-            'focus' as module,
-            '/focus' as route,
-            mode as label
-        FROM focus_sessions
-        WHERE user_id = $1 AND status = 'completed'
-        ORDER BY ended_at DESC
-        LIMIT 1
-        "#
-    )
-    .bind(user_id)
-    .fetch_optional(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?
-    .map(|(module, route, label)| ResumeLast {
-        module,
-        route,
-        label: format!("Resume {}", label),
-        last_used: chrono::Utc::now().to_rfc3339(),
+    let resume_last = sqlx::query_as::<_, (String, String, String)>(FETCH_DYNAMIC_UI_RESUME_LAST)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .map(|(module, route, label)| ResumeLast {
+            module,
+            route,
+            label: format!("Resume {}", label),
+            last_used: chrono::Utc::now().to_rfc3339(),
+        });
+    
+    // Rank next actions; the strongest one is the primer
+    let today = chrono::Utc::now().date_naive();
+    let suggestions = PersonalizationRepo::suggestions(pool, user_id, today, MAX_SUGGESTIONS).await?;
+    let interest_primer = suggestions.first().map(|s| InterestPrimer {
+        primer_type: match s.kind {
+            SuggestionKind::Lesson | SuggestionKind::ListeningPrompt => "learn",
+            kind => kind.as_str(),
+        }
+        .to_string(),
+        route: s.route.clone(),
+        label: s.title.clone(),
+        reason: s.reasons.first().cloned(),
     });
     
     Ok(DynamicUIData {
         quick_picks,
         resume_last,
-        interest_primer: None, // Will be populated based on user interests later
+        interest_primer,
+        suggestions,
     })
 }
@@ -5,24 +5,50 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Extension, State},
-    routing::{delete, get},
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
     Json, Router,
 };
+use chrono::NaiveTime;
 use serde::Serialize;
+use uuid::Uuid;
 
+use crate::db::account_deletion_models::*;
+use crate::db::account_deletion_repos::AccountDeletionRepo;
+use crate::db::api_token_models::*;
+use crate::db::api_token_repos::ApiTokenRepo;
+use crate::db::archive_models::*;
+use crate::db::archive_repos::ArchiveJobRepo;
 use crate::db::models::User;
 use crate::db::platform_models::*;
 use crate::db::platform_repos::{UserAccountRepo, UserSettingsRepo};
+use crate::db::repos::SessionRepo;
 use crate::error::AppError;
+use crate::middleware::auth::AuthContext;
+use crate::services::account_archive;
+use crate::shared::audit::{write_audit, AuditEventType};
+use crate::shared::auth::scopes;
 use crate::state::AppState;
+use crate::storage::{BlobCategory, SignedUrlResponse};
 
 /// Create user routes
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/settings", get(get_settings).put(update_settings))
-        .route("/delete", delete(delete_account))
+        .route("/delete", get(get_account_deletion).delete(delete_account))
+        .route("/delete/cancel", post(cancel_account_deletion))
         .route("/export", get(export_data))
+        .route("/archives", get(list_archive_jobs))
+        .route("/archives/export", post(start_export))
+        .route("/archives/import", post(start_import))
+        .route("/archives/{id}", get(get_archive_job))
+        .route("/archives/{id}/download-url", get(get_archive_download_url))
+        .route("/sessions", get(list_sessions))
+        .route("/sessions/revoke-others", post(revoke_other_sessions))
+        .route("/sessions/{id}", delete(revoke_session))
+        .route("/tokens", get(list_tokens).post(create_token))
+        .route("/tokens/{id}", delete(revoke_token))
         .nest("/inbox", super::inbox::router())
 }
 
@@ -40,11 +66,51 @@ struct DeleteWrapper {
     data: DeleteAccountResponse,
 }
 
+#[derive(Serialize)]
+struct DeletionWrapper {
+    data: Option<AccountDeletionResponse>,
+}
+
 #[derive(Serialize)]
 struct ExportWrapper {
     data: ExportDataResponse,
 }
 
+#[derive(Serialize)]
+struct ArchiveJobWrapper {
+    data: ArchiveJobResponse,
+}
+
+#[derive(Serialize)]
+struct ArchiveJobsWrapper {
+    data: Vec<ArchiveJobResponse>,
+}
+
+#[derive(Serialize)]
+struct SignedUrlWrapper {
+    data: SignedUrlResponse,
+}
+
+#[derive(Serialize)]
+struct SessionsWrapper {
+    data: Vec<UserSessionResponse>,
+}
+
+#[derive(Serialize)]
+struct RevokeWrapper {
+    data: RevokeSessionsResponse,
+}
+
+#[derive(Serialize)]
+struct TokensWrapper {
+    data: Vec<ApiTokenResponse>,
+}
+
+#[derive(Serialize)]
+struct CreatedTokenWrapper {
+    data: CreatedApiTokenResponse,
+}
+
 // ============================================================================
 // HANDLERS
 // ============================================================================
@@ -75,18 +141,125 @@ async fn update_settings(
         }
     }
 
+    // Reminder and workday times are "HH:MM" in the user's timezone
+    for (field, value) in [
+        ("daily_reminder_time", &req.daily_reminder_time),
+        ("quiet_hours_start", &req.quiet_hours_start),
+        ("quiet_hours_end", &req.quiet_hours_end),
+        ("workday_start", &req.workday_start),
+        ("workday_end", &req.workday_end),
+    ] {
+        if let Some(value) = value {
+            let clearable = field.starts_with("quiet_hours") && value.is_empty();
+            if !clearable && NaiveTime::parse_from_str(value, "%H:%M").is_err() {
+                return Err(AppError::Validation(format!(
+                    "Invalid {}. Must be HH:MM",
+                    field
+                )));
+            }
+        }
+    }
+
+    if let (Some(start), Some(end)) = (&req.workday_start, &req.workday_end) {
+        let parse = |t: &str| NaiveTime::parse_from_str(t, "%H:%M").ok();
+        if parse(start) >= parse(end) {
+            return Err(AppError::Validation(
+                "workday_end must be after workday_start".into(),
+            ));
+        }
+    }
+
+    if let Some(ref timezone) = req.timezone {
+        if !UserSettingsRepo::timezone_exists(&state.db, timezone).await? {
+            return Err(AppError::Validation(format!(
+                "Unknown timezone '{}'",
+                timezone
+            )));
+        }
+    }
+
     let settings = UserSettingsRepo::update(&state.db, user.id, &req).await?;
     Ok(Json(SettingsWrapper { data: settings }))
 }
 
 /// DELETE /user/delete
-/// Delete user account and all data
+/// Schedule deletion of the account and all data after the grace period,
+/// signing out every session and revoking API tokens
 async fn delete_account(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
 ) -> Result<Json<DeleteWrapper>, AppError> {
-    let result = UserAccountRepo::delete_account(&state.db, user.id).await?;
-    Ok(Json(DeleteWrapper { data: result }))
+    let deletion = AccountDeletionRepo::schedule(
+        &state.db,
+        user.id,
+        state.config.accounts.deletion_grace_days,
+    )
+    .await?;
+
+    if deletion.status == ACCOUNT_DELETION_STATUS_SCHEDULED {
+        write_audit(
+            state.db.clone(),
+            AuditEventType::UserUpdated,
+            Some(user.id),
+            "User scheduled account deletion",
+            Some("user"),
+            Some(user.id),
+        );
+    }
+
+    Ok(Json(DeleteWrapper {
+        data: DeleteAccountResponse {
+            success: true,
+            message: format!(
+                "Account and all associated data will be deleted on {}. You have been signed out; sign in again before then to cancel.",
+                deletion.purge_after.format("%Y-%m-%d")
+            ),
+            purge_after: deletion.purge_after,
+        },
+    }))
+}
+
+/// GET /user/delete
+/// Get the account's pending or cancelled deletion, if any
+async fn get_account_deletion(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<Json<DeletionWrapper>, AppError> {
+    let deletion = AccountDeletionRepo::get(&state.db, user.id).await?;
+    Ok(Json(DeletionWrapper {
+        data: deletion.map(Into::into),
+    }))
+}
+
+/// POST /user/delete/cancel
+/// Cancel a scheduled deletion during its grace period
+async fn cancel_account_deletion(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<Json<DeletionWrapper>, AppError> {
+    let Some(deletion) = AccountDeletionRepo::cancel(&state.db, user.id).await? else {
+        let purging = AccountDeletionRepo::get(&state.db, user.id)
+            .await?
+            .is_some_and(|d| d.status == ACCOUNT_DELETION_STATUS_PURGING);
+        return Err(if purging {
+            AppError::BadRequest("Account deletion is already in progress".to_string())
+        } else {
+            AppError::NotFound("No account deletion is scheduled".to_string())
+        });
+    };
+
+    write_audit(
+        state.db.clone(),
+        AuditEventType::UserUpdated,
+        Some(user.id),
+        "User cancelled account deletion",
+        Some("user"),
+        Some(user.id),
+    );
+
+    Ok(Json(DeletionWrapper {
+        data: Some(deletion.into()),
+    }))
 }
 
 /// GET /user/export
@@ -98,3 +271,277 @@ async fn export_data(
     let result = UserAccountRepo::export_data(&state.db, user.id, Some(user.email)).await?;
     Ok(Json(ExportWrapper { data: result }))
 }
+
+/// GET /user/archives
+/// List recent export and import jobs
+async fn list_archive_jobs(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<Json<ArchiveJobsWrapper>, AppError> {
+    let jobs = ArchiveJobRepo::list(&state.db, user.id, 20).await?;
+    Ok(Json(ArchiveJobsWrapper {
+        data: jobs.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// POST /user/archives/export
+/// Start writing a ZIP archive of all user data; the download link arrives in the inbox
+async fn start_export(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<(StatusCode, Json<ArchiveJobWrapper>), AppError> {
+    if state.storage.is_none() {
+        return Err(AppError::Config("Storage not configured".to_string()));
+    }
+
+    let job = ArchiveJobRepo::create(&state.db, user.id, ARCHIVE_JOB_KIND_EXPORT, None)
+        .await?
+        .ok_or_else(|| {
+            AppError::BadRequest("An export or import is already in progress".to_string())
+        })?;
+    account_archive::spawn_export(state.clone(), job.clone(), user.email);
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ArchiveJobWrapper { data: job.into() }),
+    ))
+}
+
+/// POST /user/archives/import
+/// Restore an uploaded export archive into this (fresh) account
+async fn start_import(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(req): Json<StartImportRequest>,
+) -> Result<(StatusCode, Json<ArchiveJobWrapper>), AppError> {
+    let storage = state
+        .storage
+        .as_ref()
+        .ok_or_else(|| AppError::Config("Storage not configured".to_string()))?;
+
+    // Ownership is checked through the user's storage prefix
+    let archive = storage
+        .get_blob_info(&user.id, &req.blob_id)
+        .await?
+        .filter(|info| info.category == BlobCategory::Exports)
+        .ok_or_else(|| AppError::NotFound("Archive not found".to_string()))?;
+
+    let job = ArchiveJobRepo::create(
+        &state.db,
+        user.id,
+        ARCHIVE_JOB_KIND_IMPORT,
+        Some(&archive.key),
+    )
+    .await?
+    .ok_or_else(|| {
+        AppError::BadRequest("An export or import is already in progress".to_string())
+    })?;
+    account_archive::spawn_import(state.clone(), job.clone());
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ArchiveJobWrapper { data: job.into() }),
+    ))
+}
+
+/// GET /user/archives/{id}
+/// Get an export or import job
+async fn get_archive_job(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ArchiveJobWrapper>, AppError> {
+    let job = ArchiveJobRepo::get(&state.db, user.id, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Archive job not found".to_string()))?;
+    Ok(Json(ArchiveJobWrapper { data: job.into() }))
+}
+
+/// GET /user/archives/{id}/download-url
+/// Fresh signed link for a completed export
+async fn get_archive_download_url(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SignedUrlWrapper>, AppError> {
+    let storage = state
+        .storage
+        .as_ref()
+        .ok_or_else(|| AppError::Config("Storage not configured".to_string()))?;
+
+    let key = ArchiveJobRepo::get(&state.db, user.id, id)
+        .await?
+        .filter(|job| {
+            job.kind == ARCHIVE_JOB_KIND_EXPORT && job.status == ARCHIVE_JOB_STATUS_COMPLETED
+        })
+        .and_then(|job| job.storage_key)
+        .ok_or_else(|| AppError::NotFound("Export not found".to_string()))?;
+
+    let url = storage.generate_signed_download_url(&key).await?;
+    Ok(Json(SignedUrlWrapper { data: url }))
+}
+
+/// GET /user/sessions
+/// List the user's active sessions (devices)
+async fn list_sessions(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<SessionsWrapper>, AppError> {
+    let sessions = SessionRepo::list_for_user(&state.db, auth.user_id).await?;
+    let data = sessions
+        .into_iter()
+        .map(|s| UserSessionResponse::from_session(s, auth.session_id))
+        .collect();
+    Ok(Json(SessionsWrapper { data }))
+}
+
+/// DELETE /user/sessions/{id}
+/// Sign out a single session
+async fn revoke_session(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RevokeWrapper>, AppError> {
+    if !SessionRepo::delete_for_user(&state.db, auth.user_id, id).await? {
+        return Err(AppError::NotFound("Session not found".into()));
+    }
+
+    write_audit(
+        state.db.clone(),
+        AuditEventType::SessionRevoked,
+        Some(auth.user_id),
+        "User revoked a session",
+        Some("session"),
+        Some(id),
+    );
+
+    Ok(Json(RevokeWrapper {
+        data: RevokeSessionsResponse { revoked: 1 },
+    }))
+}
+
+/// POST /user/sessions/revoke-others
+/// Sign out every session except the current one
+async fn revoke_other_sessions(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<RevokeWrapper>, AppError> {
+    let revoked =
+        SessionRepo::delete_others_for_user(&state.db, auth.user_id, auth.session_id).await?;
+
+    write_audit(
+        state.db.clone(),
+        AuditEventType::SessionRevoked,
+        Some(auth.user_id),
+        &format!("User revoked {} other session(s)", revoked),
+        Some("session"),
+        Some(auth.session_id),
+    );
+
+    Ok(Json(RevokeWrapper {
+        data: RevokeSessionsResponse { revoked },
+    }))
+}
+
+/// GET /user/tokens
+/// List the user's personal API tokens
+async fn list_tokens(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<TokensWrapper>, AppError> {
+    let tokens = ApiTokenRepo::list_for_user(&state.db, auth.user_id).await?;
+    Ok(Json(TokensWrapper {
+        data: tokens.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// POST /user/tokens
+/// Create a personal API token; the raw token is only returned here
+async fn create_token(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Json(req): Json<CreateApiTokenRequest>,
+) -> Result<Json<CreatedTokenWrapper>, AppError> {
+    if auth.is_dev_bypass {
+        return Err(AppError::Forbidden);
+    }
+
+    let name = req.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_API_TOKEN_NAME_LEN {
+        return Err(AppError::Validation(format!(
+            "Token name must be 1-{} characters",
+            MAX_API_TOKEN_NAME_LEN
+        )));
+    }
+
+    let mut token_scopes = req.scopes;
+    token_scopes.sort();
+    token_scopes.dedup();
+    if token_scopes.is_empty() {
+        return Err(AppError::Validation(
+            "At least one scope is required".into(),
+        ));
+    }
+    if let Some(bad) = token_scopes.iter().find(|s| !scopes::is_valid_scope(s)) {
+        return Err(AppError::Validation(format!("Unknown scope: {}", bad)));
+    }
+
+    if let Some(days) = req.expires_in_days {
+        if !(1..=MAX_API_TOKEN_EXPIRY_DAYS).contains(&days) {
+            return Err(AppError::Validation(format!(
+                "expires_in_days must be between 1 and {}",
+                MAX_API_TOKEN_EXPIRY_DAYS
+            )));
+        }
+    }
+
+    let (token, raw) = ApiTokenRepo::create(
+        &state.db,
+        auth.user_id,
+        name,
+        &token_scopes,
+        req.expires_in_days,
+    )
+    .await?;
+
+    write_audit(
+        state.db.clone(),
+        AuditEventType::ApiTokenCreated,
+        Some(auth.user_id),
+        &format!("Created API token with scopes {}", token_scopes.join(",")),
+        Some("api_token"),
+        Some(token.id),
+    );
+
+    Ok(Json(CreatedTokenWrapper {
+        data: CreatedApiTokenResponse {
+            token: raw,
+            info: token.into(),
+        },
+    }))
+}
+
+/// DELETE /user/tokens/{id}
+/// Revoke a personal API token
+async fn revoke_token(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RevokeWrapper>, AppError> {
+    if !ApiTokenRepo::revoke(&state.db, auth.user_id, id).await? {
+        return Err(AppError::NotFound("API token not found".into()));
+    }
+
+    write_audit(
+        state.db.clone(),
+        AuditEventType::ApiTokenRevoked,
+        Some(auth.user_id),
+        "User revoked an API token",
+        Some("api_token"),
+        Some(id),
+    );
+
+    Ok(Json(RevokeWrapper {
+        data: RevokeSessionsResponse { revoked: 1 },
+    }))
+}
@@ -0,0 +1,651 @@
+//! Account archives
+//!
+//! Export jobs collect the user's rows and their audio and image blobs into a
+//! versioned ZIP under their exports prefix, then drop a signed download link
+//! into the inbox. Import jobs read such an archive back into a fresh
+//! account with new IDs.
+//!
+//! Archives are built and read in a temporary file, and blobs pass through
+//! one at a time, so memory holds the rows but never the stored files.
+//!
+//! Layout:
+//! - `manifest.json`: format and schema version, row counts, blob list
+//! - `data/<domain>.json`: `{ "<table>": [rows...] }` per domain
+//! - `blobs/<category>/<file>`: stored files, named as in storage
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Read, Seek, Write};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::db::archive_models::*;
+use crate::db::archive_repos::{ArchiveJobRepo, ArchiveRepo, ARCHIVE_TABLES};
+use crate::db::inbox_models::CreateInboxRequest;
+use crate::db::inbox_repos::InboxRepo;
+use crate::error::AppError;
+use crate::state::AppState;
+use crate::storage::{
+    generate_blob_key, get_mime_from_extension, parse_blob_key, validate_file_size, BlobCategory,
+    StorageClient, TempFile, SIGNED_URL_EXPIRY_SECONDS,
+};
+
+/// Blob categories carried in archives
+const ARCHIVED_BLOB_CATEGORIES: [BlobCategory; 2] = [BlobCategory::Audio, BlobCategory::Images];
+
+/// Most files an archive may hold
+const MAX_ARCHIVE_ENTRIES: usize = 10_000;
+
+/// Most bytes an archive may expand to (guards against zip bombs)
+const MAX_ARCHIVE_UNCOMPRESSED_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Most bytes of manifest and row data, which are decoded in memory
+const MAX_ARCHIVE_DATA_BYTES: u64 = 32 * 1024 * 1024;
+
+/// How often a running job extends its lease (the lease lasts five minutes)
+const LEASE_RENEW_INTERVAL: Duration = Duration::from_secs(60);
+
+const ARCHIVE_MIME_TYPE: &str = "application/zip";
+
+// ============================================================================
+// ZIP FORMAT
+// ============================================================================
+
+fn zip_error(e: zip::result::ZipError) -> AppError {
+    AppError::BadRequest(format!("Invalid archive: {}", e))
+}
+
+fn write_error(e: impl std::fmt::Display) -> AppError {
+    AppError::Internal(format!("ZIP write failed: {}", e))
+}
+
+/// Run blocking archive I/O off the async workers
+async fn blocking<T, F>(f: F) -> Result<T, AppError>
+where
+    F: FnOnce() -> Result<T, AppError> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/// Writes an archive an entry at a time
+pub struct ArchiveWriter<W: Write + Seek> {
+    zip: ZipWriter<W>,
+}
+
+impl<W: Write + Seek> ArchiveWriter<W> {
+    /// Start an archive with its manifest and row data
+    pub fn new(inner: W, contents: &ArchiveContents) -> Result<Self, AppError> {
+        let mut zip = ZipWriter::new(inner);
+        let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let json = |e: serde_json::Error| AppError::Internal(format!("JSON encode failed: {}", e));
+
+        zip.start_file(ARCHIVE_MANIFEST_PATH, deflated)
+            .map_err(write_error)?;
+        zip.write_all(&serde_json::to_vec_pretty(&contents.manifest).map_err(json)?)
+            .map_err(write_error)?;
+
+        let mut domains: BTreeMap<&str, serde_json::Map<String, serde_json::Value>> =
+            BTreeMap::new();
+        for table in ARCHIVE_TABLES {
+            let rows = contents
+                .tables
+                .get(table.table)
+                .cloned()
+                .unwrap_or_default();
+            domains
+                .entry(table.domain)
+                .or_default()
+                .insert(table.table.to_string(), serde_json::Value::Array(rows));
+        }
+        for (domain, tables) in domains {
+            zip.start_file(format!("{}/{}.json", ARCHIVE_DATA_DIR, domain), deflated)
+                .map_err(write_error)?;
+            zip.write_all(&serde_json::to_vec(&tables).map_err(json)?)
+                .map_err(write_error)?;
+        }
+
+        Ok(Self { zip })
+    }
+
+    /// Copy a stored file into the archive
+    pub fn add_blob(&mut self, path: &str, data: &mut impl Read) -> Result<(), AppError> {
+        // Audio and images are already compressed
+        let stored = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Stored)
+            .large_file(true);
+        self.zip
+            .start_file(format!("{}/{}", ARCHIVE_BLOBS_DIR, path), stored)
+            .map_err(write_error)?;
+        std::io::copy(data, &mut self.zip).map_err(write_error)?;
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<W, AppError> {
+        self.zip.finish().map_err(write_error)
+    }
+}
+
+/// Reads an archive written by [`ArchiveWriter`] an entry at a time
+///
+/// Everything read counts against the uncompressed size limit, whatever the
+/// entries declare.
+pub struct ArchiveReader<R: Read + Seek> {
+    zip: ZipArchive<R>,
+    remaining: u64,
+}
+
+impl<R: Read + Seek> ArchiveReader<R> {
+    pub fn new(inner: R) -> Result<Self, AppError> {
+        let zip = ZipArchive::new(inner).map_err(zip_error)?;
+        if zip.len() > MAX_ARCHIVE_ENTRIES {
+            return Err(AppError::BadRequest(
+                "Archive has too many files".to_string(),
+            ));
+        }
+        Ok(Self {
+            zip,
+            remaining: MAX_ARCHIVE_UNCOMPRESSED_BYTES,
+        })
+    }
+
+    /// Copy entry `index` into `out`, within the remaining size budget
+    fn copy_entry(
+        &mut self,
+        index: usize,
+        out: &mut impl Write,
+        limit: u64,
+    ) -> Result<u64, AppError> {
+        let limit = limit.min(self.remaining);
+        let file = self.zip.by_index(index).map_err(zip_error)?;
+        // The declared size can lie; never read past the budget
+        let copied = std::io::copy(&mut file.take(limit + 1), out)
+            .map_err(|e| AppError::BadRequest(format!("Invalid archive: {}", e)))?;
+        if copied > limit {
+            return Err(AppError::BadRequest("Archive is too large".to_string()));
+        }
+        self.remaining -= copied;
+        Ok(copied)
+    }
+
+    /// Check entry `index` has a safe path and return it
+    fn entry_name(&mut self, index: usize) -> Result<Option<String>, AppError> {
+        let file = self.zip.by_index_raw(index).map_err(zip_error)?;
+        if file.is_dir() {
+            return Ok(None);
+        }
+        let name = file.name().to_string();
+        if file.enclosed_name().is_none() {
+            return Err(AppError::BadRequest(format!(
+                "Unsafe archive path: {}",
+                name
+            )));
+        }
+        Ok(Some(name))
+    }
+
+    /// Manifest and rows
+    pub fn contents(&mut self) -> Result<ArchiveContents, AppError> {
+        let mut manifest = None;
+        let mut tables = BTreeMap::new();
+        let mut data_budget = MAX_ARCHIVE_DATA_BYTES;
+
+        for i in 0..self.zip.len() {
+            let Some(name) = self.entry_name(i)? else {
+                continue;
+            };
+            let is_data = name
+                .strip_prefix(&format!("{}/", ARCHIVE_DATA_DIR))
+                .is_some_and(|domain| domain.ends_with(".json"));
+            if name != ARCHIVE_MANIFEST_PATH && !is_data {
+                continue;
+            }
+
+            let mut data = Vec::new();
+            data_budget -= self.copy_entry(i, &mut data, data_budget)?;
+
+            let invalid =
+                |e: serde_json::Error| AppError::BadRequest(format!("Invalid {}: {}", name, e));
+            if name == ARCHIVE_MANIFEST_PATH {
+                manifest = Some(serde_json::from_slice::<ArchiveManifest>(&data).map_err(invalid)?);
+            } else {
+                let domain_tables: BTreeMap<String, Vec<serde_json::Value>> =
+                    serde_json::from_slice(&data).map_err(invalid)?;
+                tables.extend(domain_tables);
+            }
+        }
+
+        let manifest =
+            manifest.ok_or_else(|| AppError::BadRequest("Archive has no manifest".to_string()))?;
+        Ok(ArchiveContents { manifest, tables })
+    }
+
+    /// Blob entries as (index, path below `blobs/`)
+    pub fn blobs(&mut self) -> Result<Vec<(usize, String)>, AppError> {
+        let mut blobs = Vec::new();
+        for i in 0..self.zip.len() {
+            let Some(name) = self.entry_name(i)? else {
+                continue;
+            };
+            if let Some(path) = name.strip_prefix(&format!("{}/", ARCHIVE_BLOBS_DIR)) {
+                blobs.push((i, path.to_string()));
+            }
+        }
+        Ok(blobs)
+    }
+
+    /// Copy blob entry `index` into `out`
+    pub fn extract_blob(&mut self, index: usize, out: &mut impl Write) -> Result<u64, AppError> {
+        self.copy_entry(index, out, u64::MAX)
+    }
+}
+
+/// Collect a user's rows and the list of their blobs into archive contents
+pub async fn collect_archive(
+    pool: &PgPool,
+    storage: Option<&StorageClient>,
+    user_id: Uuid,
+    email: Option<String>,
+    schema_version: Option<i64>,
+) -> Result<ArchiveContents, AppError> {
+    let tables = ArchiveRepo::collect(pool, user_id).await?;
+
+    let mut blobs = Vec::new();
+    if let Some(storage) = storage {
+        let prefix = format!("{}/", user_id);
+        for category in ARCHIVED_BLOB_CATEGORIES {
+            for info in storage.list_blobs(&user_id, Some(category)).await? {
+                if let Some(path) = info.key.strip_prefix(&prefix) {
+                    blobs.push(format!("{}/{}", ARCHIVE_BLOBS_DIR, path));
+                }
+            }
+        }
+    }
+
+    let manifest = ArchiveManifest {
+        format_version: ARCHIVE_FORMAT_VERSION,
+        schema_version,
+        exported_at: Utc::now(),
+        user_id,
+        email,
+        tables: tables
+            .iter()
+            .map(|(table, rows)| (table.clone(), rows.len()))
+            .collect(),
+        blobs,
+    };
+
+    Ok(ArchiveContents { manifest, tables })
+}
+
+// ============================================================================
+// JOBS
+// ============================================================================
+
+/// Run an export job in the background
+pub fn spawn_export(state: Arc<AppState>, job: ArchiveJob, email: String) {
+    tokio::spawn(async move {
+        run_leased(&state, &job, run_export(&state, &job, email)).await;
+    });
+}
+
+/// Run an import job in the background
+pub fn spawn_import(state: Arc<AppState>, job: ArchiveJob) {
+    tokio::spawn(async move {
+        run_leased(&state, &job, run_import(&state, &job)).await;
+    });
+}
+
+/// Claim the job, run it while renewing the lease, and record the outcome
+///
+/// If the lease is lost (the job was failed as abandoned) the work stops
+/// and nothing is recorded.
+async fn run_leased(
+    state: &AppState,
+    job: &ArchiveJob,
+    work: impl std::future::Future<Output = Result<(String, i64, serde_json::Value), AppError>>,
+) {
+    let lease_owner = Uuid::new_v4();
+    match ArchiveJobRepo::claim(&state.db, job.id, lease_owner).await {
+        Ok(true) => {}
+        Ok(false) => {
+            tracing::warn!(job_id = %job.id, "Archive job was no longer pending");
+            return;
+        }
+        Err(e) => {
+            tracing::error!(job_id = %job.id, "Failed to claim archive job: {}", e);
+            return;
+        }
+    }
+
+    tokio::select! {
+        result = work => finish_job(&state.db, job, lease_owner, result).await,
+        () = keep_lease(&state.db, job.id, lease_owner) => {
+            tracing::warn!(job_id = %job.id, "Archive job lost its lease; stopped");
+        }
+    }
+}
+
+/// Renew the lease until it is lost
+async fn keep_lease(pool: &PgPool, job_id: Uuid, lease_owner: Uuid) {
+    let mut interval = tokio::time::interval(LEASE_RENEW_INTERVAL);
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        match ArchiveJobRepo::renew(pool, job_id, lease_owner).await {
+            Ok(true) => {}
+            Ok(false) => return,
+            // The lease outlasts a few failed renewals
+            Err(e) => tracing::warn!(job_id = %job_id, "Failed to renew archive job lease: {}", e),
+        }
+    }
+}
+
+/// Record the outcome; failures are kept on the job for the user to see
+async fn finish_job(
+    pool: &PgPool,
+    job: &ArchiveJob,
+    lease_owner: Uuid,
+    result: Result<(String, i64, serde_json::Value), AppError>,
+) {
+    let recorded = match result {
+        Ok((storage_key, size_bytes, summary)) => {
+            ArchiveJobRepo::complete(
+                pool,
+                job.id,
+                lease_owner,
+                &storage_key,
+                size_bytes,
+                &summary,
+            )
+            .await
+        }
+        Err(e) => {
+            tracing::warn!(job_id = %job.id, kind = %job.kind, "Archive job failed: {}", e);
+            ArchiveJobRepo::fail(pool, job.id, lease_owner, &e.to_string()).await
+        }
+    };
+    match recorded {
+        Ok(true) => {}
+        Ok(false) => {
+            tracing::warn!(job_id = %job.id, "Archive job lost its lease before finishing")
+        }
+        Err(e) => tracing::error!(job_id = %job.id, "Failed to record archive job outcome: {}", e),
+    }
+}
+
+fn storage(state: &AppState) -> Result<&StorageClient, AppError> {
+    state
+        .storage
+        .as_ref()
+        .ok_or_else(|| AppError::Config("Storage not configured".to_string()))
+}
+
+fn file_error(e: std::io::Error) -> AppError {
+    AppError::Internal(format!("Temporary file I/O failed: {}", e))
+}
+
+async fn run_export(
+    state: &AppState,
+    job: &ArchiveJob,
+    email: String,
+) -> Result<(String, i64, serde_json::Value), AppError> {
+    let storage = storage(state)?;
+
+    let contents = collect_archive(
+        &state.db,
+        Some(storage),
+        job.user_id,
+        Some(email),
+        state.get_schema_version().await,
+    )
+    .await?;
+    let summary = serde_json::json!({
+        "tables": contents.manifest.tables,
+        "blobs": contents.manifest.blobs.len(),
+    });
+    let blob_paths: Vec<String> = contents
+        .manifest
+        .blobs
+        .iter()
+        .filter_map(|b| b.strip_prefix(&format!("{}/", ARCHIVE_BLOBS_DIR)))
+        .map(String::from)
+        .collect();
+
+    let archive = TempFile::new("export");
+    let path = archive.path().to_path_buf();
+    let mut writer = blocking(move || {
+        let file = File::create(&path).map_err(file_error)?;
+        ArchiveWriter::new(file, &contents)
+    })
+    .await?;
+
+    for blob_path in blob_paths {
+        let blob = TempFile::new("export-blob");
+        storage
+            .get_to_file(&format!("{}/{}", job.user_id, blob_path), blob.path())
+            .await?;
+        writer = blocking(move || {
+            let mut data = File::open(blob.path()).map_err(file_error)?;
+            writer.add_blob(&blob_path, &mut data)?;
+            Ok(writer)
+        })
+        .await?;
+    }
+
+    let file = blocking(move || writer.finish()).await?;
+    let size_bytes = file.metadata().map_err(file_error)?.len();
+    drop(file);
+    validate_file_size(size_bytes, ARCHIVE_MIME_TYPE).map_err(AppError::Validation)?;
+
+    let (_, key) = generate_blob_key(&job.user_id, BlobCategory::Exports, "zip");
+    storage
+        .put_file_by_key(&key, archive.path(), ARCHIVE_MIME_TYPE)
+        .await?;
+
+    let link = storage.generate_signed_download_url(&key).await?;
+    InboxRepo::create(
+        &state.db,
+        job.user_id,
+        &CreateInboxRequest {
+            item_type: "export".to_string(),
+            title: "Your data export is ready".to_string(),
+            body: Some(format!(
+                "The download link expires in {} minutes. Request a new link from your account settings.",
+                SIGNED_URL_EXPIRY_SECONDS / 60
+            )),
+            action_url: Some(link.url),
+            action_data: Some(serde_json::json!({ "job_id": job.id })),
+            priority: None,
+            expires_at: Some(Utc::now() + chrono::Duration::seconds(SIGNED_URL_EXPIRY_SECONDS as i64)),
+        },
+    )
+    .await?;
+
+    Ok((key, size_bytes as i64, summary))
+}
+
+async fn run_import(
+    state: &AppState,
+    job: &ArchiveJob,
+) -> Result<(String, i64, serde_json::Value), AppError> {
+    let storage = storage(state)?;
+    let key = job
+        .storage_key
+        .clone()
+        .ok_or_else(|| AppError::Internal("Import job has no archive".to_string()))?;
+
+    let archive = TempFile::new("import");
+    let size_bytes = storage.get_to_file(&key, archive.path()).await?;
+
+    // Every blob path is checked before anything is uploaded
+    let user_id = job.user_id;
+    let path = archive.path().to_path_buf();
+    let (mut reader, contents, blobs) = blocking(move || {
+        let file = File::open(&path).map_err(file_error)?;
+        let mut reader = ArchiveReader::new(file)?;
+        let contents = reader.contents()?;
+        let mut blobs = Vec::new();
+        for (index, blob_path) in reader.blobs()? {
+            let key = format!("{}/{}", user_id, blob_path);
+            let parsed = parse_blob_key(&key)
+                .filter(|p| ARCHIVED_BLOB_CATEGORIES.contains(&p.category))
+                .ok_or_else(|| AppError::BadRequest(format!("Invalid blob path: {}", blob_path)))?;
+            blobs.push((index, key, get_mime_from_extension(&parsed.extension)));
+        }
+        Ok((reader, contents, blobs))
+    })
+    .await?;
+
+    // Blobs first: rows may point at them, and a failed upload leaves no rows behind
+    for (index, key, mime_type) in &blobs {
+        let blob = TempFile::new("import-blob");
+        let path = blob.path().to_path_buf();
+        let index = *index;
+        reader = blocking(move || {
+            let mut out = File::create(&path).map_err(file_error)?;
+            reader.extract_blob(index, &mut out)?;
+            out.flush().map_err(file_error)?;
+            Ok(reader)
+        })
+        .await?;
+        storage.put_file_by_key(key, blob.path(), mime_type).await?;
+    }
+
+    let restored = ArchiveRepo::restore(&state.db, job.user_id, &contents).await?;
+    let summary = serde_json::json!({
+        "tables": restored,
+        "blobs": blobs.len(),
+        "source_exported_at": contents.manifest.exported_at,
+    });
+
+    InboxRepo::create(
+        &state.db,
+        job.user_id,
+        &CreateInboxRequest {
+            item_type: "import".to_string(),
+            title: "Your data import is complete".to_string(),
+            body: Some(format!(
+                "Restored {} records and {} files.",
+                restored.values().sum::<usize>(),
+                blobs.len()
+            )),
+            action_url: None,
+            action_data: Some(serde_json::json!({ "job_id": job.id })),
+            priority: None,
+            expires_at: None,
+        },
+    )
+    .await?;
+
+    Ok((key, size_bytes as i64, summary))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn sample_contents() -> ArchiveContents {
+        let user_id = Uuid::new_v4();
+        let tables = BTreeMap::from([
+            (
+                "habits".to_string(),
+                vec![serde_json::json!({ "id": Uuid::new_v4(), "name": "Read" })],
+            ),
+            ("goals".to_string(), vec![]),
+        ]);
+        ArchiveContents {
+            manifest: ArchiveManifest {
+                format_version: ARCHIVE_FORMAT_VERSION,
+                schema_version: Some(12),
+                exported_at: Utc::now(),
+                user_id,
+                email: Some("a@example.com".to_string()),
+                tables: tables.iter().map(|(t, r)| (t.clone(), r.len())).collect(),
+                blobs: vec!["blobs/audio/x.mp3".to_string()],
+            },
+            tables,
+        }
+    }
+
+    fn write_sample() -> Vec<u8> {
+        let mut writer = ArchiveWriter::new(Cursor::new(Vec::new()), &sample_contents()).unwrap();
+        writer
+            .add_blob("audio/x.mp3", &mut Cursor::new(vec![1, 2, 3]))
+            .unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_archive_round_trip() {
+        let contents = sample_contents();
+        let mut writer = ArchiveWriter::new(Cursor::new(Vec::new()), &contents).unwrap();
+        writer
+            .add_blob("audio/x.mp3", &mut Cursor::new(vec![1, 2, 3]))
+            .unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+
+        let mut reader = ArchiveReader::new(Cursor::new(bytes)).unwrap();
+        let decoded = reader.contents().unwrap();
+        let blobs = reader.blobs().unwrap();
+        let mut data = Vec::new();
+        reader.extract_blob(blobs[0].0, &mut data).unwrap();
+
+        assert_eq!(decoded.manifest.user_id, contents.manifest.user_id);
+        assert_eq!(decoded.tables["habits"], contents.tables["habits"]);
+        assert_eq!(blobs.len(), 1);
+        assert_eq!(blobs[0].1, "audio/x.mp3");
+        assert_eq!(data, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_archive_groups_tables_by_domain() {
+        let zip = ZipArchive::new(Cursor::new(write_sample())).unwrap();
+        let names: Vec<&str> = zip.file_names().collect();
+
+        assert!(names.contains(&"data/habits_goals.json"));
+        assert!(names.contains(&"data/profile.json"));
+        assert!(!names.contains(&"data/habits.json"));
+    }
+
+    #[test]
+    fn test_archive_blobs_count_against_size_limit() {
+        let mut reader = ArchiveReader::new(Cursor::new(write_sample())).unwrap();
+        reader.remaining = 2;
+        let blobs = reader.blobs().unwrap();
+
+        let result = reader.extract_blob(blobs[0].0, &mut std::io::sink());
+
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn test_archive_without_manifest_is_rejected() {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file("data/habits_goals.json", SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(b"{}").unwrap();
+        let bytes = zip.finish().unwrap().into_inner();
+
+        let mut reader = ArchiveReader::new(Cursor::new(bytes)).unwrap();
+        assert!(matches!(reader.contents(), Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn test_garbage_is_rejected() {
+        assert!(matches!(
+            ArchiveReader::new(Cursor::new(b"not a zip".to_vec())),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+}
@@ -0,0 +1,155 @@
+//! Account deletion purger
+//!
+//! Purges accounts whose deletion grace period is over: first every stored
+//! object under the user's `{user_id}/` prefix, then the user's rows. Rows
+//! pointing at keys outside that prefix are removed with the account, but
+//! the objects stay: those keys are client-supplied and may belong to
+//! someone else. Each step is safe to repeat, so a
+//! purge that fails or dies half-way is simply claimed again once its lease
+//! runs out.
+
+use std::time::Duration;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db::account_deletion_models::*;
+use crate::db::account_deletion_repos::AccountDeletionRepo;
+use crate::error::AppError;
+use crate::shared::audit::{write_audit, AuditEventType};
+use crate::storage::StorageClient;
+
+/// Deletions claimed per run
+const BATCH_SIZE: i64 = 10;
+
+/// Background purger for due account deletions
+pub struct AccountPurger {
+    storage: Option<StorageClient>,
+    interval: Duration,
+}
+
+impl AccountPurger {
+    pub fn new(storage: Option<StorageClient>, interval: Duration) -> Self {
+        Self { storage, interval }
+    }
+
+    /// Run the purger until the process exits
+    pub fn start(self, pool: PgPool) {
+        tracing::info!(
+            storage = self.storage.is_some(),
+            "Starting account deletion purger"
+        );
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                match self.run_once(&pool).await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!("Purged {} deleted accounts", n),
+                    Err(e) => tracing::warn!("Account purger run failed: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Purge every due deletion, returning how many completed
+    pub async fn run_once(&self, pool: &PgPool) -> Result<usize, AppError> {
+        let mut purged = 0;
+
+        for deletion in AccountDeletionRepo::claim_due(pool, BATCH_SIZE).await? {
+            match purge_account(pool, self.storage.as_ref(), &deletion).await {
+                Ok(_) => purged += 1,
+                Err(e) => tracing::warn!(
+                    user_id = %deletion.user_id,
+                    attempts = deletion.attempts,
+                    "Account purge failed: {}",
+                    e
+                ),
+            }
+        }
+
+        Ok(purged)
+    }
+}
+
+/// Purge a claimed deletion: stored objects, then rows
+///
+/// Errors are recorded on the deletion before being returned.
+pub async fn purge_account(
+    pool: &PgPool,
+    storage: Option<&StorageClient>,
+    deletion: &AccountDeletion,
+) -> Result<AccountPurgeReport, AppError> {
+    let lease_owner = deletion
+        .lease_owner
+        .ok_or_else(|| AppError::Internal("Account deletion is not leased".to_string()))?;
+    match purge_steps(pool, storage, deletion.user_id, lease_owner).await {
+        Ok(completed) => {
+            write_audit(
+                pool.clone(),
+                AuditEventType::UserDeleted,
+                completed.requested_by,
+                &format!("Purged deleted account {}", completed.user_id),
+                Some("user"),
+                Some(completed.user_id),
+            );
+            Ok(completed.into())
+        }
+        Err(e) => {
+            AccountDeletionRepo::fail(pool, deletion.user_id, lease_owner, &e.to_string()).await?;
+            Err(e)
+        }
+    }
+}
+
+async fn purge_steps(
+    pool: &PgPool,
+    storage: Option<&StorageClient>,
+    user_id: Uuid,
+    lease_owner: Uuid,
+) -> Result<AccountDeletion, AppError> {
+    let prefix = format!("{}/", user_id);
+
+    match storage {
+        Some(storage) => {
+            let removed = storage.delete_prefix(&prefix).await?;
+            let removed = i32::try_from(removed).unwrap_or(i32::MAX);
+            AccountDeletionRepo::record_objects(pool, user_id, removed).await?;
+        }
+        None => tracing::warn!(
+            user_id = %user_id,
+            "Storage is not configured; purging account rows only"
+        ),
+    }
+
+    AccountDeletionRepo::complete(pool, user_id, lease_owner).await
+}
+
+/// Purge an account right away, skipping any grace period
+///
+/// Returns the stored report when the account was already purged.
+pub async fn purge_now(
+    pool: &PgPool,
+    storage: Option<&StorageClient>,
+    user_id: Uuid,
+    admin_id: Uuid,
+) -> Result<AccountPurgeReport, AppError> {
+    let existing = AccountDeletionRepo::get(pool, user_id).await?;
+    match existing {
+        Some(deletion) if deletion.status == ACCOUNT_DELETION_STATUS_COMPLETED => {
+            return Ok(deletion.into());
+        }
+        None if !AccountDeletionRepo::user_exists(pool, user_id).await? => {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+        _ => {}
+    }
+
+    let deletion = AccountDeletionRepo::claim_now(pool, user_id, admin_id)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("This account is already being purged".to_string()))?;
+
+    purge_account(pool, storage, &deletion).await
+}
@@ -11,6 +11,7 @@ use crate::db::{
     repos::{AccountRepo, AuditLogRepo, RbacRepo, SessionRepo, UserRepo},
 };
 use crate::error::AppError;
+use crate::services::oauth::TokenInfo;
 
 /// Authentication service
 pub struct AuthService;
@@ -20,21 +21,44 @@ impl AuthService {
     ///
     /// Account linking policy (per auth_inventory.md):
     /// - If account exists: link to existing user
-    /// - If email exists but no account: link new provider to existing user
+    /// - If email exists but no account: link new provider to existing user,
+    ///   but only when the provider vouches for the email (`email_verified`)
     /// - If neither: create new user and account
+    ///
+    /// Provider tokens, when given, are stored on the account via `AccountRepo::upsert`.
     pub async fn authenticate_oauth(
         pool: &PgPool,
         oauth_info: OAuthUserInfo,
+        tokens: Option<&TokenInfo>,
         user_agent: Option<String>,
         ip_address: Option<String>,
         session_ttl_days: i64,
     ) -> Result<(User, Session), AppError> {
         let provider = oauth_info.provider.as_str();
+        let access_token = tokens.map(|t| t.access_token.as_str());
+        let refresh_token = tokens.and_then(|t| t.refresh_token.as_deref());
+        let expires_at = tokens.and_then(|t| t.expires_at);
+        let id_token = tokens.and_then(|t| t.id_token.as_deref());
 
         // 1. Check if OAuth account already exists
         if let Some(account) =
             AccountRepo::find_by_provider(pool, provider, &oauth_info.provider_account_id).await?
         {
+            // Refresh stored provider tokens
+            if tokens.is_some() {
+                AccountRepo::upsert(
+                    pool,
+                    account.user_id,
+                    provider,
+                    &oauth_info.provider_account_id,
+                    access_token,
+                    refresh_token,
+                    expires_at,
+                    id_token,
+                )
+                .await?;
+            }
+
             // Account exists, get user and create session
             let user = UserRepo::find_by_id(pool, account.user_id)
                 .await?
@@ -79,101 +103,120 @@ impl AuthService {
         }
 
         // 2. Check if user exists by email (account linking)
-        let user =
-            if let Some(existing_user) = UserRepo::find_by_email(pool, &oauth_info.email).await? {
-                // User exists, link new OAuth provider
-                AccountRepo::upsert(
-                    pool,
-                    existing_user.id,
-                    provider,
-                    &oauth_info.provider_account_id,
-                    None, // access_token
-                    None, // refresh_token
-                    None, // expires_at
-                    None, // id_token
-                )
-                .await?;
+        let user = if let Some(existing_user) =
+            UserRepo::find_by_email(pool, &oauth_info.email).await?
+        {
+            // Linking on an unverified email would let anyone who can register
+            // that address at some provider take over the account
+            if !oauth_info.email_verified {
+                return Err(AppError::OAuthError(format!(
+                        "{} did not verify this email address. Sign in with your original provider instead.",
+                        provider
+                    )));
+            }
+
+            // The existing account may itself have been created from an
+            // unverified provider email, in which case it isn't really owned
+            // by whoever controls the address yet
+            if existing_user.email_verified.is_none() {
+                return Err(AppError::OAuthError(
+                    "This email address has not been verified on the existing account. Sign in with your original provider instead.".to_string(),
+                ));
+            }
+
+            // User exists, link new OAuth provider
+            AccountRepo::upsert(
+                pool,
+                existing_user.id,
+                provider,
+                &oauth_info.provider_account_id,
+                access_token,
+                refresh_token,
+                expires_at,
+                id_token,
+            )
+            .await?;
 
-                // Log account link
-                AuditLogRepo::log(
-                    pool,
-                    AuditLogEntry {
-                        user_id: Some(existing_user.id),
-                        session_id: None,
-                        event_type: "account_linked".to_string(),
-                        resource_type: Some("account".to_string()),
-                        resource_id: None,
-                        action: "create".to_string(),
-                        status: "success".to_string(),
-                        details: Some(serde_json::json!({
-                            "provider": provider,
-                            "email": oauth_info.email
-                        })),
-                        ip_address: ip_address.clone(),
-                        user_agent: None,
-                        request_id: None,
-                    },
-                )
-                .await?;
+            // Log account link
+            AuditLogRepo::log(
+                pool,
+                AuditLogEntry {
+                    user_id: Some(existing_user.id),
+                    session_id: None,
+                    event_type: "account_linked".to_string(),
+                    resource_type: Some("account".to_string()),
+                    resource_id: None,
+                    action: "create".to_string(),
+                    status: "success".to_string(),
+                    details: Some(serde_json::json!({
+                        "provider": provider,
+                        "email": oauth_info.email
+                    })),
+                    ip_address: ip_address.clone(),
+                    user_agent: None,
+                    request_id: None,
+                },
+            )
+            .await?;
 
-                existing_user
-            } else {
-                // 3. Create new user
-                let new_user = UserRepo::create(
-                    pool,
-                    CreateUserInput {
-                        email: oauth_info.email.clone(),
-                        name: oauth_info.name.unwrap_or_else(|| "User".to_string()),
-                        image: oauth_info.image,
-                        email_verified: if oauth_info.email_verified {
-                            Some(Utc::now())
-                        } else {
-                            None
-                        },
+            existing_user
+        } else {
+            // 3. Create new user
+            let new_user = UserRepo::create(
+                pool,
+                CreateUserInput {
+                    email: oauth_info.email.clone(),
+                    name: oauth_info.name.unwrap_or_else(|| "User".to_string()),
+                    image: oauth_info.image,
+                    email_verified: if oauth_info.email_verified {
+                        Some(Utc::now())
+                    } else {
+                        None
                     },
-                )
-                .await?;
+                },
+            )
+            .await?;
 
-                // Create OAuth account link
-                AccountRepo::upsert(
-                    pool,
-                    new_user.id,
-                    provider,
-                    &oauth_info.provider_account_id,
-                    None,
-                    None,
-                    None,
-                    None,
-                )
-                .await?;
+            // Create OAuth account link
+            AccountRepo::upsert(
+                pool,
+                new_user.id,
+                provider,
+                &oauth_info.provider_account_id,
+                access_token,
+                refresh_token,
+                expires_at,
+                id_token,
+            )
+            .await?;
 
-                // Assign default 'user' role
-                RbacRepo::assign_role(pool, new_user.id, "user", None).await?;
+            // Assign default 'user' role
+            RbacRepo::assign_role(pool, new_user.id, "user", None).await?;
 
-                // Log user creation
-                AuditLogRepo::log(
-                    pool,
-                    AuditLogEntry {
-                        user_id: Some(new_user.id),
-                        session_id: None,
-                        event_type: "user_created".to_string(),
-                        resource_type: Some("user".to_string()),
-                        resource_id: Some(new_user.id),
-                        action: "create".to_string(),
-                        status: "success".to_string(),
-                        details: Some(serde_json::json!({
-                            "provider": provider,
-                            "email": oauth_info.email
-                        })),
-                        ip_address: ip_address.clone(),
-                        user_agent: None,
-                        request_id: None,
-                    },
-                )
-                .await?;
+            // Log user creation
+            AuditLogRepo::log(
+                pool,
+                AuditLogEntry {
+                    user_id: Some(new_user.id),
+                    session_id: None,
+                    event_type: "user_created".to_string(),
+                    resource_type: Some("user".to_string()),
+                    resource_id: Some(new_user.id),
+                    action: "create".to_string(),
+                    status: "success".to_string(),
+                    details: Some(serde_json::json!({
+                        "provider": provider,
+                        "email": oauth_info.email
+                    })),
+                    ip_address: ip_address.clone(),
+                    user_agent: None,
+                    request_id: None,
+                },
+            )
+            .await?;
 
-                new_user
-            };
+            new_user
+        };
 
         // Create session for user
         let session = SessionRepo::create(
@@ -276,6 +319,54 @@ impl AuthService {
 
         Ok(new_session)
     }
+
+    /// Create a session for a user who signed in with a passkey
+    pub async fn authenticate_passkey(
+        pool: &PgPool,
+        user_id: Uuid,
+        authenticator_id: Uuid,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+        session_ttl_days: i64,
+    ) -> Result<(User, Session), AppError> {
+        let user = UserRepo::find_by_id(pool, user_id)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        let session = SessionRepo::create(
+            pool,
+            CreateSessionInput {
+                user_id: user.id,
+                user_agent,
+                ip_address: ip_address.clone(),
+            },
+            session_ttl_days,
+        )
+        .await?;
+
+        AuditLogRepo::log(
+            pool,
+            AuditLogEntry {
+                user_id: Some(user.id),
+                session_id: Some(session.id),
+                event_type: "login".to_string(),
+                resource_type: Some("session".to_string()),
+                resource_id: Some(session.id),
+                action: "create".to_string(),
+                status: "success".to_string(),
+                details: Some(serde_json::json!({
+                    "provider": "passkey",
+                    "authenticator_id": authenticator_id
+                })),
+                ip_address,
+                user_agent: None,
+                request_id: None,
+            },
+        )
+        .await?;
+
+        Ok((user, session))
+    }
 }
 
 /// Dev bypass authentication (for local development only)
@@ -0,0 +1,504 @@
+//! Logical backups for the admin console
+//!
+//! Every table is read in one REPEATABLE READ snapshot and streamed as
+//! gzip-compressed NDJSON into a temporary file; once the snapshot is closed
+//! the files are uploaded to `_backups/<id>/<table>.ndjson.gz`. The
+//! `manifest.json` is written last, so a backup without one is incomplete
+//! and cannot be restored.
+//!
+//! A restore only accepts a backup taken at the current schema version. The
+//! table files are downloaded first, then one transaction truncates, reloads
+//! tables with referenced tables first (with their own triggers off, so no
+//! sync events are generated) a batch of rows at a time, and moves serial
+//! sequences past the restored IDs.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures_util::TryStreamExt;
+use serde_json::Value;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db::admin_models::*;
+use crate::db::admin_repos::*;
+use crate::error::AppError;
+use crate::storage::{StorageClient, TempFile};
+
+/// Storage prefix for backups; user prefixes are UUIDs, so it cannot clash
+const BACKUP_PREFIX: &str = "_backups/";
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Timestamp at the start of every backup ID
+const BACKUP_ID_TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Rows inserted per statement on restore
+const INSERT_BATCH: usize = 500;
+
+/// Incomplete backups younger than this may still be running and are not
+/// pruned
+const INCOMPLETE_GRACE_HOURS: i64 = 6;
+
+fn io_error(e: std::io::Error) -> AppError {
+    AppError::Internal(format!("Backup I/O failed: {}", e))
+}
+
+/// New backup ID, sortable by creation time
+pub fn new_backup_id(now: DateTime<Utc>) -> String {
+    let suffix = Uuid::new_v4().simple().to_string();
+    format!("{}-{}", now.format(BACKUP_ID_TIME_FORMAT), &suffix[..8])
+}
+
+/// Creation time encoded in a backup ID; `None` for anything else
+pub fn backup_created_at(backup_id: &str) -> Option<DateTime<Utc>> {
+    let (time, suffix) = backup_id.split_once('-')?;
+    if suffix.is_empty() || !suffix.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    NaiveDateTime::parse_from_str(time, BACKUP_ID_TIME_FORMAT)
+        .ok()
+        .map(|t| t.and_utc())
+}
+
+fn manifest_key(backup_id: &str) -> String {
+    format!("{}{}/{}", BACKUP_PREFIX, backup_id, MANIFEST_FILE)
+}
+
+fn table_key(backup_id: &str, table: &str) -> String {
+    format!("{}{}/{}.ndjson.gz", BACKUP_PREFIX, backup_id, table)
+}
+
+/// Order tables so every table comes after the tables it references
+///
+/// Self-references are ignored; tables caught in a cycle go last, by name.
+pub fn fk_order(tables: &[String], foreign_keys: &[(String, String)]) -> Vec<String> {
+    let mut remaining: BTreeSet<&str> = tables.iter().map(String::as_str).collect();
+    let mut ordered = Vec::with_capacity(tables.len());
+
+    loop {
+        let ready: Vec<&str> = remaining
+            .iter()
+            .copied()
+            .filter(|table| {
+                !foreign_keys.iter().any(|(child, parent)| {
+                    child == table && parent != table && remaining.contains(parent.as_str())
+                })
+            })
+            .collect();
+        if ready.is_empty() {
+            break;
+        }
+        for table in ready {
+            remaining.remove(table);
+            ordered.push(table.to_string());
+        }
+    }
+
+    ordered.extend(remaining.into_iter().map(String::from));
+    ordered
+}
+
+/// IDs of backups that retention removes, given backups newest first
+///
+/// Keeps the newest `keep` complete backups and incomplete ones that may
+/// still be running.
+pub fn backups_to_prune(backups: &[BackupInfo], keep: usize, now: DateTime<Utc>) -> Vec<String> {
+    let mut complete = 0;
+    backups
+        .iter()
+        .filter(|b| {
+            if b.status == BACKUP_STATUS_COMPLETE {
+                complete += 1;
+                complete > keep
+            } else {
+                now - b.created_at > chrono::Duration::hours(INCOMPLETE_GRACE_HOURS)
+            }
+        })
+        .map(|b| b.id.clone())
+        .collect()
+}
+
+/// Write a backup of every table
+pub async fn create_backup(
+    pool: &PgPool,
+    storage: &StorageClient,
+    schema_version: Option<i64>,
+    created_by: Option<Uuid>,
+) -> Result<BackupManifest, AppError> {
+    let created_at = Utc::now();
+    let backup_id = new_backup_id(created_at);
+
+    let mut tx = pool.begin().await?;
+    sqlx::query(ADMIN_BACKUP_SNAPSHOT).execute(&mut *tx).await?;
+
+    let tables = AdminBackupRepo::tables(&mut tx).await?;
+    let foreign_keys = AdminBackupRepo::foreign_keys(&mut tx).await?;
+
+    let mut files = Vec::with_capacity(tables.len());
+    for table in fk_order(&tables, &foreign_keys) {
+        let file = TempFile::new("backup");
+        let out = File::create(file.path()).map_err(io_error)?;
+        let mut encoder = GzEncoder::new(BufWriter::new(out), Compression::default());
+        let mut rows = 0;
+
+        let select = backup_select_sql(&table);
+        let mut stream = sqlx::query_scalar::<_, String>(&select).fetch(&mut *tx);
+        while let Some(row) = stream.try_next().await? {
+            encoder.write_all(row.as_bytes()).map_err(io_error)?;
+            encoder.write_all(b"\n").map_err(io_error)?;
+            rows += 1;
+        }
+        drop(stream);
+
+        encoder
+            .finish()
+            .map_err(io_error)?
+            .flush()
+            .map_err(io_error)?;
+        files.push((table, rows, file));
+    }
+
+    // The snapshot is not held open while uploading
+    tx.commit().await?;
+
+    let mut written = Vec::with_capacity(files.len());
+    for (table, rows, file) in files {
+        let size_bytes = storage
+            .put_file_by_key(
+                &table_key(&backup_id, &table),
+                file.path(),
+                "application/gzip",
+            )
+            .await?;
+        written.push(BackupTable {
+            name: table,
+            rows,
+            size_bytes,
+        });
+    }
+
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        backup_id: backup_id.clone(),
+        created_at,
+        created_by,
+        schema_version,
+        tables: written,
+    };
+    let json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| AppError::Internal(format!("Failed to encode manifest: {}", e)))?;
+    storage
+        .put_by_key(&manifest_key(&backup_id), &json, "application/json")
+        .await?;
+
+    Ok(manifest)
+}
+
+/// Backups in storage, newest first
+pub async fn list_backups(storage: &StorageClient) -> Result<Vec<BackupInfo>, AppError> {
+    let mut backups: BTreeMap<String, BackupInfo> = BTreeMap::new();
+
+    for object in storage.list_by_prefix(BACKUP_PREFIX).await? {
+        let Some((backup_id, file)) = object
+            .key
+            .strip_prefix(BACKUP_PREFIX)
+            .and_then(|rest| rest.split_once('/'))
+        else {
+            continue;
+        };
+        let Some(created_at) = backup_created_at(backup_id) else {
+            continue;
+        };
+
+        let info = backups
+            .entry(backup_id.to_string())
+            .or_insert_with(|| BackupInfo {
+                id: backup_id.to_string(),
+                created_at,
+                size_bytes: 0,
+                status: BACKUP_STATUS_INCOMPLETE.to_string(),
+            });
+        info.size_bytes += object.size_bytes as i64;
+        if file == MANIFEST_FILE {
+            info.status = BACKUP_STATUS_COMPLETE.to_string();
+        }
+    }
+
+    Ok(backups.into_values().rev().collect())
+}
+
+/// Remove backups beyond the retention count, returning their IDs
+pub async fn prune_backups(storage: &StorageClient, keep: usize) -> Result<Vec<String>, AppError> {
+    let backups = list_backups(storage).await?;
+    let removed = backups_to_prune(&backups, keep, Utc::now());
+
+    for backup_id in &removed {
+        storage
+            .delete_prefix(&format!("{}{}/", BACKUP_PREFIX, backup_id))
+            .await?;
+    }
+
+    Ok(removed)
+}
+
+async fn read_manifest(
+    storage: &StorageClient,
+    backup_id: &str,
+) -> Result<BackupManifest, AppError> {
+    if backup_created_at(backup_id).is_none() {
+        return Err(AppError::BadRequest("Invalid backup ID".to_string()));
+    }
+
+    let key = manifest_key(backup_id);
+    let exists = storage
+        .list_by_prefix(&key)
+        .await?
+        .iter()
+        .any(|o| o.key == key);
+    if !exists {
+        return Err(AppError::NotFound("Backup not found".to_string()));
+    }
+
+    let data = storage.get_by_key(&key).await?;
+    serde_json::from_slice(&data)
+        .map_err(|e| AppError::BadRequest(format!("Invalid backup manifest: {}", e)))
+}
+
+/// Reads the rows of one decompressed table file a batch at a time
+struct RowReader<R: BufRead> {
+    lines: std::io::Lines<R>,
+}
+
+impl<R: BufRead> RowReader<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+        }
+    }
+
+    /// Up to `size` rows; empty once the file is exhausted
+    fn next_batch(&mut self, size: usize) -> Result<Vec<Value>, AppError> {
+        let mut batch = Vec::new();
+        while batch.len() < size {
+            let Some(line) = self.lines.next() else {
+                break;
+            };
+            let row = serde_json::from_str(&line.map_err(io_error)?)
+                .map_err(|e| AppError::BadRequest(format!("Invalid backup row: {}", e)))?;
+            batch.push(row);
+        }
+        Ok(batch)
+    }
+}
+
+/// Replace the contents of every table with a backup
+///
+/// Returns the number of rows loaded per table.
+pub async fn restore_backup(
+    pool: &PgPool,
+    storage: &StorageClient,
+    backup_id: &str,
+    schema_version: Option<i64>,
+) -> Result<BTreeMap<String, u64>, AppError> {
+    let manifest = read_manifest(storage, backup_id).await?;
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(AppError::BadRequest(format!(
+            "Backup format {} is newer than supported ({})",
+            manifest.format_version, BACKUP_FORMAT_VERSION
+        )));
+    }
+    if manifest.schema_version != schema_version {
+        return Err(AppError::BadRequest(format!(
+            "Backup was taken at schema version {:?}, the database is at {:?}",
+            manifest.schema_version, schema_version
+        )));
+    }
+
+    // Backups taken before a table was excluded still carry it; it is skipped
+    let entries: Vec<_> = manifest
+        .tables
+        .iter()
+        .filter(|t| !BACKUP_EXCLUDED_TABLES.contains(&t.name.as_str()))
+        .collect();
+
+    // Downloaded before the transaction, which locks every table
+    let mut files = BTreeMap::new();
+    for entry in &entries {
+        let file = TempFile::new("restore");
+        storage
+            .get_to_file(&table_key(backup_id, &entry.name), file.path())
+            .await?;
+        files.insert(entry.name.clone(), file);
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let tables = AdminBackupRepo::tables(&mut tx).await?;
+    if let Some(unknown) = entries.iter().find(|t| !tables.contains(&t.name)) {
+        return Err(AppError::BadRequest(format!(
+            "Backup contains unknown table {}",
+            unknown.name
+        )));
+    }
+    let foreign_keys = AdminBackupRepo::foreign_keys(&mut tx).await?;
+    let ordered = fk_order(&tables, &foreign_keys);
+
+    sqlx::query(&backup_truncate_sql(&ordered))
+        .execute(&mut *tx)
+        .await?;
+
+    let mut restored = BTreeMap::new();
+    for table in &ordered {
+        let (Some(entry), Some(file)) =
+            (entries.iter().find(|t| &t.name == table), files.get(table))
+        else {
+            continue;
+        };
+
+        sqlx::query(&backup_triggers_sql(table, false))
+            .execute(&mut *tx)
+            .await?;
+        let insert = backup_insert_sql(table);
+        let mut reader = RowReader::new(BufReader::new(GzDecoder::new(
+            File::open(file.path()).map_err(io_error)?,
+        )));
+        let mut rows = 0;
+        loop {
+            let batch = reader.next_batch(INSERT_BATCH)?;
+            if batch.is_empty() {
+                break;
+            }
+            rows += batch.len() as u64;
+            sqlx::query(&insert)
+                .bind(Value::Array(batch))
+                .execute(&mut *tx)
+                .await?;
+        }
+        if rows != entry.rows {
+            return Err(AppError::BadRequest(format!(
+                "Backup file for {} has {} rows, the manifest lists {}",
+                table, rows, entry.rows
+            )));
+        }
+        sqlx::query(&backup_triggers_sql(table, true))
+            .execute(&mut *tx)
+            .await?;
+
+        restored.insert(table.clone(), entry.rows);
+    }
+
+    for (table, column) in AdminBackupRepo::serial_columns(&mut tx).await? {
+        if restored.contains_key(&table) {
+            sqlx::query(&backup_reset_sequence_sql(&table, &column))
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+
+    tx.commit().await?;
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(id: &str, status: &str) -> BackupInfo {
+        BackupInfo {
+            id: id.to_string(),
+            created_at: backup_created_at(id).unwrap(),
+            size_bytes: 0,
+            status: status.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_backup_id_round_trip() {
+        let now = Utc::now();
+        let id = new_backup_id(now);
+        let parsed = backup_created_at(&id).unwrap();
+        assert_eq!(parsed.timestamp(), now.timestamp());
+
+        assert!(backup_created_at("../users").is_none());
+        assert!(backup_created_at("20260101T000000Z-zz/x").is_none());
+        assert!(backup_created_at("20260101T000000Z").is_none());
+    }
+
+    #[test]
+    fn test_fk_order_puts_parents_first() {
+        let tables: Vec<String> = ["drill_sessions", "drill_session_answers", "users", "habits"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let fks = vec![
+            (
+                "drill_session_answers".to_string(),
+                "drill_sessions".to_string(),
+            ),
+            ("drill_sessions".to_string(), "users".to_string()),
+            ("users".to_string(), "users".to_string()),
+        ];
+
+        let ordered = fk_order(&tables, &fks);
+        let pos = |t: &str| ordered.iter().position(|o| o == t).unwrap();
+
+        assert_eq!(ordered.len(), 4);
+        assert!(pos("users") < pos("drill_sessions"));
+        assert!(pos("drill_sessions") < pos("drill_session_answers"));
+    }
+
+    #[test]
+    fn test_fk_order_keeps_cycles() {
+        let tables = vec!["a".to_string(), "b".to_string()];
+        let fks = vec![
+            ("a".to_string(), "b".to_string()),
+            ("b".to_string(), "a".to_string()),
+        ];
+
+        assert_eq!(fk_order(&tables, &fks), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_prune_keeps_newest_complete() {
+        let now = backup_created_at("20260110T000000Z-00000000").unwrap();
+        let backups = vec![
+            info("20260109T230000Z-00000001", BACKUP_STATUS_INCOMPLETE),
+            info("20260109T000000Z-00000002", BACKUP_STATUS_COMPLETE),
+            info("20260108T000000Z-00000003", BACKUP_STATUS_COMPLETE),
+            info("20260107T000000Z-00000004", BACKUP_STATUS_INCOMPLETE),
+            info("20260106T000000Z-00000005", BACKUP_STATUS_COMPLETE),
+        ];
+
+        assert_eq!(
+            backups_to_prune(&backups, 2, now),
+            vec!["20260107T000000Z-00000004", "20260106T000000Z-00000005"]
+        );
+    }
+
+    #[test]
+    fn test_row_reader_decodes_ndjson_in_batches() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(b"{\"id\":1}\n{\"id\":2}\n{\"id\":3}\n")
+            .unwrap();
+        let data = encoder.finish().unwrap();
+
+        let mut reader = RowReader::new(BufReader::new(GzDecoder::new(&data[..])));
+
+        assert_eq!(
+            reader.next_batch(2).unwrap(),
+            vec![serde_json::json!({"id": 1}), serde_json::json!({"id": 2})]
+        );
+        assert_eq!(
+            reader.next_batch(2).unwrap(),
+            vec![serde_json::json!({"id": 3})]
+        );
+        assert!(reader.next_batch(2).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,845 @@
+//! Drill engine
+//!
+//! Server-side question generation, scoring and audio synthesis for
+//! ear-training drills (intervals, chord quality, EQ frequency, BPM tapping).
+//!
+//! A question set is a pure function of `(drill_type, config_json, seed, level)`,
+//! so the server can regenerate it when answers arrive instead of trusting
+//! anything the client reports about the questions or the score.
+
+use std::f64::consts::PI;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+
+use crate::db::learn_models::UserDrillStats;
+use crate::error::AppError;
+
+/// Lowest adaptive difficulty level
+pub const MIN_LEVEL: u8 = 1;
+/// Highest adaptive difficulty level
+pub const MAX_LEVEL: u8 = 5;
+
+const DEFAULT_QUESTION_COUNT: usize = 10;
+const MAX_QUESTION_COUNT: usize = 50;
+
+/// Sample rate for synthesized audio (high enough for the 16 kHz EQ band)
+pub const SAMPLE_RATE: u32 = 44_100;
+
+// ============================================================================
+// DRILL KINDS AND CONFIG
+// ============================================================================
+
+/// Drill types scored by the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrillKind {
+    Interval,
+    ChordQuality,
+    EqFrequency,
+    BpmTap,
+}
+
+impl DrillKind {
+    /// Map a `learn_drills.drill_type` value to a server-scored kind
+    pub fn from_drill_type(drill_type: &str) -> Option<Self> {
+        match drill_type {
+            "interval" | "interval_recognition" => Some(DrillKind::Interval),
+            "chord" | "chord_quality" => Some(DrillKind::ChordQuality),
+            "eq" | "eq_frequency" => Some(DrillKind::EqFrequency),
+            "bpm" | "bpm_tap" | "tempo" => Some(DrillKind::BpmTap),
+            _ => None,
+        }
+    }
+}
+
+/// Typed view of `learn_drills.config_json`
+///
+/// Every field is optional; unknown fields are ignored so existing
+/// drill rows keep working.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DrillConfig {
+    /// Number of questions per session
+    pub question_count: Option<usize>,
+    /// Per-question answer window; slower answers are scored as wrong
+    pub time_limit_ms: Option<u32>,
+    /// Interval pool override (e.g. `["M3", "P5"]`)
+    pub intervals: Option<Vec<String>>,
+    /// Chord quality pool override (e.g. `["major", "minor"]`)
+    pub qualities: Option<Vec<String>>,
+    /// EQ band pool override in Hz
+    pub bands: Option<Vec<u32>>,
+    /// Lowest tempo for BPM drills
+    pub min_bpm: Option<u32>,
+    /// Highest tempo for BPM drills
+    pub max_bpm: Option<u32>,
+    /// Pre-rendered clips in storage; when present questions are drawn from these
+    pub clips: Vec<DrillClip>,
+}
+
+impl DrillConfig {
+    /// Parse a drill's `config_json`
+    pub fn from_json(value: &serde_json::Value) -> Result<Self, AppError> {
+        if value.is_null() {
+            return Ok(Self::default());
+        }
+        serde_json::from_value(value.clone())
+            .map_err(|e| AppError::Internal(format!("Invalid drill config: {}", e)))
+    }
+
+    /// Number of questions to generate
+    pub fn question_count(&self) -> usize {
+        self.question_count
+            .unwrap_or(DEFAULT_QUESTION_COUNT)
+            .clamp(1, MAX_QUESTION_COUNT)
+    }
+}
+
+/// A stored audio clip referenced by a drill config
+#[derive(Debug, Clone, Deserialize)]
+pub struct DrillClip {
+    /// Full R2 key of the clip
+    pub r2_key: String,
+    /// Expected answer (choice label, or tempo for BPM drills)
+    pub answer: String,
+    /// Optional prompt override
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+// ============================================================================
+// QUESTIONS
+// ============================================================================
+
+/// Audio for a question. Never sent to the client, since it encodes the answer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AudioSource {
+    /// Sine-ish tones, played one after another or together
+    Tones {
+        frequencies: Vec<f64>,
+        arpeggiate: bool,
+    },
+    /// Pink noise played flat, then through a peaking filter
+    Noise { center_hz: f64, gain_db: f64 },
+    /// Metronome clicks
+    Clicks { bpm: f64, beats: u32 },
+    /// Pre-rendered clip in storage
+    Clip { r2_key: String },
+}
+
+/// Expected answer for a question
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpectedAnswer {
+    /// One of the question's choices
+    Choice(String),
+    /// A tempo within `tolerance` BPM
+    Tempo { bpm: f64, tolerance: f64 },
+}
+
+/// A generated question (server-side view)
+#[derive(Debug, Clone)]
+pub struct DrillQuestion {
+    pub index: usize,
+    pub prompt: String,
+    pub choices: Vec<String>,
+    pub audio: AudioSource,
+    pub expected: ExpectedAnswer,
+    pub time_limit_ms: Option<u32>,
+}
+
+impl DrillQuestion {
+    /// Check a submitted answer
+    pub fn is_correct(&self, answer: &str) -> bool {
+        let answer = answer.trim();
+        match &self.expected {
+            ExpectedAnswer::Choice(expected) => answer == expected,
+            ExpectedAnswer::Tempo { bpm, tolerance } => answer
+                .parse::<f64>()
+                .map(|value| (value - bpm).abs() <= *tolerance)
+                .unwrap_or(false),
+        }
+    }
+
+    /// Check a submitted answer, including the answer window
+    pub fn grade(&self, answer: &str, response_ms: u32) -> bool {
+        let in_time = self.time_limit_ms.is_none_or(|limit| response_ms <= limit);
+        in_time && self.is_correct(answer)
+    }
+
+    /// Human-readable expected answer (revealed after answering)
+    pub fn expected_label(&self) -> String {
+        match &self.expected {
+            ExpectedAnswer::Choice(label) => label.clone(),
+            ExpectedAnswer::Tempo { bpm, .. } => format!("{}", bpm.round()),
+        }
+    }
+
+    /// Input widget the client should show
+    pub fn input_type(&self) -> &'static str {
+        match self.expected {
+            ExpectedAnswer::Choice(_) => "choice",
+            ExpectedAnswer::Tempo { .. } => "tempo",
+        }
+    }
+}
+
+/// Intervals: (label, semitones, minimum level)
+const INTERVALS: &[(&str, i32, u8)] = &[
+    ("m2", 1, 3),
+    ("M2", 2, 2),
+    ("m3", 3, 2),
+    ("M3", 4, 1),
+    ("P4", 5, 1),
+    ("TT", 6, 4),
+    ("P5", 7, 1),
+    ("m6", 8, 3),
+    ("M6", 9, 2),
+    ("m7", 10, 3),
+    ("M7", 11, 3),
+    ("P8", 12, 1),
+];
+
+/// Chord qualities: (label, semitones above root, minimum level)
+const CHORDS: &[(&str, &[i32], u8)] = &[
+    ("major", &[0, 4, 7], 1),
+    ("minor", &[0, 3, 7], 1),
+    ("diminished", &[0, 3, 6], 2),
+    ("augmented", &[0, 4, 8], 2),
+    ("dom7", &[0, 4, 7, 10], 3),
+    ("maj7", &[0, 4, 7, 11], 3),
+    ("min7", &[0, 3, 7, 10], 3),
+    ("sus2", &[0, 2, 7], 4),
+    ("sus4", &[0, 5, 7], 4),
+    ("m7b5", &[0, 3, 6, 10], 5),
+];
+
+const EQ_EASY_BANDS: &[u32] = &[125, 500, 2000, 8000];
+const EQ_OCTAVE_BANDS: &[u32] = &[63, 125, 250, 500, 1000, 2000, 4000, 8000, 16000];
+const EQ_THIRD_OCTAVE_BANDS: &[u32] = &[
+    100, 125, 160, 200, 250, 315, 400, 500, 630, 800, 1000, 1250, 1600, 2000, 2500, 3150, 4000,
+    5000, 6300, 8000, 10000,
+];
+
+/// Generate the question set for a session
+pub fn generate_questions(
+    kind: DrillKind,
+    config: &DrillConfig,
+    seed: u64,
+    level: u8,
+) -> Vec<DrillQuestion> {
+    let level = level.clamp(MIN_LEVEL, MAX_LEVEL);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    (0..config.question_count())
+        .map(|index| {
+            let mut question = if config.clips.is_empty() {
+                match kind {
+                    DrillKind::Interval => interval_question(&mut rng, config, level),
+                    DrillKind::ChordQuality => chord_question(&mut rng, config, level),
+                    DrillKind::EqFrequency => eq_question(&mut rng, config, level),
+                    DrillKind::BpmTap => bpm_question(&mut rng, config, level),
+                }
+            } else {
+                clip_question(&mut rng, kind, config, level)
+            };
+            question.index = index;
+            question.time_limit_ms = config.time_limit_ms;
+            question
+        })
+        .collect()
+}
+
+fn interval_question(rng: &mut StdRng, config: &DrillConfig, level: u8) -> DrillQuestion {
+    let pool = level_pool(
+        INTERVALS
+            .iter()
+            .map(|(label, semis, min)| (*label, *semis, *min)),
+        config.intervals.as_deref(),
+        level,
+    );
+    let (label, semitones) = *pool.choose(rng).expect("interval pool is never empty");
+
+    let root = rng.gen_range(48..=67);
+    let descending = level >= 3 && rng.gen_bool(0.5);
+    let harmonic = level >= 4 && rng.gen_bool(0.5);
+    let (first, second) = if descending {
+        (root + semitones, root)
+    } else {
+        (root, root + semitones)
+    };
+
+    DrillQuestion {
+        index: 0,
+        prompt: "Name the interval".to_string(),
+        choices: pool.iter().map(|(label, _)| label.to_string()).collect(),
+        audio: AudioSource::Tones {
+            frequencies: vec![midi_to_hz(first), midi_to_hz(second)],
+            arpeggiate: !harmonic,
+        },
+        expected: ExpectedAnswer::Choice(label.to_string()),
+        time_limit_ms: None,
+    }
+}
+
+fn chord_question(rng: &mut StdRng, config: &DrillConfig, level: u8) -> DrillQuestion {
+    let pool = level_pool(
+        CHORDS
+            .iter()
+            .map(|(label, notes, min)| (*label, *notes, *min)),
+        config.qualities.as_deref(),
+        level,
+    );
+    let (label, notes) = *pool.choose(rng).expect("chord pool is never empty");
+
+    let root = rng.gen_range(48..=60);
+    let mut midi: Vec<i32> = notes.iter().map(|n| root + n).collect();
+    // Inversions from level 4: move the lowest notes up an octave
+    if level >= 4 {
+        let inversion = rng.gen_range(0..notes.len());
+        for note in midi.iter_mut().take(inversion) {
+            *note += 12;
+        }
+    }
+
+    DrillQuestion {
+        index: 0,
+        prompt: "Name the chord quality".to_string(),
+        choices: pool.iter().map(|(label, _)| label.to_string()).collect(),
+        audio: AudioSource::Tones {
+            frequencies: midi.into_iter().map(midi_to_hz).collect(),
+            arpeggiate: level <= 1,
+        },
+        expected: ExpectedAnswer::Choice(label.to_string()),
+        time_limit_ms: None,
+    }
+}
+
+fn eq_question(rng: &mut StdRng, config: &DrillConfig, level: u8) -> DrillQuestion {
+    let bands: Vec<u32> = match &config.bands {
+        Some(bands) if !bands.is_empty() => bands.clone(),
+        _ => match level {
+            1 => EQ_EASY_BANDS.to_vec(),
+            2 | 3 => EQ_OCTAVE_BANDS.to_vec(),
+            _ => EQ_THIRD_OCTAVE_BANDS.to_vec(),
+        },
+    };
+    let band = *bands.choose(rng).expect("band pool is never empty");
+
+    let magnitude = match level {
+        1 => 12.0,
+        2 => 9.0,
+        3 | 4 => 6.0,
+        _ => 4.0,
+    };
+    let cut = level >= 3 && rng.gen_bool(0.5);
+    let gain_db = if cut { -magnitude } else { magnitude };
+
+    DrillQuestion {
+        index: 0,
+        prompt: if cut {
+            "Which frequency band was cut?".to_string()
+        } else {
+            "Which frequency band was boosted?".to_string()
+        },
+        choices: bands.iter().map(|b| b.to_string()).collect(),
+        audio: AudioSource::Noise {
+            center_hz: band as f64,
+            gain_db,
+        },
+        expected: ExpectedAnswer::Choice(band.to_string()),
+        time_limit_ms: None,
+    }
+}
+
+fn bpm_question(rng: &mut StdRng, config: &DrillConfig, level: u8) -> DrillQuestion {
+    let min = config.min_bpm.unwrap_or(70).max(20);
+    let max = config.max_bpm.unwrap_or(160).max(min);
+    let bpm = rng.gen_range(min..=max) as f64;
+    let beats = 10u32.saturating_sub(level as u32).max(4);
+
+    DrillQuestion {
+        index: 0,
+        prompt: "Tap along and enter the tempo".to_string(),
+        choices: Vec::new(),
+        audio: AudioSource::Clicks { bpm, beats },
+        expected: ExpectedAnswer::Tempo {
+            bpm,
+            tolerance: tempo_tolerance(level),
+        },
+        time_limit_ms: None,
+    }
+}
+
+fn clip_question(
+    rng: &mut StdRng,
+    kind: DrillKind,
+    config: &DrillConfig,
+    level: u8,
+) -> DrillQuestion {
+    let clip = config.clips.choose(rng).expect("clip pool is never empty");
+
+    let (choices, expected) = match kind {
+        DrillKind::BpmTap => (
+            Vec::new(),
+            ExpectedAnswer::Tempo {
+                bpm: clip.answer.trim().parse().unwrap_or(0.0),
+                tolerance: tempo_tolerance(level),
+            },
+        ),
+        _ => {
+            let mut choices: Vec<String> = config.clips.iter().map(|c| c.answer.clone()).collect();
+            choices.sort();
+            choices.dedup();
+            (choices, ExpectedAnswer::Choice(clip.answer.clone()))
+        }
+    };
+
+    DrillQuestion {
+        index: 0,
+        prompt: clip
+            .prompt
+            .clone()
+            .unwrap_or_else(|| "Listen and answer".to_string()),
+        choices,
+        audio: AudioSource::Clip {
+            r2_key: clip.r2_key.clone(),
+        },
+        expected,
+        time_limit_ms: None,
+    }
+}
+
+/// Filter a (label, value, min_level) table by level and an optional config override.
+///
+/// An explicit override wins over the level filter when the two don't overlap,
+/// so authored drills never end up with an empty pool.
+fn level_pool<T: Copy>(
+    table: impl Iterator<Item = (&'static str, T, u8)>,
+    allowed: Option<&[String]>,
+    level: u8,
+) -> Vec<(&'static str, T)> {
+    let table: Vec<_> = table.collect();
+    let allowed_by_config = |label: &str| allowed.is_none_or(|a| a.iter().any(|x| x == label));
+
+    let pool: Vec<_> = table
+        .iter()
+        .filter(|(label, _, min)| *min <= level && allowed_by_config(label))
+        .map(|(label, value, _)| (*label, *value))
+        .collect();
+    if !pool.is_empty() {
+        return pool;
+    }
+
+    let pool: Vec<_> = table
+        .iter()
+        .filter(|(label, _, _)| allowed_by_config(label))
+        .map(|(label, value, _)| (*label, *value))
+        .collect();
+    if !pool.is_empty() {
+        return pool;
+    }
+
+    table
+        .iter()
+        .filter(|(_, _, min)| *min <= level)
+        .map(|(label, value, _)| (*label, *value))
+        .collect()
+}
+
+fn tempo_tolerance(level: u8) -> f64 {
+    match level {
+        1 => 6.0,
+        2 => 4.0,
+        3 => 3.0,
+        4 => 2.0,
+        _ => 1.0,
+    }
+}
+
+fn midi_to_hz(note: i32) -> f64 {
+    440.0 * 2f64.powf((note - 69) as f64 / 12.0)
+}
+
+/// Seed for a single question's audio (noise generation)
+pub fn question_seed(session_seed: u64, index: usize) -> u64 {
+    session_seed ^ (index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+// ============================================================================
+// SCORING AND DIFFICULTY
+// ============================================================================
+
+/// Percentage score (0-100)
+pub fn score(correct: usize, total: usize) -> i32 {
+    if total == 0 {
+        return 0;
+    }
+    ((correct as f64 / total as f64) * 100.0).round() as i32
+}
+
+/// Pick the session difficulty from the drill's authored difficulty and the
+/// user's history: consistently strong results step it up, weak ones step it down.
+pub fn adaptive_level(difficulty: &str, stats: Option<&UserDrillStats>) -> u8 {
+    let base: i32 = match difficulty {
+        "intermediate" => 2,
+        "advanced" => 3,
+        _ => 1,
+    };
+
+    let adjustment = match stats {
+        Some(s) if s.total_attempts >= 3 => {
+            let average = s.average_score.unwrap_or(0.0);
+            if average >= 90.0 && s.current_streak >= 3 {
+                2
+            } else if average >= 80.0 {
+                1
+            } else if average < 50.0 {
+                -1
+            } else {
+                0
+            }
+        }
+        _ => 0,
+    };
+
+    (base + adjustment).clamp(MIN_LEVEL as i32, MAX_LEVEL as i32) as u8
+}
+
+// ============================================================================
+// AUDIO SYNTHESIS
+// ============================================================================
+
+/// Render a question's audio as a 16-bit mono WAV.
+///
+/// Returns `None` for stored clips, which are served from storage instead.
+pub fn render_wav(audio: &AudioSource, seed: u64) -> Option<Vec<u8>> {
+    let samples = match audio {
+        AudioSource::Tones {
+            frequencies,
+            arpeggiate,
+        } => synth_tones(frequencies, *arpeggiate),
+        AudioSource::Noise { center_hz, gain_db } => synth_eq_noise(*center_hz, *gain_db, seed),
+        AudioSource::Clicks { bpm, beats } => synth_clicks(*bpm, *beats),
+        AudioSource::Clip { .. } => return None,
+    };
+    Some(encode_wav(&samples))
+}
+
+fn synth_tones(frequencies: &[f64], arpeggiate: bool) -> Vec<f32> {
+    let rate = SAMPLE_RATE as f64;
+    if arpeggiate {
+        let note_len = (0.9 * rate) as usize;
+        let mut out = Vec::with_capacity(note_len * frequencies.len());
+        for &freq in frequencies {
+            out.extend(
+                (0..note_len).map(|i| (tone_sample(freq, i) * envelope(i, note_len)) as f32 * 0.6),
+            );
+        }
+        out
+    } else {
+        let len = (1.6 * rate) as usize;
+        let voices = frequencies.len().max(1) as f64;
+        (0..len)
+            .map(|i| {
+                let mix: f64 = frequencies.iter().map(|&f| tone_sample(f, i)).sum();
+                (mix / voices * envelope(i, len)) as f32 * 0.6
+            })
+            .collect()
+    }
+}
+
+/// A soft, piano-ish tone: fundamental plus two quieter harmonics
+fn tone_sample(freq: f64, i: usize) -> f64 {
+    let t = i as f64 / SAMPLE_RATE as f64;
+    let w = 2.0 * PI * freq * t;
+    (w.sin() + 0.3 * (2.0 * w).sin() + 0.1 * (3.0 * w).sin()) / 1.4
+}
+
+/// Linear attack/release envelope to avoid clicks
+fn envelope(i: usize, len: usize) -> f64 {
+    let attack = (0.01 * SAMPLE_RATE as f64) as usize;
+    let release = (0.12 * SAMPLE_RATE as f64) as usize;
+    if i < attack {
+        i as f64 / attack as f64
+    } else if i + release > len {
+        (len - i) as f64 / release as f64
+    } else {
+        1.0
+    }
+}
+
+fn synth_eq_noise(center_hz: f64, gain_db: f64, seed: u64) -> Vec<f32> {
+    let rate = SAMPLE_RATE as f64;
+    let half = (2.0 * rate) as usize;
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    // Paul Kellet's economy pink noise filter
+    let (mut b0, mut b1, mut b2) = (0.0f64, 0.0f64, 0.0f64);
+    let mut pink: Vec<f64> = (0..half * 2)
+        .map(|_| {
+            let white: f64 = rng.gen_range(-1.0..1.0);
+            b0 = 0.99765 * b0 + white * 0.099_046;
+            b1 = 0.963 * b1 + white * 0.296_516_4;
+            b2 = 0.57 * b2 + white * 1.052_691_3;
+            b0 + b1 + b2 + white * 0.1848
+        })
+        .collect();
+
+    // RBJ peaking EQ on the second half only: flat reference, then processed
+    let q = 1.4;
+    let a = 10f64.powf(gain_db / 40.0);
+    let w0 = 2.0 * PI * center_hz.min(rate / 2.0 - 1.0) / rate;
+    let alpha = w0.sin() / (2.0 * q);
+    let cos_w0 = w0.cos();
+    let a0 = 1.0 + alpha / a;
+    let (c0, c1, c2) = (
+        (1.0 + alpha * a) / a0,
+        (-2.0 * cos_w0) / a0,
+        (1.0 - alpha * a) / a0,
+    );
+    let (d1, d2) = ((-2.0 * cos_w0) / a0, (1.0 - alpha / a) / a0);
+
+    let (mut x1, mut x2, mut y1, mut y2) = (0.0, 0.0, 0.0, 0.0);
+    for sample in pink.iter_mut().skip(half) {
+        let x0 = *sample;
+        let y0 = c0 * x0 + c1 * x1 + c2 * x2 - d1 * y1 - d2 * y2;
+        x2 = x1;
+        x1 = x0;
+        y2 = y1;
+        y1 = y0;
+        *sample = y0;
+    }
+
+    let peak = pink
+        .iter()
+        .fold(0.0f64, |m, s| m.max(s.abs()))
+        .max(f64::EPSILON);
+    let len = pink.len();
+    pink.iter()
+        .enumerate()
+        .map(|(i, s)| (s / peak * 0.8 * envelope(i, len)) as f32)
+        .collect()
+}
+
+fn synth_clicks(bpm: f64, beats: u32) -> Vec<f32> {
+    let rate = SAMPLE_RATE as f64;
+    let beat_len = (60.0 / bpm.max(1.0) * rate) as usize;
+    let click_len = (0.015 * rate) as usize;
+    let mut out = vec![0.0f32; beat_len * beats as usize];
+
+    for beat in 0..beats as usize {
+        let freq = if beat % 4 == 0 { 2000.0 } else { 1500.0 };
+        let start = beat * beat_len;
+        for i in 0..click_len.min(beat_len) {
+            let t = i as f64 / rate;
+            let decay = (-(i as f64) / (click_len as f64 / 5.0)).exp();
+            out[start + i] = ((2.0 * PI * freq * t).sin() * decay * 0.8) as f32;
+        }
+    }
+    out
+}
+
+fn encode_wav(samples: &[f32]) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut out = Vec::with_capacity(44 + data_len as usize);
+
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + data_len).to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+    out.extend_from_slice(b"fmt ");
+    out.extend_from_slice(&16u32.to_le_bytes()); // fmt chunk size
+    out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    out.extend_from_slice(&1u16.to_le_bytes()); // mono
+    out.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    out.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // byte rate
+    out.extend_from_slice(&2u16.to_le_bytes()); // block align
+    out.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+
+    for sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    out
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn stats(total_attempts: i32, average: f64, streak: i32) -> UserDrillStats {
+        UserDrillStats {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            drill_id: Uuid::new_v4(),
+            total_attempts,
+            correct_answers: 0,
+            best_score: None,
+            average_score: Some(average),
+            current_streak: streak,
+            best_streak: streak,
+            last_attempt_at: None,
+            total_time_seconds: 0,
+        }
+    }
+
+    #[test]
+    fn test_drill_kind_from_type() {
+        assert_eq!(
+            DrillKind::from_drill_type("interval_recognition"),
+            Some(DrillKind::Interval)
+        );
+        assert_eq!(
+            DrillKind::from_drill_type("bpm_tap"),
+            Some(DrillKind::BpmTap)
+        );
+        assert_eq!(DrillKind::from_drill_type("flashcard"), None);
+    }
+
+    #[test]
+    fn test_generation_is_deterministic() {
+        let config = DrillConfig::default();
+        let a = generate_questions(DrillKind::Interval, &config, 42, 3);
+        let b = generate_questions(DrillKind::Interval, &config, 42, 3);
+        assert_eq!(a.len(), DEFAULT_QUESTION_COUNT);
+        for (qa, qb) in a.iter().zip(b.iter()) {
+            assert_eq!(qa.expected, qb.expected);
+            assert_eq!(qa.audio, qb.audio);
+        }
+
+        let c = generate_questions(DrillKind::Interval, &config, 43, 3);
+        assert!(a.iter().zip(c.iter()).any(|(qa, qc)| qa.audio != qc.audio));
+    }
+
+    #[test]
+    fn test_level_limits_interval_pool() {
+        let config = DrillConfig {
+            question_count: Some(50),
+            ..Default::default()
+        };
+        for q in generate_questions(DrillKind::Interval, &config, 7, 1) {
+            assert_eq!(q.choices, vec!["M3", "P4", "P5", "P8"]);
+            assert!(q.choices.contains(&q.expected_label()));
+        }
+    }
+
+    #[test]
+    fn test_config_overrides_pool() {
+        let config = DrillConfig {
+            qualities: Some(vec!["major".to_string(), "minor".to_string()]),
+            ..Default::default()
+        };
+        for q in generate_questions(DrillKind::ChordQuality, &config, 1, 5) {
+            assert_eq!(q.choices, vec!["major", "minor"]);
+        }
+    }
+
+    #[test]
+    fn test_choice_grading() {
+        let config = DrillConfig::default();
+        let q = &generate_questions(DrillKind::EqFrequency, &config, 9, 2)[0];
+        let expected = q.expected_label();
+        assert!(q.is_correct(&expected));
+        assert!(q.is_correct(&format!(" {} ", expected)));
+        assert!(!q.is_correct("not-a-band"));
+    }
+
+    #[test]
+    fn test_tempo_tolerance() {
+        let q = DrillQuestion {
+            index: 0,
+            prompt: String::new(),
+            choices: vec![],
+            audio: AudioSource::Clicks {
+                bpm: 120.0,
+                beats: 8,
+            },
+            expected: ExpectedAnswer::Tempo {
+                bpm: 120.0,
+                tolerance: 3.0,
+            },
+            time_limit_ms: Some(5000),
+        };
+        assert!(q.grade("122.5", 1000));
+        assert!(!q.grade("124", 1000));
+        assert!(!q.grade("fast", 1000));
+        assert!(!q.grade("120", 6000));
+    }
+
+    #[test]
+    fn test_clip_questions() {
+        let config = DrillConfig {
+            clips: vec![
+                DrillClip {
+                    r2_key: "drills/a.wav".to_string(),
+                    answer: "1000".to_string(),
+                    prompt: None,
+                },
+                DrillClip {
+                    r2_key: "drills/b.wav".to_string(),
+                    answer: "4000".to_string(),
+                    prompt: None,
+                },
+            ],
+            ..Default::default()
+        };
+        for q in generate_questions(DrillKind::EqFrequency, &config, 5, 1) {
+            assert_eq!(q.choices, vec!["1000", "4000"]);
+            assert!(matches!(q.audio, AudioSource::Clip { .. }));
+            assert!(render_wav(&q.audio, 0).is_none());
+        }
+    }
+
+    #[test]
+    fn test_score() {
+        assert_eq!(score(0, 0), 0);
+        assert_eq!(score(7, 10), 70);
+        assert_eq!(score(2, 3), 67);
+    }
+
+    #[test]
+    fn test_adaptive_level() {
+        assert_eq!(adaptive_level("beginner", None), 1);
+        assert_eq!(adaptive_level("advanced", None), 3);
+        // Too few attempts to adapt
+        assert_eq!(adaptive_level("beginner", Some(&stats(2, 100.0, 5))), 1);
+        assert_eq!(adaptive_level("beginner", Some(&stats(5, 85.0, 0))), 2);
+        assert_eq!(adaptive_level("intermediate", Some(&stats(5, 95.0, 4))), 4);
+        assert_eq!(adaptive_level("beginner", Some(&stats(5, 30.0, 0))), 1);
+        assert_eq!(adaptive_level("advanced", Some(&stats(10, 99.0, 10))), 5);
+    }
+
+    #[test]
+    fn test_wav_header() {
+        let wav = render_wav(
+            &AudioSource::Tones {
+                frequencies: vec![440.0, 660.0],
+                arpeggiate: true,
+            },
+            0,
+        )
+        .unwrap();
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(&wav[8..12], b"WAVE");
+        let data_len = u32::from_le_bytes([wav[40], wav[41], wav[42], wav[43]]) as usize;
+        assert_eq!(wav.len(), 44 + data_len);
+    }
+
+    #[test]
+    fn test_eq_noise_is_seeded() {
+        let audio = AudioSource::Noise {
+            center_hz: 1000.0,
+            gain_db: 9.0,
+        };
+        assert_eq!(render_wav(&audio, 11), render_wav(&audio, 11));
+        assert_ne!(render_wav(&audio, 11), render_wav(&audio, 12));
+    }
+}
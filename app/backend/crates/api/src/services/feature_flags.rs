@@ -0,0 +1,215 @@
+//! Feature flags
+//!
+//! Flags are read from `feature_flags` into memory and refreshed
+//! periodically, so evaluating one never touches the database. Admin edits
+//! refresh the instance that made them at once; other instances pick them up
+//! on their next refresh.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db::feature_flag_models::FeatureFlag;
+use crate::db::feature_flag_repos::FeatureFlagRepo;
+use crate::error::AppError;
+use crate::middleware::auth::AuthContext;
+
+/// How often flags are reloaded from the database
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Who a flag is evaluated for
+#[derive(Debug, Clone, Copy)]
+pub struct FlagSubject<'a> {
+    pub user_id: Uuid,
+    pub role: &'a str,
+    pub entitlements: &'a [String],
+}
+
+impl<'a> From<&'a AuthContext> for FlagSubject<'a> {
+    fn from(auth: &'a AuthContext) -> Self {
+        Self {
+            user_id: auth.user_id,
+            role: &auth.role,
+            entitlements: &auth.entitlements,
+        }
+    }
+}
+
+/// In-memory copy of every flag
+#[derive(Clone, Default)]
+pub struct FeatureFlags {
+    flags: Arc<RwLock<HashMap<String, FeatureFlag>>>,
+}
+
+impl FeatureFlags {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reload flags periodically (call `refresh` once first)
+    pub fn start(&self, pool: PgPool) {
+        let flags = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+            // The first tick completes immediately and startup has just loaded
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                if let Err(e) = flags.refresh(&pool).await {
+                    tracing::warn!("Failed to refresh feature flags: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Replace the in-memory flags with the database's; returns how many
+    pub async fn refresh(&self, pool: &PgPool) -> Result<usize, AppError> {
+        let loaded: HashMap<String, FeatureFlag> = FeatureFlagRepo::list(pool)
+            .await?
+            .into_iter()
+            .map(|f| (f.flag_name.clone(), f))
+            .collect();
+        let count = loaded.len();
+        *self.flags.write().unwrap_or_else(|e| e.into_inner()) = loaded;
+        Ok(count)
+    }
+
+    /// Whether a flag is on; unknown flags are off
+    pub fn is_enabled(&self, flag_name: &str, subject: Option<FlagSubject<'_>>) -> bool {
+        self.flags
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(flag_name)
+            .is_some_and(|flag| flag_enabled(flag, subject))
+    }
+
+    /// Every flag's value for one subject, as sent to the client
+    pub fn evaluate_all(&self, subject: Option<FlagSubject<'_>>) -> BTreeMap<String, bool> {
+        self.flags
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .map(|flag| (flag.flag_name.clone(), flag_enabled(flag, subject)))
+            .collect()
+    }
+}
+
+/// Evaluate one flag
+///
+/// Targeted users always get an enabled flag; everyone else falls into the
+/// rollout by bucket. Without a user only a full rollout is on.
+pub fn flag_enabled(flag: &FeatureFlag, subject: Option<FlagSubject<'_>>) -> bool {
+    if !flag.enabled {
+        return false;
+    }
+    let Some(subject) = subject else {
+        return flag.rollout_percentage >= 100;
+    };
+
+    let targeted = flag.target_user_ids.contains(&subject.user_id)
+        || flag.target_roles.iter().any(|r| r == subject.role)
+        || flag
+            .target_entitlements
+            .iter()
+            .any(|e| subject.entitlements.contains(e));
+
+    targeted || (rollout_bucket(&flag.flag_name, subject.user_id) as i32) < flag.rollout_percentage
+}
+
+/// Stable 0-99 bucket of a user for one flag
+///
+/// Hashing the flag name in keeps different flags' rollouts independent.
+pub fn rollout_bucket(flag_name: &str, user_id: Uuid) -> u32 {
+    let digest = Sha256::new()
+        .chain_update(flag_name.as_bytes())
+        .chain_update(b":")
+        .chain_update(user_id.as_bytes())
+        .finalize();
+    u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]) % 100
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn flag(enabled: bool, rollout_percentage: i32) -> FeatureFlag {
+        FeatureFlag {
+            id: Uuid::new_v4(),
+            flag_name: "new_editor".to_string(),
+            enabled,
+            description: None,
+            metadata: None,
+            rollout_percentage,
+            target_user_ids: vec![],
+            target_roles: vec![],
+            target_entitlements: vec![],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn subject(user_id: Uuid) -> FlagSubject<'static> {
+        FlagSubject {
+            user_id,
+            role: "user",
+            entitlements: &[],
+        }
+    }
+
+    #[test]
+    fn test_disabled_flag_ignores_targeting() {
+        let mut f = flag(false, 100);
+        let user_id = Uuid::new_v4();
+        f.target_user_ids.push(user_id);
+
+        assert!(!flag_enabled(&f, Some(subject(user_id))));
+    }
+
+    #[test]
+    fn test_targeting_overrides_rollout() {
+        let mut f = flag(true, 0);
+        let user_id = Uuid::new_v4();
+        assert!(!flag_enabled(&f, Some(subject(user_id))));
+
+        f.target_roles.push("user".to_string());
+        assert!(flag_enabled(&f, Some(subject(user_id))));
+
+        f.target_roles.clear();
+        f.target_entitlements.push("beta".to_string());
+        let entitlements = ["beta".to_string()];
+        let beta = FlagSubject {
+            entitlements: &entitlements,
+            ..subject(user_id)
+        };
+        assert!(flag_enabled(&f, Some(beta)));
+    }
+
+    #[test]
+    fn test_rollout_is_stable_and_proportional() {
+        let f = flag(true, 30);
+        let users: Vec<Uuid> = (0..2000).map(|_| Uuid::new_v4()).collect();
+
+        let on = users
+            .iter()
+            .filter(|u| flag_enabled(&f, Some(subject(**u))))
+            .count();
+        assert!((450..750).contains(&on), "{} of 2000 in a 30% rollout", on);
+
+        let u = users[0];
+        assert_eq!(
+            rollout_bucket("new_editor", u),
+            rollout_bucket("new_editor", u)
+        );
+    }
+
+    #[test]
+    fn test_anonymous_needs_full_rollout() {
+        assert!(flag_enabled(&flag(true, 100), None));
+        assert!(!flag_enabled(&flag(true, 99), None));
+    }
+}
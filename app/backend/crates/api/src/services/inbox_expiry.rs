@@ -0,0 +1,42 @@
+//! Inbox expiry
+//!
+//! Archives inbox items left unprocessed past their `expires_at`, so stale
+//! captures drop out of the inbox without being lost. The same sweep deletes
+//! passkey challenges that expired without being used.
+
+use std::time::Duration;
+
+use sqlx::PgPool;
+use tokio::time::MissedTickBehavior;
+
+use crate::db::inbox_repos::InboxRepo;
+use crate::db::passkey_repos::WebauthnChallengeRepo;
+
+/// How often expired items are swept
+const EXPIRY_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Periodic inbox expiry sweep
+pub struct InboxExpirer;
+
+impl InboxExpirer {
+    /// Spawn the sweep loop
+    pub fn start(pool: PgPool) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(EXPIRY_INTERVAL);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                match InboxRepo::expire(&pool).await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!("Archived {} expired inbox items", n),
+                    Err(e) => tracing::warn!("Inbox expiry sweep failed: {}", e),
+                }
+                match WebauthnChallengeRepo::cleanup_expired(&pool).await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::debug!("Deleted {} expired passkey challenges", n),
+                    Err(e) => tracing::warn!("Passkey challenge sweep failed: {}", e),
+                }
+            }
+        });
+    }
+}
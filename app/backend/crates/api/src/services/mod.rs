@@ -2,8 +2,21 @@
 //!
 //! Business logic services for the application.
 
+pub mod account_archive;
+pub mod account_deletion;
 pub mod auth;
+pub mod backups;
+pub mod drills;
+pub mod feature_flags;
+pub mod inbox_expiry;
+pub mod notifications;
 pub mod oauth;
+pub mod oidc;
+pub mod rate_limit;
+pub mod sql_console;
+pub mod sync_hub;
+pub mod web_push;
+pub mod webauthn;
 
 pub use auth::*;
 pub use oauth::*;
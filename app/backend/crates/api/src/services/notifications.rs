@@ -0,0 +1,527 @@
+//! Notification scheduler
+//!
+//! Materializes due reminders (calendar events, habit check-ins, expiring
+//! quests and focus-session ends) into `notification_deliveries`, then
+//! delivers pending rows through the configured channels. Claimed rows are
+//! leased, so several API instances can run the scheduler side by side.
+//!
+//! Quiet hours and timezones are resolved in SQL through the
+//! `notification_preferences` view; email and push wait until quiet hours
+//! end, inbox items are delivered right away.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use chrono::Utc;
+use futures_util::future::join_all;
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use sqlx::PgPool;
+
+use super::web_push::{PushOutcome, VapidKeys, WebPushClient};
+use crate::config::{AppConfig, SmtpConfig, SmtpSecurity};
+use crate::db::inbox_models::CreateInboxRequest;
+use crate::db::inbox_repos::InboxRepo;
+use crate::db::notification_models::*;
+use crate::db::notification_repos::{NotificationRepo, PushSubscriptionRepo};
+use crate::error::AppError;
+
+/// Deliveries claimed per batch
+const BATCH_SIZE: i64 = 50;
+
+/// How long a claimed delivery is reserved before another run may retry it
+const LEASE_SECONDS: f64 = 300.0;
+
+/// How often finished deliveries are pruned
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Channels each reminder kind goes out on (when configured and enabled by the user)
+const REMINDER_CHANNELS: [(&str, &[&str]); 4] = [
+    (
+        NOTIFICATION_KIND_CALENDAR_EVENT,
+        &[
+            NOTIFICATION_CHANNEL_INBOX,
+            NOTIFICATION_CHANNEL_EMAIL,
+            NOTIFICATION_CHANNEL_PUSH,
+        ],
+    ),
+    (
+        NOTIFICATION_KIND_HABIT_CHECKIN,
+        &[
+            NOTIFICATION_CHANNEL_INBOX,
+            NOTIFICATION_CHANNEL_EMAIL,
+            NOTIFICATION_CHANNEL_PUSH,
+        ],
+    ),
+    (
+        NOTIFICATION_KIND_QUEST_EXPIRING,
+        &[
+            NOTIFICATION_CHANNEL_INBOX,
+            NOTIFICATION_CHANNEL_EMAIL,
+            NOTIFICATION_CHANNEL_PUSH,
+        ],
+    ),
+    // Only useful while the user is away from the tab
+    (NOTIFICATION_KIND_FOCUS_END, &[NOTIFICATION_CHANNEL_PUSH]),
+];
+
+/// Delay before retrying after `attempts` failed attempts (`None` = give up)
+pub fn retry_delay(attempts: i32) -> Option<chrono::Duration> {
+    match attempts {
+        1 => Some(chrono::Duration::minutes(1)),
+        2 => Some(chrono::Duration::minutes(5)),
+        3 => Some(chrono::Duration::minutes(30)),
+        4 => Some(chrono::Duration::hours(2)),
+        _ => None,
+    }
+}
+
+// ============================================================================
+// CHANNELS
+// ============================================================================
+
+/// Why a delivery attempt did not succeed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryError {
+    /// Temporary failure; try again later
+    Retry(String),
+    /// Will never succeed (e.g. invalid address)
+    Permanent(String),
+    /// Nothing to deliver to (e.g. no push subscriptions)
+    Skipped(String),
+}
+
+/// A way of reaching the user
+pub trait NotificationChannel: Send + Sync {
+    /// Channel name stored on deliveries (`NOTIFICATION_CHANNEL_*`)
+    fn name(&self) -> &'static str;
+
+    /// Deliver one notification
+    fn deliver<'a>(
+        &'a self,
+        pool: &'a PgPool,
+        notification: &'a DueNotification,
+    ) -> Pin<Box<dyn Future<Output = Result<(), DeliveryError>> + Send + 'a>>;
+}
+
+/// Inbox items via `InboxRepo::create`
+pub struct InboxChannel;
+
+impl NotificationChannel for InboxChannel {
+    fn name(&self) -> &'static str {
+        NOTIFICATION_CHANNEL_INBOX
+    }
+
+    fn deliver<'a>(
+        &'a self,
+        pool: &'a PgPool,
+        notification: &'a DueNotification,
+    ) -> Pin<Box<dyn Future<Output = Result<(), DeliveryError>> + Send + 'a>> {
+        Box::pin(async move {
+            let req = CreateInboxRequest {
+                item_type: "reminder".to_string(),
+                title: notification.title.clone(),
+                body: notification.body.clone(),
+                action_url: notification.action_url.clone(),
+                action_data: Some(serde_json::json!({
+                    "kind": notification.kind,
+                    "ref_id": notification.ref_id,
+                })),
+                priority: None,
+                expires_at: None,
+            };
+
+            InboxRepo::create(pool, notification.user_id, &req)
+                .await
+                .map_err(|e| DeliveryError::Retry(e.to_string()))?;
+            Ok(())
+        })
+    }
+}
+
+/// Plain-text email over SMTP
+pub struct EmailChannel {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    /// Base for links in the message body
+    frontend_url: String,
+}
+
+impl EmailChannel {
+    pub fn from_config(config: &SmtpConfig, frontend_url: &str) -> Result<Self, AppError> {
+        let builder = match config.security {
+            SmtpSecurity::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+            }
+            SmtpSecurity::Starttls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+                    .map_err(|e| AppError::Config(format!("Invalid SMTP host: {}", e)))?
+            }
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)
+                .map_err(|e| AppError::Config(format!("Invalid SMTP host: {}", e)))?,
+        };
+
+        let builder = builder.port(config.port);
+        let builder = match (&config.username, &config.password) {
+            (Some(username), Some(password)) => {
+                builder.credentials(Credentials::new(username.clone(), password.clone()))
+            }
+            _ => builder,
+        };
+
+        let from = config
+            .from
+            .parse()
+            .map_err(|e| AppError::Config(format!("Invalid SMTP from address: {}", e)))?;
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+            frontend_url: frontend_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    fn message(&self, notification: &DueNotification) -> Result<Message, DeliveryError> {
+        let to: Mailbox = notification
+            .email
+            .parse()
+            .map_err(|e| DeliveryError::Permanent(format!("Invalid recipient: {}", e)))?;
+
+        let mut text = notification.title.clone();
+        if let Some(body) = &notification.body {
+            text.push_str("\n\n");
+            text.push_str(body);
+        }
+        if let Some(url) = &notification.action_url {
+            text.push_str("\n\n");
+            text.push_str(&self.frontend_url);
+            text.push_str(url);
+        }
+
+        Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(&notification.title)
+            .header(ContentType::TEXT_PLAIN)
+            .body(text)
+            .map_err(|e| DeliveryError::Permanent(format!("Invalid message: {}", e)))
+    }
+}
+
+impl NotificationChannel for EmailChannel {
+    fn name(&self) -> &'static str {
+        NOTIFICATION_CHANNEL_EMAIL
+    }
+
+    fn deliver<'a>(
+        &'a self,
+        _pool: &'a PgPool,
+        notification: &'a DueNotification,
+    ) -> Pin<Box<dyn Future<Output = Result<(), DeliveryError>> + Send + 'a>> {
+        Box::pin(async move {
+            let message = self.message(notification)?;
+            match self.transport.send(message).await {
+                Ok(_) => Ok(()),
+                Err(e) if e.is_permanent() => Err(DeliveryError::Permanent(e.to_string())),
+                Err(e) => Err(DeliveryError::Retry(e.to_string())),
+            }
+        })
+    }
+}
+
+/// Web Push to every browser the user subscribed
+pub struct PushChannel {
+    client: WebPushClient,
+}
+
+impl PushChannel {
+    pub fn new(keys: VapidKeys) -> Self {
+        Self {
+            client: WebPushClient::new(keys),
+        }
+    }
+}
+
+impl NotificationChannel for PushChannel {
+    fn name(&self) -> &'static str {
+        NOTIFICATION_CHANNEL_PUSH
+    }
+
+    fn deliver<'a>(
+        &'a self,
+        pool: &'a PgPool,
+        notification: &'a DueNotification,
+    ) -> Pin<Box<dyn Future<Output = Result<(), DeliveryError>> + Send + 'a>> {
+        Box::pin(async move {
+            let subscriptions = PushSubscriptionRepo::list_for_user(pool, notification.user_id)
+                .await
+                .map_err(|e| DeliveryError::Retry(e.to_string()))?;
+
+            // Read by the service worker's push handler
+            let payload = serde_json::json!({
+                "title": notification.title,
+                "body": notification.body,
+                "url": notification.action_url,
+                "kind": notification.kind,
+            })
+            .to_string();
+
+            let mut delivered = false;
+            let mut retry = None;
+            let mut rejected = None;
+            for subscription in &subscriptions {
+                match self.client.send(subscription, payload.as_bytes()).await {
+                    PushOutcome::Delivered => {
+                        delivered = true;
+                        let _ = PushSubscriptionRepo::touch(pool, subscription.id).await;
+                    }
+                    PushOutcome::Gone => {
+                        let _ = PushSubscriptionRepo::delete_by_id(pool, subscription.id).await;
+                    }
+                    PushOutcome::Retry(e) => retry = Some(e),
+                    PushOutcome::Rejected(e) => rejected = Some(e),
+                }
+            }
+
+            // Reaching any device counts; retrying would repeat it on the others
+            if delivered {
+                return Ok(());
+            }
+            match (retry, rejected) {
+                (Some(e), _) => Err(DeliveryError::Retry(e)),
+                (None, Some(e)) => Err(DeliveryError::Permanent(e)),
+                (None, None) => Err(DeliveryError::Skipped("No push subscriptions".to_string())),
+            }
+        })
+    }
+}
+
+// ============================================================================
+// SCHEDULER
+// ============================================================================
+
+/// Counts from one scheduler run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunSummary {
+    pub materialized: u64,
+    pub sent: u64,
+    pub retried: u64,
+    pub failed: u64,
+    pub skipped: u64,
+}
+
+/// Materializes and delivers reminders
+pub struct NotificationScheduler {
+    channels: Vec<Box<dyn NotificationChannel>>,
+    interval: Duration,
+}
+
+impl NotificationScheduler {
+    pub fn new(channels: Vec<Box<dyn NotificationChannel>>, interval: Duration) -> Self {
+        Self { channels, interval }
+    }
+
+    /// Inbox plus whichever of email and push are configured
+    pub fn from_config(config: &AppConfig) -> Result<Self, AppError> {
+        let notifications = &config.notifications;
+        let mut channels: Vec<Box<dyn NotificationChannel>> = vec![Box::new(InboxChannel)];
+
+        if let Some(smtp) = &notifications.smtp {
+            channels.push(Box::new(EmailChannel::from_config(
+                smtp,
+                &config.server.frontend_url,
+            )?));
+        }
+        if let Some(vapid) = &notifications.vapid {
+            channels.push(Box::new(PushChannel::new(VapidKeys::from_config(vapid)?)));
+        }
+
+        Ok(Self::new(
+            channels,
+            Duration::from_secs(notifications.interval_seconds.max(1)),
+        ))
+    }
+
+    /// Run on an interval and prune old deliveries
+    pub fn start(self, pool: PgPool) {
+        tracing::info!(
+            channels = ?self.channels.iter().map(|c| c.name()).collect::<Vec<_>>(),
+            "Starting notification scheduler"
+        );
+
+        let prune_pool = pool.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+            loop {
+                interval.tick().await;
+                match NotificationRepo::prune(&prune_pool).await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::debug!("Pruned {} notification deliveries", n),
+                    Err(e) => tracing::warn!("Failed to prune notification deliveries: {}", e),
+                }
+            }
+        });
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                match self.run_once(&pool).await {
+                    Ok(summary) if summary != RunSummary::default() => {
+                        tracing::debug!(?summary, "Notification scheduler run");
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Notification scheduler run failed: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Materialize due reminders and deliver everything pending
+    pub async fn run_once(&self, pool: &PgPool) -> Result<RunSummary, AppError> {
+        let mut summary = RunSummary::default();
+
+        for (kind, kind_channels) in REMINDER_CHANNELS {
+            let channels: Vec<&str> = kind_channels
+                .iter()
+                .copied()
+                .filter(|name| self.channel(name).is_some())
+                .collect();
+            if !channels.is_empty() {
+                summary.materialized +=
+                    NotificationRepo::materialize(pool, kind, &channels).await?;
+            }
+        }
+
+        summary.skipped += NotificationRepo::skip_expired(pool).await?;
+
+        loop {
+            let due = NotificationRepo::claim_due(pool, BATCH_SIZE, LEASE_SECONDS).await?;
+            let claimed = due.len() as i64;
+
+            let outcomes = join_all(due.iter().map(|n| self.deliver(pool, n))).await;
+            for outcome in outcomes {
+                match outcome? {
+                    DELIVERY_STATUS_SENT => summary.sent += 1,
+                    DELIVERY_STATUS_PENDING => summary.retried += 1,
+                    DELIVERY_STATUS_FAILED => summary.failed += 1,
+                    _ => summary.skipped += 1,
+                }
+            }
+
+            if claimed < BATCH_SIZE {
+                break;
+            }
+        }
+
+        Ok(summary)
+    }
+
+    fn channel(&self, name: &str) -> Option<&dyn NotificationChannel> {
+        self.channels
+            .iter()
+            .find(|c| c.name() == name)
+            .map(|c| c.as_ref())
+    }
+
+    /// Attempt one delivery and record the outcome; returns the new status
+    async fn deliver(
+        &self,
+        pool: &PgPool,
+        notification: &DueNotification,
+    ) -> Result<&'static str, AppError> {
+        let result = match self.channel(&notification.channel) {
+            Some(channel) => channel.deliver(pool, notification).await,
+            None => Err(DeliveryError::Skipped(format!(
+                "Channel {} is not configured",
+                notification.channel
+            ))),
+        };
+
+        let status = match result {
+            Ok(()) => {
+                NotificationRepo::mark_sent(pool, notification.id).await?;
+                DELIVERY_STATUS_SENT
+            }
+            Err(DeliveryError::Retry(error)) => match retry_delay(notification.attempts) {
+                Some(delay) => {
+                    NotificationRepo::schedule_retry(
+                        pool,
+                        notification.id,
+                        Utc::now() + delay,
+                        &error,
+                    )
+                    .await?;
+                    DELIVERY_STATUS_PENDING
+                }
+                None => {
+                    NotificationRepo::mark_finished(
+                        pool,
+                        notification.id,
+                        DELIVERY_STATUS_FAILED,
+                        &error,
+                    )
+                    .await?;
+                    DELIVERY_STATUS_FAILED
+                }
+            },
+            Err(DeliveryError::Permanent(error)) => {
+                NotificationRepo::mark_finished(
+                    pool,
+                    notification.id,
+                    DELIVERY_STATUS_FAILED,
+                    &error,
+                )
+                .await?;
+                DELIVERY_STATUS_FAILED
+            }
+            Err(DeliveryError::Skipped(reason)) => {
+                NotificationRepo::mark_finished(
+                    pool,
+                    notification.id,
+                    DELIVERY_STATUS_SKIPPED,
+                    &reason,
+                )
+                .await?;
+                DELIVERY_STATUS_SKIPPED
+            }
+        };
+
+        if status != DELIVERY_STATUS_SENT {
+            tracing::debug!(
+                delivery_id = %notification.id,
+                channel = %notification.channel,
+                attempts = notification.attempts,
+                status,
+                "Notification not delivered"
+            );
+        }
+
+        Ok(status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backs_off_then_gives_up() {
+        let delays: Vec<_> = (1..=5).map(retry_delay).collect();
+
+        assert!(delays[..4].iter().all(Option::is_some));
+        assert!(delays[..4].windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(delays[4], None);
+    }
+
+    #[test]
+    fn test_every_reminder_kind_has_channels() {
+        for (kind, channels) in REMINDER_CHANNELS {
+            assert!(!channels.is_empty(), "{} has no channels", kind);
+        }
+    }
+}
@@ -0,0 +1,775 @@
+//! Generic OpenID Connect provider service
+//!
+//! Serves every provider listed under `auth.oauth.oidc`. Endpoints come from
+//! `.well-known/openid-configuration` discovery (cached per issuer), ID tokens
+//! are validated against the provider's JWKS, and every flow uses PKCE plus a
+//! nonce. Providers without an issuer are treated as plain OAuth 2.0 and
+//! identified through their userinfo endpoint.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use oauth2::{CsrfToken, PkceCodeChallenge};
+use reqwest::Client as HttpClient;
+use ring::signature;
+use serde::Deserialize;
+
+use crate::config::{AppConfig, OidcProviderConfig};
+use crate::db::models::{OAuthProvider, OAuthUserInfo};
+use crate::error::AppError;
+use crate::services::oauth::TokenInfo;
+
+/// How long discovery documents and key sets are reused
+const METADATA_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Allowed clock skew when checking `exp` / `iat`
+const CLOCK_SKEW_SECS: i64 = 60;
+
+static METADATA_CACHE: OnceLock<Mutex<HashMap<String, (Instant, ProviderMetadata)>>> =
+    OnceLock::new();
+static JWKS_CACHE: OnceLock<Mutex<HashMap<String, (Instant, Jwks)>>> = OnceLock::new();
+
+fn oidc_error(msg: impl Into<String>) -> AppError {
+    AppError::OAuthError(msg.into())
+}
+
+// ============================================================================
+// PROVIDER METADATA
+// ============================================================================
+
+/// Subset of the OpenID Provider Metadata we use
+#[derive(Debug, Clone, Deserialize)]
+struct ProviderMetadata {
+    issuer: Option<String>,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    #[serde(default)]
+    userinfo_endpoint: Option<String>,
+    #[serde(default)]
+    jwks_uri: Option<String>,
+    #[serde(default)]
+    token_endpoint_auth_methods_supported: Vec<String>,
+}
+
+/// JSON Web Key Set
+#[derive(Debug, Clone, Deserialize)]
+pub struct Jwks {
+    pub keys: Vec<Jwk>,
+}
+
+/// JSON Web Key (RSA, EC P-256/P-384 and Ed25519 are supported)
+#[derive(Debug, Clone, Deserialize)]
+pub struct Jwk {
+    pub kty: String,
+    #[serde(default)]
+    pub kid: Option<String>,
+    #[serde(default)]
+    pub crv: Option<String>,
+    #[serde(default)]
+    pub n: Option<String>,
+    #[serde(default)]
+    pub e: Option<String>,
+    #[serde(default)]
+    pub x: Option<String>,
+    #[serde(default)]
+    pub y: Option<String>,
+}
+
+/// State generated for a new authorization request
+#[derive(Debug, Clone)]
+pub struct OidcAuthState {
+    pub csrf_token: String,
+    pub pkce_verifier: String,
+    pub nonce: String,
+}
+
+// ============================================================================
+// PROVIDER
+// ============================================================================
+
+/// A configured generic OIDC / OAuth 2.0 provider
+pub struct OidcProvider {
+    config: OidcProviderConfig,
+    redirect_uri: String,
+    http_client: HttpClient,
+}
+
+impl OidcProvider {
+    /// Look up a configured provider by ID
+    pub fn from_config(app_config: &AppConfig, provider_id: &str) -> Option<Self> {
+        let config = configured_providers(app_config)
+            .into_iter()
+            .find(|p| p.id == provider_id)?
+            .clone();
+
+        let redirect_uri = format!(
+            "{}/auth/callback/{}",
+            app_config.server.public_url, config.id
+        );
+
+        Some(Self {
+            config,
+            redirect_uri,
+            http_client: HttpClient::new(),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    /// Build the authorization URL (PKCE S256 + nonce)
+    pub async fn authorization_url(&self) -> Result<(String, OidcAuthState), AppError> {
+        let metadata = self.metadata().await?;
+        let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+        let csrf_token = CsrfToken::new_random();
+        let nonce = CsrfToken::new_random();
+
+        let mut url = url::Url::parse(&metadata.authorization_endpoint)?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.config.client_id)
+            .append_pair("redirect_uri", &self.redirect_uri)
+            .append_pair("scope", &self.config.scopes.join(" "))
+            .append_pair("state", csrf_token.secret())
+            .append_pair("nonce", nonce.secret())
+            .append_pair("code_challenge", pkce_challenge.as_str())
+            .append_pair("code_challenge_method", "S256");
+
+        let state = OidcAuthState {
+            csrf_token: csrf_token.secret().clone(),
+            pkce_verifier: pkce_verifier.secret().clone(),
+            nonce: nonce.secret().clone(),
+        };
+
+        Ok((url.to_string(), state))
+    }
+
+    /// Exchange authorization code for tokens
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        pkce_verifier: &str,
+    ) -> Result<TokenInfo, AppError> {
+        #[derive(Deserialize)]
+        struct TokenResponse {
+            access_token: String,
+            #[serde(default)]
+            refresh_token: Option<String>,
+            #[serde(default)]
+            expires_in: Option<i64>,
+            #[serde(default)]
+            id_token: Option<String>,
+        }
+
+        let metadata = self.metadata().await?;
+
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.redirect_uri.as_str()),
+            ("code_verifier", pkce_verifier),
+        ];
+
+        // client_secret_basic is the spec default; fall back to client_secret_post
+        // only when the provider says it does not support basic
+        let methods = &metadata.token_endpoint_auth_methods_supported;
+        let use_basic = methods.is_empty() || methods.iter().any(|m| m == "client_secret_basic");
+
+        let mut request = self
+            .http_client
+            .post(&metadata.token_endpoint)
+            .header(reqwest::header::ACCEPT, "application/json");
+        if use_basic {
+            request = request.basic_auth(
+                urlencoding::encode(&self.config.client_id),
+                Some(urlencoding::encode(&self.config.client_secret)),
+            );
+        } else {
+            form.push(("client_id", self.config.client_id.as_str()));
+            form.push(("client_secret", self.config.client_secret.as_str()));
+        }
+
+        let response = request
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| oidc_error(format!("Token exchange failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(oidc_error(format!(
+                "Token exchange failed ({}): {}",
+                status,
+                body.chars().take(200).collect::<String>()
+            )));
+        }
+
+        let tokens: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| oidc_error(format!("Failed to parse token response: {}", e)))?;
+
+        Ok(TokenInfo {
+            access_token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
+            expires_at: tokens
+                .expires_in
+                .map(|secs| chrono::Utc::now().timestamp() + secs),
+            id_token: tokens.id_token,
+        })
+    }
+
+    /// Resolve the signed-in identity
+    ///
+    /// OIDC providers must return an ID token, which is validated (signature,
+    /// issuer, audience, expiry, nonce). Missing profile claims are filled from
+    /// the userinfo endpoint, whose `sub` must match the ID token.
+    pub async fn get_user_info(
+        &self,
+        tokens: &TokenInfo,
+        expected_nonce: Option<&str>,
+    ) -> Result<OAuthUserInfo, AppError> {
+        let provider = OAuthProvider::Oidc(self.config.id.clone());
+        let metadata = self.metadata().await?;
+
+        let Some(ref issuer) = self.config.issuer else {
+            let userinfo = self.fetch_userinfo(&metadata, &tokens.access_token).await?;
+            return user_info_from_claims(provider, &userinfo);
+        };
+
+        let id_token = tokens
+            .id_token
+            .as_deref()
+            .ok_or_else(|| oidc_error("Provider did not return an ID token"))?;
+        let nonce = expected_nonce.ok_or_else(|| oidc_error("Missing nonce for OIDC flow"))?;
+
+        let jwks_uri = metadata
+            .jwks_uri
+            .as_deref()
+            .ok_or_else(|| oidc_error("Provider metadata has no jwks_uri"))?;
+        let kid = token_kid(id_token)?;
+        let jwks = self.jwks(jwks_uri, kid.as_deref()).await?;
+
+        let mut claims = validate_id_token(
+            id_token,
+            &jwks,
+            issuer,
+            &self.config.client_id,
+            nonce,
+            chrono::Utc::now().timestamp(),
+        )?;
+
+        if claims.get("email").and_then(|v| v.as_str()).is_none() {
+            let userinfo = self.fetch_userinfo(&metadata, &tokens.access_token).await?;
+            if userinfo.get("sub") != claims.get("sub") {
+                return Err(oidc_error("userinfo subject does not match ID token"));
+            }
+            if let (Some(target), Some(extra)) = (claims.as_object_mut(), userinfo.as_object()) {
+                for (key, value) in extra {
+                    target.entry(key.clone()).or_insert_with(|| value.clone());
+                }
+            }
+        }
+
+        user_info_from_claims(provider, &claims)
+    }
+
+    async fn fetch_userinfo(
+        &self,
+        metadata: &ProviderMetadata,
+        access_token: &str,
+    ) -> Result<serde_json::Value, AppError> {
+        let endpoint = metadata
+            .userinfo_endpoint
+            .as_deref()
+            .ok_or_else(|| oidc_error("Provider has no userinfo endpoint"))?;
+
+        self.http_client
+            .get(endpoint)
+            .bearer_auth(access_token)
+            .header(reqwest::header::ACCEPT, "application/json")
+            // Some APIs (e.g. GitHub) reject requests without a User-Agent
+            .header(reqwest::header::USER_AGENT, "ignition-api")
+            .send()
+            .await
+            .map_err(|e| oidc_error(format!("Failed to get user info: {}", e)))?
+            .error_for_status()
+            .map_err(|e| oidc_error(format!("Failed to get user info: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| oidc_error(format!("Failed to parse user info: {}", e)))
+    }
+
+    /// Provider metadata from discovery (cached) with config overrides applied
+    async fn metadata(&self) -> Result<ProviderMetadata, AppError> {
+        let mut metadata = match self.config.issuer {
+            Some(ref issuer) => discover(&self.http_client, issuer).await?,
+            None => ProviderMetadata {
+                issuer: None,
+                authorization_endpoint: self.config.authorization_endpoint.clone().ok_or_else(
+                    || {
+                        AppError::Config(format!(
+                            "{}: authorization_endpoint is required",
+                            self.config.id
+                        ))
+                    },
+                )?,
+                token_endpoint: self.config.token_endpoint.clone().ok_or_else(|| {
+                    AppError::Config(format!("{}: token_endpoint is required", self.config.id))
+                })?,
+                userinfo_endpoint: None,
+                jwks_uri: None,
+                token_endpoint_auth_methods_supported: Vec::new(),
+            },
+        };
+
+        if let Some(ref endpoint) = self.config.authorization_endpoint {
+            metadata.authorization_endpoint = endpoint.clone();
+        }
+        if let Some(ref endpoint) = self.config.token_endpoint {
+            metadata.token_endpoint = endpoint.clone();
+        }
+        if let Some(ref endpoint) = self.config.userinfo_endpoint {
+            metadata.userinfo_endpoint = Some(endpoint.clone());
+        }
+
+        Ok(metadata)
+    }
+
+    /// Key set for `jwks_uri`, refetched when the token's `kid` is unknown
+    /// (providers rotate keys without notice)
+    async fn jwks(&self, jwks_uri: &str, kid: Option<&str>) -> Result<Jwks, AppError> {
+        let cache = JWKS_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+        if let Some((fetched_at, jwks)) = cache.lock().unwrap().get(jwks_uri) {
+            let has_kid =
+                kid.is_none_or(|kid| jwks.keys.iter().any(|k| k.kid.as_deref() == Some(kid)));
+            if fetched_at.elapsed() < METADATA_TTL && has_kid {
+                return Ok(jwks.clone());
+            }
+        }
+
+        let jwks: Jwks = self
+            .http_client
+            .get(jwks_uri)
+            .send()
+            .await
+            .map_err(|e| oidc_error(format!("Failed to fetch JWKS: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| oidc_error(format!("Failed to parse JWKS: {}", e)))?;
+
+        cache
+            .lock()
+            .unwrap()
+            .insert(jwks_uri.to_string(), (Instant::now(), jwks.clone()));
+
+        Ok(jwks)
+    }
+}
+
+/// All usable configured OIDC providers
+pub fn configured_providers(config: &AppConfig) -> Vec<&OidcProviderConfig> {
+    config
+        .auth
+        .oauth
+        .as_ref()
+        .map(|oauth| {
+            oauth
+                .oidc
+                .iter()
+                .filter(|p| !p.id.is_empty() && !p.client_id.is_empty())
+                // Built-in providers keep their dedicated routes
+                .filter(|p| !matches!(p.id.as_str(), "google" | "azure"))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Fetch (or reuse) the discovery document for an issuer
+async fn discover(http_client: &HttpClient, issuer: &str) -> Result<ProviderMetadata, AppError> {
+    let cache = METADATA_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some((fetched_at, metadata)) = cache.lock().unwrap().get(issuer) {
+        if fetched_at.elapsed() < METADATA_TTL {
+            return Ok(metadata.clone());
+        }
+    }
+
+    let url = format!(
+        "{}/.well-known/openid-configuration",
+        issuer.trim_end_matches('/')
+    );
+    let metadata: ProviderMetadata = http_client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| oidc_error(format!("Discovery failed for {}: {}", issuer, e)))?
+        .error_for_status()
+        .map_err(|e| oidc_error(format!("Discovery failed for {}: {}", issuer, e)))?
+        .json()
+        .await
+        .map_err(|e| oidc_error(format!("Invalid discovery document for {}: {}", issuer, e)))?;
+
+    // The issuer in the document must be exactly the configured one
+    if metadata.issuer.as_deref() != Some(issuer) {
+        return Err(oidc_error(format!(
+            "Discovery issuer mismatch: expected {}, got {:?}",
+            issuer, metadata.issuer
+        )));
+    }
+
+    cache
+        .lock()
+        .unwrap()
+        .insert(issuer.to_string(), (Instant::now(), metadata.clone()));
+
+    Ok(metadata)
+}
+
+// ============================================================================
+// ID TOKEN VALIDATION
+// ============================================================================
+
+#[derive(Deserialize)]
+struct JwtHeader {
+    alg: String,
+    #[serde(default)]
+    kid: Option<String>,
+}
+
+fn b64url_json<T: serde::de::DeserializeOwned>(part: &str) -> Result<T, AppError> {
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(part)
+        .map_err(|_| oidc_error("Malformed ID token"))?;
+    serde_json::from_slice(&bytes).map_err(|_| oidc_error("Malformed ID token"))
+}
+
+fn b64url_field(value: &Option<String>) -> Result<Vec<u8>, AppError> {
+    let value = value
+        .as_deref()
+        .ok_or_else(|| oidc_error("Incomplete JWK"))?;
+    base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(value)
+        .map_err(|_| oidc_error("Incomplete JWK"))
+}
+
+fn token_kid(token: &str) -> Result<Option<String>, AppError> {
+    let header = token
+        .split('.')
+        .next()
+        .ok_or_else(|| oidc_error("Malformed ID token"))?;
+    Ok(b64url_json::<JwtHeader>(header)?.kid)
+}
+
+/// Verify a compact JWS against a JWK
+fn verify_signature(alg: &str, jwk: &Jwk, message: &[u8], sig: &[u8]) -> Result<(), AppError> {
+    let ec_point = |size: usize| -> Result<Vec<u8>, AppError> {
+        let x = b64url_field(&jwk.x)?;
+        let y = b64url_field(&jwk.y)?;
+        if x.len() != size || y.len() != size {
+            return Err(oidc_error("Invalid EC key"));
+        }
+        Ok([&[0x04u8][..], &x, &y].concat())
+    };
+
+    let result = match (alg, jwk.kty.as_str()) {
+        ("RS256" | "RS384" | "RS512" | "PS256" | "PS384" | "PS512", "RSA") => {
+            let params: &signature::RsaParameters = match alg {
+                "RS256" => &signature::RSA_PKCS1_2048_8192_SHA256,
+                "RS384" => &signature::RSA_PKCS1_2048_8192_SHA384,
+                "RS512" => &signature::RSA_PKCS1_2048_8192_SHA512,
+                "PS256" => &signature::RSA_PSS_2048_8192_SHA256,
+                "PS384" => &signature::RSA_PSS_2048_8192_SHA384,
+                _ => &signature::RSA_PSS_2048_8192_SHA512,
+            };
+            let n = b64url_field(&jwk.n)?;
+            let e = b64url_field(&jwk.e)?;
+            signature::RsaPublicKeyComponents { n: &n, e: &e }.verify(params, message, sig)
+        }
+        ("ES256", "EC") if jwk.crv.as_deref() == Some("P-256") => {
+            signature::UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_FIXED, ec_point(32)?)
+                .verify(message, sig)
+        }
+        ("ES384", "EC") if jwk.crv.as_deref() == Some("P-384") => {
+            signature::UnparsedPublicKey::new(&signature::ECDSA_P384_SHA384_FIXED, ec_point(48)?)
+                .verify(message, sig)
+        }
+        ("EdDSA", "OKP") if jwk.crv.as_deref() == Some("Ed25519") => {
+            signature::UnparsedPublicKey::new(&signature::ED25519, b64url_field(&jwk.x)?)
+                .verify(message, sig)
+        }
+        _ => {
+            return Err(oidc_error(format!(
+                "Unsupported ID token algorithm: {}",
+                alg
+            )))
+        }
+    };
+
+    result.map_err(|_| oidc_error("Invalid ID token signature"))
+}
+
+/// Validate an ID token and return its claims
+///
+/// Checks signature (never `none` or HMAC), `iss`, `aud`/`azp`, `exp`, `iat`
+/// and `nonce` per OpenID Connect Core 3.1.3.7.
+pub fn validate_id_token(
+    token: &str,
+    jwks: &Jwks,
+    issuer: &str,
+    client_id: &str,
+    expected_nonce: &str,
+    now: i64,
+) -> Result<serde_json::Value, AppError> {
+    let mut parts = token.split('.');
+    let (Some(header_part), Some(payload_part), Some(sig_part), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(oidc_error("Malformed ID token"));
+    };
+
+    let header: JwtHeader = b64url_json(header_part)?;
+    let signature = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(sig_part)
+        .map_err(|_| oidc_error("Malformed ID token"))?;
+
+    let jwk = jwks
+        .keys
+        .iter()
+        .find(|k| match header.kid {
+            Some(ref kid) => k.kid.as_deref() == Some(kid.as_str()),
+            None => true,
+        })
+        .ok_or_else(|| oidc_error("No matching signing key for ID token"))?;
+
+    let signing_input = format!("{}.{}", header_part, payload_part);
+    verify_signature(&header.alg, jwk, signing_input.as_bytes(), &signature)?;
+
+    let claims: serde_json::Value = b64url_json(payload_part)?;
+    let claim_str = |name: &str| claims.get(name).and_then(|v| v.as_str());
+
+    if claim_str("iss") != Some(issuer) {
+        return Err(oidc_error("ID token issuer mismatch"));
+    }
+
+    let audiences: Vec<&str> = match claims.get("aud") {
+        Some(serde_json::Value::String(aud)) => vec![aud.as_str()],
+        Some(serde_json::Value::Array(auds)) => auds.iter().filter_map(|a| a.as_str()).collect(),
+        _ => Vec::new(),
+    };
+    if !audiences.contains(&client_id) {
+        return Err(oidc_error("ID token audience mismatch"));
+    }
+    if audiences.len() > 1 && claim_str("azp") != Some(client_id) {
+        return Err(oidc_error("ID token authorized party mismatch"));
+    }
+
+    let exp = claims
+        .get("exp")
+        .and_then(|v| v.as_i64())
+        .ok_or_else(|| oidc_error("ID token has no expiry"))?;
+    if exp + CLOCK_SKEW_SECS < now {
+        return Err(oidc_error("ID token has expired"));
+    }
+    if let Some(iat) = claims.get("iat").and_then(|v| v.as_i64()) {
+        if iat - CLOCK_SKEW_SECS > now {
+            return Err(oidc_error("ID token issued in the future"));
+        }
+    }
+
+    if claim_str("nonce") != Some(expected_nonce) {
+        return Err(oidc_error("ID token nonce mismatch"));
+    }
+    if claim_str("sub").is_none_or(str::is_empty) {
+        return Err(oidc_error("ID token has no subject"));
+    }
+
+    Ok(claims)
+}
+
+/// Map ID token / userinfo claims to our user info
+///
+/// Standard OIDC claim names are preferred; GitHub-style `id`, `login` and
+/// `avatar_url` are accepted for plain OAuth 2.0 providers.
+pub fn user_info_from_claims(
+    provider: OAuthProvider,
+    claims: &serde_json::Value,
+) -> Result<OAuthUserInfo, AppError> {
+    let text = |name: &str| {
+        claims
+            .get(name)
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+    };
+
+    let subject = match claims.get("sub").or_else(|| claims.get("id")) {
+        Some(serde_json::Value::String(s)) if !s.is_empty() => s.clone(),
+        Some(serde_json::Value::Number(n)) => n.to_string(),
+        _ => return Err(oidc_error("Provider returned no subject")),
+    };
+
+    let email = text("email").ok_or_else(|| {
+        oidc_error("Provider returned no email address; make sure the email scope is granted")
+    })?;
+
+    let email_verified = match claims.get("email_verified") {
+        Some(serde_json::Value::Bool(b)) => *b,
+        // Some providers serialize booleans as strings
+        Some(serde_json::Value::String(s)) => s == "true",
+        _ => false,
+    };
+
+    Ok(OAuthUserInfo {
+        provider,
+        provider_account_id: subject,
+        email,
+        name: text("name")
+            .or_else(|| text("preferred_username"))
+            .or_else(|| text("login")),
+        image: text("picture").or_else(|| text("avatar_url")),
+        email_verified,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    const ISSUER: &str = "https://id.example.com/realms/main";
+    const CLIENT_ID: &str = "ignition";
+
+    fn b64(bytes: &[u8]) -> String {
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    fn keypair() -> (Ed25519KeyPair, Jwks) {
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let jwks = Jwks {
+            keys: vec![Jwk {
+                kty: "OKP".to_string(),
+                kid: Some("k1".to_string()),
+                crv: Some("Ed25519".to_string()),
+                n: None,
+                e: None,
+                x: Some(b64(pair.public_key().as_ref())),
+                y: None,
+            }],
+        };
+        (pair, jwks)
+    }
+
+    fn sign(pair: &Ed25519KeyPair, header: serde_json::Value, claims: serde_json::Value) -> String {
+        let input = format!(
+            "{}.{}",
+            b64(header.to_string().as_bytes()),
+            b64(claims.to_string().as_bytes())
+        );
+        let sig = pair.sign(input.as_bytes());
+        format!("{}.{}", input, b64(sig.as_ref()))
+    }
+
+    fn claims(now: i64) -> serde_json::Value {
+        serde_json::json!({
+            "iss": ISSUER,
+            "sub": "user-1",
+            "aud": CLIENT_ID,
+            "exp": now + 300,
+            "iat": now,
+            "nonce": "n-123",
+            "email": "a@example.com",
+            "email_verified": true,
+        })
+    }
+
+    fn header() -> serde_json::Value {
+        serde_json::json!({ "alg": "EdDSA", "kid": "k1" })
+    }
+
+    #[test]
+    fn test_valid_id_token() {
+        let now = 1_700_000_000;
+        let (pair, jwks) = keypair();
+        let token = sign(&pair, header(), claims(now));
+
+        let claims = validate_id_token(&token, &jwks, ISSUER, CLIENT_ID, "n-123", now).unwrap();
+        assert_eq!(claims["sub"], "user-1");
+    }
+
+    #[test]
+    fn test_id_token_claim_checks() {
+        let now = 1_700_000_000;
+        let (pair, jwks) = keypair();
+        let token = sign(&pair, header(), claims(now));
+
+        assert!(validate_id_token(&token, &jwks, ISSUER, CLIENT_ID, "other", now).is_err());
+        assert!(validate_id_token(&token, &jwks, ISSUER, "someone-else", "n-123", now).is_err());
+        assert!(validate_id_token(&token, &jwks, "https://evil", CLIENT_ID, "n-123", now).is_err());
+        assert!(validate_id_token(&token, &jwks, ISSUER, CLIENT_ID, "n-123", now + 3600).is_err());
+    }
+
+    #[test]
+    fn test_id_token_multiple_audiences_require_azp() {
+        let now = 1_700_000_000;
+        let (pair, jwks) = keypair();
+        let mut c = claims(now);
+        c["aud"] = serde_json::json!([CLIENT_ID, "other"]);
+        let token = sign(&pair, header(), c.clone());
+        assert!(validate_id_token(&token, &jwks, ISSUER, CLIENT_ID, "n-123", now).is_err());
+
+        c["azp"] = serde_json::json!(CLIENT_ID);
+        let token = sign(&pair, header(), c);
+        assert!(validate_id_token(&token, &jwks, ISSUER, CLIENT_ID, "n-123", now).is_ok());
+    }
+
+    #[test]
+    fn test_id_token_rejects_tampering_and_none_alg() {
+        let now = 1_700_000_000;
+        let (pair, jwks) = keypair();
+        let token = sign(&pair, header(), claims(now));
+
+        // Swap in a different payload, keeping the signature
+        let mut parts: Vec<&str> = token.split('.').collect();
+        let mut forged = claims(now);
+        forged["sub"] = serde_json::json!("admin");
+        let forged_payload = b64(forged.to_string().as_bytes());
+        parts[1] = &forged_payload;
+        let forged_token = parts.join(".");
+        assert!(validate_id_token(&forged_token, &jwks, ISSUER, CLIENT_ID, "n-123", now).is_err());
+
+        let none_token = format!(
+            "{}.{}.",
+            b64(br#"{"alg":"none","kid":"k1"}"#),
+            b64(claims(now).to_string().as_bytes())
+        );
+        assert!(validate_id_token(&none_token, &jwks, ISSUER, CLIENT_ID, "n-123", now).is_err());
+    }
+
+    #[test]
+    fn test_user_info_from_github_style_claims() {
+        let claims = serde_json::json!({
+            "id": 12345,
+            "login": "octocat",
+            "email": "octo@example.com",
+            "avatar_url": "https://avatars.example/u/12345",
+        });
+        let info =
+            user_info_from_claims(OAuthProvider::Oidc("github".to_string()), &claims).unwrap();
+        assert_eq!(info.provider_account_id, "12345");
+        assert_eq!(info.name.as_deref(), Some("octocat"));
+        assert!(!info.email_verified);
+        assert_eq!(info.provider.as_str(), "github");
+    }
+
+    #[test]
+    fn test_user_info_requires_email() {
+        let claims = serde_json::json!({ "sub": "abc" });
+        assert!(user_info_from_claims(OAuthProvider::Oidc("kc".to_string()), &claims).is_err());
+    }
+}
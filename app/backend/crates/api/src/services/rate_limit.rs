@@ -0,0 +1,361 @@
+//! Rate limiting
+//!
+//! Token buckets per policy and subject. Each policy in
+//! `AppConfig::rate_limits` covers some paths and methods and counts requests
+//! by user, session, client IP or route; a bucket holds `capacity` tokens and
+//! refills completely over `period_seconds`. Buckets live in memory, or in
+//! `rate_limit_buckets` when several instances must share them. A Postgres
+//! failure lets the request through rather than taking the API down with it.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::{RateLimitBackend, RateLimitKey, RateLimitPolicy, RateLimitsConfig};
+use crate::db::rate_limit_repos::RateLimitRepo;
+use crate::shared::audit::{AuditEvent, AuditEventType, AuditSink, PostgresAuditSink};
+
+/// How often idle buckets are dropped
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A limited key is audited at most once in this window
+const AUDIT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Who a request is counted against
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitSubject {
+    pub user_id: Option<Uuid>,
+    pub session_id: Option<Uuid>,
+    pub ip: Option<String>,
+}
+
+/// Outcome of checking a request against its policy
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decision {
+    pub allowed: bool,
+    /// Bucket capacity
+    pub limit: u32,
+    /// Whole tokens left
+    pub remaining: u32,
+    /// Seconds until the bucket is full again
+    pub reset: u64,
+    /// Seconds until a token is available, when refused
+    pub retry_after: Option<u64>,
+    /// Window the capacity applies to
+    pub period_seconds: u64,
+}
+
+/// In-memory bucket
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Shared rate limiter
+#[derive(Clone)]
+pub struct RateLimiter {
+    config: RateLimitsConfig,
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+    audited: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: &RateLimitsConfig) -> Self {
+        Self {
+            config: config.clone(),
+            buckets: Arc::default(),
+            audited: Arc::default(),
+        }
+    }
+
+    /// Drop idle buckets periodically
+    pub fn start(&self, pool: PgPool) {
+        let limiter = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+            loop {
+                interval.tick().await;
+                limiter.prune(&pool).await;
+            }
+        });
+    }
+
+    /// A bucket idle for its longest period is full, so forgetting it
+    /// changes nothing
+    async fn prune(&self, pool: &PgPool) {
+        let idle = Duration::from_secs(
+            self.config
+                .policies
+                .iter()
+                .map(|p| p.period_seconds)
+                .max()
+                .unwrap_or(0),
+        );
+        let now = Instant::now();
+        lock(&self.buckets).retain(|_, b| now.duration_since(b.updated) < idle);
+        lock(&self.audited).retain(|_, at| now.duration_since(*at) < AUDIT_INTERVAL);
+
+        if self.config.backend == RateLimitBackend::Postgres {
+            if let Err(e) = RateLimitRepo::prune(pool, idle.as_secs_f64()).await {
+                tracing::warn!("Failed to prune rate limit buckets: {}", e);
+            }
+        }
+    }
+
+    /// Take a token for a request; `None` when no policy covers it
+    pub async fn check(
+        &self,
+        pool: &PgPool,
+        method: &str,
+        path: &str,
+        subject: &RateLimitSubject,
+    ) -> Option<Decision> {
+        if !self.config.enabled {
+            return None;
+        }
+        let policy = self
+            .config
+            .policies
+            .iter()
+            .find(|p| policy_matches(p, method, path))?;
+        let key = bucket_key(policy, subject);
+        let capacity = policy.capacity as f64;
+        let rate = refill_rate(policy);
+
+        let (tokens, allowed) = match self.config.backend {
+            RateLimitBackend::Memory => {
+                let mut buckets = lock(&self.buckets);
+                let bucket = buckets.entry(key.clone()).or_insert(Bucket {
+                    tokens: capacity,
+                    updated: Instant::now(),
+                });
+                let now = Instant::now();
+                let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+                bucket.updated = now;
+                take_token(&mut bucket.tokens, capacity, rate, elapsed)
+            }
+            RateLimitBackend::Postgres => {
+                match RateLimitRepo::take(pool, &key, capacity, rate).await {
+                    Ok(bucket) => (bucket.tokens, bucket.allowed),
+                    Err(e) => {
+                        tracing::warn!("Rate limit store unavailable, allowing request: {}", e);
+                        return None;
+                    }
+                }
+            }
+        };
+
+        if !allowed {
+            self.audit(pool, policy, &key, path, subject);
+        }
+        Some(decision(policy, tokens, allowed))
+    }
+
+    /// Record a limit tripping, once per key per `AUDIT_INTERVAL`
+    fn audit(
+        &self,
+        pool: &PgPool,
+        policy: &RateLimitPolicy,
+        key: &str,
+        path: &str,
+        subject: &RateLimitSubject,
+    ) {
+        let now = Instant::now();
+        {
+            let mut audited = lock(&self.audited);
+            if audited
+                .get(key)
+                .is_some_and(|at| now.duration_since(*at) < AUDIT_INTERVAL)
+            {
+                return;
+            }
+            audited.insert(key.to_string(), now);
+        }
+
+        let mut event = AuditEvent::new(
+            AuditEventType::RateLimited,
+            format!("Rate limited by policy {}", policy.name),
+        )
+        .with_metadata("policy", &policy.name)
+        .with_metadata("path", path)
+        .with_metadata("key", policy.key);
+        if let Some(user_id) = subject.user_id {
+            event = event.with_user(user_id);
+        }
+        // The audit sink casts the IP to INET; skip anything a proxy mangled
+        if let Some(ip) = subject
+            .ip
+            .as_deref()
+            .filter(|ip| ip.parse::<IpAddr>().is_ok())
+        {
+            event = event.with_ip(ip);
+        }
+
+        let sink = PostgresAuditSink::new(pool.clone());
+        tokio::spawn(async move {
+            if let Err(e) = sink.record(event).await {
+                tracing::error!("Failed to record rate limit audit event: {}", e);
+            }
+        });
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Whether a policy covers a request; paths match whole segments, and a `*`
+/// segment matches any one segment (e.g. `/api/ideas/*/attachments`)
+pub fn policy_matches(policy: &RateLimitPolicy, method: &str, path: &str) -> bool {
+    let method_matches = policy.methods.is_empty()
+        || policy
+            .methods
+            .iter()
+            .any(|m| m.eq_ignore_ascii_case(method));
+    method_matches
+        && policy.paths.iter().any(|prefix| {
+            let mut segments = path.split('/');
+            prefix.trim_end_matches('/').split('/').all(|want| {
+                segments
+                    .next()
+                    .is_some_and(|got| want == "*" || want == got)
+            })
+        })
+}
+
+/// Bucket for a subject under a policy
+///
+/// User and session policies fall back to the client IP for signed-out
+/// requests, so they cannot dodge the limit by dropping their cookie.
+pub fn bucket_key(policy: &RateLimitPolicy, subject: &RateLimitSubject) -> String {
+    let ip = || format!("ip:{}", subject.ip.as_deref().unwrap_or("unknown"));
+    let part = match policy.key {
+        RateLimitKey::User => subject.user_id.map(|id| format!("user:{}", id)),
+        RateLimitKey::Session => subject.session_id.map(|id| format!("session:{}", id)),
+        RateLimitKey::Ip => None,
+        RateLimitKey::Route => Some("route".to_string()),
+    }
+    .unwrap_or_else(ip);
+    format!("{}:{}", policy.name, part)
+}
+
+/// Tokens regained per second
+fn refill_rate(policy: &RateLimitPolicy) -> f64 {
+    policy.capacity as f64 / policy.period_seconds as f64
+}
+
+/// Refill `tokens` for `elapsed` seconds, then take one if a whole one is
+/// there; returns the tokens left and whether one was taken
+pub fn take_token(tokens: &mut f64, capacity: f64, rate: f64, elapsed: f64) -> (f64, bool) {
+    let refilled = (*tokens + elapsed * rate).min(capacity);
+    let allowed = refilled >= 1.0;
+    *tokens = if allowed { refilled - 1.0 } else { refilled };
+    (*tokens, allowed)
+}
+
+/// Headers' view of a bucket
+pub fn decision(policy: &RateLimitPolicy, tokens: f64, allowed: bool) -> Decision {
+    let rate = refill_rate(policy);
+    let capacity = policy.capacity as f64;
+    Decision {
+        allowed,
+        limit: policy.capacity,
+        remaining: tokens.max(0.0).floor() as u32,
+        reset: ((capacity - tokens).max(0.0) / rate).ceil() as u64,
+        retry_after: (!allowed).then(|| ((1.0 - tokens).max(0.0) / rate).ceil().max(1.0) as u64),
+        period_seconds: policy.period_seconds,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(key: RateLimitKey) -> RateLimitPolicy {
+        RateLimitPolicy {
+            name: "feedback".to_string(),
+            paths: vec!["/api/feedback".to_string()],
+            methods: vec!["POST".to_string()],
+            key,
+            capacity: 10,
+            period_seconds: 3600,
+        }
+    }
+
+    #[test]
+    fn test_bucket_refills_to_capacity() {
+        let mut tokens = 2.0;
+        assert_eq!(take_token(&mut tokens, 2.0, 1.0, 0.0), (1.0, true));
+        assert_eq!(take_token(&mut tokens, 2.0, 1.0, 0.0), (0.0, true));
+        assert_eq!(take_token(&mut tokens, 2.0, 1.0, 0.5), (0.5, false));
+        assert_eq!(take_token(&mut tokens, 2.0, 1.0, 0.5), (0.0, true));
+        // Idle time beyond a full refill is not banked
+        assert_eq!(take_token(&mut tokens, 2.0, 1.0, 100.0), (1.0, true));
+    }
+
+    #[test]
+    fn test_paths_match_whole_segments() {
+        let p = policy(RateLimitKey::User);
+        assert!(policy_matches(&p, "POST", "/api/feedback"));
+        assert!(policy_matches(&p, "post", "/api/feedback/123/vote"));
+        assert!(!policy_matches(&p, "GET", "/api/feedback"));
+        assert!(!policy_matches(&p, "POST", "/api/feedbacks"));
+
+        let wildcard = RateLimitPolicy {
+            paths: vec!["/api/ideas/*/attachments".to_string()],
+            ..p
+        };
+        assert!(policy_matches(
+            &wildcard,
+            "POST",
+            "/api/ideas/42/attachments"
+        ));
+        assert!(!policy_matches(&wildcard, "POST", "/api/ideas/42"));
+        assert!(!policy_matches(
+            &wildcard,
+            "POST",
+            "/api/ideas/42/revisions"
+        ));
+    }
+
+    #[test]
+    fn test_signed_out_subjects_fall_back_to_ip() {
+        let user_id = Uuid::new_v4();
+        let anonymous = RateLimitSubject {
+            ip: Some("203.0.113.9".to_string()),
+            ..Default::default()
+        };
+        let signed_in = RateLimitSubject {
+            user_id: Some(user_id),
+            ..anonymous.clone()
+        };
+
+        let p = policy(RateLimitKey::User);
+        assert_eq!(
+            bucket_key(&p, &signed_in),
+            format!("feedback:user:{}", user_id)
+        );
+        assert_eq!(bucket_key(&p, &anonymous), "feedback:ip:203.0.113.9");
+        assert_eq!(
+            bucket_key(&policy(RateLimitKey::Route), &signed_in),
+            "feedback:route"
+        );
+    }
+
+    #[test]
+    fn test_decision_headers() {
+        let p = policy(RateLimitKey::Ip);
+        let ok = decision(&p, 4.5, true);
+        assert_eq!((ok.limit, ok.remaining, ok.retry_after), (10, 4, None));
+        assert_eq!(ok.reset, 1980);
+
+        let refused = decision(&p, 0.25, false);
+        assert_eq!(refused.remaining, 0);
+        assert_eq!(refused.retry_after, Some(270));
+    }
+}
@@ -0,0 +1,532 @@
+//! SQL console
+//!
+//! Runs admin queries against the live database without letting them write.
+//! Queries go over a pool of their own that logs in as the console role, so
+//! nothing a query does can reach the API role's privileges. Every query gets
+//! its own READ ONLY transaction bounded by a statement timeout, and is read
+//! through a cursor a page at a time. The transaction stays open between
+//! pages until the result is exhausted, the admin closes it, or it sits
+//! unread past the cursor TTL.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures_util::stream::{self, BoxStream, StreamExt};
+use sqlx::{
+    Column, Executor, PgConnection, PgPool, Postgres, Row, Transaction, TypeInfo, ValueRef,
+};
+use uuid::Uuid;
+
+use crate::config::SqlConsoleConfig;
+use crate::db::admin_models::{
+    ConsoleColumn, ConsoleExplainResponse, ConsolePage, CONSOLE_MAX_PAGE_SIZE,
+};
+use crate::db::admin_repos::{
+    console_declare_sql, console_explain_sql, console_fetch_sql, console_session_sql,
+};
+use crate::error::AppError;
+
+/// Cursors one admin may hold; opening another closes their oldest
+const CURSORS_PER_ADMIN: usize = 2;
+
+/// Rows read per fetch while exporting
+const EXPORT_BATCH_ROWS: i64 = 1000;
+
+/// How often expired cursors are closed
+const SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Extra idle time the server allows past the TTL before killing the session
+const IDLE_GRACE: Duration = Duration::from_secs(30);
+
+/// SQLSTATE of a statement cancelled by `statement_timeout`
+const QUERY_CANCELED: &str = "57014";
+
+/// Result still being read
+struct OpenCursor {
+    owner: Uuid,
+    tx: Transaction<'static, Postgres>,
+    columns: Vec<ConsoleColumn>,
+    sql: String,
+    /// First row of the next page, read to learn whether there is one
+    lookahead: Option<Vec<serde_json::Value>>,
+    opened_at: Instant,
+    expires_at: Instant,
+}
+
+/// CSV export in progress; counts against the open cursor limit until dropped
+struct ExportSlot(Arc<AtomicUsize>);
+
+impl Drop for ExportSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Read-only query runner shared by the admin console routes
+#[derive(Clone)]
+pub struct SqlConsole {
+    config: SqlConsoleConfig,
+    /// Pool logged in as the console role; None when not configured
+    pool: Option<PgPool>,
+    cursors: Arc<Mutex<HashMap<Uuid, OpenCursor>>>,
+    exports: Arc<AtomicUsize>,
+}
+
+impl SqlConsole {
+    pub fn new(config: &SqlConsoleConfig, pool: Option<PgPool>) -> Self {
+        Self {
+            config: config.clone(),
+            pool,
+            cursors: Arc::new(Mutex::new(HashMap::new())),
+            exports: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Close expired cursors in the background so they release their connections
+    pub fn start(&self) {
+        let console = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                console.sweep();
+            }
+        });
+    }
+
+    /// Run a query and return its first page
+    pub async fn run(
+        &self,
+        owner: Uuid,
+        sql: &str,
+        page_size: Option<i64>,
+    ) -> Result<ConsolePage, AppError> {
+        let sql = console_sql(sql)?;
+        self.make_room(owner)?;
+
+        let start = Instant::now();
+        let mut tx = self.begin().await?;
+        let columns = describe(&mut tx, sql).await?;
+        sqlx::query(&console_declare_sql(sql))
+            .persistent(false)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| self.query_error(e))?;
+
+        let now = Instant::now();
+        let cursor = OpenCursor {
+            owner,
+            tx,
+            columns,
+            sql: sql.to_string(),
+            lookahead: None,
+            opened_at: now,
+            expires_at: now,
+        };
+        self.read_page(cursor, page_size, start).await
+    }
+
+    /// Next page of an open cursor
+    pub async fn next_page(
+        &self,
+        owner: Uuid,
+        cursor_id: Uuid,
+        page_size: Option<i64>,
+    ) -> Result<ConsolePage, AppError> {
+        let start = Instant::now();
+        let cursor = self
+            .take(owner, cursor_id)
+            .ok_or_else(|| AppError::NotFound("Query cursor not found or expired".to_string()))?;
+        self.read_page(cursor, page_size, start).await
+    }
+
+    /// Close a cursor before it is exhausted; false if it was not open
+    pub fn close(&self, owner: Uuid, cursor_id: Uuid) -> bool {
+        // Dropping the transaction rolls it back and returns the connection
+        self.take(owner, cursor_id).is_some()
+    }
+
+    /// Plan of a query, optionally with actual timings
+    pub async fn explain(
+        &self,
+        sql: &str,
+        analyze: bool,
+    ) -> Result<ConsoleExplainResponse, AppError> {
+        let sql = console_sql(sql)?;
+        let start = Instant::now();
+        let mut tx = self.begin().await?;
+        let plan: serde_json::Value = sqlx::query_scalar(&console_explain_sql(sql, analyze))
+            .persistent(false)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| self.query_error(e))?;
+        tx.rollback().await?;
+
+        Ok(ConsoleExplainResponse {
+            plan,
+            analyze,
+            duration_ms: start.elapsed().as_millis(),
+            sql: sql.to_string(),
+        })
+    }
+
+    /// Stream a query's result as CSV, up to the export row limit
+    ///
+    /// The header line is produced before the stream is returned, so a bad
+    /// query fails the request instead of the download. The export holds a
+    /// console connection, so it counts against the open cursor limit until
+    /// the stream is finished or dropped.
+    pub async fn export_csv(
+        &self,
+        sql: &str,
+    ) -> Result<BoxStream<'static, Result<String, AppError>>, AppError> {
+        let sql = console_sql(sql)?;
+        let slot = self.reserve_export()?;
+        let mut tx = self.begin().await?;
+        let columns = describe(&mut tx, sql).await?;
+        sqlx::query(&console_declare_sql(sql))
+            .persistent(false)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| self.query_error(e))?;
+
+        let header = csv_line(
+            columns
+                .iter()
+                .map(|c| serde_json::Value::String(c.name.clone())),
+        );
+        let console = self.clone();
+        let remaining = self.config.export_row_limit;
+        let rows = stream::unfold(Some((tx, remaining, slot)), move |state| {
+            let console = console.clone();
+            async move {
+                let (mut tx, remaining, slot) = state?;
+                if remaining <= 0 {
+                    return None;
+                }
+                let batch = remaining.min(EXPORT_BATCH_ROWS);
+                match fetch_rows(&mut tx, &[], batch).await {
+                    Err(e) => Some((Err(console.query_error(e)), None)),
+                    Ok(rows) if rows.is_empty() => None,
+                    Ok(rows) => {
+                        let done = (rows.len() as i64) < batch;
+                        let remaining = remaining - rows.len() as i64;
+                        let chunk: String = rows.into_iter().map(csv_line).collect();
+                        let next = if done {
+                            None
+                        } else {
+                            Some((tx, remaining, slot))
+                        };
+                        Some((Ok(chunk), next))
+                    }
+                }
+            }
+        });
+
+        Ok(stream::once(async move { Ok(header) }).chain(rows).boxed())
+    }
+
+    /// Start a console transaction
+    async fn begin(&self) -> Result<Transaction<'static, Postgres>, AppError> {
+        let pool = self.pool.as_ref().ok_or_else(|| {
+            AppError::Config("SQL console database is not configured".to_string())
+        })?;
+        let mut tx = pool.begin().await?;
+        let idle = Duration::from_secs(self.config.cursor_ttl_seconds) + IDLE_GRACE;
+        let session = console_session_sql(
+            &self.config.role,
+            self.config.statement_timeout_ms,
+            idle.as_millis() as u64,
+        );
+        (&mut *tx)
+            .execute(sqlx::raw_sql(&session))
+            .await
+            .map_err(|e| {
+                tracing::error!("SQL console role {} unavailable: {}", self.config.role, e);
+                AppError::Config(format!(
+                    "SQL console role '{}' is not available",
+                    self.config.role
+                ))
+            })?;
+        Ok(tx)
+    }
+
+    /// Read one page and keep the cursor open if more rows follow
+    async fn read_page(
+        &self,
+        mut cursor: OpenCursor,
+        page_size: Option<i64>,
+        start: Instant,
+    ) -> Result<ConsolePage, AppError> {
+        let page_size = page_size
+            .unwrap_or(self.config.page_size)
+            .clamp(1, CONSOLE_MAX_PAGE_SIZE);
+
+        // One row past the page tells whether there is a next page
+        let wanted = page_size + 1 - cursor.lookahead.is_some() as i64;
+        let fetched = fetch_rows(&mut cursor.tx, &cursor.columns, wanted)
+            .await
+            .map_err(|e| self.query_error(e))?;
+        let mut rows: Vec<Vec<serde_json::Value>> = cursor.lookahead.take().into_iter().collect();
+        rows.extend(fetched);
+        let has_more = rows.len() as i64 > page_size;
+        if has_more {
+            cursor.lookahead = rows.pop();
+        }
+
+        let page = ConsolePage {
+            cursor: None,
+            columns: cursor.columns.clone(),
+            count: rows.len(),
+            rows,
+            has_more,
+            duration_ms: start.elapsed().as_millis(),
+            sql: cursor.sql.clone(),
+        };
+
+        if !has_more {
+            cursor.tx.commit().await?;
+            return Ok(page);
+        }
+        let id = Uuid::new_v4();
+        cursor.expires_at = Instant::now() + Duration::from_secs(self.config.cursor_ttl_seconds);
+        self.lock().insert(id, cursor);
+        Ok(ConsolePage {
+            cursor: Some(id),
+            ..page
+        })
+    }
+
+    /// Make room for one more cursor owned by `owner`
+    fn make_room(&self, owner: Uuid) -> Result<(), AppError> {
+        self.sweep();
+        let mut cursors = self.lock();
+
+        let mut own: Vec<(Uuid, Instant)> = cursors
+            .iter()
+            .filter(|(_, c)| c.owner == owner)
+            .map(|(id, c)| (*id, c.opened_at))
+            .collect();
+        own.sort_by_key(|(_, opened_at)| *opened_at);
+        let excess = (own.len() + 1).saturating_sub(CURSORS_PER_ADMIN);
+        for (id, _) in own.into_iter().take(excess) {
+            cursors.remove(&id);
+        }
+
+        if cursors.len() + self.exports.load(Ordering::SeqCst) >= self.config.max_open_cursors {
+            return Err(too_many_open());
+        }
+        Ok(())
+    }
+
+    /// Count a CSV export against the open cursor limit
+    fn reserve_export(&self) -> Result<ExportSlot, AppError> {
+        self.sweep();
+        // Held so a cursor cannot be opened between the check and the count
+        let cursors = self.lock();
+        if cursors.len() + self.exports.load(Ordering::SeqCst) >= self.config.max_open_cursors {
+            return Err(too_many_open());
+        }
+        self.exports.fetch_add(1, Ordering::SeqCst);
+        Ok(ExportSlot(self.exports.clone()))
+    }
+
+    /// Remove a cursor so it can be read; only its owner may
+    fn take(&self, owner: Uuid, cursor_id: Uuid) -> Option<OpenCursor> {
+        let mut cursors = self.lock();
+        match cursors.get(&cursor_id) {
+            Some(c) if c.owner == owner && c.expires_at > Instant::now() => {
+                cursors.remove(&cursor_id)
+            }
+            _ => None,
+        }
+    }
+
+    /// Close every expired cursor
+    fn sweep(&self) {
+        let now = Instant::now();
+        self.lock().retain(|_, c| c.expires_at > now);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, OpenCursor>> {
+        self.cursors.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Errors from the admin's SQL are the admin's to fix
+    fn query_error(&self, e: sqlx::Error) -> AppError {
+        match e {
+            sqlx::Error::Database(db) if db.code().as_deref() == Some(QUERY_CANCELED) => {
+                AppError::BadRequest(format!(
+                    "Query exceeded the {}ms statement timeout",
+                    self.config.statement_timeout_ms
+                ))
+            }
+            sqlx::Error::Database(db) => AppError::BadRequest(db.message().to_string()),
+            other => other.into(),
+        }
+    }
+}
+
+fn too_many_open() -> AppError {
+    AppError::BadRequest("Too many open query results; close one or try again shortly".to_string())
+}
+
+/// Trim the admin's SQL
+fn console_sql(sql: &str) -> Result<&str, AppError> {
+    let sql = sql.trim().trim_end_matches(';').trim_end();
+    if sql.is_empty() {
+        return Err(AppError::Validation("Query is empty".to_string()));
+    }
+    Ok(sql)
+}
+
+/// Result columns of a query, without running it
+///
+/// Preparing the statement also rejects input holding more than one.
+async fn describe(conn: &mut PgConnection, sql: &str) -> Result<Vec<ConsoleColumn>, AppError> {
+    let described = conn.describe(sql).await.map_err(|e| match e {
+        sqlx::Error::Database(db) => AppError::BadRequest(db.message().to_string()),
+        other => other.into(),
+    })?;
+    if described.columns().is_empty() {
+        return Err(AppError::Validation(
+            "Only statements that return rows can be run".to_string(),
+        ));
+    }
+    Ok(described
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(i, c)| ConsoleColumn {
+            name: c.name().to_string(),
+            type_name: c.type_info().name().to_lowercase(),
+            nullable: described.nullable(i),
+        })
+        .collect())
+}
+
+/// Fetch up to `rows` rows from the console cursor as JSON values
+///
+/// FETCH goes over the simple protocol so every value arrives as text and
+/// any column type can be converted; `columns` gives each its JSON shape.
+/// Without columns (CSV export) values are kept as text.
+async fn fetch_rows(
+    conn: &mut PgConnection,
+    columns: &[ConsoleColumn],
+    rows: i64,
+) -> Result<Vec<Vec<serde_json::Value>>, sqlx::Error> {
+    let sql = console_fetch_sql(rows);
+    // Through `Executor` directly the future is boxed, keeping the export
+    // stream `Send` for any connection lifetime
+    let fetched = conn.fetch_all(sqlx::raw_sql(&sql)).await?;
+
+    fetched
+        .iter()
+        .map(|row| {
+            (0..row.len())
+                .map(|i| {
+                    let raw = row.try_get_raw(i)?;
+                    if raw.is_null() {
+                        return Ok(serde_json::Value::Null);
+                    }
+                    let text = raw.as_str().map_err(sqlx::Error::Decode)?;
+                    Ok(match columns.get(i) {
+                        Some(column) => console_value(&column.type_name, text),
+                        None => serde_json::Value::String(text.to_string()),
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// JSON value for a column's text representation
+///
+/// Booleans, integers, floats and JSON keep their type; everything else,
+/// including numerics that would lose precision as floats, stays a string.
+pub fn console_value(type_name: &str, text: &str) -> serde_json::Value {
+    use serde_json::Value;
+
+    match type_name {
+        "bool" => match text {
+            "t" => Value::Bool(true),
+            "f" => Value::Bool(false),
+            _ => Value::String(text.to_string()),
+        },
+        "int2" | "int4" | "int8" | "oid" => text
+            .parse::<i64>()
+            .map(Value::from)
+            .unwrap_or_else(|_| Value::String(text.to_string())),
+        "float4" | "float8" => text
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            // NaN and Infinity have no JSON number
+            .unwrap_or_else(|| Value::String(text.to_string())),
+        "json" | "jsonb" => {
+            serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string()))
+        }
+        _ => Value::String(text.to_string()),
+    }
+}
+
+/// One CSV record (RFC 4180), ending in CRLF
+pub fn csv_line(values: impl IntoIterator<Item = serde_json::Value>) -> String {
+    let fields: Vec<String> = values
+        .into_iter()
+        .map(|value| {
+            let text = match value {
+                serde_json::Value::Null => String::new(),
+                serde_json::Value::String(s) => s,
+                other => other.to_string(),
+            };
+            if text.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", text.replace('"', "\"\""))
+            } else {
+                text
+            }
+        })
+        .collect();
+    format!("{}\r\n", fields.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_console_value_keeps_types() {
+        assert_eq!(console_value("bool", "t"), json!(true));
+        assert_eq!(console_value("int8", "-42"), json!(-42));
+        assert_eq!(console_value("float8", "1.5"), json!(1.5));
+        assert_eq!(console_value("float8", "NaN"), json!("NaN"));
+        assert_eq!(console_value("jsonb", r#"{"a": [1]}"#), json!({"a": [1]}));
+        assert_eq!(
+            console_value("numeric", "12345678901234567890.01"),
+            json!("12345678901234567890.01")
+        );
+        assert_eq!(console_value("int4[]", "{1,2}"), json!("{1,2}"));
+    }
+
+    #[test]
+    fn test_csv_line_quotes_when_needed() {
+        let line = csv_line(vec![
+            json!("plain"),
+            json!("a,b"),
+            json!("say \"hi\""),
+            serde_json::Value::Null,
+            json!(3),
+        ]);
+        assert_eq!(line, "plain,\"a,b\",\"say \"\"hi\"\"\",,3\r\n");
+    }
+
+    #[test]
+    fn test_console_sql_trims_terminator() {
+        assert_eq!(console_sql("  SELECT 1;  ").unwrap(), "SELECT 1");
+        assert!(console_sql(" ; ").is_err());
+    }
+}
@@ -0,0 +1,118 @@
+//! Sync hub
+//!
+//! Fans the `sync_events` NOTIFY channel out to open `/api/sync/stream`
+//! connections. Each API instance holds one listener connection, so writes
+//! made through any instance reach every stream.
+
+use std::time::Duration;
+
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use tokio::sync::broadcast;
+
+use crate::db::sync_models::{SyncEvent, SYNC_EVENTS_CHANNEL};
+use crate::db::sync_repos::SyncEventRepo;
+
+/// Buffered signals per subscriber before it lags and has to catch up
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// How often expired events are pruned
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Delay before retrying a failed listener connection
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Signal delivered to stream connections
+#[derive(Debug, Clone)]
+pub enum SyncSignal {
+    /// A change was committed
+    Event(SyncEvent),
+    /// Notifications may have been missed; streams should catch up from the table
+    Resync,
+}
+
+/// Broadcasts sync events to stream connections
+#[derive(Clone)]
+pub struct SyncHub {
+    sender: broadcast::Sender<SyncSignal>,
+}
+
+impl Default for SyncHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SyncHub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// Receive every signal from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<SyncSignal> {
+        self.sender.subscribe()
+    }
+
+    /// Start listening for notifications and pruning old events
+    pub fn start(&self, pool: PgPool) {
+        tokio::spawn(Self::listen(self.sender.clone(), pool.clone()));
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+            loop {
+                interval.tick().await;
+                match SyncEventRepo::prune(&pool).await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::debug!("Pruned {} sync events", n),
+                    Err(e) => tracing::warn!("Failed to prune sync events: {}", e),
+                }
+            }
+        });
+    }
+
+    async fn listen(sender: broadcast::Sender<SyncSignal>, pool: PgPool) {
+        loop {
+            let mut listener = match Self::connect(&pool).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    tracing::error!("Sync listener failed to connect: {}", e);
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                    continue;
+                }
+            };
+            // Anything committed while disconnected was not heard
+            let _ = sender.send(SyncSignal::Resync);
+
+            loop {
+                match listener.try_recv().await {
+                    Ok(Some(notification)) => {
+                        match serde_json::from_str::<SyncEvent>(notification.payload()) {
+                            Ok(event) => {
+                                let _ = sender.send(SyncSignal::Event(event));
+                            }
+                            Err(e) => {
+                                tracing::warn!("Ignoring malformed sync notification: {}", e);
+                            }
+                        }
+                    }
+                    // Connection dropped and was re-established
+                    Ok(None) => {
+                        tracing::warn!("Sync listener reconnected");
+                        let _ = sender.send(SyncSignal::Resync);
+                    }
+                    Err(e) => {
+                        tracing::error!("Sync listener error: {}", e);
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn connect(pool: &PgPool) -> Result<PgListener, sqlx::Error> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(SYNC_EVENTS_CHANNEL).await?;
+        Ok(listener)
+    }
+}
@@ -0,0 +1,430 @@
+//! Web Push service
+//!
+//! Sends notifications to browser push services. Requests carry a VAPID
+//! token (RFC 8292) and the payload is encrypted with aes128gcm (RFC 8291),
+//! so the push service only ever relays ciphertext.
+
+use std::time::Duration;
+
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{self, EcdsaKeyPair, KeyPair};
+use ring::{aead, agreement, hkdf};
+
+use super::webauthn::{b64url_decode, b64url_encode};
+use crate::config::VapidConfig;
+use crate::db::notification_models::PushSubscription;
+use crate::error::AppError;
+
+/// Record size advertised in the aes128gcm header (single record)
+const RECORD_SIZE: u32 = 4096;
+
+/// AES-GCM tag plus the padding delimiter
+const RECORD_OVERHEAD: usize = 16 + 1;
+
+/// How long push services hold undelivered messages
+const MESSAGE_TTL_SECONDS: u32 = 24 * 60 * 60;
+
+/// VAPID tokens may be valid for at most 24 hours
+const TOKEN_LIFETIME_SECONDS: i64 = 12 * 60 * 60;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// ============================================================================
+// VAPID
+// ============================================================================
+
+/// Application server signing key
+pub struct VapidKeys {
+    key_pair: EcdsaKeyPair,
+    /// Uncompressed public key (base64url), the browser's `applicationServerKey`
+    public_key: String,
+    subject: String,
+    rng: SystemRandom,
+}
+
+impl VapidKeys {
+    /// Load the key pair from config
+    pub fn from_config(config: &VapidConfig) -> Result<Self, AppError> {
+        let rng = SystemRandom::new();
+        let alg = &signature::ECDSA_P256_SHA256_FIXED_SIGNING;
+        let private_key = b64url_decode(config.private_key.trim())
+            .map_err(|_| AppError::Config("VAPID private key is not base64url".to_string()))?;
+
+        let key_pair = if private_key.len() == 32 {
+            // Raw scalar, as printed by most VAPID key generators
+            let public_key = config
+                .public_key
+                .as_deref()
+                .ok_or_else(|| {
+                    AppError::Config("VAPID public key is required with a raw private key".into())
+                })
+                .and_then(|k| {
+                    b64url_decode(k.trim()).map_err(|_| {
+                        AppError::Config("VAPID public key is not base64url".to_string())
+                    })
+                })?;
+            EcdsaKeyPair::from_private_key_and_public_key(alg, &private_key, &public_key, &rng)
+        } else {
+            EcdsaKeyPair::from_pkcs8(alg, &private_key, &rng)
+        }
+        .map_err(|e| AppError::Config(format!("Invalid VAPID key pair: {}", e)))?;
+
+        let public_key = b64url_encode(key_pair.public_key().as_ref());
+
+        Ok(Self {
+            key_pair,
+            public_key,
+            subject: config.subject.clone(),
+            rng,
+        })
+    }
+
+    /// Public key for `pushManager.subscribe({ applicationServerKey })`
+    pub fn public_key(&self) -> &str {
+        &self.public_key
+    }
+
+    /// `Authorization` header value for a push to `endpoint`
+    fn authorization(&self, endpoint: &str, now: i64) -> Result<String, AppError> {
+        let endpoint = url::Url::parse(endpoint)
+            .map_err(|_| AppError::BadRequest("Invalid push endpoint".to_string()))?;
+
+        let header = b64url_encode(br#"{"typ":"JWT","alg":"ES256"}"#);
+        let claims = serde_json::json!({
+            "aud": endpoint.origin().ascii_serialization(),
+            "exp": now + TOKEN_LIFETIME_SECONDS,
+            "sub": self.subject,
+        });
+        let signing_input = format!(
+            "{}.{}",
+            header,
+            b64url_encode(claims.to_string().as_bytes())
+        );
+
+        let signature = self
+            .key_pair
+            .sign(&self.rng, signing_input.as_bytes())
+            .map_err(|_| AppError::Internal("VAPID signing failed".to_string()))?;
+
+        Ok(format!(
+            "vapid t={}.{}, k={}",
+            signing_input,
+            b64url_encode(signature.as_ref()),
+            self.public_key
+        ))
+    }
+}
+
+// ============================================================================
+// PAYLOAD ENCRYPTION
+// ============================================================================
+
+/// Encrypt `payload` for a subscription's `p256dh` key and `auth` secret
+pub fn encrypt_payload(
+    payload: &[u8],
+    ua_public: &[u8],
+    auth_secret: &[u8],
+) -> Result<Vec<u8>, AppError> {
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; 16];
+    rng.fill(&mut salt)
+        .map_err(|_| AppError::Internal("Failed to generate salt".to_string()))?;
+    let as_private = agreement::EphemeralPrivateKey::generate(&agreement::ECDH_P256, &rng)
+        .map_err(|_| AppError::Internal("Failed to generate push key".to_string()))?;
+
+    encrypt_with(payload, ua_public, auth_secret, as_private, salt)
+}
+
+fn encrypt_with(
+    payload: &[u8],
+    ua_public: &[u8],
+    auth_secret: &[u8],
+    as_private: agreement::EphemeralPrivateKey,
+    salt: [u8; 16],
+) -> Result<Vec<u8>, AppError> {
+    if payload.len() + RECORD_OVERHEAD > RECORD_SIZE as usize {
+        return Err(AppError::BadRequest("Push payload too large".to_string()));
+    }
+
+    let as_public = as_private
+        .compute_public_key()
+        .map_err(|_| AppError::Internal("Failed to compute push key".to_string()))?;
+    let peer = agreement::UnparsedPublicKey::new(&agreement::ECDH_P256, ua_public);
+    let (cek, nonce) = agreement::agree_ephemeral(as_private, &peer, |ecdh_secret| {
+        derive_keys(
+            ecdh_secret,
+            auth_secret,
+            ua_public,
+            as_public.as_ref(),
+            &salt,
+        )
+    })
+    .map_err(|_| AppError::BadRequest("Invalid push subscription key".to_string()))?;
+
+    // Single record: payload, then the last-record delimiter with no padding
+    let mut record = Vec::with_capacity(payload.len() + RECORD_OVERHEAD);
+    record.extend_from_slice(payload);
+    record.push(0x02);
+
+    let key = aead::UnboundKey::new(&aead::AES_128_GCM, &cek)
+        .map(aead::LessSafeKey::new)
+        .map_err(|_| AppError::Internal("Invalid content encryption key".to_string()))?;
+    key.seal_in_place_append_tag(
+        aead::Nonce::assume_unique_for_key(nonce),
+        aead::Aad::empty(),
+        &mut record,
+    )
+    .map_err(|_| AppError::Internal("Push payload encryption failed".to_string()))?;
+
+    // Header: salt, record size, key id (the ephemeral public key)
+    let as_public = as_public.as_ref();
+    let mut body = Vec::with_capacity(16 + 4 + 1 + as_public.len() + record.len());
+    body.extend_from_slice(&salt);
+    body.extend_from_slice(&RECORD_SIZE.to_be_bytes());
+    body.push(as_public.len() as u8);
+    body.extend_from_slice(as_public);
+    body.extend_from_slice(&record);
+    Ok(body)
+}
+
+/// Content encryption key and nonce (RFC 8291 section 3.4)
+fn derive_keys(
+    ecdh_secret: &[u8],
+    auth_secret: &[u8],
+    ua_public: &[u8],
+    as_public: &[u8],
+    salt: &[u8],
+) -> ([u8; 16], [u8; 12]) {
+    let mut key_info = Vec::with_capacity(14 + ua_public.len() + as_public.len());
+    key_info.extend_from_slice(b"WebPush: info\0");
+    key_info.extend_from_slice(ua_public);
+    key_info.extend_from_slice(as_public);
+
+    let mut ikm = [0u8; 32];
+    let prk_key = hkdf::Salt::new(hkdf::HKDF_SHA256, auth_secret).extract(ecdh_secret);
+    hkdf_expand(&prk_key, &key_info, &mut ikm);
+
+    let prk = hkdf::Salt::new(hkdf::HKDF_SHA256, salt).extract(&ikm);
+    let mut cek = [0u8; 16];
+    hkdf_expand(&prk, b"Content-Encoding: aes128gcm\0", &mut cek);
+    let mut nonce = [0u8; 12];
+    hkdf_expand(&prk, b"Content-Encoding: nonce\0", &mut nonce);
+
+    (cek, nonce)
+}
+
+struct OkmLen(usize);
+
+impl hkdf::KeyType for OkmLen {
+    fn len(&self) -> usize {
+        self.0
+    }
+}
+
+fn hkdf_expand(prk: &hkdf::Prk, info: &[u8], out: &mut [u8]) {
+    // Expand only fails for outputs longer than 255 hash blocks
+    prk.expand(&[info], OkmLen(out.len()))
+        .and_then(|okm| okm.fill(out))
+        .expect("HKDF output length is within limits");
+}
+
+// ============================================================================
+// CLIENT
+// ============================================================================
+
+/// Result of one push request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushOutcome {
+    Delivered,
+    /// Subscription expired or was revoked; it should be deleted
+    Gone,
+    /// Temporary failure (rate limit, server or network error)
+    Retry(String),
+    /// The push service refused the message
+    Rejected(String),
+}
+
+/// Push service client
+pub struct WebPushClient {
+    http: reqwest::Client,
+    keys: VapidKeys,
+}
+
+impl WebPushClient {
+    pub fn new(keys: VapidKeys) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self { http, keys }
+    }
+
+    /// Encrypt and send `payload` to one subscription
+    pub async fn send(&self, subscription: &PushSubscription, payload: &[u8]) -> PushOutcome {
+        let body = match b64url_decode(&subscription.p256dh)
+            .and_then(|p256dh| Ok((p256dh, b64url_decode(&subscription.auth)?)))
+            .and_then(|(p256dh, auth)| encrypt_payload(payload, &p256dh, &auth))
+        {
+            Ok(body) => body,
+            Err(e) => return PushOutcome::Rejected(e.to_string()),
+        };
+
+        let authorization = match self
+            .keys
+            .authorization(&subscription.endpoint, chrono::Utc::now().timestamp())
+        {
+            Ok(value) => value,
+            Err(e) => return PushOutcome::Rejected(e.to_string()),
+        };
+
+        let response = self
+            .http
+            .post(&subscription.endpoint)
+            .header("Authorization", authorization)
+            .header("Content-Encoding", "aes128gcm")
+            .header("Content-Type", "application/octet-stream")
+            .header("TTL", MESSAGE_TTL_SECONDS.to_string())
+            .body(body)
+            .send()
+            .await;
+
+        match response {
+            Ok(response) => {
+                let status = response.status();
+                match status.as_u16() {
+                    200..=299 => PushOutcome::Delivered,
+                    404 | 410 => PushOutcome::Gone,
+                    429 | 500..=599 => {
+                        PushOutcome::Retry(format!("Push service returned {}", status))
+                    }
+                    _ => PushOutcome::Rejected(format!("Push service returned {}", status)),
+                }
+            }
+            Err(e) => PushOutcome::Retry(format!("Push request failed: {}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Recipient side of RFC 8291, as a browser would decrypt
+    fn decrypt(
+        body: &[u8],
+        ua_private: agreement::EphemeralPrivateKey,
+        ua_public: &[u8],
+        auth_secret: &[u8],
+    ) -> Vec<u8> {
+        let salt = &body[..16];
+        let record_size = u32::from_be_bytes(body[16..20].try_into().unwrap());
+        assert_eq!(record_size, RECORD_SIZE);
+        let id_len = body[20] as usize;
+        let as_public = &body[21..21 + id_len];
+
+        let peer = agreement::UnparsedPublicKey::new(&agreement::ECDH_P256, as_public);
+        let (cek, nonce) = agreement::agree_ephemeral(ua_private, &peer, |ecdh_secret| {
+            derive_keys(ecdh_secret, auth_secret, ua_public, as_public, salt)
+        })
+        .unwrap();
+
+        let key = aead::LessSafeKey::new(aead::UnboundKey::new(&aead::AES_128_GCM, &cek).unwrap());
+        let mut record = body[21 + id_len..].to_vec();
+        let plaintext = key
+            .open_in_place(
+                aead::Nonce::assume_unique_for_key(nonce),
+                aead::Aad::empty(),
+                &mut record,
+            )
+            .unwrap();
+
+        assert_eq!(plaintext.last(), Some(&0x02));
+        plaintext[..plaintext.len() - 1].to_vec()
+    }
+
+    fn vapid_keys() -> VapidKeys {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&signature::ECDSA_P256_SHA256_FIXED_SIGNING, &rng)
+            .unwrap();
+        VapidKeys::from_config(&VapidConfig {
+            private_key: b64url_encode(pkcs8.as_ref()),
+            public_key: None,
+            subject: "mailto:ops@example.com".to_string(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_payload_round_trip() {
+        let rng = SystemRandom::new();
+        let ua_private =
+            agreement::EphemeralPrivateKey::generate(&agreement::ECDH_P256, &rng).unwrap();
+        let ua_public = ua_private.compute_public_key().unwrap().as_ref().to_vec();
+        let auth_secret = [7u8; 16];
+
+        let body = encrypt_payload(b"Dentist at 14:00", &ua_public, &auth_secret).unwrap();
+
+        assert_eq!(
+            decrypt(&body, ua_private, &ua_public, &auth_secret),
+            b"Dentist at 14:00"
+        );
+    }
+
+    #[test]
+    fn test_payload_rejects_invalid_key() {
+        let result = encrypt_payload(b"hello", &[4u8; 65], &[0u8; 16]);
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn test_payload_size_limit() {
+        let rng = SystemRandom::new();
+        let ua_private =
+            agreement::EphemeralPrivateKey::generate(&agreement::ECDH_P256, &rng).unwrap();
+        let ua_public = ua_private.compute_public_key().unwrap();
+
+        let payload = vec![b'x'; RECORD_SIZE as usize];
+        let result = encrypt_payload(&payload, ua_public.as_ref(), &[0u8; 16]);
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn test_vapid_token_is_signed_for_endpoint_origin() {
+        let keys = vapid_keys();
+        let header = keys
+            .authorization("https://push.example.net/send/abc123", 1_700_000_000)
+            .unwrap();
+
+        let (token, public_key) = header
+            .strip_prefix("vapid t=")
+            .and_then(|rest| rest.split_once(", k="))
+            .unwrap();
+        assert_eq!(public_key, keys.public_key());
+
+        let (signing_input, signature) = token.rsplit_once('.').unwrap();
+        let claims = signing_input.split('.').nth(1).unwrap();
+        let claims: serde_json::Value =
+            serde_json::from_slice(&b64url_decode(claims).unwrap()).unwrap();
+        assert_eq!(claims["aud"], "https://push.example.net");
+        assert_eq!(claims["exp"], 1_700_000_000 + TOKEN_LIFETIME_SECONDS);
+        assert_eq!(claims["sub"], "mailto:ops@example.com");
+
+        let verifier = signature::UnparsedPublicKey::new(
+            &signature::ECDSA_P256_SHA256_FIXED,
+            b64url_decode(public_key).unwrap(),
+        );
+        assert!(verifier
+            .verify(signing_input.as_bytes(), &b64url_decode(signature).unwrap())
+            .is_ok());
+    }
+
+    #[test]
+    fn test_raw_private_key_requires_public_key() {
+        let result = VapidKeys::from_config(&VapidConfig {
+            private_key: b64url_encode(&[1u8; 32]),
+            public_key: None,
+            subject: "mailto:ops@example.com".to_string(),
+        });
+        assert!(matches!(result, Err(AppError::Config(_))));
+    }
+}
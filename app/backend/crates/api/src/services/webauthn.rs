@@ -0,0 +1,717 @@
+//! WebAuthn (passkey) service
+//!
+//! Minimal relying-party implementation of the registration and assertion
+//! ceremonies. Only attestation "none" is supported: the attestation
+//! statement is ignored and the credential key is trusted on first use.
+//!
+//! Supported algorithms: ES256 (-7), EdDSA (-8) and RS256 (-257).
+
+use base64::Engine;
+use ring::signature;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+
+/// COSE algorithm identifiers we advertise, in preference order
+pub const SUPPORTED_ALGORITHMS: [i64; 3] = [ALG_ES256, ALG_EDDSA, ALG_RS256];
+
+const ALG_ES256: i64 = -7;
+const ALG_EDDSA: i64 = -8;
+const ALG_RS256: i64 = -257;
+
+// Authenticator data flags
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_BACKUP_ELIGIBLE: u8 = 0x08;
+const FLAG_BACKED_UP: u8 = 0x10;
+const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+/// Challenge lifetime in minutes
+pub const CHALLENGE_TTL_MINUTES: i64 = 5;
+
+/// Ceremony timeout advertised to the browser (milliseconds)
+pub const CEREMONY_TIMEOUT_MS: u64 = 300_000;
+
+// ============================================================================
+// RELYING PARTY
+// ============================================================================
+
+/// Relying party identity derived from configuration
+#[derive(Debug, Clone)]
+pub struct RelyingParty {
+    /// RP ID (registrable domain, e.g. "ecent.online")
+    pub id: String,
+    /// Display name shown by the authenticator
+    pub name: String,
+    /// Origins allowed to run ceremonies
+    pub origins: Vec<String>,
+}
+
+impl RelyingParty {
+    /// Build the relying party from app config
+    ///
+    /// The RP ID defaults to the session cookie domain so passkeys work on
+    /// every subdomain that shares the session.
+    pub fn from_config(config: &AppConfig) -> Self {
+        let id = config
+            .auth
+            .passkey_rp_id
+            .clone()
+            .filter(|id| !id.is_empty())
+            .unwrap_or_else(|| {
+                config
+                    .auth
+                    .cookie_domain
+                    .trim_start_matches('.')
+                    .to_string()
+            });
+
+        let mut origins = vec![config.server.frontend_url.trim_end_matches('/').to_string()];
+        for origin in &config.cors.allowed_origins {
+            let origin = origin.trim_end_matches('/').to_string();
+            if !origins.contains(&origin) {
+                origins.push(origin);
+            }
+        }
+
+        Self {
+            id,
+            name: "Ignition".to_string(),
+            origins,
+        }
+    }
+
+    fn id_hash(&self) -> [u8; 32] {
+        Sha256::digest(self.id.as_bytes()).into()
+    }
+}
+
+// ============================================================================
+// ENCODING HELPERS
+// ============================================================================
+
+/// Generate a random base64url challenge
+pub fn generate_challenge() -> String {
+    use rand::RngCore;
+
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    b64url_encode(&bytes)
+}
+
+/// Base64url encode without padding (the WebAuthn wire format)
+pub fn b64url_encode(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Base64url decode, tolerating padding
+pub fn b64url_decode(value: &str) -> Result<Vec<u8>, AppError> {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .map_err(|_| AppError::BadRequest("Invalid base64url value".to_string()))
+}
+
+// ============================================================================
+// CLIENT DATA
+// ============================================================================
+
+/// Parsed `clientDataJSON`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientData {
+    #[serde(rename = "type")]
+    pub ceremony_type: String,
+    pub challenge: String,
+    pub origin: String,
+    #[serde(default)]
+    pub cross_origin: bool,
+}
+
+/// Parse `clientDataJSON` and check its type and origin
+///
+/// The challenge is returned to the caller, which must consume the stored
+/// challenge before trusting the ceremony.
+pub fn verify_client_data(
+    rp: &RelyingParty,
+    client_data_json: &[u8],
+    expected_type: &str,
+) -> Result<ClientData, AppError> {
+    let client_data: ClientData = serde_json::from_slice(client_data_json)
+        .map_err(|_| AppError::BadRequest("Invalid clientDataJSON".to_string()))?;
+
+    if client_data.ceremony_type != expected_type {
+        return Err(AppError::BadRequest("Unexpected ceremony type".to_string()));
+    }
+    if client_data.cross_origin {
+        return Err(AppError::BadRequest(
+            "Cross-origin ceremonies are not allowed".to_string(),
+        ));
+    }
+    if !rp.origins.iter().any(|o| o == &client_data.origin) {
+        return Err(AppError::InvalidOrigin);
+    }
+
+    Ok(client_data)
+}
+
+// ============================================================================
+// AUTHENTICATOR DATA
+// ============================================================================
+
+/// Credential data attached during registration
+#[derive(Debug, Clone)]
+pub struct AttestedCredential {
+    pub credential_id: Vec<u8>,
+    /// COSE_Key bytes, stored as-is
+    pub public_key: Vec<u8>,
+}
+
+/// Parsed authenticator data
+#[derive(Debug, Clone)]
+pub struct AuthenticatorData {
+    pub rp_id_hash: [u8; 32],
+    pub flags: u8,
+    pub sign_count: u32,
+    pub attested: Option<AttestedCredential>,
+}
+
+impl AuthenticatorData {
+    /// Parse the binary authenticator data structure
+    pub fn parse(data: &[u8]) -> Result<Self, AppError> {
+        let invalid = || AppError::BadRequest("Invalid authenticator data".to_string());
+
+        if data.len() < 37 {
+            return Err(invalid());
+        }
+        let mut rp_id_hash = [0u8; 32];
+        rp_id_hash.copy_from_slice(&data[..32]);
+        let flags = data[32];
+        let sign_count = u32::from_be_bytes([data[33], data[34], data[35], data[36]]);
+
+        let attested = if flags & FLAG_ATTESTED_CREDENTIAL != 0 {
+            // aaguid (16) + credential id length (2)
+            let rest = data.get(37..).ok_or_else(invalid)?;
+            if rest.len() < 18 {
+                return Err(invalid());
+            }
+            let id_len = u16::from_be_bytes([rest[16], rest[17]]) as usize;
+            let credential_id = rest.get(18..18 + id_len).ok_or_else(invalid)?.to_vec();
+            let key_bytes = rest.get(18 + id_len..).ok_or_else(invalid)?;
+            let (_, key_len) = cbor::decode(key_bytes)?;
+            Some(AttestedCredential {
+                credential_id,
+                public_key: key_bytes[..key_len].to_vec(),
+            })
+        } else {
+            None
+        };
+
+        Ok(Self {
+            rp_id_hash,
+            flags,
+            sign_count,
+            attested,
+        })
+    }
+
+    pub fn user_present(&self) -> bool {
+        self.flags & FLAG_USER_PRESENT != 0
+    }
+
+    pub fn backup_eligible(&self) -> bool {
+        self.flags & FLAG_BACKUP_ELIGIBLE != 0
+    }
+
+    pub fn backed_up(&self) -> bool {
+        self.flags & FLAG_BACKED_UP != 0
+    }
+
+    fn check_rp(&self, rp: &RelyingParty) -> Result<(), AppError> {
+        if self.rp_id_hash != rp.id_hash() {
+            return Err(AppError::BadRequest("RP ID mismatch".to_string()));
+        }
+        if !self.user_present() {
+            return Err(AppError::BadRequest(
+                "User presence is required".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// CEREMONIES
+// ============================================================================
+
+/// Result of a verified registration
+#[derive(Debug, Clone)]
+pub struct VerifiedRegistration {
+    pub credential_id: Vec<u8>,
+    pub public_key: Vec<u8>,
+    pub sign_count: u32,
+    /// "multiDevice" for synced passkeys, "singleDevice" otherwise
+    pub device_type: &'static str,
+    pub backed_up: bool,
+}
+
+/// Verify a registration response (client data already checked)
+pub fn verify_registration(
+    rp: &RelyingParty,
+    attestation_object: &[u8],
+) -> Result<VerifiedRegistration, AppError> {
+    let (object, _) = cbor::decode(attestation_object)?;
+    let auth_data = object
+        .map_get_text("authData")
+        .and_then(cbor::Value::as_bytes)
+        .ok_or_else(|| AppError::BadRequest("Missing authData".to_string()))?;
+
+    let auth_data = AuthenticatorData::parse(auth_data)?;
+    auth_data.check_rp(rp)?;
+
+    let attested = auth_data
+        .attested
+        .clone()
+        .ok_or_else(|| AppError::BadRequest("Missing attested credential".to_string()))?;
+
+    // Reject keys we could never verify an assertion with
+    CoseKey::parse(&attested.public_key)?;
+
+    Ok(VerifiedRegistration {
+        credential_id: attested.credential_id,
+        public_key: attested.public_key,
+        sign_count: auth_data.sign_count,
+        device_type: if auth_data.backup_eligible() {
+            "multiDevice"
+        } else {
+            "singleDevice"
+        },
+        backed_up: auth_data.backed_up(),
+    })
+}
+
+/// Result of a verified assertion
+#[derive(Debug, Clone)]
+pub struct VerifiedAssertion {
+    pub sign_count: u32,
+    pub backed_up: bool,
+}
+
+/// Verify an assertion signature against a stored COSE key
+/// (client data already checked)
+pub fn verify_assertion(
+    rp: &RelyingParty,
+    public_key: &[u8],
+    stored_counter: u32,
+    client_data_json: &[u8],
+    authenticator_data: &[u8],
+    signature: &[u8],
+) -> Result<VerifiedAssertion, AppError> {
+    let auth_data = AuthenticatorData::parse(authenticator_data)?;
+    auth_data.check_rp(rp)?;
+
+    let mut signed = authenticator_data.to_vec();
+    signed.extend_from_slice(&Sha256::digest(client_data_json));
+
+    CoseKey::parse(public_key)?.verify(&signed, signature)?;
+    check_counter(stored_counter, auth_data.sign_count)?;
+
+    Ok(VerifiedAssertion {
+        sign_count: auth_data.sign_count,
+        backed_up: auth_data.backed_up(),
+    })
+}
+
+/// Validate the signature counter
+///
+/// Authenticators that do not implement a counter always report 0. Otherwise
+/// the counter must strictly increase; a stale value suggests a cloned key.
+pub fn check_counter(stored: u32, received: u32) -> Result<(), AppError> {
+    if (stored != 0 || received != 0) && received <= stored {
+        return Err(AppError::Unauthorized);
+    }
+    Ok(())
+}
+
+// ============================================================================
+// COSE KEYS
+// ============================================================================
+
+/// Public key decoded from a COSE_Key
+#[derive(Debug, Clone)]
+enum CoseKey {
+    Es256 { point: Vec<u8> },
+    EdDsa { x: Vec<u8> },
+    Rs256 { n: Vec<u8>, e: Vec<u8> },
+}
+
+impl CoseKey {
+    fn parse(bytes: &[u8]) -> Result<Self, AppError> {
+        let invalid = |msg: &str| AppError::BadRequest(format!("Invalid credential key: {}", msg));
+        let (key, _) = cbor::decode(bytes)?;
+
+        let kty = key.map_get_int(1).and_then(cbor::Value::as_int);
+        let alg = key.map_get_int(3).and_then(cbor::Value::as_int);
+        let param = |label: i64| key.map_get_int(label).and_then(cbor::Value::as_bytes);
+
+        match (kty, alg) {
+            (Some(2), Some(ALG_ES256)) => {
+                if key.map_get_int(-1).and_then(cbor::Value::as_int) != Some(1) {
+                    return Err(invalid("unsupported curve"));
+                }
+                let x = param(-2)
+                    .filter(|x| x.len() == 32)
+                    .ok_or_else(|| invalid("x"))?;
+                let y = param(-3)
+                    .filter(|y| y.len() == 32)
+                    .ok_or_else(|| invalid("y"))?;
+                let mut point = Vec::with_capacity(65);
+                point.push(0x04);
+                point.extend_from_slice(x);
+                point.extend_from_slice(y);
+                Ok(CoseKey::Es256 { point })
+            }
+            (Some(1), Some(ALG_EDDSA)) => {
+                if key.map_get_int(-1).and_then(cbor::Value::as_int) != Some(6) {
+                    return Err(invalid("unsupported curve"));
+                }
+                let x = param(-2)
+                    .filter(|x| x.len() == 32)
+                    .ok_or_else(|| invalid("x"))?;
+                Ok(CoseKey::EdDsa { x: x.to_vec() })
+            }
+            (Some(3), Some(ALG_RS256)) => {
+                let n = param(-1).ok_or_else(|| invalid("n"))?;
+                let e = param(-2).ok_or_else(|| invalid("e"))?;
+                Ok(CoseKey::Rs256 {
+                    n: n.to_vec(),
+                    e: e.to_vec(),
+                })
+            }
+            _ => Err(invalid("unsupported algorithm")),
+        }
+    }
+
+    fn verify(&self, message: &[u8], sig: &[u8]) -> Result<(), AppError> {
+        let result = match self {
+            CoseKey::Es256 { point } => {
+                signature::UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_ASN1, point)
+                    .verify(message, sig)
+            }
+            CoseKey::EdDsa { x } => {
+                signature::UnparsedPublicKey::new(&signature::ED25519, x).verify(message, sig)
+            }
+            CoseKey::Rs256 { n, e } => signature::RsaPublicKeyComponents { n, e }.verify(
+                &signature::RSA_PKCS1_2048_8192_SHA256,
+                message,
+                sig,
+            ),
+        };
+        result.map_err(|_| AppError::Unauthorized)
+    }
+}
+
+// ============================================================================
+// CBOR
+// ============================================================================
+
+/// Just enough CBOR to read attestation objects and COSE keys
+mod cbor {
+    use crate::error::AppError;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Value {
+        Int(i64),
+        Bytes(Vec<u8>),
+        Text(String),
+        Array(Vec<Value>),
+        Map(Vec<(Value, Value)>),
+        Simple(u8),
+    }
+
+    impl Value {
+        pub fn as_int(&self) -> Option<i64> {
+            match self {
+                Value::Int(i) => Some(*i),
+                _ => None,
+            }
+        }
+
+        pub fn as_bytes(&self) -> Option<&[u8]> {
+            match self {
+                Value::Bytes(b) => Some(b),
+                _ => None,
+            }
+        }
+
+        pub fn map_get_int(&self, label: i64) -> Option<&Value> {
+            self.map_get(&Value::Int(label))
+        }
+
+        pub fn map_get_text(&self, label: &str) -> Option<&Value> {
+            self.map_get(&Value::Text(label.to_string()))
+        }
+
+        fn map_get(&self, key: &Value) -> Option<&Value> {
+            match self {
+                Value::Map(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+                _ => None,
+            }
+        }
+    }
+
+    const MAX_DEPTH: usize = 16;
+
+    /// Decode one item, returning it and the number of bytes consumed
+    pub fn decode(bytes: &[u8]) -> Result<(Value, usize), AppError> {
+        let mut pos = 0;
+        let value = read(bytes, &mut pos, 0)?;
+        Ok((value, pos))
+    }
+
+    fn invalid() -> AppError {
+        AppError::BadRequest("Invalid CBOR".to_string())
+    }
+
+    fn take<'a>(bytes: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8], AppError> {
+        let end = pos.checked_add(len).ok_or_else(invalid)?;
+        let slice = bytes.get(*pos..end).ok_or_else(invalid)?;
+        *pos = end;
+        Ok(slice)
+    }
+
+    fn read_arg(bytes: &[u8], pos: &mut usize, info: u8) -> Result<u64, AppError> {
+        Ok(match info {
+            0..=23 => info as u64,
+            24 => take(bytes, pos, 1)?[0] as u64,
+            25 => u16::from_be_bytes(take(bytes, pos, 2)?.try_into().unwrap()) as u64,
+            26 => u32::from_be_bytes(take(bytes, pos, 4)?.try_into().unwrap()) as u64,
+            27 => u64::from_be_bytes(take(bytes, pos, 8)?.try_into().unwrap()),
+            // Indefinite lengths are not used by authenticators
+            _ => return Err(invalid()),
+        })
+    }
+
+    fn read(bytes: &[u8], pos: &mut usize, depth: usize) -> Result<Value, AppError> {
+        if depth > MAX_DEPTH {
+            return Err(invalid());
+        }
+        let initial = take(bytes, pos, 1)?[0];
+        let major = initial >> 5;
+        let info = initial & 0x1f;
+
+        match major {
+            0 => {
+                let n = read_arg(bytes, pos, info)?;
+                Ok(Value::Int(i64::try_from(n).map_err(|_| invalid())?))
+            }
+            1 => {
+                let n = read_arg(bytes, pos, info)?;
+                let n = i64::try_from(n).map_err(|_| invalid())?;
+                Ok(Value::Int(-1 - n))
+            }
+            2 => {
+                let len = read_arg(bytes, pos, info)? as usize;
+                Ok(Value::Bytes(take(bytes, pos, len)?.to_vec()))
+            }
+            3 => {
+                let len = read_arg(bytes, pos, info)? as usize;
+                let text = std::str::from_utf8(take(bytes, pos, len)?).map_err(|_| invalid())?;
+                Ok(Value::Text(text.to_string()))
+            }
+            4 => {
+                let len = read_arg(bytes, pos, info)? as usize;
+                let mut items = Vec::with_capacity(len.min(64));
+                for _ in 0..len {
+                    items.push(read(bytes, pos, depth + 1)?);
+                }
+                Ok(Value::Array(items))
+            }
+            5 => {
+                let len = read_arg(bytes, pos, info)? as usize;
+                let mut entries = Vec::with_capacity(len.min(64));
+                for _ in 0..len {
+                    let key = read(bytes, pos, depth + 1)?;
+                    let value = read(bytes, pos, depth + 1)?;
+                    entries.push((key, value));
+                }
+                Ok(Value::Map(entries))
+            }
+            // Tags: decode and drop the tag number
+            6 => {
+                read_arg(bytes, pos, info)?;
+                read(bytes, pos, depth + 1)
+            }
+            _ => match info {
+                0..=23 => Ok(Value::Simple(info)),
+                24 => Ok(Value::Simple(take(bytes, pos, 1)?[0])),
+                // Floats are skipped over; nothing we read uses them
+                25 => take(bytes, pos, 2).map(|_| Value::Simple(0)),
+                26 => take(bytes, pos, 4).map(|_| Value::Simple(0)),
+                27 => take(bytes, pos, 8).map(|_| Value::Simple(0)),
+                _ => Err(invalid()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rp() -> RelyingParty {
+        RelyingParty {
+            id: "ecent.online".to_string(),
+            name: "Ignition".to_string(),
+            origins: vec!["https://ignition.ecent.online".to_string()],
+        }
+    }
+
+    /// CBOR-encode a small map of int labels (test helper)
+    fn cose_es256(x: &[u8], y: &[u8]) -> Vec<u8> {
+        let mut out = vec![0xa5];
+        out.extend_from_slice(&[0x01, 0x02]); // kty: EC2
+        out.extend_from_slice(&[0x03, 0x26]); // alg: -7
+        out.extend_from_slice(&[0x20, 0x01]); // crv: P-256
+        out.extend_from_slice(&[0x21, 0x58, 0x20]); // x
+        out.extend_from_slice(x);
+        out.extend_from_slice(&[0x22, 0x58, 0x20]); // y
+        out.extend_from_slice(y);
+        out
+    }
+
+    fn auth_data(
+        rp: &RelyingParty,
+        flags: u8,
+        count: u32,
+        attested: Option<(&[u8], &[u8])>,
+    ) -> Vec<u8> {
+        let mut out = rp.id_hash().to_vec();
+        out.push(flags);
+        out.extend_from_slice(&count.to_be_bytes());
+        if let Some((cred_id, key)) = attested {
+            out.extend_from_slice(&[0u8; 16]);
+            out.extend_from_slice(&(cred_id.len() as u16).to_be_bytes());
+            out.extend_from_slice(cred_id);
+            out.extend_from_slice(key);
+        }
+        out
+    }
+
+    #[test]
+    fn test_cbor_decodes_negative_ints_and_maps() {
+        let (value, used) =
+            cbor::decode(&[0xa2, 0x01, 0x02, 0x39, 0x01, 0x00, 0x41, 0xff]).unwrap();
+        assert_eq!(used, 8);
+        assert_eq!(value.map_get_int(1).and_then(cbor::Value::as_int), Some(2));
+        assert_eq!(
+            value.map_get_int(-257).and_then(cbor::Value::as_bytes),
+            Some(&[0xff][..])
+        );
+    }
+
+    #[test]
+    fn test_cbor_rejects_truncated_input() {
+        assert!(cbor::decode(&[0x58, 0x20, 0x00]).is_err());
+        assert!(cbor::decode(&[]).is_err());
+    }
+
+    #[test]
+    fn test_client_data_checks_type_and_origin() {
+        let rp = rp();
+        let ok = br#"{"type":"webauthn.get","challenge":"abc","origin":"https://ignition.ecent.online"}"#;
+        assert_eq!(
+            verify_client_data(&rp, ok, "webauthn.get")
+                .unwrap()
+                .challenge,
+            "abc"
+        );
+        assert!(verify_client_data(&rp, ok, "webauthn.create").is_err());
+
+        let evil = br#"{"type":"webauthn.get","challenge":"abc","origin":"https://evil.example"}"#;
+        assert!(verify_client_data(&rp, evil, "webauthn.get").is_err());
+    }
+
+    #[test]
+    fn test_registration_extracts_credential() {
+        let rp = rp();
+        let key = cose_es256(&[1u8; 32], &[2u8; 32]);
+        let data = auth_data(
+            &rp,
+            FLAG_USER_PRESENT | FLAG_ATTESTED_CREDENTIAL | FLAG_BACKUP_ELIGIBLE,
+            0,
+            Some((b"cred", &key)),
+        );
+
+        // {"fmt": "none", "attStmt": {}, "authData": <bytes>}
+        let mut att = vec![0xa3, 0x63];
+        att.extend_from_slice(b"fmt");
+        att.push(0x64);
+        att.extend_from_slice(b"none");
+        att.push(0x67);
+        att.extend_from_slice(b"attStmt");
+        att.push(0xa0);
+        att.push(0x68);
+        att.extend_from_slice(b"authData");
+        att.extend_from_slice(&[0x59, (data.len() >> 8) as u8, data.len() as u8]);
+        att.extend_from_slice(&data);
+
+        let reg = verify_registration(&rp, &att).unwrap();
+        assert_eq!(reg.credential_id, b"cred");
+        assert_eq!(reg.public_key, key);
+        assert_eq!(reg.device_type, "multiDevice");
+    }
+
+    #[test]
+    fn test_auth_data_requires_matching_rp_and_presence() {
+        let rp = rp();
+        let other = RelyingParty {
+            id: "example.com".to_string(),
+            ..rp.clone()
+        };
+        let data =
+            AuthenticatorData::parse(&auth_data(&other, FLAG_USER_PRESENT, 1, None)).unwrap();
+        assert!(data.check_rp(&rp).is_err());
+
+        let data = AuthenticatorData::parse(&auth_data(&rp, 0, 1, None)).unwrap();
+        assert!(data.check_rp(&rp).is_err());
+    }
+
+    #[test]
+    fn test_assertion_with_ed25519_key() {
+        use ring::signature::{Ed25519KeyPair, KeyPair};
+
+        let rp = rp();
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+
+        let mut key = vec![0xa4, 0x01, 0x01, 0x03, 0x27, 0x20, 0x06, 0x21, 0x58, 0x20];
+        key.extend_from_slice(pair.public_key().as_ref());
+
+        let client_data = br#"{"type":"webauthn.get","challenge":"abc","origin":"https://ignition.ecent.online"}"#;
+        let data = auth_data(&rp, FLAG_USER_PRESENT, 5, None);
+        let mut signed = data.clone();
+        signed.extend_from_slice(&Sha256::digest(client_data));
+        let sig = pair.sign(&signed);
+
+        let result = verify_assertion(&rp, &key, 4, client_data, &data, sig.as_ref()).unwrap();
+        assert_eq!(result.sign_count, 5);
+
+        // Replayed counter is rejected
+        assert!(verify_assertion(&rp, &key, 5, client_data, &data, sig.as_ref()).is_err());
+        // Tampered signature is rejected
+        let mut bad = sig.as_ref().to_vec();
+        bad[0] ^= 1;
+        assert!(verify_assertion(&rp, &key, 4, client_data, &data, &bad).is_err());
+    }
+
+    #[test]
+    fn test_counter_rules() {
+        assert!(check_counter(0, 0).is_ok());
+        assert!(check_counter(0, 1).is_ok());
+        assert!(check_counter(5, 6).is_ok());
+        assert!(check_counter(5, 5).is_err());
+        assert!(check_counter(5, 0).is_err());
+    }
+}
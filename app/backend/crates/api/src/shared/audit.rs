@@ -23,7 +23,11 @@ pub enum AuditEventType {
     Logout,
     SessionCreated,
     SessionExpired,
+    SessionRevoked,
     PasswordChanged,
+    ApiTokenCreated,
+    ApiTokenRevoked,
+    ApiTokenUsed,
 
     // User events
     UserCreated,
@@ -64,7 +68,11 @@ impl std::fmt::Display for AuditEventType {
             AuditEventType::Logout => write!(f, "logout"),
             AuditEventType::SessionCreated => write!(f, "session_created"),
             AuditEventType::SessionExpired => write!(f, "session_expired"),
+            AuditEventType::SessionRevoked => write!(f, "session_revoked"),
             AuditEventType::PasswordChanged => write!(f, "password_changed"),
+            AuditEventType::ApiTokenCreated => write!(f, "api_token_created"),
+            AuditEventType::ApiTokenRevoked => write!(f, "api_token_revoked"),
+            AuditEventType::ApiTokenUsed => write!(f, "api_token_used"),
             AuditEventType::UserCreated => write!(f, "user_created"),
             AuditEventType::UserUpdated => write!(f, "user_updated"),
             AuditEventType::UserDeleted => write!(f, "user_deleted"),
@@ -181,6 +189,15 @@ impl PostgresAuditSink {
     }
 }
 
+pub const POSTGRES_AUDIT_SINK_RECORD: &str = r#"
+    INSERT INTO audit_log (
+        id, event_type, user_id, resource_type, resource_id,
+        action, status, details, ip_address, user_agent, created_at
+    ) VALUES (
+        $1, $2, $3, $4, $5, $6, 'success', $7, $8::inet, $9, $10
+    )
+"#;
+
 impl AuditSink for PostgresAuditSink {
     fn record(
         &self,
@@ -191,29 +208,20 @@ impl AuditSink for PostgresAuditSink {
             let metadata = serde_json::to_value(&event.metadata).unwrap_or(serde_json::json!({}));
 
             // Use audit_log table with correct schema
-            sqlx::query(
-                r#"
-                INSERT INTO audit_log (
-                    id, event_type, user_id, resource_type, resource_id,
-                    action, status, details, ip_address, user_agent, created_at
-                ) VALUES (
-                    $1, $2, $3, $4, $5, $6, 'success', $7, $8::inet, $9, $10
-                )
-                "#,
-            )
-            .bind(Uuid::new_v4())
-            .bind(event.event_type.to_string())
-            .bind(event.user_id)
-            .bind(&event.resource_type)
-            .bind(event.resource_id.map(|id| id.to_string()))
-            .bind(&event.action)
-            .bind(metadata)
-            .bind(&event.ip_address)
-            .bind(&event.user_agent)
-            .bind(event.timestamp)
-            .execute(&pool)
-            .await
-            .map_err(|e| AppError::Database(e.to_string()))?;
+            sqlx::query(POSTGRES_AUDIT_SINK_RECORD)
+                .bind(Uuid::new_v4())
+                .bind(event.event_type.to_string())
+                .bind(event.user_id)
+                .bind(&event.resource_type)
+                .bind(event.resource_id)
+                .bind(&event.action)
+                .bind(metadata)
+                .bind(&event.ip_address)
+                .bind(&event.user_agent)
+                .bind(event.timestamp)
+                .execute(&pool)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?;
 
             Ok(())
         })
@@ -314,6 +322,11 @@ pub fn record_async<S: AuditSink + 'static>(sink: &'static S, event: AuditEvent)
     });
 }
 
+pub const WRITE_AUDIT: &str = r#"
+    INSERT INTO audit_log (event_type, user_id, action, resource_type, resource_id, status)
+    VALUES ($1, $2, $3, $4, $5, $6)
+"#;
+
 /// Simple audit writer for route handlers
 /// Writes directly to audit_log table (fire and forget)
 pub fn write_audit(
@@ -323,24 +336,60 @@ pub fn write_audit(
     action: &str,
     resource_type: Option<&str>,
     resource_id: Option<Uuid>,
+) {
+    spawn_audit(
+        pool,
+        event_type,
+        user_id,
+        action,
+        resource_type,
+        resource_id,
+        "success",
+    );
+}
+
+/// Like `write_audit`, for an action that was attempted but failed
+pub fn write_audit_failure(
+    pool: PgPool,
+    event_type: AuditEventType,
+    user_id: Option<Uuid>,
+    action: &str,
+    resource_type: Option<&str>,
+    resource_id: Option<Uuid>,
+) {
+    spawn_audit(
+        pool,
+        event_type,
+        user_id,
+        action,
+        resource_type,
+        resource_id,
+        "failure",
+    );
+}
+
+fn spawn_audit(
+    pool: PgPool,
+    event_type: AuditEventType,
+    user_id: Option<Uuid>,
+    action: &str,
+    resource_type: Option<&str>,
+    resource_id: Option<Uuid>,
+    status: &'static str,
 ) {
     let action = action.to_string();
     let resource_type = resource_type.map(|s| s.to_string());
 
     tokio::spawn(async move {
-        let result = sqlx::query(
-            r#"
-            INSERT INTO audit_log (event_type, user_id, action, resource_type, resource_id, status)
-            VALUES ($1, $2, $3, $4, $5, 'success')
-            "#,
-        )
-        .bind(event_type.to_string())
-        .bind(user_id)
-        .bind(&action)
-        .bind(&resource_type)
-        .bind(resource_id.map(|id| id.to_string()))
-        .execute(&pool)
-        .await;
+        let result = sqlx::query(WRITE_AUDIT)
+            .bind(event_type.to_string())
+            .bind(user_id)
+            .bind(&action)
+            .bind(&resource_type)
+            .bind(resource_id)
+            .bind(status)
+            .execute(&pool)
+            .await;
 
         if let Err(e) = result {
             tracing::error!("Failed to write audit log: {}", e);
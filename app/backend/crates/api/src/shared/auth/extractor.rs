@@ -40,6 +40,8 @@ pub struct Auth {
     pub entitlements: Vec<String>,
     /// Whether this is a dev bypass session
     pub is_dev_bypass: bool,
+    /// API token used for this request, if not a browser session
+    pub api_token_id: Option<Uuid>,
 }
 
 impl From<&AuthContext> for Auth {
@@ -52,13 +54,17 @@ impl From<&AuthContext> for Auth {
             session_id: ctx.session_id,
             entitlements: ctx.entitlements.clone(),
             is_dev_bypass: ctx.is_dev_bypass,
+            api_token_id: ctx.api_token_id,
         }
     }
 }
 
 impl Auth {
-    /// Check if user is an admin
+    /// Check if user is an admin (never true for API tokens)
     pub fn is_admin(&self) -> bool {
+        if self.api_token_id.is_some() {
+            return false;
+        }
         self.role == "admin" || self.entitlements.contains(&"admin:access".to_string())
     }
 
@@ -134,6 +140,7 @@ mod tests {
             session_id: Uuid::new_v4(),
             entitlements: entitlements.into_iter().map(String::from).collect(),
             is_dev_bypass: false,
+            api_token_id: None,
         }
     }
 
@@ -6,4 +6,4 @@ pub mod csrf;
 pub mod extractor;
 pub mod origin;
 pub mod rbac;
-
+pub mod scopes;
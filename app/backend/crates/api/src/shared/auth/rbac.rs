@@ -209,6 +209,7 @@ mod tests {
             session_id: Uuid::new_v4(),
             entitlements: entitlements.into_iter().map(String::from).collect(),
             is_dev_bypass: false,
+            api_token_id: None,
         }
     }
 
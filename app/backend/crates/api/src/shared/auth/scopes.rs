@@ -0,0 +1,114 @@
+//! API token scopes
+//!
+//! Scopes are entitlement strings of the form `<area>:read` / `<area>:write`,
+//! where `<area>` is the first segment of an `/api` path. `write` implies
+//! `read`. Account management (`/api/user/*` other than the inbox) is never
+//! reachable with a token.
+
+use axum::http::Method;
+
+use super::rbac::RbacPolicy;
+
+/// API areas that tokens can be scoped to
+pub const SCOPE_AREAS: &[&str] = &[
+    "books",
+    "calendar",
+    "daily-plan",
+    "exercise",
+    "focus",
+    "gamification",
+    "goals",
+    "habits",
+    "ideas",
+    "inbox",
+    "infobase",
+    "learn",
+    "market",
+    "notifications",
+    "quests",
+    "reference",
+    "references",
+    "search",
+    "settings",
+    "sync",
+    "today",
+];
+
+/// Check a scope string against the catalog
+pub fn is_valid_scope(scope: &str) -> bool {
+    match scope.split_once(':') {
+        Some((area, access)) => SCOPE_AREAS.contains(&area) && matches!(access, "read" | "write"),
+        None => false,
+    }
+}
+
+/// Area a path (relative to `/api`) belongs to, if tokens may reach it
+pub fn scope_area(path: &str) -> Option<&'static str> {
+    let mut segments = path.trim_start_matches('/').split('/');
+    let first = segments.next()?;
+
+    // The inbox lives under /user but is ordinary user data
+    let area = if first == "user" {
+        match segments.next() {
+            Some("inbox") => "inbox",
+            _ => return None,
+        }
+    } else {
+        first
+    };
+
+    SCOPE_AREAS.iter().copied().find(|a| *a == area)
+}
+
+/// Policy a token must satisfy for a request, or None if tokens are not allowed
+pub fn policy_for_request(path: &str, method: &Method) -> Option<RbacPolicy> {
+    let area = scope_area(path)?;
+    let read = format!("{}:read", area);
+    let write = format!("{}:write", area);
+
+    let policy = if matches!(method, &Method::GET | &Method::HEAD | &Method::OPTIONS) {
+        RbacPolicy::any(&[read.as_str(), write.as_str()])
+    } else {
+        RbacPolicy::any(&[write.as_str()])
+    };
+
+    // A token never inherits admin powers from its owner
+    Some(policy.no_admin_bypass())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_scopes() {
+        assert!(is_valid_scope("focus:write"));
+        assert!(is_valid_scope("habits:read"));
+        assert!(!is_valid_scope("habits:delete"));
+        assert!(!is_valid_scope("user:write"));
+        assert!(!is_valid_scope("admin:access"));
+        assert!(!is_valid_scope("focus"));
+    }
+
+    #[test]
+    fn test_scope_area() {
+        assert_eq!(scope_area("/focus/sessions"), Some("focus"));
+        assert_eq!(scope_area("/user/inbox/123"), Some("inbox"));
+        assert_eq!(scope_area("/search"), Some("search"));
+        assert_eq!(scope_area("/notifications/1/read"), Some("notifications"));
+        assert_eq!(scope_area("/user/sessions"), None);
+        assert_eq!(scope_area("/user/tokens"), None);
+        assert_eq!(scope_area("/analysis"), None);
+        assert_eq!(scope_area("/"), None);
+    }
+
+    #[test]
+    fn test_write_scope_implies_read() {
+        let read = policy_for_request("/habits", &Method::GET).unwrap();
+        assert_eq!(read.any_of, vec!["habits:read", "habits:write"]);
+        assert!(!read.admin_bypass);
+
+        let write = policy_for_request("/habits/1/complete", &Method::POST).unwrap();
+        assert_eq!(write.any_of, vec!["habits:write"]);
+    }
+}
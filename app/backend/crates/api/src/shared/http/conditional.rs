@@ -0,0 +1,58 @@
+//! Conditional Writes
+//!
+//! Versioned resources answer with their version as an `ETag`; clients send
+//! it back as `If-Match` so a save based on an older version can be refused.
+
+use axum::http::{header, HeaderMap, HeaderName};
+
+use crate::error::AppError;
+
+/// Version named by the request's `If-Match`, if any
+///
+/// Accepts `"3"`, `W/"3"` or a bare `3`; `*` matches any version.
+pub fn if_match_version(headers: &HeaderMap) -> Result<Option<i32>, AppError> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+    let invalid = || AppError::BadRequest("If-Match must name a single version".into());
+    let value = value.to_str().map_err(|_| invalid())?.trim();
+    if value == "*" {
+        return Ok(None);
+    }
+    let value = value.strip_prefix("W/").unwrap_or(value);
+    value
+        .trim_matches('"')
+        .parse::<i32>()
+        .map(Some)
+        .map_err(|_| invalid())
+}
+
+/// `ETag` header for a version
+pub fn version_etag(version: i32) -> [(HeaderName, String); 1] {
+    [(header::ETAG, format!("\"{}\"", version))]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn with_if_match(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_MATCH, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn test_if_match_versions() {
+        assert_eq!(if_match_version(&HeaderMap::new()).unwrap(), None);
+        assert_eq!(if_match_version(&with_if_match("\"3\"")).unwrap(), Some(3));
+        assert_eq!(
+            if_match_version(&with_if_match("W/\"3\"")).unwrap(),
+            Some(3)
+        );
+        assert_eq!(if_match_version(&with_if_match("7")).unwrap(), Some(7));
+        assert_eq!(if_match_version(&with_if_match("*")).unwrap(), None);
+        assert!(if_match_version(&with_if_match("\"1\", \"2\"")).is_err());
+    }
+}
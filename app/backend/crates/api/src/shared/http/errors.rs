@@ -99,6 +99,8 @@ impl IntoResponse for ApiError {
             "unauthorized" => StatusCode::UNAUTHORIZED,
             "forbidden" | "csrf_violation" | "invalid_origin" => StatusCode::FORBIDDEN,
             "bad_request" => StatusCode::BAD_REQUEST,
+            "conflict" => StatusCode::CONFLICT,
+            "rate_limited" => StatusCode::TOO_MANY_REQUESTS,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         };
 
@@ -118,6 +120,8 @@ impl From<AppError> for ApiError {
             AppError::Validation(msg) => Self::validation(msg),
             AppError::OAuthError(msg) => Self::new("oauth_error", msg),
             AppError::SessionExpired => Self::new("session_expired", "Session has expired"),
+            AppError::RateLimited(msg) => Self::new("rate_limited", msg),
+            AppError::Conflict { message, .. } => Self::new("conflict", message),
             AppError::Database(_) => Self::internal(),
             AppError::DatabaseWithContext { operation, table, .. } => {
                 // Log is already emitted by the error handler, return generic response
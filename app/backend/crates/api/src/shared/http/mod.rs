@@ -1,7 +1,9 @@
 //! HTTP shared utilities
 //!
-//! Provides response helpers, error mapping, and validation utilities.
+//! Provides response helpers, error mapping, conditional writes, and
+//! validation utilities.
 
+pub mod conditional;
 pub mod errors;
 pub mod response;
 pub mod validation;
@@ -9,9 +9,11 @@
 //! - Typed IDs
 //! - Database transactions and pagination
 //! - Audit logging
+//! - Serde field helpers
 
 pub mod audit;
 pub mod auth;
 pub mod db;
 pub mod http;
 pub mod ids;
+pub mod serde_helpers;
@@ -0,0 +1,17 @@
+//! Serde helpers
+//!
+//! Field deserializers shared by request models.
+
+/// Tells an explicit `null` apart from a missing field; use with
+/// `#[serde(default, deserialize_with = "double_option::deserialize")]`
+pub mod double_option {
+    use serde::{Deserialize, Deserializer};
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        Option::<T>::deserialize(deserializer).map(Some)
+    }
+}
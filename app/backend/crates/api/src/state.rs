@@ -7,6 +7,10 @@ use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
 
 use crate::config::AppConfig;
+use crate::services::feature_flags::FeatureFlags;
+use crate::services::rate_limit::RateLimiter;
+use crate::services::sql_console::SqlConsole;
+use crate::services::sync_hub::SyncHub;
 use crate::storage::StorageClient;
 
 /// Shared application state
@@ -18,6 +22,14 @@ pub struct AppState {
     pub db: PgPool,
     /// Storage client (R2/S3) - optional, only available if configured
     pub storage: Option<StorageClient>,
+    /// Sync event fan-out for `/api/sync/stream` (started by `main`)
+    pub sync: SyncHub,
+    /// Admin SQL console and its open result cursors
+    pub console: SqlConsole,
+    /// Feature flags, evaluated in memory (loaded and refreshed by `main`)
+    pub flags: FeatureFlags,
+    /// Request rate limits (pruned by `main`)
+    pub rate_limits: RateLimiter,
 }
 
 impl AppState {
@@ -90,10 +102,34 @@ impl AppState {
             None
         };
 
+        // The SQL console logs in as its own role over a separate pool
+        let console_pool = match &config.sql_console.database_url {
+            Some(url) => match PgPoolOptions::new()
+                .max_connections(config.sql_console.pool_size)
+                .acquire_timeout(Duration::from_secs(5))
+                .idle_timeout(Duration::from_secs(300))
+                .connect_lazy(url)
+            {
+                Ok(pool) => Some(pool),
+                Err(e) => {
+                    tracing::warn!("SQL console database not available: {}", e);
+                    None
+                }
+            },
+            None => {
+                tracing::info!("SQL console database not configured");
+                None
+            }
+        };
+
         Ok(Self {
             config: Arc::new(config.clone()),
             db,
             storage,
+            sync: SyncHub::new(),
+            console: SqlConsole::new(&config.sql_console, console_pool),
+            flags: FeatureFlags::new(),
+            rate_limits: RateLimiter::new(&config.rate_limits),
         })
     }
 
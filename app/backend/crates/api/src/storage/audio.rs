@@ -0,0 +1,374 @@
+//! Audio probing
+//!
+//! Reads the duration of an uploaded audio file from its container headers,
+//! without decoding any audio. WAV, FLAC, Ogg (Vorbis/Opus), MP4/M4A and MP3
+//! are recognised by their leading bytes, whatever MIME type the client sent;
+//! anything else (raw AAC, truncated files) has no duration.
+
+/// Duration in seconds, if the container says
+pub fn probe_duration(data: &[u8]) -> Option<f32> {
+    let seconds = if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WAVE") {
+        wav_duration(data)
+    } else if data.starts_with(b"fLaC") {
+        flac_duration(data)
+    } else if data.starts_with(b"OggS") {
+        ogg_duration(data)
+    } else if data.get(4..8) == Some(b"ftyp") {
+        mp4_duration(data)
+    } else {
+        mp3_duration(data)
+    }?;
+
+    (seconds.is_finite() && seconds > 0.0).then_some(seconds as f32)
+}
+
+fn u16_le(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn u32_le(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn u32_be(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn u64_be(data: &[u8], at: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(data.get(at..at + 8)?.try_into().ok()?))
+}
+
+/// `data` chunk size over the `fmt ` chunk's byte rate
+fn wav_duration(data: &[u8]) -> Option<f64> {
+    let mut at = 12;
+    let mut byte_rate = None;
+    while at + 8 <= data.len() {
+        let id = &data[at..at + 4];
+        let size = u32_le(data, at + 4)? as usize;
+        match id {
+            b"fmt " => byte_rate = u32_le(data, at + 16),
+            // Streamed recordings leave the size unset; use what is there
+            b"data" => {
+                let size = size.min(data.len() - at - 8);
+                return Some(size as f64 / byte_rate.filter(|r| *r > 0)? as f64);
+            }
+            _ => {}
+        }
+        // Chunks are padded to an even length
+        at += 8 + size + (size & 1);
+    }
+    None
+}
+
+/// Total samples over sample rate, from STREAMINFO
+fn flac_duration(data: &[u8]) -> Option<f64> {
+    // STREAMINFO is always the first metadata block
+    if data.get(4)? & 0x7F != 0 {
+        return None;
+    }
+    let info = data.get(8..8 + 18)?;
+    let rate =
+        (u32::from(info[10]) << 12) | (u32::from(info[11]) << 4) | (u32::from(info[12]) >> 4);
+    let samples = (u64::from(info[13] & 0x0F) << 32) | u64::from(u32_be(info, 14)?);
+    if rate == 0 || samples == 0 {
+        return None;
+    }
+    Some(samples as f64 / rate as f64)
+}
+
+/// Granule position of the last page over the codec's sample rate
+fn ogg_duration(data: &[u8]) -> Option<f64> {
+    let segments = *data.get(26)? as usize;
+    let packet = data.get(27 + segments..)?;
+    let (rate, skip) = if packet.starts_with(b"\x01vorbis") {
+        (u32_le(packet, 12)?, 0)
+    } else if packet.starts_with(b"OpusHead") {
+        // Opus granules always count 48 kHz samples
+        (48_000, u16_le(packet, 10)?)
+    } else {
+        return None;
+    };
+
+    let last = data.windows(4).rposition(|w| w == b"OggS")?;
+    let granule = i64::from_le_bytes(data.get(last + 6..last + 14)?.try_into().ok()?);
+    if rate == 0 || granule <= i64::from(skip) {
+        return None;
+    }
+    Some((granule - i64::from(skip)) as f64 / rate as f64)
+}
+
+/// Duration over timescale, from `moov/mvhd`
+fn mp4_duration(data: &[u8]) -> Option<f64> {
+    let moov = mp4_box(data, b"moov")?;
+    let mvhd = mp4_box(moov, b"mvhd")?;
+    let (timescale, duration) = match mvhd.first()? {
+        0 => (u32_be(mvhd, 12)?, u64::from(u32_be(mvhd, 16)?)),
+        1 => (u32_be(mvhd, 20)?, u64_be(mvhd, 24)?),
+        _ => return None,
+    };
+    if timescale == 0 {
+        return None;
+    }
+    Some(duration as f64 / timescale as f64)
+}
+
+/// Body of the first box of `kind` directly inside `data`
+fn mp4_box<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    let mut at = 0;
+    while at + 8 <= data.len() {
+        let (size, header) = match u32_be(data, at)? {
+            0 => (data.len() - at, 8),
+            1 => (usize::try_from(u64_be(data, at + 8)?).ok()?, 16),
+            size => (size as usize, 8),
+        };
+        let end = at.checked_add(size)?;
+        if size < header || end > data.len() {
+            return None;
+        }
+        if &data[at + 4..at + 8] == kind {
+            return Some(&data[at + header..end]);
+        }
+        at = end;
+    }
+    None
+}
+
+/// MPEG audio frame header
+struct Mp3Frame {
+    mpeg1: bool,
+    mono: bool,
+    bitrate_kbps: u32,
+    sample_rate: u32,
+}
+
+impl Mp3Frame {
+    fn parse(header: &[u8]) -> Option<Self> {
+        if header.len() < 4 || header[0] != 0xFF || header[1] & 0xE0 != 0xE0 {
+            return None;
+        }
+        let version = (header[1] >> 3) & 0x03;
+        let layer = (header[1] >> 1) & 0x03;
+        // Layer III only; version 1 is reserved
+        if version == 1 || layer != 1 {
+            return None;
+        }
+        let mpeg1 = version == 3;
+        let bitrates: [u32; 15] = if mpeg1 {
+            [
+                0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+            ]
+        } else {
+            [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160]
+        };
+        let bitrate_kbps = *bitrates.get(usize::from(header[2] >> 4))?;
+        let base_rate = *[44_100, 48_000, 32_000].get(usize::from((header[2] >> 2) & 0x03))?;
+        let sample_rate = match version {
+            3 => base_rate,
+            2 => base_rate / 2,
+            _ => base_rate / 4,
+        };
+        Some(Mp3Frame {
+            mpeg1,
+            mono: header[3] >> 6 == 3,
+            bitrate_kbps,
+            sample_rate,
+        })
+    }
+
+    fn samples_per_frame(&self) -> u32 {
+        if self.mpeg1 {
+            1152
+        } else {
+            576
+        }
+    }
+
+    /// Offset of a Xing/Info header from the frame start
+    fn xing_offset(&self) -> usize {
+        4 + match (self.mpeg1, self.mono) {
+            (true, false) => 32,
+            (true, true) | (false, false) => 17,
+            (false, true) => 9,
+        }
+    }
+}
+
+/// Frame count from a Xing/Info or VBRI header, else the first frame's
+/// bitrate over the audio length (constant bitrate)
+fn mp3_duration(data: &[u8]) -> Option<f64> {
+    let mut start = 0;
+    if data.starts_with(b"ID3") {
+        let header = data.get(..10)?;
+        let size = header[6..10]
+            .iter()
+            .fold(0usize, |acc, b| (acc << 7) | usize::from(b & 0x7F));
+        let footer = if header[5] & 0x10 != 0 { 10 } else { 0 };
+        start = 10 + size + footer;
+    }
+    // Some encoders pad between the tag and the first frame
+    let search = data.get(start..)?;
+    let offset = search
+        .windows(4)
+        .take(64 * 1024)
+        .position(|w| Mp3Frame::parse(w).is_some_and(|f| f.bitrate_kbps > 0))?;
+    let frame_start = start + offset;
+    let frame_bytes = &data[frame_start..];
+    let frame = Mp3Frame::parse(frame_bytes)?;
+
+    let xing = frame.xing_offset();
+    let frames = match frame_bytes.get(xing..xing + 4) {
+        Some(b"Xing") | Some(b"Info") if u32_be(frame_bytes, xing + 4)? & 0x01 != 0 => {
+            u32_be(frame_bytes, xing + 8)
+        }
+        _ if frame_bytes.get(36..40) == Some(b"VBRI") => u32_be(frame_bytes, 36 + 14),
+        _ => None,
+    };
+    if let Some(frames) = frames {
+        return Some(
+            f64::from(frames) * f64::from(frame.samples_per_frame()) / f64::from(frame.sample_rate),
+        );
+    }
+
+    let mut audio_len = frame_bytes.len();
+    if data.len() >= 128 && &data[data.len() - 128..data.len() - 125] == b"TAG" {
+        audio_len = audio_len.saturating_sub(128);
+    }
+    Some(audio_len as f64 * 8.0 / (f64::from(frame.bitrate_kbps) * 1000.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav(sample_rate: u32, channels: u16, seconds: u32) -> Vec<u8> {
+        let byte_rate = sample_rate * u32::from(channels) * 2;
+        let data_len = byte_rate * seconds;
+        let mut out = Vec::new();
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&(36 + data_len).to_le_bytes());
+        out.extend_from_slice(b"WAVEfmt ");
+        out.extend_from_slice(&16u32.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&channels.to_le_bytes());
+        out.extend_from_slice(&sample_rate.to_le_bytes());
+        out.extend_from_slice(&byte_rate.to_le_bytes());
+        out.extend_from_slice(&(channels * 2).to_le_bytes());
+        out.extend_from_slice(&16u16.to_le_bytes());
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&data_len.to_le_bytes());
+        out.resize(out.len() + data_len as usize, 0);
+        out
+    }
+
+    fn mp4_box_bytes(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut out = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(kind);
+        out.extend_from_slice(body);
+        out
+    }
+
+    fn ogg_page(granule: i64, packet: &[u8]) -> Vec<u8> {
+        let mut out = b"OggS\x00\x00".to_vec();
+        out.extend_from_slice(&granule.to_le_bytes());
+        out.extend_from_slice(&[0; 12]);
+        out.push(1);
+        out.push(packet.len() as u8);
+        out.extend_from_slice(packet);
+        out
+    }
+
+    #[test]
+    fn test_wav_duration() {
+        assert_eq!(probe_duration(&wav(8_000, 1, 2)), Some(2.0));
+        assert_eq!(probe_duration(&wav(44_100, 2, 1)), Some(1.0));
+    }
+
+    #[test]
+    fn test_flac_duration() {
+        let mut data = b"fLaC".to_vec();
+        data.extend_from_slice(&[0x80, 0, 0, 34]);
+        let mut info = [0u8; 34];
+        // 44.1 kHz, stereo, 16 bit, 132300 samples
+        let rate: u32 = 44_100;
+        info[10] = (rate >> 12) as u8;
+        info[11] = (rate >> 4) as u8;
+        info[12] = ((rate << 4) as u8) | (1 << 1);
+        info[13] = 0xF0;
+        info[14..18].copy_from_slice(&132_300u32.to_be_bytes());
+        data.extend_from_slice(&info);
+        assert_eq!(probe_duration(&data), Some(3.0));
+    }
+
+    #[test]
+    fn test_ogg_duration() {
+        let mut ident = b"\x01vorbis".to_vec();
+        ident.extend_from_slice(&0u32.to_le_bytes());
+        ident.push(2);
+        ident.extend_from_slice(&48_000u32.to_le_bytes());
+        let mut data = ogg_page(0, &ident);
+        data.extend(ogg_page(96_000, b"audio"));
+        assert_eq!(probe_duration(&data), Some(2.0));
+
+        let mut head = b"OpusHead\x01\x02".to_vec();
+        head.extend_from_slice(&312u16.to_le_bytes());
+        let mut data = ogg_page(0, &head);
+        data.extend(ogg_page(48_000 + 312, b"audio"));
+        assert_eq!(probe_duration(&data), Some(1.0));
+    }
+
+    #[test]
+    fn test_mp4_duration() {
+        let mut mvhd = vec![0u8; 4];
+        mvhd.extend_from_slice(&[0; 8]);
+        mvhd.extend_from_slice(&600u32.to_be_bytes());
+        mvhd.extend_from_slice(&2_700u32.to_be_bytes());
+        let mut data = mp4_box_bytes(b"ftyp", b"M4A \x00\x00\x00\x00");
+        data.extend(mp4_box_bytes(b"free", &[0; 4]));
+        data.extend(mp4_box_bytes(b"moov", &mp4_box_bytes(b"mvhd", &mvhd)));
+        assert_eq!(probe_duration(&data), Some(4.5));
+    }
+
+    #[test]
+    fn test_mp4_huge_largesize() {
+        let mut data = mp4_box_bytes(b"ftyp", b"M4A \x00\x00\x00\x00");
+        data.extend_from_slice(&1u32.to_be_bytes());
+        data.extend_from_slice(b"moov");
+        data.extend_from_slice(&u64::MAX.to_be_bytes());
+        data.extend_from_slice(&[0; 8]);
+        assert_eq!(probe_duration(&data), None);
+    }
+
+    #[test]
+    fn test_mp3_constant_bitrate() {
+        // MPEG-1 Layer III, 128 kbps, 44.1 kHz, joint stereo
+        let mut data = b"ID3\x04\x00\x00\x00\x00\x00\x0a".to_vec();
+        data.extend_from_slice(&[0; 10]);
+        let frame_start = data.len();
+        data.extend_from_slice(&[0xFF, 0xFB, 0x90, 0x40]);
+        data.resize(frame_start + 16_000 * 3, 0);
+        assert_eq!(probe_duration(&data), Some(3.0));
+    }
+
+    #[test]
+    fn test_mp3_xing_frame_count() {
+        let mut data = vec![0xFF, 0xFB, 0x90, 0x40];
+        data.resize(36, 0);
+        data.extend_from_slice(b"Xing");
+        data.extend_from_slice(&1u32.to_be_bytes());
+        data.extend_from_slice(&1_000u32.to_be_bytes());
+        data.resize(4_000, 0);
+        let seconds = probe_duration(&data).unwrap();
+        assert!((seconds - 1_000.0 * 1152.0 / 44_100.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_unknown_audio_has_no_duration() {
+        assert_eq!(probe_duration(b""), None);
+        assert_eq!(probe_duration(b"not audio at all"), None);
+        // ADTS AAC carries no length
+        assert_eq!(
+            probe_duration(&[0xFF, 0xF1, 0x50, 0x80, 0, 0x1F, 0xFC]),
+            None
+        );
+    }
+}
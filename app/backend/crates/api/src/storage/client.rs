@@ -7,14 +7,35 @@
 use chrono::Utc;
 use s3::creds::Credentials;
 use s3::region::Region;
+use s3::serde_types::Part;
 use s3::Bucket;
+use std::path::Path;
 use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 use uuid::Uuid;
 
 use super::types::*;
 use crate::config::StorageConfig;
 use crate::error::AppError;
 
+/// Bytes per multipart upload part (S3 requires at least 5 MiB)
+const UPLOAD_PART_SIZE: usize = 8 * 1024 * 1024;
+
+fn file_error(e: std::io::Error) -> AppError {
+    AppError::Internal(format!("Temporary file I/O failed: {}", e))
+}
+
+/// Read up to one upload part; shorter only at the end of the input
+async fn read_part<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Vec<u8>, AppError> {
+    let mut part = Vec::with_capacity(UPLOAD_PART_SIZE);
+    (&mut *reader)
+        .take(UPLOAD_PART_SIZE as u64)
+        .read_to_end(&mut part)
+        .await
+        .map_err(file_error)?;
+    Ok(part)
+}
+
 /// R2 Storage Client
 ///
 /// Provides secure, backend-only access to R2/S3 storage.
@@ -419,6 +440,149 @@ impl StorageClient {
         Ok(true)
     }
 
+    /// List every object under a key prefix (no ownership check - caller must verify)
+    pub async fn list_by_prefix(&self, prefix: &str) -> Result<Vec<StoredObject>, AppError> {
+        let list_result = self
+            .bucket
+            .list(prefix.to_string(), None)
+            .await
+            .map_err(|e| AppError::Internal(format!("S3 list failed: {}", e)))?;
+
+        Ok(list_result
+            .iter()
+            .flat_map(|r| &r.contents)
+            .map(|o| StoredObject {
+                key: o.key.clone(),
+                size_bytes: o.size,
+            })
+            .collect())
+    }
+
+    /// Delete every object under a key prefix, returning how many were removed
+    /// (no ownership check - caller must verify)
+    pub async fn delete_prefix(&self, prefix: &str) -> Result<u64, AppError> {
+        let mut removed = 0;
+        for obj in self.list_by_prefix(prefix).await? {
+            self.delete_by_key(&obj.key).await?;
+            removed += 1;
+        }
+
+        Ok(removed)
+    }
+
+    /// Read a blob by its full R2 key (no ownership check - caller must verify)
+    pub async fn get_by_key(&self, key: &str) -> Result<Vec<u8>, AppError> {
+        let response = self
+            .bucket
+            .get_object(key)
+            .await
+            .map_err(|e| AppError::Internal(format!("S3 get failed: {}", e)))?;
+
+        Ok(response.to_vec())
+    }
+
+    /// Write a blob under a caller-chosen R2 key (no ownership check - caller must verify)
+    pub async fn put_by_key(
+        &self,
+        key: &str,
+        data: &[u8],
+        mime_type: &str,
+    ) -> Result<(), AppError> {
+        self.bucket
+            .put_object_with_content_type(key, data, mime_type)
+            .await
+            .map_err(|e| AppError::Internal(format!("S3 upload failed: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Upload a local file under a caller-chosen R2 key (no ownership check - caller must verify)
+    ///
+    /// Files larger than one part go up as a multipart upload, a part at a
+    /// time, so only one part is held in memory. Returns the bytes uploaded.
+    pub async fn put_file_by_key(
+        &self,
+        key: &str,
+        path: &Path,
+        mime_type: &str,
+    ) -> Result<u64, AppError> {
+        let mut file = tokio::fs::File::open(path).await.map_err(file_error)?;
+
+        let first = read_part(&mut file).await?;
+        if first.len() < UPLOAD_PART_SIZE {
+            self.put_by_key(key, &first, mime_type).await?;
+            return Ok(first.len() as u64);
+        }
+
+        let upload = self
+            .bucket
+            .initiate_multipart_upload(key, mime_type)
+            .await
+            .map_err(|e| AppError::Internal(format!("S3 upload failed: {}", e)))?;
+        let uploaded = self
+            .put_parts(key, &upload.upload_id, mime_type, first, &mut file)
+            .await;
+        match uploaded {
+            Ok((parts, size)) => {
+                self.bucket
+                    .complete_multipart_upload(key, &upload.upload_id, parts)
+                    .await
+                    .map_err(|e| AppError::Internal(format!("S3 upload failed: {}", e)))?;
+                Ok(size)
+            }
+            Err(e) => {
+                if let Err(abort) = self.bucket.abort_upload(key, &upload.upload_id).await {
+                    tracing::warn!(key, "Failed to abort multipart upload: {}", abort);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Upload the parts of a multipart upload, starting with `first`
+    async fn put_parts(
+        &self,
+        key: &str,
+        upload_id: &str,
+        mime_type: &str,
+        first: Vec<u8>,
+        file: &mut tokio::fs::File,
+    ) -> Result<(Vec<Part>, u64), AppError> {
+        let mut parts = Vec::new();
+        let mut size = 0;
+        let mut chunk = first;
+        while !chunk.is_empty() {
+            let last = chunk.len() < UPLOAD_PART_SIZE;
+            size += chunk.len() as u64;
+            let part = self
+                .bucket
+                .put_multipart_chunk(chunk, key, parts.len() as u32 + 1, upload_id, mime_type)
+                .await
+                .map_err(|e| AppError::Internal(format!("S3 upload failed: {}", e)))?;
+            parts.push(part);
+            if last {
+                break;
+            }
+            chunk = read_part(file).await?;
+        }
+        Ok((parts, size))
+    }
+
+    /// Download a blob into a local file (no ownership check - caller must verify)
+    ///
+    /// Returns the bytes written.
+    pub async fn get_to_file(&self, key: &str, path: &Path) -> Result<u64, AppError> {
+        let mut file = tokio::fs::File::create(path).await.map_err(file_error)?;
+        self.bucket
+            .get_object_to_writer(key, &mut file)
+            .await
+            .map_err(|e| AppError::Internal(format!("S3 get failed: {}", e)))?;
+        file.flush().await.map_err(file_error)?;
+
+        let metadata = file.metadata().await.map_err(file_error)?;
+        Ok(metadata.len())
+    }
+
     /// Generate a signed download URL for a specific key (no ownership check - caller must verify)
     pub async fn generate_signed_download_url(
         &self,
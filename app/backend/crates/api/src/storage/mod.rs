@@ -3,8 +3,11 @@
 //! Backend-only R2/S3 storage access.
 //! Frontend never receives credentials - all access is through backend APIs.
 
+pub mod audio;
 pub mod client;
+pub mod temp;
 pub mod types;
 
 pub use client::StorageClient;
+pub use temp::TempFile;
 pub use types::*;
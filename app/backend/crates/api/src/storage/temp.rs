@@ -0,0 +1,34 @@
+//! Scratch files
+//!
+//! Large objects (archives, backup tables) pass through local files instead
+//! of memory; the file is removed when its handle is dropped.
+
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+/// A file under the system temp directory, removed on drop
+pub struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// Reserve a fresh path; the file itself is created by whoever writes it
+    pub fn new(label: &str) -> Self {
+        let name = format!("ignition-{}-{}", label, Uuid::new_v4().simple());
+        Self {
+            path: std::env::temp_dir().join(name),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        // Never created, or already gone
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
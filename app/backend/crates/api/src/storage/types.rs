@@ -167,6 +167,13 @@ pub struct BlobInfo {
     pub etag: Option<String>,
 }
 
+/// Stored object listed by key prefix
+#[derive(Debug, Clone)]
+pub struct StoredObject {
+    pub key: String,
+    pub size_bytes: u64,
+}
+
 /// Signed URL response
 #[derive(Debug, Clone, Serialize)]
 pub struct SignedUrlResponse {
@@ -256,6 +263,33 @@ pub fn get_extension_from_mime(mime: &str) -> &'static str {
     }
 }
 
+/// Get MIME type from file extension (inverse of `get_extension_from_mime`)
+pub fn get_mime_from_extension(extension: &str) -> &'static str {
+    match extension {
+        // Audio
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "flac" => "audio/flac",
+        "aac" => "audio/aac",
+        "m4a" => "audio/m4a",
+        // Images
+        "jpg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        // Documents
+        "pdf" => "application/pdf",
+        "json" => "application/json",
+        "txt" => "text/plain",
+        // Archives
+        "zip" => "application/zip",
+        // Default
+        _ => "application/octet-stream",
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -312,4 +346,12 @@ mod tests {
         assert_eq!(get_extension_from_mime("image/jpeg"), "jpg");
         assert_eq!(get_extension_from_mime("unknown/type"), "bin");
     }
+
+    #[test]
+    fn test_mime_from_extension_round_trips() {
+        for mime in ["audio/mpeg", "image/jpeg", "image/png", "application/zip"] {
+            assert_eq!(get_mime_from_extension(get_extension_from_mime(mime)), mime);
+        }
+        assert_eq!(get_mime_from_extension("bin"), "application/octet-stream");
+    }
 }
@@ -0,0 +1,269 @@
+//! Account archive tests
+//!
+//! Export archives round-trip through ZIP and restore into a fresh account
+//! with new IDs, relations and storage keys intact.
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use crate::db::archive_models::*;
+    use crate::db::archive_repos::{ArchiveJobRepo, ArchiveRepo};
+    use crate::error::AppError;
+    use crate::services::account_archive::{collect_archive, ArchiveReader, ArchiveWriter};
+
+    // ========================================================================
+    // TEST HELPERS
+    // ========================================================================
+
+    async fn create_test_user(pool: &PgPool) -> Uuid {
+        let user_id = Uuid::new_v4();
+        let email = format!("test-archive-{}@example.com", user_id);
+
+        sqlx::query(
+            r#"INSERT INTO users (id, email, name, role, approved, age_verified, tos_accepted)
+               VALUES ($1, $2, 'Test Archive User', 'user', true, true, true)"#,
+        )
+        .bind(user_id)
+        .bind(&email)
+        .execute(pool)
+        .await
+        .expect("Failed to create test user");
+
+        user_id
+    }
+
+    /// A habit with a completion, a goal with a milestone, an event linked
+    /// to the habit and a reference track with a region
+    async fn seed_user_data(pool: &PgPool, user_id: Uuid) {
+        sqlx::query(
+            r#"WITH habit AS (
+                   INSERT INTO habits (user_id, name, frequency, target_count, is_active,
+                                       current_streak, longest_streak, sort_order)
+                   VALUES ($1, 'Read', 'daily', 1, true, 0, 0, 0)
+                   RETURNING id
+               ), completion AS (
+                   INSERT INTO habit_completions (user_id, habit_id, completed_date)
+                   SELECT $1, id, CURRENT_DATE FROM habit
+               ), goal AS (
+                   INSERT INTO goals (user_id, title, status, sort_order, priority, progress)
+                   VALUES ($1, 'Finish album', 'active', 0, 0, 0)
+                   RETURNING id
+               ), milestone AS (
+                   INSERT INTO goal_milestones (goal_id, title, sort_order, is_completed)
+                   SELECT id, 'Mix', 0, false FROM goal
+               ), event AS (
+                   INSERT INTO calendar_events (user_id, title, event_type, start_time, all_day,
+                                                habit_id)
+                   SELECT $1, 'Reading', 'habit', NOW(), false, id FROM habit
+               ), track AS (
+                   INSERT INTO reference_tracks (user_id, title, r2_key)
+                   VALUES ($1, 'Demo', $1 || '/audio/' || gen_random_uuid() || '.mp3')
+                   RETURNING id
+               )
+               INSERT INTO track_regions (user_id, track_id, name, start_time_seconds,
+                                          end_time_seconds)
+               SELECT $1, id, 'Chorus', 10, 20 FROM track"#,
+        )
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .expect("Failed to seed user data");
+    }
+
+    async fn archive_of(pool: &PgPool, user_id: Uuid) -> ArchiveContents {
+        let contents = collect_archive(pool, None, user_id, None, Some(12))
+            .await
+            .expect("Failed to collect archive");
+        let bytes = ArchiveWriter::new(Cursor::new(Vec::new()), &contents)
+            .and_then(|writer| writer.finish())
+            .unwrap()
+            .into_inner();
+        ArchiveReader::new(Cursor::new(bytes))
+            .and_then(|mut reader| reader.contents())
+            .unwrap()
+    }
+
+    // ========================================================================
+    // EXPORT / IMPORT
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_export_covers_child_tables(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        seed_user_data(&pool, user_id).await;
+
+        let archive = archive_of(&pool, user_id).await;
+
+        assert_eq!(archive.manifest.format_version, ARCHIVE_FORMAT_VERSION);
+        assert_eq!(archive.manifest.tables["goal_milestones"], 1);
+        assert_eq!(archive.manifest.tables["track_regions"], 1);
+        assert_eq!(archive.tables["habit_completions"].len(), 1);
+    }
+
+    #[sqlx::test]
+    async fn test_import_remaps_ids_and_relations(pool: PgPool) {
+        let source = create_test_user(&pool).await;
+        seed_user_data(&pool, source).await;
+        let archive = archive_of(&pool, source).await;
+
+        let target = create_test_user(&pool).await;
+        // The account's own balance survives; archived balances are not imported
+        sqlx::query(
+            "INSERT INTO user_wallet (user_id, coins, total_earned, total_spent) VALUES ($1, 5, 5, 0)",
+        )
+        .bind(target)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let restored = ArchiveRepo::restore(&pool, target, &archive).await.unwrap();
+        assert_eq!(restored["habits"], 1);
+        assert!(!restored.contains_key("user_wallet"));
+
+        let coins: i64 = sqlx::query_scalar("SELECT coins FROM user_wallet WHERE user_id = $1")
+            .bind(target)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(coins, 5);
+
+        // Source rows are untouched and the copies point at each other
+        let (linked, milestones, regions): (bool, i64, i64) = sqlx::query_as(
+            r#"SELECT
+                   EXISTS (SELECT 1 FROM calendar_events e
+                           JOIN habits h ON h.id = e.habit_id AND h.user_id = $1
+                           JOIN habit_completions c ON c.habit_id = h.id AND c.user_id = $1
+                           WHERE e.user_id = $1),
+                   (SELECT COUNT(*) FROM goal_milestones m
+                    JOIN goals g ON g.id = m.goal_id WHERE g.user_id = $1),
+                   (SELECT COUNT(*) FROM track_regions r
+                    JOIN reference_tracks t ON t.id = r.track_id AND t.user_id = $1
+                    WHERE r.user_id = $1 AND t.r2_key LIKE $1 || '/audio/%')"#,
+        )
+        .bind(target)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert!(linked);
+        assert_eq!((milestones, regions), (1, 1));
+
+        let shared_ids: i64 = sqlx::query_scalar(
+            r#"SELECT COUNT(*) FROM habits a JOIN habits b ON a.id = b.id
+               WHERE a.user_id = $1 AND b.user_id = $2"#,
+        )
+        .bind(source)
+        .bind(target)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(shared_ids, 0);
+    }
+
+    #[sqlx::test]
+    async fn test_import_requires_account_without_data(pool: PgPool) {
+        let source = create_test_user(&pool).await;
+        seed_user_data(&pool, source).await;
+        let archive = archive_of(&pool, source).await;
+
+        let result = ArchiveRepo::restore(&pool, source, &archive).await;
+
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[sqlx::test]
+    async fn test_import_rejects_newer_format(pool: PgPool) {
+        let source = create_test_user(&pool).await;
+        let mut archive = archive_of(&pool, source).await;
+        archive.manifest.format_version = ARCHIVE_FORMAT_VERSION + 1;
+        let target = create_test_user(&pool).await;
+
+        let result = ArchiveRepo::restore(&pool, target, &archive).await;
+
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    // ========================================================================
+    // JOBS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_one_active_job_per_user(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+
+        let first = ArchiveJobRepo::create(&pool, user_id, ARCHIVE_JOB_KIND_EXPORT, None)
+            .await
+            .unwrap()
+            .expect("first job is created");
+        let second = ArchiveJobRepo::create(&pool, user_id, ARCHIVE_JOB_KIND_EXPORT, None)
+            .await
+            .unwrap();
+        assert!(second.is_none());
+
+        let lease = Uuid::new_v4();
+        assert!(ArchiveJobRepo::claim(&pool, first.id, lease).await.unwrap());
+        ArchiveJobRepo::fail(&pool, first.id, lease, "boom")
+            .await
+            .unwrap();
+        let third = ArchiveJobRepo::create(&pool, user_id, ARCHIVE_JOB_KIND_IMPORT, Some("k"))
+            .await
+            .unwrap();
+        assert!(third.is_some());
+    }
+
+    #[sqlx::test]
+    async fn test_job_outcome_needs_the_lease(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let job = ArchiveJobRepo::create(&pool, user_id, ARCHIVE_JOB_KIND_EXPORT, None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let lease = Uuid::new_v4();
+        assert!(ArchiveJobRepo::claim(&pool, job.id, lease).await.unwrap());
+        assert!(!ArchiveJobRepo::claim(&pool, job.id, Uuid::new_v4())
+            .await
+            .unwrap());
+        assert!(
+            !ArchiveJobRepo::fail(&pool, job.id, Uuid::new_v4(), "not mine")
+                .await
+                .unwrap()
+        );
+
+        // Another instance starting up leaves a live lease alone
+        assert_eq!(ArchiveJobRepo::fail_expired(&pool, None).await.unwrap(), 0);
+        assert!(ArchiveJobRepo::renew(&pool, job.id, lease).await.unwrap());
+    }
+
+    #[sqlx::test]
+    async fn test_expired_lease_is_failed(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let job = ArchiveJobRepo::create(&pool, user_id, ARCHIVE_JOB_KIND_EXPORT, None)
+            .await
+            .unwrap()
+            .unwrap();
+        let lease = Uuid::new_v4();
+        assert!(ArchiveJobRepo::claim(&pool, job.id, lease).await.unwrap());
+        sqlx::query(
+            "UPDATE account_archive_jobs SET leased_until = NOW() - INTERVAL '1 second' WHERE id = $1",
+        )
+        .bind(job.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(ArchiveJobRepo::fail_expired(&pool, None).await.unwrap(), 1);
+
+        // The stalled worker can no longer overwrite the outcome
+        assert!(
+            !ArchiveJobRepo::complete(&pool, job.id, lease, "k", 1, &serde_json::json!({}))
+                .await
+                .unwrap()
+        );
+        let jobs = ArchiveJobRepo::list(&pool, user_id, 10).await.unwrap();
+        assert!(jobs.iter().all(|j| j.status == ARCHIVE_JOB_STATUS_FAILED));
+    }
+}
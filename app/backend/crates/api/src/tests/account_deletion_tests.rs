@@ -0,0 +1,298 @@
+//! Account deletion tests
+//!
+//! Deletion is scheduled with a grace period, can be cancelled until then,
+//! and the purge removes the user's rows everywhere.
+
+#[cfg(test)]
+mod tests {
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use crate::db::account_deletion_models::*;
+    use crate::db::account_deletion_repos::{AccountDeletionRepo, ACCOUNT_TABLES};
+    use crate::db::archive_repos::ARCHIVE_TABLES;
+    use crate::error::AppError;
+    use crate::services::account_deletion::{purge_now, AccountPurger};
+
+    /// Tables with a `user_id` whose rows outlive the account
+    const RETAINED_TABLES: &[&str] =
+        &["audit_log", "account_deletions", "notification_preferences"];
+
+    // ========================================================================
+    // TEST HELPERS
+    // ========================================================================
+
+    async fn create_test_user(pool: &PgPool) -> Uuid {
+        let user_id = Uuid::new_v4();
+        let email = format!("test-deletion-{}@example.com", user_id);
+
+        sqlx::query(
+            r#"INSERT INTO users (id, email, name, role, approved, age_verified, tos_accepted)
+               VALUES ($1, $2, 'Test Deletion User', 'user', true, true, true)"#,
+        )
+        .bind(user_id)
+        .bind(&email)
+        .execute(pool)
+        .await
+        .expect("Failed to create test user");
+
+        user_id
+    }
+
+    /// A goal with a milestone, a session and a feedback entry
+    async fn seed_user_data(pool: &PgPool, user_id: Uuid) {
+        sqlx::query(
+            r#"WITH goal AS (
+                   INSERT INTO goals (user_id, title, status, sort_order, priority, progress)
+                   VALUES ($1, 'Finish album', 'active', 0, 0, 0)
+                   RETURNING id
+               ), milestone AS (
+                   INSERT INTO goal_milestones (goal_id, title, sort_order, is_completed)
+                   SELECT id, 'Mix', 0, false FROM goal
+               ), session AS (
+                   INSERT INTO sessions (user_id, token, expires_at)
+                   VALUES ($1, 'token-' || $1, NOW() + INTERVAL '1 day')
+               )
+               INSERT INTO feedback (user_id, feedback_type, title, description, status)
+               VALUES ($1, 'bug', 'Broken', 'Something broke', 'open')"#,
+        )
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .expect("Failed to seed user data");
+    }
+
+    async fn row_count(pool: &PgPool, sql: &str, user_id: Uuid) -> i64 {
+        sqlx::query_scalar(sql)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    // ========================================================================
+    // SCHEDULING
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_schedule_is_idempotent_and_cancellable(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+
+        let first = AccountDeletionRepo::schedule(&pool, user_id, 30)
+            .await
+            .unwrap();
+        let second = AccountDeletionRepo::schedule(&pool, user_id, 1)
+            .await
+            .unwrap();
+        assert_eq!(first.status, ACCOUNT_DELETION_STATUS_SCHEDULED);
+        assert_eq!(second.purge_after, first.purge_after);
+        assert!(first.purge_after > chrono::Utc::now() + chrono::Duration::days(29));
+
+        let cancelled = AccountDeletionRepo::cancel(&pool, user_id)
+            .await
+            .unwrap()
+            .expect("scheduled deletion is cancelled");
+        assert_eq!(cancelled.status, ACCOUNT_DELETION_STATUS_CANCELLED);
+        assert!(AccountDeletionRepo::cancel(&pool, user_id)
+            .await
+            .unwrap()
+            .is_none());
+
+        let again = AccountDeletionRepo::schedule(&pool, user_id, 7)
+            .await
+            .unwrap();
+        assert_eq!(again.status, ACCOUNT_DELETION_STATUS_SCHEDULED);
+        assert!(again.cancelled_at.is_none());
+    }
+
+    #[sqlx::test]
+    async fn test_only_due_deletions_are_claimed(pool: PgPool) {
+        let waiting = create_test_user(&pool).await;
+        let due = create_test_user(&pool).await;
+        AccountDeletionRepo::schedule(&pool, waiting, 30)
+            .await
+            .unwrap();
+        AccountDeletionRepo::schedule(&pool, due, 0).await.unwrap();
+
+        let claimed = AccountDeletionRepo::claim_due(&pool, 10).await.unwrap();
+
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].user_id, due);
+        assert_eq!(claimed[0].status, ACCOUNT_DELETION_STATUS_PURGING);
+        // Leased, so a second run does not pick it up again
+        assert!(AccountDeletionRepo::claim_due(&pool, 10)
+            .await
+            .unwrap()
+            .is_empty());
+        // Too late to cancel
+        assert!(AccountDeletionRepo::cancel(&pool, due)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[sqlx::test]
+    async fn test_schedule_signs_the_account_out(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        seed_user_data(&pool, user_id).await;
+        sqlx::query(
+            r#"INSERT INTO api_tokens (user_id, name, token_hash, token_prefix)
+               VALUES ($1, 'CLI', 'hash-' || $1, 'ign_')"#,
+        )
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        AccountDeletionRepo::schedule(&pool, user_id, 30)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            row_count(
+                &pool,
+                "SELECT COUNT(*) FROM sessions WHERE user_id = $1",
+                user_id
+            )
+            .await,
+            0
+        );
+        assert_eq!(
+            row_count(
+                &pool,
+                "SELECT COUNT(*) FROM api_tokens WHERE user_id = $1 AND revoked_at IS NULL",
+                user_id
+            )
+            .await,
+            0
+        );
+    }
+
+    // ========================================================================
+    // PURGE
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_only_the_lease_owner_completes_a_purge(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        AccountDeletionRepo::schedule(&pool, user_id, 0)
+            .await
+            .unwrap();
+        let claimed = AccountDeletionRepo::claim_due(&pool, 10).await.unwrap();
+        let lease_owner = claimed[0].lease_owner.unwrap();
+
+        let stale = AccountDeletionRepo::complete(&pool, user_id, Uuid::new_v4()).await;
+        assert!(stale.is_err());
+        // Rolled back
+        assert!(AccountDeletionRepo::user_exists(&pool, user_id)
+            .await
+            .unwrap());
+
+        let completed = AccountDeletionRepo::complete(&pool, user_id, lease_owner)
+            .await
+            .unwrap();
+        assert_eq!(completed.status, ACCOUNT_DELETION_STATUS_COMPLETED);
+    }
+
+    #[sqlx::test]
+    async fn test_purger_removes_rows_and_reports(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let other = create_test_user(&pool).await;
+        AccountDeletionRepo::schedule(&pool, user_id, 0)
+            .await
+            .unwrap();
+        // Signed in again during the grace period
+        seed_user_data(&pool, user_id).await;
+        seed_user_data(&pool, other).await;
+        // The deleted user resolved the other user's feedback
+        sqlx::query("UPDATE feedback SET resolved_by = $1 WHERE user_id = $2")
+            .bind(user_id)
+            .bind(other)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let purger = AccountPurger::new(None, std::time::Duration::from_secs(60));
+        assert_eq!(purger.run_once(&pool).await.unwrap(), 1);
+
+        let deletion = AccountDeletionRepo::get(&pool, user_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(deletion.status, ACCOUNT_DELETION_STATUS_COMPLETED);
+        let report = AccountPurgeReport::from(deletion);
+        assert_eq!(report.rows_removed["goal_milestones"], 1);
+        assert_eq!(report.rows_removed["sessions"], 1);
+        assert_eq!(report.rows_removed["users"], 1);
+
+        assert_eq!(
+            row_count(&pool, "SELECT COUNT(*) FROM users WHERE id = $1", user_id).await,
+            0
+        );
+        // The other user's rows are untouched
+        assert_eq!(
+            row_count(
+                &pool,
+                r#"SELECT COUNT(*) FROM goal_milestones m
+                   JOIN goals g ON g.id = m.goal_id WHERE g.user_id = $1"#,
+                other
+            )
+            .await,
+            1
+        );
+        assert_eq!(
+            row_count(
+                &pool,
+                "SELECT COUNT(*) FROM feedback WHERE user_id = $1 AND resolved_by IS NULL",
+                other
+            )
+            .await,
+            1
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_purge_now_skips_grace_and_repeats_report(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let admin_id = create_test_user(&pool).await;
+        seed_user_data(&pool, user_id).await;
+        AccountDeletionRepo::schedule(&pool, user_id, 30)
+            .await
+            .unwrap();
+
+        let report = purge_now(&pool, None, user_id, admin_id).await.unwrap();
+        assert_eq!(report.rows_removed["goals"], 1);
+
+        let again = purge_now(&pool, None, user_id, admin_id).await.unwrap();
+        assert_eq!(again.rows_removed, report.rows_removed);
+
+        let missing = purge_now(&pool, None, Uuid::new_v4(), admin_id).await;
+        assert!(matches!(missing, Err(AppError::NotFound(_))));
+    }
+
+    #[sqlx::test]
+    async fn test_purge_covers_every_user_table(pool: PgPool) {
+        let tables: Vec<String> = sqlx::query_scalar(
+            r#"SELECT table_name::text FROM information_schema.columns
+               WHERE table_schema = 'public' AND column_name = 'user_id'"#,
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+
+        let uncovered: Vec<&String> = tables
+            .iter()
+            .filter(|t| {
+                !ARCHIVE_TABLES.iter().any(|a| a.table == t.as_str())
+                    && !ACCOUNT_TABLES.contains(&t.as_str())
+                    && !RETAINED_TABLES.contains(&t.as_str())
+            })
+            .collect();
+
+        assert!(
+            uncovered.is_empty(),
+            "Tables not removed on account deletion: {:?}",
+            uncovered
+        );
+    }
+}
@@ -189,4 +189,38 @@ mod unit_tests {
         // POST, PUT, PATCH, DELETE are not safe
         assert!(Method::POST != Method::GET);
     }
+
+    #[test]
+    fn test_session_token_hash_is_hex_sha256() {
+        use crate::db::repos::{generate_session_token, hash_session_token};
+
+        let token = generate_session_token();
+        let hash = hash_session_token(&token);
+
+        assert_eq!(hash.len(), 64);
+        assert!(hash
+            .chars()
+            .all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase()));
+        assert_ne!(hash, token);
+        assert_eq!(hash, hash_session_token(&token));
+        assert_eq!(
+            hash_session_token("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_describe_device() {
+        use crate::db::platform_models::describe_device;
+
+        let mac_chrome = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+        let win_edge = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36 Edg/120.0.0.0";
+        let iphone_safari = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+
+        assert_eq!(describe_device(Some(mac_chrome)), "Chrome on macOS");
+        assert_eq!(describe_device(Some(win_edge)), "Edge on Windows");
+        assert_eq!(describe_device(Some(iphone_safari)), "Safari on iOS");
+        assert_eq!(describe_device(None), "Unknown device");
+        assert_eq!(describe_device(Some("  ")), "Unknown device");
+    }
 }
@@ -0,0 +1,240 @@
+//! Daily plan tests
+//!
+//! Generation fits focus blocks around the calendar, picks the habits due
+//! that day and carries over what was left undone; templates can be shared
+//! and applied.
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use crate::db::plan_models::*;
+    use crate::db::plan_repos::PlanTemplateRepo;
+    use crate::db::platform_models::PlanItem;
+    use crate::db::platform_repos::DailyPlanRepo;
+    use crate::error::AppError;
+
+    // ========================================================================
+    // TEST HELPERS
+    // ========================================================================
+
+    async fn create_test_user(pool: &PgPool) -> Uuid {
+        let user_id = Uuid::new_v4();
+        sqlx::query(
+            r#"INSERT INTO users (id, email, name, role, approved, age_verified, tos_accepted)
+               VALUES ($1, $2, 'Test Daily Plan User', 'user', true, true, true)"#,
+        )
+        .bind(user_id)
+        .bind(format!("test-daily-plan-{}@example.com", user_id))
+        .execute(pool)
+        .await
+        .expect("Failed to create test user");
+        user_id
+    }
+
+    async fn create_habit(pool: &PgPool, user_id: Uuid, name: &str, days: Option<&[i32]>) -> Uuid {
+        sqlx::query_scalar(
+            r#"INSERT INTO habits (user_id, name, frequency, target_count, custom_days,
+                                   is_active, current_streak, longest_streak, sort_order)
+               VALUES ($1, $2, CASE WHEN $3::int[] IS NULL THEN 'daily' ELSE 'custom' END,
+                       1, $3, true, 0, 0, 0)
+               RETURNING id"#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(days)
+        .fetch_one(pool)
+        .await
+        .expect("Failed to create habit")
+    }
+
+    /// A Wednesday far enough ahead that focus blocks are never in the past
+    fn plan_date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2030, 3, 13).unwrap()
+    }
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.from_utc_datetime(&plan_date().and_hms_opt(hour, minute, 0).unwrap())
+    }
+
+    fn task(id: &str, completed: bool) -> PlanItem {
+        PlanItem {
+            id: id.to_string(),
+            item_type: "task".to_string(),
+            title: format!("Task {}", id),
+            description: None,
+            duration: None,
+            action_url: "/planner".to_string(),
+            completed,
+            priority: 0,
+            starts_at: None,
+            ends_at: None,
+            carried_over_from: None,
+        }
+    }
+
+    // ========================================================================
+    // TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_generate_schedules_around_calendar(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let date = plan_date();
+        let yesterday = date.pred_opt().unwrap();
+
+        let event: Uuid = sqlx::query_scalar(
+            r#"INSERT INTO calendar_events (user_id, title, event_type, start_time, end_time, all_day)
+               VALUES ($1, 'Band practice', 'event', $2, $3, false)
+               RETURNING id"#,
+        )
+        .bind(user_id)
+        .bind(at(9, 0))
+        .bind(at(10, 0))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let stretch = create_habit(&pool, user_id, "Stretch", None).await;
+        let piano = create_habit(&pool, user_id, "Piano", Some(&[1, 5])).await;
+        let goal: Uuid = sqlx::query_scalar(
+            r#"INSERT INTO goals (user_id, title, status, sort_order, priority, progress, target_date)
+               VALUES ($1, 'Finish the EP', 'active', 0, 0, 60, $2)
+               RETURNING id"#,
+        )
+        .bind(user_id)
+        .bind(date + chrono::Duration::days(3))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        let mut habit_item = task(&format!("plan_habit_{}", stretch), false);
+        habit_item.item_type = "habit".to_string();
+        DailyPlanRepo::save(
+            &pool,
+            user_id,
+            yesterday,
+            &[task("left", false), task("done", true), habit_item],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let plan = DailyPlanRepo::generate(&pool, user_id, date).await.unwrap();
+        let ids: Vec<&str> = plan.items.iter().map(|i| i.id.as_str()).collect();
+
+        // The event, then focus blocks in the free time after it
+        assert_eq!(ids[0], format!("plan_event_{}", event));
+        let focus: Vec<&PlanItem> = plan
+            .items
+            .iter()
+            .filter(|i| i.item_type == "focus")
+            .collect();
+        assert_eq!(focus.len(), FOCUS_BLOCKS);
+        assert_eq!(focus[0].title, "Focus: Finish the EP");
+        assert_eq!(focus[0].starts_at, Some(at(10, 0)));
+        assert_eq!(focus[1].starts_at, Some(at(10, 35)));
+
+        assert!(ids.contains(&format!("plan_goal_{}", goal).as_str()));
+        assert!(ids.contains(&format!("plan_habit_{}", stretch).as_str()));
+        assert!(!ids.contains(&format!("plan_habit_{}", piano).as_str()));
+
+        // Only the unfinished task comes along, once
+        let carried: Vec<&PlanItem> = plan
+            .items
+            .iter()
+            .filter(|i| i.carried_over_from.is_some())
+            .collect();
+        assert_eq!(carried.len(), 1);
+        assert_eq!(carried[0].id, "left");
+        assert_eq!(carried[0].carried_over_from, Some(yesterday));
+
+        // Regenerating keeps what was checked off
+        let stretch_id = format!("plan_habit_{}", stretch);
+        DailyPlanRepo::complete_item(&pool, user_id, date, &stretch_id, true)
+            .await
+            .unwrap();
+        let again = DailyPlanRepo::generate(&pool, user_id, date).await.unwrap();
+        assert_eq!(again.id, plan.id);
+        assert_eq!(again.total_count, plan.total_count);
+        assert!(again
+            .items
+            .iter()
+            .any(|i| i.id == stretch_id && i.completed));
+    }
+
+    #[sqlx::test]
+    async fn test_templates_are_shared_and_count_uses(pool: PgPool) {
+        let owner = create_test_user(&pool).await;
+        let other = create_test_user(&pool).await;
+        let items = vec![PlanTemplateItem {
+            item_type: "focus".to_string(),
+            title: "Deep work".to_string(),
+            description: None,
+            duration: Some(50),
+            action_url: "/focus".to_string(),
+        }];
+        let req = CreatePlanTemplateRequest {
+            name: "Studio day".to_string(),
+            description: None,
+            category: Some("music".to_string()),
+            is_public: true,
+            items: Some(items.clone()),
+            from_date: None,
+        };
+        let template = PlanTemplateRepo::create(&pool, owner, &req, &items)
+            .await
+            .unwrap();
+        assert!(template.is_owner);
+
+        let duplicate = CreatePlanTemplateRequest {
+            name: "studio DAY".to_string(),
+            ..req.clone()
+        };
+        let result = PlanTemplateRepo::create(&pool, owner, &duplicate, &items).await;
+        assert!(matches!(result, Err(AppError::Conflict { .. })));
+
+        let listed = PlanTemplateRepo::list(&pool, other).await.unwrap();
+        let shared = listed.iter().find(|t| t.id == template.id).unwrap();
+        assert!(!shared.is_owner);
+
+        DailyPlanRepo::save(
+            &pool,
+            other,
+            plan_date(),
+            &[task("mine", false)],
+            Some("notes"),
+        )
+        .await
+        .unwrap();
+        let apply = ApplyPlanTemplateRequest {
+            date: Some(plan_date()),
+            replace: false,
+        };
+        let plan = PlanTemplateRepo::apply(&pool, other, template.id, &apply)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = plan.items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec!["mine".to_string(), format!("tpl_{}_1", template.id)]
+        );
+        assert_eq!(plan.notes.as_deref(), Some("notes"));
+
+        // Applying again adds nothing but still counts
+        let plan = PlanTemplateRepo::apply(&pool, other, template.id, &apply)
+            .await
+            .unwrap();
+        assert_eq!(plan.total_count, 2);
+        let listed = PlanTemplateRepo::list(&pool, owner).await.unwrap();
+        assert_eq!(listed[0].use_count, 2);
+
+        // Only the owner may change it
+        let result = PlanTemplateRepo::delete(&pool, other, template.id).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+        PlanTemplateRepo::delete(&pool, owner, template.id)
+            .await
+            .unwrap();
+    }
+}
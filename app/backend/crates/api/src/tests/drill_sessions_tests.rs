@@ -0,0 +1,91 @@
+//! Drill session tests
+//!
+//! Session tokens are handed out raw and stored hashed.
+
+#[cfg(test)]
+mod tests {
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use crate::db::learn_repos::DrillSessionRepo;
+    use crate::db::repos::hash_session_token;
+    use crate::error::AppError;
+
+    // ========================================================================
+    // TEST HELPERS
+    // ========================================================================
+
+    async fn create_test_user(pool: &PgPool) -> Uuid {
+        let user_id = Uuid::new_v4();
+        let email = format!("test-drill-sessions-{}@example.com", user_id);
+
+        sqlx::query(
+            r#"INSERT INTO users (id, email, name, role, approved, age_verified, tos_accepted)
+               VALUES ($1, $2, 'Test Drill Sessions User', 'user', true, true, true)"#,
+        )
+        .bind(user_id)
+        .bind(&email)
+        .execute(pool)
+        .await
+        .expect("Failed to create test user");
+
+        user_id
+    }
+
+    /// An interval recognition drill with the default config
+    async fn create_interval_drill(pool: &PgPool) -> Uuid {
+        let topic_id: Uuid = sqlx::query_scalar(
+            r#"INSERT INTO learn_topics (key, name, category, sort_order, is_active)
+               VALUES ('ear-training', 'Ear Training', 'theory', 0, true)
+               RETURNING id"#,
+        )
+        .fetch_one(pool)
+        .await
+        .expect("Failed to create topic");
+
+        sqlx::query_scalar(
+            r#"INSERT INTO learn_drills (topic_id, key, title, drill_type, config_json,
+                                         difficulty, xp_reward, sort_order, is_active)
+               VALUES ($1, 'intervals', 'Intervals', 'interval', '{}', 'beginner', 10, 0, true)
+               RETURNING id"#,
+        )
+        .bind(topic_id)
+        .fetch_one(pool)
+        .await
+        .expect("Failed to create drill")
+    }
+
+    // ========================================================================
+    // TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_session_tokens_are_stored_hashed(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let drill_id = create_interval_drill(&pool).await;
+
+        let (session, questions) = DrillSessionRepo::start(&pool, user_id, drill_id)
+            .await
+            .unwrap();
+        assert!(!questions.is_empty());
+
+        let stored: String = sqlx::query_scalar("SELECT token FROM drill_sessions WHERE id = $1")
+            .bind(session.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_ne!(stored, session.token);
+        assert_eq!(stored, hash_session_token(&session.token));
+
+        let (loaded, _) = DrillSessionRepo::load(&pool, user_id, &session.token)
+            .await
+            .unwrap();
+        assert_eq!(loaded.id, session.id);
+
+        // The stored hash is not itself a usable token
+        assert!(matches!(
+            DrillSessionRepo::load(&pool, user_id, &stored).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,151 @@
+//! Feature flag tests
+//!
+//! Flags are loaded into memory, evaluated per user and gate routes with a 404.
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        middleware::from_fn,
+        routing::get,
+        Extension, Router,
+    };
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use crate::db::feature_flag_models::*;
+    use crate::db::feature_flag_repos::FeatureFlagRepo;
+    use crate::middleware::flags::require_flag;
+    use crate::services::feature_flags::{FeatureFlags, FlagSubject};
+
+    // ========================================================================
+    // TEST HELPERS
+    // ========================================================================
+
+    fn create_request(flag_name: &str) -> CreateFeatureFlagRequest {
+        CreateFeatureFlagRequest {
+            flag_name: flag_name.to_string(),
+            enabled: true,
+            description: None,
+            metadata: None,
+            rollout_percentage: Some(0),
+            target_user_ids: vec![],
+            target_roles: vec![],
+            target_entitlements: vec![],
+        }
+    }
+
+    fn subject(user_id: Uuid, role: &str) -> FlagSubject<'_> {
+        FlagSubject {
+            user_id,
+            role,
+            entitlements: &[],
+        }
+    }
+
+    async fn gated_status(flags: &FeatureFlags, flag: &'static str) -> StatusCode {
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .route_layer(from_fn(require_flag(flag)))
+            .layer(Extension(flags.clone()));
+        app.oneshot(Request::get("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    // ========================================================================
+    // TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_seeded_rollout_moved_out_of_metadata(pool: PgPool) {
+        let social = FeatureFlagRepo::get(&pool, "social_features")
+            .await
+            .unwrap()
+            .expect("seeded flag");
+
+        assert_eq!(social.rollout_percentage, 0);
+        assert!(social.metadata.is_none());
+    }
+
+    #[sqlx::test]
+    async fn test_targeting_applies_after_refresh(pool: PgPool) {
+        let flags = FeatureFlags::new();
+        let tester = Uuid::new_v4();
+        FeatureFlagRepo::create(&pool, &create_request("new_editor"))
+            .await
+            .unwrap()
+            .expect("created");
+        assert!(
+            FeatureFlagRepo::create(&pool, &create_request("new_editor"))
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        flags.refresh(&pool).await.unwrap();
+        assert!(!flags.is_enabled("new_editor", Some(subject(tester, "user"))));
+
+        let update = UpdateFeatureFlagRequest {
+            enabled: None,
+            description: None,
+            metadata: None,
+            rollout_percentage: None,
+            target_user_ids: Some(vec![tester]),
+            target_roles: Some(vec!["admin".to_string()]),
+            target_entitlements: None,
+        };
+        FeatureFlagRepo::update(&pool, "new_editor", &update)
+            .await
+            .unwrap()
+            .expect("updated");
+        // Not visible until the cache is refreshed
+        assert!(!flags.is_enabled("new_editor", Some(subject(tester, "user"))));
+
+        flags.refresh(&pool).await.unwrap();
+        assert!(flags.is_enabled("new_editor", Some(subject(tester, "user"))));
+        assert!(flags.is_enabled("new_editor", Some(subject(Uuid::new_v4(), "admin"))));
+        assert!(!flags.is_enabled("new_editor", Some(subject(Uuid::new_v4(), "user"))));
+
+        let evaluated = flags.evaluate_all(None);
+        assert_eq!(evaluated.get("new_editor"), Some(&false));
+        assert_eq!(evaluated.get(FLAG_MUSIC_ANALYSIS), Some(&true));
+    }
+
+    #[sqlx::test]
+    async fn test_gate_hides_route_when_flag_off(pool: PgPool) {
+        let flags = FeatureFlags::new();
+        flags.refresh(&pool).await.unwrap();
+        assert_eq!(
+            gated_status(&flags, FLAG_MUSIC_ANALYSIS).await,
+            StatusCode::OK
+        );
+
+        let off = UpdateFeatureFlagRequest {
+            enabled: Some(false),
+            description: None,
+            metadata: None,
+            rollout_percentage: None,
+            target_user_ids: None,
+            target_roles: None,
+            target_entitlements: None,
+        };
+        FeatureFlagRepo::update(&pool, FLAG_MUSIC_ANALYSIS, &off)
+            .await
+            .unwrap();
+        flags.refresh(&pool).await.unwrap();
+        assert_eq!(
+            gated_status(&flags, FLAG_MUSIC_ANALYSIS).await,
+            StatusCode::NOT_FOUND
+        );
+
+        // Unknown flags are off
+        assert_eq!(
+            gated_status(&flags, "no_such_flag").await,
+            StatusCode::NOT_FOUND
+        );
+    }
+}
@@ -391,6 +391,7 @@ mod tests {
                 event_id: None,
                 reason: Some("Test activity".to_string()),
                 idempotency_key: None,
+                quantity: None,
             },
         )
         .await
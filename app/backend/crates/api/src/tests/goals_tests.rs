@@ -2,15 +2,25 @@
 //!
 //! Unit tests for goals: create, list, complete, progress.
 //! MIGRATION: Restored during cross-feature extraction (January 2026)
+//!
+//! Progress rolls up from weighted milestones and linked items, completes
+//! the goal at 100% and flags goals behind their target date.
 
 #[cfg(test)]
 mod tests {
+    use chrono::{Duration, Utc};
     use sqlx::PgPool;
     use uuid::Uuid;
 
+    use crate::db::books_models::UpdateBookRequest;
+    use crate::db::books_repos::BookRepo;
     use crate::db::gamification_repos::UserProgressRepo;
-    use crate::db::habits_goals_models::{CreateGoalRequest, CreateMilestoneRequest};
-    use crate::db::habits_goals_repos::GoalsRepo;
+    use crate::db::habits_goals_models::{
+        CreateGoalLinkRequest, CreateGoalRequest, CreateMilestoneRequest, GoalStatus,
+        UpdateGoalRequest, UpdateMilestoneRequest,
+    };
+    use crate::db::habits_goals_repos::{GoalsRepo, HabitsRepo};
+    use crate::error::AppError;
 
     // ========================================================================
     // TEST HELPERS
@@ -21,8 +31,8 @@ mod tests {
         let email = format!("test-goals-{}@example.com", user_id);
 
         sqlx::query(
-            r#"INSERT INTO users (id, email, name, role)
-               VALUES ($1, $2, 'Test Goals User', 'user')"#,
+            r#"INSERT INTO users (id, email, name, role, approved, age_verified, tos_accepted)
+               VALUES ($1, $2, 'Test Goals User', 'user', true, true, true)"#,
         )
         .bind(user_id)
         .bind(&email)
@@ -38,6 +48,79 @@ mod tests {
         user_id
     }
 
+    async fn create_goal(pool: &PgPool, user_id: Uuid, title: &str) -> Uuid {
+        GoalsRepo::create(
+            pool,
+            user_id,
+            &CreateGoalRequest {
+                title: title.to_string(),
+                description: None,
+                category: None,
+                target_date: None,
+                priority: None,
+            },
+        )
+        .await
+        .expect("Failed to create goal")
+        .id
+    }
+
+    async fn add_milestone(pool: &PgPool, user_id: Uuid, goal_id: Uuid, weight: i32) -> Uuid {
+        GoalsRepo::add_milestone(
+            pool,
+            goal_id,
+            user_id,
+            &CreateMilestoneRequest {
+                title: format!("Weighs {}", weight),
+                description: None,
+                weight: Some(weight),
+            },
+        )
+        .await
+        .expect("Failed to add milestone")
+        .id
+    }
+
+    async fn create_habit(pool: &PgPool, user_id: Uuid) -> Uuid {
+        sqlx::query_scalar(
+            r#"INSERT INTO habits (user_id, name, frequency, target_count, is_active,
+                                   current_streak, longest_streak, sort_order)
+               VALUES ($1, 'Practice scales', 'daily', 1, true, 0, 0, 0)
+               RETURNING id"#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .expect("Failed to create habit")
+    }
+
+    async fn complete_habit_on(pool: &PgPool, user_id: Uuid, habit_id: Uuid, days_ago: i64) {
+        sqlx::query(
+            r#"INSERT INTO habit_completions (habit_id, user_id, completed_date)
+               VALUES ($1, $2, $3)"#,
+        )
+        .bind(habit_id)
+        .bind(user_id)
+        .bind(Utc::now().date_naive() - Duration::days(days_ago))
+        .execute(pool)
+        .await
+        .expect("Failed to complete habit");
+    }
+
+    fn link(
+        target_type: &str,
+        target_id: Uuid,
+        weight: i32,
+        target: Option<i32>,
+    ) -> CreateGoalLinkRequest {
+        CreateGoalLinkRequest {
+            target_type: target_type.to_string(),
+            target_id,
+            weight: Some(weight),
+            target,
+        }
+    }
+
     // ========================================================================
     // CREATE TESTS
     // ========================================================================
@@ -170,6 +253,7 @@ mod tests {
             &CreateMilestoneRequest {
                 title: "First Step".to_string(),
                 description: Some("Get started".to_string()),
+                weight: None,
             },
         )
         .await
@@ -204,6 +288,7 @@ mod tests {
             &CreateMilestoneRequest {
                 title: "Complete This".to_string(),
                 description: None,
+                weight: None,
             },
         )
         .await
@@ -218,4 +303,383 @@ mod tests {
         // Verify goal progress was updated
         assert!(result.goal_progress > 0);
     }
+    // ========================================================================
+    // PROGRESS TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_progress_rolls_up_from_milestones_and_links(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let goal_id = create_goal(&pool, user_id, "Play the recital").await;
+        let big = add_milestone(&pool, user_id, goal_id, 3).await;
+        let small = add_milestone(&pool, user_id, goal_id, 1).await;
+
+        // Two of four practice sessions, one before the goal started
+        let habit_id = create_habit(&pool, user_id).await;
+        complete_habit_on(&pool, user_id, habit_id, 0).await;
+        complete_habit_on(&pool, user_id, habit_id, 0).await;
+        complete_habit_on(&pool, user_id, habit_id, 3).await;
+        let book_id: Uuid = sqlx::query_scalar(
+            r#"INSERT INTO books (user_id, title, total_pages, current_page, status)
+               VALUES ($1, 'Piano method', 200, 100, 'reading')
+               RETURNING id"#,
+        )
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        GoalsRepo::add_link(
+            &pool,
+            goal_id,
+            user_id,
+            &link("habit", habit_id, 1, Some(4)),
+        )
+        .await
+        .unwrap();
+        let goal = GoalsRepo::add_link(&pool, goal_id, user_id, &link("book", book_id, 2, None))
+            .await
+            .unwrap();
+        // Weights 3 + 1 undone, 1 at 50%, 2 at 50%
+        assert_eq!(goal.progress, 21);
+        assert!(goal.auto_progress);
+        assert_eq!(goal.links[1].title.as_deref(), Some("Piano method"));
+
+        let result = GoalsRepo::complete_milestone(&pool, big, user_id)
+            .await
+            .unwrap();
+        assert_eq!(result.goal_progress, 64);
+        assert!(!result.goal_completed);
+
+        // Finishing everything completes the goal
+        sqlx::query("UPDATE books SET current_page = 200, status = 'completed' WHERE id = $1")
+            .bind(book_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        complete_habit_on(&pool, user_id, habit_id, 0).await;
+        complete_habit_on(&pool, user_id, habit_id, 0).await;
+        let result = GoalsRepo::complete_milestone(&pool, small, user_id)
+            .await
+            .unwrap();
+        assert_eq!(result.goal_progress, 100);
+        assert!(result.goal_completed);
+        let goal = GoalsRepo::get_by_id(&pool, goal_id, user_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(goal.status, "completed");
+        assert!(goal.completed_at.is_some());
+
+        // More to do opens it again
+        add_milestone(&pool, user_id, goal_id, 1).await;
+        let goal = GoalsRepo::get_by_id(&pool, goal_id, user_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(goal.progress, 87);
+        assert_eq!(goal.status, "active");
+        assert!(goal.completed_at.is_none());
+
+        // A heavier milestone and a removed link
+        let goal = GoalsRepo::update_milestone(
+            &pool,
+            small,
+            user_id,
+            &UpdateMilestoneRequest {
+                title: None,
+                description: None,
+                weight: Some(5),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(goal.progress, 91);
+        let goal = GoalsRepo::remove_link(&pool, goal_id, goal.links[0].id, user_id)
+            .await
+            .unwrap();
+        assert_eq!(goal.links.len(), 1);
+        assert_eq!(goal.progress, 90);
+    }
+
+    #[sqlx::test]
+    async fn test_links_are_validated(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let other = create_test_user(&pool).await;
+        let goal_id = create_goal(&pool, user_id, "Get stronger").await;
+        let habit_id = create_habit(&pool, user_id).await;
+
+        let result =
+            GoalsRepo::add_link(&pool, goal_id, user_id, &link("habit", habit_id, 1, None)).await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+        let result =
+            GoalsRepo::add_link(&pool, goal_id, user_id, &link("movie", habit_id, 1, None)).await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+        let result = GoalsRepo::add_link(
+            &pool,
+            goal_id,
+            user_id,
+            &link("workout_program", habit_id, 1, None),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+
+        // Someone else's habit can't be linked
+        let theirs = create_habit(&pool, other).await;
+        let result =
+            GoalsRepo::add_link(&pool, goal_id, user_id, &link("habit", theirs, 1, Some(5))).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+
+        GoalsRepo::add_link(
+            &pool,
+            goal_id,
+            user_id,
+            &link("habit", habit_id, 1, Some(5)),
+        )
+        .await
+        .unwrap();
+        let result = GoalsRepo::add_link(
+            &pool,
+            goal_id,
+            user_id,
+            &link("habit", habit_id, 2, Some(9)),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Conflict { .. })));
+
+        // Once the habit is gone the link shows as missing and stops counting
+        sqlx::query("DELETE FROM habits WHERE id = $1")
+            .bind(habit_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let goal = GoalsRepo::get_by_id(&pool, goal_id, user_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(goal.links[0].title.is_none());
+        assert!(!goal.auto_progress);
+    }
+
+    async fn stored_progress(pool: &PgPool, goal_id: Uuid) -> (i32, String) {
+        sqlx::query_as("SELECT progress, status FROM goals WHERE id = $1")
+            .bind(goal_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test]
+    async fn test_linked_items_update_goal_progress(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let goal_id = create_goal(&pool, user_id, "Learn Italian").await;
+        let habit_id = create_habit(&pool, user_id).await;
+        let book_id: Uuid = sqlx::query_scalar(
+            r#"INSERT INTO books (user_id, title, total_pages, current_page, status)
+               VALUES ($1, 'Italian grammar', 100, 0, 'reading')
+               RETURNING id"#,
+        )
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        GoalsRepo::add_link(
+            &pool,
+            goal_id,
+            user_id,
+            &link("habit", habit_id, 1, Some(2)),
+        )
+        .await
+        .unwrap();
+        GoalsRepo::add_link(&pool, goal_id, user_id, &link("book", book_id, 1, None))
+            .await
+            .unwrap();
+
+        // Completing the habit saves the goal's progress
+        HabitsRepo::complete_habit(&pool, habit_id, user_id, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            stored_progress(&pool, goal_id).await,
+            (25, "active".to_string())
+        );
+
+        BookRepo::update(
+            &pool,
+            book_id,
+            user_id,
+            &UpdateBookRequest {
+                title: None,
+                author: None,
+                total_pages: None,
+                current_page: Some(100),
+                status: Some("completed".to_string()),
+                rating: None,
+                notes: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            stored_progress(&pool, goal_id).await,
+            (75, "active".to_string())
+        );
+
+        // Reading a goal doesn't write it
+        complete_habit_on(&pool, user_id, habit_id, 1).await;
+        let updated_at: chrono::DateTime<Utc> =
+            sqlx::query_scalar("SELECT updated_at FROM goals WHERE id = $1")
+                .bind(goal_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        let goal = GoalsRepo::get_by_id(&pool, goal_id, user_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(goal.progress, 75);
+        GoalsRepo::list(&pool, user_id, None).await.unwrap();
+        let after: chrono::DateTime<Utc> =
+            sqlx::query_scalar("SELECT updated_at FROM goals WHERE id = $1")
+                .bind(goal_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(after, updated_at);
+
+        // Once the habit is deleted only the finished book counts
+        HabitsRepo::delete(&pool, habit_id, user_id).await.unwrap();
+        assert_eq!(
+            stored_progress(&pool, goal_id).await,
+            (100, "completed".to_string())
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_manual_progress_and_at_risk(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let goal_id = create_goal(&pool, user_id, "Write an album").await;
+        let today = Utc::now().date_naive();
+
+        // Started 30 days ago, due in 10
+        sqlx::query("UPDATE goals SET started_at = NOW() - INTERVAL '30 days' WHERE id = $1")
+            .bind(goal_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let update = UpdateGoalRequest {
+            target_date: Some(Some(today + Duration::days(10))),
+            progress: Some(10),
+            ..Default::default()
+        };
+        let goal = GoalsRepo::update(&pool, goal_id, user_id, &update)
+            .await
+            .unwrap();
+        assert_eq!(goal.progress, 10);
+        assert!(goal.at_risk);
+
+        let update = UpdateGoalRequest {
+            progress: Some(80),
+            ..Default::default()
+        };
+        let goal = GoalsRepo::update(&pool, goal_id, user_id, &update)
+            .await
+            .unwrap();
+        assert!(!goal.at_risk);
+
+        let update = UpdateGoalRequest {
+            progress: Some(100),
+            target_date: Some(None),
+            ..Default::default()
+        };
+        let goal = GoalsRepo::update(&pool, goal_id, user_id, &update)
+            .await
+            .unwrap();
+        assert_eq!(goal.status, "completed");
+        assert!(goal.target_date.is_none());
+
+        // Reopened by hand, it stays open at 100%
+        let update = UpdateGoalRequest {
+            status: Some(GoalStatus::Active),
+            ..Default::default()
+        };
+        let goal = GoalsRepo::update(&pool, goal_id, user_id, &update)
+            .await
+            .unwrap();
+        assert_eq!(goal.status, "active");
+        assert!(goal.completed_at.is_none());
+
+        // Milestones take over progress
+        add_milestone(&pool, user_id, goal_id, 1).await;
+        let update = UpdateGoalRequest {
+            progress: Some(50),
+            ..Default::default()
+        };
+        let result = GoalsRepo::update(&pool, goal_id, user_id, &update).await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    // ========================================================================
+    // MANAGEMENT TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_archive_reorder_and_delete(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let first = create_goal(&pool, user_id, "First").await;
+        let second = create_goal(&pool, user_id, "Second").await;
+        let third = create_goal(&pool, user_id, "Third").await;
+
+        let list = GoalsRepo::reorder(&pool, user_id, &[third, first, second])
+            .await
+            .unwrap();
+        let ids: Vec<Uuid> = list.goals.iter().map(|g| g.id).collect();
+        assert_eq!(ids, vec![third, first, second]);
+
+        GoalsRepo::set_archived(&pool, first, user_id, true)
+            .await
+            .unwrap();
+        let list = GoalsRepo::list(&pool, user_id, None).await.unwrap();
+        assert_eq!(list.total, 2);
+        let archived = GoalsRepo::list_archived(&pool, user_id).await.unwrap();
+        assert_eq!(archived.goals[0].id, first);
+        let goal = GoalsRepo::set_archived(&pool, first, user_id, false)
+            .await
+            .unwrap();
+        assert!(goal.archived_at.is_none());
+
+        let milestone = add_milestone(&pool, user_id, second, 1).await;
+        let habit_id = create_habit(&pool, user_id).await;
+        GoalsRepo::add_link(&pool, second, user_id, &link("habit", habit_id, 1, Some(3)))
+            .await
+            .unwrap();
+        GoalsRepo::delete(&pool, second, user_id).await.unwrap();
+
+        let result = GoalsRepo::delete_milestone(&pool, milestone, user_id).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+        let links: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM goal_links WHERE goal_id = $1")
+            .bind(second)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(links, 0);
+        let result = GoalsRepo::delete(&pool, second, user_id).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+
+        // Links also go when a goal is deleted some other way
+        GoalsRepo::add_link(&pool, third, user_id, &link("habit", habit_id, 1, Some(3)))
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM goals WHERE id = $1")
+            .bind(third)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let links: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM goal_links WHERE goal_id = $1")
+            .bind(third)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(links, 0);
+    }
 }
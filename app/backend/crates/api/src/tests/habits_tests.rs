@@ -1,15 +1,18 @@
 //! Habits tests
 //!
-//! Unit tests for habit tracking: create, complete, streak tracking, XP awards.
+//! Unit tests for habit tracking: create, complete, streak tracking, XP awards,
+//! schedules, undo, history and habit management.
 
 #[cfg(test)]
 mod tests {
+    use chrono::{Datelike, Duration, NaiveDate, Utc};
     use sqlx::PgPool;
     use uuid::Uuid;
 
     use crate::db::gamification_repos::UserProgressRepo;
-    use crate::db::habits_goals_models::CreateHabitRequest;
+    use crate::db::habits_goals_models::*;
     use crate::db::habits_goals_repos::HabitsRepo;
+    use crate::error::AppError;
 
     // ========================================================================
     // TEST HELPERS
@@ -20,8 +23,8 @@ mod tests {
         let email = format!("test-habits-{}@example.com", user_id);
 
         sqlx::query(
-            r#"INSERT INTO users (id, email, name, role)
-               VALUES ($1, $2, 'Test Habits User', 'user')"#,
+            r#"INSERT INTO users (id, email, name, role, approved, age_verified, tos_accepted)
+               VALUES ($1, $2, 'Test Habits User', 'user', true, true, true)"#,
         )
         .bind(user_id)
         .bind(&email)
@@ -37,6 +40,48 @@ mod tests {
         user_id
     }
 
+    async fn create_scheduled_habit(
+        pool: &PgPool,
+        user_id: Uuid,
+        frequency: &str,
+        target_count: i32,
+        custom_days: Option<Vec<i32>>,
+    ) -> Habit {
+        HabitsRepo::create(
+            pool,
+            user_id,
+            &CreateHabitRequest {
+                name: format!("{} x{}", frequency, target_count),
+                description: None,
+                frequency: frequency.to_string(),
+                target_count,
+                custom_days,
+                icon: None,
+                color: None,
+            },
+        )
+        .await
+        .expect("Failed to create habit")
+    }
+
+    /// Record a completion on an earlier day
+    async fn complete_on(pool: &PgPool, habit: &Habit, date: NaiveDate) {
+        sqlx::query(
+            r#"INSERT INTO habit_completions (habit_id, user_id, completed_date)
+               VALUES ($1, $2, $3)"#,
+        )
+        .bind(habit.id)
+        .bind(habit.user_id)
+        .bind(date)
+        .execute(pool)
+        .await
+        .expect("Failed to record completion");
+    }
+
+    fn today() -> NaiveDate {
+        Utc::now().date_naive()
+    }
+
     // ========================================================================
     // CREATE TESTS
     // ========================================================================
@@ -277,4 +322,223 @@ mod tests {
 
         assert!(found.is_none());
     }
+
+    // ========================================================================
+    // SCHEDULE TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_times_per_day_habit_fills_up(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let habit = create_scheduled_habit(&pool, user_id, "daily", 2, None).await;
+
+        let first = HabitsRepo::complete_habit(&pool, habit.id, user_id, None)
+            .await
+            .expect("Failed to complete");
+        assert!(!first.habit.completed_today);
+        assert_eq!(first.habit.completions_today, 1);
+        assert_eq!(first.new_streak, 0);
+        assert_eq!(first.xp_awarded, 5);
+
+        let second = HabitsRepo::complete_habit(&pool, habit.id, user_id, None)
+            .await
+            .expect("Failed to complete");
+        assert!(second.habit.completed_today);
+        assert_eq!(second.new_streak, 1);
+        assert_eq!(second.xp_awarded, 5);
+
+        // The day is full
+        let third = HabitsRepo::complete_habit(&pool, habit.id, user_id, None)
+            .await
+            .expect("Failed to complete");
+        assert_eq!(third.xp_awarded, 0);
+        assert_eq!(third.habit.completions_today, 2);
+    }
+
+    #[sqlx::test]
+    async fn test_weekday_streak_skips_days_off(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let two_days_ago = today() - Duration::days(2);
+        let days = vec![
+            two_days_ago.weekday().num_days_from_sunday() as i32,
+            today().weekday().num_days_from_sunday() as i32,
+        ];
+        let habit = create_scheduled_habit(&pool, user_id, "custom", 1, Some(days)).await;
+        complete_on(&pool, &habit, two_days_ago).await;
+
+        // Yesterday wasn't due, so the streak carries on
+        let result = HabitsRepo::complete_habit(&pool, habit.id, user_id, None)
+            .await
+            .expect("Failed to complete");
+        assert_eq!(result.new_streak, 2);
+        assert_eq!(result.habit.longest_streak, 2);
+        assert!(result.habit.due_today);
+    }
+
+    #[sqlx::test]
+    async fn test_invalid_schedules_are_rejected(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+
+        for (frequency, target_count, custom_days) in [
+            ("custom", 1, None),
+            ("custom", 1, Some(vec![7])),
+            ("weekly", 8, None),
+            ("hourly", 1, None),
+        ] {
+            let result = HabitsRepo::create(
+                &pool,
+                user_id,
+                &CreateHabitRequest {
+                    name: "Bad".to_string(),
+                    description: None,
+                    frequency: frequency.to_string(),
+                    target_count,
+                    custom_days,
+                    icon: None,
+                    color: None,
+                },
+            )
+            .await;
+            assert!(
+                matches!(result, Err(AppError::Validation(_))),
+                "{}",
+                frequency
+            );
+        }
+    }
+
+    // ========================================================================
+    // UNDO TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_undo_completion_recomputes_streak(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let habit = create_scheduled_habit(&pool, user_id, "daily", 1, None).await;
+        complete_on(&pool, &habit, today() - Duration::days(1)).await;
+
+        let result = HabitsRepo::complete_habit(&pool, habit.id, user_id, None)
+            .await
+            .expect("Failed to complete");
+        assert_eq!(result.new_streak, 2);
+
+        let undone = HabitsRepo::uncomplete_habit(&pool, habit.id, user_id, today())
+            .await
+            .expect("Failed to undo");
+        assert_eq!(undone.current_streak, 1);
+        assert_eq!(undone.longest_streak, 1);
+        assert_eq!(undone.completions_today, 0);
+        assert!(!undone.completed_today);
+
+        let again = HabitsRepo::uncomplete_habit(&pool, habit.id, user_id, today()).await;
+        assert!(matches!(again, Err(AppError::NotFound(_))));
+
+        // Completing again restores the streak without paying out twice
+        let redone = HabitsRepo::complete_habit(&pool, habit.id, user_id, None)
+            .await
+            .expect("Failed to complete");
+        assert_eq!(redone.new_streak, 2);
+        assert_eq!(redone.xp_awarded, 0);
+    }
+
+    // ========================================================================
+    // MANAGEMENT TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_update_archive_reorder_and_delete(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let a = create_scheduled_habit(&pool, user_id, "daily", 1, None).await;
+        let b = create_scheduled_habit(&pool, user_id, "daily", 2, None).await;
+        let c = create_scheduled_habit(&pool, user_id, "weekly", 3, None).await;
+
+        let list = HabitsRepo::reorder(&pool, user_id, &[c.id, a.id, b.id])
+            .await
+            .expect("Failed to reorder");
+        let ids: Vec<Uuid> = list.habits.iter().map(|h| h.id).collect();
+        assert_eq!(ids, vec![c.id, a.id, b.id]);
+
+        let bad = UpdateHabitRequest {
+            name: None,
+            description: None,
+            frequency: Some("custom".to_string()),
+            target_count: None,
+            custom_days: None,
+            icon: None,
+            color: None,
+        };
+        let result = HabitsRepo::update(&pool, b.id, user_id, &bad).await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+
+        let updated = HabitsRepo::update(
+            &pool,
+            b.id,
+            user_id,
+            &UpdateHabitRequest {
+                name: Some("Stretch".to_string()),
+                custom_days: Some(vec![5, 1, 1]),
+                ..bad
+            },
+        )
+        .await
+        .expect("Failed to update");
+        assert_eq!(updated.name, "Stretch");
+        assert_eq!(updated.target_count, 2);
+        assert_eq!(updated.custom_days, Some(vec![1, 5]));
+
+        HabitsRepo::set_active(&pool, a.id, user_id, false)
+            .await
+            .expect("Failed to archive");
+        let active = HabitsRepo::list_active(&pool, user_id).await.unwrap();
+        assert!(active.habits.iter().all(|h| h.id != a.id));
+        let archived = HabitsRepo::list_archived(&pool, user_id).await.unwrap();
+        assert_eq!(archived.habits.len(), 1);
+        assert_eq!(archived.habits[0].id, a.id);
+
+        complete_on(&pool, &c, today()).await;
+        HabitsRepo::delete(&pool, c.id, user_id)
+            .await
+            .expect("Failed to delete");
+        assert!(HabitsRepo::get_by_id(&pool, c.id, user_id)
+            .await
+            .unwrap()
+            .is_none());
+        let result = HabitsRepo::delete(&pool, c.id, user_id).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    // ========================================================================
+    // HISTORY TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_history_counts_each_day(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let habit = create_scheduled_habit(&pool, user_id, "daily", 2, None).await;
+        let three_days_ago = today() - Duration::days(3);
+        complete_on(&pool, &habit, three_days_ago).await;
+        complete_on(&pool, &habit, three_days_ago).await;
+        complete_on(&pool, &habit, today()).await;
+
+        let history = HabitsRepo::history(
+            &pool,
+            habit.id,
+            user_id,
+            today() - Duration::days(6),
+            today(),
+        )
+        .await
+        .expect("Failed to get history");
+
+        assert_eq!(history.days.len(), 7);
+        assert_eq!(history.total_completions, 3);
+        let day = history
+            .days
+            .iter()
+            .find(|d| d.date == three_days_ago)
+            .unwrap();
+        assert_eq!(day.count, 2);
+        assert!(day.done);
+        assert!(!history.days.last().unwrap().done);
+    }
 }
@@ -0,0 +1,221 @@
+//! Idea music tests
+//!
+//! Key, BPM and the other music fields are stored as columns and filterable;
+//! attached audio can be promoted to a reference track once.
+
+#[cfg(test)]
+mod tests {
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use crate::db::idea_attachment_models::*;
+    use crate::db::idea_attachment_repos::IdeaAttachmentRepo;
+    use crate::db::platform_models::*;
+    use crate::db::platform_repos::IdeasRepo;
+    use crate::error::AppError;
+
+    // ========================================================================
+    // TEST HELPERS
+    // ========================================================================
+
+    async fn create_test_user(pool: &PgPool) -> Uuid {
+        let user_id = Uuid::new_v4();
+        sqlx::query(
+            r#"INSERT INTO users (id, email, name, role, approved, age_verified, tos_accepted)
+               VALUES ($1, $2, 'Test Idea Music User', 'user', true, true, true)"#,
+        )
+        .bind(user_id)
+        .bind(format!("test-idea-music-{}@example.com", user_id))
+        .execute(pool)
+        .await
+        .expect("Failed to create test user");
+        user_id
+    }
+
+    async fn create_idea(pool: &PgPool, user_id: Uuid, title: &str, key: &str, bpm: i32) -> Uuid {
+        IdeasRepo::create(
+            pool,
+            user_id,
+            &CreateIdeaRequest {
+                title: title.to_string(),
+                content: Some("hook".to_string()),
+                category: "general".to_string(),
+                tags: None,
+                music: IdeaMusic {
+                    key: Some(key.to_string()),
+                    bpm: Some(bpm),
+                    genre: Some("house".to_string()),
+                    ..Default::default()
+                },
+            },
+        )
+        .await
+        .expect("Failed to create idea")
+        .id
+    }
+
+    fn memo(r2_key: &str) -> NewAttachment<'_> {
+        NewAttachment {
+            kind: AttachmentKind::VoiceMemo,
+            r2_key,
+            filename: "memo.m4a",
+            mime_type: "audio/m4a",
+            size_bytes: 1024,
+            duration_seconds: Some(12.5),
+        }
+    }
+
+    // ========================================================================
+    // TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_music_fields_are_stored_not_folded(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let id = create_idea(&pool, user_id, "Night drive", "A#m", 124).await;
+
+        let idea = IdeasRepo::get(&pool, id, user_id).await.unwrap();
+        assert_eq!(idea.content.as_deref(), Some("hook"));
+        assert_eq!(idea.music.key.as_deref(), Some("Bb minor"));
+        assert_eq!(idea.music.bpm, Some(124));
+
+        let update = UpdateIdeaRequest {
+            title: None,
+            content: None,
+            category: None,
+            tags: None,
+            is_pinned: None,
+            music: IdeaMusic {
+                time_signature: Some("6/8".to_string()),
+                ..Default::default()
+            },
+        };
+        let updated = IdeasRepo::update(&pool, id, user_id, &update, None)
+            .await
+            .unwrap();
+        assert_eq!(updated.music.key.as_deref(), Some("Bb minor"));
+        assert_eq!(updated.music.time_signature.as_deref(), Some("6/8"));
+    }
+
+    #[sqlx::test]
+    async fn test_filter_by_key_and_bpm_range(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        create_idea(&pool, user_id, "In range", "Am", 122).await;
+        create_idea(&pool, user_id, "Too fast", "A minor", 140).await;
+        create_idea(&pool, user_id, "Wrong key", "C", 124).await;
+
+        let filter = IdeaFilter {
+            key: Some("a min".to_string()),
+            bpm_min: Some(120),
+            bpm_max: Some(128),
+            ..Default::default()
+        };
+        let ideas = IdeasRepo::list(&pool, user_id, &filter)
+            .await
+            .unwrap()
+            .ideas;
+        assert_eq!(
+            ideas.iter().map(|i| i.title.as_str()).collect::<Vec<_>>(),
+            vec!["In range"]
+        );
+
+        let genre = IdeaFilter {
+            genre: Some("HOUSE".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            IdeasRepo::list(&pool, user_id, &genre)
+                .await
+                .unwrap()
+                .ideas
+                .len(),
+            3
+        );
+
+        let bad = IdeaFilter {
+            key: Some("Q".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            IdeasRepo::list(&pool, user_id, &bad).await,
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    #[sqlx::test]
+    async fn test_attachments_belong_to_own_ideas(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let other_id = create_test_user(&pool).await;
+        let id = create_idea(&pool, user_id, "Memo", "C", 90).await;
+
+        let key = format!("{}/audio/{}.m4a", other_id, Uuid::new_v4());
+        assert!(matches!(
+            IdeaAttachmentRepo::create(&pool, other_id, id, &memo(&key)).await,
+            Err(AppError::NotFound(_))
+        ));
+
+        let key = format!("{}/audio/{}.m4a", user_id, Uuid::new_v4());
+        let attachment = IdeaAttachmentRepo::create(&pool, user_id, id, &memo(&key))
+            .await
+            .unwrap();
+        assert_eq!(attachment.kind, "voice_memo");
+        assert_eq!(attachment.duration_seconds, Some(12.5));
+        assert_eq!(
+            IdeaAttachmentRepo::list(&pool, user_id, id)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        IdeasRepo::delete(&pool, id, user_id).await.unwrap();
+        assert!(IdeaAttachmentRepo::list(&pool, user_id, id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[sqlx::test]
+    async fn test_promote_to_reference_track_once(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let id = create_idea(&pool, user_id, "Loop idea", "F#m", 128).await;
+        let key = format!("{}/audio/{}.m4a", user_id, Uuid::new_v4());
+        let attachment = IdeaAttachmentRepo::create(&pool, user_id, id, &memo(&key))
+            .await
+            .unwrap();
+
+        let track_key = format!("{}/audio/{}.m4a", user_id, Uuid::new_v4());
+        let track = IdeaAttachmentRepo::promote(
+            &pool,
+            user_id,
+            id,
+            attachment.id,
+            &track_key,
+            &PromoteAttachmentRequest::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(track.title, "Loop idea");
+        assert_eq!(track.r2_key, track_key);
+        assert_eq!(track.key.as_deref(), Some("F# minor"));
+        assert_eq!(track.bpm, Some(128.0));
+        assert_eq!(track.duration_seconds, Some(12.5));
+        assert_eq!(track.source.as_deref(), Some("idea"));
+
+        let linked = IdeaAttachmentRepo::get(&pool, user_id, id, attachment.id)
+            .await
+            .unwrap();
+        assert_eq!(linked.reference_track_id, Some(track.id));
+
+        let again = IdeaAttachmentRepo::promote(
+            &pool,
+            user_id,
+            id,
+            attachment.id,
+            &track_key,
+            &PromoteAttachmentRequest::default(),
+        )
+        .await;
+        assert!(matches!(again, Err(AppError::Conflict { .. })));
+    }
+}
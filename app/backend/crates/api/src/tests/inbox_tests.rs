@@ -0,0 +1,248 @@
+//! Inbox triage tests
+//!
+//! Converting an item creates the target and marks the item processed in one
+//! transaction; bulk triage and expiry only touch the intended items.
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use crate::db::habits_goals_models::CreateGoalRequest;
+    use crate::db::habits_goals_repos::GoalsRepo;
+    use crate::db::inbox_models::*;
+    use crate::db::inbox_repos::InboxRepo;
+    use crate::db::platform_repos::IdeasRepo;
+    use crate::error::AppError;
+
+    // ========================================================================
+    // TEST HELPERS
+    // ========================================================================
+
+    async fn create_test_user(pool: &PgPool) -> Uuid {
+        let user_id = Uuid::new_v4();
+        sqlx::query(
+            r#"INSERT INTO users (id, email, name, role, approved, age_verified, tos_accepted)
+               VALUES ($1, $2, 'Test Inbox User', 'user', true, true, true)"#,
+        )
+        .bind(user_id)
+        .bind(format!("test-inbox-{}@example.com", user_id))
+        .execute(pool)
+        .await
+        .expect("Failed to create test user");
+        user_id
+    }
+
+    async fn capture(pool: &PgPool, user_id: Uuid, title: &str) -> InboxItem {
+        InboxRepo::create(
+            pool,
+            user_id,
+            &CreateInboxRequest {
+                item_type: "note".to_string(),
+                title: title.to_string(),
+                body: Some(format!("{} notes", title)),
+                action_url: None,
+                action_data: None,
+                priority: None,
+                expires_at: None,
+            },
+        )
+        .await
+        .expect("Failed to capture inbox item")
+    }
+
+    fn convert_request(target: ConvertTarget) -> ConvertInboxRequest {
+        ConvertInboxRequest {
+            title: None,
+            target,
+        }
+    }
+
+    // ========================================================================
+    // TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_convert_to_quest_links_back(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let item = capture(&pool, user_id, "Finish the mixdown").await;
+
+        let converted = InboxRepo::convert(
+            &pool,
+            user_id,
+            item.id,
+            &convert_request(ConvertTarget::Quest {
+                category: None,
+                difficulty: None,
+            }),
+        )
+        .await
+        .unwrap();
+
+        assert!(converted.item.is_processed);
+        assert!(converted.item.processed_at.is_some());
+        assert_eq!(converted.conversion.target_type, "quest");
+        assert_eq!(converted.target["title"], "Finish the mixdown");
+        assert_eq!(converted.target["description"], "Finish the mixdown notes");
+
+        let source = InboxRepo::source_for(&pool, user_id, "quest", converted.conversion.target_id)
+            .await
+            .unwrap();
+        assert_eq!(source.id, item.id);
+
+        let err = InboxRepo::convert(
+            &pool,
+            user_id,
+            item.id,
+            &convert_request(ConvertTarget::Idea { category: None }),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, AppError::Conflict { .. }));
+    }
+
+    #[sqlx::test]
+    async fn test_convert_to_idea_uses_given_title(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let item = capture(&pool, user_id, "hook idea").await;
+
+        let converted = InboxRepo::convert(
+            &pool,
+            user_id,
+            item.id,
+            &ConvertInboxRequest {
+                title: Some("Falsetto hook".to_string()),
+                target: ConvertTarget::Idea { category: None },
+            },
+        )
+        .await
+        .unwrap();
+
+        let idea = IdeasRepo::get(&pool, converted.conversion.target_id, user_id)
+            .await
+            .unwrap();
+        assert_eq!(idea.title, "Falsetto hook");
+        assert_eq!(idea.content.as_deref(), Some("hook idea notes"));
+    }
+
+    #[sqlx::test]
+    async fn test_failed_convert_leaves_item_unprocessed(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let other_id = create_test_user(&pool).await;
+        let goal = GoalsRepo::create(
+            &pool,
+            other_id,
+            &CreateGoalRequest {
+                title: "Not yours".to_string(),
+                description: None,
+                category: None,
+                target_date: None,
+                priority: None,
+            },
+        )
+        .await
+        .unwrap();
+        let item = capture(&pool, user_id, "Step one").await;
+
+        let result = InboxRepo::convert(
+            &pool,
+            user_id,
+            item.id,
+            &convert_request(ConvertTarget::GoalMilestone { goal_id: goal.id }),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+
+        let stored = InboxRepo::get(&pool, user_id, item.id).await.unwrap();
+        assert!(!stored.is_processed);
+
+        // Converted items are validated like ones created directly
+        let blank = capture(&pool, user_id, "  ").await;
+        let result = InboxRepo::convert(
+            &pool,
+            user_id,
+            blank.id,
+            &convert_request(ConvertTarget::Habit { frequency: None }),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    #[sqlx::test]
+    async fn test_triage_only_touches_own_items(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let other_id = create_test_user(&pool).await;
+        let a = capture(&pool, user_id, "a").await;
+        let b = capture(&pool, user_id, "b").await;
+        let theirs = capture(&pool, other_id, "theirs").await;
+
+        let archive = TriageInboxRequest {
+            ids: vec![a.id, b.id, theirs.id],
+            action: TriageAction::Archive,
+        };
+        assert_eq!(
+            InboxRepo::triage(&pool, user_id, &archive).await.unwrap(),
+            2
+        );
+        // Already archived items are not counted again
+        assert_eq!(
+            InboxRepo::triage(&pool, user_id, &archive).await.unwrap(),
+            0
+        );
+        assert!(
+            !InboxRepo::get(&pool, other_id, theirs.id)
+                .await
+                .unwrap()
+                .is_archived
+        );
+
+        let delete = TriageInboxRequest {
+            ids: vec![a.id],
+            action: TriageAction::Delete,
+        };
+        assert_eq!(InboxRepo::triage(&pool, user_id, &delete).await.unwrap(), 1);
+        assert!(InboxRepo::get(&pool, user_id, a.id).await.is_err());
+    }
+
+    #[sqlx::test]
+    async fn test_expiry_archives_unprocessed_items(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let expired = capture(&pool, user_id, "stale").await;
+        let handled = capture(&pool, user_id, "handled").await;
+        let fresh = capture(&pool, user_id, "fresh").await;
+        for (item, offset) in [(&expired, -1), (&handled, -1), (&fresh, 1)] {
+            sqlx::query("UPDATE inbox_items SET expires_at = $1 WHERE id = $2")
+                .bind(Utc::now() + Duration::hours(offset))
+                .bind(item.id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        let process = TriageInboxRequest {
+            ids: vec![handled.id],
+            action: TriageAction::Process,
+        };
+        InboxRepo::triage(&pool, user_id, &process).await.unwrap();
+
+        assert_eq!(InboxRepo::expire(&pool).await.unwrap(), 1);
+        assert!(
+            InboxRepo::get(&pool, user_id, expired.id)
+                .await
+                .unwrap()
+                .is_archived
+        );
+        assert!(
+            !InboxRepo::get(&pool, user_id, handled.id)
+                .await
+                .unwrap()
+                .is_archived
+        );
+        assert!(
+            !InboxRepo::get(&pool, user_id, fresh.id)
+                .await
+                .unwrap()
+                .is_archived
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! Infobase link tests
+//!
+//! Wiki links are recorded on save, resolve by title, follow renames and
+//! report broken targets.
+
+#[cfg(test)]
+mod tests {
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use crate::db::infobase_link_repos::InfobaseLinkRepo;
+    use crate::db::platform_models::{
+        CreateInfobaseEntryRequest, InfobaseEntryResponse, UpdateInfobaseEntryRequest,
+    };
+    use crate::db::platform_repos::InfobaseRepo;
+    use crate::db::revision_models::RevisionEntity;
+    use crate::db::revision_repos::RevisionRepo;
+
+    // ========================================================================
+    // TEST HELPERS
+    // ========================================================================
+
+    async fn create_test_user(pool: &PgPool) -> Uuid {
+        let user_id = Uuid::new_v4();
+        sqlx::query(
+            r#"INSERT INTO users (id, email, name, role, approved, age_verified, tos_accepted)
+               VALUES ($1, $2, 'Test Infobase User', 'user', true, true, true)"#,
+        )
+        .bind(user_id)
+        .bind(format!("test-infobase-{}@example.com", user_id))
+        .execute(pool)
+        .await
+        .expect("Failed to create test user");
+        user_id
+    }
+
+    async fn create_entry(
+        pool: &PgPool,
+        user_id: Uuid,
+        title: &str,
+        content: &str,
+    ) -> InfobaseEntryResponse {
+        InfobaseRepo::create(
+            pool,
+            user_id,
+            &CreateInfobaseEntryRequest {
+                title: title.to_string(),
+                content: content.to_string(),
+                category: "Tips".to_string(),
+                tags: Some(vec!["mixing".to_string()]),
+            },
+        )
+        .await
+        .expect("Failed to create entry")
+    }
+
+    async fn broken_raw(pool: &PgPool, user_id: Uuid) -> Vec<String> {
+        let mut raw: Vec<String> = InfobaseLinkRepo::broken(pool, user_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|l| l.raw)
+            .collect();
+        raw.sort();
+        raw
+    }
+
+    // ========================================================================
+    // TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_links_resolve_and_report_broken(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let missing_idea = Uuid::new_v4();
+
+        let basics = create_entry(&pool, user_id, "Mixing Basics", "Start here.").await;
+        let notes = create_entry(
+            &pool,
+            user_id,
+            "Session notes",
+            &format!(
+                "Read [[mixing basics]] and [[Gain Staging]] first. See [[idea:{}]].",
+                missing_idea
+            ),
+        )
+        .await;
+        assert_eq!(notes.tags, vec!["mixing".to_string()]);
+
+        let backlinks = InfobaseLinkRepo::backlinks(&pool, user_id, basics.id)
+            .await
+            .unwrap();
+        assert_eq!(backlinks.len(), 1);
+        assert_eq!(backlinks[0].id, notes.id);
+
+        assert_eq!(
+            broken_raw(&pool, user_id).await,
+            vec!["Gain Staging".to_string(), format!("idea:{}", missing_idea)]
+        );
+
+        // Creating the missing entry resolves the dangling link
+        let gain = create_entry(&pool, user_id, "gain staging", "Leave headroom.").await;
+        assert_eq!(
+            broken_raw(&pool, user_id).await,
+            vec![format!("idea:{}", missing_idea)]
+        );
+
+        let graph = InfobaseLinkRepo::graph(&pool, user_id).await.unwrap();
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.edges.len(), 2);
+        assert!(graph
+            .edges
+            .iter()
+            .any(|e| e.source == notes.id && e.target == gain.id));
+    }
+
+    #[sqlx::test]
+    async fn test_rename_rewrites_inbound_links(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let basics = create_entry(&pool, user_id, "Mixing Basics", "See [[Mixing Basics]].").await;
+        let notes = create_entry(&pool, user_id, "Notes", "Read [[mixing basics]] first.").await;
+
+        let renamed = InfobaseRepo::update(
+            &pool,
+            basics.id,
+            user_id,
+            &UpdateInfobaseEntryRequest {
+                title: Some("Mixing 101".to_string()),
+                content: None,
+                category: None,
+                tags: None,
+            },
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(renamed.content, "See [[Mixing 101]].");
+
+        let notes = InfobaseRepo::get(&pool, notes.id, user_id).await.unwrap();
+        assert_eq!(notes.content, "Read [[Mixing 101]] first.");
+        assert_eq!(notes.version, 2);
+        let revisions = RevisionRepo::list(&pool, user_id, RevisionEntity::Infobase, notes.id)
+            .await
+            .unwrap();
+        assert_eq!(revisions[0].version, 2);
+
+        let backlinks = InfobaseLinkRepo::backlinks(&pool, user_id, basics.id)
+            .await
+            .unwrap();
+        let mut sources: Vec<Uuid> = backlinks.iter().map(|b| b.id).collect();
+        sources.sort();
+        let mut expected = vec![basics.id, notes.id];
+        expected.sort();
+        assert_eq!(sources, expected);
+        assert!(broken_raw(&pool, user_id).await.is_empty());
+
+        // Deleting the target breaks the links to it
+        InfobaseRepo::delete(&pool, basics.id, user_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            broken_raw(&pool, user_id).await,
+            vec!["Mixing 101".to_string()]
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_rename_onto_a_title_the_source_already_links(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let old = create_entry(&pool, user_id, "Old", "Old notes.").await;
+        let notes = create_entry(&pool, user_id, "Notes", "See [[Old]] and [[New]].").await;
+
+        InfobaseRepo::update(
+            &pool,
+            old.id,
+            user_id,
+            &UpdateInfobaseEntryRequest {
+                title: Some("New".to_string()),
+                content: None,
+                category: None,
+                tags: None,
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+        let notes = InfobaseRepo::get(&pool, notes.id, user_id).await.unwrap();
+        assert_eq!(notes.content, "See [[New]] and [[New]].");
+        let backlinks = InfobaseLinkRepo::backlinks(&pool, user_id, old.id)
+            .await
+            .unwrap();
+        assert_eq!(backlinks.len(), 1);
+        assert_eq!(backlinks[0].id, notes.id);
+        assert!(broken_raw(&pool, user_id).await.is_empty());
+    }
+}
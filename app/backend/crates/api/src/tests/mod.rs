@@ -1,8 +1,23 @@
 //! Test modules
 
+#[cfg(test)]
+mod account_archive_tests;
+
+#[cfg(test)]
+mod account_deletion_tests;
+
 #[cfg(test)]
 mod auth_tests;
 
+#[cfg(test)]
+mod daily_plan_tests;
+
+#[cfg(test)]
+mod drill_sessions_tests;
+
+#[cfg(test)]
+mod feature_flags_tests;
+
 #[cfg(test)]
 mod focus_tests;
 
@@ -18,17 +33,56 @@ mod goals_tests;
 #[cfg(test)]
 mod habits_tests;
 
+#[cfg(test)]
+mod idea_music_tests;
+
+#[cfg(test)]
+mod inbox_tests;
+
+#[cfg(test)]
+mod infobase_links_tests;
+
+#[cfg(test)]
+mod notifications_tests;
+
+#[cfg(test)]
+mod onboarding_tests;
+
+#[cfg(test)]
+mod personalization_tests;
+
+#[cfg(test)]
+mod query_catalog_tests;
+
 #[cfg(test)]
 mod quests_tests;
 
+#[cfg(test)]
+mod rate_limit_tests;
+
 #[cfg(test)]
 mod reference_tests;
 
 #[cfg(test)]
 mod reference_golden_tests;
 
+#[cfg(test)]
+mod revisions_tests;
+
+#[cfg(test)]
+mod search_tests;
+
+#[cfg(test)]
+mod sql_console_tests;
+
 #[cfg(test)]
 mod storage_tests;
 
+#[cfg(test)]
+mod sync_tests;
+
 #[cfg(test)]
 mod template_tests;
+
+#[cfg(test)]
+mod universal_quests_tests;
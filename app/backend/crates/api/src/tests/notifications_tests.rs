@@ -0,0 +1,554 @@
+//! Notification scheduler tests
+//!
+//! Reminder materialization, quiet hours, retries and delivery over the
+//! inbox, SMTP (local sink) and Web Push (local push service) channels.
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::{Datelike, Timelike, Utc};
+    use ring::agreement;
+    use ring::rand::SystemRandom;
+    use ring::signature::{EcdsaKeyPair, ECDSA_P256_SHA256_FIXED_SIGNING};
+    use sqlx::PgPool;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+    use uuid::Uuid;
+
+    use crate::config::{SmtpConfig, SmtpSecurity, VapidConfig};
+    use crate::db::notification_models::*;
+    use crate::db::notification_repos::{NotificationRepo, PushSubscriptionRepo};
+    use crate::services::notifications::{
+        EmailChannel, InboxChannel, NotificationChannel, NotificationScheduler, PushChannel,
+    };
+    use crate::services::web_push::VapidKeys;
+    use crate::services::webauthn::b64url_encode;
+
+    // ========================================================================
+    // TEST HELPERS
+    // ========================================================================
+
+    async fn create_test_user(pool: &PgPool) -> Uuid {
+        let user_id = Uuid::new_v4();
+        let email = format!("test-notifications-{}@example.com", user_id);
+
+        sqlx::query(
+            r#"INSERT INTO users (id, email, name, role, approved, age_verified, tos_accepted)
+               VALUES ($1, $2, 'Test Notifications User', 'user', true, true, true)"#,
+        )
+        .bind(user_id)
+        .bind(&email)
+        .execute(pool)
+        .await
+        .expect("Failed to create test user");
+
+        user_id
+    }
+
+    async fn set_setting(pool: &PgPool, user_id: Uuid, key: &str, value: serde_json::Value) {
+        sqlx::query(
+            r#"INSERT INTO user_settings (user_id, key, value, created_at, updated_at)
+               VALUES ($1, $2, $3, NOW(), NOW())
+               ON CONFLICT (user_id, key) DO UPDATE SET value = EXCLUDED.value"#,
+        )
+        .bind(user_id)
+        .bind(key)
+        .bind(value)
+        .execute(pool)
+        .await
+        .expect("Failed to save setting");
+    }
+
+    /// Event starting in 10 minutes with a 15 minute reminder (already due)
+    async fn create_due_event(pool: &PgPool, user_id: Uuid) -> Uuid {
+        sqlx::query_scalar(
+            r#"INSERT INTO calendar_events
+               (user_id, title, event_type, start_time, all_day, reminder_minutes)
+               VALUES ($1, 'Standup', 'meeting', NOW() + INTERVAL '10 minutes', false, 15)
+               RETURNING id"#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .expect("Failed to create event")
+    }
+
+    async fn create_habit(
+        pool: &PgPool,
+        user_id: Uuid,
+        frequency: &str,
+        target_count: i32,
+        custom_days: Option<Vec<i32>>,
+    ) -> Uuid {
+        sqlx::query_scalar(
+            r#"INSERT INTO habits (user_id, name, frequency, target_count, custom_days, is_active,
+                                  current_streak, longest_streak, sort_order)
+               VALUES ($1, $2, $2, $3, $4, true, 0, 0, 0)
+               RETURNING id"#,
+        )
+        .bind(user_id)
+        .bind(frequency)
+        .bind(target_count)
+        .bind(custom_days)
+        .fetch_one(pool)
+        .await
+        .expect("Failed to create habit")
+    }
+
+    async fn preference_timezone(pool: &PgPool, user_id: Uuid) -> String {
+        sqlx::query_scalar("SELECT timezone FROM notification_preferences WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await
+            .expect("Failed to load preferences")
+    }
+
+    async fn delivery_status(pool: &PgPool, user_id: Uuid, channel: &str) -> (String, i32) {
+        sqlx::query_as(
+            "SELECT status, attempts FROM notification_deliveries WHERE user_id = $1 AND channel = $2",
+        )
+        .bind(user_id)
+        .bind(channel)
+        .fetch_one(pool)
+        .await
+        .expect("Failed to load delivery")
+    }
+
+    fn all_channels() -> [&'static str; 3] {
+        [
+            NOTIFICATION_CHANNEL_INBOX,
+            NOTIFICATION_CHANNEL_EMAIL,
+            NOTIFICATION_CHANNEL_PUSH,
+        ]
+    }
+
+    /// Accepts one SMTP session and returns the message data
+    async fn smtp_sink() -> (u16, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (read, mut write) = stream.into_split();
+            let mut lines = BufReader::new(read).lines();
+            let mut data = String::new();
+            let mut in_data = false;
+
+            write.write_all(b"220 sink ESMTP\r\n").await.unwrap();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if in_data {
+                    if line == "." {
+                        in_data = false;
+                        write.write_all(b"250 queued\r\n").await.unwrap();
+                    } else {
+                        data.push_str(&line);
+                        data.push('\n');
+                    }
+                    continue;
+                }
+                let reply: &[u8] = match line.get(..4).unwrap_or("").to_ascii_uppercase().as_str() {
+                    "DATA" => {
+                        in_data = true;
+                        b"354 go ahead\r\n"
+                    }
+                    "QUIT" => {
+                        write.write_all(b"221 bye\r\n").await.unwrap();
+                        break;
+                    }
+                    _ => b"250 ok\r\n",
+                };
+                write.write_all(reply).await.unwrap();
+            }
+            data
+        });
+
+        (port, handle)
+    }
+
+    /// Answers one push request with `status` and returns the request head
+    async fn push_service(status: u16) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!(
+            "http://{}/push/{}",
+            listener.local_addr().unwrap(),
+            Uuid::new_v4()
+        );
+
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some(head_end) = text.find("\r\n\r\n") {
+                    let length = text[..head_end]
+                        .lines()
+                        .find_map(|l| {
+                            l.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= head_end + 4 + length {
+                        break;
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 {} X\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                status
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            let text = String::from_utf8_lossy(&request);
+            text[..text.find("\r\n\r\n").unwrap_or(text.len())].to_string()
+        });
+
+        (endpoint, handle)
+    }
+
+    fn test_vapid_keys() -> VapidKeys {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng).unwrap();
+        VapidKeys::from_config(&VapidConfig {
+            private_key: b64url_encode(pkcs8.as_ref()),
+            public_key: None,
+            subject: "mailto:ops@example.com".to_string(),
+        })
+        .unwrap()
+    }
+
+    async fn subscribe(pool: &PgPool, user_id: Uuid, endpoint: &str) -> PushSubscription {
+        let rng = SystemRandom::new();
+        let browser_key =
+            agreement::EphemeralPrivateKey::generate(&agreement::ECDH_P256, &rng).unwrap();
+        let p256dh = browser_key.compute_public_key().unwrap();
+
+        PushSubscriptionRepo::upsert(
+            pool,
+            user_id,
+            &CreatePushSubscriptionRequest {
+                endpoint: endpoint.to_string(),
+                keys: PushSubscriptionKeys {
+                    p256dh: b64url_encode(p256dh.as_ref()),
+                    auth: b64url_encode(&[7u8; 16]),
+                },
+            },
+            Some("test-agent"),
+        )
+        .await
+        .expect("Failed to subscribe")
+    }
+
+    // ========================================================================
+    // MATERIALIZATION
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_calendar_reminder_follows_preferences_and_dedupes(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        set_setting(
+            &pool,
+            user_id,
+            "push_notifications",
+            serde_json::json!(false),
+        )
+        .await;
+        create_due_event(&pool, user_id).await;
+
+        let first =
+            NotificationRepo::materialize(&pool, NOTIFICATION_KIND_CALENDAR_EVENT, &all_channels())
+                .await
+                .unwrap();
+        let second =
+            NotificationRepo::materialize(&pool, NOTIFICATION_KIND_CALENDAR_EVENT, &all_channels())
+                .await
+                .unwrap();
+
+        // Inbox and email (default on), not push (turned off)
+        assert_eq!(first, 2);
+        assert_eq!(second, 0);
+
+        let deliveries = NotificationRepo::list_recent(&pool, user_id, 10)
+            .await
+            .unwrap();
+        assert!(deliveries.iter().all(|d| d.title == "Standup"));
+        assert!(deliveries
+            .iter()
+            .all(|d| d.channel != NOTIFICATION_CHANNEL_PUSH));
+    }
+
+    #[sqlx::test]
+    async fn test_notifications_disabled_materializes_nothing(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        set_setting(
+            &pool,
+            user_id,
+            "notifications_enabled",
+            serde_json::json!(false),
+        )
+        .await;
+        create_due_event(&pool, user_id).await;
+
+        let count =
+            NotificationRepo::materialize(&pool, NOTIFICATION_KIND_CALENDAR_EVENT, &all_channels())
+                .await
+                .unwrap();
+
+        assert_eq!(count, 0);
+    }
+
+    #[sqlx::test]
+    async fn test_preferences_follow_settings_writes(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        assert_eq!(preference_timezone(&pool, user_id).await, "UTC");
+
+        set_setting(
+            &pool,
+            user_id,
+            "timezone",
+            serde_json::json!("Europe/Paris"),
+        )
+        .await;
+        assert_eq!(preference_timezone(&pool, user_id).await, "Europe/Paris");
+
+        // Unknown names fall back to UTC
+        set_setting(
+            &pool,
+            user_id,
+            "timezone",
+            serde_json::json!("Mars/Olympus"),
+        )
+        .await;
+        assert_eq!(preference_timezone(&pool, user_id).await, "UTC");
+
+        set_setting(&pool, user_id, "timezone", serde_json::json!("Asia/Tokyo")).await;
+        sqlx::query("DELETE FROM user_settings WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(preference_timezone(&pool, user_id).await, "UTC");
+    }
+
+    #[sqlx::test]
+    async fn test_habit_checkin_counts_habits_due_today(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let now = Utc::now();
+        set_setting(
+            &pool,
+            user_id,
+            "daily_reminder_time",
+            serde_json::json!(format!("{:02}:{:02}", now.hour(), now.minute())),
+        )
+        .await;
+        let today = now.weekday().num_days_from_sunday() as i32;
+        let done = create_habit(&pool, user_id, "daily", 1, None).await;
+        sqlx::query(
+            "INSERT INTO habit_completions (user_id, habit_id, completed_date) VALUES ($1, $2, CURRENT_DATE)",
+        )
+        .bind(user_id)
+        .bind(done)
+        .execute(&pool)
+        .await
+        .unwrap();
+        create_habit(&pool, user_id, "weekly", 3, None).await;
+        create_habit(&pool, user_id, "custom", 1, Some(vec![today])).await;
+        create_habit(&pool, user_id, "custom", 1, Some(vec![(today + 1) % 7])).await;
+
+        let count = NotificationRepo::materialize(
+            &pool,
+            NOTIFICATION_KIND_HABIT_CHECKIN,
+            &[NOTIFICATION_CHANNEL_INBOX],
+        )
+        .await
+        .unwrap();
+
+        // The weekly habit and the custom one due today
+        assert_eq!(count, 1);
+        let deliveries = NotificationRepo::list_recent(&pool, user_id, 10)
+            .await
+            .unwrap();
+        assert_eq!(deliveries[0].body.as_deref(), Some("2 habits left today"));
+    }
+
+    // ========================================================================
+    // DELIVERY
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_quiet_hours_hold_email_but_not_inbox(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let now = Utc::now();
+        let start = format!("{:02}:00", (now.hour() + 23) % 24);
+        let end = format!("{:02}:00", (now.hour() + 2) % 24);
+        set_setting(
+            &pool,
+            user_id,
+            "quiet_hours_start",
+            serde_json::json!(start),
+        )
+        .await;
+        set_setting(&pool, user_id, "quiet_hours_end", serde_json::json!(end)).await;
+        create_due_event(&pool, user_id).await;
+
+        NotificationRepo::materialize(
+            &pool,
+            NOTIFICATION_KIND_CALENDAR_EVENT,
+            &[NOTIFICATION_CHANNEL_INBOX, NOTIFICATION_CHANNEL_EMAIL],
+        )
+        .await
+        .unwrap();
+        let claimed = NotificationRepo::claim_due(&pool, 10, 60.0).await.unwrap();
+
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].channel, NOTIFICATION_CHANNEL_INBOX);
+        assert_eq!(
+            delivery_status(&pool, user_id, NOTIFICATION_CHANNEL_EMAIL).await,
+            (DELIVERY_STATUS_PENDING.to_string(), 0)
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_scheduler_delivers_to_inbox(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let event_id = create_due_event(&pool, user_id).await;
+        let scheduler =
+            NotificationScheduler::new(vec![Box::new(InboxChannel)], Duration::from_secs(60));
+
+        let summary = scheduler.run_once(&pool).await.unwrap();
+
+        assert_eq!(summary.materialized, 1);
+        assert_eq!(summary.sent, 1);
+        let (item_type, action_data): (String, serde_json::Value) =
+            sqlx::query_as("SELECT item_type, action_data FROM inbox_items WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(item_type, "reminder");
+        assert_eq!(action_data["ref_id"], serde_json::json!(event_id));
+    }
+
+    #[sqlx::test]
+    async fn test_failed_delivery_retries_then_fails(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        create_due_event(&pool, user_id).await;
+
+        // Nothing listens on this port
+        let closed = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = closed.local_addr().unwrap().port();
+        drop(closed);
+        let email = EmailChannel::from_config(
+            &SmtpConfig {
+                host: "127.0.0.1".to_string(),
+                port,
+                username: None,
+                password: None,
+                from: "Ignition <reminders@example.com>".to_string(),
+                security: SmtpSecurity::None,
+            },
+            "https://app.example.com",
+        )
+        .unwrap();
+        let scheduler = NotificationScheduler::new(vec![Box::new(email)], Duration::from_secs(60));
+
+        let summary = scheduler.run_once(&pool).await.unwrap();
+        assert_eq!(summary.retried, 1);
+        let (status, attempts) = delivery_status(&pool, user_id, NOTIFICATION_CHANNEL_EMAIL).await;
+        assert_eq!((status.as_str(), attempts), (DELIVERY_STATUS_PENDING, 1));
+
+        // Exhaust the remaining attempts
+        for _ in 0..4 {
+            sqlx::query(
+                "UPDATE notification_deliveries SET next_attempt_at = NOW() WHERE user_id = $1",
+            )
+            .bind(user_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+            scheduler.run_once(&pool).await.unwrap();
+        }
+
+        let (status, attempts) = delivery_status(&pool, user_id, NOTIFICATION_CHANNEL_EMAIL).await;
+        assert_eq!((status.as_str(), attempts), (DELIVERY_STATUS_FAILED, 5));
+    }
+
+    #[sqlx::test]
+    async fn test_email_channel_sends_over_smtp(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        create_due_event(&pool, user_id).await;
+        let (port, sink) = smtp_sink().await;
+        let email = EmailChannel::from_config(
+            &SmtpConfig {
+                host: "127.0.0.1".to_string(),
+                port,
+                username: None,
+                password: None,
+                from: "Ignition <reminders@example.com>".to_string(),
+                security: SmtpSecurity::None,
+            },
+            "https://app.example.com",
+        )
+        .unwrap();
+        let scheduler = NotificationScheduler::new(vec![Box::new(email)], Duration::from_secs(60));
+
+        let summary = scheduler.run_once(&pool).await.unwrap();
+        let message = sink.await.unwrap();
+
+        assert_eq!(summary.sent, 1);
+        assert!(message.contains("Subject: Standup"));
+        assert!(message.contains("https://app.example.com/calendar"));
+        assert!(message.contains(&format!("test-notifications-{}@example.com", user_id)));
+    }
+
+    #[sqlx::test]
+    async fn test_push_channel_delivers_and_drops_gone_subscriptions(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let (endpoint, service) = push_service(201).await;
+        let subscription = subscribe(&pool, user_id, &endpoint).await;
+        let channel = PushChannel::new(test_vapid_keys());
+        let notification = DueNotification {
+            id: Uuid::new_v4(),
+            user_id,
+            kind: NOTIFICATION_KIND_FOCUS_END.to_string(),
+            ref_id: None,
+            channel: NOTIFICATION_CHANNEL_PUSH.to_string(),
+            title: "Focus session complete".to_string(),
+            body: None,
+            action_url: Some("/focus".to_string()),
+            attempts: 1,
+            email: String::new(),
+        };
+
+        channel.deliver(&pool, &notification).await.unwrap();
+        let request = service.await.unwrap().to_ascii_lowercase();
+        assert!(request.contains("authorization: vapid t="));
+        assert!(request.contains("content-encoding: aes128gcm"));
+        let subscriptions = PushSubscriptionRepo::list_for_user(&pool, user_id)
+            .await
+            .unwrap();
+        assert!(subscriptions[0].last_used_at.is_some());
+
+        // The push service reports the subscription expired
+        PushSubscriptionRepo::delete_by_id(&pool, subscription.id)
+            .await
+            .unwrap();
+        let (endpoint, service) = push_service(410).await;
+        subscribe(&pool, user_id, &endpoint).await;
+
+        let result = channel.deliver(&pool, &notification).await;
+        service.await.unwrap();
+
+        assert!(result.is_err());
+        assert!(PushSubscriptionRepo::list_for_user(&pool, user_id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,257 @@
+//! Onboarding flow tests
+//!
+//! Conditional steps follow earlier responses, users finish the version they
+//! started, and funnels count how far users got.
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use crate::db::onboarding_models::*;
+    use crate::db::onboarding_repos::OnboardingFlowRepo;
+    use crate::db::platform_repos::OnboardingRepo;
+    use crate::error::AppError;
+
+    // ========================================================================
+    // TEST HELPERS
+    // ========================================================================
+
+    async fn create_test_user(pool: &PgPool) -> Uuid {
+        let user_id = Uuid::new_v4();
+        sqlx::query(
+            r#"INSERT INTO users (id, email, name, role, approved, age_verified, tos_accepted)
+               VALUES ($1, $2, 'Test Onboarding User', 'user', true, true, true)"#,
+        )
+        .bind(user_id)
+        .bind(format!("test-onboarding-{}@example.com", user_id))
+        .execute(pool)
+        .await
+        .expect("Failed to create test user");
+        user_id
+    }
+
+    fn step(title: &str, show_if: Option<serde_json::Value>) -> CreateOnboardingStepRequest {
+        CreateOnboardingStepRequest {
+            step_order: None,
+            step_type: "choice".to_string(),
+            title: title.to_string(),
+            description: None,
+            target_selector: None,
+            target_route: None,
+            fallback_content: None,
+            options: None,
+            allows_multiple: true,
+            required: false,
+            action_type: None,
+            action_config: None,
+            show_if: show_if.map(|c| serde_json::from_value(c).unwrap()),
+        }
+    }
+
+    /// Published flow: Interests, then DAW for producers only, then Focus
+    async fn producer_flow(pool: &PgPool) -> (Uuid, [Uuid; 3]) {
+        let flow = OnboardingFlowRepo::create(
+            pool,
+            &CreateOnboardingFlowRequest {
+                name: "producers".to_string(),
+                description: None,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let interests = OnboardingFlowRepo::add_step(pool, flow.id, &step("Interests", None))
+            .await
+            .unwrap();
+        let focus = OnboardingFlowRepo::add_step(pool, flow.id, &step("Focus", None))
+            .await
+            .unwrap();
+        // Inserted between the two
+        let daw = OnboardingFlowRepo::add_step(
+            pool,
+            flow.id,
+            &CreateOnboardingStepRequest {
+                step_order: Some(2),
+                ..step(
+                    "DAW",
+                    Some(json!({"includes": {"field": "interests", "value": "production"}})),
+                )
+            },
+        )
+        .await
+        .unwrap();
+        OnboardingFlowRepo::publish(pool, flow.id).await.unwrap();
+        (flow.id, [interests.id, daw.id, focus.id])
+    }
+
+    // ========================================================================
+    // TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_conditional_steps_follow_responses(pool: PgPool) {
+        let (flow_id, [interests, daw, focus]) = producer_flow(&pool).await;
+        let detail = OnboardingFlowRepo::get(&pool, flow_id).await.unwrap();
+        assert_eq!(detail.flow.total_steps, 3);
+        assert_eq!(
+            detail.steps.iter().map(|s| s.id).collect::<Vec<_>>(),
+            vec![interests, daw, focus]
+        );
+
+        let producer = create_test_user(&pool).await;
+        let started = OnboardingRepo::start(&pool, producer).await.unwrap();
+        assert_eq!(started.current_step.unwrap().id, interests);
+        let next = OnboardingRepo::complete_step(
+            &pool,
+            producer,
+            interests,
+            Some(json!({"interests": ["focus", "production"]})),
+        )
+        .await
+        .unwrap();
+        assert_eq!(next.next_step.unwrap().id, daw);
+
+        // Steps are completed in order, and only once
+        assert!(matches!(
+            OnboardingRepo::complete_step(&pool, producer, focus, None).await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(matches!(
+            OnboardingRepo::complete_step(&pool, producer, interests, None).await,
+            Err(AppError::BadRequest(_))
+        ));
+
+        let writer = create_test_user(&pool).await;
+        OnboardingRepo::start(&pool, writer).await.unwrap();
+        let next = OnboardingRepo::complete_step(
+            &pool,
+            writer,
+            interests,
+            Some(json!({"interests": ["focus"]})),
+        )
+        .await
+        .unwrap();
+        assert_eq!(next.next_step.unwrap().id, focus);
+
+        let progress = OnboardingRepo::get_progress(&pool, writer).await.unwrap();
+        assert_eq!((progress.completed_steps, progress.total_steps), (1, 2));
+        let done = OnboardingRepo::complete_step(&pool, writer, focus, None)
+            .await
+            .unwrap();
+        assert!(done.completed);
+        let progress = OnboardingRepo::get_progress(&pool, writer).await.unwrap();
+        assert_eq!(progress.percent_complete, 100);
+    }
+
+    #[sqlx::test]
+    async fn test_users_finish_the_version_they_started(pool: PgPool) {
+        let (v1, [interests, _, focus]) = producer_flow(&pool).await;
+        let early = create_test_user(&pool).await;
+        OnboardingRepo::start(&pool, early).await.unwrap();
+
+        // Published versions are frozen and in-use ones are kept
+        assert!(matches!(
+            OnboardingFlowRepo::add_step(&pool, v1, &step("Late", None)).await,
+            Err(AppError::Conflict { .. })
+        ));
+        assert!(matches!(
+            OnboardingFlowRepo::delete(&pool, v1).await,
+            Err(AppError::Conflict { .. })
+        ));
+        let described = UpdateOnboardingFlowRequest {
+            description: Some("For producers".to_string()),
+        };
+        assert!(matches!(
+            OnboardingFlowRepo::update(&pool, v1, &described).await,
+            Err(AppError::Conflict { .. })
+        ));
+
+        let v2 = OnboardingFlowRepo::new_version(&pool, v1).await.unwrap();
+        assert_eq!(v2.flow.version, 2);
+        assert!(v2.flow.published_at.is_none());
+        let updated = OnboardingFlowRepo::update(&pool, v2.flow.id, &described)
+            .await
+            .unwrap();
+        assert_eq!(updated.description.as_deref(), Some("For producers"));
+        assert!(matches!(
+            OnboardingFlowRepo::new_version(&pool, v1).await,
+            Err(AppError::Conflict { .. })
+        ));
+        let first_v2 = v2.steps[0].id;
+        OnboardingFlowRepo::delete_step(&pool, v2.flow.id, v2.steps[2].id)
+            .await
+            .unwrap();
+        OnboardingFlowRepo::publish(&pool, v2.flow.id)
+            .await
+            .unwrap();
+
+        // The early user resumes and finishes version 1
+        let resumed = OnboardingRepo::start(&pool, early).await.unwrap();
+        assert_eq!(resumed.current_step.unwrap().id, interests);
+        let next = OnboardingRepo::complete_step(&pool, early, interests, None)
+            .await
+            .unwrap();
+        assert_eq!(next.next_step.unwrap().id, focus);
+        assert!(matches!(
+            OnboardingRepo::complete_step(&pool, early, first_v2, None).await,
+            Err(AppError::BadRequest(_))
+        ));
+
+        let late = create_test_user(&pool).await;
+        let started = OnboardingRepo::start(&pool, late).await.unwrap();
+        assert_eq!(started.current_step.unwrap().id, first_v2);
+        let state = OnboardingRepo::get_full_state(&pool, late).await.unwrap();
+        assert_eq!(state.flow.unwrap().version, 2);
+    }
+
+    #[sqlx::test]
+    async fn test_funnel_counts_each_step(pool: PgPool) {
+        let (flow_id, [interests, daw, focus]) = producer_flow(&pool).await;
+
+        for interest in ["production", "production", "focus"] {
+            let user_id = create_test_user(&pool).await;
+            OnboardingRepo::start(&pool, user_id).await.unwrap();
+            OnboardingRepo::complete_step(
+                &pool,
+                user_id,
+                interests,
+                Some(json!({"interests": [interest]})),
+            )
+            .await
+            .unwrap();
+        }
+        let skipper = create_test_user(&pool).await;
+        OnboardingRepo::start(&pool, skipper).await.unwrap();
+        OnboardingRepo::skip(&pool, skipper).await.unwrap();
+
+        let funnel = OnboardingFlowRepo::funnel(&pool, flow_id).await.unwrap();
+        assert_eq!(funnel.totals.started, 4);
+        assert_eq!(funnel.totals.in_progress, 3);
+        assert_eq!(funnel.totals.skipped, 1);
+
+        let counts: Vec<_> = funnel
+            .steps
+            .iter()
+            .map(|s| {
+                (
+                    s.step_id,
+                    s.reached,
+                    s.completed,
+                    s.in_progress,
+                    s.skipped_here,
+                )
+            })
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                (interests, 4, 3, 0, 1),
+                (daw, 2, 0, 2, 0),
+                (focus, 1, 0, 1, 0),
+            ]
+        );
+        assert!(funnel.steps[1].conditional);
+    }
+}
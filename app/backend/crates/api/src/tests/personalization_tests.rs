@@ -0,0 +1,207 @@
+//! Today personalization tests
+//!
+//! Suggestions follow stated interests and recent activity, explain
+//! themselves and hold still for the day.
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use crate::db::personalization_models::*;
+    use crate::db::personalization_repos::PersonalizationRepo;
+
+    // ========================================================================
+    // TEST HELPERS
+    // ========================================================================
+
+    async fn create_test_user(pool: &PgPool) -> Uuid {
+        let user_id = Uuid::new_v4();
+        sqlx::query(
+            r#"INSERT INTO users (id, email, name, role, approved, age_verified, tos_accepted)
+               VALUES ($1, $2, 'Test Personalization User', 'user', true, true, true)"#,
+        )
+        .bind(user_id)
+        .bind(format!("test-personalization-{}@example.com", user_id))
+        .execute(pool)
+        .await
+        .expect("Failed to create test user");
+        user_id
+    }
+
+    /// Two lessons in the seeded topic, in order
+    async fn create_lessons(pool: &PgPool, topic_key: &str) -> [Uuid; 2] {
+        let mut ids = [Uuid::nil(); 2];
+        for (i, id) in ids.iter_mut().enumerate() {
+            *id = sqlx::query_scalar(
+                r#"INSERT INTO learn_lessons (topic_id, key, title, difficulty, xp_reward,
+                                              coin_reward, skill_star_reward, sort_order, is_active)
+                   SELECT id, $2, $3, 'beginner', 10, 5, 0, $4, true
+                   FROM learn_topics WHERE key = $1
+                   RETURNING id"#,
+            )
+            .bind(topic_key)
+            .bind(format!("{}_{}", topic_key, i + 1))
+            .bind(format!("{} lesson {}", topic_key, i + 1))
+            .bind(i as i32 + 1)
+            .fetch_one(pool)
+            .await
+            .expect("Failed to create lesson");
+        }
+        ids
+    }
+
+    async fn create_goal(pool: &PgPool, user_id: Uuid, title: &str, idle_days: i32) -> Uuid {
+        sqlx::query_scalar(
+            r#"INSERT INTO goals (user_id, title, status, sort_order, priority, progress, updated_at)
+               VALUES ($1, $2, 'active', 0, 0, 30, NOW() - make_interval(days => $3))
+               RETURNING id"#,
+        )
+        .bind(user_id)
+        .bind(title)
+        .bind(idle_days)
+        .fetch_one(pool)
+        .await
+        .expect("Failed to create goal")
+    }
+
+    fn today() -> NaiveDate {
+        chrono::Utc::now().date_naive()
+    }
+
+    // ========================================================================
+    // TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_lessons_follow_interests(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let [first, second] = create_lessons(&pool, "music_theory").await;
+        create_lessons(&pool, "fitness_basics").await;
+
+        // No interests yet: nothing to go on
+        let suggestions = PersonalizationRepo::suggestions(&pool, user_id, today(), 5)
+            .await
+            .unwrap();
+        assert!(suggestions.is_empty());
+
+        sqlx::query(
+            "INSERT INTO user_interests (user_id, interest_key, interest_label) VALUES ($1, 'music', 'Music')",
+        )
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let suggestions = PersonalizationRepo::suggestions(&pool, user_id, today(), 5)
+            .await
+            .unwrap();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].id, first);
+        assert_eq!(suggestions[0].kind, SuggestionKind::Lesson);
+        assert_eq!(
+            suggestions[0].reasons,
+            vec!["You're interested in Music", "Next lesson in Music Theory"]
+        );
+
+        sqlx::query(
+            r#"INSERT INTO user_lesson_progress (user_id, lesson_id, status, completed_at, attempts)
+               VALUES ($1, $2, 'completed', NOW(), 1)"#,
+        )
+        .bind(user_id)
+        .bind(first)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let suggestions = PersonalizationRepo::suggestions(&pool, user_id, today(), 5)
+            .await
+            .unwrap();
+        assert_eq!(suggestions[0].id, second);
+    }
+
+    #[sqlx::test]
+    async fn test_onboarding_answers_and_activity_count_as_interests(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let step_id: Uuid = sqlx::query_scalar(
+            r#"WITH flow AS (
+                   INSERT INTO onboarding_flows (name, is_active, total_steps)
+                   VALUES ('interests_test', false, 1)
+                   RETURNING id
+               )
+               INSERT INTO onboarding_steps (flow_id, step_order, step_type, title, allows_multiple, required)
+               SELECT id, 1, 'choice', 'Interests', true, false FROM flow
+               RETURNING id"#,
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"INSERT INTO user_onboarding_responses (user_id, step_id, response)
+               VALUES ($1, $2, '{"interests": ["fitness"]}')"#,
+        )
+        .bind(user_id)
+        .bind(step_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        for _ in 0..4 {
+            sqlx::query(
+                r#"INSERT INTO points_ledger (user_id, event_type, xp, created_at)
+                   VALUES ($1, 'focus_complete', 10, NOW() - INTERVAL '2 days')"#,
+            )
+            .bind(user_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let profile = PersonalizationRepo::interest_profile(&pool, user_id, today())
+            .await
+            .unwrap();
+        assert_eq!(
+            profile.affinity(["fitness_basics"]).unwrap().reason,
+            "You picked fitness during onboarding"
+        );
+        assert_eq!(
+            profile.affinity(["productivity"]).unwrap().reason,
+            "You've logged 4 focus sessions in the last two weeks"
+        );
+        assert!(profile.affinity(["music_theory"]).is_none());
+    }
+
+    #[sqlx::test]
+    async fn test_stalled_goals_and_books_are_stable_for_the_day(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let stalled = create_goal(&pool, user_id, "Finish the EP", 10).await;
+        create_goal(&pool, user_id, "Moving along", 1).await;
+        let book: Uuid = sqlx::query_scalar(
+            r#"INSERT INTO books (user_id, title, total_pages, current_page, status)
+               VALUES ($1, 'Mixing Secrets', 300, 120, 'reading')
+               RETURNING id"#,
+        )
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        let first = PersonalizationRepo::suggestions(&pool, user_id, today(), MAX_SUGGESTIONS)
+            .await
+            .unwrap();
+        let mut ids: Vec<_> = first.iter().map(|s| s.id).collect();
+        let again = PersonalizationRepo::suggestions(&pool, user_id, today(), MAX_SUGGESTIONS)
+            .await
+            .unwrap();
+        assert_eq!(ids, again.iter().map(|s| s.id).collect::<Vec<_>>());
+
+        ids.sort();
+        let mut expected = vec![stalled, book];
+        expected.sort();
+        assert_eq!(ids, expected);
+
+        let goal = first.iter().find(|s| s.id == stalled).unwrap();
+        assert_eq!(goal.reasons, vec!["No progress in 10 days (30% done)"]);
+        assert_eq!(goal.route, format!("/goals?goal={}", stalled));
+    }
+}
@@ -0,0 +1,37 @@
+//! Query catalog tests
+//!
+//! Every catalogued statement must prepare against a freshly migrated schema.
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use sqlx::PgPool;
+
+    use crate::db::catalog;
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_all_queries_prepare(pool: PgPool) {
+        let failures = catalog::check(&pool)
+            .await
+            .expect("Failed to acquire connection");
+
+        let report: Vec<String> = failures
+            .iter()
+            .map(|f| format!("{}: {}", f.name, f.error))
+            .collect();
+        assert!(
+            failures.is_empty(),
+            "{} queries failed to prepare:\n{}",
+            failures.len(),
+            report.join("\n")
+        );
+    }
+
+    #[test]
+    fn test_query_names_are_unique() {
+        let queries = catalog::queries();
+        let names: HashSet<_> = queries.iter().map(|q| q.name.as_ref()).collect();
+        assert_eq!(names.len(), queries.len());
+    }
+}
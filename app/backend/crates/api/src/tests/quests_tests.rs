@@ -58,6 +58,7 @@ mod tests {
                 difficulty: "medium".to_string(),
                 xp_reward: None,
                 coin_reward: None,
+                target: None,
                 is_repeatable: Some(false),
                 repeat_frequency: None,
             },
@@ -87,6 +88,7 @@ mod tests {
                 difficulty: "starter".to_string(),
                 xp_reward: Some(100),
                 coin_reward: Some(50),
+                target: None,
                 is_repeatable: None,
                 repeat_frequency: None,
             },
@@ -117,6 +119,7 @@ mod tests {
                 difficulty: "easy".to_string(),
                 xp_reward: None,
                 coin_reward: None,
+                target: None,
                 is_repeatable: None,
                 repeat_frequency: None,
             },
@@ -134,6 +137,7 @@ mod tests {
                 difficulty: "hard".to_string(),
                 xp_reward: None,
                 coin_reward: None,
+                target: None,
                 is_repeatable: None,
                 repeat_frequency: None,
             },
@@ -163,6 +167,7 @@ mod tests {
                 difficulty: "easy".to_string(),
                 xp_reward: None,
                 coin_reward: None,
+                target: None,
                 is_repeatable: None,
                 repeat_frequency: None,
             },
@@ -206,6 +211,7 @@ mod tests {
                 difficulty: "easy".to_string(),
                 xp_reward: None,
                 coin_reward: None,
+                target: None,
                 is_repeatable: None,
                 repeat_frequency: None,
             },
@@ -235,6 +241,7 @@ mod tests {
                 difficulty: "easy".to_string(),
                 xp_reward: None,
                 coin_reward: None,
+                target: None,
                 is_repeatable: None,
                 repeat_frequency: None,
             },
@@ -270,6 +277,7 @@ mod tests {
                 difficulty: "hard".to_string(), // 100 XP, 50 coins
                 xp_reward: None,
                 coin_reward: None,
+                target: None,
                 is_repeatable: None,
                 repeat_frequency: None,
             },
@@ -306,6 +314,7 @@ mod tests {
                 difficulty: "easy".to_string(),
                 xp_reward: None,
                 coin_reward: None,
+                target: None,
                 is_repeatable: None,
                 repeat_frequency: None,
             },
@@ -347,6 +356,7 @@ mod tests {
                 difficulty: "easy".to_string(),
                 xp_reward: None,
                 coin_reward: None,
+                target: None,
                 is_repeatable: None,
                 repeat_frequency: None,
             },
@@ -379,6 +389,7 @@ mod tests {
                 difficulty: "easy".to_string(),
                 xp_reward: None,
                 coin_reward: None,
+                target: None,
                 is_repeatable: None,
                 repeat_frequency: None,
             },
@@ -417,6 +428,7 @@ mod tests {
                 difficulty: "epic".to_string(),
                 xp_reward: None,
                 coin_reward: None,
+                target: None,
                 is_repeatable: None,
                 repeat_frequency: None,
             },
@@ -448,6 +460,7 @@ mod tests {
                 difficulty: "easy".to_string(),
                 xp_reward: None,
                 coin_reward: None,
+                target: None,
                 is_repeatable: None,
                 repeat_frequency: None,
             },
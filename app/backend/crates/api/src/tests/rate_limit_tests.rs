@@ -0,0 +1,116 @@
+//! Rate limit tests
+//!
+//! Both bucket stores refuse requests past capacity, keep subjects apart and
+//! audit a limit tripping.
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use crate::config::{RateLimitBackend, RateLimitKey, RateLimitPolicy, RateLimitsConfig};
+    use crate::services::rate_limit::{RateLimitSubject, RateLimiter};
+
+    // ========================================================================
+    // TEST HELPERS
+    // ========================================================================
+
+    fn limiter(backend: RateLimitBackend) -> RateLimiter {
+        RateLimiter::new(&RateLimitsConfig {
+            enabled: true,
+            backend,
+            policies: vec![RateLimitPolicy {
+                name: "feedback".to_string(),
+                paths: vec!["/api/feedback".to_string()],
+                methods: vec!["POST".to_string()],
+                key: RateLimitKey::User,
+                capacity: 2,
+                period_seconds: 3600,
+            }],
+        })
+    }
+
+    fn user(user_id: Uuid) -> RateLimitSubject {
+        RateLimitSubject {
+            user_id: Some(user_id),
+            session_id: None,
+            ip: Some("198.51.100.7".to_string()),
+        }
+    }
+
+    async fn allowed(pool: &PgPool, limiter: &RateLimiter, subject: &RateLimitSubject) -> bool {
+        limiter
+            .check(pool, "POST", "/api/feedback", subject)
+            .await
+            .expect("policy applies")
+            .allowed
+    }
+
+    async fn exhausts_after_capacity(pool: &PgPool, limiter: &RateLimiter) {
+        let first = user(Uuid::new_v4());
+        assert!(allowed(pool, limiter, &first).await);
+        assert!(allowed(pool, limiter, &first).await);
+
+        let refused = limiter
+            .check(pool, "POST", "/api/feedback", &first)
+            .await
+            .unwrap();
+        assert!(!refused.allowed);
+        assert_eq!(refused.remaining, 0);
+        assert!(refused.retry_after.is_some_and(|s| s > 0 && s <= 1800));
+
+        // Other users have their own bucket; other routes are not limited
+        assert!(allowed(pool, limiter, &user(Uuid::new_v4())).await);
+        assert!(limiter
+            .check(pool, "GET", "/api/feedback", &first)
+            .await
+            .is_none());
+    }
+
+    // ========================================================================
+    // TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_memory_store_limits(pool: PgPool) {
+        exhausts_after_capacity(&pool, &limiter(RateLimitBackend::Memory)).await;
+    }
+
+    #[sqlx::test]
+    async fn test_postgres_store_is_shared(pool: PgPool) {
+        exhausts_after_capacity(&pool, &limiter(RateLimitBackend::Postgres)).await;
+
+        // A second instance sees the buckets the first spent
+        let subject = user(Uuid::new_v4());
+        assert!(allowed(&pool, &limiter(RateLimitBackend::Postgres), &subject).await);
+        assert!(allowed(&pool, &limiter(RateLimitBackend::Postgres), &subject).await);
+        assert!(!allowed(&pool, &limiter(RateLimitBackend::Postgres), &subject).await);
+    }
+
+    #[sqlx::test]
+    async fn test_tripped_limit_is_audited_once(pool: PgPool) {
+        let limiter = limiter(RateLimitBackend::Memory);
+        let subject = user(Uuid::new_v4());
+        for _ in 0..5 {
+            allowed(&pool, &limiter, &subject).await;
+        }
+
+        let mut audited = 0;
+        for _ in 0..50 {
+            audited = sqlx::query_scalar::<_, i64>(
+                "SELECT COUNT(*) FROM audit_log WHERE event_type = 'rate_limited' AND user_id = $1",
+            )
+            .bind(subject.user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            if audited > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(audited, 1);
+    }
+}
@@ -0,0 +1,202 @@
+//! Revision tests
+//!
+//! Saves append revisions, stale versions are refused, and old revisions can
+//! be diffed and restored.
+
+#[cfg(test)]
+mod tests {
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use crate::db::platform_models::*;
+    use crate::db::platform_repos::{IdeasRepo, InfobaseRepo};
+    use crate::db::revision_models::RevisionEntity;
+    use crate::db::revision_repos::{content_hash, RevisionRepo};
+    use crate::error::AppError;
+
+    // ========================================================================
+    // TEST HELPERS
+    // ========================================================================
+
+    async fn create_test_user(pool: &PgPool) -> Uuid {
+        let user_id = Uuid::new_v4();
+        sqlx::query(
+            r#"INSERT INTO users (id, email, name, role, approved, age_verified, tos_accepted)
+               VALUES ($1, $2, 'Test Revisions User', 'user', true, true, true)"#,
+        )
+        .bind(user_id)
+        .bind(format!("test-revisions-{}@example.com", user_id))
+        .execute(pool)
+        .await
+        .expect("Failed to create test user");
+        user_id
+    }
+
+    fn new_entry(title: &str, content: &str) -> CreateInfobaseEntryRequest {
+        CreateInfobaseEntryRequest {
+            title: title.to_string(),
+            content: content.to_string(),
+            category: "Tips".to_string(),
+            tags: None,
+        }
+    }
+
+    fn content_update(content: &str) -> UpdateInfobaseEntryRequest {
+        UpdateInfobaseEntryRequest {
+            title: None,
+            content: Some(content.to_string()),
+            category: None,
+            tags: None,
+        }
+    }
+
+    // ========================================================================
+    // TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_updates_append_revisions(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let entry = InfobaseRepo::create(&pool, user_id, &new_entry("Gain", "one\ntwo"))
+            .await
+            .unwrap();
+        assert_eq!(entry.version, 1);
+
+        let updated =
+            InfobaseRepo::update(&pool, entry.id, user_id, &content_update("one\n2"), Some(1))
+                .await
+                .unwrap();
+        assert_eq!(updated.version, 2);
+
+        let revisions = RevisionRepo::list(&pool, user_id, RevisionEntity::Infobase, entry.id)
+            .await
+            .unwrap();
+        assert_eq!(
+            revisions.iter().map(|r| r.version).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+        assert_eq!(revisions[0].author_id, Some(user_id));
+        assert_eq!(revisions[0].content_hash, content_hash(Some("one\n2")));
+
+        let diff = RevisionRepo::diff(&pool, user_id, RevisionEntity::Infobase, entry.id, 1, 2)
+            .await
+            .unwrap();
+        assert!(!diff.title_changed);
+        assert_eq!(
+            diff.diff,
+            "--- v1\n+++ v2\n@@ -1,2 +1,2 @@\n one\n-two\n+2\n"
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_stale_version_conflicts(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let entry = InfobaseRepo::create(&pool, user_id, &new_entry("Gain", "first"))
+            .await
+            .unwrap();
+        InfobaseRepo::update(&pool, entry.id, user_id, &content_update("second"), Some(1))
+            .await
+            .unwrap();
+
+        let err = InfobaseRepo::update(&pool, entry.id, user_id, &content_update("stale"), Some(1))
+            .await
+            .unwrap_err();
+        let AppError::Conflict { details, .. } = err else {
+            panic!("expected a conflict, got {:?}", err);
+        };
+        let details = details.expect("conflict details");
+        assert_eq!(details["current"]["version"], 2);
+        assert_eq!(details["current"]["content"], "second");
+        assert_eq!(details["submitted"]["version"], 1);
+        assert_eq!(details["submitted"]["changes"]["content"], "stale");
+
+        let stored = InfobaseRepo::get(&pool, entry.id, user_id).await.unwrap();
+        assert_eq!((stored.version, stored.content.as_str()), (2, "second"));
+    }
+
+    #[sqlx::test]
+    async fn test_restore_saves_a_new_version(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let idea = IdeasRepo::create(
+            &pool,
+            user_id,
+            &CreateIdeaRequest {
+                title: "Hook".to_string(),
+                content: Some("original".to_string()),
+                category: "general".to_string(),
+                tags: Some(vec!["vocal".to_string()]),
+                music: IdeaMusic::default(),
+            },
+        )
+        .await
+        .unwrap();
+        let rewrite = UpdateIdeaRequest {
+            title: Some("Chorus".to_string()),
+            content: Some("rewritten".to_string()),
+            category: None,
+            tags: Some(vec![]),
+            is_pinned: Some(true),
+            music: IdeaMusic::default(),
+        };
+        IdeasRepo::update(&pool, idea.id, user_id, &rewrite, None)
+            .await
+            .unwrap();
+
+        let restored = IdeasRepo::restore(&pool, idea.id, user_id, 1, Some(2))
+            .await
+            .unwrap();
+        assert_eq!(restored.version, 3);
+        assert_eq!(restored.title, "Hook");
+        assert_eq!(restored.content.as_deref(), Some("original"));
+        assert_eq!(restored.tags, vec!["vocal".to_string()]);
+        // Pinning is not part of the content
+        assert!(restored.is_pinned);
+
+        assert!(matches!(
+            IdeasRepo::restore(&pool, idea.id, user_id, 9, None).await,
+            Err(AppError::NotFound(_))
+        ));
+
+        IdeasRepo::delete(&pool, idea.id, user_id).await.unwrap();
+        let revisions = RevisionRepo::list(&pool, user_id, RevisionEntity::Idea, idea.id)
+            .await
+            .unwrap();
+        assert!(revisions.is_empty());
+    }
+
+    #[sqlx::test]
+    async fn test_sync_does_not_clobber_newer_entries(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let entry = InfobaseRepo::create(&pool, user_id, &new_entry("Gain", "first"))
+            .await
+            .unwrap();
+        InfobaseRepo::update(&pool, entry.id, user_id, &content_update("newer"), None)
+            .await
+            .unwrap();
+
+        let result = InfobaseRepo::sync(
+            &pool,
+            user_id,
+            vec![
+                SyncInfobaseEntryRequest {
+                    id: Some(entry.id),
+                    version: Some(1),
+                    entry: new_entry("Gain", "offline edit"),
+                },
+                SyncInfobaseEntryRequest {
+                    id: None,
+                    version: None,
+                    entry: new_entry("Compression", "new"),
+                },
+            ],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.synced, 1);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].content, "newer");
+        let stored = InfobaseRepo::get(&pool, entry.id, user_id).await.unwrap();
+        assert_eq!(stored.content, "newer");
+    }
+}
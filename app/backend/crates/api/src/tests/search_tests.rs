@@ -0,0 +1,171 @@
+//! Search tests
+//!
+//! Full-text search ranks every content type together, filters by type and
+//! tag, and pages with a cursor.
+
+#[cfg(test)]
+mod tests {
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use crate::db::search_models::SEARCH_TYPES;
+    use crate::db::search_repos::{search_tsquery, SearchCursor, SearchRepo};
+
+    // ========================================================================
+    // TEST HELPERS
+    // ========================================================================
+
+    async fn create_test_user(pool: &PgPool) -> Uuid {
+        let user_id = Uuid::new_v4();
+        sqlx::query(
+            r#"INSERT INTO users (id, email, name, role, approved, age_verified, tos_accepted)
+               VALUES ($1, $2, 'Test Search User', 'user', true, true, true)"#,
+        )
+        .bind(user_id)
+        .bind(format!("test-search-{}@example.com", user_id))
+        .execute(pool)
+        .await
+        .expect("Failed to create test user");
+        user_id
+    }
+
+    async fn create_track(pool: &PgPool, user_id: Uuid, title: &str, artist: &str) -> Uuid {
+        let (track_id,): (Uuid,) = sqlx::query_as(
+            r#"INSERT INTO reference_tracks (user_id, title, artist, r2_key, tags)
+               VALUES ($1, $2, $3, 'test/key', ARRAY['reference'])
+               RETURNING id"#,
+        )
+        .bind(user_id)
+        .bind(title)
+        .bind(artist)
+        .fetch_one(pool)
+        .await
+        .expect("Failed to create track");
+        track_id
+    }
+
+    fn all_types() -> Vec<String> {
+        SEARCH_TYPES.iter().map(|t| t.to_string()).collect()
+    }
+
+    // ========================================================================
+    // TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_search_across_types(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let other_user = create_test_user(&pool).await;
+
+        sqlx::query(
+            r#"INSERT INTO ideas (user_id, title, content, tags, is_pinned)
+               VALUES ($1, 'Sidechain the pads', 'Pump the pads against the kick',
+                       ARRAY['mixing'], false)"#,
+        )
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"INSERT INTO infobase_entries (user_id, title, content, category, tags, is_pinned)
+               VALUES ($1, 'Compression basics', 'Sidechaining ducks one sound under another.',
+                       'Tips', ARRAY['mixing'], false)"#,
+        )
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let track_id = create_track(&pool, user_id, "Strobe", "deadmau5").await;
+        sqlx::query(
+            r#"INSERT INTO track_annotations (track_id, user_id, start_time_seconds, title, content)
+               VALUES ($1, $2, 12.5, 'Drop', 'Huge sidechain pumping here')"#,
+        )
+        .bind(track_id)
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        create_track(&pool, other_user, "Sidechain anthem", "someone").await;
+
+        // Prefix match: "sidech" finds sidechain and sidechaining
+        let query = search_tsquery("sidech").unwrap();
+        let results = SearchRepo::search(&pool, user_id, &query, &all_types(), &[], None, 10)
+            .await
+            .unwrap();
+        let types: Vec<&str> = results.iter().map(|r| r.entity_type.as_str()).collect();
+        assert_eq!(results.len(), 3, "{:?}", types);
+        // A title match outranks body matches
+        assert_eq!(results[0].entity_type, "idea");
+        assert!(results.windows(2).all(|w| w[0].rank >= w[1].rank));
+
+        let annotation = results
+            .iter()
+            .find(|r| r.entity_type == "annotation")
+            .unwrap();
+        assert_eq!(annotation.parent_id, Some(track_id));
+        assert!(annotation.snippet.contains("<mark>"));
+
+        // Artist is searchable on tracks
+        let query = search_tsquery("deadmau5").unwrap();
+        let tracks = SearchRepo::search(
+            &pool,
+            user_id,
+            &query,
+            &["track".to_string()],
+            &[],
+            None,
+            10,
+        )
+        .await
+        .unwrap();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].id, track_id);
+
+        // Tag filter
+        let query = search_tsquery("sidechain").unwrap();
+        let tagged = SearchRepo::search(
+            &pool,
+            user_id,
+            &query,
+            &all_types(),
+            &["mixing".to_string()],
+            None,
+            10,
+        )
+        .await
+        .unwrap();
+        assert_eq!(tagged.len(), 2);
+        assert!(tagged
+            .iter()
+            .all(|r| r.tags.contains(&"mixing".to_string())));
+    }
+
+    #[sqlx::test]
+    async fn test_cursor_paging(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        for i in 0..5 {
+            create_track(&pool, user_id, &format!("Groove {}", i), "Various").await;
+        }
+
+        let query = search_tsquery("groove").unwrap();
+        let mut seen = Vec::new();
+        let mut after: Option<SearchCursor> = None;
+        loop {
+            let page =
+                SearchRepo::search(&pool, user_id, &query, &all_types(), &[], after.as_ref(), 2)
+                    .await
+                    .unwrap();
+            let Some(last) = page.last() else { break };
+            after = Some(SearchCursor {
+                rank: last.rank,
+                entity_type: last.entity_type.clone(),
+                id: last.id,
+            });
+            seen.extend(page.iter().map(|r| r.id));
+        }
+
+        seen.sort();
+        seen.dedup();
+        assert_eq!(seen.len(), 5);
+    }
+}
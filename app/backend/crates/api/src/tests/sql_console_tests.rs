@@ -0,0 +1,230 @@
+//! SQL console tests
+//!
+//! Console queries are read-only, time-bounded, run logged in as the console
+//! role and are paged through cursors owned by the admin who ran them.
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use serde_json::json;
+    use sqlx::postgres::PgPoolOptions;
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use crate::config::SqlConsoleConfig;
+    use crate::db::admin_repos::AdminConsoleRepo;
+    use crate::error::AppError;
+    use crate::services::sql_console::SqlConsole;
+
+    // ========================================================================
+    // TEST HELPERS
+    // ========================================================================
+
+    async fn create_test_user(pool: &PgPool) -> Uuid {
+        let user_id = Uuid::new_v4();
+        let email = format!("test-console-{}@example.com", user_id);
+
+        sqlx::query(
+            r#"INSERT INTO users (id, email, name, role, approved, age_verified, tos_accepted)
+               VALUES ($1, $2, 'Test Console Admin', 'admin', true, true, true)"#,
+        )
+        .bind(user_id)
+        .bind(&email)
+        .execute(pool)
+        .await
+        .expect("Failed to create test user");
+
+        user_id
+    }
+
+    /// Console over a pool logged in as the console role, as in production
+    async fn console(pool: &PgPool) -> SqlConsole {
+        console_with(
+            pool,
+            SqlConsoleConfig {
+                statement_timeout_ms: 200,
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    async fn console_with(pool: &PgPool, config: SqlConsoleConfig) -> SqlConsole {
+        sqlx::query("ALTER ROLE ignition_console PASSWORD 'console-test'")
+            .execute(pool)
+            .await
+            .expect("Failed to set console role password");
+        let options = (*pool.connect_options())
+            .clone()
+            .username("ignition_console")
+            .password("console-test");
+        let console_pool = PgPoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await
+            .expect("Failed to log in as the console role");
+
+        SqlConsole::new(&config, Some(console_pool))
+    }
+
+    const SERIES: &str = r#"
+        SELECT g AS n, g % 2 = 0 AS even, jsonb_build_object('g', g) AS doc
+        FROM generate_series(1, 5) g
+    "#;
+
+    // ========================================================================
+    // PAGING
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_results_page_through_cursor(pool: PgPool) {
+        let console = console(&pool).await;
+        let admin = Uuid::new_v4();
+
+        let first = console.run(admin, SERIES, Some(2)).await.unwrap();
+        let types: Vec<&str> = first.columns.iter().map(|c| c.type_name.as_str()).collect();
+        assert_eq!(types, ["int4", "bool", "jsonb"]);
+        assert_eq!(first.rows[1], vec![json!(2), json!(true), json!({"g": 2})]);
+        assert!(first.has_more);
+        let cursor = first.cursor.expect("cursor stays open");
+
+        // Only the admin who ran the query can read on
+        let other = console.next_page(Uuid::new_v4(), cursor, Some(2)).await;
+        assert!(matches!(other, Err(AppError::NotFound(_))));
+
+        let second = console.next_page(admin, cursor, Some(2)).await.unwrap();
+        assert_eq!(second.rows[0][0], json!(3));
+        let cursor = second.cursor.expect("one row left");
+
+        let last = console.next_page(admin, cursor, Some(2)).await.unwrap();
+        assert_eq!(last.count, 1);
+        assert!(!last.has_more);
+        assert!(last.cursor.is_none());
+        assert!(!console.close(admin, cursor));
+    }
+
+    #[sqlx::test]
+    async fn test_closed_cursor_cannot_be_read(pool: PgPool) {
+        let console = console(&pool).await;
+        let admin = Uuid::new_v4();
+
+        let page = console.run(admin, SERIES, Some(1)).await.unwrap();
+        let cursor = page.cursor.unwrap();
+        assert!(console.close(admin, cursor));
+
+        let next = console.next_page(admin, cursor, None).await;
+        assert!(matches!(next, Err(AppError::NotFound(_))));
+    }
+
+    #[sqlx::test]
+    async fn test_exports_count_against_open_cursors(pool: PgPool) {
+        let console = console_with(
+            &pool,
+            SqlConsoleConfig {
+                max_open_cursors: 1,
+                ..Default::default()
+            },
+        )
+        .await;
+        let admin = Uuid::new_v4();
+
+        let export = console.export_csv(SERIES).await.unwrap();
+        let blocked = console.run(admin, SERIES, Some(1)).await;
+        assert!(matches!(blocked, Err(AppError::BadRequest(_))));
+
+        let csv: Vec<String> = export.map(|chunk| chunk.unwrap()).collect().await;
+        assert_eq!(csv[0], "n,even,doc\r\n");
+        assert!(console.run(admin, SERIES, Some(1)).await.is_ok());
+    }
+
+    // ========================================================================
+    // SAFETY
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_writes_and_secrets_are_refused(pool: PgPool) {
+        let console = console(&pool).await;
+        let admin = create_test_user(&pool).await;
+
+        for sql in [
+            "DELETE FROM users",
+            "UPDATE users SET name = 'x' RETURNING id",
+            "SELECT 1; DELETE FROM users",
+            "SELECT token FROM sessions",
+            "SELECT set_config('role', 'postgres', true)",
+            r#"SELECT U&"set\005fconfig"('role', 'none', true), count(*) FROM sessions"#,
+        ] {
+            let result = console.run(admin, sql, None).await;
+            assert!(
+                matches!(
+                    result,
+                    Err(AppError::BadRequest(_) | AppError::Validation(_))
+                ),
+                "{} was not refused",
+                sql
+            );
+        }
+
+        let users: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE name = 'Test Console Admin'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(users, 1);
+    }
+
+    #[sqlx::test]
+    async fn test_statement_timeout_stops_slow_queries(pool: PgPool) {
+        let console = console(&pool).await;
+
+        let result = console
+            .run(Uuid::new_v4(), "SELECT pg_sleep(2)", None)
+            .await;
+
+        match result {
+            Err(AppError::BadRequest(message)) => assert!(message.contains("timeout")),
+            other => panic!("expected a timeout, got {:?}", other.map(|p| p.count)),
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_explain_returns_json_plan(pool: PgPool) {
+        let console = console(&pool).await;
+
+        let plan = console.explain(SERIES, true).await.unwrap();
+
+        assert!(plan.plan[0]["Plan"]["Actual Rows"].is_number());
+    }
+
+    // ========================================================================
+    // SAVED QUERIES
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_saved_queries_replace_by_name(pool: PgPool) {
+        let admin = create_test_user(&pool).await;
+        let other = create_test_user(&pool).await;
+
+        let first = AdminConsoleRepo::save(&pool, admin, "users", "SELECT 1")
+            .await
+            .unwrap();
+        let replaced = AdminConsoleRepo::save(&pool, admin, "users", "SELECT 2")
+            .await
+            .unwrap();
+        assert_eq!(replaced.id, first.id);
+        assert_eq!(replaced.sql, "SELECT 2");
+        AdminConsoleRepo::save(&pool, other, "users", "SELECT 3")
+            .await
+            .unwrap();
+
+        let saved = AdminConsoleRepo::list_saved(&pool, admin).await.unwrap();
+        assert_eq!(saved.len(), 1);
+
+        assert!(!AdminConsoleRepo::delete_saved(&pool, first.id, other)
+            .await
+            .unwrap());
+        assert!(AdminConsoleRepo::delete_saved(&pool, first.id, admin)
+            .await
+            .unwrap());
+    }
+}
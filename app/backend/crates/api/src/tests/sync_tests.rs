@@ -0,0 +1,222 @@
+//! Sync tests
+//!
+//! Change feed recorded by triggers, cursors used to resume streams and the
+//! NOTIFY payload the sync hub decodes.
+
+#[cfg(test)]
+mod tests {
+    use sqlx::postgres::PgListener;
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use crate::db::inbox_models::CreateInboxRequest;
+    use crate::db::inbox_repos::InboxRepo;
+    use crate::db::sync_models::*;
+    use crate::db::sync_repos::SyncEventRepo;
+
+    // ========================================================================
+    // TEST HELPERS
+    // ========================================================================
+
+    async fn create_test_user(pool: &PgPool) -> Uuid {
+        let user_id = Uuid::new_v4();
+        let email = format!("test-sync-{}@example.com", user_id);
+
+        sqlx::query(
+            r#"INSERT INTO users (id, email, name, role, approved, age_verified, tos_accepted)
+               VALUES ($1, $2, 'Test Sync User', 'user', true, true, true)"#,
+        )
+        .bind(user_id)
+        .bind(&email)
+        .execute(pool)
+        .await
+        .expect("Failed to create test user");
+
+        user_id
+    }
+
+    async fn create_inbox_item(pool: &PgPool, user_id: Uuid, title: &str) -> Uuid {
+        InboxRepo::create(
+            pool,
+            user_id,
+            &CreateInboxRequest {
+                item_type: "notification".to_string(),
+                title: title.to_string(),
+                body: None,
+                action_url: None,
+                action_data: None,
+                priority: None,
+                expires_at: None,
+            },
+        )
+        .await
+        .expect("Failed to create inbox item")
+        .id
+    }
+
+    // ========================================================================
+    // CHANGE FEED TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_inbox_insert_records_event_with_item(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let item_id = create_inbox_item(&pool, user_id, "Welcome").await;
+
+        let events = SyncEventRepo::list_since(&pool, user_id, 0, 0.0, 100)
+            .await
+            .unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, SYNC_KIND_INBOX);
+        assert_eq!(events[0].ref_id, Some(item_id));
+        assert_eq!(
+            SyncEventRepo::latest_id(&pool, user_id).await.unwrap(),
+            events[0].id
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_events_are_per_user_and_after_cursor(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let other_id = create_test_user(&pool).await;
+
+        create_inbox_item(&pool, user_id, "First").await;
+        let cursor = SyncEventRepo::latest_id(&pool, user_id).await.unwrap();
+        create_inbox_item(&pool, other_id, "Someone else's").await;
+        create_inbox_item(&pool, user_id, "Second").await;
+
+        let events = SyncEventRepo::list_since(&pool, user_id, cursor, 0.0, 100)
+            .await
+            .unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert!(events[0].id > cursor);
+        assert!(events.iter().all(|e| e.user_id == user_id));
+    }
+
+    #[sqlx::test]
+    async fn test_recent_events_behind_cursor_are_read_again(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        create_inbox_item(&pool, user_id, "First").await;
+        let cursor = SyncEventRepo::latest_id(&pool, user_id).await.unwrap();
+        create_inbox_item(&pool, user_id, "Second").await;
+
+        // Within the grace window the event at the cursor may have committed late
+        let events = SyncEventRepo::list_since(&pool, user_id, cursor, 60.0, 100)
+            .await
+            .unwrap();
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].id, cursor);
+    }
+
+    #[sqlx::test]
+    async fn test_updates_record_events_without_ref(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let item_id = create_inbox_item(&pool, user_id, "Read me").await;
+        let cursor = SyncEventRepo::latest_id(&pool, user_id).await.unwrap();
+
+        sqlx::query("UPDATE inbox_items SET is_read = true WHERE id = $1")
+            .bind(item_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let events = SyncEventRepo::list_since(&pool, user_id, cursor, 0.0, 100)
+            .await
+            .unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, SYNC_KIND_INBOX);
+        assert_eq!(events[0].ref_id, None);
+    }
+
+    #[sqlx::test]
+    async fn test_deleting_user_with_synced_rows(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        create_inbox_item(&pool, user_id, "Goodbye").await;
+
+        sqlx::query("DELETE FROM users WHERE id = $1")
+            .bind(user_id)
+            .execute(&pool)
+            .await
+            .expect("Cascading delete should not record events for the deleted user");
+
+        assert_eq!(SyncEventRepo::latest_id(&pool, user_id).await.unwrap(), 0);
+    }
+
+    // ========================================================================
+    // CURSOR TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_cursor_is_resumable(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        create_inbox_item(&pool, user_id, "One").await;
+        create_inbox_item(&pool, user_id, "Two").await;
+
+        let latest = SyncEventRepo::latest_id(&pool, user_id).await.unwrap();
+
+        assert!(SyncEventRepo::cursor_is_resumable(&pool, latest)
+            .await
+            .unwrap());
+        assert!(SyncEventRepo::cursor_is_resumable(&pool, latest - 1)
+            .await
+            .unwrap());
+        // Issued by some other database
+        assert!(!SyncEventRepo::cursor_is_resumable(&pool, latest + 1000)
+            .await
+            .unwrap());
+    }
+
+    #[sqlx::test]
+    async fn test_pruned_cursor_is_not_resumable(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        create_inbox_item(&pool, user_id, "Old").await;
+        let cursor = SyncEventRepo::latest_id(&pool, user_id).await.unwrap();
+        create_inbox_item(&pool, user_id, "Also old").await;
+        create_inbox_item(&pool, user_id, "Recent").await;
+
+        // Age everything but the latest event past the retention window
+        sqlx::query(
+            r#"UPDATE sync_events SET created_at = NOW() - INTERVAL '2 days'
+               WHERE id < (SELECT MAX(id) FROM sync_events)"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let pruned = SyncEventRepo::prune(&pool).await.unwrap();
+        assert_eq!(pruned, 2);
+
+        assert!(!SyncEventRepo::cursor_is_resumable(&pool, cursor)
+            .await
+            .unwrap());
+    }
+
+    // ========================================================================
+    // NOTIFY TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_notification_payload_decodes(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+
+        let mut listener = PgListener::connect_with(&pool).await.unwrap();
+        listener.listen(SYNC_EVENTS_CHANNEL).await.unwrap();
+
+        let item_id = create_inbox_item(&pool, user_id, "Live").await;
+
+        let notification = listener.recv().await.unwrap();
+        let event: SyncEvent = serde_json::from_str(notification.payload()).unwrap();
+
+        assert_eq!(event.user_id, user_id);
+        assert_eq!(event.kind, SYNC_KIND_INBOX);
+        assert_eq!(event.ref_id, Some(item_id));
+        assert_eq!(
+            event.id,
+            SyncEventRepo::latest_id(&pool, user_id).await.unwrap()
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//! Universal quests tests
+//!
+//! Progress driven by points ledger events, claiming rewards and period resets.
+
+#[cfg(test)]
+mod tests {
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use crate::db::gamification_models::AwardPointsInput;
+    use crate::db::gamification_repos::{GamificationRepo, UserWalletRepo};
+    use crate::db::quests_models::UniversalQuestWithProgress;
+    use crate::db::quests_repos::UniversalQuestRepo;
+    use crate::error::AppError;
+
+    // ========================================================================
+    // TEST HELPERS
+    // ========================================================================
+
+    async fn create_test_user(pool: &PgPool) -> Uuid {
+        let user_id = Uuid::new_v4();
+        let email = format!("test-universal-quests-{}@example.com", user_id);
+
+        sqlx::query(
+            r#"INSERT INTO users (id, email, name, role, approved, age_verified, tos_accepted)
+               VALUES ($1, $2, 'Test Universal Quests User', 'user', true, true, true)"#,
+        )
+        .bind(user_id)
+        .bind(&email)
+        .execute(pool)
+        .await
+        .expect("Failed to create test user");
+
+        user_id
+    }
+
+    /// A daily quest needing 25 focus minutes
+    async fn create_focus_quest(pool: &PgPool) -> Uuid {
+        sqlx::query_scalar(
+            r#"INSERT INTO universal_quests
+               (title, type, xp_reward, coin_reward, target, target_type, is_active,
+                sort_order, is_recurring, recurrence_period)
+               VALUES ('Test Focus', 'daily', 40, 15, 25, 'focus_minutes', true, 0, true, 'daily')
+               RETURNING id"#,
+        )
+        .fetch_one(pool)
+        .await
+        .expect("Failed to create quest")
+    }
+
+    async fn complete_focus(pool: &PgPool, user_id: Uuid, key: &str, minutes: i32) {
+        GamificationRepo::award_points(
+            pool,
+            user_id,
+            &AwardPointsInput {
+                xp: Some(minutes),
+                coins: Some(2),
+                skill_stars: None,
+                skill_key: None,
+                event_type: "focus_complete".to_string(),
+                event_id: None,
+                reason: None,
+                idempotency_key: Some(key.to_string()),
+                quantity: Some(minutes),
+            },
+        )
+        .await
+        .expect("Failed to award points");
+    }
+
+    async fn find_quest(
+        pool: &PgPool,
+        user_id: Uuid,
+        quest_id: Uuid,
+    ) -> UniversalQuestWithProgress {
+        UniversalQuestRepo::list_for_user(pool, user_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|q| q.quest.id == quest_id)
+            .expect("Quest should be listed")
+    }
+
+    // ========================================================================
+    // PROGRESS TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_focus_event_advances_quest_once(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let quest_id = create_focus_quest(&pool).await;
+
+        complete_focus(&pool, user_id, "focus_a", 10).await;
+        // Retried award is not counted again
+        complete_focus(&pool, user_id, "focus_a", 10).await;
+
+        let quest = find_quest(&pool, user_id, quest_id).await;
+        let progress = quest.progress.expect("Progress should be recorded");
+        assert_eq!(progress.progress, 10);
+        assert_eq!(progress.status, "in_progress");
+        assert!(!quest.is_completed);
+    }
+
+    #[sqlx::test]
+    async fn test_progress_is_capped_at_target(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let quest_id = create_focus_quest(&pool).await;
+
+        complete_focus(&pool, user_id, "focus_a", 20).await;
+        complete_focus(&pool, user_id, "focus_b", 20).await;
+        // Completed quests stop counting until claimed and reset
+        complete_focus(&pool, user_id, "focus_c", 20).await;
+
+        let quest = find_quest(&pool, user_id, quest_id).await;
+        let progress = quest.progress.unwrap();
+        assert_eq!(progress.progress, 25);
+        assert_eq!(progress.status, "completed");
+        assert_eq!(progress.times_completed, 1);
+        assert!(quest.is_completed);
+        assert!(quest.is_available);
+    }
+
+    // ========================================================================
+    // CLAIM TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_claim_before_target_fails(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let quest_id = create_focus_quest(&pool).await;
+
+        complete_focus(&pool, user_id, "focus_a", 5).await;
+
+        let result = UniversalQuestRepo::claim(&pool, user_id, quest_id).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[sqlx::test]
+    async fn test_claim_awards_once(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let quest_id = create_focus_quest(&pool).await;
+
+        complete_focus(&pool, user_id, "focus_a", 30).await;
+        let coins_before = UserWalletRepo::get_or_create(&pool, user_id)
+            .await
+            .unwrap()
+            .coins;
+
+        let first = UniversalQuestRepo::claim(&pool, user_id, quest_id)
+            .await
+            .unwrap();
+        assert!(!first.already_claimed);
+        assert_eq!(first.coins_awarded, 15);
+        assert_eq!(first.progress.status, "claimed");
+
+        let second = UniversalQuestRepo::claim(&pool, user_id, quest_id)
+            .await
+            .unwrap();
+        assert!(second.already_claimed);
+        assert_eq!(second.coins_awarded, 0);
+
+        let coins_after = UserWalletRepo::get_or_create(&pool, user_id)
+            .await
+            .unwrap()
+            .coins;
+        assert_eq!(coins_after - coins_before, 15);
+    }
+
+    // ========================================================================
+    // RESET TESTS
+    // ========================================================================
+
+    #[sqlx::test]
+    async fn test_claimed_quest_resets_next_period(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let quest_id = create_focus_quest(&pool).await;
+
+        complete_focus(&pool, user_id, "focus_a", 30).await;
+        UniversalQuestRepo::claim(&pool, user_id, quest_id)
+            .await
+            .unwrap();
+
+        sqlx::query(
+            r#"UPDATE user_quest_progress SET last_reset_at = NOW() - INTERVAL '2 days'
+               WHERE user_id = $1 AND quest_id = $2"#,
+        )
+        .bind(user_id)
+        .bind(quest_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let quest = find_quest(&pool, user_id, quest_id).await;
+        let progress = quest.progress.unwrap();
+        assert_eq!(progress.progress, 0);
+        assert_eq!(progress.status, "in_progress");
+        assert_eq!(progress.times_completed, 1);
+        assert!(!quest.is_completed);
+
+        // The next completion can be claimed again
+        complete_focus(&pool, user_id, "focus_b", 30).await;
+        let claim = UniversalQuestRepo::claim(&pool, user_id, quest_id)
+            .await
+            .unwrap();
+        assert!(!claim.already_claimed);
+        assert_eq!(claim.progress.times_completed, 2);
+    }
+
+    #[sqlx::test]
+    async fn test_unclaimed_completion_lapses_next_period(pool: PgPool) {
+        let user_id = create_test_user(&pool).await;
+        let quest_id = create_focus_quest(&pool).await;
+
+        complete_focus(&pool, user_id, "focus_a", 30).await;
+
+        sqlx::query(
+            r#"UPDATE user_quest_progress
+               SET last_reset_at = NOW() - INTERVAL '2 days',
+                   completed_at = NOW() - INTERVAL '2 days'
+               WHERE user_id = $1 AND quest_id = $2"#,
+        )
+        .bind(user_id)
+        .bind(quest_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let quest = find_quest(&pool, user_id, quest_id).await;
+        let progress = quest.progress.unwrap();
+        assert_eq!(progress.progress, 0);
+        assert_eq!(progress.status, "in_progress");
+        assert!(!quest.is_completed);
+
+        // Nothing is left to claim, and today's progress counts again
+        let claim = UniversalQuestRepo::claim(&pool, user_id, quest_id).await;
+        assert!(claim.is_err());
+        complete_focus(&pool, user_id, "focus_b", 30).await;
+        let quest = find_quest(&pool, user_id, quest_id).await;
+        assert!(quest.is_completed);
+    }
+}
@@ -0,0 +1,37 @@
+-- 0003_drill_sessions.sql
+-- Server-scored drill sessions
+-- Questions are regenerated from (seed, config snapshot, difficulty_level),
+-- so only the client's answers and timings need to be persisted.
+
+CREATE TABLE drill_sessions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    drill_id UUID NOT NULL REFERENCES learn_drills(id) ON DELETE CASCADE,
+    token TEXT NOT NULL UNIQUE,
+    drill_type TEXT NOT NULL,
+    config_json JSONB NOT NULL,
+    seed BIGINT NOT NULL,
+    difficulty_level INTEGER NOT NULL,
+    question_count INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'active',
+    score INTEGER,
+    correct_count INTEGER,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    completed_at TIMESTAMPTZ
+);
+
+CREATE TABLE drill_session_answers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    session_id UUID NOT NULL REFERENCES drill_sessions(id) ON DELETE CASCADE,
+    question_index INTEGER NOT NULL,
+    answer TEXT NOT NULL,
+    is_correct BOOLEAN NOT NULL,
+    response_ms INTEGER NOT NULL,
+    answered_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT drill_session_answers_unique UNIQUE (session_id, question_index)
+);
+
+CREATE INDEX idx_drill_sessions_user_id ON drill_sessions(user_id);
+CREATE INDEX idx_drill_sessions_drill_id ON drill_sessions(drill_id);
+CREATE INDEX idx_drill_session_answers_session_id ON drill_session_answers(session_id);
@@ -0,0 +1,10 @@
+-- 0004_hashed_session_tokens.sql
+-- Store session tokens as SHA-256 hashes
+-- The cookie keeps the raw token; sessions.token now holds the lowercase hex
+-- digest so a read of the table no longer yields usable credentials.
+
+UPDATE sessions
+SET token = encode(sha256(convert_to(token, 'UTF8')), 'hex')
+WHERE token !~ '^[0-9a-f]{64}$';
+
+DELETE FROM sessions WHERE expires_at < NOW();
@@ -0,0 +1,19 @@
+-- 0005_passkeys.sql
+-- WebAuthn passkey support
+-- Challenges are single-use and short-lived, stored like oauth_states so any
+-- backend instance can complete a ceremony.
+
+CREATE TABLE webauthn_challenges (
+    challenge TEXT PRIMARY KEY,
+    ceremony TEXT NOT NULL,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+ALTER TABLE authenticators ADD COLUMN name TEXT;
+ALTER TABLE authenticators ADD COLUMN last_used_at TIMESTAMPTZ;
+
+CREATE UNIQUE INDEX idx_authenticators_credential_id ON authenticators(credential_id);
+CREATE INDEX idx_authenticators_user_id ON authenticators(user_id);
+CREATE INDEX idx_webauthn_challenges_expires_at ON webauthn_challenges(expires_at);
@@ -0,0 +1,7 @@
+-- 0006_oidc_providers.sql
+-- Generic OpenID Connect providers
+-- OAuth state now records which provider started the flow and the OIDC nonce
+-- that must come back in the ID token.
+
+ALTER TABLE oauth_states ADD COLUMN provider TEXT;
+ALTER TABLE oauth_states ADD COLUMN nonce TEXT;
@@ -0,0 +1,96 @@
+-- 0008_schema_alignment.sql
+-- Bring tables in line with the repositories that use them
+-- Found by `ignition-api check-queries`: these columns are read and written by
+-- the inbox, feedback, admin quest/skill editors and reference tracks, but were
+-- missing from the generated schema.
+
+-- Inbox: notifications and action items
+ALTER TABLE inbox_items ADD COLUMN body TEXT;
+ALTER TABLE inbox_items ADD COLUMN action_url TEXT;
+ALTER TABLE inbox_items ADD COLUMN action_data JSONB;
+ALTER TABLE inbox_items ADD COLUMN priority INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE inbox_items ADD COLUMN is_read BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE inbox_items ADD COLUMN is_archived BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE inbox_items ADD COLUMN expires_at TIMESTAMPTZ;
+ALTER TABLE inbox_items ALTER COLUMN is_processed SET DEFAULT false;
+
+UPDATE inbox_items SET body = description WHERE body IS NULL;
+UPDATE inbox_items SET is_read = true, is_archived = true WHERE is_processed;
+
+CREATE INDEX idx_inbox_items_user_unread ON inbox_items(user_id) WHERE NOT is_read AND NOT is_archived;
+
+-- Feedback: who resolved it
+ALTER TABLE feedback ADD COLUMN resolved_by UUID REFERENCES users(id) ON DELETE SET NULL;
+
+-- Universal quests: admin editor fields
+ALTER TABLE universal_quests ADD COLUMN key TEXT UNIQUE;
+ALTER TABLE universal_quests ADD COLUMN category TEXT;
+ALTER TABLE universal_quests ADD COLUMN skill_star_reward INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE universal_quests ADD COLUMN is_recurring BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE universal_quests ADD COLUMN recurrence_period TEXT;
+ALTER TABLE universal_quests ALTER COLUMN target SET DEFAULT 1;
+ALTER TABLE universal_quests ALTER COLUMN target_type SET DEFAULT 'manual';
+
+UPDATE universal_quests SET is_recurring = true, recurrence_period = type
+WHERE type IN ('daily', 'weekly');
+
+-- Skill definitions: admin editor fields
+ALTER TABLE skill_definitions ADD COLUMN color TEXT NOT NULL DEFAULT '#8b5cf6';
+ALTER TABLE skill_definitions ADD COLUMN xp_scaling_base INTEGER NOT NULL DEFAULT 100;
+ALTER TABLE skill_definitions ADD COLUMN xp_scaling_multiplier DOUBLE PRECISION NOT NULL DEFAULT 1.5;
+ALTER TABLE skill_definitions ADD COLUMN is_active BOOLEAN NOT NULL DEFAULT true;
+ALTER TABLE skill_definitions ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE skill_definitions ALTER COLUMN category SET DEFAULT 'general';
+ALTER TABLE skill_definitions ALTER COLUMN stars_per_level SET DEFAULT 10;
+
+-- Reference tracks: the library stores titles, keys and source metadata
+ALTER TABLE reference_tracks RENAME COLUMN name TO title;
+ALTER TABLE reference_tracks RENAME COLUMN key_signature TO key;
+ALTER TABLE reference_tracks ADD COLUMN waveform_r2_key TEXT;
+ALTER TABLE reference_tracks ADD COLUMN thumbnail_r2_key TEXT;
+ALTER TABLE reference_tracks ADD COLUMN file_format TEXT;
+ALTER TABLE reference_tracks ADD COLUMN sample_rate INTEGER;
+ALTER TABLE reference_tracks ADD COLUMN bit_depth INTEGER;
+ALTER TABLE reference_tracks ADD COLUMN channels INTEGER;
+ALTER TABLE reference_tracks ADD COLUMN is_reference BOOLEAN NOT NULL DEFAULT true;
+ALTER TABLE reference_tracks ADD COLUMN is_user_upload BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE reference_tracks ADD COLUMN source TEXT;
+ALTER TABLE reference_tracks ADD COLUMN source_url TEXT;
+ALTER TABLE reference_tracks ADD COLUMN metadata JSONB;
+ALTER TABLE reference_tracks ALTER COLUMN file_size_bytes SET DEFAULT 0;
+ALTER TABLE reference_tracks ALTER COLUMN mime_type SET DEFAULT 'application/octet-stream';
+ALTER TABLE reference_tracks ALTER COLUMN status SET DEFAULT 'ready';
+
+ALTER TABLE track_analyses ADD COLUMN parameters JSONB;
+ALTER TABLE track_analyses ADD COLUMN results JSONB;
+ALTER TABLE track_analyses ALTER COLUMN version SET DEFAULT '1';
+
+-- Annotations and regions are positioned in seconds
+ALTER TABLE track_annotations ADD COLUMN start_time_seconds REAL;
+ALTER TABLE track_annotations ADD COLUMN end_time_seconds REAL;
+UPDATE track_annotations
+SET start_time_seconds = start_time_ms / 1000.0, end_time_seconds = end_time_ms / 1000.0;
+ALTER TABLE track_annotations ALTER COLUMN start_time_seconds SET NOT NULL;
+ALTER TABLE track_annotations DROP COLUMN start_time_ms;
+ALTER TABLE track_annotations DROP COLUMN end_time_ms;
+ALTER TABLE track_annotations RENAME COLUMN category TO annotation_type;
+UPDATE track_annotations SET annotation_type = 'note' WHERE annotation_type IS NULL;
+ALTER TABLE track_annotations ALTER COLUMN annotation_type SET DEFAULT 'note';
+ALTER TABLE track_annotations ALTER COLUMN annotation_type SET NOT NULL;
+ALTER TABLE track_annotations ALTER COLUMN title DROP NOT NULL;
+ALTER TABLE track_annotations ALTER COLUMN is_private SET DEFAULT true;
+ALTER TABLE track_annotations ADD COLUMN tags TEXT[];
+
+ALTER TABLE track_regions ADD COLUMN start_time_seconds REAL;
+ALTER TABLE track_regions ADD COLUMN end_time_seconds REAL;
+UPDATE track_regions
+SET start_time_seconds = start_time_ms / 1000.0, end_time_seconds = end_time_ms / 1000.0;
+ALTER TABLE track_regions ALTER COLUMN start_time_seconds SET NOT NULL;
+ALTER TABLE track_regions ALTER COLUMN end_time_seconds SET NOT NULL;
+ALTER TABLE track_regions DROP COLUMN start_time_ms;
+ALTER TABLE track_regions DROP COLUMN end_time_ms;
+ALTER TABLE track_regions RENAME COLUMN section_type TO region_type;
+ALTER TABLE track_regions RENAME COLUMN description TO notes;
+ALTER TABLE track_regions ALTER COLUMN display_order SET DEFAULT 0;
+ALTER TABLE track_regions ADD COLUMN loop_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE track_regions ADD COLUMN is_favorite BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,93 @@
+-- 0009_sync_events.sql
+-- Change feed behind /api/sync/stream
+-- Triggers on the tables shown in the sync HUD append a row per change and
+-- NOTIFY the 'sync_events' channel, so every API instance hears about writes
+-- made by any other. The row id is the client's resume cursor.
+
+CREATE TABLE sync_events (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    ref_id UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_sync_events_user_id ON sync_events(user_id, id);
+CREATE INDEX idx_sync_events_created_at ON sync_events(created_at);
+
+-- Notify listeners after commit. The payload is the event row, so live
+-- delivery needs no extra reads; reconnects replay from the table.
+CREATE FUNCTION notify_sync_event() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('sync_events', json_build_object(
+        'id', NEW.id,
+        'user_id', NEW.user_id,
+        'kind', NEW.kind,
+        'ref_id', NEW.ref_id
+    )::text);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER sync_events_notify
+AFTER INSERT ON sync_events
+FOR EACH ROW EXECUTE FUNCTION notify_sync_event();
+
+-- Record a change of kind TG_ARGV[0] for the affected row's user.
+-- New inbox items also carry their id so the stream can push the item itself.
+CREATE FUNCTION record_sync_event() RETURNS trigger AS $$
+DECLARE
+    affected RECORD;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        affected := OLD;
+    ELSE
+        affected := NEW;
+    END IF;
+
+    -- Skip rows removed by a cascading user delete
+    INSERT INTO sync_events (user_id, kind, ref_id)
+    SELECT
+        affected.user_id,
+        TG_ARGV[0],
+        CASE WHEN TG_OP = 'INSERT' AND TG_TABLE_NAME = 'inbox_items' THEN affected.id END
+    WHERE EXISTS (SELECT 1 FROM users WHERE id = affected.user_id);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER focus_sessions_sync
+AFTER INSERT OR UPDATE OR DELETE ON focus_sessions
+FOR EACH ROW EXECUTE FUNCTION record_sync_event('focus');
+
+CREATE TRIGGER user_progress_sync
+AFTER INSERT OR UPDATE ON user_progress
+FOR EACH ROW EXECUTE FUNCTION record_sync_event('progress');
+
+CREATE TRIGGER user_wallet_sync
+AFTER INSERT OR UPDATE ON user_wallet
+FOR EACH ROW EXECUTE FUNCTION record_sync_event('progress');
+
+CREATE TRIGGER user_streaks_sync
+AFTER INSERT OR UPDATE ON user_streaks
+FOR EACH ROW EXECUTE FUNCTION record_sync_event('progress');
+
+CREATE TRIGGER inbox_items_sync
+AFTER INSERT OR UPDATE OR DELETE ON inbox_items
+FOR EACH ROW EXECUTE FUNCTION record_sync_event('inbox');
+
+CREATE TRIGGER user_quests_sync
+AFTER INSERT OR UPDATE OR DELETE ON user_quests
+FOR EACH ROW EXECUTE FUNCTION record_sync_event('badges');
+
+CREATE TRIGGER habits_sync
+AFTER INSERT OR UPDATE OR DELETE ON habits
+FOR EACH ROW EXECUTE FUNCTION record_sync_event('badges');
+
+CREATE TRIGGER habit_completions_sync
+AFTER INSERT OR UPDATE OR DELETE ON habit_completions
+FOR EACH ROW EXECUTE FUNCTION record_sync_event('badges');
+
+CREATE TRIGGER daily_plans_sync
+AFTER INSERT OR UPDATE OR DELETE ON daily_plans
+FOR EACH ROW EXECUTE FUNCTION record_sync_event('plan');
@@ -0,0 +1,28 @@
+-- 0010_quest_progress.sql
+-- Event-driven progress for universal quests
+-- Progress rows are created on the first matching points_ledger event, so each
+-- user has at most one row per quest; recurring quests reset in place.
+
+-- Ledger rows record one currency at a time; the other defaults to zero
+ALTER TABLE points_ledger ALTER COLUMN coins SET DEFAULT 0;
+ALTER TABLE points_ledger ALTER COLUMN xp SET DEFAULT 0;
+
+CREATE INDEX idx_points_ledger_idempotency_key ON points_ledger(idempotency_key)
+WHERE idempotency_key IS NOT NULL;
+
+-- One progress row per user and quest (keep the most recently updated)
+DELETE FROM user_quest_progress p
+USING user_quest_progress newer
+WHERE p.user_id = newer.user_id
+  AND p.quest_id = newer.quest_id
+  AND (p.updated_at, p.id) < (newer.updated_at, newer.id);
+
+ALTER TABLE user_quest_progress ALTER COLUMN status SET DEFAULT 'in_progress';
+ALTER TABLE user_quest_progress ALTER COLUMN progress SET DEFAULT 0;
+ALTER TABLE user_quest_progress ALTER COLUMN times_completed SET DEFAULT 0;
+ALTER TABLE user_quest_progress
+    ADD CONSTRAINT user_quest_progress_user_quest_key UNIQUE (user_id, quest_id);
+
+-- Monthly quests recur too (0008 only marked daily and weekly ones)
+UPDATE universal_quests SET is_recurring = true, recurrence_period = type
+WHERE type = 'monthly' AND recurrence_period IS NULL;
@@ -0,0 +1,91 @@
+-- 0011_notifications.sql
+-- Reminder delivery: push subscriptions, the delivery outbox and per-user preferences
+-- The scheduler materializes due reminders into notification_deliveries (one row
+-- per channel, deduplicated by dedupe_key) and works through pending rows,
+-- recording attempts and the last error for retries.
+
+CREATE TABLE push_subscriptions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    endpoint TEXT NOT NULL UNIQUE,
+    p256dh TEXT NOT NULL,
+    auth TEXT NOT NULL,
+    user_agent TEXT,
+    last_used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_push_subscriptions_user_id ON push_subscriptions(user_id);
+
+CREATE TABLE notification_deliveries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    ref_id UUID,
+    dedupe_key TEXT NOT NULL,
+    channel TEXT NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT,
+    action_url TEXT,
+    due_at TIMESTAMPTZ NOT NULL,
+    -- Pending rows past this point are skipped rather than sent late
+    expires_at TIMESTAMPTZ,
+    status TEXT NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL,
+    last_error TEXT,
+    sent_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (dedupe_key, channel)
+);
+
+CREATE INDEX idx_notification_deliveries_pending ON notification_deliveries(next_attempt_at)
+WHERE status = 'pending';
+CREATE INDEX idx_notification_deliveries_user_id ON notification_deliveries(user_id, created_at);
+
+CREATE INDEX idx_calendar_events_reminders ON calendar_events(user_id, start_time)
+WHERE reminder_minutes IS NOT NULL;
+
+-- Delivery preferences resolved from user_settings, with the same defaults as
+-- UserSettingsResponse. Unknown timezones fall back to UTC and malformed
+-- times are ignored, so a bad setting cannot stall the scheduler.
+CREATE VIEW notification_preferences AS
+SELECT
+    p.*,
+    p.quiet_hours_start IS NOT NULL
+        AND p.quiet_hours_end IS NOT NULL
+        AND p.quiet_hours_start <> p.quiet_hours_end
+        AND CASE
+            WHEN p.quiet_hours_start < p.quiet_hours_end
+                THEN p.local_now::time >= p.quiet_hours_start
+                    AND p.local_now::time < p.quiet_hours_end
+            -- Window wraps past midnight
+            ELSE p.local_now::time >= p.quiet_hours_start
+                OR p.local_now::time < p.quiet_hours_end
+        END AS in_quiet_hours
+FROM (
+    SELECT
+        u.id AS user_id,
+        u.email,
+        COALESCE(CASE WHEN jsonb_typeof(s.settings->'notifications_enabled') = 'boolean'
+            THEN (s.settings->'notifications_enabled')::boolean END, true) AS notifications_enabled,
+        COALESCE(CASE WHEN jsonb_typeof(s.settings->'email_notifications') = 'boolean'
+            THEN (s.settings->'email_notifications')::boolean END, true) AS email_notifications,
+        COALESCE(CASE WHEN jsonb_typeof(s.settings->'push_notifications') = 'boolean'
+            THEN (s.settings->'push_notifications')::boolean END, false) AS push_notifications,
+        COALESCE(tz.name, 'UTC') AS timezone,
+        CASE WHEN s.settings->>'daily_reminder_time' ~ '^([01]?[0-9]|2[0-3]):[0-5][0-9]$'
+            THEN (s.settings->>'daily_reminder_time')::time END AS daily_reminder_time,
+        CASE WHEN s.settings->>'quiet_hours_start' ~ '^([01]?[0-9]|2[0-3]):[0-5][0-9]$'
+            THEN (s.settings->>'quiet_hours_start')::time END AS quiet_hours_start,
+        CASE WHEN s.settings->>'quiet_hours_end' ~ '^([01]?[0-9]|2[0-3]):[0-5][0-9]$'
+            THEN (s.settings->>'quiet_hours_end')::time END AS quiet_hours_end,
+        NOW() AT TIME ZONE COALESCE(tz.name, 'UTC') AS local_now
+    FROM users u
+    LEFT JOIN (
+        SELECT user_id, jsonb_object_agg(key, value) AS settings
+        FROM user_settings
+        GROUP BY user_id
+    ) s ON s.user_id = u.id
+    LEFT JOIN pg_timezone_names tz ON tz.name = s.settings->>'timezone'
+) p;
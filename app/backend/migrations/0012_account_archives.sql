@@ -0,0 +1,29 @@
+-- 0012_account_archives.sql
+-- Background jobs for account export archives and their import
+-- An export job writes a versioned ZIP to the user's exports prefix and
+-- delivers a signed download link to the inbox; an import job restores such
+-- an archive into a fresh account. Jobs interrupted by a restart are failed
+-- on startup.
+
+CREATE TABLE account_archive_jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('export', 'import')),
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'running', 'completed', 'failed')),
+    -- Archive written (export) or read (import)
+    storage_key TEXT,
+    size_bytes BIGINT,
+    -- Rows per table and blob count
+    summary JSONB,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_account_archive_jobs_user_id ON account_archive_jobs(user_id, created_at);
+
+-- One export or import at a time per user
+CREATE UNIQUE INDEX idx_account_archive_jobs_active ON account_archive_jobs(user_id)
+WHERE status IN ('pending', 'running');
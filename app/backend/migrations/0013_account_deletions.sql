@@ -0,0 +1,29 @@
+-- 0013_account_deletions.sql
+-- Scheduled account deletion with a grace period and storage purge
+-- Deleting an account records a row here instead of removing the user. Until
+-- purge_after the owner can cancel; after it the purger removes every stored
+-- object under the user's prefix, then the user's rows, and keeps this row as
+-- the report of what was removed. A purge that fails or is interrupted is
+-- retried once its lease runs out.
+
+CREATE TABLE account_deletions (
+    -- No foreign key: the row outlives the user it describes
+    user_id UUID PRIMARY KEY,
+    status TEXT NOT NULL DEFAULT 'scheduled'
+        CHECK (status IN ('scheduled', 'purging', 'completed', 'cancelled')),
+    -- Admin who forced the purge; NULL when the owner asked for deletion
+    requested_by UUID,
+    requested_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    purge_after TIMESTAMPTZ NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    leased_until TIMESTAMPTZ,
+    objects_removed INTEGER NOT NULL DEFAULT 0,
+    -- Rows removed per table
+    rows_removed JSONB,
+    error TEXT,
+    cancelled_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_account_deletions_due ON account_deletions(purge_after)
+WHERE status IN ('scheduled', 'purging');
@@ -0,0 +1,47 @@
+-- 0014_sql_console.sql
+-- Read-only SQL console for the admin panel
+-- Console queries run in a READ ONLY transaction as ignition_console, a
+-- NOLOGIN role that can only read. Tables holding credentials are not
+-- readable at all, so a query cannot leak session or OAuth tokens even
+-- through a bug in the console itself. The API's own role is granted the
+-- console role so it can SET ROLE into it for the length of a transaction.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_roles WHERE rolname = 'ignition_console') THEN
+        CREATE ROLE ignition_console NOLOGIN NOINHERIT;
+    END IF;
+EXCEPTION
+    -- Another database on the cluster created it first
+    WHEN duplicate_object THEN NULL;
+    -- Without CREATEROLE the console reports the role as unavailable
+    WHEN insufficient_privilege THEN
+        RAISE NOTICE 'ignition_console role not created: insufficient privilege';
+END
+$$;
+
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM pg_roles WHERE rolname = 'ignition_console') THEN
+        GRANT USAGE ON SCHEMA public TO ignition_console;
+        GRANT SELECT ON ALL TABLES IN SCHEMA public TO ignition_console;
+        ALTER DEFAULT PRIVILEGES IN SCHEMA public GRANT SELECT ON TABLES TO ignition_console;
+        REVOKE ALL ON sessions, accounts, api_tokens, verification_tokens,
+            webauthn_challenges, oauth_states, push_subscriptions FROM ignition_console;
+        IF NOT pg_has_role(CURRENT_USER, 'ignition_console', 'MEMBER') THEN
+            EXECUTE format('GRANT ignition_console TO %I', CURRENT_USER);
+        END IF;
+    END IF;
+END
+$$;
+
+-- Queries an admin keeps for reuse
+CREATE TABLE admin_saved_queries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    sql TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
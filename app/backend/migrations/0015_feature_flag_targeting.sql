@@ -0,0 +1,19 @@
+-- 0015_feature_flag_targeting.sql
+-- Percentage rollouts and targeting for feature flags
+-- A flag that is enabled is on for users it targets by ID, role or
+-- entitlement, and for the given percentage of everyone else (bucketed by a
+-- hash of flag name and user ID, so a user keeps their answer as the
+-- percentage grows). A disabled flag is off for everyone.
+
+ALTER TABLE feature_flags
+    ADD COLUMN rollout_percentage INTEGER NOT NULL DEFAULT 100
+        CHECK (rollout_percentage BETWEEN 0 AND 100),
+    ADD COLUMN target_user_ids UUID[] NOT NULL DEFAULT '{}',
+    ADD COLUMN target_roles TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN target_entitlements TEXT[] NOT NULL DEFAULT '{}';
+
+-- Seeded flags kept their rollout in metadata
+UPDATE feature_flags
+SET rollout_percentage = LEAST(GREATEST((metadata->>'rollout_percentage')::INTEGER, 0), 100),
+    metadata = NULLIF(metadata - 'rollout_percentage', '{}'::JSONB)
+WHERE metadata ? 'rollout_percentage';
@@ -0,0 +1,17 @@
+-- 0016_rate_limits.sql
+-- Token buckets shared by every API instance
+-- Used when rate limits run with the `postgres` backend. A bucket is keyed
+-- by policy and subject (user, session, IP or route) and refills
+-- continuously; taking a token is a single upsert, so concurrent requests on
+-- different instances cannot both spend the last one. Idle buckets are full
+-- by definition and are pruned.
+
+CREATE TABLE rate_limit_buckets (
+    key TEXT PRIMARY KEY,
+    tokens DOUBLE PRECISION NOT NULL,
+    -- Whether the last request took a token
+    allowed BOOLEAN NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_rate_limit_buckets_updated_at ON rate_limit_buckets(updated_at);
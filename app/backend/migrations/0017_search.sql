@@ -0,0 +1,132 @@
+-- 0017_search.sql
+-- Full-text search over ideas, infobase, references, inbox and tracks
+-- Each searchable table gets a `search_vector` kept current by a trigger:
+-- weight A is the title, B tags and other short labels, C the body. A
+-- trigger rather than a generated column, so archive and backup restores,
+-- which insert whole rows, keep working.
+
+CREATE FUNCTION search_document(title TEXT, labels TEXT, body TEXT) RETURNS tsvector AS $$
+    SELECT setweight(to_tsvector('english', coalesce(title, '')), 'A')
+        || setweight(to_tsvector('english', coalesce(labels, '')), 'B')
+        || setweight(to_tsvector('english', coalesce(body, '')), 'C')
+$$ LANGUAGE sql IMMUTABLE;
+
+-- Ideas
+ALTER TABLE ideas ADD COLUMN search_vector tsvector;
+
+CREATE FUNCTION ideas_search_vector() RETURNS trigger AS $$
+BEGIN
+    NEW.search_vector := search_document(
+        NEW.title,
+        concat_ws(' ', NEW.category, array_to_string(NEW.tags, ' ')),
+        NEW.content
+    );
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER ideas_search BEFORE INSERT OR UPDATE ON ideas
+    FOR EACH ROW EXECUTE FUNCTION ideas_search_vector();
+
+-- Infobase entries
+ALTER TABLE infobase_entries ADD COLUMN search_vector tsvector;
+
+CREATE FUNCTION infobase_entries_search_vector() RETURNS trigger AS $$
+BEGIN
+    NEW.search_vector := search_document(
+        NEW.title,
+        concat_ws(' ', NEW.category, array_to_string(NEW.tags, ' ')),
+        NEW.content
+    );
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER infobase_entries_search BEFORE INSERT OR UPDATE ON infobase_entries
+    FOR EACH ROW EXECUTE FUNCTION infobase_entries_search_vector();
+
+-- User references
+ALTER TABLE user_references ADD COLUMN search_vector tsvector;
+
+CREATE FUNCTION user_references_search_vector() RETURNS trigger AS $$
+BEGIN
+    NEW.search_vector := search_document(
+        NEW.title,
+        concat_ws(' ', NEW.category, array_to_string(NEW.tags, ' ')),
+        concat_ws(' ', NEW.content, NEW.url)
+    );
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER user_references_search BEFORE INSERT OR UPDATE ON user_references
+    FOR EACH ROW EXECUTE FUNCTION user_references_search_vector();
+
+-- Inbox items
+ALTER TABLE inbox_items ADD COLUMN search_vector tsvector;
+
+CREATE FUNCTION inbox_items_search_vector() RETURNS trigger AS $$
+BEGIN
+    NEW.search_vector := search_document(
+        NEW.title,
+        array_to_string(NEW.tags, ' '),
+        concat_ws(' ', NEW.description, NEW.body)
+    );
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER inbox_items_search BEFORE INSERT OR UPDATE ON inbox_items
+    FOR EACH ROW EXECUTE FUNCTION inbox_items_search_vector();
+
+-- Reference tracks
+ALTER TABLE reference_tracks ADD COLUMN search_vector tsvector;
+
+CREATE FUNCTION reference_tracks_search_vector() RETURNS trigger AS $$
+BEGIN
+    NEW.search_vector := search_document(
+        NEW.title,
+        concat_ws(' ', NEW.artist, NEW.album, NEW.genre, array_to_string(NEW.tags, ' ')),
+        NEW.description
+    );
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER reference_tracks_search BEFORE INSERT OR UPDATE ON reference_tracks
+    FOR EACH ROW EXECUTE FUNCTION reference_tracks_search_vector();
+
+-- Track annotations
+ALTER TABLE track_annotations ADD COLUMN search_vector tsvector;
+
+CREATE FUNCTION track_annotations_search_vector() RETURNS trigger AS $$
+BEGIN
+    NEW.search_vector := search_document(
+        NEW.title,
+        concat_ws(' ', NEW.annotation_type, array_to_string(NEW.tags, ' ')),
+        NEW.content
+    );
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER track_annotations_search BEFORE INSERT OR UPDATE ON track_annotations
+    FOR EACH ROW EXECUTE FUNCTION track_annotations_search_vector();
+
+-- Backfill through the triggers; inbox rows are not changed for clients, so
+-- no sync events
+ALTER TABLE inbox_items DISABLE TRIGGER inbox_items_sync;
+UPDATE ideas SET search_vector = NULL;
+UPDATE infobase_entries SET search_vector = NULL;
+UPDATE user_references SET search_vector = NULL;
+UPDATE inbox_items SET search_vector = NULL;
+UPDATE reference_tracks SET search_vector = NULL;
+UPDATE track_annotations SET search_vector = NULL;
+ALTER TABLE inbox_items ENABLE TRIGGER inbox_items_sync;
+
+CREATE INDEX idx_ideas_search ON ideas USING GIN (search_vector);
+CREATE INDEX idx_infobase_entries_search ON infobase_entries USING GIN (search_vector);
+CREATE INDEX idx_user_references_search ON user_references USING GIN (search_vector);
+CREATE INDEX idx_inbox_items_search ON inbox_items USING GIN (search_vector);
+CREATE INDEX idx_reference_tracks_search ON reference_tracks USING GIN (search_vector);
+CREATE INDEX idx_track_annotations_search ON track_annotations USING GIN (search_vector);
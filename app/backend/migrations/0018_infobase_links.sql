@@ -0,0 +1,55 @@
+-- 0018_infobase_links.sql
+-- Wiki-style links between infobase entries, ideas and tracks
+-- `[[Entry Title]]`, `[[idea:<uuid>]]` and `[[track:<uuid>]]` in an entry's
+-- content become rows here whenever the entry is saved. Title links resolve
+-- to the user's oldest entry with that title (case-insensitively); an
+-- unresolved link keeps its title and resolves once such an entry exists.
+
+CREATE TABLE infobase_links (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    source_id UUID NOT NULL REFERENCES infobase_entries(id) ON DELETE CASCADE,
+    target_type TEXT NOT NULL CHECK (target_type IN ('entry', 'idea', 'track')),
+    -- Linked row; NULL for an entry title nothing matches
+    target_id UUID,
+    -- Title as written, for entry links
+    target_title TEXT,
+    -- Text between the brackets
+    raw TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_infobase_links_source_raw
+    ON infobase_links(source_id, target_type, lower(raw));
+CREATE INDEX idx_infobase_links_target ON infobase_links(target_type, target_id);
+CREATE INDEX idx_infobase_links_title ON infobase_links(user_id, lower(target_title))
+    WHERE target_type = 'entry';
+
+-- Links already written in existing entries
+INSERT INTO infobase_links (user_id, source_id, target_type, target_id, target_title, raw)
+SELECT DISTINCT ON (m.source_id, m.target_type, lower(m.raw))
+       m.user_id, m.source_id, m.target_type,
+       CASE m.target_type
+           WHEN 'entry' THEN (
+               SELECT t.id FROM infobase_entries t
+               WHERE t.user_id = m.user_id AND lower(t.title) = lower(m.raw)
+               ORDER BY t.created_at, t.id
+               LIMIT 1
+           )
+           ELSE substring(m.raw FROM position(':' IN m.raw) + 1)::uuid
+       END,
+       CASE m.target_type WHEN 'entry' THEN m.raw END,
+       m.raw
+FROM (
+    SELECT e.user_id, e.id AS source_id, trim(l.match[1]) AS raw,
+           CASE
+               WHEN trim(l.match[1]) ~* '^idea:[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$'
+                   THEN 'idea'
+               WHEN trim(l.match[1]) ~* '^track:[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$'
+                   THEN 'track'
+               ELSE 'entry'
+           END AS target_type
+    FROM infobase_entries e,
+         regexp_matches(e.content, '\[\[([^][\n]+)\]\]', 'g') AS l(match)
+) m
+WHERE m.raw <> '';
@@ -0,0 +1,44 @@
+-- 0019_revisions.sql
+-- Revision history for infobase entries and ideas
+-- Every save bumps the row's `version` and appends the saved title, content,
+-- category and tags here. Clients send the version they edited as `If-Match`,
+-- so a write based on an older version is refused instead of overwriting.
+-- Restoring a revision saves its content again as a new version.
+
+ALTER TABLE infobase_entries ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE ideas ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+
+CREATE TABLE content_revisions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    entity_type TEXT NOT NULL CHECK (entity_type IN ('infobase', 'idea')),
+    entity_id UUID NOT NULL,
+    version INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    content TEXT,
+    category TEXT,
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    -- Who saved this version
+    author_id UUID,
+    -- Lowercase hex SHA-256 of the content
+    content_hash TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (entity_type, entity_id, version)
+);
+
+CREATE INDEX idx_content_revisions_user ON content_revisions(user_id);
+
+-- Existing rows start their history at version 1
+INSERT INTO content_revisions
+    (user_id, entity_type, entity_id, version, title, content, category, tags,
+     author_id, content_hash, created_at)
+SELECT user_id, 'infobase', id, 1, title, content, category, coalesce(tags, '{}'),
+       user_id, encode(sha256(convert_to(coalesce(content, ''), 'UTF8')), 'hex'), updated_at
+FROM infobase_entries;
+
+INSERT INTO content_revisions
+    (user_id, entity_type, entity_id, version, title, content, category, tags,
+     author_id, content_hash, created_at)
+SELECT user_id, 'idea', id, 1, title, content, category, coalesce(tags, '{}'),
+       user_id, encode(sha256(convert_to(coalesce(content, ''), 'UTF8')), 'hex'), updated_at
+FROM ideas;
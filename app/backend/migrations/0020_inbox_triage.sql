@@ -0,0 +1,23 @@
+-- 0020_inbox_triage.sql
+-- Converting inbox items into quests, habits, events, milestones, ideas or
+-- infobase entries
+-- Each conversion records the row it created, so the target can be traced
+-- back to the captured item. A converted item is marked processed; items
+-- left unprocessed past `expires_at` are archived by a periodic sweep.
+
+CREATE TABLE inbox_conversions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    inbox_item_id UUID NOT NULL UNIQUE REFERENCES inbox_items(id) ON DELETE CASCADE,
+    target_type TEXT NOT NULL CHECK (target_type IN (
+        'quest', 'habit', 'calendar_event', 'goal_milestone', 'idea', 'infobase_entry'
+    )),
+    target_id UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_inbox_conversions_target ON inbox_conversions(target_type, target_id);
+CREATE INDEX idx_inbox_conversions_user ON inbox_conversions(user_id);
+
+CREATE INDEX idx_inbox_items_expiry ON inbox_items(expires_at)
+    WHERE expires_at IS NOT NULL AND NOT is_processed AND NOT is_archived;
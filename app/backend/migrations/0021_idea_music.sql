@@ -0,0 +1,62 @@
+-- 0021_idea_music.sql
+-- Musical metadata and audio attachments on ideas
+-- Key, BPM, mood, genre and time signature become columns so ideas can be
+-- filtered by them. Keys are stored in one spelling per pitch class ("A minor",
+-- "Eb major"), so a filter matches whichever enharmonic was typed.
+-- Ideas used to fold key, BPM and mood into their content as "Key: ..." lines;
+-- those are parsed into the new columns here and left in the content, which
+-- is versioned (see 0019).
+
+ALTER TABLE ideas
+    ADD COLUMN key TEXT,
+    ADD COLUMN bpm INTEGER CHECK (bpm BETWEEN 20 AND 400),
+    ADD COLUMN mood TEXT,
+    ADD COLUMN genre TEXT,
+    ADD COLUMN time_signature TEXT;
+
+CREATE INDEX idx_ideas_user_key ON ideas(user_id, key) WHERE key IS NOT NULL;
+CREATE INDEX idx_ideas_user_bpm ON ideas(user_id, bpm) WHERE bpm IS NOT NULL;
+
+WITH folded AS (
+    SELECT id,
+           regexp_match(
+               trim(substring(content FROM '(?n)^Key: (.*)$')),
+               '^([A-Ga-g])([#b♯♭]?)\s*(.*)$'
+           ) AS k,
+           substring(content FROM '(?n)^BPM: (\d{1,3})$')::INTEGER AS bpm,
+           nullif(trim(substring(content FROM '(?n)^Mood: (.*)$')), '') AS mood
+    FROM ideas
+    WHERE content ~ '(?n)^(Key|BPM|Mood): '
+)
+UPDATE ideas i
+SET key = (ARRAY['C', 'Db', 'D', 'Eb', 'E', 'F', 'F#', 'G', 'Ab', 'A', 'Bb', 'B'])[
+              (position(upper(f.k[1]) IN 'C D EF G A B') - 1
+               + CASE WHEN f.k[2] IN ('#', '♯') THEN 1 WHEN f.k[2] IN ('b', '♭') THEN 11 ELSE 0 END)
+              % 12 + 1]
+          || CASE
+                 WHEN f.k[3] = 'M' OR lower(f.k[3]) IN ('', 'maj', 'major') THEN ' major'
+                 WHEN lower(f.k[3]) IN ('m', 'min', 'minor') THEN ' minor'
+             END,
+    bpm = CASE WHEN f.bpm BETWEEN 20 AND 400 THEN f.bpm END,
+    mood = f.mood
+FROM folded f
+WHERE i.id = f.id;
+
+CREATE TABLE idea_attachments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    idea_id UUID NOT NULL REFERENCES ideas(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL DEFAULT 'voice_memo'
+        CHECK (kind IN ('voice_memo', 'loop', 'stem', 'other')),
+    r2_key TEXT NOT NULL,
+    filename TEXT NOT NULL,
+    mime_type TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    duration_seconds REAL,
+    -- Track the audio was promoted to; the track owns its own copy
+    reference_track_id UUID REFERENCES reference_tracks(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_idea_attachments_idea ON idea_attachments(idea_id, created_at);
+CREATE INDEX idx_idea_attachments_user ON idea_attachments(user_id);
@@ -0,0 +1,38 @@
+-- 0022_onboarding_versions.sql
+-- Admin-authored onboarding flows with versions and conditional steps
+-- A flow name has numbered versions. A version is edited as a draft and is
+-- frozen once published; publishing makes it the one active flow new users
+-- start, while users already part-way through keep the version in their
+-- `user_onboarding_state.flow_id` until they finish.
+-- A step with `show_if` is only shown when the condition holds against the
+-- user's earlier responses; otherwise it is passed over for the next one.
+
+ALTER TABLE onboarding_flows DROP CONSTRAINT onboarding_flows_name_unique;
+
+ALTER TABLE onboarding_flows
+    ADD COLUMN version INTEGER NOT NULL DEFAULT 1,
+    ADD COLUMN published_at TIMESTAMPTZ;
+
+-- Flows served so far were live without a publish step
+UPDATE onboarding_flows SET published_at = created_at;
+
+ALTER TABLE onboarding_flows
+    ADD CONSTRAINT onboarding_flows_name_version_unique UNIQUE (name, version),
+    ADD CONSTRAINT onboarding_flows_active_published
+        CHECK (NOT is_active OR published_at IS NOT NULL);
+
+CREATE UNIQUE INDEX idx_onboarding_flows_single_active ON onboarding_flows ((true))
+    WHERE is_active;
+
+-- Steps of flows that no longer exist were never reachable
+DELETE FROM onboarding_steps s
+WHERE NOT EXISTS (SELECT 1 FROM onboarding_flows f WHERE f.id = s.flow_id);
+
+ALTER TABLE onboarding_steps
+    ADD COLUMN show_if JSONB,
+    ADD CONSTRAINT onboarding_steps_flow_fk
+        FOREIGN KEY (flow_id) REFERENCES onboarding_flows(id) ON DELETE CASCADE;
+
+CREATE INDEX idx_onboarding_steps_flow ON onboarding_steps(flow_id, step_order);
+CREATE INDEX idx_user_onboarding_state_flow ON user_onboarding_state(flow_id);
+CREATE INDEX idx_user_onboarding_responses_step ON user_onboarding_responses(step_id);
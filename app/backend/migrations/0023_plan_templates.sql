@@ -0,0 +1,31 @@
+-- 0023_plan_templates.sql
+-- One daily plan per user and day, and reusable plan templates
+-- Generation regenerates a day's plan in place and carries unfinished items
+-- over from the day before, so a day needs a single plan row. Templates are
+-- named per user; public ones can be applied by anyone and count their uses.
+
+-- Keep the most recently updated plan when a day has several
+DELETE FROM daily_plans d
+USING daily_plans newer
+WHERE newer.user_id = d.user_id
+  AND newer.date = d.date
+  AND (newer.updated_at, newer.id) > (d.updated_at, d.id);
+
+ALTER TABLE daily_plans
+    ADD CONSTRAINT daily_plans_user_date_unique UNIQUE (user_id, date);
+
+ALTER TABLE plan_templates
+    ALTER COLUMN is_public SET DEFAULT false,
+    ALTER COLUMN use_count SET DEFAULT 0;
+
+-- Number repeated names before making them unique
+UPDATE plan_templates t
+SET name = t.name || ' (' || d.n || ')'
+FROM (
+    SELECT id, row_number() OVER (PARTITION BY user_id, lower(name) ORDER BY created_at, id) AS n
+    FROM plan_templates
+) d
+WHERE d.id = t.id AND d.n > 1;
+
+CREATE UNIQUE INDEX idx_plan_templates_user_name ON plan_templates (user_id, lower(name));
+CREATE INDEX idx_plan_templates_public ON plan_templates (use_count DESC) WHERE is_public;
@@ -0,0 +1,14 @@
+-- 0024_habit_schedules.sql
+-- Habit frequencies, and completion lookups by habit and day
+-- Streaks and history are computed from a habit's completions per day, so
+-- they are indexed by habit and date. Frequencies are limited to the ones
+-- the schedule understands: daily, custom weekdays and weekly.
+
+UPDATE habits SET frequency = 'daily' WHERE frequency NOT IN ('daily', 'custom', 'weekly');
+UPDATE habits SET target_count = 1 WHERE target_count < 1;
+
+ALTER TABLE habits
+    ADD CONSTRAINT habits_frequency_check CHECK (frequency IN ('daily', 'custom', 'weekly')),
+    ADD CONSTRAINT habits_target_count_check CHECK (target_count >= 1);
+
+CREATE INDEX idx_habit_completions_habit_date ON habit_completions (habit_id, completed_date);
@@ -0,0 +1,39 @@
+-- 0025_goal_progress.sql
+-- Weighted milestones, goal links and archiving
+-- A goal's progress is derived from its milestones, weighted, and from the
+-- habits, quests, workout programs and books linked to it. Goals complete
+-- themselves at 100%, carry an at-risk flag for their target date, and can be
+-- archived and reordered.
+
+UPDATE goals SET status = 'active' WHERE status NOT IN ('active', 'completed', 'abandoned', 'paused');
+
+ALTER TABLE goals
+    ALTER COLUMN status SET DEFAULT 'active',
+    ALTER COLUMN progress SET DEFAULT 0,
+    ALTER COLUMN priority SET DEFAULT 0,
+    ALTER COLUMN sort_order SET DEFAULT 0,
+    ADD COLUMN at_risk BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN archived_at TIMESTAMPTZ,
+    ADD CONSTRAINT goals_status_check CHECK (status IN ('active', 'completed', 'abandoned', 'paused'));
+
+ALTER TABLE goal_milestones
+    ALTER COLUMN is_completed SET DEFAULT false,
+    ALTER COLUMN sort_order SET DEFAULT 0,
+    ADD COLUMN weight INTEGER NOT NULL DEFAULT 1 CHECK (weight BETWEEN 1 AND 100);
+
+CREATE INDEX idx_goal_milestones_goal_id ON goal_milestones (goal_id, sort_order);
+
+-- `target` is the number of completions a linked habit counts toward
+CREATE TABLE goal_links (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    goal_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    target_type TEXT NOT NULL CHECK (target_type IN ('habit', 'quest', 'workout_program', 'book')),
+    target_id UUID NOT NULL,
+    weight INTEGER NOT NULL DEFAULT 1 CHECK (weight BETWEEN 1 AND 100),
+    target INTEGER CHECK (target >= 1),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (goal_id, target_type, target_id)
+);
+
+CREATE INDEX idx_goal_links_target ON goal_links (target_type, target_id);
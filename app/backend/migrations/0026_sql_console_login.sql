@@ -0,0 +1,38 @@
+-- 0026_sql_console_login.sql
+-- The SQL console logs in as its own role
+-- Switching into ignition_console with SET LOCAL ROLE left the session user
+-- as the API's role, so a query that reset `role` got the API's privileges
+-- back. The console now connects over its own pool as ignition_console, a
+-- NOINHERIT role that is a member of no other role, and the API role's
+-- membership in it is dropped.
+--
+-- The role gets no password here; give it one and set SQL_CONSOLE_DATABASE_URL:
+--   ALTER ROLE ignition_console PASSWORD '...';
+
+DO $$
+DECLARE
+    granted RECORD;
+BEGIN
+    IF EXISTS (SELECT 1 FROM pg_roles WHERE rolname = 'ignition_console') THEN
+        ALTER ROLE ignition_console LOGIN NOINHERIT;
+        FOR granted IN
+            SELECT r.rolname
+            FROM pg_auth_members m
+            JOIN pg_roles r ON r.oid = m.roleid
+            WHERE m.member = 'ignition_console'::regrole
+        LOOP
+            EXECUTE format('REVOKE %I FROM ignition_console', granted.rolname);
+        END LOOP;
+        IF EXISTS (
+            SELECT 1 FROM pg_auth_members
+            WHERE roleid = 'ignition_console'::regrole AND member = CURRENT_USER::regrole
+        ) THEN
+            EXECUTE format('REVOKE ignition_console FROM %I', CURRENT_USER);
+        END IF;
+    END IF;
+EXCEPTION
+    -- Without CREATEROLE the console reports its database as unavailable
+    WHEN insufficient_privilege THEN
+        RAISE NOTICE 'ignition_console role not updated: insufficient privilege';
+END
+$$;
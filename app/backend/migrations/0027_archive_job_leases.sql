@@ -0,0 +1,11 @@
+-- 0027_archive_job_leases.sql
+-- Archive jobs are leased by the worker running them
+-- Startup used to fail every pending or running job, including jobs other
+-- instances were still running. A running job now records the worker that
+-- claimed it and a lease that worker keeps extending. Only jobs whose lease
+-- ran out, or that were never started, are failed, and only the lease
+-- holder can record a job's outcome.
+
+ALTER TABLE account_archive_jobs
+    ADD COLUMN lease_owner UUID,
+    ADD COLUMN leased_until TIMESTAMPTZ;
@@ -0,0 +1,110 @@
+-- 0028_notification_settings.sql
+-- Delivery preferences stored as validated columns
+-- The notification_preferences view aggregated every user's settings and
+-- matched timezones against pg_timezone_names on each scheduler tick. The
+-- delivery settings now live in notification_settings, kept in step with
+-- user_settings by a trigger, so they are parsed and checked once per write.
+-- The view keeps its columns and adds only the clock-dependent ones.
+
+CREATE TABLE notification_settings (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    notifications_enabled BOOLEAN NOT NULL DEFAULT true,
+    email_notifications BOOLEAN NOT NULL DEFAULT true,
+    push_notifications BOOLEAN NOT NULL DEFAULT false,
+    -- Always a name in pg_timezone_names
+    timezone TEXT NOT NULL DEFAULT 'UTC',
+    daily_reminder_time TIME,
+    quiet_hours_start TIME,
+    quiet_hours_end TIME
+);
+
+-- Same defaults as UserSettingsResponse. Unknown timezones fall back to UTC
+-- and malformed times are ignored, so a bad setting cannot stall the
+-- scheduler. A user without settings has no row.
+CREATE FUNCTION refresh_notification_settings(uid UUID) RETURNS void AS $$
+BEGIN
+    DELETE FROM notification_settings WHERE user_id = uid;
+    INSERT INTO notification_settings
+        (user_id, notifications_enabled, email_notifications, push_notifications, timezone,
+         daily_reminder_time, quiet_hours_start, quiet_hours_end)
+    SELECT
+        uid,
+        COALESCE(CASE WHEN jsonb_typeof(s.settings->'notifications_enabled') = 'boolean'
+            THEN (s.settings->'notifications_enabled')::boolean END, true),
+        COALESCE(CASE WHEN jsonb_typeof(s.settings->'email_notifications') = 'boolean'
+            THEN (s.settings->'email_notifications')::boolean END, true),
+        COALESCE(CASE WHEN jsonb_typeof(s.settings->'push_notifications') = 'boolean'
+            THEN (s.settings->'push_notifications')::boolean END, false),
+        COALESCE((SELECT tz.name FROM pg_timezone_names tz
+                  WHERE tz.name = s.settings->>'timezone'), 'UTC'),
+        CASE WHEN s.settings->>'daily_reminder_time' ~ '^([01]?[0-9]|2[0-3]):[0-5][0-9]$'
+            THEN (s.settings->>'daily_reminder_time')::time END,
+        CASE WHEN s.settings->>'quiet_hours_start' ~ '^([01]?[0-9]|2[0-3]):[0-5][0-9]$'
+            THEN (s.settings->>'quiet_hours_start')::time END,
+        CASE WHEN s.settings->>'quiet_hours_end' ~ '^([01]?[0-9]|2[0-3]):[0-5][0-9]$'
+            THEN (s.settings->>'quiet_hours_end')::time END
+    FROM (
+        SELECT jsonb_object_agg(key, value) AS settings
+        FROM user_settings
+        WHERE user_id = uid
+    ) s
+    WHERE s.settings IS NOT NULL
+      AND EXISTS (SELECT 1 FROM users WHERE id = uid);
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE FUNCTION user_settings_notification_settings() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP <> 'INSERT' AND OLD.key IN (
+        'notifications_enabled', 'email_notifications', 'push_notifications', 'timezone',
+        'daily_reminder_time', 'quiet_hours_start', 'quiet_hours_end'
+    ) THEN
+        PERFORM refresh_notification_settings(OLD.user_id);
+    END IF;
+    IF TG_OP <> 'DELETE' AND NEW.key IN (
+        'notifications_enabled', 'email_notifications', 'push_notifications', 'timezone',
+        'daily_reminder_time', 'quiet_hours_start', 'quiet_hours_end'
+    ) THEN
+        PERFORM refresh_notification_settings(NEW.user_id);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER user_settings_notifications AFTER INSERT OR UPDATE OR DELETE ON user_settings
+    FOR EACH ROW EXECUTE FUNCTION user_settings_notification_settings();
+
+SELECT refresh_notification_settings(user_id)
+FROM (SELECT DISTINCT user_id FROM user_settings) u;
+
+DROP VIEW notification_preferences;
+
+CREATE VIEW notification_preferences AS
+SELECT
+    p.*,
+    p.quiet_hours_start IS NOT NULL
+        AND p.quiet_hours_end IS NOT NULL
+        AND p.quiet_hours_start <> p.quiet_hours_end
+        AND CASE
+            WHEN p.quiet_hours_start < p.quiet_hours_end
+                THEN p.local_now::time >= p.quiet_hours_start
+                    AND p.local_now::time < p.quiet_hours_end
+            -- Window wraps past midnight
+            ELSE p.local_now::time >= p.quiet_hours_start
+                OR p.local_now::time < p.quiet_hours_end
+        END AS in_quiet_hours
+FROM (
+    SELECT
+        u.id AS user_id,
+        u.email,
+        COALESCE(n.notifications_enabled, true) AS notifications_enabled,
+        COALESCE(n.email_notifications, true) AS email_notifications,
+        COALESCE(n.push_notifications, false) AS push_notifications,
+        COALESCE(n.timezone, 'UTC') AS timezone,
+        n.daily_reminder_time,
+        n.quiet_hours_start,
+        n.quiet_hours_end,
+        NOW() AT TIME ZONE COALESCE(n.timezone, 'UTC') AS local_now
+    FROM users u
+    LEFT JOIN notification_settings n ON n.user_id = u.id
+) p;
@@ -0,0 +1,7 @@
+-- 0029_account_deletion_lease_owner.sql
+-- Account purges record the worker holding their lease
+-- A purge whose lease ran out can be claimed again while the first worker is
+-- still finishing. Each claim now sets a new lease owner, and only that owner
+-- can mark the purge completed or record its error.
+
+ALTER TABLE account_deletions ADD COLUMN lease_owner UUID;
@@ -0,0 +1,12 @@
+-- 0030_gamification_totals_bigint.sql
+-- XP and wallet totals are 64-bit
+-- The API reads user_progress.total_xp and the user_wallet balances as
+-- BIGINT, but the tables were created with INTEGER columns, so awarding XP or
+-- coins failed to decode the row it had just written.
+
+ALTER TABLE user_progress ALTER COLUMN total_xp TYPE BIGINT;
+
+ALTER TABLE user_wallet
+    ALTER COLUMN coins TYPE BIGINT,
+    ALTER COLUMN total_earned TYPE BIGINT,
+    ALTER COLUMN total_spent TYPE BIGINT;
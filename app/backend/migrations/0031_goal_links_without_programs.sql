@@ -0,0 +1,13 @@
+-- 0031_goal_links_without_programs.sql
+-- Goals no longer link to workout programs
+-- Nothing records a training program's weeks or completion, so a linked
+-- program always counted as 0% and held its goals back. Existing program links
+-- are dropped and the goals they belonged to are recomputed the next time
+-- they change.
+
+DELETE FROM goal_links WHERE target_type = 'workout_program';
+
+ALTER TABLE goal_links
+    DROP CONSTRAINT goal_links_target_type_check,
+    ADD CONSTRAINT goal_links_target_type_check
+        CHECK (target_type IN ('habit', 'quest', 'book'));
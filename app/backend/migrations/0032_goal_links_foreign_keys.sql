@@ -0,0 +1,14 @@
+-- 0032_goal_links_foreign_keys.sql
+-- Goal links go with their goal and user
+-- goal_links had no foreign keys, so deleting a goal or a user any other way
+-- than through the goals API left its links behind.
+
+DELETE FROM goal_links l
+WHERE NOT EXISTS (SELECT 1 FROM goals g WHERE g.id = l.goal_id)
+   OR NOT EXISTS (SELECT 1 FROM users u WHERE u.id = l.user_id);
+
+ALTER TABLE goal_links
+    ADD CONSTRAINT goal_links_goal_id_fkey
+        FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE,
+    ADD CONSTRAINT goal_links_user_id_fkey
+        FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE;
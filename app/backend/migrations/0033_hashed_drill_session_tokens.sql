@@ -0,0 +1,8 @@
+-- 0033_hashed_drill_session_tokens.sql
+-- Store drill session tokens as SHA-256 hashes, like sessions.token (0004)
+-- The client keeps the raw token; drill_sessions.token now holds the
+-- lowercase hex digest.
+
+UPDATE drill_sessions
+SET token = encode(sha256(convert_to(token, 'UTF8')), 'hex')
+WHERE token !~ '^[0-9a-f]{64}$';
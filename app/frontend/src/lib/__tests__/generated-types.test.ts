@@ -152,7 +152,12 @@ describe('Generated Types', () => {
         max_level: 100,
         stars_per_level: 10,
         sort_order: 1,
+        color: '#8b5cf6',
+        xp_scaling_base: 100,
+        xp_scaling_multiplier: 1.5,
+        is_active: true,
         created_at: '2025-01-01T00:00:00Z',
+        updated_at: '2025-01-01T00:00:00Z',
       };
 
       expect(skill.key).toBe('focus');
@@ -187,6 +192,8 @@ describe('Generated Types', () => {
         coin_reward: 10,
         target: 25,
         target_type: 'focus_minutes',
+        skill_star_reward: 1,
+        is_recurring: true,
         is_active: true,
         sort_order: 1,
         created_at: '2025-01-01T00:00:00Z',
@@ -244,6 +251,7 @@ describe('Generated Types', () => {
         progress: 0,
         priority: 1,
         sort_order: 0,
+        at_risk: false,
         created_at: '2025-01-01T00:00:00Z',
         updated_at: '2025-01-01T00:00:00Z',
       };
@@ -298,6 +306,10 @@ describe('Generated Types', () => {
         id: 'uuid',
         flag_name: 'ai_coach',
         enabled: false,
+        rollout_percentage: 0,
+        target_user_ids: [],
+        target_roles: [],
+        target_entitlements: [],
         created_at: '2025-01-01T00:00:00Z',
         updated_at: '2025-01-01T00:00:00Z',
       };
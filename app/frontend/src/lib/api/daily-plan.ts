@@ -1,14 +1,14 @@
 /**
  * Daily Plan API
  *
- * API client methods for daily planning.
+ * API client methods for daily planning and plan templates.
  * All calls go through the backend at api.ecent.online.
  *
  * Wave 4: Daily Plan routes
  * REFACTOR: Uses shared client (January 2026)
  */
 
-import { apiGet, apiPost } from './client';
+import { apiDelete, apiGet, apiPost, apiPut } from './client';
 
 // ============================================
 // Types
@@ -16,15 +16,30 @@ import { apiGet, apiPost } from './client';
 
 export interface PlanItem {
   id: string;
-  type: 'focus' | 'quest' | 'workout' | 'learning' | 'habit';
+  type: PlanItemType;
   title: string;
   description?: string;
   duration?: number;
   action_url: string;
   completed: boolean;
   priority: number;
+  /** Scheduled time, for calendar events and focus blocks */
+  starts_at?: string;
+  ends_at?: string;
+  /** Date of the plan this unfinished item was carried over from */
+  carried_over_from?: string;
 }
 
+export type PlanItemType =
+  | 'focus'
+  | 'event'
+  | 'goal'
+  | 'habit'
+  | 'quest'
+  | 'workout'
+  | 'learning'
+  | 'task';
+
 export interface DailyPlan {
   id: string;
   date: string;
@@ -38,6 +53,39 @@ interface PlanResponse {
   plan: DailyPlan | null;
 }
 
+export interface PlanTemplateItem {
+  type: Exclude<PlanItemType, 'event'>;
+  title: string;
+  description?: string | null;
+  duration?: number | null;
+  action_url: string;
+}
+
+export interface PlanTemplate {
+  id: string;
+  name: string;
+  description: string | null;
+  category: string | null;
+  is_public: boolean;
+  use_count: number;
+  items: PlanTemplateItem[];
+  is_owner: boolean;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface CreatePlanTemplateInput {
+  name: string;
+  description?: string;
+  category?: string;
+  is_public?: boolean;
+  /** Items to save; or copy them from the plan on `from_date` */
+  items?: PlanTemplateItem[];
+  from_date?: string;
+}
+
+export type UpdatePlanTemplateInput = Partial<Omit<CreatePlanTemplateInput, 'from_date'>>;
+
 // ============================================
 // API Functions
 // ============================================
@@ -105,3 +153,61 @@ export async function getOrGenerateTodayPlan(): Promise<DailyPlan | null> {
   if (existing) return existing;
   return generateDailyPlan(today);
 }
+
+// ============================================
+// Templates
+// ============================================
+
+/**
+ * List the user's plan templates and public ones
+ */
+export async function listPlanTemplates(): Promise<PlanTemplate[]> {
+  const response = await apiGet<{ templates: PlanTemplate[] }>('/api/daily-plan/templates');
+  return response.templates;
+}
+
+/**
+ * Save a plan template
+ */
+export async function createPlanTemplate(input: CreatePlanTemplateInput): Promise<PlanTemplate> {
+  const response = await apiPost<{ template: PlanTemplate }>('/api/daily-plan/templates', input);
+  return response.template;
+}
+
+/**
+ * Update one of the user's plan templates
+ */
+export async function updatePlanTemplate(
+  id: string,
+  input: UpdatePlanTemplateInput
+): Promise<PlanTemplate> {
+  const response = await apiPut<{ template: PlanTemplate }>(
+    `/api/daily-plan/templates/${id}`,
+    input
+  );
+  return response.template;
+}
+
+/**
+ * Delete one of the user's plan templates
+ */
+export async function deletePlanTemplate(id: string): Promise<void> {
+  await apiDelete(`/api/daily-plan/templates/${id}`);
+}
+
+/**
+ * Add a template's items to a day's plan (defaults to today)
+ *
+ * With `replace`, unfinished items are dropped first.
+ */
+export async function applyPlanTemplate(
+  id: string,
+  date?: string,
+  replace: boolean = false
+): Promise<DailyPlan | null> {
+  const response = await apiPost<PlanResponse>(`/api/daily-plan/templates/${id}/apply`, {
+    date,
+    replace,
+  });
+  return response.plan;
+}
@@ -8,7 +8,7 @@
  * REFACTOR: Uses shared client (January 2026)
  */
 
-import { apiGet, apiPost } from './client';
+import { apiDelete, apiGet, apiPost, apiPut } from './client';
 
 // ============================================
 // Types
@@ -22,6 +22,23 @@ export interface GoalMilestone {
   is_completed: boolean;
   completed_at: string | null;
   sort_order: number;
+  weight: number;
+}
+
+export type GoalLinkType = 'habit' | 'quest' | 'book';
+
+export interface GoalLink {
+  id: string;
+  goal_id: string;
+  target_type: GoalLinkType;
+  target_id: string;
+  weight: number;
+  /** Completions a linked habit counts toward */
+  target: number | null;
+  /** Null once the linked item is gone */
+  title: string | null;
+  /** Percent done, 0 to 100 */
+  progress: number;
 }
 
 export interface Goal {
@@ -34,10 +51,16 @@ export interface Goal {
   completed_at: string | null;
   status: 'active' | 'completed' | 'abandoned' | 'paused';
   progress: number;
+  /** Whether progress comes from milestones and links rather than by hand */
+  auto_progress: boolean;
+  at_risk: boolean;
   priority: number;
+  sort_order: number;
+  archived_at: string | null;
   milestones: GoalMilestone[];
   total_milestones: number;
   completed_milestones: number;
+  links: GoalLink[];
 }
 
 export interface GoalsList {
@@ -53,9 +76,36 @@ export interface CreateGoalRequest {
   priority?: number;
 }
 
+export interface UpdateGoalRequest {
+  title?: string;
+  description?: string;
+  category?: string;
+  /** null clears the target date */
+  target_date?: string | null;
+  priority?: number;
+  status?: Goal['status'];
+  /** Only for goals without milestones or links */
+  progress?: number;
+}
+
 export interface CreateMilestoneRequest {
   title: string;
   description?: string;
+  weight?: number;
+}
+
+export interface UpdateMilestoneRequest {
+  title?: string;
+  description?: string;
+  weight?: number;
+}
+
+export interface CreateGoalLinkRequest {
+  target_type: GoalLinkType;
+  target_id: string;
+  weight?: number;
+  /** Required for habits */
+  target?: number;
 }
 
 export interface CompleteMilestoneResult {
@@ -69,17 +119,20 @@ export interface CompleteMilestoneResult {
 // ============================================
 
 /**
- * List goals for user
+ * List goals for user, or the archived ones
  * GET /api/goals
  */
-export async function listGoals(status?: string): Promise<GoalsList> {
-  const query = status ? `?status=${status}` : '';
+export async function listGoals(status?: string, archived = false): Promise<GoalsList> {
+  const params = new URLSearchParams();
+  if (status) params.set('status', status);
+  if (archived) params.set('archived', 'true');
+  const query = params.toString() ? `?${params}` : '';
   const response = await apiGet<{ data: GoalsList }>(`/api/goals${query}`);
   return response.data;
 }
 
 /**
- * Get a goal with milestones
+ * Get a goal with milestones and links
  * GET /api/goals/:id
  */
 export async function getGoal(goalId: string): Promise<Goal> {
@@ -96,6 +149,50 @@ export async function createGoal(req: CreateGoalRequest): Promise<Goal> {
   return response.data;
 }
 
+/**
+ * Update a goal
+ * PUT /api/goals/:id
+ */
+export async function updateGoal(goalId: string, req: UpdateGoalRequest): Promise<Goal> {
+  const response = await apiPut<{ data: Goal }>(`/api/goals/${goalId}`, req);
+  return response.data;
+}
+
+/**
+ * Archive a goal
+ * POST /api/goals/:id/archive
+ */
+export async function archiveGoal(goalId: string): Promise<Goal> {
+  const response = await apiPost<{ data: Goal }>(`/api/goals/${goalId}/archive`);
+  return response.data;
+}
+
+/**
+ * Restore an archived goal
+ * POST /api/goals/:id/restore
+ */
+export async function restoreGoal(goalId: string): Promise<Goal> {
+  const response = await apiPost<{ data: Goal }>(`/api/goals/${goalId}/restore`);
+  return response.data;
+}
+
+/**
+ * Delete a goal with its milestones and links
+ * DELETE /api/goals/:id
+ */
+export async function deleteGoal(goalId: string): Promise<void> {
+  await apiDelete(`/api/goals/${goalId}`);
+}
+
+/**
+ * Sort goals in the order given
+ * POST /api/goals/reorder
+ */
+export async function reorderGoals(ids: string[]): Promise<GoalsList> {
+  const response = await apiPost<{ data: GoalsList }>('/api/goals/reorder', { ids });
+  return response.data;
+}
+
 /**
  * Add a milestone to a goal
  * POST /api/goals/:id/milestones
@@ -114,12 +211,48 @@ export async function completeMilestone(milestoneId: string): Promise<CompleteMi
   return response.data;
 }
 
+/**
+ * Update a milestone; returns its goal
+ * PUT /api/goals/milestones/:id
+ */
+export async function updateMilestone(milestoneId: string, req: UpdateMilestoneRequest): Promise<Goal> {
+  const response = await apiPut<{ data: Goal }>(`/api/goals/milestones/${milestoneId}`, req);
+  return response.data;
+}
+
+/**
+ * Delete a milestone; returns its goal
+ * DELETE /api/goals/milestones/:id
+ */
+export async function deleteMilestone(milestoneId: string): Promise<Goal> {
+  const response = await apiDelete<{ data: Goal }>(`/api/goals/milestones/${milestoneId}`);
+  return response.data;
+}
+
+/**
+ * Count a habit, quest or book toward a goal
+ * POST /api/goals/:id/links
+ */
+export async function addGoalLink(goalId: string, req: CreateGoalLinkRequest): Promise<Goal> {
+  const response = await apiPost<{ data: Goal }>(`/api/goals/${goalId}/links`, req);
+  return response.data;
+}
+
+/**
+ * Unlink an item from a goal
+ * DELETE /api/goals/:id/links/:linkId
+ */
+export async function removeGoalLink(goalId: string, linkId: string): Promise<Goal> {
+  const response = await apiDelete<{ data: Goal }>(`/api/goals/${goalId}/links/${linkId}`);
+  return response.data;
+}
+
 // ============================================
 // React Query Keys
 // ============================================
 
 export const goalsKeys = {
   all: ['goals'] as const,
-  list: (status?: string) => [...goalsKeys.all, 'list', status] as const,
+  list: (status?: string, archived = false) => [...goalsKeys.all, 'list', status, archived] as const,
   detail: (id: string) => [...goalsKeys.all, 'detail', id] as const,
 };
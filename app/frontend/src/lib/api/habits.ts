@@ -1,32 +1,45 @@
 /**
  * Habits API
  *
- * API client methods for habit tracking.
+ * API client methods for habit tracking, schedules and history.
  * All calls go through the backend at api.ecent.online.
  *
  * PARITY-026: Habits routes
  * REFACTOR: Uses shared client (January 2026)
  */
 
-import { apiGet, apiPost } from './client';
+import { apiDelete, apiGet, apiPost, apiPut } from './client';
 
 // ============================================
 // Types
 // ============================================
 
+/**
+ * - daily: `target_count` completions every day
+ * - custom: `target_count` completions on each of `custom_days`
+ *   (0 = Sunday ... 6 = Saturday)
+ * - weekly: completions on `target_count` different days each week
+ */
+export type HabitFrequency = 'daily' | 'weekly' | 'custom';
+
 export interface Habit {
   id: string;
   name: string;
   description: string | null;
-  frequency: 'daily' | 'weekly' | 'custom';
+  frequency: HabitFrequency;
   target_count: number;
+  custom_days: number[] | null;
   icon: string | null;
   color: string | null;
   is_active: boolean;
+  /** Due days met in a row, or weeks for a weekly habit */
   current_streak: number;
   longest_streak: number;
   last_completed_at: string | null;
+  /** Today has all the completions it can take */
   completed_today: boolean;
+  completions_today: number;
+  due_today: boolean;
   sort_order: number;
 }
 
@@ -37,12 +50,15 @@ export interface HabitsList {
 export interface CreateHabitRequest {
   name: string;
   description?: string;
-  frequency?: 'daily' | 'weekly' | 'custom';
+  frequency?: HabitFrequency;
   target_count?: number;
+  custom_days?: number[];
   icon?: string;
   color?: string;
 }
 
+export type UpdateHabitRequest = Partial<CreateHabitRequest>;
+
 export interface CompleteHabitResult {
   habit: Habit;
   new_streak: number;
@@ -50,16 +66,34 @@ export interface CompleteHabitResult {
   streak_bonus: boolean;
 }
 
+export interface HabitHistoryDay {
+  date: string;
+  count: number;
+  due: boolean;
+  done: boolean;
+}
+
+export interface HabitHistory {
+  habit_id: string;
+  from: string;
+  to: string;
+  current_streak: number;
+  longest_streak: number;
+  total_completions: number;
+  days: HabitHistoryDay[];
+}
+
 // ============================================
 // API Methods
 // ============================================
 
 /**
- * List all active habits with today's completion status
+ * List all active habits with today's completion status, or archived ones
  * GET /api/habits
  */
-export async function listHabits(): Promise<HabitsList> {
-  const response = await apiGet<{ data: HabitsList }>('/api/habits');
+export async function listHabits(archived: boolean = false): Promise<HabitsList> {
+  const query = archived ? '?archived=true' : '';
+  const response = await apiGet<{ data: HabitsList }>(`/api/habits${query}`);
   return response.data;
 }
 
@@ -84,6 +118,86 @@ export async function completeHabit(habitId: string, notes?: string): Promise<Co
   return response.data;
 }
 
+/**
+ * Undo the latest completion, today's unless a date is given
+ * DELETE /api/habits/:id/complete
+ */
+export async function uncompleteHabit(habitId: string, date?: string): Promise<Habit> {
+  const query = date ? `?date=${date}` : '';
+  const response = await apiDelete<{ data: Habit }>(`/api/habits/${habitId}/complete${query}`);
+  return response.data;
+}
+
+/**
+ * Get a habit
+ * GET /api/habits/:id
+ */
+export async function getHabit(habitId: string): Promise<Habit> {
+  const response = await apiGet<{ data: Habit }>(`/api/habits/${habitId}`);
+  return response.data;
+}
+
+/**
+ * Update a habit
+ * PUT /api/habits/:id
+ */
+export async function updateHabit(habitId: string, req: UpdateHabitRequest): Promise<Habit> {
+  const response = await apiPut<{ data: Habit }>(`/api/habits/${habitId}`, req);
+  return response.data;
+}
+
+/**
+ * Archive a habit, keeping its history
+ * POST /api/habits/:id/archive
+ */
+export async function archiveHabit(habitId: string): Promise<Habit> {
+  const response = await apiPost<{ data: Habit }>(`/api/habits/${habitId}/archive`);
+  return response.data;
+}
+
+/**
+ * Restore an archived habit
+ * POST /api/habits/:id/restore
+ */
+export async function restoreHabit(habitId: string): Promise<Habit> {
+  const response = await apiPost<{ data: Habit }>(`/api/habits/${habitId}/restore`);
+  return response.data;
+}
+
+/**
+ * Delete a habit and its history
+ * DELETE /api/habits/:id
+ */
+export async function deleteHabit(habitId: string): Promise<void> {
+  await apiDelete(`/api/habits/${habitId}`);
+}
+
+/**
+ * Sort active habits in the order given
+ * POST /api/habits/reorder
+ */
+export async function reorderHabits(ids: string[]): Promise<HabitsList> {
+  const response = await apiPost<{ data: HabitsList }>('/api/habits/reorder', { ids });
+  return response.data;
+}
+
+/**
+ * Completions per day for a heatmap (the last year by default)
+ * GET /api/habits/:id/history
+ */
+export async function getHabitHistory(
+  habitId: string,
+  from?: string,
+  to?: string
+): Promise<HabitHistory> {
+  const params = new URLSearchParams();
+  if (from) params.set('from', from);
+  if (to) params.set('to', to);
+  const query = params.toString() ? `?${params}` : '';
+  const response = await apiGet<{ data: HabitHistory }>(`/api/habits/${habitId}/history${query}`);
+  return response.data;
+}
+
 // ============================================
 // React Query Keys
 // ============================================
@@ -91,4 +205,5 @@ export async function completeHabit(habitId: string, notes?: string): Promise<Co
 export const habitsKeys = {
   all: ['habits'] as const,
   list: () => [...habitsKeys.all, 'list'] as const,
+  history: (id: string) => [...habitsKeys.all, 'history', id] as const,
 };
@@ -54,6 +54,7 @@ export interface OnboardingProgress {
 export interface OnboardingFlow {
   id: string;
   name: string;
+  version: number;
   total_steps: number;
 }
 
@@ -54,6 +54,8 @@ export interface PollResponse {
   plan: PlanStatusData;
   server_time: string;
   etag: string;
+  /** Latest change seen; resume /api/sync/stream from here */
+  cursor: number;
 }
 
 // ============================================
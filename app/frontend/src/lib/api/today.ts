@@ -35,18 +35,41 @@ export interface ResumeLast {
 }
 
 export interface InterestPrimer {
-  type: "learn" | "hub";
+  type: "learn" | "hub" | "goal" | "book";
   route: string;
   label: string;
+  reason: string | null;
+}
+
+export type SuggestionType = "lesson" | "listening_prompt" | "goal" | "book";
+
+export interface Suggestion {
+  type: SuggestionType;
+  id: string;
+  title: string;
+  route: string;
+  /** Why this was suggested, strongest first */
+  reasons: string[];
+  score: number;
 }
 
 export interface DynamicUIData {
   quickPicks: QuickPick[];
   resumeLast: ResumeLast | null;
   interestPrimer: InterestPrimer | null;
+  /** Ranked next actions, stable for the day */
+  suggestions: Suggestion[];
 }
 
-export type PlanItemType = "focus" | "quest" | "workout" | "learning" | "habit";
+export type PlanItemType =
+  | "focus"
+  | "event"
+  | "goal"
+  | "habit"
+  | "quest"
+  | "workout"
+  | "learning"
+  | "task";
 
 export interface DailyPlanSummary {
   planExists: boolean;
@@ -35,6 +35,8 @@ export interface AuthUser {
  */
 export interface SessionResponse {
   user: AuthUser | null;
+  /** Feature flags evaluated for this user; absent when the backend is unreachable */
+  flags?: Record<string, boolean>;
 }
 
 /**
@@ -1,5 +1,5 @@
-// GENERATED FROM schema.json v2.0.0 - DO NOT EDIT
-// Generated: 2026-01-10
+// GENERATED FROM schema.json v2.1.0 - DO NOT EDIT
+// Generated: 2026-10-19
 //
 // Source of truth for database types. Import from here.
 //
@@ -43,6 +43,21 @@ export interface Accounts {
   updated_at: string;
 }
 
+/** Database model for `api_tokens` table */
+export interface ApiTokens {
+  id: string;
+  user_id: string;
+  name: string;
+  token_hash: string;
+  token_prefix: string;
+  scopes: string[];
+  expires_at?: string;
+  last_used_at?: string;
+  last_used_ip?: string;
+  revoked_at?: string;
+  created_at: string;
+}
+
 /** Database model for `authenticators` table */
 export interface Authenticators {
   id: string;
@@ -55,6 +70,8 @@ export interface Authenticators {
   credential_backed_up: boolean;
   transports: string[];
   created_at: string;
+  name?: string;
+  last_used_at?: string;
 }
 
 /** Database model for `entitlements` table */
@@ -67,6 +84,17 @@ export interface Entitlements {
   created_at: string;
 }
 
+/** Database model for `oauth_states` table */
+export interface OauthStates {
+  state_key: string;
+  pkce_verifier: string;
+  redirect_uri?: string;
+  created_at: string;
+  expires_at: string;
+  provider?: string;
+  nonce?: string;
+}
+
 /** Database model for `role_entitlements` table */
 export interface RoleEntitlements {
   role_id: string;
@@ -132,6 +160,15 @@ export interface VerificationTokens {
   created_at: string;
 }
 
+/** Database model for `webauthn_challenges` table */
+export interface WebauthnChallenges {
+  challenge: string;
+  ceremony: string;
+  user_id?: string;
+  created_at: string;
+  expires_at: string;
+}
+
 // =============================================================================
 // GAMIFICATION & PROGRESS
 // =============================================================================
@@ -177,6 +214,11 @@ export interface SkillDefinitions {
   stars_per_level: number;
   sort_order: number;
   created_at: string;
+  color: string;
+  xp_scaling_base: number;
+  xp_scaling_multiplier: number;
+  is_active: boolean;
+  updated_at: string;
 }
 
 /** Database model for `universal_quests` table */
@@ -196,6 +238,11 @@ export interface UniversalQuests {
   sort_order: number;
   created_at: string;
   updated_at: string;
+  key?: string;
+  category?: string;
+  skill_star_reward: number;
+  is_recurring: boolean;
+  recurrence_period?: string;
 }
 
 /** Database model for `user_achievements` table */
@@ -310,6 +357,18 @@ export interface FocusSessions {
 // HABITS & GOALS
 // =============================================================================
 
+/** Database model for `goal_links` table */
+export interface GoalLinks {
+  id: string;
+  goal_id: string;
+  user_id: string;
+  target_type: string;
+  target_id: string;
+  weight: number;
+  target?: number;
+  created_at: string;
+}
+
 /** Database model for `goal_milestones` table */
 export interface GoalMilestones {
   id: string;
@@ -319,6 +378,7 @@ export interface GoalMilestones {
   is_completed: boolean;
   completed_at?: string;
   sort_order: number;
+  weight: number;
 }
 
 /** Database model for `goals` table */
@@ -337,6 +397,8 @@ export interface Goals {
   sort_order: number;
   created_at: string;
   updated_at: string;
+  at_risk: boolean;
+  archived_at?: string;
 }
 
 /** Database model for `habit_completions` table */
@@ -457,6 +519,36 @@ export interface WorkoutSessions {
 // LEARNING & COURSES
 // =============================================================================
 
+/** Database model for `drill_session_answers` table */
+export interface DrillSessionAnswers {
+  id: string;
+  session_id: string;
+  question_index: number;
+  answer: string;
+  is_correct: boolean;
+  response_ms: number;
+  answered_at: string;
+}
+
+/** Database model for `drill_sessions` table */
+export interface DrillSessions {
+  id: string;
+  user_id: string;
+  drill_id: string;
+  token: string;
+  drill_type: string;
+  config_json: Record<string, unknown>;
+  seed: number;
+  difficulty_level: number;
+  question_count: number;
+  status: string;
+  score?: number;
+  correct_count?: number;
+  started_at: string;
+  expires_at: string;
+  completed_at?: string;
+}
+
 /** Database model for `learn_lessons` table */
 export interface LearnLessons {
   id: string;
@@ -653,6 +745,64 @@ export interface FeatureFlags {
   metadata?: Record<string, unknown>;
   created_at: string;
   updated_at: string;
+  rollout_percentage: number;
+  target_user_ids: string[];
+  target_roles: string[];
+  target_entitlements: string[];
+}
+
+/** Database model for `notification_deliveries` table */
+export interface NotificationDeliveries {
+  id: string;
+  user_id: string;
+  kind: string;
+  ref_id?: string;
+  dedupe_key: string;
+  channel: string;
+  title: string;
+  body?: string;
+  action_url?: string;
+  due_at: string;
+  expires_at?: string;
+  status: string;
+  attempts: number;
+  next_attempt_at: string;
+  last_error?: string;
+  sent_at?: string;
+  created_at: string;
+}
+
+/** Database model for `notification_settings` table */
+export interface NotificationSettings {
+  user_id: string;
+  notifications_enabled: boolean;
+  email_notifications: boolean;
+  push_notifications: boolean;
+  timezone: string;
+  daily_reminder_time?: string;
+  quiet_hours_start?: string;
+  quiet_hours_end?: string;
+}
+
+/** Database model for `push_subscriptions` table */
+export interface PushSubscriptions {
+  id: string;
+  user_id: string;
+  endpoint: string;
+  p256dh: string;
+  auth: string;
+  user_agent?: string;
+  last_used_at?: string;
+  created_at: string;
+}
+
+/** Database model for `sync_events` table */
+export interface SyncEvents {
+  id: number;
+  user_id: string;
+  kind: string;
+  ref_id?: string;
+  created_at: string;
 }
 
 /** Database model for `user_settings` table */
@@ -669,6 +819,37 @@ export interface UserSettings {
 // CONTENT & REFERENCES
 // =============================================================================
 
+/** Database model for `content_revisions` table */
+export interface ContentRevisions {
+  id: string;
+  user_id: string;
+  entity_type: string;
+  entity_id: string;
+  version: number;
+  title: string;
+  content?: string;
+  category?: string;
+  tags: string[];
+  author_id?: string;
+  content_hash: string;
+  created_at: string;
+}
+
+/** Database model for `idea_attachments` table */
+export interface IdeaAttachments {
+  id: string;
+  user_id: string;
+  idea_id: string;
+  kind: string;
+  r2_key: string;
+  filename: string;
+  mime_type: string;
+  size_bytes: number;
+  duration_seconds?: number;
+  reference_track_id?: string;
+  created_at: string;
+}
+
 /** Database model for `ideas` table */
 export interface Ideas {
   id: string;
@@ -680,6 +861,22 @@ export interface Ideas {
   is_pinned: boolean;
   created_at: string;
   updated_at: string;
+  version: number;
+  key?: string;
+  bpm?: number;
+  mood?: string;
+  genre?: string;
+  time_signature?: string;
+}
+
+/** Database model for `inbox_conversions` table */
+export interface InboxConversions {
+  id: string;
+  user_id: string;
+  inbox_item_id: string;
+  target_type: string;
+  target_id: string;
+  created_at: string;
 }
 
 /** Database model for `inbox_items` table */
@@ -694,6 +891,25 @@ export interface InboxItems {
   processed_at?: string;
   created_at: string;
   updated_at: string;
+  body?: string;
+  action_url?: string;
+  action_data?: Record<string, unknown>;
+  priority: number;
+  is_read: boolean;
+  is_archived: boolean;
+  expires_at?: string;
+}
+
+/** Database model for `infobase_links` table */
+export interface InfobaseLinks {
+  id: string;
+  user_id: string;
+  source_id: string;
+  target_type: string;
+  target_id?: string;
+  target_title?: string;
+  raw: string;
+  created_at: string;
 }
 
 // =============================================================================
@@ -709,6 +925,8 @@ export interface OnboardingFlows {
   total_steps: number;
   created_at: string;
   updated_at: string;
+  version: number;
+  published_at?: string;
 }
 
 /** Database model for `onboarding_steps` table */
@@ -729,12 +947,57 @@ export interface OnboardingSteps {
   action_config?: Record<string, unknown>;
   created_at: string;
   updated_at: string;
+  show_if?: Record<string, unknown>;
 }
 
 // =============================================================================
 // ADMIN & PLATFORM
 // =============================================================================
 
+/** Database model for `account_archive_jobs` table */
+export interface AccountArchiveJobs {
+  id: string;
+  user_id: string;
+  kind: string;
+  status: string;
+  storage_key?: string;
+  size_bytes?: number;
+  summary?: Record<string, unknown>;
+  error?: string;
+  created_at: string;
+  started_at?: string;
+  completed_at?: string;
+  lease_owner?: string;
+  leased_until?: string;
+}
+
+/** Database model for `account_deletions` table */
+export interface AccountDeletions {
+  user_id: string;
+  status: string;
+  requested_by?: string;
+  requested_at: string;
+  purge_after: string;
+  attempts: number;
+  leased_until?: string;
+  objects_removed: number;
+  rows_removed?: Record<string, unknown>;
+  error?: string;
+  cancelled_at?: string;
+  completed_at?: string;
+  lease_owner?: string;
+}
+
+/** Database model for `admin_saved_queries` table */
+export interface AdminSavedQueries {
+  id: string;
+  user_id: string;
+  name: string;
+  sql: string;
+  created_at: string;
+  updated_at: string;
+}
+
 /** Database model for `audit_log` table */
 export interface AuditLog {
   id: string;
@@ -766,6 +1029,15 @@ export interface Feedback {
   metadata?: Record<string, unknown>;
   created_at: string;
   updated_at: string;
+  resolved_by?: string;
+}
+
+/** Database model for `rate_limit_buckets` table */
+export interface RateLimitBuckets {
+  key: string;
+  tokens: number;
+  allowed: boolean;
+  updated_at: string;
 }
 
 // =============================================================================
@@ -840,6 +1112,7 @@ export interface InfobaseEntries {
   is_pinned: boolean;
   created_at: string;
   updated_at: string;
+  version: number;
 }
 
 /** Database model for `learn_drills` table */
@@ -912,15 +1185,6 @@ export interface MarketTransactions {
   created_at: string;
 }
 
-/** Database model for `oauth_states` table */
-export interface OauthStates {
-  state_key: string;
-  pkce_verifier: string;
-  redirect_uri?: string;
-  created_at: string;
-  expires_at: string;
-}
-
 /** Database model for `plan_templates` table */
 export interface PlanTemplates {
   id: string;
@@ -974,7 +1238,7 @@ export interface ProgramWorkouts {
 export interface ReferenceTracks {
   id: string;
   user_id: string;
-  name: string;
+  title: string;
   description?: string;
   r2_key: string;
   file_size_bytes: number;
@@ -984,12 +1248,23 @@ export interface ReferenceTracks {
   album?: string;
   genre?: string;
   bpm?: number;
-  key_signature?: string;
+  key?: string;
   tags?: string[];
   status: string;
   error_message?: string;
   created_at: string;
   updated_at: string;
+  waveform_r2_key?: string;
+  thumbnail_r2_key?: string;
+  file_format?: string;
+  sample_rate?: number;
+  bit_depth?: number;
+  channels?: number;
+  is_reference: boolean;
+  is_user_upload: boolean;
+  source?: string;
+  source_url?: string;
+  metadata?: Record<string, unknown>;
 }
 
 /** Database model for `track_analyses` table */
@@ -1006,6 +1281,8 @@ export interface TrackAnalyses {
   manifest?: Record<string, unknown>;
   created_at: string;
   updated_at: string;
+  parameters?: Record<string, unknown>;
+  results?: Record<string, unknown>;
 }
 
 /** Database model for `track_annotations` table */
@@ -1013,15 +1290,16 @@ export interface TrackAnnotations {
   id: string;
   track_id: string;
   user_id: string;
-  start_time_ms: number;
-  end_time_ms?: number;
-  title: string;
+  title?: string;
   content?: string;
-  category?: string;
+  annotation_type: string;
   color?: string;
   is_private: boolean;
   created_at: string;
   updated_at: string;
+  start_time_seconds: number;
+  end_time_seconds?: number;
+  tags?: string[];
 }
 
 /** Database model for `track_regions` table */
@@ -1029,15 +1307,17 @@ export interface TrackRegions {
   id: string;
   track_id: string;
   user_id: string;
-  start_time_ms: number;
-  end_time_ms: number;
   name: string;
-  description?: string;
-  section_type?: string;
+  notes?: string;
+  region_type?: string;
   color?: string;
   display_order: number;
   created_at: string;
   updated_at: string;
+  start_time_seconds: number;
+  end_time_seconds: number;
+  loop_count: number;
+  is_favorite: boolean;
 }
 
 /** Database model for `training_programs` table */
@@ -1190,15 +1470,22 @@ export interface Workouts {
 // TYPE ALIASES
 // =============================================================================
 
+export type AccountArchiveJob = AccountArchiveJobs;
+export type AccountDeletion = AccountDeletions;
 export type Account = Accounts;
 export type AchievementDefinition = AchievementDefinitions;
 export type ActivityEvent = ActivityEvents;
+export type AdminSavedQuery = AdminSavedQueries;
 export type AnalysisEvent = AnalysisEvents;
 export type AnalysisFrameManifest = AnalysisFrameManifests;
+export type ApiToken = ApiTokens;
 export type Authenticator = Authenticators;
 export type Book = Books;
 export type CalendarEvent = CalendarEvents;
+export type ContentRevision = ContentRevisions;
 export type DailyPlan = DailyPlans;
+export type DrillSessionAnswer = DrillSessionAnswers;
+export type DrillSession = DrillSessions;
 export type Entitlement = Entitlements;
 export type ExerciseSet = ExerciseSets;
 export type Exercise = Exercises;
@@ -1206,13 +1493,17 @@ export type FeatureFlag = FeatureFlags;
 export type FocusLibrary = FocusLibraries;
 export type FocusLibraryTrack = FocusLibraryTracks;
 export type FocusSession = FocusSessions;
+export type GoalLink = GoalLinks;
 export type GoalMileston = GoalMilestones;
 export type Goal = Goals;
 export type HabitCompletion = HabitCompletions;
 export type Habit = Habits;
+export type IdeaAttachment = IdeaAttachments;
 export type Idea = Ideas;
+export type InboxConversion = InboxConversions;
 export type InboxItem = InboxItems;
 export type InfobaseEntry = InfobaseEntries;
+export type InfobaseLink = InfobaseLinks;
 export type LearnDrill = LearnDrills;
 export type LearnLesson = LearnLessons;
 export type LearnTopic = LearnTopics;
@@ -1221,6 +1512,8 @@ export type ListeningPromptTemplat = ListeningPromptTemplates;
 export type MarketItem = MarketItems;
 export type MarketRecommendation = MarketRecommendations;
 export type MarketTransaction = MarketTransactions;
+export type NotificationDelivery = NotificationDeliveries;
+export type NotificationSetting = NotificationSettings;
 export type OauthStat = OauthStates;
 export type OnboardingFlow = OnboardingFlows;
 export type OnboardingStep = OnboardingSteps;
@@ -1228,12 +1521,15 @@ export type PersonalRecord = PersonalRecords;
 export type PlanTemplat = PlanTemplates;
 export type ProgramWeek = ProgramWeeks;
 export type ProgramWorkout = ProgramWorkouts;
+export type PushSubscription = PushSubscriptions;
+export type RateLimitBucket = RateLimitBuckets;
 export type ReadingSession = ReadingSessions;
 export type ReferenceTrack = ReferenceTracks;
 export type RoleEntitlement = RoleEntitlements;
 export type Rol = Roles;
 export type Session = Sessions;
 export type SkillDefinition = SkillDefinitions;
+export type SyncEvent = SyncEvents;
 export type TrackAnalyse = TrackAnalyses;
 export type TrackAnnotation = TrackAnnotations;
 export type TrackRegion = TrackRegions;
@@ -1253,12 +1549,13 @@ export type UserSkill = UserSkills;
 export type UserStreak = UserStreaks;
 export type User = Users;
 export type VerificationToken = VerificationTokens;
+export type WebauthnChalleng = WebauthnChallenges;
 export type WorkoutExercise = WorkoutExercises;
 export type WorkoutSection = WorkoutSections;
 export type WorkoutSession = WorkoutSessions;
 export type Workout = Workouts;
 
-export const SCHEMA_VERSION = "2.0.0";
+export const SCHEMA_VERSION = "2.1.0";
 
 // =============================================================================
 // UTILITY TYPES
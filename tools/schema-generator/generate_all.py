@@ -2,12 +2,16 @@
 """
 Generate Rust, TypeScript, SQL, and Seeds from schema.json
 
-Single source of truth: schema.json defines all table schemas, seed data, and type mappings.
+schema.json mirrors the schema the migrations build and defines seed data and
+type mappings. The numbered migrations are the source of truth for the database:
+0001_schema.sql and 0002_seeds.sql were generated from here once and are now
+applied migrations, so they are only rewritten on request (--sql).
 
 Usage:
-  python generate_all.py                    # Generate all with defaults
+  python generate_all.py                    # Generate Rust and TypeScript types
   python generate_all.py --validate         # Validate schema without generating
   python generate_all.py --dry-run          # Show what would be generated
+  python generate_all.py --sql              # Also rewrite 0001_schema.sql and 0002_seeds.sql
   python generate_all.py --sql-only         # Only generate SQL files
   python generate_all.py --rust PATH        # Override Rust output path
   python generate_all.py --ts PATH          # Override TypeScript output path
@@ -55,7 +59,7 @@ DOMAINS = {
         "tables": [
             "users", "sessions", "accounts", "authenticators", 
             "verification_tokens", "roles", "entitlements", "role_entitlements",
-            "user_roles", "oauth_state"
+            "user_roles", "oauth_state", "oauth_states", "webauthn_challenges", "api_tokens"
         ]
     },
     "gamification": {
@@ -75,7 +79,7 @@ DOMAINS = {
         "title": "Habits & Goals",
         "tables": [
             "habits", "habit_completions", "habit_schedules",
-            "goals", "goal_milestones", "goal_progress"
+            "goals", "goal_milestones", "goal_progress", "goal_links"
         ]
     },
     "books": {
@@ -94,7 +98,7 @@ DOMAINS = {
         "title": "Learning & Courses",
         "tables": [
             "learn_topics", "learn_lessons", "learn_quizzes", "learn_quiz_questions",
-            "user_lesson_progress", "user_quiz_attempts"
+            "user_lesson_progress", "user_quiz_attempts", "drill_sessions", "drill_session_answers"
         ]
     },
     "market": {
@@ -115,11 +119,17 @@ DOMAINS = {
     },
     "sync": {
         "title": "Sync & Settings",
-        "tables": ["sync_queue", "user_preferences", "user_settings", "feature_flags"]
+        "tables": [
+            "sync_queue", "user_preferences", "user_settings", "feature_flags", "sync_events",
+            "notification_settings", "notification_deliveries", "push_subscriptions"
+        ]
     },
     "content": {
         "title": "Content & References",
-        "tables": ["infobase_items", "inbox_items", "ideas", "references_library", "tags", "tag_associations"]
+        "tables": [
+            "infobase_items", "inbox_items", "ideas", "references_library", "tags", "tag_associations",
+            "idea_attachments", "inbox_conversions", "infobase_links", "content_revisions"
+        ]
     },
     "onboarding": {
         "title": "Onboarding",
@@ -127,7 +137,10 @@ DOMAINS = {
     },
     "admin": {
         "title": "Admin & Platform",
-        "tables": ["feedback", "system_stats", "schema_version", "audit_log", "email_templates", "notification_templates"]
+        "tables": [
+            "feedback", "system_stats", "schema_version", "audit_log", "email_templates", "notification_templates",
+            "account_archive_jobs", "account_deletions", "admin_saved_queries", "rate_limit_buckets"
+        ]
     }
 }
 
@@ -679,6 +692,7 @@ def main():
     parser = argparse.ArgumentParser(description='Generate code from schema.json')
     parser.add_argument('--validate', action='store_true', help='Validate schema only')
     parser.add_argument('--dry-run', action='store_true', help='Show what would be generated')
+    parser.add_argument('--sql', action='store_true', help='Also rewrite the baseline SQL migrations')
     parser.add_argument('--sql-only', action='store_true', help='Generate only SQL files')
     parser.add_argument('--rust', type=Path, help='Rust output path')
     parser.add_argument('--ts', type=Path, help='TypeScript output path')
@@ -720,13 +734,15 @@ def main():
     migrations_dir = args.migrations or DEFAULT_PATHS['migrations']
     sql_path = migrations_dir / '0001_schema.sql'
     seeds_path = migrations_dir / '0002_seeds.sql'
+    write_sql = args.sql or args.sql_only
     
     if args.dry_run:
         print(f"Would generate (schema v{schema['version']}):")
         print(f"  Rust:       {rust_path} ({len(rust_content)} bytes)")
         print(f"  TypeScript: {ts_path} ({len(ts_content)} bytes)")
-        print(f"  Schema:     {sql_path} ({len(sql_content)} bytes)")
-        print(f"  Seeds:      {seeds_path} ({len(seeds_content)} bytes)")
+        if write_sql:
+            print(f"  Schema:     {sql_path} ({len(sql_content)} bytes)")
+            print(f"  Seeds:      {seeds_path} ({len(seeds_content)} bytes)")
         return
     
     # Write files
@@ -741,20 +757,22 @@ def main():
         ts_path.write_text(ts_content)
         outputs.append(('TypeScript', ts_path))
     
-    migrations_dir.mkdir(parents=True, exist_ok=True)
-    sql_path.write_text(sql_content)
-    outputs.append(('Schema', sql_path))
-    
-    seeds_path.write_text(seeds_content)
-    outputs.append(('Seeds', seeds_path))
+    if write_sql:
+        migrations_dir.mkdir(parents=True, exist_ok=True)
+        sql_path.write_text(sql_content)
+        outputs.append(('Schema', sql_path))
+
+        seeds_path.write_text(seeds_content)
+        outputs.append(('Seeds', seeds_path))
     
     # Reference copies
     if not args.no_refs:
         if not args.sql_only:
             DEFAULT_PATHS['rust_ref'].write_text(rust_content)
             DEFAULT_PATHS['ts_ref'].write_text(ts_content)
-        DEFAULT_PATHS['sql_ref'].write_text(sql_content)
-        DEFAULT_PATHS['seeds_ref'].write_text(seeds_content)
+        if write_sql:
+            DEFAULT_PATHS['sql_ref'].write_text(sql_content)
+            DEFAULT_PATHS['seeds_ref'].write_text(seeds_content)
     
     # Summary
     seed_count = sum(len(s.get('records', [])) for s in schema.get('seeds', {}).values())